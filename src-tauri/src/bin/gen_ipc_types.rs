@@ -0,0 +1,1777 @@
+//! IPC contract generation
+//! Writes `src/types/ipc.generated.ts` with the parameters and response of every command
+//! registered in `generate_handler!`, the payload of every event sent through `emit_event`,
+//! and the serde models they reference. The Rust sources are read as text rather than
+//! compiled, so only the serde attributes this crate uses are understood: `rename_all`,
+//! `rename`, `tag`, `content`, `untagged`, `skip`, `flatten`, `default` and
+//! `skip_serializing_if`
+//!
+//! Run `cargo run --bin gen-ipc-types` after changing a command, event or model, and commit
+//! the result; `--check` only reports whether the file is current, which `cargo test` also
+//! checks.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const OUTPUT: &str = "../src/types/ipc.generated.ts";
+
+/// Parameters Tauri injects rather than reading from the invoke arguments
+const INJECTED_PARAMS: &[&str] = &[
+    "AppHandle",
+    "State",
+    "Window",
+    "WebviewWindow",
+    "Webview",
+    "Request",
+    "CommandScope",
+    "GlobalScope",
+];
+
+fn main() -> ExitCode {
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = manifest_dir.join(OUTPUT);
+
+    let contents = match render_contract(&manifest_dir.join("src")) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Failed to generate IPC types: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if fs::read_to_string(&output).ok().as_deref() == Some(contents.as_str()) {
+        println!("{} is up to date", output.display());
+        return ExitCode::SUCCESS;
+    }
+    if check {
+        eprintln!(
+            "{} is out of date; run `cargo run --bin gen-ipc-types`",
+            output.display()
+        );
+        return ExitCode::FAILURE;
+    }
+    match fs::write(&output, contents) {
+        Ok(()) => {
+            println!("Wrote {}", output.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", output.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn render_contract(src_dir: &Path) -> Result<String, String> {
+    let mut files = Vec::new();
+    collect_sources(src_dir, &mut files).map_err(|e| e.to_string())?;
+    // Models win name clashes with private helper types elsewhere
+    files.sort_by_key(|path| (!path.ends_with("models.rs"), path.clone()));
+
+    let mut sources = Vec::new();
+    for path in &files {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        sources.push(SourceFile::parse(&text));
+    }
+    let lib = fs::read_to_string(src_dir.join("lib.rs")).map_err(|e| e.to_string())?;
+    let registered = registered_commands(&tokenize(&lib));
+
+    let mut models = HashMap::new();
+    let mut model_order = Vec::new();
+    for model in sources.iter().flat_map(|source| &source.models) {
+        if !models.contains_key(&model.name) {
+            model_order.push(model.name.clone());
+            models.insert(model.name.clone(), model);
+        }
+    }
+
+    let mut commands = BTreeMap::new();
+    for command in sources.iter().flat_map(|source| &source.commands) {
+        if registered.contains(&command.name) {
+            commands.entry(command.name.clone()).or_insert(command);
+        }
+    }
+
+    let functions: HashMap<&str, &Ty> = sources
+        .iter()
+        .flat_map(|source| &source.functions)
+        .map(|(name, ty)| (name.as_str(), ty))
+        .collect();
+    let mut events: BTreeMap<String, Vec<Ty>> = BTreeMap::new();
+    for (name, payload) in sources.iter().flat_map(|source| source.events(&functions)) {
+        let payloads = events.entry(name).or_default();
+        if !payloads.contains(&payload) {
+            payloads.push(payload);
+        }
+    }
+
+    // Only models reachable from a command or event are part of the contract
+    let mut queue: VecDeque<&Ty> = VecDeque::new();
+    for command in commands.values() {
+        queue.extend(command.params.iter().map(|(_, ty)| ty));
+        queue.push_back(&command.response);
+    }
+    queue.extend(events.values().flatten());
+    let mut reachable = BTreeSet::new();
+    while let Some(ty) = queue.pop_front() {
+        let mut names = Vec::new();
+        ty.referenced(&mut names);
+        for name in names {
+            if let Some(model) = models.get(&name) {
+                if reachable.insert(name) {
+                    queue.extend(model.referenced_types());
+                }
+            }
+        }
+    }
+
+    let known: BTreeSet<&str> = reachable.iter().map(String::as_str).collect();
+    let mut out = String::new();
+    out.push_str(
+        "// Generated by `cargo run --bin gen-ipc-types` from the backend's Rust sources; do not\n\
+         // edit. `cargo test` fails while it is out of date, so regenerate it with the Rust change.\n\n\
+         import { invoke, type InvokeArgs } from '@tauri-apps/api/core';\n\
+         import { listen, type EventCallback, type UnlistenFn } from '@tauri-apps/api/event';\n",
+    );
+
+    for name in model_order.iter().filter(|name| reachable.contains(*name)) {
+        out.push('\n');
+        models[name].render(&known, &mut out);
+    }
+
+    out.push_str("\n/** Arguments and response of each command, keyed by command name */\n");
+    out.push_str("export interface Commands {\n");
+    for command in commands.values() {
+        push_docs(&command.docs, "  ", &mut out);
+        let args = if command.params.is_empty() {
+            "Record<string, never>".to_string()
+        } else {
+            let params: Vec<String> = command
+                .params
+                .iter()
+                .map(|(name, ty)| field_signature(&camel_case(name), ty, false, false, &[], &known))
+                .collect();
+            format!("{{ {} }}", params.join("; "))
+        };
+        out.push_str(&format!(
+            "  {}: {{\n    args: {};\n    response: {};\n  }};\n",
+            property_name(&command.name),
+            args,
+            command.response.to_ts(&[], &known)
+        ));
+    }
+    out.push_str("}\n");
+
+    out.push_str("\n/** Payload of each event the backend emits, keyed by event name */\n");
+    out.push_str("export interface Events {\n");
+    for (name, payloads) in &events {
+        let mut types: Vec<String> = Vec::new();
+        for ty in payloads {
+            let ty = ty.to_ts(&[], &known);
+            if !types.contains(&ty) {
+                types.push(ty);
+            }
+        }
+        if types.len() > 1 {
+            types.retain(|ty| ty != "unknown");
+        }
+        out.push_str(&format!(
+            "  {}: {};\n",
+            property_name(name),
+            types.join(" | ")
+        ));
+    }
+    out.push_str("}\n");
+
+    out.push_str(
+        "\n\
+         type CommandArgs<C extends keyof Commands> =\n  \
+         Record<string, never> extends Commands[C]['args']\n    \
+         ? [args?: Commands[C]['args']]\n    \
+         : [args: Commands[C]['args']];\n\
+         \n\
+         /** `invoke` with the arguments and response checked against the command's signature */\n\
+         export function invokeCommand<C extends keyof Commands>(\n  \
+         command: C,\n  \
+         ...args: CommandArgs<C>\n\
+         ): Promise<Commands[C]['response']> {\n  \
+         return invoke<Commands[C]['response']>(command, args[0] as InvokeArgs | undefined);\n\
+         }\n\
+         \n\
+         /** `listen` with the payload typed by event name */\n\
+         export function listenEvent<E extends keyof Events>(\n  \
+         event: E,\n  \
+         handler: EventCallback<Events[E]>,\n\
+         ): Promise<UnlistenFn> {\n  \
+         return listen<Events[E]>(event, handler);\n\
+         }\n",
+    );
+    Ok(out)
+}
+
+fn collect_sources(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            // Standalone binaries don't take part in IPC
+            if !path.ends_with("bin") {
+                collect_sources(&path, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tokens
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Str(String),
+    /// Numbers and char literals
+    Literal,
+    Lifetime,
+    /// Text of a `///` comment
+    Doc(String),
+}
+
+impl Token {
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self, Token::Ident(ident) if ident == name)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        *self == Token::Punct(c)
+    }
+}
+
+fn tokenize(src: &str) -> Vec<Token> {
+    let chars: Vec<char> = src.chars().collect();
+    let at = |i: usize| chars.get(i).copied().unwrap_or('\0');
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && at(i + 1) == '/' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .map_or(chars.len(), |offset| i + offset);
+            if at(i + 2) == '/' && at(i + 3) != '/' {
+                let text: String = chars[i + 3..end].iter().collect();
+                let text = text.strip_prefix(' ').unwrap_or(&text);
+                tokens.push(Token::Doc(text.trim_end().to_string()));
+            }
+            i = end;
+        } else if c == '/' && at(i + 1) == '*' {
+            let mut depth = 0;
+            while i < chars.len() {
+                if at(i) == '/' && at(i + 1) == '*' {
+                    depth += 1;
+                    i += 2;
+                } else if at(i) == '*' && at(i + 1) == '/' {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if c == 'r'
+            && (at(i + 1) == '"' || (at(i + 1) == '#' && matches!(at(i + 2), '#' | '"')))
+        {
+            let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+            let start = i + 2 + hashes;
+            let mut end = start;
+            while end < chars.len()
+                && !(chars[end] == '"' && (0..hashes).all(|h| at(end + 1 + h) == '#'))
+            {
+                end += 1;
+            }
+            tokens.push(Token::Str(
+                chars[start..end.min(chars.len())].iter().collect(),
+            ));
+            i = end + 1 + hashes;
+        } else if c == 'r' && at(i + 1) == '#' {
+            // Raw identifier, e.g. `r#type`
+            i += 2;
+        } else if c == '"' {
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                text.push(at(i));
+                i += 1;
+            }
+            tokens.push(Token::Str(text));
+            i += 1;
+        } else if c == '\'' {
+            if at(i + 1) == '\\' {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                tokens.push(Token::Literal);
+                i += 1;
+            } else if at(i + 2) == '\'' {
+                tokens.push(Token::Literal);
+                i += 3;
+            } else {
+                i += 1;
+                while at(i).is_alphanumeric() || at(i) == '_' {
+                    i += 1;
+                }
+                tokens.push(Token::Lifetime);
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while at(i).is_alphanumeric() || at(i) == '_' {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() {
+            while at(i).is_alphanumeric()
+                || at(i) == '_'
+                || (at(i) == '.' && at(i + 1).is_ascii_digit())
+            {
+                i += 1;
+            }
+            tokens.push(Token::Literal);
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// Index of the token closing the group opened at `open`, or the last token
+fn group_end(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len().saturating_sub(1)
+}
+
+/// Split a token list at commas outside any brackets, generics included when `angles`
+fn split_commas(tokens: &[Token], angles: bool) -> Vec<&[Token]> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth -= 1,
+            Token::Punct('<') if angles => depth += 1,
+            // Not the `>` of `->`
+            Token::Punct('>') if angles && !(i > 0 && tokens[i - 1].is_punct('-')) => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                parts.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+    parts
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    /// Last path segment and its generic arguments
+    Path(String, Vec<Ty>),
+    Tuple(Vec<Ty>),
+    Array(Box<Ty>),
+    Unknown,
+}
+
+impl Ty {
+    fn parse(tokens: &[Token]) -> Ty {
+        Ty::parse_at(tokens, &mut 0)
+    }
+
+    fn parse_at(tokens: &[Token], i: &mut usize) -> Ty {
+        match tokens.get(*i) {
+            Some(Token::Punct('&')) => {
+                *i += 1;
+                while matches!(tokens.get(*i), Some(Token::Lifetime))
+                    || tokens.get(*i).is_some_and(|t| t.is_ident("mut"))
+                {
+                    *i += 1;
+                }
+                Ty::parse_at(tokens, i)
+            }
+            Some(Token::Punct('(')) => {
+                let end = group_end(tokens, *i);
+                let elements = split_commas(&tokens[*i + 1..end], true)
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .map(Ty::parse)
+                    .collect();
+                *i = end + 1;
+                Ty::Tuple(elements)
+            }
+            Some(Token::Punct('[')) => {
+                let end = group_end(tokens, *i);
+                *i += 1;
+                let element = Ty::parse_at(tokens, i);
+                *i = end + 1;
+                Ty::Array(Box::new(element))
+            }
+            Some(Token::Ident(ident)) if ident == "dyn" || ident == "impl" => Ty::Unknown,
+            Some(Token::Ident(_)) => {
+                let mut name = String::new();
+                while let Some(Token::Ident(segment)) = tokens.get(*i) {
+                    name = segment.clone();
+                    *i += 1;
+                    if tokens.get(*i).is_some_and(|t| t.is_punct(':'))
+                        && tokens.get(*i + 1).is_some_and(|t| t.is_punct(':'))
+                    {
+                        *i += 2;
+                    } else {
+                        break;
+                    }
+                }
+                let mut args = Vec::new();
+                if tokens.get(*i).is_some_and(|t| t.is_punct('<')) {
+                    *i += 1;
+                    while *i < tokens.len() && !tokens[*i].is_punct('>') {
+                        let start = *i;
+                        match &tokens[*i] {
+                            Token::Lifetime | Token::Punct(',') => *i += 1,
+                            _ => args.push(Ty::parse_at(tokens, i)),
+                        }
+                        // Skip what isn't a type, like the `=` of `Iterator<Item = T>`
+                        if *i == start {
+                            *i += 1;
+                        }
+                    }
+                    *i += 1;
+                }
+                Ty::Path(name, args)
+            }
+            _ => Ty::Unknown,
+        }
+    }
+
+    /// Names of every path in the type, generic arguments included
+    fn referenced(&self, names: &mut Vec<String>) {
+        match self {
+            Ty::Path(name, args) => {
+                names.push(name.clone());
+                args.iter().for_each(|arg| arg.referenced(names));
+            }
+            Ty::Tuple(elements) => elements.iter().for_each(|ty| ty.referenced(names)),
+            Ty::Array(element) => element.referenced(names),
+            Ty::Unknown => {}
+        }
+    }
+
+    fn is_option(&self) -> bool {
+        matches!(self, Ty::Path(name, args) if name == "Option" && args.len() == 1)
+    }
+
+    fn to_ts(&self, generics: &[String], known: &BTreeSet<&str>) -> String {
+        let arg = |args: &[Ty], index: usize| {
+            args.get(index)
+                .map_or("unknown".to_string(), |ty| ty.to_ts(generics, known))
+        };
+        match self {
+            Ty::Path(name, args) => match name.as_str() {
+                _ if generics.contains(name) => name.clone(),
+                "String" | "str" | "char" | "PathBuf" | "Path" | "OsString" | "Uuid"
+                | "DateTime" | "NaiveDate" | "NaiveDateTime" => "string".to_string(),
+                "bool" => "boolean".to_string(),
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" | "f32" | "f64" => "number".to_string(),
+                "Option" => match arg(args, 0) {
+                    inner if inner == "unknown" => inner,
+                    inner => format!("{} | null", inner),
+                },
+                "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => array_of(&arg(args, 0)),
+                "HashMap" | "BTreeMap" => format!("Record<string, {}>", arg(args, 1)),
+                "Box" | "Arc" | "Rc" | "Cow" => arg(args, 0),
+                "Duration" => "{ secs: number; nanos: number }".to_string(),
+                _ if known.contains(name.as_str()) => {
+                    if args.is_empty() {
+                        name.clone()
+                    } else {
+                        let args: Vec<String> =
+                            args.iter().map(|ty| ty.to_ts(generics, known)).collect();
+                        format!("{}<{}>", name, args.join(", "))
+                    }
+                }
+                _ => "unknown".to_string(),
+            },
+            Ty::Tuple(elements) if elements.is_empty() => "null".to_string(),
+            Ty::Tuple(elements) => {
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|ty| ty.to_ts(generics, known))
+                    .collect();
+                format!("[{}]", elements.join(", "))
+            }
+            Ty::Array(element) => array_of(&element.to_ts(generics, known)),
+            Ty::Unknown => "unknown".to_string(),
+        }
+    }
+}
+
+fn array_of(element: &str) -> String {
+    if element.contains(' ') {
+        format!("({})[]", element)
+    } else {
+        format!("{}[]", element)
+    }
+}
+
+// ============================================================================
+// Source items
+// ============================================================================
+
+struct Attribute {
+    path: String,
+    body: Vec<Token>,
+}
+
+impl Attribute {
+    /// `key` and `key = "value"` entries of a `#[serde(...)]` attribute
+    fn serde_entries(attributes: &[Attribute]) -> Vec<(String, Option<String>)> {
+        attributes
+            .iter()
+            .filter(|attribute| attribute.path == "serde")
+            .flat_map(|attribute| split_commas(&attribute.body, false))
+            .filter_map(|entry| match entry {
+                [Token::Ident(key)] => Some((key.clone(), None)),
+                [Token::Ident(key), Token::Punct('='), Token::Str(value), ..] => {
+                    Some((key.clone(), Some(value.clone())))
+                }
+                [Token::Ident(key), ..] => Some((key.clone(), None)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn serde_value(entries: &[(String, Option<String>)], key: &str) -> Option<String> {
+    entries
+        .iter()
+        .find(|(entry, _)| entry == key)
+        .and_then(|(_, value)| value.clone())
+}
+
+fn serde_flag(entries: &[(String, Option<String>)], key: &str) -> bool {
+    entries.iter().any(|(entry, _)| entry == key)
+}
+
+/// Doc comments and attributes in front of an item, field or variant
+#[derive(Default)]
+struct Preamble {
+    docs: Vec<String>,
+    attributes: Vec<Attribute>,
+}
+
+impl Preamble {
+    /// Consume docs and attributes starting at `i`
+    fn parse(tokens: &[Token], i: &mut usize) -> Preamble {
+        let mut preamble = Preamble::default();
+        loop {
+            match tokens.get(*i) {
+                Some(Token::Doc(doc)) => {
+                    preamble.docs.push(doc.clone());
+                    *i += 1;
+                }
+                Some(Token::Punct('#')) if tokens.get(*i + 1).is_some_and(|t| t.is_punct('[')) => {
+                    let end = group_end(tokens, *i + 1);
+                    preamble
+                        .attributes
+                        .push(parse_attribute(&tokens[*i + 2..end]));
+                    *i = end + 1;
+                }
+                _ => return preamble,
+            }
+        }
+    }
+
+    fn derives_serde(&self) -> bool {
+        self.attributes.iter().any(|attribute| {
+            attribute.path == "derive"
+                && attribute
+                    .body
+                    .iter()
+                    .any(|t| t.is_ident("Serialize") || t.is_ident("Deserialize"))
+        })
+    }
+
+    fn has(&self, path: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.path == path)
+    }
+
+    fn is_test_only(&self) -> bool {
+        self.attributes.iter().any(|attribute| {
+            attribute.path == "cfg" && attribute.body.iter().any(|t| t.is_ident("test"))
+        })
+    }
+}
+
+fn parse_attribute(tokens: &[Token]) -> Attribute {
+    let mut path = String::new();
+    let mut i = 0;
+    while let Some(token) = tokens.get(i) {
+        match token {
+            Token::Ident(segment) => path.push_str(segment),
+            Token::Punct(':') => path.push(':'),
+            _ => break,
+        }
+        i += 1;
+    }
+    let body = if tokens.get(i).is_some_and(|t| t.is_punct('(')) {
+        tokens[i + 1..group_end(tokens, i)].to_vec()
+    } else {
+        Vec::new()
+    };
+    Attribute { path, body }
+}
+
+/// Skip `pub`, `pub(crate)` and the like
+fn skip_visibility(tokens: &[Token], i: &mut usize) {
+    if tokens.get(*i).is_some_and(|t| t.is_ident("pub")) {
+        *i += 1;
+        if tokens.get(*i).is_some_and(|t| t.is_punct('(')) {
+            *i = group_end(tokens, *i) + 1;
+        }
+    }
+}
+
+struct Field {
+    /// Name on the wire
+    name: String,
+    docs: Vec<String>,
+    ty: Ty,
+    flatten: bool,
+    /// Left out when empty rather than sent as `null`
+    skip_if_empty: bool,
+    /// May be left out of arguments, the backend filling in its default
+    has_default: bool,
+}
+
+impl Field {
+    fn signature(&self, generics: &[String], known: &BTreeSet<&str>) -> String {
+        field_signature(
+            &self.name,
+            &self.ty,
+            self.skip_if_empty,
+            self.has_default,
+            generics,
+            known,
+        )
+    }
+}
+
+enum Body {
+    Fields(Vec<Field>),
+    Tuple(Vec<Ty>),
+    Unit,
+}
+
+impl Body {
+    /// Parse the body following a struct or variant name, advancing past it
+    fn parse(tokens: &[Token], i: &mut usize, rename_all: Option<&str>) -> Body {
+        match tokens.get(*i) {
+            Some(Token::Punct('{')) => {
+                let end = group_end(tokens, *i);
+                let fields = parse_fields(&tokens[*i + 1..end], rename_all);
+                *i = end + 1;
+                Body::Fields(fields)
+            }
+            Some(Token::Punct('(')) => {
+                let end = group_end(tokens, *i);
+                let elements = split_commas(&tokens[*i + 1..end], true)
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .map(|part| {
+                        let mut j = 0;
+                        Preamble::parse(part, &mut j);
+                        skip_visibility(part, &mut j);
+                        Ty::parse(&part[j..])
+                    })
+                    .collect();
+                *i = end + 1;
+                Body::Tuple(elements)
+            }
+            _ => Body::Unit,
+        }
+    }
+
+    fn referenced_types(&self) -> Vec<&Ty> {
+        match self {
+            Body::Fields(fields) => fields.iter().map(|field| &field.ty).collect(),
+            Body::Tuple(elements) => elements.iter().collect(),
+            Body::Unit => Vec::new(),
+        }
+    }
+
+    /// Object members of a struct body with their docs, one per line
+    fn members(&self, indent: &str, generics: &[String], known: &BTreeSet<&str>) -> String {
+        let mut out = String::new();
+        if let Body::Fields(fields) = self {
+            for field in fields.iter().filter(|field| !field.flatten) {
+                push_docs(&field.docs, indent, &mut out);
+                out.push_str(indent);
+                out.push_str(&field.signature(generics, known));
+                out.push_str(";\n");
+            }
+        }
+        out
+    }
+
+    /// Struct body as a one-line object type, preceded by `leading` members
+    fn inline_object(
+        &self,
+        leading: Option<String>,
+        generics: &[String],
+        known: &BTreeSet<&str>,
+    ) -> String {
+        let mut members: Vec<String> = leading.into_iter().collect();
+        if let Body::Fields(fields) = self {
+            members.extend(
+                fields
+                    .iter()
+                    .filter(|field| !field.flatten)
+                    .map(|field| field.signature(generics, known)),
+            );
+        }
+        let mut parts = vec![format!("{{ {} }}", members.join("; "))];
+        parts.extend(self.flattened(generics, known));
+        parts.join(" & ")
+    }
+
+    fn flattened(&self, generics: &[String], known: &BTreeSet<&str>) -> Vec<String> {
+        match self {
+            Body::Fields(fields) => fields
+                .iter()
+                .filter(|field| field.flatten)
+                .map(|field| field.ty.to_ts(generics, known))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn parse_fields(tokens: &[Token], rename_all: Option<&str>) -> Vec<Field> {
+    split_commas(tokens, true)
+        .into_iter()
+        .filter_map(|part| {
+            let mut i = 0;
+            let preamble = Preamble::parse(part, &mut i);
+            skip_visibility(part, &mut i);
+            let Some(Token::Ident(name)) = part.get(i) else {
+                return None;
+            };
+            let serde = Attribute::serde_entries(&preamble.attributes);
+            if serde_flag(&serde, "skip") || serde_flag(&serde, "skip_serializing") {
+                return None;
+            }
+            Some(Field {
+                name: serde_value(&serde, "rename")
+                    .unwrap_or_else(|| rename(name, rename_all, false)),
+                docs: preamble.docs,
+                ty: Ty::parse(part.get(i + 2..).unwrap_or_default()),
+                flatten: serde_flag(&serde, "flatten"),
+                skip_if_empty: serde_flag(&serde, "skip_serializing_if"),
+                has_default: serde_flag(&serde, "default"),
+            })
+        })
+        .collect()
+}
+
+/// A member for a field; options may be absent or `null` unless skipped when empty, and
+/// fields that are skipped or have a default may be absent
+fn field_signature(
+    name: &str,
+    ty: &Ty,
+    skip_if_empty: bool,
+    has_default: bool,
+    generics: &[String],
+    known: &BTreeSet<&str>,
+) -> String {
+    match ty {
+        Ty::Path(_, args) if ty.is_option() => {
+            let inner = args[0].to_ts(generics, known);
+            if skip_if_empty || inner == "unknown" {
+                format!("{}?: {}", property_name(name), inner)
+            } else {
+                format!("{}?: {} | null", property_name(name), inner)
+            }
+        }
+        _ if skip_if_empty || has_default => {
+            format!("{}?: {}", property_name(name), ty.to_ts(generics, known))
+        }
+        _ => format!("{}: {}", property_name(name), ty.to_ts(generics, known)),
+    }
+}
+
+enum Tagging {
+    External,
+    Internal(String),
+    Adjacent(String, String),
+    Untagged,
+}
+
+struct Variant {
+    name: String,
+    docs: Vec<String>,
+    body: Body,
+}
+
+enum Shape {
+    Struct(Body),
+    Enum(Tagging, Vec<Variant>),
+}
+
+struct Model {
+    name: String,
+    generics: Vec<String>,
+    docs: Vec<String>,
+    shape: Shape,
+}
+
+impl Model {
+    /// Parse the struct or enum whose keyword is at `i`, advancing past it
+    fn parse(tokens: &[Token], i: &mut usize, preamble: Preamble) -> Option<Model> {
+        let is_enum = tokens[*i].is_ident("enum");
+        let Some(Token::Ident(name)) = tokens.get(*i + 1) else {
+            return None;
+        };
+        *i += 2;
+
+        let mut generics = Vec::new();
+        if tokens.get(*i).is_some_and(|t| t.is_punct('<')) {
+            let start = *i + 1;
+            let mut depth = 1;
+            while depth > 0 && *i + 1 < tokens.len() {
+                *i += 1;
+                match tokens[*i] {
+                    Token::Punct('<') => depth += 1,
+                    Token::Punct('>') => depth -= 1,
+                    _ => {}
+                }
+            }
+            for param in split_commas(&tokens[start..*i], true) {
+                if let Some(Token::Ident(param)) = param.first() {
+                    generics.push(param.clone());
+                }
+            }
+            *i += 1;
+        }
+        // Skip any where clause
+        while *i < tokens.len() && !matches!(tokens[*i], Token::Punct('{' | '(' | ';')) {
+            *i += 1;
+        }
+
+        let serde = Attribute::serde_entries(&preamble.attributes);
+        let rename_all = serde_value(&serde, "rename_all");
+        let shape = if is_enum {
+            let tagging = match (serde_value(&serde, "tag"), serde_value(&serde, "content")) {
+                _ if serde_flag(&serde, "untagged") => Tagging::Untagged,
+                (Some(tag), Some(content)) => Tagging::Adjacent(tag, content),
+                (Some(tag), None) => Tagging::Internal(tag),
+                _ => Tagging::External,
+            };
+            let end = group_end(tokens, *i);
+            let variants = parse_variants(&tokens[*i + 1..end], rename_all.as_deref());
+            *i = end + 1;
+            Shape::Enum(tagging, variants)
+        } else {
+            let mut body = Body::parse(tokens, i, rename_all.as_deref());
+            // A container `default` fills in every missing field
+            if let Body::Fields(ref mut fields) = body {
+                if serde_flag(&serde, "default") {
+                    fields.iter_mut().for_each(|field| field.has_default = true);
+                }
+            }
+            Shape::Struct(body)
+        };
+
+        Some(Model {
+            name: name.clone(),
+            generics,
+            docs: preamble.docs,
+            shape,
+        })
+    }
+
+    fn referenced_types(&self) -> Vec<&Ty> {
+        match &self.shape {
+            Shape::Struct(body) => body.referenced_types(),
+            Shape::Enum(_, variants) => variants
+                .iter()
+                .flat_map(|variant| variant.body.referenced_types())
+                .collect(),
+        }
+    }
+
+    fn render(&self, known: &BTreeSet<&str>, out: &mut String) {
+        push_docs(&self.docs, "", out);
+        let name = if self.generics.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}<{}>", self.name, self.generics.join(", "))
+        };
+        let generics = &self.generics;
+        match &self.shape {
+            Shape::Struct(body @ Body::Fields(_)) => {
+                let flattened = body.flattened(generics, known);
+                if flattened.is_empty() {
+                    out.push_str(&format!(
+                        "export interface {} {{\n{}}}\n",
+                        name,
+                        body.members("  ", generics, known)
+                    ));
+                } else {
+                    out.push_str(&format!(
+                        "export type {} = {{\n{}}} & {};\n",
+                        name,
+                        body.members("  ", generics, known),
+                        flattened.join(" & ")
+                    ));
+                }
+            }
+            Shape::Struct(Body::Tuple(elements)) if elements.len() == 1 => {
+                out.push_str(&format!(
+                    "export type {} = {};\n",
+                    name,
+                    elements[0].to_ts(generics, known)
+                ));
+            }
+            Shape::Struct(body) => {
+                let ty = match body {
+                    Body::Tuple(elements) => Ty::Tuple(elements.clone()),
+                    _ => Ty::Tuple(Vec::new()),
+                };
+                out.push_str(&format!(
+                    "export type {} = {};\n",
+                    name,
+                    ty.to_ts(generics, known)
+                ));
+            }
+            Shape::Enum(tagging, variants) => {
+                out.push_str(&format!("export type {} =\n", name));
+                for variant in variants {
+                    push_docs(&variant.docs, "  ", out);
+                    out.push_str("  | ");
+                    out.push_str(&variant.to_ts(tagging, generics, known));
+                    out.push('\n');
+                }
+                if variants.is_empty() {
+                    out.push_str("  never");
+                }
+                out.truncate(out.trim_end().len());
+                out.push_str(";\n");
+            }
+        }
+    }
+}
+
+fn parse_variants(tokens: &[Token], rename_all: Option<&str>) -> Vec<Variant> {
+    split_commas(tokens, true)
+        .into_iter()
+        .filter_map(|part| {
+            let mut i = 0;
+            let preamble = Preamble::parse(part, &mut i);
+            let Some(Token::Ident(name)) = part.get(i) else {
+                return None;
+            };
+            let serde = Attribute::serde_entries(&preamble.attributes);
+            if serde_flag(&serde, "skip") || serde_flag(&serde, "skip_serializing") {
+                return None;
+            }
+            i += 1;
+            let fields_rename = serde_value(&serde, "rename_all");
+            Some(Variant {
+                name: serde_value(&serde, "rename")
+                    .unwrap_or_else(|| rename(name, rename_all, true)),
+                docs: preamble.docs,
+                body: Body::parse(part, &mut i, fields_rename.as_deref()),
+            })
+        })
+        .collect()
+}
+
+impl Variant {
+    fn to_ts(&self, tagging: &Tagging, generics: &[String], known: &BTreeSet<&str>) -> String {
+        let literal = format!("'{}'", self.name);
+        let payload = || match &self.body {
+            Body::Fields(_) => self.body.inline_object(None, generics, known),
+            Body::Tuple(elements) if elements.len() == 1 => elements[0].to_ts(generics, known),
+            Body::Tuple(elements) => Ty::Tuple(elements.clone()).to_ts(generics, known),
+            Body::Unit => "null".to_string(),
+        };
+        match (tagging, &self.body) {
+            (Tagging::External, Body::Unit) => literal,
+            (Tagging::External, _) => {
+                format!("{{ {}: {} }}", property_name(&self.name), payload())
+            }
+            (Tagging::Internal(tag), Body::Unit) => {
+                format!("{{ {}: {} }}", property_name(tag), literal)
+            }
+            (Tagging::Internal(tag), Body::Fields(_)) => self.body.inline_object(
+                Some(format!("{}: {}", property_name(tag), literal)),
+                generics,
+                known,
+            ),
+            (Tagging::Internal(tag), Body::Tuple(_)) => {
+                format!(
+                    "({{ {}: {} }} & {})",
+                    property_name(tag),
+                    literal,
+                    payload()
+                )
+            }
+            (Tagging::Adjacent(tag, _), Body::Unit) => {
+                format!("{{ {}: {} }}", property_name(tag), literal)
+            }
+            (Tagging::Adjacent(tag, content), _) => format!(
+                "{{ {}: {}; {}: {} }}",
+                property_name(tag),
+                literal,
+                property_name(content),
+                payload()
+            ),
+            (Tagging::Untagged, _) => payload(),
+        }
+    }
+}
+
+struct Command {
+    name: String,
+    docs: Vec<String>,
+    /// Rust parameter names and types, injected ones left out
+    params: Vec<(String, Ty)>,
+    response: Ty,
+}
+
+impl Command {
+    /// Parse the signature of the function whose `fn` keyword is at `i`
+    fn parse(tokens: &[Token], i: usize, docs: Vec<String>) -> Option<Command> {
+        let Some(Token::Ident(name)) = tokens.get(i + 1) else {
+            return None;
+        };
+        let mut open = i + 2;
+        while open < tokens.len() && !tokens[open].is_punct('(') {
+            open += 1;
+        }
+        let close = group_end(tokens, open);
+        let params = split_commas(tokens.get(open + 1..close)?, true)
+            .into_iter()
+            .filter_map(|param| {
+                let colon = param.iter().position(|t| t.is_punct(':'))?;
+                let Some(Token::Ident(name)) = param[..colon].last() else {
+                    return None;
+                };
+                let ty = Ty::parse(&param[colon + 1..]);
+                let injected =
+                    matches!(&ty, Ty::Path(ty, _) if INJECTED_PARAMS.contains(&ty.as_str()));
+                (!injected).then(|| (name.clone(), ty))
+            })
+            .collect();
+
+        let has_return = tokens.get(close + 1).is_some_and(|t| t.is_punct('-'))
+            && tokens.get(close + 2).is_some_and(|t| t.is_punct('>'));
+        let response = if has_return {
+            match Ty::parse(&tokens[close + 3..]) {
+                Ty::Path(name, mut args) if name == "Result" && !args.is_empty() => {
+                    args.swap_remove(0)
+                }
+                ty => ty,
+            }
+        } else {
+            Ty::Tuple(Vec::new())
+        };
+
+        Some(Command {
+            name: name.clone(),
+            docs,
+            params,
+            response,
+        })
+    }
+}
+
+struct SourceFile {
+    tokens: Vec<Token>,
+    models: Vec<Model>,
+    commands: Vec<Command>,
+    /// Name and unwrapped return type of every function, for tracing event payloads
+    functions: Vec<(String, Ty)>,
+    /// Positions of `emit_event` calls
+    emits: Vec<usize>,
+}
+
+impl SourceFile {
+    fn parse(text: &str) -> SourceFile {
+        let mut file = SourceFile {
+            tokens: tokenize(text),
+            models: Vec::new(),
+            commands: Vec::new(),
+            functions: Vec::new(),
+            emits: Vec::new(),
+        };
+        let tokens = &file.tokens;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let preamble = Preamble::parse(tokens, &mut i);
+            skip_visibility(tokens, &mut i);
+            while tokens
+                .get(i)
+                .is_some_and(|t| t.is_ident("async") || t.is_ident("unsafe"))
+            {
+                i += 1;
+            }
+            match tokens.get(i) {
+                Some(Token::Ident(keyword))
+                    if (keyword == "struct" || keyword == "enum") && preamble.derives_serde() =>
+                {
+                    let start = i;
+                    match Model::parse(tokens, &mut i, preamble) {
+                        Some(model) => file.models.push(model),
+                        None => i = start + 1,
+                    }
+                }
+                Some(Token::Ident(keyword)) if keyword == "mod" && preamble.is_test_only() => {
+                    while i < tokens.len() && !matches!(tokens[i], Token::Punct('{' | ';')) {
+                        i += 1;
+                    }
+                    if tokens.get(i).is_some_and(|t| t.is_punct('{')) {
+                        i = group_end(tokens, i);
+                    }
+                    i += 1;
+                }
+                Some(Token::Ident(keyword)) if keyword == "fn" => {
+                    let is_command = preamble.has("tauri::command");
+                    if let Some(function) = Command::parse(tokens, i, preamble.docs) {
+                        file.functions
+                            .push((function.name.clone(), function.response.clone()));
+                        if is_command {
+                            file.commands.push(function);
+                        }
+                    }
+                    i += 1;
+                }
+                Some(Token::Ident(ident)) if ident == "emit_event" => {
+                    file.emits.push(i);
+                    i += 1;
+                }
+                Some(_) => i += 1,
+                None => {}
+            }
+        }
+        file
+    }
+
+    /// Event names and payload types of the file's `emit_event(app, "name", payload)` calls
+    fn events(&self, functions: &HashMap<&str, &Ty>) -> Vec<(String, Ty)> {
+        let tokens = &self.tokens;
+        // Functions of this file shadow same-named ones elsewhere
+        let mut functions = functions.clone();
+        functions.extend(self.functions.iter().map(|(name, ty)| (name.as_str(), ty)));
+        let functions = &functions;
+        self.emits
+            .iter()
+            .filter(|call| tokens.get(*call + 1).is_some_and(|t| t.is_punct('(')))
+            .filter_map(|call| {
+                let close = group_end(tokens, call + 1);
+                let args = split_commas(&tokens[call + 2..close], false);
+                let [_, [Token::Str(event)], payload, ..] = args.as_slice() else {
+                    return None;
+                };
+                Some((
+                    event.clone(),
+                    payload_type(tokens, *call, payload, functions),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Best-effort type of an event payload expression: struct literals and constructors
+/// name their type, function calls have their return type, and variables are traced
+/// back to their annotation, `let` or `for` binding
+fn payload_type(
+    tokens: &[Token],
+    position: usize,
+    expression: &[Token],
+    functions: &HashMap<&str, &Ty>,
+) -> Ty {
+    let expression: Vec<&Token> = expression
+        .iter()
+        .skip_while(|t| t.is_punct('&'))
+        .filter(|t| !t.is_ident("await"))
+        .collect();
+    // `json!` and other macros
+    if expression.iter().any(|t| t.is_punct('!')) {
+        return Ty::Unknown;
+    }
+    let path: Vec<&str> = expression
+        .iter()
+        .take_while(|t| matches!(t, Token::Ident(_) | Token::Punct(':')))
+        .filter_map(|t| match t {
+            Token::Ident(ident) => Some(ident.as_str()),
+            _ => None,
+        })
+        .collect();
+    if let Some(ty) = path
+        .iter()
+        .rev()
+        .find(|segment| segment.starts_with(|c: char| c.is_ascii_uppercase()))
+    {
+        return Ty::Path(ty.to_string(), Vec::new());
+    }
+    // `{ ..; tail }` and `match scrutinee { Ok(value) => value, .. }`
+    let owned: Vec<Token> = expression.iter().map(|t| (*t).clone()).collect();
+    if owned.first().is_some_and(|t| t.is_punct('{')) {
+        let end = group_end(&owned, 0);
+        let tail = statements(&owned[1..end]).pop().unwrap_or_default();
+        return payload_type(tokens, position, tail, functions);
+    }
+    if owned.first().is_some_and(|t| t.is_ident("match")) {
+        let brace = owned
+            .iter()
+            .position(|t| t.is_punct('{'))
+            .unwrap_or(owned.len());
+        return payload_type(tokens, position, &owned[1..brace], functions);
+    }
+    let [Token::Ident(name), rest @ ..] = expression.as_slice() else {
+        return Ty::Unknown;
+    };
+
+    // `function(..)`, `function(..)?`
+    if rest.first().is_some_and(|t| t.is_punct('(')) {
+        let returned = functions
+            .get(name.as_str())
+            .map_or(Ty::Unknown, |ty| (*ty).clone());
+        return match returned {
+            Ty::Path(wrapper, mut args)
+                if wrapper == "Option" && rest.last().is_some_and(|t| t.is_punct('?')) =>
+            {
+                args.pop().unwrap_or(Ty::Unknown)
+            }
+            ty => ty,
+        };
+    }
+
+    // `var`, `var.clone()`
+    let is_variable = matches!(
+        rest,
+        [] | [
+            Token::Punct('.'),
+            Token::Ident(_),
+            Token::Punct('('),
+            Token::Punct(')')
+        ]
+    );
+    if !is_variable {
+        return Ty::Unknown;
+    }
+    for j in (0..position).rev() {
+        if !tokens[j].is_ident(name) {
+            continue;
+        }
+        let next = tokens.get(j + 1);
+        let previous = j.checked_sub(1).map(|k| &tokens[k]);
+        // Parameters and annotated lets; struct literal fields hold expressions instead
+        if next.is_some_and(|t| t.is_punct(':'))
+            && !tokens.get(j + 2).is_some_and(|t| t.is_punct(':'))
+        {
+            let end = tokens[j + 2..]
+                .iter()
+                .position(|t| matches!(t, Token::Punct(',' | ')' | '=' | ';')))
+                .map_or(tokens.len(), |offset| j + 2 + offset);
+            let ty = Ty::parse(&tokens[j + 2..end]);
+            if matches!(&ty, Ty::Path(name, _) if name.starts_with(|c: char| c.is_ascii_uppercase()))
+            {
+                return ty;
+            }
+        } else if next.is_some_and(|t| t.is_punct('='))
+            && previous.is_some_and(|t| t.is_ident("let") || t.is_ident("mut"))
+        {
+            let value = statements(&tokens[j + 2..]).swap_remove(0);
+            return payload_type(tokens, j, value, functions);
+        } else if next.is_some_and(|t| t.is_punct(')'))
+            && j >= 2
+            && tokens[j - 1].is_punct('(')
+            && (tokens[j - 2].is_ident("Ok") || tokens[j - 2].is_ident("Some"))
+        {
+            // `if let Some(value) = scrutinee {`, or an arm of the closest `match`
+            // enclosing the binding
+            let brace_after = |k: usize| {
+                tokens[k..]
+                    .iter()
+                    .position(|t| t.is_punct('{'))
+                    .map_or(tokens.len(), |offset| k + offset)
+            };
+            let is_if_let = tokens.get(j + 2).is_some_and(|t| t.is_punct('='))
+                && !tokens.get(j + 3).is_some_and(|t| t.is_punct('>'));
+            let scrutinee = if is_if_let {
+                Some((j, j + 3, brace_after(j + 3)))
+            } else {
+                (0..j)
+                    .rev()
+                    .find(|&k| tokens[k].is_ident("match") && group_end(tokens, brace_after(k)) > j)
+                    .map(|k| (k, k + 1, brace_after(k)))
+            };
+            if let Some((k, start, end)) = scrutinee {
+                let ty = payload_type(tokens, k, &tokens[start..end], functions);
+                return match ty {
+                    Ty::Path(wrapper, mut args)
+                        if wrapper == "Option" && tokens[j - 2].is_ident("Some") =>
+                    {
+                        args.pop().unwrap_or(Ty::Unknown)
+                    }
+                    ty => ty,
+                };
+            }
+        } else if next.is_some_and(|t| t.is_ident("in"))
+            && previous.is_some_and(|t| t.is_ident("for"))
+        {
+            let end = tokens[j + 2..]
+                .iter()
+                .position(|t| t.is_punct('{'))
+                .map_or(tokens.len(), |offset| j + 2 + offset);
+            return match payload_type(tokens, j, &tokens[j + 2..end], functions) {
+                Ty::Path(collection, mut args)
+                    if matches!(
+                        collection.as_str(),
+                        "Vec" | "VecDeque" | "HashSet" | "BTreeSet"
+                    ) =>
+                {
+                    args.pop().unwrap_or(Ty::Unknown)
+                }
+                Ty::Array(element) => *element,
+                _ => Ty::Unknown,
+            };
+        }
+    }
+    Ty::Unknown
+}
+
+/// Split at semicolons outside any brackets; always returns at least one statement
+fn statements(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut statements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth -= 1,
+            Token::Punct(';') if depth == 0 => {
+                statements.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        if depth < 0 {
+            break;
+        }
+    }
+    if start < tokens.len() || statements.is_empty() {
+        statements.push(&tokens[start..]);
+    }
+    statements
+}
+
+/// Command names listed in `generate_handler!`
+fn registered_commands(tokens: &[Token]) -> BTreeSet<String> {
+    let Some(start) = tokens.iter().position(|t| t.is_ident("generate_handler")) else {
+        return BTreeSet::new();
+    };
+    let open = start + 2;
+    let close = group_end(tokens, open);
+    split_commas(&tokens[open + 1..close], false)
+        .into_iter()
+        .filter_map(|path| match path.last() {
+            Some(Token::Ident(name)) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+// ============================================================================
+// Naming
+// ============================================================================
+
+/// Apply a serde `rename_all` rule to a field (snake_case) or variant (PascalCase) name
+fn rename(name: &str, rule: Option<&str>, is_variant: bool) -> String {
+    let snake = if is_variant {
+        let mut snake = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    } else {
+        name.to_string()
+    };
+    match rule {
+        Some("lowercase") if is_variant => name.to_ascii_lowercase(),
+        Some("UPPERCASE") if is_variant => name.to_ascii_uppercase(),
+        Some("lowercase") => snake.to_ascii_lowercase(),
+        Some("UPPERCASE") => snake.to_ascii_uppercase(),
+        Some("camelCase") => camel_case(&snake),
+        Some("PascalCase") => {
+            let camel = camel_case(&snake);
+            camel[..1].to_ascii_uppercase() + &camel[1..]
+        }
+        Some("snake_case") => snake,
+        Some("SCREAMING_SNAKE_CASE") => snake.to_ascii_uppercase(),
+        Some("kebab-case") => snake.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => snake.replace('_', "-").to_ascii_uppercase(),
+        _ => name.to_string(),
+    }
+}
+
+/// `snake_case` to `camelCase`, as serde and Tauri's argument names do it
+fn camel_case(snake: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    for c in snake.chars() {
+        if c == '_' {
+            upper = !camel.is_empty();
+        } else if upper {
+            camel.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+/// Quote names that aren't valid TypeScript identifiers
+fn property_name(name: &str) -> String {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if valid {
+        name.to_string()
+    } else {
+        format!("'{}'", name)
+    }
+}
+
+fn push_docs(docs: &[String], indent: &str, out: &mut String) {
+    let docs: Vec<String> = docs.iter().map(|doc| doc.replace("*/", "*\\/")).collect();
+    match docs.as_slice() {
+        [] => {}
+        [doc] => out.push_str(&format!("{}/** {} */\n", indent, doc)),
+        docs => {
+            out.push_str(&format!("{}/**\n", indent));
+            for doc in docs {
+                if doc.is_empty() {
+                    out.push_str(&format!("{} *\n", indent));
+                } else {
+                    out.push_str(&format!("{} * {}\n", indent, doc));
+                }
+            }
+            out.push_str(&format!("{} */\n", indent));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str) -> String {
+        let file = SourceFile::parse(source);
+        let known: BTreeSet<&str> = file
+            .models
+            .iter()
+            .map(|model| model.name.as_str())
+            .collect();
+        let mut out = String::new();
+        for model in &file.models {
+            model.render(&known, &mut out);
+        }
+        out
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize(
+            "/// Docs\n// plain\n/* block /* nested */ */ r#\"raw \"str\"\"# 'a' 'static x: 1.5",
+        );
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Doc("Docs".to_string()),
+                Token::Str("raw \"str\"".to_string()),
+                Token::Literal,
+                Token::Lifetime,
+                Token::Ident("x".to_string()),
+                Token::Punct(':'),
+                Token::Literal,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_to_ts() {
+        let ts = |source: &str| Ty::parse(&tokenize(source)).to_ts(&[], &BTreeSet::new());
+        assert_eq!(ts("Option<Vec<String>>"), "string[] | null");
+        assert_eq!(
+            ts("std::collections::HashMap<String, u64>"),
+            "Record<string, number>"
+        );
+        assert_eq!(ts("&'a [PathBuf]"), "string[]");
+        assert_eq!(ts("(String, bool)"), "[string, boolean]");
+        assert_eq!(ts("Vec<Option<u8>>"), "(number | null)[]");
+        assert_eq!(ts("Box<dyn Error>"), "unknown");
+        assert_eq!(ts("()"), "null");
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let out = render(
+            r#"
+            /// A run
+            #[derive(Serialize, Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            pub struct RunInfo {
+                pub run_id: String,
+                /// Exit code
+                pub exit_code: Option<i32>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub note: Option<String>,
+                #[serde(default)]
+                pub simulate: bool,
+                #[serde(rename = "type")]
+                pub kind: String,
+                #[serde(skip)]
+                pub secret: String,
+            }
+            "#,
+        );
+        assert_eq!(
+            out,
+            "/** A run */\n\
+             export interface RunInfo {\n  \
+             runId: string;\n  \
+             /** Exit code */\n  \
+             exitCode?: number | null;\n  \
+             note?: string;\n  \
+             simulate?: boolean;\n  \
+             type: string;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_container_default_and_flatten() {
+        let out = render(
+            r#"
+            #[derive(Serialize, Deserialize, Default)]
+            #[serde(default)]
+            struct Limits { max_runs: u32 }
+
+            #[derive(Serialize)]
+            struct Wrapped { name: String, #[serde(flatten)] limits: Limits }
+            "#,
+        );
+        assert!(out.contains("export interface Limits {\n  max_runs?: number;\n}"));
+        assert!(out.contains("export type Wrapped = {\n  name: string;\n} & Limits;"));
+    }
+
+    #[test]
+    fn test_enum_tagging() {
+        let out = render(
+            r#"
+            #[derive(Serialize)]
+            #[serde(rename_all = "snake_case")]
+            enum Status { NeedsSetup, Ready }
+
+            #[derive(Serialize)]
+            #[serde(tag = "kind", rename_all = "lowercase")]
+            enum Sink { File { path: String }, Cloud }
+
+            #[derive(Serialize)]
+            #[serde(tag = "type", content = "data")]
+            enum Message { Text(String), Empty }
+
+            #[derive(Serialize)]
+            #[serde(untagged)]
+            enum Value { Number(f64), Flag(bool) }
+
+            #[derive(Serialize)]
+            enum Shape { Circle(f64), Point }
+            "#,
+        );
+        assert!(out.contains("export type Status =\n  | 'needs_setup'\n  | 'ready';"));
+        assert!(out.contains("  | { kind: 'file'; path: string }\n  | { kind: 'cloud' };"));
+        assert!(out.contains("  | { type: 'Text'; data: string }\n  | { type: 'Empty' };"));
+        assert!(out.contains("export type Value =\n  | number\n  | boolean;"));
+        assert!(out.contains("  | { Circle: number }\n  | 'Point';"));
+    }
+
+    #[test]
+    fn test_commands_and_test_modules() {
+        let file = SourceFile::parse(
+            r#"
+            /// Start a run
+            #[tauri::command]
+            pub async fn start_run(
+                app: AppHandle,
+                state: State<'_, Store>,
+                spec: RunSpec,
+                profile_name: Option<String>,
+            ) -> Result<ApiResponse<RunResult>, String> { todo!() }
+
+            fn helper() -> u32 { 1 }
+
+            #[cfg(test)]
+            mod tests {
+                #[derive(Serialize)]
+                struct Fixture { id: u32 }
+            }
+            "#,
+        );
+        assert!(file.models.is_empty());
+        let [command] = file.commands.as_slice() else {
+            panic!("expected one command");
+        };
+        assert_eq!(command.name, "start_run");
+        assert_eq!(command.docs, vec!["Start a run".to_string()]);
+        let params: Vec<&str> = command
+            .params
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(params, vec!["spec", "profile_name"]);
+        assert_eq!(
+            command.response,
+            Ty::Path(
+                "ApiResponse".to_string(),
+                vec![Ty::Path("RunResult".to_string(), Vec::new())]
+            )
+        );
+        assert_eq!(file.functions.len(), 2);
+    }
+
+    #[test]
+    fn test_event_payloads() {
+        let file = SourceFile::parse(
+            r#"
+            fn progress() -> Option<Progress> { None }
+
+            fn run(app: &AppHandle, event: LogEvent, runs: Vec<RunResult>) {
+                emit_event(app, "log-event", event.clone());
+                emit_event(app, "started", RunStarted { id: 1 });
+                let snapshot = QueueSnapshot::new();
+                emit_event(app, "queue", &snapshot);
+                if let Some(progress) = progress() {
+                    emit_event(app, "progress", progress);
+                }
+                for run in runs {
+                    emit_event(app, "finished", run);
+                }
+                emit_event(app, "raw", json!({ "a": 1 }));
+            }
+            "#,
+        );
+        let functions = HashMap::new();
+        let events: Vec<(String, String)> = file
+            .events(&functions)
+            .into_iter()
+            .map(|(name, ty)| (name, format!("{:?}", ty)))
+            .collect();
+        let path = |name: &str| format!("{:?}", Ty::Path(name.to_string(), Vec::new()));
+        assert_eq!(
+            events,
+            vec![
+                ("log-event".to_string(), path("LogEvent")),
+                ("started".to_string(), path("RunStarted")),
+                ("queue".to_string(), path("QueueSnapshot")),
+                ("progress".to_string(), path("Progress")),
+                ("finished".to_string(), path("RunResult")),
+                ("raw".to_string(), format!("{:?}", Ty::Unknown)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registered_commands() {
+        let tokens = tokenize(
+            "builder.invoke_handler(tauri::generate_handler![commands::start_run, stop_run,])",
+        );
+        let registered: Vec<String> = registered_commands(&tokens).into_iter().collect();
+        assert_eq!(registered, vec!["start_run", "stop_run"]);
+    }
+
+    #[test]
+    fn test_naming() {
+        assert_eq!(
+            rename("NeedsSetup", Some("snake_case"), true),
+            "needs_setup"
+        );
+        assert_eq!(
+            rename("NeedsSetup", Some("kebab-case"), true),
+            "needs-setup"
+        );
+        assert_eq!(rename("max_runs", Some("camelCase"), false), "maxRuns");
+        assert_eq!(rename("max_runs", Some("PascalCase"), false), "MaxRuns");
+        assert_eq!(camel_case("_private_field"), "privateField");
+        assert_eq!(property_name("run-queue"), "'run-queue'");
+        assert_eq!(property_name("runId"), "runId");
+    }
+
+    /// Drift check: the committed bindings match what the sources generate
+    #[test]
+    fn test_generated_file_is_current() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let expected = render_contract(&manifest_dir.join("src")).unwrap();
+        let current = fs::read_to_string(manifest_dir.join(OUTPUT)).unwrap_or_default();
+        assert!(
+            current == expected,
+            "{} is out of date; run `cargo run --bin gen-ipc-types`",
+            OUTPUT
+        );
+    }
+}
@@ -0,0 +1,6 @@
+//! Scripted ElizaOS CLI stand-in for end-to-end tests (`--features test-harness`)
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(mvp_tauri_eliza_cli_lib::test_harness::run_mock_cli(args))
+}
@@ -2,7 +2,14 @@
 //! Provides headless functionality and CLI-based operations
 
 use tauri_plugin_cli::CliExt;
-use crate::commands::config;
+use crate::commands::{agents, audit, config, doctor, windows};
+use crate::commands::shortcuts::RUN_AGENT_ARG;
+use crate::models::{
+    AuditAction, AuditEntry, AuditOrigin, DoctorCheckStatus, SandboxConfig, WindowKind,
+};
+
+/// Command-line flag that opens secondary windows by label
+const OPEN_WINDOW_ARG: &str = "open-window";
 
 pub async fn handle_cli(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     match app.cli().matches() {
@@ -12,6 +19,29 @@ pub async fn handle_cli(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error
             // For now, just handle basic CLI functionality
             // TODO: Add proper CLI argument parsing when API is clearer
 
+            // Agent shortcuts launch the app with the agent to start
+            if let Some(agent_id) = matches
+                .args
+                .get(RUN_AGENT_ARG)
+                .and_then(|arg| arg.value.as_str())
+            {
+                agents::spawn_agent_launch(app.clone(), agent_id.to_string());
+            }
+
+            // `--open-window terminal` and friends, possibly repeated
+            if let Some(arg) = matches.args.get(OPEN_WINDOW_ARG) {
+                let labels: Vec<&str> = match &arg.value {
+                    serde_json::Value::String(label) => vec![label.as_str()],
+                    serde_json::Value::Array(values) => {
+                        values.iter().filter_map(|v| v.as_str()).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                for label in labels {
+                    open_window_by_label(app, label);
+                }
+            }
+
             // Handle subcommands if available
             if let Some(subcommand) = &matches.subcommand {
                 log::info!("Processing CLI subcommand: {}", subcommand.name);
@@ -32,7 +62,8 @@ pub async fn handle_cli(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error
                     },
                     "terminal" => {
                         println!("💻 Launching terminal mode...");
-                        // Don't exit, allow GUI to launch with terminal focused
+                        // Don't exit, allow GUI to launch with the terminal window open
+                        open_window_by_label(app, WindowKind::Terminal.label());
                     },
                     _ => {
                         eprintln!("Unknown subcommand: {}", subcommand.name);
@@ -52,63 +83,63 @@ pub async fn handle_cli(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error
     }
 }
 
+/// Open a secondary window named on the command line; unknown names are reported, not fatal
+fn open_window_by_label(app: &tauri::AppHandle, label: &str) {
+    match WindowKind::from_label(label) {
+        Some(kind) => {
+            if let Err(e) = windows::open_window_kind(app, kind) {
+                log::error!("Failed to open {} window: {}", label, e);
+            }
+        }
+        None => eprintln!(
+            "Unknown window '{}'; expected log-viewer, terminal or playground",
+            label
+        ),
+    }
+}
+
 /// Run the doctor health check
 async fn run_doctor_check(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Load config and run health checks
-    let config_result = config::load_sandbox_config(app.clone()).await;
-
-    match config_result {
+    // Load config; diagnostics still run without one so CLI problems are reported
+    let config = match config::load_sandbox_config(app.clone(), None).await {
+        Ok(config_response) if config_response.success => {
+            println!("📋 Configuration loaded successfully");
+            config_response.data.unwrap_or_default()
+        }
         Ok(config_response) => {
-            if config_response.success {
-                let config = config_response.data.unwrap_or_default();
-
-                println!("📋 Configuration loaded successfully");
-
-                // Run connection test
-                match config::test_sandbox_connection(config.clone()).await {
-                    Ok(result) => {
-                        if result.success {
-                            if let Some(connection_data) = result.data {
-                                if connection_data.success {
-                                    let latency = connection_data.latency_ms.map(|ms| ms.to_string()).unwrap_or_default();
-                                    println!("✅ API Connection: HEALTHY ({}ms)", latency);
-                                } else {
-                                    println!("❌ API Connection: FAILED - {}", connection_data.error.unwrap_or_default());
-                                }
-                            } else {
-                                println!("❌ API Connection: NO DATA");
-                            }
-                        } else {
-                            println!("❌ API Connection: ERROR - {}", result.error.unwrap_or_default().message);
-                        }
-                    }
-                    Err(e) => {
-                        println!("❌ API Connection: ERROR - {}", e);
-                    }
-                }
-            } else {
-                println!("❌ Configuration: NOT LOADED - {}", config_response.error.unwrap_or_default().message);
-            }
+            println!(
+                "❌ Configuration: NOT LOADED - {}",
+                config_response.error.unwrap_or_default().message
+            );
+            SandboxConfig::default()
         }
         Err(e) => {
             println!("❌ Configuration: ERROR - {}", e);
+            SandboxConfig::default()
         }
+    };
+
+    let run_id = crate::models::generate_safe_run_id();
+    let report = doctor::run_doctor_diagnostics(app, &config, &run_id).await;
+
+    let failed = report.overall_status == DoctorCheckStatus::Fail;
+    let entry = AuditEntry::new(AuditAction::RunStarted, AuditOrigin::Cli, run_id.clone())
+        .with_outcome(!failed, Some("doctor".to_string()));
+    audit::record_audit(app, entry).await;
+
+    for check in &report.checks {
+        let icon = match check.status {
+            DoctorCheckStatus::Pass => "✅",
+            DoctorCheckStatus::Warn => "⚠️",
+            DoctorCheckStatus::Fail => "❌",
+            DoctorCheckStatus::Skipped => "⏭️",
+        };
+        println!("{} {}: {} ({}ms)", icon, check.name, check.message, check.duration_ms);
     }
 
-    // Check ElizaOS CLI availability
-    match std::process::Command::new("elizaos").arg("--version").output() {
-        Ok(output) => {
-            if output.status.success() {
-                let version = String::from_utf8_lossy(&output.stdout);
-                println!("✅ ElizaOS CLI: v{}", version.trim());
-            } else {
-                println!("❌ ElizaOS CLI: COMMAND FAILED");
-            }
-        }
-        Err(_) => {
-            println!("❌ ElizaOS CLI: NOT FOUND");
-        }
+    if failed {
+        return Err("one or more checks failed".into());
     }
 
     Ok(())
-}
\ No newline at end of file
+}
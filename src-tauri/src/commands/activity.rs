@@ -0,0 +1,451 @@
+//! Activity feed
+//! One timeline of what happened across runs, the terminal, agent health and the app itself,
+//! so the UI doesn't have to stitch it together from separate events. Entries are recorded
+//! from the events passing through `emit_event` (CLI output lines excluded) and from
+//! terminal commands, numbered in order and stored as JSON lines in the app data directory
+
+use crate::commands::network_capture::redact_text;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    current_timestamp, ActivityEntry, ActivityFeedPage, ActivityKind, ApiResponse, AppError,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const ACTIVITY_FILE: &str = "activity_feed.jsonl";
+/// Entries kept on disk; the file is compacted back to this many once it grows past it
+const MAX_ENTRIES: usize = 5_000;
+const COMPACT_SLACK: usize = 500;
+const DEFAULT_PAGE_LIMIT: usize = 200;
+/// Payloads larger than this are dropped from the entry, keeping only the summary
+const MAX_PAYLOAD_BYTES: usize = 4 * 1024;
+
+/// Sequence and size bookkeeping of the feed file, loaded on first use
+#[derive(Default)]
+struct ActivityWriter {
+    next_seq: Option<u64>,
+    lines: usize,
+}
+
+pub struct ActivityLog {
+    // A std mutex because entries are recorded from the synchronous `emit_event`; it also
+    // keeps concurrent writers from interleaving lines or reusing a sequence number
+    writer: Mutex<ActivityWriter>,
+}
+
+pub type ActivityLogState = Arc<ActivityLog>;
+
+/// Initialize the activity log (called from main)
+pub fn init_activity_log() -> ActivityLogState {
+    Arc::new(ActivityLog {
+        writer: Mutex::new(ActivityWriter::default()),
+    })
+}
+
+/// Activity entries after sequence number `since` (all kept entries when omitted), oldest
+/// first, limited to the newest `limit` and optionally to some kinds
+#[tauri::command]
+pub async fn get_activity_feed(
+    app: AppHandle,
+    since: Option<u64>,
+    kinds: Option<Vec<ActivityKind>>,
+    limit: Option<usize>,
+) -> Result<ApiResponse<ActivityFeedPage>, String> {
+    let result = (|| {
+        let log = app.state::<ActivityLogState>();
+        let _writer = log.writer.lock().unwrap_or_else(|e| e.into_inner());
+        read_entries(&activity_path(&app)?)
+    })();
+
+    match result {
+        Ok(entries) => Ok(ApiResponse::success(feed_page(
+            entries,
+            since,
+            kinds.as_deref(),
+            limit.unwrap_or(DEFAULT_PAGE_LIMIT),
+        ))),
+        Err(e) => {
+            log::error!("Failed to read activity feed: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to read activity feed: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Record the events that belong in the timeline; called for every emitted event
+pub(crate) fn observe_event<S: Serialize>(app: &AppHandle, event: &str, payload: &S) {
+    if !is_tracked(event) {
+        return;
+    }
+    let Ok(payload) = serde_json::to_value(payload) else {
+        return;
+    };
+    if let Some((kind, summary)) = describe_event(event, &payload) {
+        let run_id = payload
+            .get("runId")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        record_activity(app, kind, event, summary, run_id, payload);
+    }
+}
+
+/// Append an entry and announce it as `activity-recorded`; failures are only logged
+pub(crate) fn record_activity(
+    app: &AppHandle,
+    kind: ActivityKind,
+    source: &str,
+    summary: String,
+    run_id: Option<String>,
+    payload: Value,
+) {
+    let Some(log) = app.try_state::<ActivityLogState>() else {
+        return;
+    };
+
+    let entry = {
+        let mut writer = log.writer.lock().unwrap_or_else(|e| e.into_inner());
+        append_entry(app, &mut writer, kind, source, summary, run_id, payload)
+    };
+    match entry {
+        Ok(entry) => emit_event(app, "activity-recorded", entry),
+        Err(e) => log::warn!("Failed to record {} activity: {}", source, e),
+    }
+}
+
+fn append_entry(
+    app: &AppHandle,
+    writer: &mut ActivityWriter,
+    kind: ActivityKind,
+    source: &str,
+    summary: String,
+    run_id: Option<String>,
+    payload: Value,
+) -> Result<ActivityEntry, AppError> {
+    let path = activity_path(app)?;
+    let seq = match writer.next_seq {
+        Some(seq) => seq,
+        None => {
+            let entries = read_entries(&path)?;
+            writer.lines = entries.len();
+            entries.last().map_or(1, |entry| entry.seq + 1)
+        }
+    };
+
+    let entry = ActivityEntry {
+        seq,
+        timestamp: current_timestamp(),
+        kind,
+        source: source.to_string(),
+        summary: redact_text(&summary),
+        run_id,
+        payload: redact_payload(payload),
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    writer.next_seq = Some(seq + 1);
+    writer.lines += 1;
+
+    if writer.lines > MAX_ENTRIES + COMPACT_SLACK {
+        let mut entries = read_entries(&path)?;
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        let mut contents = String::new();
+        for entry in &entries {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        fs::write(&path, contents)?;
+        writer.lines = entries.len();
+    }
+
+    Ok(entry)
+}
+
+fn activity_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+    Ok(app_data_dir.join(ACTIVITY_FILE))
+}
+
+/// Entries of the feed file, skipping lines that don't parse
+fn read_entries(path: &Path) -> Result<Vec<ActivityEntry>, AppError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Skipping malformed activity feed line: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+fn feed_page(
+    entries: Vec<ActivityEntry>,
+    since: Option<u64>,
+    kinds: Option<&[ActivityKind]>,
+    limit: usize,
+) -> ActivityFeedPage {
+    let latest_seq = entries.last().map_or(since.unwrap_or(0), |entry| entry.seq);
+    let mut entries: Vec<ActivityEntry> = entries
+        .into_iter()
+        .filter(|entry| since.is_none_or(|since| entry.seq > since))
+        .filter(|entry| kinds.is_none_or(|kinds| kinds.contains(&entry.kind)))
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    entries.drain(..skip);
+
+    ActivityFeedPage {
+        entries,
+        latest_seq,
+    }
+}
+
+/// Token-shaped strings are redacted; oversized payloads are dropped
+fn redact_payload(payload: Value) -> Value {
+    let text = redact_text(&payload.to_string());
+    if text.len() > MAX_PAYLOAD_BYTES {
+        return Value::Null;
+    }
+    serde_json::from_str(&text).unwrap_or(Value::Null)
+}
+
+fn is_tracked(event: &str) -> bool {
+    matches!(
+        event,
+        "log-event"
+            | "run-retry-suggested"
+            | "watch-event"
+            | "agent-autostart"
+            | "connectivity-changed"
+            | "budget-threshold"
+            | "cloud-deploy-status"
+            | "config-changed"
+            | "app-locked"
+            | "approval-requested"
+            | "approval-resolved"
+            | "sandbox-login"
+            | "sandbox-webhook"
+            | "onboarding-step-completed"
+            | "startup-report"
+    )
+}
+
+/// Kind and one-line summary of a tracked event; None for events not worth an entry
+fn describe_event(event: &str, payload: &Value) -> Option<(ActivityKind, String)> {
+    let text = |key: &str| payload.get(key).and_then(Value::as_str).unwrap_or_default();
+    let number = |key: &str| payload.get(key).and_then(Value::as_u64).unwrap_or_default();
+    let flag = |key: &str| {
+        payload
+            .get(key)
+            .and_then(Value::as_bool)
+            .unwrap_or_default()
+    };
+
+    let described = match event {
+        // CLI output lines carry an offset into the run log; only the app's own notices
+        // about the run (start, exit, queueing, stalls, unexpected egress) belong in the
+        // timeline. Stalls and egress alerts also have their own events, which aren't
+        // tracked so they aren't recorded twice
+        "log-event" => {
+            let app_notice = payload.get("offset").is_none_or(Value::is_null)
+                && matches!(text("logType"), "system" | "info" | "error");
+            if !app_notice {
+                return None;
+            }
+            (ActivityKind::Run, text("message").to_string())
+        }
+        "run-retry-suggested" => (
+            ActivityKind::Run,
+            format!(
+                "Run {} failed on a busy port; {} on port {}",
+                text("runId"),
+                if flag("automatic") {
+                    "retrying"
+                } else {
+                    "retry suggested"
+                },
+                number("suggestedPort")
+            ),
+        ),
+        "watch-event" => (ActivityKind::Run, text("message").to_string()),
+        "agent-autostart" => {
+            let mut summary = format!("Agent {} {}", text("name"), text("state"));
+            if !text("message").is_empty() {
+                summary.push_str(&format!(": {}", text("message")));
+            }
+            (ActivityKind::AgentHealth, summary)
+        }
+        "connectivity-changed" => (
+            ActivityKind::AgentHealth,
+            format!("Sandbox connectivity is now {}", text("state")),
+        ),
+        "budget-threshold" => (
+            ActivityKind::System,
+            format!(
+                "{} {} budget is {}% used",
+                text("period"),
+                text("metric"),
+                number("percent")
+            ),
+        ),
+        "cloud-deploy-status" => (
+            ActivityKind::System,
+            format!("Cloud deploy {}: {}", text("stage"), text("message")),
+        ),
+        "config-changed" => (
+            ActivityKind::System,
+            format!("Configuration profile {} {}", text("profile"), text("kind")),
+        ),
+        "app-locked" => (
+            ActivityKind::System,
+            format!("App locked ({})", text("reason")),
+        ),
+        "approval-requested" => (
+            ActivityKind::System,
+            format!(
+                "Approval requested for '{}': {}",
+                text("command"),
+                text("reason")
+            ),
+        ),
+        "approval-resolved" => (
+            ActivityKind::System,
+            format!("Approval for '{}' resolved", text("command")),
+        ),
+        "sandbox-login" => (
+            ActivityKind::System,
+            format!("Sandbox login for {}: {}", text("profile"), text("status")),
+        ),
+        "sandbox-webhook" => (
+            ActivityKind::System,
+            format!("Sandbox webhook received: {}", text("eventType")),
+        ),
+        "onboarding-step-completed" => (
+            ActivityKind::System,
+            format!("Onboarding step completed: {}", text("step")),
+        ),
+        "startup-report" => {
+            let interrupted = payload
+                .get("interruptedRuns")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            (
+                ActivityKind::System,
+                format!(
+                    "App started; {} run(s) were interrupted in the previous session",
+                    interrupted
+                ),
+            )
+        }
+        _ => return None,
+    };
+    Some(described)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LogEvent;
+    use serde_json::json;
+
+    fn entry(seq: u64, kind: ActivityKind) -> ActivityEntry {
+        ActivityEntry {
+            seq,
+            timestamp: current_timestamp(),
+            kind,
+            source: "test".to_string(),
+            summary: String::new(),
+            run_id: None,
+            payload: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_log_events_exclude_cli_output() {
+        let notice = serde_json::to_value(LogEvent::system(
+            "run_1".to_string(),
+            "Process completed successfully".to_string(),
+        ))
+        .unwrap();
+        assert_eq!(
+            describe_event("log-event", &notice),
+            Some((
+                ActivityKind::Run,
+                "Process completed successfully".to_string()
+            ))
+        );
+
+        let output =
+            serde_json::to_value(LogEvent::stdout("run_1".to_string(), "hello".to_string()))
+                .unwrap();
+        assert!(describe_event("log-event", &output).is_none());
+    }
+
+    #[test]
+    fn test_describe_health_events() {
+        let autostart = json!({ "name": "Eliza", "state": "failed", "message": "exit code 1" });
+        assert_eq!(
+            describe_event("agent-autostart", &autostart),
+            Some((
+                ActivityKind::AgentHealth,
+                "Agent Eliza failed: exit code 1".to_string()
+            ))
+        );
+        assert!(!is_tracked("run-progress-line"));
+        assert!(describe_event("kv-changed", &json!({})).is_none());
+    }
+
+    #[test]
+    fn test_feed_page() {
+        let entries = vec![
+            entry(1, ActivityKind::Run),
+            entry(2, ActivityKind::Terminal),
+            entry(3, ActivityKind::Run),
+            entry(4, ActivityKind::System),
+        ];
+
+        let page = feed_page(entries.clone(), Some(2), None, 10);
+        assert_eq!(
+            page.entries.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(page.latest_seq, 4);
+
+        let page = feed_page(entries.clone(), None, Some(&[ActivityKind::Run]), 1);
+        assert_eq!(
+            page.entries.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![3]
+        );
+
+        let page = feed_page(Vec::new(), Some(7), None, 10);
+        assert!(page.entries.is_empty());
+        assert_eq!(page.latest_seq, 7);
+    }
+
+    #[test]
+    fn test_redact_payload() {
+        let payload = redact_payload(json!({ "args": ["--key", "sk-abcdefghijklmnop"] }));
+        assert_eq!(payload, json!({ "args": ["--key", "[REDACTED]"] }));
+        assert_eq!(
+            redact_payload(json!({ "blob": "x".repeat(MAX_PAYLOAD_BYTES) })),
+            Value::Null
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Local agent manager
+//! Saved agent profiles kept in the key-value store under the `agents` namespace; agents
+//! flagged to start on launch are brought up once preflight checks pass
+
+use crate::commands::app_lock::require_unlocked;
+use crate::commands::args::validated_mode_args;
+use crate::commands::config::load_config_from_file;
+use crate::commands::kv::{load_value, update_value};
+use crate::commands::ports::{release_agent_ports, reserve_agent_port};
+use crate::commands::preflight::run_preflight_checks;
+use crate::commands::process::{
+    audit_run, describe_run_for_audit, execute_eliza_run_streaming_with_id,
+};
+use crate::commands::stats::emit_event;
+use crate::models::{
+    current_timestamp, AgentAutostartEvent, AgentAutostartState, AgentProfile, ApiResponse,
+    AppError, AuditAction, PreflightStatus, RunMode, RunStatus, SandboxConfig,
+};
+use crate::path_env::spawn_path_for_app;
+use serde_json::Value;
+use tauri::AppHandle;
+
+const AGENTS_NAMESPACE: &str = "agents";
+const PROFILES_KEY: &str = "profiles";
+const MAX_NAME_LEN: usize = 128;
+
+/// Saved local agents, in the order they were created
+#[tauri::command]
+pub async fn list_agents(app: AppHandle) -> Result<ApiResponse<Vec<AgentProfile>>, String> {
+    match read_profiles(&app).await {
+        Ok(profiles) => Ok(ApiResponse::success(profiles)),
+        Err(e) => Ok(error_response("Failed to list agents", e)),
+    }
+}
+
+/// Create an agent, or replace the one with the same ID
+#[tauri::command]
+pub async fn save_agent(
+    app: AppHandle,
+    profile: AgentProfile,
+) -> Result<ApiResponse<AgentProfile>, String> {
+    let result = async {
+        let mut profile = profile;
+        validate_profile(&profile)?;
+        if profile.id.is_empty() {
+            profile.id = generate_agent_id();
+        }
+        profile.name = profile.name.trim().to_string();
+        profile.updated_at = Some(current_timestamp());
+
+        let saved = profile.clone();
+        update_profiles(&app, move |profiles| {
+            match profiles.iter_mut().find(|p| p.id == saved.id) {
+                Some(existing) => *existing = saved,
+                None => profiles.push(saved),
+            }
+            Ok(())
+        })
+        .await?;
+        Ok::<_, AppError>(profile)
+    }
+    .await;
+
+    match result {
+        Ok(profile) => {
+            log::info!("Saved agent {} ({})", profile.id, profile.name);
+            Ok(ApiResponse::success(profile))
+        }
+        Err(e) => Ok(error_response("Failed to save agent", e)),
+    }
+}
+
+/// Delete a saved agent, returning whether it existed
+#[tauri::command]
+pub async fn remove_agent(app: AppHandle, agent_id: String) -> Result<ApiResponse<bool>, String> {
+    let mut existed = false;
+    let result = update_profiles(&app, |profiles| {
+        let before = profiles.len();
+        profiles.retain(|p| p.id != agent_id);
+        existed = profiles.len() != before;
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(()) => {
+            log::info!("Removed agent {} (existed: {})", agent_id, existed);
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to remove agent", e)),
+    }
+}
+
+/// Turn starting an agent on app launch on or off
+#[tauri::command]
+pub async fn set_agent_start_on_launch(
+    app: AppHandle,
+    agent_id: String,
+    enabled: bool,
+) -> Result<ApiResponse<AgentProfile>, String> {
+    let mut updated = None;
+    let result = update_profiles(&app, |profiles| {
+        let profile = profiles
+            .iter_mut()
+            .find(|p| p.id == agent_id)
+            .ok_or_else(|| AppError::Config(format!("Agent '{}' not found", agent_id)))?;
+        profile.start_on_launch = enabled;
+        profile.updated_at = Some(current_timestamp());
+        updated = Some(profile.clone());
+        Ok(())
+    })
+    .await;
+
+    match (result, updated) {
+        (Ok(()), Some(profile)) => {
+            log::info!("Agent {} start on launch: {}", agent_id, enabled);
+            Ok(ApiResponse::success(profile))
+        }
+        (Err(e), _) => Ok(error_response("Failed to update agent", e)),
+        (Ok(()), None) => Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Agent '{}' not found", agent_id),
+        )),
+    }
+}
+
+/// Start the agents flagged to start on launch; called once from app setup
+pub fn spawn_agent_autostart(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let agents: Vec<AgentProfile> = match read_profiles(&app).await {
+            Ok(profiles) => profiles.into_iter().filter(|p| p.start_on_launch).collect(),
+            Err(e) => {
+                log::warn!("Failed to load agents for autostart: {}", e);
+                return;
+            }
+        };
+        if agents.is_empty() {
+            return;
+        }
+
+        log::info!("Starting {} agent(s) on launch", agents.len());
+        start_agents(&app, agents).await;
+    });
+}
+
+/// Start a saved agent named on the command line, as desktop shortcuts do
+pub fn spawn_agent_launch(app: AppHandle, agent_id: String) {
+    tauri::async_runtime::spawn(async move {
+        let agent = match find_agent(&app, &agent_id).await {
+            Ok(agent) => agent,
+            Err(e) => {
+                log::error!("Failed to launch agent {}: {}", agent_id, e);
+                return;
+            }
+        };
+        // Autostart already brings these up
+        if agent.start_on_launch {
+            log::info!("Agent {} starts on launch; not starting it twice", agent.id);
+            return;
+        }
+
+        log::info!("Launching agent {} from the command line", agent.id);
+        start_agents(&app, vec![agent]).await;
+    });
+}
+
+/// A saved agent by ID
+pub(crate) async fn find_agent(app: &AppHandle, agent_id: &str) -> Result<AgentProfile, AppError> {
+    read_profiles(app)
+        .await?
+        .into_iter()
+        .find(|p| p.id == agent_id)
+        .ok_or_else(|| AppError::Config(format!("Agent '{}' not found", agent_id)))
+}
+
+/// Start agents once the configuration and preflight checks allow it
+async fn start_agents(app: &AppHandle, agents: Vec<AgentProfile>) {
+    for agent in &agents {
+        emit_autostart(
+            app,
+            agent,
+            AgentAutostartState::Pending,
+            None,
+            Some("Waiting for preflight checks".to_string()),
+        );
+    }
+
+    let config = match autostart_environment(app).await {
+        Ok(config) => config,
+        Err(reason) => {
+            log::warn!("Skipping agent autostart: {}", reason);
+            for agent in &agents {
+                emit_autostart(
+                    app,
+                    agent,
+                    AgentAutostartState::Skipped,
+                    None,
+                    Some(reason.clone()),
+                );
+            }
+            return;
+        }
+    };
+
+    for agent in agents {
+        start_agent(app, agent, config.clone()).await;
+    }
+}
+
+/// Saved configuration, once it is valid and preflight checks report ready
+async fn autostart_environment(app: &AppHandle) -> Result<SandboxConfig, String> {
+    require_unlocked(app).map_err(|e| e.to_string())?;
+    let config = load_config_from_file(app)
+        .await
+        .map_err(|e| format!("Failed to load Sandbox configuration: {}", e))?
+        .filter(|config| config.is_valid())
+        .ok_or_else(|| "Sandbox configuration is missing or invalid".to_string())?;
+
+    let path_env = spawn_path_for_app(app).await;
+    let preflight = run_preflight_checks(&path_env)
+        .await
+        .map_err(|e| format!("Preflight checks failed: {}", e))?;
+    if !matches!(preflight.overall_status, PreflightStatus::Ready) {
+        return Err(format!(
+            "Preflight checks did not pass ({:?})",
+            preflight.overall_status
+        ));
+    }
+    Ok(config)
+}
+
+/// Launch one agent's run in the background and report how it went
+async fn start_agent(app: &AppHandle, agent: AgentProfile, config: SandboxConfig) {
+    let run_id = crate::models::generate_safe_run_id();
+    let mut spec = agent.spec.clone();
+
+    let prepared = async {
+        validated_mode_args(&spec, &config.allowed_custom_subcommands())?;
+        if spec.port.is_none() {
+            spec.port = Some(reserve_agent_port(app, &run_id).await?);
+        }
+        Ok::<_, AppError>(())
+    }
+    .await;
+    if let Err(e) = prepared {
+        log::error!("Failed to start agent {} on launch: {}", agent.id, e);
+        release_agent_ports(app, &run_id).await;
+        emit_autostart(
+            app,
+            &agent,
+            AgentAutostartState::Failed,
+            None,
+            Some(e.to_string()),
+        );
+        return;
+    }
+
+    let detail = format!(
+        "autostart {}: {}",
+        agent.name,
+        describe_run_for_audit(&spec)
+    );
+    audit_run(app, AuditAction::RunStarted, &run_id, true, Some(detail)).await;
+    emit_autostart(
+        app,
+        &agent,
+        AgentAutostartState::Started,
+        Some(run_id.clone()),
+        None,
+    );
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        let result =
+            execute_eliza_run_streaming_with_id(app.clone(), spec, config, run_id.clone()).await;
+        release_agent_ports(&app, &run_id).await;
+
+        let failure = match result {
+            Ok(run_result) if run_result.status == RunStatus::Failed => {
+                Some(match run_result.exit_code {
+                    Some(code) => format!("Agent exited with code {}", code),
+                    None => "Agent exited with an error".to_string(),
+                })
+            }
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(message) = failure {
+            log::error!("Agent {} started on launch failed: {}", agent.id, message);
+            emit_autostart(
+                &app,
+                &agent,
+                AgentAutostartState::Failed,
+                Some(run_id),
+                Some(message),
+            );
+        }
+    });
+}
+
+fn emit_autostart(
+    app: &AppHandle,
+    agent: &AgentProfile,
+    state: AgentAutostartState,
+    run_id: Option<String>,
+    message: Option<String>,
+) {
+    emit_event(
+        app,
+        "agent-autostart",
+        AgentAutostartEvent {
+            agent_id: agent.id.clone(),
+            name: agent.name.clone(),
+            state,
+            run_id,
+            message,
+            timestamp: chrono::Utc::now().timestamp(),
+        },
+    );
+}
+
+async fn read_profiles(app: &AppHandle) -> Result<Vec<AgentProfile>, AppError> {
+    parse_profiles(load_value(app, AGENTS_NAMESPACE, PROFILES_KEY).await?)
+}
+
+async fn update_profiles<F>(app: &AppHandle, update: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut Vec<AgentProfile>) -> Result<(), AppError>,
+{
+    update_value(
+        app,
+        AGENTS_NAMESPACE.to_string(),
+        PROFILES_KEY.to_string(),
+        |current| {
+            let mut profiles = parse_profiles(current)?;
+            update(&mut profiles)?;
+            Ok(serde_json::to_value(profiles)?)
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+fn parse_profiles(value: Option<Value>) -> Result<Vec<AgentProfile>, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| AppError::Config(format!("Saved agents are corrupted: {}", e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn validate_profile(profile: &AgentProfile) -> Result<(), AppError> {
+    let name = profile.name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(AppError::Config(format!(
+            "Invalid agent name: use 1-{} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if !matches!(profile.spec.mode, RunMode::Run) {
+        return Err(AppError::Config(format!(
+            "Agents must use run mode, got {} mode",
+            profile.spec.mode
+        )));
+    }
+    Ok(())
+}
+
+fn generate_agent_id() -> String {
+    format!(
+        "agent_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RunSpec;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_profiles() {
+        assert!(parse_profiles(None).unwrap().is_empty());
+
+        let profiles = parse_profiles(Some(json!([{
+            "id": "agent_1",
+            "name": "Ada",
+            "spec": { "id": "spec_1", "mode": "run", "args": [], "env": {} },
+        }])))
+        .unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert!(!profiles[0].start_on_launch);
+
+        assert!(parse_profiles(Some(json!({ "id": "agent_1" }))).is_err());
+    }
+
+    #[test]
+    fn test_validate_profile() {
+        let mut profile = AgentProfile {
+            id: String::new(),
+            name: "Ada".to_string(),
+            spec: RunSpec::new("spec_1".to_string(), RunMode::Run, vec![]),
+            start_on_launch: true,
+            updated_at: None,
+        };
+        assert!(validate_profile(&profile).is_ok());
+
+        profile.name = "  ".to_string();
+        assert!(validate_profile(&profile).is_err());
+
+        profile.name = "Ada".to_string();
+        profile.spec.mode = RunMode::Eval;
+        assert!(validate_profile(&profile).is_err());
+    }
+}
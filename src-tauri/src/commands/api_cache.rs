@@ -0,0 +1,195 @@
+//! Sandbox API response cache
+//! Keeps idempotent Sandbox GET responses for a short TTL, keyed by base URL, API key and
+//! path, so repeated UI refreshes don't hit the API or count against its rate limits
+
+use crate::commands::cloud::send_json;
+use crate::commands::sandbox_http::sandbox_http;
+use crate::models::{ApiResponse, AppError, SandboxConfig};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+/// The model catalog rarely changes
+pub const MODELS_TTL: Duration = Duration::from_secs(300);
+pub const USAGE_TTL: Duration = Duration::from_secs(30);
+pub const CLOUD_AGENTS_TTL: Duration = Duration::from_secs(15);
+
+#[derive(Debug)]
+struct CachedResponse {
+    path: String,
+    value: Value,
+    fetched_at: Instant,
+    ttl: Duration,
+}
+
+impl CachedResponse {
+    fn is_fresh(&self, now: Instant) -> bool {
+        now.duration_since(self.fetched_at) < self.ttl
+    }
+}
+
+/// Cached responses by account and path
+#[derive(Debug, Default)]
+pub struct ApiCache {
+    entries: HashMap<String, CachedResponse>,
+}
+
+impl ApiCache {
+    fn get(&self, key: &str, now: Instant) -> Option<Value> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.is_fresh(now))
+            .map(|entry| entry.value.clone())
+    }
+
+    fn insert(&mut self, key: String, path: &str, value: Value, ttl: Duration, now: Instant) {
+        self.entries.retain(|_, entry| entry.is_fresh(now));
+        self.entries.insert(
+            key,
+            CachedResponse {
+                path: path.to_string(),
+                value,
+                fetched_at: now,
+                ttl,
+            },
+        );
+    }
+
+    /// Drop entries whose path starts with `prefix`, or every entry; returns how many
+    fn invalidate(&mut self, prefix: Option<&str>) -> usize {
+        let before = self.entries.len();
+        match prefix.map(|p| p.trim_start_matches('/')) {
+            Some(prefix) => self
+                .entries
+                .retain(|_, entry| !entry.path.starts_with(prefix)),
+            None => self.entries.clear(),
+        }
+        before - self.entries.len()
+    }
+}
+
+pub type ApiCacheStore = Arc<Mutex<ApiCache>>;
+
+/// Initialize the Sandbox API response cache
+pub fn init_api_cache() -> ApiCacheStore {
+    Arc::new(Mutex::new(ApiCache::default()))
+}
+
+/// Drop cached Sandbox responses under a path (e.g. `agents`), or all of them
+#[tauri::command]
+pub async fn invalidate_api_cache(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<ApiResponse<usize>, String> {
+    let removed = invalidate_cached(&app, path.as_deref()).await;
+    log::info!(
+        "Invalidated {} cached Sandbox response(s) under {}",
+        removed,
+        path.as_deref().unwrap_or("all paths")
+    );
+    Ok(ApiResponse::success(removed))
+}
+
+/// GET a Sandbox API path, answering from the cache while the last response is fresh
+///
+/// `refresh` skips the cached response but still stores the new one.
+pub(crate) async fn cached_get(
+    app: &AppHandle,
+    config: &SandboxConfig,
+    path: &str,
+    ttl: Duration,
+    refresh: bool,
+) -> Result<Value, AppError> {
+    let path = path.trim_start_matches('/');
+    let key = cache_key(config, path);
+    let cache = app.state::<ApiCacheStore>();
+
+    if !refresh {
+        if let Some(value) = cache.lock().await.get(&key, Instant::now()) {
+            log::debug!("Sandbox cache hit for {}", path);
+            return Ok(value);
+        }
+    }
+
+    let client = sandbox_http();
+    let value = send_json(client.get(config.api_url(path)), config).await?;
+    cache
+        .lock()
+        .await
+        .insert(key, path, value.clone(), ttl, Instant::now());
+    Ok(value)
+}
+
+/// Drop cached responses after a change that makes them stale
+pub(crate) async fn invalidate_cached(app: &AppHandle, prefix: Option<&str>) -> usize {
+    app.state::<ApiCacheStore>().lock().await.invalidate(prefix)
+}
+
+/// Responses are per account, so the key includes a digest of the API key
+fn cache_key(config: &SandboxConfig, path: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(config.api_key.as_bytes()));
+    format!(
+        "{}|{}|{}",
+        config.base_url.trim_end_matches('/'),
+        &digest[..16],
+        path
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let mut cache = ApiCache::default();
+        let now = Instant::now();
+        cache.insert(
+            "k".to_string(),
+            "models",
+            json!([1]),
+            Duration::from_secs(10),
+            now,
+        );
+
+        assert_eq!(
+            cache.get("k", now + Duration::from_secs(9)),
+            Some(json!([1]))
+        );
+        assert_eq!(cache.get("k", now + Duration::from_secs(10)), None);
+        assert_eq!(cache.get("missing", now), None);
+    }
+
+    #[test]
+    fn test_invalidate_by_prefix() {
+        let mut cache = ApiCache::default();
+        let now = Instant::now();
+        let ttl = Duration::from_secs(60);
+        cache.insert("a".to_string(), "agents", json!([]), ttl, now);
+        cache.insert("b".to_string(), "agents/a1", json!({}), ttl, now);
+        cache.insert("c".to_string(), "models", json!([]), ttl, now);
+
+        assert_eq!(cache.invalidate(Some("/agents")), 2);
+        assert_eq!(cache.get("c", now), Some(json!([])));
+        assert_eq!(cache.invalidate(None), 1);
+    }
+
+    #[test]
+    fn test_cache_key_separates_accounts() {
+        let mut config = SandboxConfig::new(
+            "https://sandbox.example.com/".to_string(),
+            "eliza_key_one".to_string(),
+        );
+        let first = cache_key(&config, "models");
+        assert!(first.starts_with("https://sandbox.example.com|"));
+        assert!(!first.contains("eliza_key_one"));
+
+        config.api_key = "eliza_key_two".to_string();
+        assert_ne!(cache_key(&config, "models"), first);
+    }
+}
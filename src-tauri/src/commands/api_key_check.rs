@@ -0,0 +1,194 @@
+//! API key check
+//! Unlike the connection test, which only asks whether the Sandbox is reachable, this calls
+//! an endpoint that requires authentication and reports why a key was refused
+
+use crate::commands::config::validate_api_key as is_well_formed_key;
+use crate::commands::sandbox_http::sandbox_http;
+use crate::models::{current_timestamp, ApiKeyCheck, ApiKeyStatus, ApiResponse, SandboxConfig};
+use reqwest::header::WWW_AUTHENTICATE;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+/// Any endpoint that refuses anonymous callers will do; the model list is cheap
+const CHECK_PATH: &str = "models";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check the API key of `config` against the Sandbox; the outcome is in `status`, so
+/// refusals come back as successful responses the UI can branch on
+#[tauri::command]
+pub async fn validate_api_key(config: SandboxConfig) -> Result<ApiResponse<ApiKeyCheck>, String> {
+    let endpoint = config.api_url(CHECK_PATH);
+    let check = |status: ApiKeyStatus,
+                 message: String,
+                 http_status: Option<u16>,
+                 latency_ms: Option<u64>| ApiKeyCheck {
+        status,
+        message,
+        endpoint: endpoint.clone(),
+        http_status,
+        latency_ms,
+        checked_at: current_timestamp(),
+    };
+
+    if !is_well_formed_key(&config.api_key) {
+        return Ok(ApiResponse::success(check(
+            ApiKeyStatus::Invalid,
+            "The API key is not in the expected format ('eliza_' followed by 64 characters)"
+                .to_string(),
+            None,
+            None,
+        )));
+    }
+
+    let start = Instant::now();
+    let request = sandbox_http()
+        .get(&endpoint)
+        .timeout(CHECK_TIMEOUT)
+        .bearer_auth(&config.api_key);
+    let response = sandbox_http().send(request).await;
+    let latency_ms = Some(start.elapsed().as_millis() as u64);
+
+    let result = match response {
+        Ok(response) => {
+            let http_status = response.status();
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let body = response.json::<Value>().await.unwrap_or_default();
+            let (status, message) = classify(http_status, challenge.as_deref(), &body);
+            check(status, message, Some(http_status.as_u16()), latency_ms)
+        }
+        Err(e) => {
+            let message = if e.is_timeout() {
+                "The Sandbox did not answer in time".to_string()
+            } else if e.is_connect() {
+                "Failed to connect - check your internet connection and base URL".to_string()
+            } else {
+                format!("Network error: {}", e)
+            };
+            check(ApiKeyStatus::NetworkError, message, None, latency_ms)
+        }
+    };
+
+    log::info!(
+        "API key check against {}: {:?}",
+        result.endpoint,
+        result.status
+    );
+    Ok(ApiResponse::success(result))
+}
+
+/// Status of the key from the response; expiry is told apart from other refusals by the
+/// error code or description in the body or the `WWW-Authenticate` challenge
+fn classify(status: StatusCode, challenge: Option<&str>, body: &Value) -> (ApiKeyStatus, String) {
+    if status.is_success() {
+        return (ApiKeyStatus::Valid, "The API key is valid".to_string());
+    }
+
+    let detail = error_detail(body);
+    let mentions_expiry = [challenge, detail.as_deref()]
+        .into_iter()
+        .flatten()
+        .any(|text| text.to_ascii_lowercase().contains("expired"));
+    let with_detail = |message: &str| match detail {
+        Some(ref detail) => format!("{}: {}", message, detail),
+        None => message.to_string(),
+    };
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if mentions_expiry => (
+            ApiKeyStatus::Expired,
+            with_detail("The API key has expired"),
+        ),
+        StatusCode::UNAUTHORIZED => (
+            ApiKeyStatus::Invalid,
+            with_detail("The Sandbox does not recognize this API key"),
+        ),
+        StatusCode::FORBIDDEN => (
+            ApiKeyStatus::InsufficientPermissions,
+            with_detail("The API key is not allowed to use the Sandbox API"),
+        ),
+        _ => (
+            ApiKeyStatus::Unexpected,
+            with_detail(&format!(
+                "The Sandbox answered HTTP {} {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown")
+            )),
+        ),
+    }
+}
+
+/// Error text from bodies like `{"error": "..."}`, `{"error": {"code": ..., "message": ...}}`
+/// or `{"message": "..."}`
+fn error_detail(body: &Value) -> Option<String> {
+    let error = body.get("error");
+    let parts: Vec<&str> = [
+        error.and_then(Value::as_str),
+        error.and_then(|e| e.get("code")).and_then(Value::as_str),
+        error.and_then(|e| e.get("message")).and_then(Value::as_str),
+        body.get("code").and_then(Value::as_str),
+        body.get("error_description").and_then(Value::as_str),
+        body.get("message").and_then(Value::as_str),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !part.trim().is_empty())
+    .collect();
+
+    (!parts.is_empty()).then(|| parts.join(" - "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify_statuses() {
+        let none = Value::Null;
+        assert_eq!(classify(StatusCode::OK, None, &none).0, ApiKeyStatus::Valid);
+        assert_eq!(
+            classify(StatusCode::UNAUTHORIZED, None, &none).0,
+            ApiKeyStatus::Invalid
+        );
+        assert_eq!(
+            classify(StatusCode::FORBIDDEN, None, &none).0,
+            ApiKeyStatus::InsufficientPermissions
+        );
+        assert_eq!(
+            classify(StatusCode::BAD_GATEWAY, None, &none).0,
+            ApiKeyStatus::Unexpected
+        );
+    }
+
+    #[test]
+    fn test_classify_expiry() {
+        let (status, message) = classify(
+            StatusCode::UNAUTHORIZED,
+            None,
+            &json!({ "error": { "code": "key_expired", "message": "Key expired on 2024-01-01" } }),
+        );
+        assert_eq!(status, ApiKeyStatus::Expired);
+        assert!(message.contains("Key expired on 2024-01-01"));
+
+        let challenge = r#"Bearer error="invalid_token", error_description="The token expired""#;
+        assert_eq!(
+            classify(StatusCode::UNAUTHORIZED, Some(challenge), &Value::Null).0,
+            ApiKeyStatus::Expired
+        );
+    }
+
+    #[test]
+    fn test_error_detail() {
+        assert_eq!(
+            error_detail(&json!({ "error": "forbidden", "message": "Missing scope agents:read" }))
+                .as_deref(),
+            Some("forbidden - Missing scope agents:read")
+        );
+        assert!(error_detail(&json!({ "data": [] })).is_none());
+    }
+}
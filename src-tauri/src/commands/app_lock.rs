@@ -0,0 +1,537 @@
+//! App lock
+//! When enabled, the Sandbox API key and local secrets move into a vault encrypted with a
+//! key derived from the user's passphrase (see `crate::crypto`). Commands that need them fail
+//! with `APP_LOCKED` until `unlock_app` succeeds, and the vault locks itself again after a
+//! period without use
+
+use crate::commands::audit::record_audit;
+use crate::commands::config::{read_plaintext_api_keys, write_plaintext_api_keys, DEFAULT_PROFILE};
+use crate::commands::secrets::{
+    read_plaintext_secrets, remove_plaintext_secrets, write_plaintext_secrets,
+};
+use crate::commands::stats::emit_event;
+use crate::crypto::{decode_hex, encode_hex, DerivedKey, KDF_ITERATIONS, SALT_LEN};
+use crate::models::{ApiResponse, AppError, AppLockStatus, AuditAction, AuditEntry, AuditOrigin};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const LOCK_FILE: &str = "app-lock.json";
+const VAULT_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 8;
+const DEFAULT_AUTO_LOCK_MINUTES: u32 = 15;
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Encrypted vault as stored on disk; byte fields are hex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    iterations: u32,
+    /// Minutes without use before locking; `0` never locks automatically
+    auto_lock_minutes: u32,
+    nonce: String,
+    /// Sealed contents, ending with the authentication tag
+    ciphertext: String,
+}
+
+/// What the vault protects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VaultContents {
+    /// API key of the default configuration profile
+    #[serde(default)]
+    pub api_key: String,
+    /// API keys of the other configuration profiles
+    #[serde(default)]
+    pub profile_api_keys: BTreeMap<String, String>,
+    #[serde(default)]
+    pub secrets: BTreeMap<String, String>,
+}
+
+impl VaultContents {
+    /// API key of a configuration profile; empty when none is stored
+    pub fn api_key(&self, profile: &str) -> &str {
+        if profile == DEFAULT_PROFILE {
+            &self.api_key
+        } else {
+            self.profile_api_keys
+                .get(profile)
+                .map_or("", |api_key| api_key.as_str())
+        }
+    }
+
+    /// Store a profile's API key; an empty key removes a named profile's entry
+    pub fn set_api_key(&mut self, profile: &str, api_key: String) {
+        if profile == DEFAULT_PROFILE {
+            self.api_key = api_key;
+        } else if api_key.is_empty() {
+            self.profile_api_keys.remove(profile);
+        } else {
+            self.profile_api_keys.insert(profile.to_string(), api_key);
+        }
+    }
+
+    /// Every stored API key by profile
+    fn api_keys(&self) -> BTreeMap<String, String> {
+        let mut api_keys = self.profile_api_keys.clone();
+        api_keys.insert(DEFAULT_PROFILE.to_string(), self.api_key.clone());
+        api_keys
+    }
+}
+
+struct Unlocked {
+    /// Key derived from the passphrase, kept to re-seal the vault after changes
+    key: DerivedKey,
+    contents: VaultContents,
+    last_used: Instant,
+}
+
+/// Decrypted vault while the app is unlocked
+#[derive(Default)]
+pub struct AppLock {
+    unlocked: Option<Unlocked>,
+}
+
+pub type AppLockState = Arc<Mutex<AppLock>>;
+
+/// Initialize the app lock state (called from main)
+pub fn init_app_lock() -> AppLockState {
+    Arc::new(Mutex::new(AppLock::default()))
+}
+
+/// Whether the app lock is enabled and currently locked
+#[tauri::command]
+pub async fn get_app_lock_status(app: AppHandle) -> Result<ApiResponse<AppLockStatus>, String> {
+    match lock_status(&app) {
+        Ok(status) => Ok(ApiResponse::success(status)),
+        Err(e) => Ok(error_response("Failed to read app lock status", e)),
+    }
+}
+
+/// Move the API key and local secrets into a vault encrypted with `passphrase`
+///
+/// The app stays unlocked until it is locked or sits unused for `auto_lock_minutes`.
+#[tauri::command]
+pub async fn enable_app_lock(
+    app: AppHandle,
+    passphrase: String,
+    auto_lock_minutes: Option<u32>,
+) -> Result<ApiResponse<AppLockStatus>, String> {
+    let result = async {
+        if is_lock_enabled(&app)? {
+            return Err(AppError::Config("App lock is already enabled".to_string()));
+        }
+        validate_passphrase(&passphrase)?;
+
+        let api_keys = read_plaintext_api_keys(&app)?;
+        let mut contents = VaultContents {
+            secrets: read_plaintext_secrets(&app)?,
+            ..VaultContents::default()
+        };
+        for (profile, api_key) in &api_keys {
+            contents.set_api_key(profile, api_key.clone());
+        }
+        let salt: [u8; SALT_LEN] = rand::random();
+        let key = derive_vault_key(&passphrase, &salt, KDF_ITERATIONS).await?;
+        let auto_lock_minutes = auto_lock_minutes.unwrap_or(DEFAULT_AUTO_LOCK_MINUTES);
+        let vault = seal_vault(&key, &salt, KDF_ITERATIONS, auto_lock_minutes, &contents)?;
+
+        // The plain copies go only once the vault is safely written
+        write_vault(&app, &vault)?;
+        let blank_keys = api_keys.into_keys().map(|p| (p, String::new())).collect();
+        write_plaintext_api_keys(&app, &blank_keys)?;
+        remove_plaintext_secrets(&app)?;
+
+        lock_state(&app).unlocked = Some(Unlocked {
+            key,
+            contents,
+            last_used: Instant::now(),
+        });
+        lock_status(&app)
+    }
+    .await;
+
+    audit_lock_change(&app, AuditAction::AppLockEnabled, &result).await;
+    match result {
+        Ok(status) => {
+            log::info!("App lock enabled");
+            Ok(ApiResponse::success(status))
+        }
+        Err(e) => Ok(error_response("Failed to enable app lock", e)),
+    }
+}
+
+/// Decrypt the vault back into plain files and turn the app lock off
+#[tauri::command]
+pub async fn disable_app_lock(
+    app: AppHandle,
+    passphrase: String,
+) -> Result<ApiResponse<AppLockStatus>, String> {
+    let result = async {
+        let vault = read_vault(&app)?;
+        let (_, contents) = unlock_vault(&vault, &passphrase).await?;
+
+        write_plaintext_api_keys(&app, &contents.api_keys())?;
+        write_plaintext_secrets(&app, &contents.secrets)?;
+        fs::remove_file(lock_path(&app)?)?;
+        lock_state(&app).unlocked = None;
+        lock_status(&app)
+    }
+    .await;
+
+    audit_lock_change(&app, AuditAction::AppLockDisabled, &result).await;
+    match result {
+        Ok(status) => {
+            log::info!("App lock disabled");
+            Ok(ApiResponse::success(status))
+        }
+        Err(e) => Ok(error_response("Failed to disable app lock", e)),
+    }
+}
+
+/// Unlock the vault; required before commands that use the API key or secrets
+#[tauri::command]
+pub async fn unlock_app(
+    app: AppHandle,
+    passphrase: String,
+) -> Result<ApiResponse<AppLockStatus>, String> {
+    let result = async {
+        let vault = read_vault(&app)?;
+        let (key, contents) = unlock_vault(&vault, &passphrase).await?;
+        lock_state(&app).unlocked = Some(Unlocked {
+            key,
+            contents,
+            last_used: Instant::now(),
+        });
+        lock_status(&app)
+    }
+    .await;
+
+    audit_lock_change(&app, AuditAction::AppUnlocked, &result).await;
+    match result {
+        Ok(status) => {
+            log::info!("App unlocked");
+            Ok(ApiResponse::success(status))
+        }
+        Err(e) => Ok(error_response("Failed to unlock app", e)),
+    }
+}
+
+/// Forget the decrypted vault until the next `unlock_app`
+#[tauri::command]
+pub async fn lock_app(app: AppHandle) -> Result<ApiResponse<AppLockStatus>, String> {
+    lock_now(&app, "requested");
+    match lock_status(&app) {
+        Ok(status) => Ok(ApiResponse::success(status)),
+        Err(e) => Ok(error_response("Failed to read app lock status", e)),
+    }
+}
+
+/// Fail with `APP_LOCKED` while the app lock is enabled and locked
+pub(crate) fn require_unlocked(app: &AppHandle) -> Result<(), AppError> {
+    with_vault(app, |_| ()).map(|_| ())
+}
+
+/// Read the vault; `None` when the app lock is off and the plain files are authoritative
+pub(crate) fn with_vault<T>(
+    app: &AppHandle,
+    read: impl FnOnce(&VaultContents) -> T,
+) -> Result<Option<T>, AppError> {
+    if !is_lock_enabled(app)? {
+        return Ok(None);
+    }
+    let mut state = lock_state(app);
+    let unlocked = state.unlocked.as_mut().ok_or_else(locked_error)?;
+    unlocked.last_used = Instant::now();
+    Ok(Some(read(&unlocked.contents)))
+}
+
+/// Change the vault and re-encrypt it; `None` when the app lock is off
+pub(crate) fn update_vault(
+    app: &AppHandle,
+    update: impl FnOnce(&mut VaultContents),
+) -> Result<Option<()>, AppError> {
+    if !is_lock_enabled(app)? {
+        return Ok(None);
+    }
+    let vault = read_vault(app)?;
+    let mut state = lock_state(app);
+    let unlocked = state.unlocked.as_mut().ok_or_else(locked_error)?;
+
+    let mut contents = unlocked.contents.clone();
+    update(&mut contents);
+    let salt = decode_hex(&vault.salt).ok_or_else(corrupted_vault)?;
+    write_vault(
+        app,
+        &seal_vault(
+            &unlocked.key,
+            &salt,
+            vault.iterations,
+            vault.auto_lock_minutes,
+            &contents,
+        )?,
+    )?;
+    unlocked.contents = contents;
+    unlocked.last_used = Instant::now();
+    Ok(Some(()))
+}
+
+/// Lock the vault once it has gone unused for the configured time
+pub fn spawn_auto_lock_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(AUTO_LOCK_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Ok(vault) = read_vault(&app) else {
+                continue;
+            };
+            if vault.auto_lock_minutes == 0 {
+                continue;
+            }
+            let timeout = Duration::from_secs(u64::from(vault.auto_lock_minutes) * 60);
+            let idle = lock_state(&app)
+                .unlocked
+                .as_ref()
+                .is_some_and(|unlocked| unlocked.last_used.elapsed() >= timeout);
+            if idle {
+                lock_now(&app, "inactivity");
+            }
+        }
+    });
+}
+
+fn lock_now(app: &AppHandle, reason: &str) {
+    if lock_state(app).unlocked.take().is_some() {
+        log::info!("App locked ({})", reason);
+        emit_event(
+            app,
+            "app-locked",
+            serde_json::json!({
+                "reason": reason,
+                "timestamp": chrono::Utc::now().timestamp(),
+            }),
+        );
+    }
+}
+
+fn lock_status(app: &AppHandle) -> Result<AppLockStatus, AppError> {
+    if !is_lock_enabled(app)? {
+        return Ok(AppLockStatus::default());
+    }
+    let vault = read_vault(app)?;
+    Ok(AppLockStatus {
+        enabled: true,
+        locked: lock_state(app).unlocked.is_none(),
+        auto_lock_minutes: Some(vault.auto_lock_minutes),
+    })
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), AppError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::Config(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn locked_error() -> AppError {
+    AppError::Locked("Unlock the app to use the API key and secrets".to_string())
+}
+
+async fn audit_lock_change<T>(app: &AppHandle, action: AuditAction, result: &Result<T, AppError>) {
+    let entry = AuditEntry::new(action, AuditOrigin::Gui, LOCK_FILE.to_string())
+        .with_outcome(result.is_ok(), result.as_ref().err().map(|e| e.to_string()));
+    record_audit(app, entry).await;
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+fn lock_state(app: &AppHandle) -> std::sync::MutexGuard<'_, AppLock> {
+    app.state::<AppLockState>()
+        .inner()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+// ============================================================================
+// Vault encryption
+// ============================================================================
+
+/// Derive the vault key from `passphrase` on the blocking thread pool
+async fn derive_vault_key(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: u32,
+) -> Result<DerivedKey, AppError> {
+    DerivedKey::derive_blocking(passphrase.as_bytes().to_vec(), salt.to_vec(), iterations).await
+}
+
+fn seal_vault(
+    key: &DerivedKey,
+    salt: &[u8],
+    iterations: u32,
+    auto_lock_minutes: u32,
+    contents: &VaultContents,
+) -> Result<VaultFile, AppError> {
+    let sealed = key.seal(salt, &serde_json::to_vec(contents)?);
+
+    Ok(VaultFile {
+        version: VAULT_VERSION,
+        salt: encode_hex(salt),
+        iterations,
+        auto_lock_minutes,
+        nonce: encode_hex(&sealed.nonce),
+        ciphertext: encode_hex(&sealed.ciphertext),
+    })
+}
+
+/// Derive the key from `passphrase` and decrypt; a stored iteration count outside the
+/// accepted bounds is refused before any derivation runs
+async fn unlock_vault(
+    vault: &VaultFile,
+    passphrase: &str,
+) -> Result<(DerivedKey, VaultContents), AppError> {
+    check_vault_version(vault)?;
+    let salt = decode_hex(&vault.salt).ok_or_else(corrupted_vault)?;
+    let key = derive_vault_key(passphrase, &salt, vault.iterations)
+        .await
+        .map_err(|e| AppError::Config(format!("App lock vault is corrupted: {}", e)))?;
+    let contents = open_vault(vault, &key)?;
+    Ok((key, contents))
+}
+
+/// Decrypt the vault; a wrong passphrase fails authentication
+fn open_vault(vault: &VaultFile, key: &DerivedKey) -> Result<VaultContents, AppError> {
+    check_vault_version(vault)?;
+    let salt = decode_hex(&vault.salt).ok_or_else(corrupted_vault)?;
+    let nonce = decode_hex(&vault.nonce).ok_or_else(corrupted_vault)?;
+    let ciphertext = decode_hex(&vault.ciphertext).ok_or_else(corrupted_vault)?;
+
+    let data = key
+        .open(&nonce, &salt, &ciphertext)
+        .ok_or_else(|| AppError::Locked("Incorrect passphrase".to_string()))?;
+    serde_json::from_slice(&data).map_err(|_| corrupted_vault())
+}
+
+fn check_vault_version(vault: &VaultFile) -> Result<(), AppError> {
+    if vault.version != VAULT_VERSION {
+        return Err(AppError::Config(format!(
+            "Unsupported app lock vault version {}",
+            vault.version
+        )));
+    }
+    Ok(())
+}
+
+fn corrupted_vault() -> AppError {
+    AppError::Config("App lock vault is corrupted".to_string())
+}
+
+fn lock_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(LOCK_FILE))
+}
+
+fn is_lock_enabled(app: &AppHandle) -> Result<bool, AppError> {
+    Ok(lock_path(app)?.exists())
+}
+
+fn read_vault(app: &AppHandle) -> Result<VaultFile, AppError> {
+    let path = lock_path(app)?;
+    if !path.exists() {
+        return Err(AppError::Config("App lock is not enabled".to_string()));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_vault(app: &AppHandle, vault: &VaultFile) -> Result<(), AppError> {
+    let path = lock_path(app)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(vault)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents() -> VaultContents {
+        VaultContents {
+            api_key: "eliza_key".to_string(),
+            profile_api_keys: BTreeMap::from([(
+                "staging".to_string(),
+                "eliza_staging".to_string(),
+            )]),
+            secrets: BTreeMap::from([("OPENAI_API_KEY".to_string(), "sk-test".to_string())]),
+        }
+    }
+
+    fn vault_file(key: &DerivedKey, iterations: u32) -> VaultFile {
+        seal_vault(key, &[7u8; SALT_LEN], iterations, 15, &contents()).unwrap()
+    }
+
+    #[test]
+    fn test_vault_round_trip() {
+        let key = DerivedKey::for_tests(b"correct horse", &[7u8; SALT_LEN]);
+        let vault = vault_file(&key, KDF_ITERATIONS);
+        assert!(!vault.ciphertext.contains(&encode_hex(b"sk-test")));
+
+        let opened = open_vault(&vault, &key).unwrap();
+        assert_eq!(opened.api_key("default"), "eliza_key");
+        assert_eq!(opened.api_key("staging"), "eliza_staging");
+        assert_eq!(opened.api_key("production"), "");
+        assert_eq!(opened.secrets["OPENAI_API_KEY"], "sk-test");
+
+        let wrong = DerivedKey::for_tests(b"wrong horse", &[7u8; SALT_LEN]);
+        let err = open_vault(&vault, &wrong).err().unwrap();
+        assert_eq!(err.error_code(), "APP_LOCKED");
+    }
+
+    #[test]
+    fn test_tampered_vault_is_rejected() {
+        let key = DerivedKey::for_tests(b"passphrase", &[7u8; SALT_LEN]);
+        let mut vault = vault_file(&key, KDF_ITERATIONS);
+        let flipped = if vault.ciphertext.starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        vault.ciphertext.replace_range(0..1, flipped);
+        assert!(open_vault(&vault, &key).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unlock_refuses_out_of_range_iterations() {
+        let key = DerivedKey::for_tests(b"passphrase", &[7u8; SALT_LEN]);
+        for iterations in [1, u32::MAX] {
+            let vault = vault_file(&key, iterations);
+            let err = unlock_vault(&vault, "passphrase").await.err().unwrap();
+            assert_eq!(err.error_code(), "CONFIG_ERROR");
+        }
+    }
+
+    #[test]
+    fn test_validate_passphrase() {
+        assert!(validate_passphrase("short").is_err());
+        assert!(validate_passphrase("long enough").is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+//! Command approvals
+//! Parks terminal commands that fail the security policy until the user explicitly
+//! allows or denies a one-off execution
+
+use crate::commands::audit::record_audit;
+use crate::commands::stats::emit_event;
+use crate::models::{ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{oneshot, Mutex};
+
+/// How long a parked command waits for a decision before it is denied
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApproval {
+    pub id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub working_dir: String,
+    pub reason: String,
+    pub requested_at: String,
+}
+
+impl PendingApproval {
+    /// Full command line for display and auditing
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(|s| s.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A parked command and the channel its decision is sent on
+pub struct ParkedCommand {
+    approval: PendingApproval,
+    decision: oneshot::Sender<bool>,
+}
+
+// Parked commands awaiting a decision, by approval ID
+pub type ApprovalRegistry = Arc<Mutex<HashMap<String, ParkedCommand>>>;
+
+/// Initialize the approval registry (called from main)
+pub fn init_approval_registry() -> ApprovalRegistry {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Get the approval registry for the app
+pub fn get_approval_registry(app: &AppHandle) -> ApprovalRegistry {
+    app.state::<ApprovalRegistry>().inner().clone()
+}
+
+/// Allow or deny a parked command
+#[tauri::command]
+pub async fn resolve_approval(
+    app: AppHandle,
+    id: String,
+    allow: bool,
+) -> Result<ApiResponse<PendingApproval>, AppError> {
+    let parked = get_approval_registry(&app).lock().await.remove(&id);
+
+    let parked = match parked {
+        Some(parked) => parked,
+        None => {
+            return Ok(ApiResponse::error(
+                "NOT_FOUND".to_string(),
+                format!("Approval {} not found or already resolved", id),
+            ))
+        }
+    };
+
+    log::info!(
+        "Approval {} {} for: {}",
+        id,
+        if allow { "granted" } else { "denied" },
+        parked.approval.command_line()
+    );
+
+    let action = if allow {
+        AuditAction::ApprovalGranted
+    } else {
+        AuditAction::ApprovalDenied
+    };
+    let entry = AuditEntry::new(action, AuditOrigin::Gui, parked.approval.command_line())
+        .with_outcome(true, Some(parked.approval.reason.clone()));
+    record_audit(&app, entry).await;
+
+    // The waiting command may have timed out in the meantime
+    let _ = parked.decision.send(allow);
+    emit_event(&app, "approval-resolved", &parked.approval);
+
+    Ok(ApiResponse::success(parked.approval))
+}
+
+/// List commands currently waiting for approval
+#[tauri::command]
+pub async fn get_pending_approvals(
+    app: AppHandle,
+) -> Result<ApiResponse<Vec<PendingApproval>>, AppError> {
+    let registry = get_approval_registry(&app);
+    let mut pending: Vec<PendingApproval> = registry
+        .lock()
+        .await
+        .values()
+        .map(|parked| parked.approval.clone())
+        .collect();
+    pending.sort_by(|a, b| a.requested_at.cmp(&b.requested_at));
+    Ok(ApiResponse::success(pending))
+}
+
+/// Park a command, emit `approval-requested` and wait for the user's decision
+///
+/// Returns false when the command is denied or no decision arrives in time.
+pub(crate) async fn request_approval(
+    app: &AppHandle,
+    command: &str,
+    args: &[String],
+    working_dir: &str,
+    reason: &str,
+) -> bool {
+    let approval = PendingApproval {
+        id: generate_approval_id(),
+        command: command.to_string(),
+        args: args.to_vec(),
+        working_dir: working_dir.to_string(),
+        reason: reason.to_string(),
+        requested_at: crate::models::current_timestamp(),
+    };
+    let (sender, receiver) = oneshot::channel();
+
+    let registry = get_approval_registry(app);
+    registry.lock().await.insert(
+        approval.id.clone(),
+        ParkedCommand {
+            approval: approval.clone(),
+            decision: sender,
+        },
+    );
+
+    log::info!(
+        "Awaiting approval {} for: {}",
+        approval.id,
+        approval.command_line()
+    );
+    emit_event(app, "approval-requested", &approval);
+
+    match tokio::time::timeout(APPROVAL_TIMEOUT, receiver).await {
+        Ok(Ok(allow)) => allow,
+        Ok(Err(_)) => false,
+        Err(_) => {
+            log::warn!("Approval {} timed out", approval.id);
+            registry.lock().await.remove(&approval.id);
+            emit_event(app, "approval-resolved", &approval);
+            false
+        }
+    }
+}
+
+fn generate_approval_id() -> String {
+    format!(
+        "approval_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line() {
+        let approval = PendingApproval {
+            id: "approval_1".to_string(),
+            command: "rm".to_string(),
+            args: vec!["-rf".to_string(), "node_modules".to_string()],
+            working_dir: "/tmp".to_string(),
+            reason: "blocked".to_string(),
+            requested_at: crate::models::current_timestamp(),
+        };
+        assert_eq!(approval.command_line(), "rm -rf node_modules");
+    }
+}
@@ -0,0 +1,374 @@
+//! Typed ElizaOS CLI arguments
+//! Each run mode has its own argument struct that renders to CLI tokens, so adding
+//! a flag means adding a field rather than editing a token list by hand
+
+use crate::models::{AppError, EvalSpec, RunMode, RunSpec, SandboxConfig};
+
+/// Renders a typed argument struct into CLI tokens
+pub trait ToCliArgs {
+    fn to_cli_args(&self) -> Vec<String>;
+}
+
+/// Accumulates CLI tokens so flag rendering rules live in one place
+#[derive(Debug, Default)]
+struct ArgList(Vec<String>);
+
+impl ArgList {
+    fn positional(mut self, value: &str) -> Self {
+        self.0.push(value.to_string());
+        self
+    }
+
+    /// `--flag value`, omitted when the value is absent
+    fn option<T: ToString>(mut self, flag: &str, value: Option<T>) -> Self {
+        if let Some(value) = value {
+            self.0.push(flag.to_string());
+            self.0.push(value.to_string());
+        }
+        self
+    }
+
+    fn passthrough(mut self, extra: &[String]) -> Self {
+        self.0.extend(extra.iter().cloned());
+        self
+    }
+
+    fn build(self) -> Vec<String> {
+        self.0
+    }
+}
+
+/// Doctor runs are diagnosed in-process; the CLI is only asked for its version
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DoctorArgs;
+
+impl ToCliArgs for DoctorArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        ArgList::default().positional("--version").build()
+    }
+}
+
+/// `elizaos start` - run an agent server
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartArgs {
+    pub character: Option<String>,
+    pub port: Option<u16>,
+    pub extra: Vec<String>,
+}
+
+impl ToCliArgs for StartArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        ArgList::default()
+            .positional("start")
+            .option("--character", self.character.as_deref())
+            .option("--port", self.port)
+            .passthrough(&self.extra)
+            .build()
+    }
+}
+
+/// `elizaos scenario run` - evaluate a scenario and write a JSON report
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalArgs {
+    pub scenario_path: String,
+    pub iterations: Option<u32>,
+    pub report_path: String,
+    pub character: Option<String>,
+    pub extra: Vec<String>,
+}
+
+impl EvalArgs {
+    pub fn from_eval_spec(eval: &EvalSpec, run_id: &str) -> Self {
+        Self {
+            scenario_path: eval.scenario_path.clone(),
+            iterations: eval.iterations,
+            report_path: eval.report_path_for(run_id),
+            character: None,
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl ToCliArgs for EvalArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        ArgList::default()
+            .positional("scenario")
+            .positional("run")
+            .positional(&self.scenario_path)
+            .option("--iterations", self.iterations)
+            .option("--output", Some(&self.report_path))
+            .option("--character", self.character.as_deref())
+            .passthrough(&self.extra)
+            .build()
+    }
+}
+
+/// An arbitrary ElizaOS subcommand; defaults to `--help`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomArgs {
+    pub subcommand: Option<String>,
+    pub character: Option<String>,
+    pub extra: Vec<String>,
+}
+
+impl CustomArgs {
+    /// Reject subcommands outside the allowlist
+    pub fn validate(&self, allowed: &[String]) -> Result<(), AppError> {
+        let subcommand = self.subcommand.as_deref().unwrap_or("--help");
+
+        if allowed.iter().any(|a| a == subcommand) {
+            Ok(())
+        } else {
+            Err(AppError::Policy(format!(
+                "Subcommand '{}' is not allowed in custom runs (allowed: {})",
+                subcommand,
+                allowed.join(", ")
+            )))
+        }
+    }
+}
+
+impl ToCliArgs for CustomArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        ArgList::default()
+            .positional(self.subcommand.as_deref().unwrap_or("--help"))
+            .option("--character", self.character.as_deref())
+            .passthrough(&self.extra)
+            .build()
+    }
+}
+
+/// Arguments for a run, typed by mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModeArgs {
+    Doctor(DoctorArgs),
+    Start(StartArgs),
+    Eval(EvalArgs),
+    Custom(CustomArgs),
+}
+
+impl ModeArgs {
+    /// Build typed arguments from a run specification
+    pub fn from_spec(spec: &RunSpec) -> Result<Self, AppError> {
+        let character = spec.character_file.clone();
+
+        match spec.mode {
+            RunMode::Doctor => Ok(ModeArgs::Doctor(DoctorArgs)),
+            RunMode::Run => {
+                // Legacy specs pass the character file as the first positional arg
+                let (character, extra) = match (character, spec.args.split_first()) {
+                    (Some(character), _) => (Some(character), spec.args.clone()),
+                    (None, Some((first, rest))) if !first.starts_with('-') => {
+                        (Some(first.clone()), rest.to_vec())
+                    }
+                    (None, _) => (None, spec.args.clone()),
+                };
+                Ok(ModeArgs::Start(StartArgs {
+                    character,
+                    port: spec.port,
+                    extra,
+                }))
+            }
+            RunMode::Eval => {
+                let eval = spec.eval.as_ref().ok_or_else(|| {
+                    AppError::Eval(
+                        "Eval runs require an eval spec with a scenario path".to_string(),
+                    )
+                })?;
+                let mut args = EvalArgs::from_eval_spec(eval, &spec.id);
+                args.character = character;
+                args.extra = spec.args.clone();
+                Ok(ModeArgs::Eval(args))
+            }
+            RunMode::Custom => {
+                let (subcommand, extra) = match spec.args.split_first() {
+                    Some((first, rest)) => (Some(first.clone()), rest.to_vec()),
+                    None => (None, Vec::new()),
+                };
+                Ok(ModeArgs::Custom(CustomArgs {
+                    subcommand,
+                    character,
+                    extra,
+                }))
+            }
+        }
+    }
+}
+
+/// Build typed arguments for a run and enforce the Custom-mode allowlist
+///
+/// `allowed_subcommands` comes from `custom_allowlist`, never straight from the config
+/// sent with a run request.
+pub fn validated_mode_args(
+    spec: &RunSpec,
+    allowed_subcommands: &[String],
+) -> Result<ModeArgs, AppError> {
+    let args = ModeArgs::from_spec(spec)?;
+
+    if let ModeArgs::Custom(ref custom) = args {
+        custom.validate(allowed_subcommands)?;
+    }
+
+    Ok(args)
+}
+
+/// Subcommands Custom runs may invoke: the saved config's allowlist, which the config sent
+/// with a run request can narrow but never widen
+pub fn custom_allowlist(saved: &SandboxConfig, requested: &SandboxConfig) -> Vec<String> {
+    let allowed = saved.allowed_custom_subcommands();
+    match requested.allowed_custom_subcommands {
+        Some(ref narrowed) => allowed
+            .into_iter()
+            .filter(|subcommand| narrowed.contains(subcommand))
+            .collect(),
+        None => allowed,
+    }
+}
+
+impl ToCliArgs for ModeArgs {
+    fn to_cli_args(&self) -> Vec<String> {
+        match self {
+            ModeArgs::Doctor(args) => args.to_cli_args(),
+            ModeArgs::Start(args) => args.to_cli_args(),
+            ModeArgs::Eval(args) => args.to_cli_args(),
+            ModeArgs::Custom(args) => args.to_cli_args(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(mode: RunMode, args: &[&str]) -> RunSpec {
+        RunSpec::new(
+            "run_1".to_string(),
+            mode,
+            args.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_doctor_args() {
+        let args = ModeArgs::from_spec(&spec(RunMode::Doctor, &["--verbose"])).unwrap();
+        assert_eq!(args.to_cli_args(), vec!["--version"]);
+    }
+
+    #[test]
+    fn test_start_args_legacy_character() {
+        let args =
+            ModeArgs::from_spec(&spec(RunMode::Run, &["eliza.json", "--port", "3001"])).unwrap();
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["start", "--character", "eliza.json", "--port", "3001"]
+        );
+    }
+
+    #[test]
+    fn test_start_args_character_file() {
+        let mut run_spec = spec(RunMode::Run, &["--port", "3001"]);
+        run_spec.character_file = Some("agent.json".to_string());
+
+        let args = ModeArgs::from_spec(&run_spec).unwrap();
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["start", "--character", "agent.json", "--port", "3001"]
+        );
+    }
+
+    #[test]
+    fn test_start_args_assigned_port() {
+        let mut run_spec = spec(RunMode::Run, &[]);
+        run_spec.character_file = Some("agent.json".to_string());
+        run_spec.port = Some(3002);
+
+        let args = ModeArgs::from_spec(&run_spec).unwrap();
+        assert_eq!(
+            args.to_cli_args(),
+            vec!["start", "--character", "agent.json", "--port", "3002"]
+        );
+    }
+
+    #[test]
+    fn test_eval_args() {
+        let run_spec = spec(RunMode::Eval, &[]).with_eval(
+            EvalSpec::new("scenarios/greeting.yaml".to_string())
+                .with_iterations(3)
+                .with_report_path("/tmp/report.json".to_string()),
+        );
+
+        let args = ModeArgs::from_spec(&run_spec).unwrap();
+        assert_eq!(
+            args.to_cli_args(),
+            vec![
+                "scenario",
+                "run",
+                "scenarios/greeting.yaml",
+                "--iterations",
+                "3",
+                "--output",
+                "/tmp/report.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eval_args_require_spec() {
+        assert!(ModeArgs::from_spec(&spec(RunMode::Eval, &[])).is_err());
+    }
+
+    #[test]
+    fn test_custom_args() {
+        let args = ModeArgs::from_spec(&spec(RunMode::Custom, &["plugins", "list"])).unwrap();
+        assert_eq!(args.to_cli_args(), vec!["plugins", "list"]);
+
+        let args = ModeArgs::from_spec(&spec(RunMode::Custom, &[])).unwrap();
+        assert_eq!(args.to_cli_args(), vec!["--help"]);
+    }
+
+    #[test]
+    fn test_custom_args_allowlist() {
+        let allowed = SandboxConfig::default().allowed_custom_subcommands();
+
+        assert!(
+            validated_mode_args(&spec(RunMode::Custom, &["plugins", "list"]), &allowed).is_ok()
+        );
+        assert!(validated_mode_args(&spec(RunMode::Custom, &[]), &allowed).is_ok());
+
+        let err = validated_mode_args(&spec(RunMode::Custom, &["publish"]), &allowed).unwrap_err();
+        assert_eq!(err.error_code(), "POLICY_VIOLATION");
+
+        let allowed = vec!["publish".to_string()];
+        assert!(validated_mode_args(&spec(RunMode::Custom, &["publish"]), &allowed).is_ok());
+        assert!(validated_mode_args(&spec(RunMode::Custom, &["plugins"]), &allowed).is_err());
+    }
+
+    #[test]
+    fn test_request_config_cannot_widen_saved_allowlist() {
+        let saved = SandboxConfig {
+            allowed_custom_subcommands: Some(vec!["plugins".to_string()]),
+            ..SandboxConfig::default()
+        };
+        let requested = SandboxConfig {
+            allowed_custom_subcommands: Some(vec!["plugins".to_string(), "publish".to_string()]),
+            ..SandboxConfig::default()
+        };
+
+        let allowed = custom_allowlist(&saved, &requested);
+        assert_eq!(allowed, vec!["plugins"]);
+        let err = validated_mode_args(&spec(RunMode::Custom, &["publish"]), &allowed).unwrap_err();
+        assert_eq!(err.error_code(), "POLICY_VIOLATION");
+
+        // Leaving the allowlist out of the request keeps the saved one
+        assert_eq!(
+            custom_allowlist(&saved, &SandboxConfig::default()),
+            vec!["plugins"]
+        );
+        // A request may still narrow it
+        let narrowed = SandboxConfig {
+            allowed_custom_subcommands: Some(Vec::new()),
+            ..SandboxConfig::default()
+        };
+        assert!(custom_allowlist(&saved, &narrowed).is_empty());
+    }
+}
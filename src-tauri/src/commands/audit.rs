@@ -0,0 +1,247 @@
+//! Audit log
+//! Append-only record of privileged backend actions, stored as JSON lines in the app data directory
+
+use crate::models::{ApiResponse, AppError, AuditEntry, AuditRange};
+use chrono::{DateTime, Duration, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+const AUDIT_LOG_FILE: &str = "audit_log.jsonl";
+
+// Serializes writers so concurrent actions never interleave lines
+pub type AuditLogLock = Arc<Mutex<()>>;
+
+/// Initialize the audit log lock (called from main)
+pub fn init_audit_log() -> AuditLogLock {
+    Arc::new(Mutex::new(()))
+}
+
+/// Get entries from the audit log, oldest first, optionally limited to a time range
+#[tauri::command]
+pub async fn get_audit_log(
+    app: AppHandle,
+    range: Option<AuditRange>,
+) -> Result<ApiResponse<Vec<AuditEntry>>, String> {
+    let range = range.unwrap_or_default();
+
+    match read_audit_log(&app).await {
+        Ok(entries) => Ok(ApiResponse::success(filter_entries(entries, &range))),
+        Err(e) => {
+            log::error!("Failed to read audit log: {}", e);
+            Ok(ApiResponse::error(
+                "AUDIT_ERROR".to_string(),
+                format!("Failed to read audit log: {}", e),
+            ))
+        }
+    }
+}
+
+/// Append an entry to the audit log; failures are logged and never block the action
+pub async fn record_audit(app: &AppHandle, entry: AuditEntry) {
+    log::debug!("Audit: {:?} {}", entry.action, entry.subject);
+
+    if let Err(e) = append_entry(app, &entry).await {
+        log::warn!("Failed to write audit log entry: {}", e);
+    }
+}
+
+/// Drop entries older than the retention window, returning how many were removed
+pub async fn apply_audit_retention(
+    app: &AppHandle,
+    retention_days: u32,
+) -> Result<usize, AppError> {
+    let lock = app.state::<AuditLogLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let path = get_audit_log_path(app)?;
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let entries = parse_entries(&fs::read_to_string(&path)?);
+    let cutoff = Utc::now() - Duration::days(retention_days as i64);
+    let kept = retain_since(entries.clone(), cutoff);
+    let removed = entries.len() - kept.len();
+
+    if removed > 0 {
+        fs::write(&path, serialize_entries(&kept)?)?;
+        log::info!(
+            "Pruned {} audit log entries older than {} days",
+            removed,
+            retention_days
+        );
+    }
+
+    Ok(removed)
+}
+
+fn get_audit_log_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(AUDIT_LOG_FILE))
+}
+
+async fn append_entry(app: &AppHandle, entry: &AuditEntry) -> Result<(), AppError> {
+    let lock = app.state::<AuditLogLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_audit_log_path(app)?)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+pub(crate) async fn read_audit_log(app: &AppHandle) -> Result<Vec<AuditEntry>, AppError> {
+    let lock = app.state::<AuditLogLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let path = get_audit_log_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(parse_entries(&fs::read_to_string(path)?))
+}
+
+/// Parse JSON lines, skipping any line that is not a valid entry
+fn parse_entries(contents: &str) -> Vec<AuditEntry> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Skipping malformed audit log line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn serialize_entries(entries: &[AuditEntry]) -> Result<String, AppError> {
+    let mut contents = String::new();
+    for entry in entries {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    Ok(contents)
+}
+
+fn entry_time(entry: &AuditEntry) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&entry.timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Keep entries inside the range, then the most recent `limit` of them
+fn filter_entries(entries: Vec<AuditEntry>, range: &AuditRange) -> Vec<AuditEntry> {
+    let bound = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|time| time.with_timezone(&Utc))
+    };
+    let since = bound(&range.since);
+    let until = bound(&range.until);
+
+    let mut entries: Vec<AuditEntry> = entries
+        .into_iter()
+        .filter(|entry| match entry_time(entry) {
+            Some(time) => since.is_none_or(|s| time >= s) && until.is_none_or(|u| time <= u),
+            None => since.is_none() && until.is_none(),
+        })
+        .collect();
+
+    if let Some(limit) = range.limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+
+    entries
+}
+
+/// Entries at or after the cutoff; unparseable timestamps are kept rather than lost
+fn retain_since(entries: Vec<AuditEntry>, cutoff: DateTime<Utc>) -> Vec<AuditEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry_time(entry).is_none_or(|time| time >= cutoff))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AuditAction, AuditOrigin};
+
+    fn entry_at(timestamp: &str, subject: &str) -> AuditEntry {
+        let mut entry = AuditEntry::new(
+            AuditAction::TerminalCommand,
+            AuditOrigin::Gui,
+            subject.to_string(),
+        );
+        entry.timestamp = timestamp.to_string();
+        entry
+    }
+
+    #[test]
+    fn test_parse_entries_round_trip() {
+        let entries = vec![
+            entry_at("2024-01-01T00:00:00+00:00", "git status"),
+            entry_at("2024-01-02T00:00:00+00:00", "npm install"),
+        ];
+        let contents = serialize_entries(&entries).unwrap() + "not json\n";
+
+        let parsed = parse_entries(&contents);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].subject, "npm install");
+    }
+
+    #[test]
+    fn test_filter_entries_by_range() {
+        let entries = vec![
+            entry_at("2024-01-01T00:00:00+00:00", "a"),
+            entry_at("2024-01-02T00:00:00+00:00", "b"),
+            entry_at("2024-01-03T00:00:00+00:00", "c"),
+        ];
+
+        let range = AuditRange {
+            since: Some("2024-01-02T00:00:00Z".to_string()),
+            until: None,
+            limit: None,
+        };
+        let subjects: Vec<_> = filter_entries(entries.clone(), &range)
+            .into_iter()
+            .map(|e| e.subject)
+            .collect();
+        assert_eq!(subjects, vec!["b", "c"]);
+
+        let range = AuditRange {
+            limit: Some(1),
+            ..AuditRange::default()
+        };
+        assert_eq!(filter_entries(entries, &range)[0].subject, "c");
+    }
+
+    #[test]
+    fn test_retain_since() {
+        let entries = vec![
+            entry_at("2020-01-01T00:00:00+00:00", "old"),
+            entry_at(&crate::models::current_timestamp(), "new"),
+        ];
+        let kept = retain_since(entries, Utc::now() - Duration::days(30));
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].subject, "new");
+    }
+}
@@ -0,0 +1,406 @@
+//! Command authorization
+//! Every IPC call passes through `authorized` before reaching its command. The operator
+//! profile can work with runs but not change config, security policies or secrets; going
+//! back to admin needs the password set when the profile was switched to operator
+
+use crate::commands::audit::record_audit;
+use crate::crypto::{decode_hex, encode_hex, DerivedKey, KDF_ITERATIONS, SALT_LEN};
+use crate::models::{
+    ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin, PermissionProfile,
+    PermissionStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Manager, Wry};
+
+const PERMISSIONS_FILE: &str = "permissions.json";
+const MIN_PASSWORD_LEN: usize = 8;
+
+/// Commands the operator profile may not call
+const ADMIN_COMMANDS: &[&str] = &[
+    // Configuration
+    "save_sandbox_config",
+    "clear_sandbox_config",
+    "set_active_profile",
+    "delete_config_profile",
+    "import_sandbox_config",
+    "restore_config_backup",
+    "start_sandbox_login",
+    // Exports can carry the API key
+    "export_sandbox_config",
+    "set_log_level",
+    "save_agent",
+    "remove_agent",
+    "set_agent_start_on_launch",
+    // Security policies
+    "set_allowed_roots",
+    "set_allowed_custom_subcommands",
+    "set_trusted_project_hosts",
+    "enable_app_lock",
+    "disable_app_lock",
+    "resolve_approval",
+    // A shell reaches past every other restriction
+    "execute_terminal_command",
+    // Secrets
+    "set_local_secret",
+    "delete_local_secret",
+    "push_secrets_to_cloud",
+    "import_project_env",
+    "create_support_bundle",
+];
+
+/// Salted hash of the password that switches back to admin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PasswordHash {
+    salt: String,
+    iterations: u32,
+    hash: String,
+}
+
+/// Permission settings as stored in the app data directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionSettings {
+    #[serde(default)]
+    profile: PermissionProfile,
+    #[serde(default)]
+    admin_password: Option<PasswordHash>,
+}
+
+pub type PermissionState = Arc<RwLock<PermissionSettings>>;
+
+/// Initialize the permission settings (called from main); loaded from disk during setup
+pub fn init_permission_state() -> PermissionState {
+    Arc::new(RwLock::new(PermissionSettings::default()))
+}
+
+/// Current profile and the commands it may not call
+#[tauri::command]
+pub async fn get_permission_profile(
+    app: AppHandle,
+) -> Result<ApiResponse<PermissionStatus>, String> {
+    Ok(ApiResponse::success(permission_status(current_profile(
+        &app,
+    ))))
+}
+
+/// Switch profiles
+///
+/// Switching to operator sets `password`; switching back to admin must present it.
+#[tauri::command]
+pub async fn set_permission_profile(
+    app: AppHandle,
+    profile: PermissionProfile,
+    password: Option<String>,
+) -> Result<ApiResponse<PermissionStatus>, String> {
+    let result = async {
+        let state = app.state::<PermissionState>();
+        let current = state.read().unwrap_or_else(|e| e.into_inner()).clone();
+        // Hashing runs off the lock; a switch that lands meanwhile wins
+        let updated = switch_profile(&current, profile, password.as_deref()).await?;
+        let mut settings = state.write().unwrap_or_else(|e| e.into_inner());
+        if settings.profile != current.profile {
+            return Err(AppError::Policy(
+                "The permission profile changed while switching; try again".to_string(),
+            ));
+        }
+        write_settings(&app, &updated)?;
+        *settings = updated;
+        Ok::<_, AppError>(permission_status(settings.profile))
+    }
+    .await;
+
+    let entry = AuditEntry::new(
+        AuditAction::PermissionProfileChanged,
+        AuditOrigin::Gui,
+        profile.as_str().to_string(),
+    )
+    .with_outcome(result.is_ok(), result.as_ref().err().map(|e| e.to_string()));
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok(status) => {
+            log::info!("Permission profile set to {}", profile.as_str());
+            Ok(ApiResponse::success(status))
+        }
+        Err(e) => {
+            log::warn!("Failed to set permission profile: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to set permission profile: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Wrap the command handler so every call is checked against the current profile
+pub fn authorized<F>(handler: F) -> impl Fn(Invoke<Wry>) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke<Wry>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<Wry>| {
+        let command = invoke.message.command().to_string();
+        let app = invoke.message.webview_ref().app_handle().clone();
+        let Some(state) = app.try_state::<PermissionState>() else {
+            return handler(invoke);
+        };
+        let profile = state.read().unwrap_or_else(|e| e.into_inner()).profile;
+
+        match check_command(profile, &command) {
+            Ok(()) => handler(invoke),
+            Err(e) => {
+                log::warn!("Denied {} for the {} profile", command, profile.as_str());
+                invoke.resolver.resolve(ApiResponse::<()>::error(
+                    e.error_code().to_string(),
+                    e.localized_message(),
+                ));
+                tauri::async_runtime::spawn(async move {
+                    let entry =
+                        AuditEntry::new(AuditAction::CommandDenied, AuditOrigin::Gui, command)
+                            .with_outcome(false, Some(format!("{} profile", profile.as_str())));
+                    record_audit(&app, entry).await;
+                });
+                true
+            }
+        }
+    }
+}
+
+/// Restore the profile saved in a previous session
+pub fn load_permission_settings(app: &AppHandle) {
+    match read_settings(app) {
+        Ok(settings) => {
+            *app.state::<PermissionState>()
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = settings;
+        }
+        Err(e) => log::warn!("Failed to load permission settings: {}", e),
+    }
+}
+
+fn current_profile(app: &AppHandle) -> PermissionProfile {
+    app.state::<PermissionState>()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .profile
+}
+
+fn check_command(profile: PermissionProfile, command: &str) -> Result<(), AppError> {
+    if profile == PermissionProfile::Operator && ADMIN_COMMANDS.contains(&command) {
+        return Err(AppError::Policy(format!(
+            "'{}' is not available to the operator profile",
+            command
+        )));
+    }
+    Ok(())
+}
+
+fn permission_status(profile: PermissionProfile) -> PermissionStatus {
+    PermissionStatus {
+        profile,
+        admin_commands: ADMIN_COMMANDS.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+/// Settings after switching to `profile`, checking or setting the admin password
+async fn switch_profile(
+    settings: &PermissionSettings,
+    profile: PermissionProfile,
+    password: Option<&str>,
+) -> Result<PermissionSettings, AppError> {
+    if settings.profile == profile {
+        return Ok(settings.clone());
+    }
+
+    match profile {
+        PermissionProfile::Operator => {
+            let password = password.unwrap_or_default();
+            if password.chars().count() < MIN_PASSWORD_LEN {
+                return Err(AppError::Config(format!(
+                    "An admin password of at least {} characters is required",
+                    MIN_PASSWORD_LEN
+                )));
+            }
+            Ok(PermissionSettings {
+                profile,
+                admin_password: Some(hash_password(password).await?),
+            })
+        }
+        PermissionProfile::Admin => {
+            let verified = match (&settings.admin_password, password) {
+                (Some(hash), Some(password)) => verify_password(hash, password).await?,
+                _ => false,
+            };
+            if !verified {
+                return Err(AppError::Policy("Incorrect admin password".to_string()));
+            }
+            Ok(PermissionSettings {
+                profile,
+                admin_password: None,
+            })
+        }
+    }
+}
+
+/// Hash the admin password on the blocking thread pool
+async fn hash_password(password: &str) -> Result<PasswordHash, AppError> {
+    let salt: [u8; SALT_LEN] = rand::random();
+    let hash =
+        DerivedKey::derive_blocking(password.as_bytes().to_vec(), salt.to_vec(), KDF_ITERATIONS)
+            .await?;
+    Ok(PasswordHash {
+        salt: encode_hex(&salt),
+        iterations: KDF_ITERATIONS,
+        hash: encode_hex(hash.as_bytes()),
+    })
+}
+
+/// Check `password` against the stored hash; a stored iteration count outside the accepted
+/// bounds is refused rather than derived
+async fn verify_password(stored: &PasswordHash, password: &str) -> Result<bool, AppError> {
+    let (Some(salt), Some(expected)) = (decode_hex(&stored.salt), decode_hex(&stored.hash)) else {
+        return Ok(false);
+    };
+    let actual = DerivedKey::derive_blocking(password.as_bytes().to_vec(), salt, stored.iterations)
+        .await
+        .map_err(|e| AppError::Config(format!("Stored admin password is invalid: {}", e)))?;
+    Ok(actual.matches(&expected))
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(PERMISSIONS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> Result<PermissionSettings, AppError> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(PermissionSettings::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_settings(app: &AppHandle, settings: &PermissionSettings) -> Result<(), AppError> {
+    fs::write(settings_path(app)?, serde_json::to_vec_pretty(settings)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operator_is_denied_admin_commands() {
+        assert!(check_command(PermissionProfile::Admin, "save_sandbox_config").is_ok());
+        assert!(check_command(PermissionProfile::Operator, "start_eliza_run").is_ok());
+        assert!(check_command(PermissionProfile::Operator, "stop_eliza_run").is_ok());
+
+        let err = check_command(PermissionProfile::Operator, "save_sandbox_config").unwrap_err();
+        assert_eq!(err.error_code(), "POLICY_VIOLATION");
+    }
+
+    /// Names of registered commands; naming one that does not exist fails to compile
+    macro_rules! commands {
+        ($($command:ident),* $(,)?) => {{
+            $(let _ = crate::commands::$command;)*
+            [$(stringify!($command)),*]
+        }};
+    }
+
+    #[test]
+    fn test_operator_cannot_change_policy_or_secrets() {
+        let privileged = commands![
+            save_sandbox_config,
+            import_sandbox_config,
+            restore_config_backup,
+            export_sandbox_config,
+            set_allowed_custom_subcommands,
+            set_trusted_project_hosts,
+            set_allowed_roots,
+            enable_app_lock,
+            disable_app_lock,
+            resolve_approval,
+            execute_terminal_command,
+            set_local_secret,
+            push_secrets_to_cloud,
+            create_support_bundle,
+        ];
+        for command in privileged {
+            assert!(check_command(PermissionProfile::Admin, command).is_ok());
+            let err = check_command(PermissionProfile::Operator, command).unwrap_err();
+            assert_eq!(err.error_code(), "POLICY_VIOLATION", "{}", command);
+        }
+
+        // Runs carry a config, but only its connection settings are used; queue, watchdog,
+        // egress and allowlist policy comes from the saved profile (`with_saved_policy`)
+        let everyday = commands![
+            start_eliza_run,
+            start_eliza_run_streaming,
+            schedule_runs,
+            start_run_group,
+            start_eval_matrix,
+            load_sandbox_config,
+            unlock_app,
+            lock_app,
+            set_permission_profile,
+        ];
+        for command in everyday {
+            assert!(
+                check_command(PermissionProfile::Operator, command).is_ok(),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_switch_profile_needs_admin_password() {
+        let admin = PermissionSettings::default();
+        assert!(switch_profile(&admin, PermissionProfile::Operator, None)
+            .await
+            .is_err());
+        assert!(
+            switch_profile(&admin, PermissionProfile::Operator, Some("short"))
+                .await
+                .is_err()
+        );
+
+        let operator = switch_profile(&admin, PermissionProfile::Operator, Some("admin password"))
+            .await
+            .unwrap();
+        assert_eq!(operator.profile, PermissionProfile::Operator);
+
+        assert!(switch_profile(&operator, PermissionProfile::Admin, None)
+            .await
+            .is_err());
+        assert!(
+            switch_profile(&operator, PermissionProfile::Admin, Some("wrong password"))
+                .await
+                .is_err()
+        );
+        let restored = switch_profile(&operator, PermissionProfile::Admin, Some("admin password"))
+            .await
+            .unwrap();
+        assert_eq!(restored.profile, PermissionProfile::Admin);
+        assert!(restored.admin_password.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stored_iterations_are_bounded() {
+        let mut hash = hash_password("admin password").await.unwrap();
+        hash.iterations = 1;
+        let err = verify_password(&hash, "admin password").await.unwrap_err();
+        assert_eq!(err.error_code(), "CONFIG_ERROR");
+    }
+}
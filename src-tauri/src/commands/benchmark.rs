@@ -0,0 +1,335 @@
+//! Self benchmark
+//! Measures IPC round-trip latency, event throughput to the webview and ElizaOS CLI
+//! spawn latency, so performance regressions can be compared across releases
+
+use crate::commands::config::load_config_from_file;
+use crate::commands::resolver::resolve_eliza_command_cached;
+use crate::commands::stats::emit_event;
+use crate::models::ApiResponse;
+use crate::path_env::build_spawn_path;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{oneshot, Mutex};
+
+const DEFAULT_PING_COUNT: usize = 20;
+const MAX_PING_COUNT: usize = 500;
+const DEFAULT_EVENT_COUNT: usize = 1000;
+const MAX_EVENT_COUNT: usize = 100_000;
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkOptions {
+    pub pings: Option<usize>,
+    pub events: Option<usize>,
+    #[serde(default)]
+    pub skip_spawn: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventThroughput {
+    pub events: usize,
+    pub duration_ms: f64,
+    pub per_second: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnLatency {
+    pub command: String,
+    pub spawn_ms: f64,
+    pub first_output_ms: Option<f64>,
+    pub exit_ms: f64,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfBenchmarkReport {
+    pub started_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub ipc_round_trip: Option<LatencyStats>,
+    pub event_throughput: EventThroughput,
+    pub cli_spawn: Option<SpawnLatency>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchmarkPing {
+    id: String,
+    seq: usize,
+}
+
+// Outstanding pings waiting for the webview to answer, by ping ID
+pub type BenchmarkPings = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
+/// Initialize the benchmark ping registry (called from main)
+pub fn init_benchmark_pings() -> BenchmarkPings {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Run the self benchmark and return a report
+///
+/// The IPC round trip is measured by emitting `benchmark-ping` events that the
+/// webview answers by invoking `benchmark_pong`; it is skipped when nothing answers.
+#[tauri::command]
+pub async fn run_self_benchmark(
+    app: AppHandle,
+    options: Option<BenchmarkOptions>,
+) -> Result<ApiResponse<SelfBenchmarkReport>, String> {
+    let options = options.unwrap_or_default();
+    let pings = options
+        .pings
+        .unwrap_or(DEFAULT_PING_COUNT)
+        .min(MAX_PING_COUNT);
+    let events = options
+        .events
+        .unwrap_or(DEFAULT_EVENT_COUNT)
+        .min(MAX_EVENT_COUNT);
+
+    log::info!(
+        "Running self benchmark: {} pings, {} events, spawn {}",
+        pings,
+        events,
+        if options.skip_spawn {
+            "skipped"
+        } else {
+            "included"
+        }
+    );
+
+    let mut report = SelfBenchmarkReport {
+        started_at: crate::models::current_timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        ipc_round_trip: None,
+        event_throughput: measure_event_throughput(&app, events),
+        cli_spawn: None,
+        notes: Vec::new(),
+    };
+
+    match measure_ipc_round_trip(&app, pings).await {
+        Ok(stats) => report.ipc_round_trip = stats,
+        Err(note) => report.notes.push(note),
+    }
+
+    if !options.skip_spawn {
+        match measure_cli_spawn(&app).await {
+            Ok(spawn) => report.cli_spawn = Some(spawn),
+            Err(note) => report.notes.push(note),
+        }
+    }
+
+    log::info!(
+        "Self benchmark complete: {:.0} events/s, ipc p50 {:?}ms, spawn {:?}ms",
+        report.event_throughput.per_second,
+        report.ipc_round_trip.as_ref().map(|stats| stats.p50_ms),
+        report.cli_spawn.as_ref().map(|spawn| spawn.spawn_ms)
+    );
+
+    Ok(ApiResponse::success(report))
+}
+
+/// Answer a `benchmark-ping` event from the webview
+#[tauri::command]
+pub async fn benchmark_pong(app: AppHandle, id: String) -> Result<ApiResponse<bool>, String> {
+    let pings = app.state::<BenchmarkPings>().inner().clone();
+    let answered = match pings.lock().await.remove(&id) {
+        Some(sender) => sender.send(()).is_ok(),
+        None => false,
+    };
+    Ok(ApiResponse::success(answered))
+}
+
+fn measure_event_throughput(app: &AppHandle, events: usize) -> EventThroughput {
+    let started = Instant::now();
+    for seq in 0..events {
+        emit_event(
+            app,
+            "benchmark-event",
+            BenchmarkPing {
+                id: "throughput".to_string(),
+                seq,
+            },
+        );
+    }
+    let duration_ms = elapsed_ms(started);
+
+    EventThroughput {
+        events,
+        duration_ms,
+        per_second: if duration_ms > 0.0 {
+            events as f64 / (duration_ms / 1000.0)
+        } else {
+            0.0
+        },
+    }
+}
+
+/// Emit pings one at a time and time how long the webview takes to answer each
+async fn measure_ipc_round_trip(
+    app: &AppHandle,
+    count: usize,
+) -> Result<Option<LatencyStats>, String> {
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let pings = app.state::<BenchmarkPings>().inner().clone();
+    let run = chrono::Utc::now().timestamp_millis();
+    let mut samples = Vec::with_capacity(count);
+
+    for seq in 0..count {
+        let id = format!("ping_{}_{}", run, seq);
+        let (sender, receiver) = oneshot::channel();
+        pings.lock().await.insert(id.clone(), sender);
+
+        let started = Instant::now();
+        emit_event(
+            app,
+            "benchmark-ping",
+            BenchmarkPing {
+                id: id.clone(),
+                seq,
+            },
+        );
+        let answered = tokio::time::timeout(PING_TIMEOUT, receiver).await;
+
+        match answered {
+            Ok(Ok(())) => samples.push(elapsed_ms(started)),
+            _ => {
+                pings.lock().await.remove(&id);
+                return Err(format!(
+                    "IPC round trip skipped: no benchmark_pong for ping {} within {}s",
+                    seq,
+                    PING_TIMEOUT.as_secs()
+                ));
+            }
+        }
+    }
+
+    Ok(latency_stats(samples))
+}
+
+/// Time spawning `<cli> --version` until the process starts, prints and exits
+async fn measure_cli_spawn(app: &AppHandle) -> Result<SpawnLatency, String> {
+    let config = load_config_from_file(app)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let runner = resolve_eliza_command_cached(app, &config)
+        .await
+        .map_err(|e| format!("CLI spawn skipped: {}", e))?;
+
+    let mut args = runner.package_args();
+    args.push("--version".to_string());
+    let command = format!("{} {}", runner.program(), args.join(" "));
+
+    let started = Instant::now();
+    let mut child = Command::new(runner.program())
+        .args(&args)
+        .env("PATH", build_spawn_path(config.extra_path_dirs()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("CLI spawn failed: {}", e))?;
+    let spawn_ms = elapsed_ms(started);
+
+    let measured = tokio::time::timeout(SPAWN_TIMEOUT, async {
+        let mut first_output_ms = None;
+        if let Some(stdout) = child.stdout.take() {
+            let mut lines = BufReader::new(stdout).lines();
+            if let Ok(Some(_)) = lines.next_line().await {
+                first_output_ms = Some(elapsed_ms(started));
+            }
+            while let Ok(Some(_)) = lines.next_line().await {}
+        }
+        let status = child.wait().await;
+        (first_output_ms, status)
+    })
+    .await;
+
+    match measured {
+        Ok((first_output_ms, status)) => Ok(SpawnLatency {
+            command,
+            spawn_ms,
+            first_output_ms,
+            exit_ms: elapsed_ms(started),
+            exit_code: status.ok().and_then(|status| status.code()),
+        }),
+        Err(_) => Err(format!(
+            "CLI spawn timed out after {}s: {}",
+            SPAWN_TIMEOUT.as_secs(),
+            command
+        )),
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Summarize latency samples; nearest-rank percentiles
+fn latency_stats(mut samples: Vec<f64>) -> Option<LatencyStats> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_by(|a, b| a.total_cmp(b));
+
+    let percentile = |p: f64| {
+        let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        samples[rank.clamp(1, samples.len()) - 1]
+    };
+
+    Some(LatencyStats {
+        samples: samples.len(),
+        min_ms: samples[0],
+        mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+        p50_ms: percentile(50.0),
+        p95_ms: percentile(95.0),
+        max_ms: samples[samples.len() - 1],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats() {
+        assert!(latency_stats(Vec::new()).is_none());
+
+        let samples: Vec<f64> = (1..=20).rev().map(|ms| ms as f64).collect();
+        let stats = latency_stats(samples).unwrap();
+        assert_eq!(stats.samples, 20);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.max_ms, 20.0);
+        assert_eq!(stats.mean_ms, 10.5);
+        assert_eq!(stats.p50_ms, 10.0);
+        assert_eq!(stats.p95_ms, 19.0);
+    }
+}
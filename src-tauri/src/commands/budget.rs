@@ -0,0 +1,454 @@
+//! Token budgets
+//! Aggregates token usage reported by run telemetry per day, checks it against the
+//! configured daily and monthly limits, and emits `budget-threshold` events at 80% and 100%
+
+use crate::commands::config::saved_config;
+use crate::commands::stats::emit_event;
+use crate::i18n::{t, t_args, MessageId};
+use crate::models::{ApiResponse, AppError, BudgetConfig, SandboxConfig};
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Mutex;
+
+const USAGE_FILE: &str = "token_usage.json";
+/// Percentages of a limit that trigger an alert
+const THRESHOLDS: [u8; 2] = [80, 100];
+/// Days of usage kept; enough for the current and previous month
+const KEEP_DAYS: usize = 62;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    Daily,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetMetric {
+    Tokens,
+    Cost,
+}
+
+/// Payload of the `budget-threshold` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetThresholdEvent {
+    pub period: BudgetPeriod,
+    pub metric: BudgetMetric,
+    pub percent: u8,
+    pub used: f64,
+    pub limit: f64,
+    /// Day (`YYYY-MM-DD`) or month (`YYYY-MM`) the usage belongs to
+    pub period_key: String,
+}
+
+impl BudgetThresholdEvent {
+    /// Identifies the alert so each threshold fires once per period
+    fn alert_key(&self) -> String {
+        format!(
+            "{:?}:{:?}:{}:{}",
+            self.period, self.metric, self.period_key, self.percent
+        )
+    }
+
+    fn message(&self) -> String {
+        let (id, used, limit) = match (self.period, self.metric) {
+            (BudgetPeriod::Daily, BudgetMetric::Tokens) => (
+                MessageId::BudgetDailyTokensUsed,
+                format!("{:.0}", self.used),
+                format!("{:.0}", self.limit),
+            ),
+            (BudgetPeriod::Monthly, BudgetMetric::Tokens) => (
+                MessageId::BudgetMonthlyTokensUsed,
+                format!("{:.0}", self.used),
+                format!("{:.0}", self.limit),
+            ),
+            (BudgetPeriod::Daily, BudgetMetric::Cost) => (
+                MessageId::BudgetDailyCostUsed,
+                format!("{:.2}", self.used),
+                format!("{:.2}", self.limit),
+            ),
+            (BudgetPeriod::Monthly, BudgetMetric::Cost) => (
+                MessageId::BudgetMonthlyCostUsed,
+                format!("{:.2}", self.used),
+                format!("{:.2}", self.limit),
+            ),
+        };
+        t_args(
+            id,
+            &[
+                ("percent", &self.percent.to_string()),
+                ("used", &used),
+                ("limit", &limit),
+            ],
+        )
+    }
+}
+
+/// Usage so far in the current day and month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetUsage {
+    pub daily_tokens: u64,
+    pub monthly_tokens: u64,
+    pub daily_cost_usd: Option<f64>,
+    pub monthly_cost_usd: Option<f64>,
+    pub budget: BudgetConfig,
+    /// Whether any configured limit is reached
+    pub exceeded: bool,
+}
+
+/// Tokens per day plus the alerts already sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageLedger {
+    #[serde(default)]
+    days: BTreeMap<String, u64>,
+    #[serde(default)]
+    alerted: BTreeSet<String>,
+}
+
+impl UsageLedger {
+    fn usage(&self, today: NaiveDate) -> (u64, u64) {
+        let day = day_key(today);
+        let month = month_key(today);
+        let daily = self.days.get(&day).copied().unwrap_or(0);
+        let monthly = self
+            .days
+            .iter()
+            .filter(|(key, _)| key.starts_with(&month))
+            .map(|(_, tokens)| tokens)
+            .sum();
+        (daily, monthly)
+    }
+
+    /// Drop old days and alerts that no longer belong to the current day or month
+    fn prune(&mut self, today: NaiveDate) {
+        while self.days.len() > KEEP_DAYS {
+            self.days.pop_first();
+        }
+        let (day, month) = (day_key(today), month_key(today));
+        self.alerted.retain(|key| {
+            key.contains(&format!(":{}:", day)) || key.contains(&format!(":{}:", month))
+        });
+    }
+}
+
+// Serializes read-modify-write cycles on the usage file
+pub type BudgetLedgerLock = Arc<Mutex<()>>;
+
+/// Initialize the budget ledger lock (called from main)
+pub fn init_budget_ledger() -> BudgetLedgerLock {
+    Arc::new(Mutex::new(()))
+}
+
+/// Get token usage for today and this month against the configured budget
+#[tauri::command]
+pub async fn get_budget_usage(
+    app: AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<BudgetUsage>, String> {
+    let _guard = lock_ledger(&app).await;
+    match read_ledger(&app) {
+        Ok(ledger) => {
+            let budget = config.budget.unwrap_or_default();
+            Ok(ApiResponse::success(summarize(&ledger, &budget, today())))
+        }
+        Err(e) => {
+            log::error!("Failed to read token usage: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to read token usage: {}", e),
+            ))
+        }
+    }
+}
+
+/// Add tokens reported by run telemetry and alert on any newly crossed threshold
+pub async fn record_usage(app: &AppHandle, config: &SandboxConfig, tokens: u64) {
+    if tokens == 0 {
+        return;
+    }
+    if let Err(e) = record_usage_inner(app, config, tokens).await {
+        log::warn!("Failed to record token usage: {}", e);
+    }
+}
+
+async fn record_usage_inner(
+    app: &AppHandle,
+    config: &SandboxConfig,
+    tokens: u64,
+) -> Result<(), AppError> {
+    let today = today();
+    let budget = config.budget.clone().unwrap_or_default();
+
+    let alerts = {
+        let _guard = lock_ledger(app).await;
+        let mut ledger = read_ledger(app)?;
+        *ledger.days.entry(day_key(today)).or_insert(0) += tokens;
+        ledger.prune(today);
+
+        let alerts: Vec<BudgetThresholdEvent> = crossed_thresholds(&ledger, &budget, today)
+            .into_iter()
+            .filter(|alert| ledger.alerted.insert(alert.alert_key()))
+            .collect();
+        write_ledger(app, &ledger)?;
+        alerts
+    };
+
+    for alert in alerts {
+        log::warn!("Budget threshold: {}", alert.message());
+        if budget.notify {
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(t(MessageId::BudgetNotificationTitle))
+                .body(alert.message())
+                .show()
+            {
+                log::warn!("Failed to show budget notification: {}", e);
+            }
+        }
+        emit_event(app, "budget-threshold", alert);
+    }
+    Ok(())
+}
+
+/// Refuse new runs when the saved budget blocks them and a limit is reached
+///
+/// Run requests carry their own config, so the budget is read from the saved profile;
+/// changing it goes through the admin-only `save_sandbox_config`.
+pub async fn check_run_budget(app: &AppHandle) -> Result<(), AppError> {
+    let budget = match saved_config(app)?.budget {
+        Some(budget) if budget.block_when_exceeded => budget,
+        _ => return Ok(()),
+    };
+
+    let _guard = lock_ledger(app).await;
+    let ledger = read_ledger(app)?;
+    if summarize(&ledger, &budget, today()).exceeded {
+        return Err(AppError::Quota(
+            "Token budget exceeded; new runs are blocked until it resets or is raised".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn summarize(ledger: &UsageLedger, budget: &BudgetConfig, today: NaiveDate) -> BudgetUsage {
+    let (daily_tokens, monthly_tokens) = ledger.usage(today);
+    let cost = |tokens| estimated_cost(budget, tokens);
+
+    BudgetUsage {
+        daily_tokens,
+        monthly_tokens,
+        daily_cost_usd: cost(daily_tokens),
+        monthly_cost_usd: cost(monthly_tokens),
+        exceeded: crossed_thresholds(ledger, budget, today)
+            .iter()
+            .any(|alert| alert.percent >= 100),
+        budget: budget.clone(),
+    }
+}
+
+/// Every threshold currently at or above its level, for each configured limit
+fn crossed_thresholds(
+    ledger: &UsageLedger,
+    budget: &BudgetConfig,
+    today: NaiveDate,
+) -> Vec<BudgetThresholdEvent> {
+    let (daily, monthly) = ledger.usage(today);
+    let cost = |tokens| estimated_cost(budget, tokens);
+
+    let checks = [
+        (
+            BudgetPeriod::Daily,
+            BudgetMetric::Tokens,
+            Some(daily as f64),
+            budget.daily_tokens.map(|limit| limit as f64),
+        ),
+        (
+            BudgetPeriod::Monthly,
+            BudgetMetric::Tokens,
+            Some(monthly as f64),
+            budget.monthly_tokens.map(|limit| limit as f64),
+        ),
+        (
+            BudgetPeriod::Daily,
+            BudgetMetric::Cost,
+            cost(daily),
+            budget.daily_cost_usd,
+        ),
+        (
+            BudgetPeriod::Monthly,
+            BudgetMetric::Cost,
+            cost(monthly),
+            budget.monthly_cost_usd,
+        ),
+    ];
+
+    let mut crossed = Vec::new();
+    for (period, metric, used, limit) in checks {
+        let (Some(used), Some(limit)) = (used, limit) else {
+            continue;
+        };
+        if limit <= 0.0 {
+            continue;
+        }
+        let period_key = match period {
+            BudgetPeriod::Daily => day_key(today),
+            BudgetPeriod::Monthly => month_key(today),
+        };
+        for percent in THRESHOLDS {
+            if used >= limit * f64::from(percent) / 100.0 {
+                crossed.push(BudgetThresholdEvent {
+                    period,
+                    metric,
+                    percent,
+                    used,
+                    limit,
+                    period_key: period_key.clone(),
+                });
+            }
+        }
+    }
+    crossed
+}
+
+fn estimated_cost(budget: &BudgetConfig, tokens: u64) -> Option<f64> {
+    budget
+        .usd_per_1k_tokens
+        .map(|price| tokens as f64 / 1000.0 * price)
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn day_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn month_key(date: NaiveDate) -> String {
+    format!("{:04}-{:02}", date.year(), date.month())
+}
+
+async fn lock_ledger(app: &AppHandle) -> tokio::sync::OwnedMutexGuard<()> {
+    app.state::<BudgetLedgerLock>()
+        .inner()
+        .clone()
+        .lock_owned()
+        .await
+}
+
+fn get_usage_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(USAGE_FILE))
+}
+
+fn read_ledger(app: &AppHandle) -> Result<UsageLedger, AppError> {
+    let path = get_usage_path(app)?;
+    if !path.exists() {
+        return Ok(UsageLedger::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_ledger(app: &AppHandle, ledger: &UsageLedger) -> Result<(), AppError> {
+    let path = get_usage_path(app)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(ledger)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn ledger(days: &[(&str, u64)]) -> UsageLedger {
+        UsageLedger {
+            days: days
+                .iter()
+                .map(|(day, tokens)| (day.to_string(), *tokens))
+                .collect(),
+            alerted: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_usage_per_period() {
+        let ledger = ledger(&[("2024-05-31", 50), ("2024-06-01", 10), ("2024-06-02", 5)]);
+        assert_eq!(ledger.usage(date(2024, 6, 2)), (5, 15));
+        assert_eq!(ledger.usage(date(2024, 6, 3)), (0, 15));
+    }
+
+    #[test]
+    fn test_crossed_thresholds() {
+        let budget = BudgetConfig {
+            daily_tokens: Some(100),
+            monthly_cost_usd: Some(1.0),
+            usd_per_1k_tokens: Some(0.5),
+            ..BudgetConfig::default()
+        };
+        let today = date(2024, 6, 2);
+
+        let crossed = crossed_thresholds(&ledger(&[("2024-06-02", 85)]), &budget, today);
+        assert_eq!(crossed.len(), 1);
+        assert_eq!(crossed[0].period, BudgetPeriod::Daily);
+        assert_eq!(crossed[0].percent, 80);
+
+        // 2000 tokens at $0.50/1k is $1.00: both cost thresholds plus both daily ones
+        let crossed = crossed_thresholds(&ledger(&[("2024-06-02", 2000)]), &budget, today);
+        assert_eq!(crossed.len(), 4);
+        assert!(summarize(&ledger(&[("2024-06-02", 2000)]), &budget, today).exceeded);
+
+        // Cost limits are skipped without a price
+        let no_price = BudgetConfig {
+            monthly_cost_usd: Some(1.0),
+            ..BudgetConfig::default()
+        };
+        assert!(
+            crossed_thresholds(&ledger(&[("2024-06-02", 1_000_000)]), &no_price, today).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_prune_drops_stale_alerts() {
+        let mut ledger = ledger(&[]);
+        for i in 0..(KEEP_DAYS + 5) {
+            ledger.days.insert(format!("2024-01-{:03}", i), 1);
+        }
+        ledger
+            .alerted
+            .insert("Daily:Tokens:2024-06-01:80".to_string());
+        ledger
+            .alerted
+            .insert("Daily:Tokens:2024-06-02:80".to_string());
+        ledger
+            .alerted
+            .insert("Monthly:Tokens:2024-06:80".to_string());
+
+        ledger.prune(date(2024, 6, 2));
+        assert_eq!(ledger.days.len(), KEEP_DAYS);
+        assert_eq!(ledger.alerted.len(), 2);
+        assert!(!ledger.alerted.contains("Daily:Tokens:2024-06-01:80"));
+    }
+}
@@ -0,0 +1,444 @@
+//! Character assets
+//! Images and audio attached to a managed character, stored in its `assets` directory so
+//! they travel with the character in packages. Files are checked against an allow-list of
+//! formats, by extension and by content, and against per-kind size limits
+
+use crate::commands::characters::{
+    character_dir, load_character, load_metadata, save_character, slugify,
+};
+use crate::commands::path_jail::check_path_allowed;
+use crate::models::{ApiResponse, AppError, CharacterAsset, CharacterAssetKind};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+pub const ASSETS_DIR: &str = "assets";
+/// Character field holding the avatar's path relative to the character directory
+const AVATAR_FIELD: &str = "avatar";
+const AVATAR_STEM: &str = "avatar";
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_AUDIO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// An accepted asset format
+#[derive(Debug)]
+pub(crate) struct AssetFormat {
+    extension: &'static str,
+    mime_type: &'static str,
+    kind: CharacterAssetKind,
+}
+
+const FORMATS: &[AssetFormat] = &[
+    AssetFormat {
+        extension: "png",
+        mime_type: "image/png",
+        kind: CharacterAssetKind::Image,
+    },
+    AssetFormat {
+        extension: "jpg",
+        mime_type: "image/jpeg",
+        kind: CharacterAssetKind::Image,
+    },
+    AssetFormat {
+        extension: "jpeg",
+        mime_type: "image/jpeg",
+        kind: CharacterAssetKind::Image,
+    },
+    AssetFormat {
+        extension: "gif",
+        mime_type: "image/gif",
+        kind: CharacterAssetKind::Image,
+    },
+    AssetFormat {
+        extension: "webp",
+        mime_type: "image/webp",
+        kind: CharacterAssetKind::Image,
+    },
+    AssetFormat {
+        extension: "mp3",
+        mime_type: "audio/mpeg",
+        kind: CharacterAssetKind::Audio,
+    },
+    AssetFormat {
+        extension: "wav",
+        mime_type: "audio/wav",
+        kind: CharacterAssetKind::Audio,
+    },
+    AssetFormat {
+        extension: "ogg",
+        mime_type: "audio/ogg",
+        kind: CharacterAssetKind::Audio,
+    },
+    AssetFormat {
+        extension: "m4a",
+        mime_type: "audio/mp4",
+        kind: CharacterAssetKind::Audio,
+    },
+];
+
+impl AssetFormat {
+    fn max_bytes(&self) -> u64 {
+        match self.kind {
+            CharacterAssetKind::Image => MAX_IMAGE_BYTES,
+            CharacterAssetKind::Audio => MAX_AUDIO_BYTES,
+        }
+    }
+
+    /// Whether the file starts the way this format does
+    fn matches(&self, contents: &[u8]) -> bool {
+        let riff = |form: &[u8]| contents.starts_with(b"RIFF") && contents.get(8..12) == Some(form);
+        match self.extension {
+            "png" => contents.starts_with(b"\x89PNG\r\n\x1a\n"),
+            "jpg" | "jpeg" => contents.starts_with(&[0xFF, 0xD8, 0xFF]),
+            "gif" => contents.starts_with(b"GIF87a") || contents.starts_with(b"GIF89a"),
+            "webp" => riff(b"WEBP"),
+            "mp3" => {
+                contents.starts_with(b"ID3")
+                    || (contents.len() > 1 && contents[0] == 0xFF && contents[1] & 0xE0 == 0xE0)
+            }
+            "wav" => riff(b"WAVE"),
+            "ogg" => contents.starts_with(b"OggS"),
+            "m4a" => contents.get(4..8) == Some(&b"ftyp"[..]),
+            _ => false,
+        }
+    }
+}
+
+/// Assets of a managed character, sorted by name
+#[tauri::command]
+pub async fn list_character_assets(
+    app: AppHandle,
+    id: String,
+) -> Result<ApiResponse<Vec<CharacterAsset>>, String> {
+    match list_assets(&app, &id) {
+        Ok(assets) => Ok(ApiResponse::success(assets)),
+        Err(e) => Ok(error_response("Failed to list character assets", e)),
+    }
+}
+
+/// Copy an image or audio file into a character's assets, replacing one of the same name
+#[tauri::command]
+pub async fn add_character_asset(
+    app: AppHandle,
+    id: String,
+    path: String,
+) -> Result<ApiResponse<CharacterAsset>, String> {
+    let result = (|| {
+        let source = Path::new(&path);
+        let (format, contents) = read_source(&app, source)?;
+        let stem = source
+            .file_stem()
+            .map(|stem| slugify(&stem.to_string_lossy()))
+            .unwrap_or_default();
+        if stem == AVATAR_STEM {
+            return Err(AppError::CharacterError(
+                "The name 'avatar' is reserved; use set_character_avatar instead".to_string(),
+            ));
+        }
+
+        let name = format!("{}.{}", stem, format.extension);
+        write_asset(&app, &id, &name, &contents)?;
+        let avatar = avatar_name(&load_character(&app, &id)?);
+        asset_info(&assets_dir(&app, &id)?, &name, avatar.as_deref())
+    })();
+
+    match result {
+        Ok(asset) => {
+            log::info!("Added asset {} to character {}", asset.name, id);
+            Ok(ApiResponse::success(asset))
+        }
+        Err(e) => Ok(error_response("Failed to add character asset", e)),
+    }
+}
+
+/// Use an image as the character's avatar; it is stored as `assets/avatar.<ext>` and the
+/// character's `avatar` field is pointed at it
+#[tauri::command]
+pub async fn set_character_avatar(
+    app: AppHandle,
+    id: String,
+    path: String,
+) -> Result<ApiResponse<CharacterAsset>, String> {
+    let result = (|| {
+        let (format, contents) = read_source(&app, Path::new(&path))?;
+        if format.kind != CharacterAssetKind::Image {
+            return Err(AppError::CharacterError(
+                "An avatar must be an image".to_string(),
+            ));
+        }
+
+        let mut character = load_character(&app, &id)?;
+        let name = format!("{}.{}", AVATAR_STEM, format.extension);
+        write_asset(&app, &id, &name, &contents)?;
+
+        // An avatar saved earlier in another format is replaced, not kept alongside
+        let previous = avatar_name(&character).filter(|previous| *previous != name);
+        if let Some(ref previous) = previous {
+            remove_file_if_exists(&assets_dir(&app, &id)?.join(previous))?;
+        }
+
+        if let Some(object) = character.as_object_mut() {
+            object.insert(
+                AVATAR_FIELD.to_string(),
+                Value::String(format!("{}/{}", ASSETS_DIR, name)),
+            );
+        }
+        let metadata = load_metadata(&app, &id)?;
+        save_character(&app, &id, &character, &metadata, Some("Set avatar"))?;
+        asset_info(&assets_dir(&app, &id)?, &name, Some(&name))
+    })();
+
+    match result {
+        Ok(asset) => {
+            log::info!("Set avatar of character {} to {}", id, asset.name);
+            Ok(ApiResponse::success(asset))
+        }
+        Err(e) => Ok(error_response("Failed to set character avatar", e)),
+    }
+}
+
+/// Delete an asset, returning whether it existed; removing the avatar clears the field
+#[tauri::command]
+pub async fn remove_character_asset(
+    app: AppHandle,
+    id: String,
+    name: String,
+) -> Result<ApiResponse<bool>, String> {
+    let result = (|| {
+        validate_asset_name(&name)?;
+        let existed = remove_file_if_exists(&assets_dir(&app, &id)?.join(&name))?;
+
+        let mut character = load_character(&app, &id)?;
+        if avatar_name(&character).as_deref() == Some(name.as_str()) {
+            if let Some(object) = character.as_object_mut() {
+                object.remove(AVATAR_FIELD);
+            }
+            let metadata = load_metadata(&app, &id)?;
+            save_character(&app, &id, &character, &metadata, Some("Removed avatar"))?;
+        }
+        Ok::<_, AppError>(existed)
+    })();
+
+    match result {
+        Ok(existed) => {
+            log::info!(
+                "Removed asset {} of character {} (existed: {})",
+                name,
+                id,
+                existed
+            );
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to remove character asset", e)),
+    }
+}
+
+/// Check an asset's name, size and content; packages are checked with it on import
+pub(crate) fn validate_asset(
+    name: &str,
+    contents: &[u8],
+) -> Result<&'static AssetFormat, AppError> {
+    let format = format_for(Path::new(name))?;
+    if contents.len() as u64 > format.max_bytes() {
+        return Err(AppError::Quota(format!(
+            "'{}' is {} bytes; {} files may be at most {} bytes",
+            name,
+            contents.len(),
+            format.extension,
+            format.max_bytes()
+        )));
+    }
+    if !format.matches(contents) {
+        return Err(AppError::CharacterError(format!(
+            "'{}' is not a valid {} file",
+            name, format.extension
+        )));
+    }
+    Ok(format)
+}
+
+fn format_for(path: &Path) -> Result<&'static AssetFormat, AppError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    FORMATS
+        .iter()
+        .find(|format| format.extension == extension)
+        .ok_or_else(|| {
+            let allowed: Vec<&str> = FORMATS.iter().map(|format| format.extension).collect();
+            AppError::CharacterError(format!(
+                "Unsupported asset type '{}'; use one of: {}",
+                extension,
+                allowed.join(", ")
+            ))
+        })
+}
+
+fn read_source(
+    app: &AppHandle,
+    source: &Path,
+) -> Result<(&'static AssetFormat, Vec<u8>), AppError> {
+    check_path_allowed(app, source, "Asset")?;
+    let format = format_for(source)?;
+    let size = fs::metadata(source)?.len();
+    if size > format.max_bytes() {
+        return Err(AppError::Quota(format!(
+            "Asset is {} bytes; {} files may be at most {} bytes",
+            size,
+            format.extension,
+            format.max_bytes()
+        )));
+    }
+
+    let contents = fs::read(source)?;
+    let name = source.file_name().unwrap_or_default().to_string_lossy();
+    validate_asset(&name, &contents)?;
+    Ok((format, contents))
+}
+
+fn write_asset(app: &AppHandle, id: &str, name: &str, contents: &[u8]) -> Result<(), AppError> {
+    // Fails for a character that doesn't exist before anything is written
+    load_character(app, id)?;
+    let dir = assets_dir(app, id)?;
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(name);
+    let tmp_path = dir.join(format!("{}.tmp", name));
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+fn list_assets(app: &AppHandle, id: &str) -> Result<Vec<CharacterAsset>, AppError> {
+    let avatar = avatar_name(&load_character(app, id)?);
+    let dir = assets_dir(app, id)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut assets = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Leftovers of other tools and interrupted writes are not assets
+        if let Ok(asset) = asset_info(&dir, &name, avatar.as_deref()) {
+            assets.push(asset);
+        }
+    }
+    assets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(assets)
+}
+
+fn asset_info(dir: &Path, name: &str, avatar: Option<&str>) -> Result<CharacterAsset, AppError> {
+    let format = format_for(Path::new(name))?;
+    let path = dir.join(name);
+    Ok(CharacterAsset {
+        name: name.to_string(),
+        size_bytes: fs::metadata(&path)?.len(),
+        path: path.to_string_lossy().to_string(),
+        kind: format.kind,
+        mime_type: format.mime_type.to_string(),
+        is_avatar: avatar == Some(name),
+    })
+}
+
+/// File name of the avatar when the character's `avatar` field points into its assets
+fn avatar_name(character: &Value) -> Option<String> {
+    character
+        .get(AVATAR_FIELD)
+        .and_then(Value::as_str)
+        .and_then(|avatar| avatar.strip_prefix(&format!("{}/", ASSETS_DIR)))
+        .map(str::to_string)
+}
+
+fn validate_asset_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::CharacterError(format!(
+            "Invalid asset name '{}'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn assets_dir(app: &AppHandle, id: &str) -> Result<PathBuf, AppError> {
+    Ok(character_dir(app, id)?.join(ASSETS_DIR))
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<bool, AppError> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_validate_asset() {
+        let format = validate_asset("face.PNG", PNG).unwrap();
+        assert_eq!(format.mime_type, "image/png");
+        assert_eq!(format.kind, CharacterAssetKind::Image);
+
+        let wav = b"RIFF\x24\0\0\0WAVEfmt ";
+        assert_eq!(
+            validate_asset("hello.wav", wav).unwrap().kind,
+            CharacterAssetKind::Audio
+        );
+        assert!(validate_asset("voice.mp3", b"ID3\x04\0\0").is_ok());
+
+        // Content must match the extension
+        assert!(validate_asset("face.jpg", PNG).is_err());
+        assert!(validate_asset("notes.svg", b"<svg/>").is_err());
+        assert!(validate_asset("noext", PNG).is_err());
+    }
+
+    #[test]
+    fn test_validate_asset_size() {
+        let mut big = PNG.to_vec();
+        big.resize(MAX_IMAGE_BYTES as usize + 1, 0);
+        let err = validate_asset("big.png", &big).unwrap_err();
+        assert_eq!(err.error_code(), "QUOTA_EXCEEDED");
+    }
+
+    #[test]
+    fn test_avatar_name() {
+        assert_eq!(
+            avatar_name(&json!({ "avatar": "assets/avatar.png" })).as_deref(),
+            Some("avatar.png")
+        );
+        assert!(avatar_name(&json!({ "avatar": "https://example.com/a.png" })).is_none());
+        assert!(avatar_name(&json!({ "name": "Ada" })).is_none());
+    }
+
+    #[test]
+    fn test_validate_asset_name() {
+        assert!(validate_asset_name("avatar.png").is_ok());
+        assert!(validate_asset_name("../character.json").is_err());
+        assert!(validate_asset_name(".hidden").is_err());
+        assert!(validate_asset_name("a/b.png").is_err());
+    }
+}
@@ -0,0 +1,406 @@
+//! Character linting
+//! Best-practice checks that go beyond `validate_character`: empty bios, missing message
+//! examples, oversized knowledge and plugin names ElizaOS doesn't publish
+
+use crate::commands::characters::{character_dir, load_character, validate_character};
+use crate::commands::knowledge::{list_knowledge, KNOWLEDGE_DIR, MAX_KNOWLEDGE_TOTAL_BYTES};
+use crate::models::{ApiResponse, AppError, CharacterLintIssue, CharacterLintReport, LintSeverity};
+use serde_json::Value;
+use tauri::AppHandle;
+
+/// Plugins published under `@elizaos/`; other names in that scope are likely typos
+const KNOWN_PLUGINS: &[&str] = &[
+    "@elizaos/plugin-anthropic",
+    "@elizaos/plugin-bootstrap",
+    "@elizaos/plugin-discord",
+    "@elizaos/plugin-evm",
+    "@elizaos/plugin-farcaster",
+    "@elizaos/plugin-google-genai",
+    "@elizaos/plugin-groq",
+    "@elizaos/plugin-knowledge",
+    "@elizaos/plugin-local-ai",
+    "@elizaos/plugin-ollama",
+    "@elizaos/plugin-openai",
+    "@elizaos/plugin-openrouter",
+    "@elizaos/plugin-solana",
+    "@elizaos/plugin-sql",
+    "@elizaos/plugin-telegram",
+    "@elizaos/plugin-twitter",
+];
+
+/// Plugins that give an agent a language model
+const MODEL_PLUGINS: &[&str] = &[
+    "@elizaos/plugin-anthropic",
+    "@elizaos/plugin-google-genai",
+    "@elizaos/plugin-groq",
+    "@elizaos/plugin-local-ai",
+    "@elizaos/plugin-ollama",
+    "@elizaos/plugin-openai",
+    "@elizaos/plugin-openrouter",
+];
+
+/// Knowledge this large slows agent startup noticeably
+const LARGE_KNOWLEDGE_BYTES: u64 = 20 * 1024 * 1024;
+/// Inline knowledge beyond this belongs in knowledge files
+const LARGE_INLINE_KNOWLEDGE_BYTES: usize = 100 * 1024;
+/// Conversations needed for the agent to pick up a voice
+const MIN_MESSAGE_EXAMPLES: usize = 2;
+
+/// Check a managed character against best practices
+#[tauri::command]
+pub async fn lint_character(
+    app: AppHandle,
+    id: String,
+) -> Result<ApiResponse<CharacterLintReport>, String> {
+    let result = (|| {
+        let character = load_character(&app, &id)?;
+        let knowledge_bytes = list_knowledge(&character_dir(&app, &id)?.join(KNOWLEDGE_DIR))?
+            .iter()
+            .map(|file| file.size_bytes)
+            .sum();
+        Ok::<_, AppError>(lint_report(&id, lint(&character, knowledge_bytes)))
+    })();
+
+    match result {
+        Ok(report) => {
+            log::info!(
+                "Linted character {}: {} errors, {} warnings",
+                id,
+                report.errors,
+                report.warnings
+            );
+            Ok(ApiResponse::success(report))
+        }
+        Err(e) => {
+            log::error!("Failed to lint character {}: {}", id, e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to lint character: {}", e),
+            ))
+        }
+    }
+}
+
+fn lint_report(id: &str, mut issues: Vec<CharacterLintIssue>) -> CharacterLintReport {
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    let count = |severity| issues.iter().filter(|i| i.severity == severity).count();
+    CharacterLintReport {
+        character_id: id.to_string(),
+        errors: count(LintSeverity::Error),
+        warnings: count(LintSeverity::Warning),
+        issues,
+    }
+}
+
+/// All findings for a character whose knowledge files total `knowledge_bytes`
+fn lint(character: &Value, knowledge_bytes: u64) -> Vec<CharacterLintIssue> {
+    if let Err(e) = validate_character(character) {
+        return vec![issue(
+            "invalid-character",
+            LintSeverity::Error,
+            None,
+            e.to_string(),
+        )];
+    }
+
+    let mut issues = Vec::new();
+    lint_bio(character, &mut issues);
+    lint_examples(character, &mut issues);
+    lint_knowledge(character, knowledge_bytes, &mut issues);
+    lint_plugins(character, &mut issues);
+
+    if text_is_blank(character.get("system")) {
+        issues.push(issue(
+            "missing-system",
+            LintSeverity::Info,
+            Some("system"),
+            "No system prompt; the agent relies on its bio alone".to_string(),
+        ));
+    }
+    if character.get("style").is_none() {
+        issues.push(issue(
+            "missing-style",
+            LintSeverity::Info,
+            Some("style"),
+            "No style guidelines for chat or posts".to_string(),
+        ));
+    }
+    issues
+}
+
+fn lint_bio(character: &Value, issues: &mut Vec<CharacterLintIssue>) {
+    if text_is_blank(character.get("bio")) {
+        issues.push(issue(
+            "empty-bio",
+            LintSeverity::Warning,
+            Some("bio"),
+            "The bio is empty; describe who the agent is".to_string(),
+        ));
+    }
+}
+
+fn lint_examples(character: &Value, issues: &mut Vec<CharacterLintIssue>) {
+    let examples = character
+        .get("messageExamples")
+        .and_then(Value::as_array)
+        .map(|examples| {
+            examples
+                .iter()
+                .filter(|conversation| conversation.as_array().is_some_and(|m| !m.is_empty()))
+                .count()
+        })
+        .unwrap_or(0);
+
+    if examples == 0 {
+        issues.push(issue(
+            "missing-message-examples",
+            LintSeverity::Warning,
+            Some("messageExamples"),
+            "No example conversations; the agent has nothing to model its replies on".to_string(),
+        ));
+    } else if examples < MIN_MESSAGE_EXAMPLES {
+        issues.push(issue(
+            "few-message-examples",
+            LintSeverity::Info,
+            Some("messageExamples"),
+            format!(
+                "Only {} example conversation; {} or more give a steadier voice",
+                examples, MIN_MESSAGE_EXAMPLES
+            ),
+        ));
+    }
+}
+
+fn lint_knowledge(character: &Value, knowledge_bytes: u64, issues: &mut Vec<CharacterLintIssue>) {
+    let inline_bytes = character
+        .get("knowledge")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::len)
+                .sum::<usize>()
+        })
+        .unwrap_or(0);
+    if inline_bytes > LARGE_INLINE_KNOWLEDGE_BYTES {
+        issues.push(issue(
+            "large-inline-knowledge",
+            LintSeverity::Warning,
+            Some("knowledge"),
+            format!(
+                "{} KB of knowledge is inlined in the character; move it to knowledge files",
+                inline_bytes / 1024
+            ),
+        ));
+    }
+
+    let total = knowledge_bytes + inline_bytes as u64;
+    if total > MAX_KNOWLEDGE_TOTAL_BYTES {
+        issues.push(issue(
+            "excessive-knowledge",
+            LintSeverity::Error,
+            Some("knowledge"),
+            format!(
+                "{} MB of knowledge exceeds the {} MB limit",
+                total / (1024 * 1024),
+                MAX_KNOWLEDGE_TOTAL_BYTES / (1024 * 1024)
+            ),
+        ));
+    } else if total > LARGE_KNOWLEDGE_BYTES {
+        issues.push(issue(
+            "excessive-knowledge",
+            LintSeverity::Warning,
+            Some("knowledge"),
+            format!(
+                "{} MB of knowledge will slow down agent startup",
+                total / (1024 * 1024)
+            ),
+        ));
+    }
+}
+
+fn lint_plugins(character: &Value, issues: &mut Vec<CharacterLintIssue>) {
+    let plugins: Vec<&str> = character
+        .get("plugins")
+        .and_then(Value::as_array)
+        .map(|plugins| plugins.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut seen = Vec::new();
+    for plugin in &plugins {
+        if seen.contains(plugin) {
+            issues.push(issue(
+                "duplicate-plugin",
+                LintSeverity::Warning,
+                Some("plugins"),
+                format!("{} is listed more than once", plugin),
+            ));
+            continue;
+        }
+        seen.push(plugin);
+
+        if !is_valid_package_name(plugin) {
+            issues.push(issue(
+                "invalid-plugin-name",
+                LintSeverity::Error,
+                Some("plugins"),
+                format!("'{}' is not a valid npm package name", plugin),
+            ));
+        } else if plugin.starts_with("@elizaos/") && !KNOWN_PLUGINS.contains(plugin) {
+            issues.push(issue(
+                "unknown-plugin",
+                LintSeverity::Warning,
+                Some("plugins"),
+                format!("{} is not a known ElizaOS plugin; check the name", plugin),
+            ));
+        }
+    }
+
+    if !plugins.iter().any(|plugin| MODEL_PLUGINS.contains(plugin)) {
+        issues.push(issue(
+            "missing-model-plugin",
+            LintSeverity::Warning,
+            Some("plugins"),
+            "No model provider plugin (OpenAI, Anthropic, Ollama, ...); the agent can't reply"
+                .to_string(),
+        ));
+    }
+}
+
+/// Lowercase npm package name, optionally scoped
+fn is_valid_package_name(name: &str) -> bool {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-._~".contains(c))
+    };
+    match name.strip_prefix('@') {
+        Some(scoped) => scoped
+            .split_once('/')
+            .is_some_and(|(scope, package)| valid_part(scope) && valid_part(package)),
+        None => name.len() <= 214 && valid_part(name),
+    }
+}
+
+/// A missing field, blank string, or list of blank strings
+fn text_is_blank(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::String(text)) => text.trim().is_empty(),
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .all(|line| line.as_str().is_none_or(|line| line.trim().is_empty())),
+        Some(_) => false,
+        None => true,
+    }
+}
+
+fn issue(
+    rule: &str,
+    severity: LintSeverity,
+    field: Option<&str>,
+    message: String,
+) -> CharacterLintIssue {
+    CharacterLintIssue {
+        rule: rule.to_string(),
+        severity,
+        message,
+        field: field.map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(issues: &[CharacterLintIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_complete_character() {
+        let character = json!({
+            "name": "Ada",
+            "bio": ["Mathematician"],
+            "system": "You are Ada.",
+            "style": { "all": ["precise"] },
+            "messageExamples": [
+                [{ "name": "user", "content": { "text": "hi" } }],
+                [{ "name": "user", "content": { "text": "bye" } }],
+            ],
+            "plugins": ["@elizaos/plugin-sql", "@elizaos/plugin-openai"],
+        });
+        assert!(lint(&character, 0).is_empty());
+    }
+
+    #[test]
+    fn test_lint_findings() {
+        let character = json!({
+            "name": "Ada",
+            "bio": ["  "],
+            "messageExamples": [[{ "name": "user", "content": { "text": "hi" } }], []],
+            "plugins": [
+                "@elizaos/plugin-opena1",
+                "@elizaos/plugin-sql",
+                "@elizaos/plugin-sql",
+                "Bad Plugin",
+            ],
+        });
+        let issues = lint(&character, 0);
+        assert_eq!(
+            rules(&issues),
+            vec![
+                "empty-bio",
+                "few-message-examples",
+                "unknown-plugin",
+                "duplicate-plugin",
+                "invalid-plugin-name",
+                "missing-model-plugin",
+                "missing-system",
+                "missing-style",
+            ]
+        );
+
+        let report = lint_report("ada", issues);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.warnings, 4);
+        assert_eq!(report.issues[0].rule, "invalid-plugin-name");
+    }
+
+    #[test]
+    fn test_lint_knowledge_size() {
+        let character = json!({ "name": "Ada", "knowledge": ["x".repeat(200 * 1024)] });
+        let issues = lint(&character, LARGE_KNOWLEDGE_BYTES);
+        assert!(rules(&issues).contains(&"large-inline-knowledge"));
+        let excessive = issues
+            .iter()
+            .find(|issue| issue.rule == "excessive-knowledge")
+            .unwrap();
+        assert_eq!(excessive.severity, LintSeverity::Warning);
+
+        let issues = lint(&character, MAX_KNOWLEDGE_TOTAL_BYTES);
+        assert!(issues
+            .iter()
+            .any(|issue| issue.rule == "excessive-knowledge"
+                && issue.severity == LintSeverity::Error));
+    }
+
+    #[test]
+    fn test_lint_invalid_character() {
+        let issues = lint(&json!({ "bio": "no name" }), 0);
+        assert_eq!(rules(&issues), vec!["invalid-character"]);
+        assert_eq!(issues[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_package_names() {
+        assert!(is_valid_package_name("@elizaos/plugin-sql"));
+        assert!(is_valid_package_name("eliza-plugin-weather"));
+        assert!(!is_valid_package_name("@elizaos"));
+        assert!(!is_valid_package_name("Bad Plugin"));
+        assert!(!is_valid_package_name(".hidden"));
+    }
+}
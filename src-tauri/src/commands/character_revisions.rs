@@ -0,0 +1,255 @@
+//! Character revisions
+//! Every save of a managed character is kept as a numbered revision under the character's
+//! `revisions` directory, so edits can be compared and rolled back
+
+use crate::commands::characters::{
+    character_dir, load_character, load_metadata, save_character, validate_character,
+};
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, CharacterFieldChange, CharacterRevision,
+    CharacterRevisionDiff, ManagedCharacter,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+pub const REVISIONS_DIR: &str = "revisions";
+const INDEX_FILE: &str = "index.json";
+/// Oldest revisions beyond this are pruned
+const MAX_REVISIONS: usize = 100;
+
+/// Revisions of a managed character, newest first
+#[tauri::command]
+pub async fn list_character_revisions(
+    app: AppHandle,
+    id: String,
+) -> Result<ApiResponse<Vec<CharacterRevision>>, String> {
+    match read_index(&app, &id) {
+        Ok(mut revisions) => {
+            revisions.reverse();
+            Ok(ApiResponse::success(revisions))
+        }
+        Err(e) => Ok(error_response("Failed to list character revisions", e)),
+    }
+}
+
+/// Fields that changed between two revisions of a character
+#[tauri::command]
+pub async fn diff_character_revisions(
+    app: AppHandle,
+    id: String,
+    a: u32,
+    b: u32,
+) -> Result<ApiResponse<CharacterRevisionDiff>, String> {
+    let result = (|| {
+        let before = load_revision(&app, &id, a)?;
+        let after = load_revision(&app, &id, b)?;
+        let mut changes = Vec::new();
+        diff_values("", &before, &after, &mut changes);
+        Ok::<_, AppError>(CharacterRevisionDiff {
+            character_id: id.clone(),
+            from: a,
+            to: b,
+            changes,
+        })
+    })();
+
+    match result {
+        Ok(diff) => Ok(ApiResponse::success(diff)),
+        Err(e) => Ok(error_response("Failed to diff character revisions", e)),
+    }
+}
+
+/// Restore a character to an earlier revision
+///
+/// The restored content is saved as a new revision, so a rollback can itself be undone.
+#[tauri::command]
+pub async fn rollback_character(
+    app: AppHandle,
+    id: String,
+    revision: u32,
+) -> Result<ApiResponse<ManagedCharacter>, String> {
+    let result = (|| {
+        let character = load_revision(&app, &id, revision)?;
+        validate_character(&character)?;
+        let metadata = load_metadata(&app, &id)?;
+        save_character(
+            &app,
+            &id,
+            &character,
+            &metadata,
+            Some(&format!("Rolled back to revision {}", revision)),
+        )
+    })();
+
+    match result {
+        Ok(managed) => {
+            log::info!("Rolled character {} back to revision {}", id, revision);
+            Ok(ApiResponse::success(managed))
+        }
+        Err(e) => Ok(error_response("Failed to roll back character", e)),
+    }
+}
+
+/// Keep a character's content as a new revision; unchanged content is not recorded twice
+pub(crate) fn record_revision(
+    app: &AppHandle,
+    id: &str,
+    contents: &[u8],
+    message: Option<&str>,
+) -> Result<Option<CharacterRevision>, AppError> {
+    let mut revisions = read_index(app, id)?;
+    let hash = format!("{:x}", Sha256::digest(contents));
+    if revisions.last().is_some_and(|last| last.hash == hash) {
+        return Ok(None);
+    }
+
+    let revision = CharacterRevision {
+        number: revisions.last().map_or(1, |last| last.number + 1),
+        hash,
+        created_at: current_timestamp(),
+        message: message.map(|m| m.to_string()),
+        size_bytes: contents.len() as u64,
+    };
+    let dir = revisions_dir(app, id)?;
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(revision_file(revision.number)), contents)?;
+    revisions.push(revision.clone());
+
+    let excess = revisions.len().saturating_sub(MAX_REVISIONS);
+    for pruned in revisions.drain(..excess) {
+        let _ = fs::remove_file(dir.join(revision_file(pruned.number)));
+    }
+    write_index(app, id, &revisions)?;
+    Ok(Some(revision))
+}
+
+/// Record the first revision of a character saved before revisions were kept
+pub(crate) fn ensure_initial_revision(app: &AppHandle, id: &str) -> Result<(), AppError> {
+    if !read_index(app, id)?.is_empty() {
+        return Ok(());
+    }
+    if let Ok(character) = load_character(app, id) {
+        record_revision(app, id, &serde_json::to_vec_pretty(&character)?, None)?;
+    }
+    Ok(())
+}
+
+fn load_revision(app: &AppHandle, id: &str, number: u32) -> Result<Value, AppError> {
+    let path = revisions_dir(app, id)?.join(revision_file(number));
+    if !path.exists() {
+        return Err(AppError::CharacterError(format!(
+            "Character '{}' has no revision {}",
+            id, number
+        )));
+    }
+    serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        AppError::CharacterError(format!("Revision {} is not valid JSON: {}", number, e))
+    })
+}
+
+/// Changes from `before` to `after` as JSON pointer paths; objects are compared per key
+fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<CharacterFieldChange>) {
+    if before == after {
+        return;
+    }
+    if let (Value::Object(before), Value::Object(after)) = (before, after) {
+        for (key, old) in before {
+            let field = format!("{}/{}", path, escape_pointer(key));
+            match after.get(key) {
+                Some(new) => diff_values(&field, old, new, changes),
+                None => changes.push(CharacterFieldChange {
+                    path: field,
+                    before: Some(old.clone()),
+                    after: None,
+                }),
+            }
+        }
+        for (key, new) in after.iter().filter(|(key, _)| !before.contains_key(*key)) {
+            changes.push(CharacterFieldChange {
+                path: format!("{}/{}", path, escape_pointer(key)),
+                before: None,
+                after: Some(new.clone()),
+            });
+        }
+        return;
+    }
+    changes.push(CharacterFieldChange {
+        path: path.to_string(),
+        before: Some(before.clone()),
+        after: Some(after.clone()),
+    });
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn revision_file(number: u32) -> String {
+    format!("{}.json", number)
+}
+
+fn revisions_dir(app: &AppHandle, id: &str) -> Result<PathBuf, AppError> {
+    Ok(character_dir(app, id)?.join(REVISIONS_DIR))
+}
+
+fn read_index(app: &AppHandle, id: &str) -> Result<Vec<CharacterRevision>, AppError> {
+    let path = revisions_dir(app, id)?.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_index(app: &AppHandle, id: &str, revisions: &[CharacterRevision]) -> Result<(), AppError> {
+    let path = revisions_dir(app, id)?.join(INDEX_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(revisions)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_values() {
+        let before = json!({
+            "name": "Ada",
+            "bio": ["Analyst"],
+            "settings": { "model": "gpt-4o", "voice": "en" },
+        });
+        let after = json!({
+            "name": "Ada",
+            "bio": ["Analyst", "Poet"],
+            "settings": { "model": "gpt-4o-mini" },
+            "style/tone": "dry",
+        });
+
+        let mut changes = Vec::new();
+        diff_values("", &before, &after, &mut changes);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["/bio", "/settings/model", "/settings/voice", "/style~1tone"]
+        );
+        assert_eq!(changes[2].after, None);
+        assert_eq!(changes[3].before, None);
+
+        let mut unchanged = Vec::new();
+        diff_values("", &before, &before, &mut unchanged);
+        assert!(unchanged.is_empty());
+    }
+}
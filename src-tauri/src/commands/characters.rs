@@ -0,0 +1,304 @@
+//! Managed characters
+//! Characters kept in the app data directory, one directory per character ID holding the
+//! character JSON and its bookkeeping metadata
+
+use crate::commands::character_revisions::{ensure_initial_revision, record_revision};
+use crate::models::{AppError, CharacterMetadata, ManagedCharacter};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const CHARACTERS_DIR: &str = "characters";
+pub(crate) const CHARACTER_FILE: &str = "character.json";
+pub(crate) const METADATA_FILE: &str = "metadata.json";
+const MAX_ID_LEN: usize = 64;
+/// Largest character file accepted for upload
+pub const MAX_CHARACTER_BYTES: usize = 1024 * 1024;
+
+/// Directory of a managed character; IDs double as directory names
+pub(crate) fn character_dir(app: &AppHandle, id: &str) -> Result<PathBuf, AppError> {
+    validate_character_id(id)?;
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?
+        .join(CHARACTERS_DIR)
+        .join(id);
+    Ok(dir)
+}
+
+/// Path of a managed character's JSON file, failing when the character doesn't exist
+pub(crate) fn character_file(app: &AppHandle, id: &str) -> Result<PathBuf, AppError> {
+    let path = character_dir(app, id)?.join(CHARACTER_FILE);
+    if !path.exists() {
+        return Err(AppError::CharacterError(format!(
+            "Character '{}' not found",
+            id
+        )));
+    }
+    Ok(path)
+}
+
+pub(crate) fn load_character(app: &AppHandle, id: &str) -> Result<Value, AppError> {
+    let path = character_file(app, id)?;
+    serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        AppError::CharacterError(format!("Character '{}' is not valid JSON: {}", id, e))
+    })
+}
+
+/// Write a character file, replacing any previous version, and record it as a revision
+pub(crate) fn save_character(
+    app: &AppHandle,
+    id: &str,
+    character: &Value,
+    metadata: &CharacterMetadata,
+    message: Option<&str>,
+) -> Result<ManagedCharacter, AppError> {
+    let dir = character_dir(app, id)?;
+    fs::create_dir_all(&dir)?;
+
+    let contents = serde_json::to_vec_pretty(character)?;
+    ensure_initial_revision(app, id)?;
+    record_revision(app, id, &contents, message)?;
+
+    let path = dir.join(CHARACTER_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, &path)?;
+    save_metadata(app, id, metadata)?;
+
+    Ok(ManagedCharacter {
+        id: id.to_string(),
+        name: character_name(character).unwrap_or(id).to_string(),
+        path: path.to_string_lossy().to_string(),
+        metadata: metadata.clone(),
+    })
+}
+
+/// Read and validate a character JSON file from anywhere on disk
+pub(crate) fn read_character_file(path: &Path) -> Result<Value, AppError> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_CHARACTER_BYTES as u64 {
+        return Err(AppError::CharacterError(format!(
+            "Character file is {} bytes; the limit is {} bytes",
+            size, MAX_CHARACTER_BYTES
+        )));
+    }
+    let character: Value = serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| {
+        AppError::CharacterError(format!("{} is not valid JSON: {}", path.display(), e))
+    })?;
+    validate_character(&character)?;
+    Ok(character)
+}
+
+/// Save a validated character under a new ID; nothing is left behind if saving fails
+pub(crate) fn import_character(
+    app: &AppHandle,
+    character: &Value,
+) -> Result<ManagedCharacter, AppError> {
+    let id = unique_character_id(app, character_name(character).unwrap_or("character"))?;
+    let message = Some("Imported from a character file");
+    save_character(app, &id, character, &CharacterMetadata::default(), message).inspect_err(|_| {
+        if let Ok(dir) = character_dir(app, &id) {
+            let _ = fs::remove_dir_all(dir);
+        }
+    })
+}
+
+/// Whether a managed character with this ID exists
+pub(crate) fn character_exists(app: &AppHandle, id: &str) -> Result<bool, AppError> {
+    Ok(character_dir(app, id)?.join(CHARACTER_FILE).exists())
+}
+
+/// First free ID derived from a character name: `slug`, `slug-2`, `slug-3`...
+pub(crate) fn unique_character_id(app: &AppHandle, name: &str) -> Result<String, AppError> {
+    let slug = slugify(name);
+    for n in 1..=100 {
+        let id = if n == 1 {
+            slug.clone()
+        } else {
+            format!("{}-{}", slug, n)
+        };
+        if !character_exists(app, &id)? {
+            return Ok(id);
+        }
+    }
+
+    Err(AppError::CharacterError(format!(
+        "Too many characters named '{}'",
+        slug
+    )))
+}
+
+pub(crate) fn character_name(character: &Value) -> Option<&str> {
+    character
+        .get("name")
+        .and_then(|name| name.as_str())
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+}
+
+/// Turn a character name into an ID-safe slug
+pub(crate) fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug
+        .trim_end_matches('-')
+        .chars()
+        .take(MAX_ID_LEN)
+        .collect();
+    if slug.is_empty() {
+        "character".to_string()
+    } else {
+        slug
+    }
+}
+
+pub(crate) fn load_metadata(app: &AppHandle, id: &str) -> Result<CharacterMetadata, AppError> {
+    let path = character_dir(app, id)?.join(METADATA_FILE);
+    if !path.exists() {
+        return Ok(CharacterMetadata::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub(crate) fn save_metadata(
+    app: &AppHandle,
+    id: &str,
+    metadata: &CharacterMetadata,
+) -> Result<(), AppError> {
+    let dir = character_dir(app, id)?;
+    fs::create_dir_all(&dir)?;
+    fs::write(
+        dir.join(METADATA_FILE),
+        serde_json::to_vec_pretty(metadata)?,
+    )?;
+    Ok(())
+}
+
+pub(crate) fn validate_character_id(id: &str) -> Result<(), AppError> {
+    let valid = !id.is_empty()
+        && id.len() <= MAX_ID_LEN
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if !valid {
+        return Err(AppError::CharacterError(format!(
+            "Invalid character ID '{}': use 1-{} letters, digits, '-', '_' or '.'",
+            id, MAX_ID_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Check the fields ElizaOS requires before a character is uploaded or run
+pub fn validate_character(character: &Value) -> Result<(), AppError> {
+    let object = character
+        .as_object()
+        .ok_or_else(|| AppError::CharacterError("Character must be a JSON object".to_string()))?;
+
+    if character_name(character).is_none() {
+        return Err(AppError::CharacterError(
+            "Character needs a non-empty \"name\"".to_string(),
+        ));
+    }
+
+    if let Some(bio) = object.get("bio") {
+        let valid = bio.is_string()
+            || bio
+                .as_array()
+                .is_some_and(|lines| lines.iter().all(|line| line.is_string()));
+        if !valid {
+            return Err(AppError::CharacterError(
+                "\"bio\" must be a string or a list of strings".to_string(),
+            ));
+        }
+    }
+
+    for field in ["plugins", "topics", "adjectives"] {
+        if let Some(value) = object.get(field) {
+            let valid = value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| item.is_string()));
+            if !valid {
+                return Err(AppError::CharacterError(format!(
+                    "\"{}\" must be a list of strings",
+                    field
+                )));
+            }
+        }
+    }
+
+    let size = serde_json::to_vec(character)?.len();
+    if size > MAX_CHARACTER_BYTES {
+        return Err(AppError::CharacterError(format!(
+            "Character is {} bytes; the limit is {} bytes",
+            size, MAX_CHARACTER_BYTES
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_character() {
+        assert!(validate_character(&json!({ "name": "Ada", "bio": ["a", "b"] })).is_ok());
+        assert!(validate_character(&json!({ "name": "Ada", "bio": "short" })).is_ok());
+        assert!(validate_character(&json!([])).is_err());
+        assert!(validate_character(&json!({ "name": " " })).is_err());
+        assert!(validate_character(&json!({ "name": "Ada", "bio": 3 })).is_err());
+        assert!(validate_character(&json!({ "name": "Ada", "plugins": [1] })).is_err());
+    }
+
+    #[test]
+    fn test_read_character_file() {
+        let dir = std::env::temp_dir().join(format!("characters-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let valid = dir.join("ada.json");
+        fs::write(&valid, r#"{ "name": "Ada", "bio": "Analyst" }"#).unwrap();
+        assert_eq!(read_character_file(&valid).unwrap()["name"], "Ada");
+
+        let invalid = dir.join("broken.json");
+        fs::write(&invalid, "{ name: Ada").unwrap();
+        assert_eq!(
+            read_character_file(&invalid).unwrap_err().error_code(),
+            "CHARACTER_ERROR"
+        );
+
+        let nameless = dir.join("nameless.json");
+        fs::write(&nameless, r#"{ "bio": "?" }"#).unwrap();
+        assert!(read_character_file(&nameless).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Ada Lovelace"), "ada-lovelace");
+        assert_eq!(slugify("  C-3PO!! "), "c-3po");
+        assert_eq!(slugify("日本"), "character");
+        assert!(validate_character_id(&slugify(&"x".repeat(200))).is_ok());
+    }
+
+    #[test]
+    fn test_validate_character_id() {
+        assert!(validate_character_id("ada-v2").is_ok());
+        assert!(validate_character_id("../ada").is_err());
+        assert!(validate_character_id(".ada").is_err());
+        assert!(validate_character_id("").is_err());
+    }
+}
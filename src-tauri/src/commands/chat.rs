@@ -0,0 +1,173 @@
+//! Local agent chat bridge
+//! Talks to agents served by a locally running ElizaOS instance over its REST API
+
+use crate::models::{ApiResponse, AppError};
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Agents may call tools or models before answering
+const CHAT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Base URL of an agent server listening on a local port
+pub fn agent_base_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}", port)
+}
+
+/// One conversation with a local agent server; every message uses the same user and room
+pub struct ChatBridge {
+    client: Client,
+    base_url: String,
+    user_id: String,
+}
+
+impl ChatBridge {
+    pub fn new(base_url: String) -> Result<Self, AppError> {
+        let client = Client::builder()
+            .timeout(CHAT_TIMEOUT)
+            .user_agent("ElizaOS-Desktop/0.1.0")
+            .build()
+            .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            user_id: uuid::Uuid::new_v4().to_string(),
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The given agent, or the first agent the server reports
+    pub async fn resolve_agent(&self, agent: Option<&str>) -> Result<String, AppError> {
+        if let Some(agent) = agent.filter(|a| !a.trim().is_empty()) {
+            return Ok(agent.to_string());
+        }
+
+        let body = self
+            .send(self.client.get(format!("{}/agents", self.base_url)))
+            .await?;
+        first_agent_id(&body)
+            .ok_or_else(|| AppError::Network(format!("No agents running at {}", self.base_url)))
+    }
+
+    /// Send a message to an agent and return its reply text
+    pub async fn send_message(&self, agent: &str, text: &str) -> Result<String, AppError> {
+        let request = self
+            .client
+            .post(format!("{}/{}/message", self.base_url, agent))
+            .json(&json!({
+                "text": text,
+                "userId": self.user_id,
+                "roomId": format!("desktop-{}", self.user_id),
+                "userName": "User",
+            }));
+        Ok(reply_text(&self.send(request).await?))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Value, AppError> {
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                AppError::Network("Agent did not answer in time".to_string())
+            } else if e.is_connect() {
+                AppError::Network(format!("Failed to connect to agent at {}", self.base_url))
+            } else {
+                AppError::Network(format!("Agent request failed: {}", e))
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AppError::Network(format!(
+                "Agent returned HTTP {}: {}",
+                status.as_u16(),
+                error_text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::Network(format!("Invalid agent response: {}", e)))
+    }
+}
+
+/// Send one message to an agent running on a local port
+#[tauri::command]
+pub async fn send_agent_message(
+    port: u16,
+    agent: Option<String>,
+    text: String,
+) -> Result<ApiResponse<String>, String> {
+    let result = async {
+        let bridge = ChatBridge::new(agent_base_url(port))?;
+        let agent = bridge.resolve_agent(agent.as_deref()).await?;
+        bridge.send_message(&agent, &text).await
+    }
+    .await;
+
+    match result {
+        Ok(reply) => Ok(ApiResponse::success(reply)),
+        Err(e) => {
+            log::error!("Failed to message agent on port {}: {}", port, e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to message agent: {}", e),
+            ))
+        }
+    }
+}
+
+/// `{"agents": [{"id": ...}]}` or a bare list of agents
+fn first_agent_id(body: &Value) -> Option<String> {
+    let agents = body.get("agents").unwrap_or(body).as_array()?;
+    agents
+        .first()?
+        .get("id")
+        .and_then(|id| id.as_str())
+        .map(str::to_string)
+}
+
+/// Replies arrive as a list of messages, a single message, or `{"messages": [...]}`
+fn reply_text(body: &Value) -> String {
+    let messages = match body.get("messages").unwrap_or(body) {
+        Value::Array(messages) => messages.iter().collect(),
+        message => vec![message],
+    };
+    messages
+        .into_iter()
+        .filter_map(|message| message.get("text").and_then(|t| t.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_agent_id() {
+        let body = json!({ "agents": [{ "id": "a1", "name": "Ada" }, { "id": "a2" }] });
+        assert_eq!(first_agent_id(&body).as_deref(), Some("a1"));
+        assert_eq!(
+            first_agent_id(&json!([{ "id": "b1" }])).as_deref(),
+            Some("b1")
+        );
+        assert!(first_agent_id(&json!({ "agents": [] })).is_none());
+    }
+
+    #[test]
+    fn test_reply_text() {
+        let list = json!([{ "text": "Hello" }, { "text": "How can I help?" }]);
+        assert_eq!(reply_text(&list), "Hello\nHow can I help?");
+        assert_eq!(reply_text(&json!({ "text": "Hi" })), "Hi");
+        assert_eq!(
+            reply_text(&json!({ "messages": [{ "text": "Hey" }, { "action": "NONE" }] })),
+            "Hey"
+        );
+        assert_eq!(reply_text(&json!({})), "");
+    }
+}
@@ -0,0 +1,398 @@
+//! CLI cache cleanup
+//! Every `@latest` resolution can leave another copy of the ElizaOS CLI in npx's `_npx`
+//! cache or bun's install cache; this finds copies other than the newest, plus npx
+//! entries nobody has touched in a while, and removes them
+
+use crate::commands::process::has_running_runs;
+use crate::commands::storage::dir_usage;
+use crate::models::{ApiResponse, AppError, CliCacheEntry, CliCacheKind, CliCacheReport};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+const CLI_PACKAGE: &str = "@elizaos/cli";
+/// npx entries for other packages unused this long are removed
+const STALE_AFTER: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// npx entries without a manifest are interrupted installs once they are this old
+const PARTIAL_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A cached install found on disk
+#[derive(Debug, Clone)]
+struct CachedInstall {
+    kind: CliCacheKind,
+    path: PathBuf,
+    /// `name@spec` of the packages the entry provides; `None` without a readable manifest
+    packages: Option<Vec<String>>,
+    modified: SystemTime,
+    bytes: u64,
+}
+
+impl CachedInstall {
+    fn provides_cli(&self) -> bool {
+        self.packages.as_ref().is_some_and(|packages| {
+            packages.iter().any(|package| {
+                package
+                    .strip_prefix(CLI_PACKAGE)
+                    .is_some_and(|rest| rest.starts_with('@'))
+            })
+        })
+    }
+}
+
+/// Remove stale npx and bunx copies of the CLI, reporting the space reclaimed
+///
+/// With `dry_run` the stale entries are only reported. Nothing is removed while runs are
+/// active, since one of them may be executing from the cache.
+#[tauri::command]
+pub async fn clean_cli_caches(
+    app: AppHandle,
+    dry_run: Option<bool>,
+) -> Result<ApiResponse<CliCacheReport>, String> {
+    let dry_run = dry_run.unwrap_or(false);
+
+    let result = async {
+        if !dry_run && has_running_runs(&app).await {
+            return Err(AppError::Process(
+                "CLI caches can't be cleaned while runs are active".to_string(),
+            ));
+        }
+
+        let mut installs = Vec::new();
+        if let Some(dir) = npx_cache_dir() {
+            installs.extend(npx_installs(&dir)?);
+        }
+        if let Some(dir) = bun_cache_dir() {
+            installs.extend(bunx_installs(&dir)?);
+        }
+        Ok(clean(installs, SystemTime::now(), dry_run))
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            log::info!(
+                "{} {} stale CLI cache entries ({} bytes)",
+                if dry_run { "Found" } else { "Removed" },
+                report.entries.len(),
+                report.reclaimed_bytes
+            );
+            Ok(ApiResponse::success(report))
+        }
+        Err(e) => {
+            log::error!("Failed to clean CLI caches: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to clean CLI caches: {}", e),
+            ))
+        }
+    }
+}
+
+/// Remove (or with `dry_run`, only list) the stale installs
+fn clean(installs: Vec<CachedInstall>, now: SystemTime, dry_run: bool) -> CliCacheReport {
+    let mut report = CliCacheReport {
+        dry_run,
+        ..CliCacheReport::default()
+    };
+
+    for install in stale_installs(installs, now) {
+        let removed = !dry_run && {
+            match fs::remove_dir_all(&install.path) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to remove {:?}: {}", install.path, e);
+                    false
+                }
+            }
+        };
+        if dry_run || removed {
+            report.reclaimed_bytes += install.bytes;
+        }
+        report.entries.push(CliCacheEntry {
+            kind: install.kind,
+            path: install.path.to_string_lossy().to_string(),
+            packages: install.packages.unwrap_or_default(),
+            bytes: install.bytes,
+            last_modified: chrono::DateTime::<chrono::Utc>::from(install.modified).to_rfc3339(),
+            removed,
+        });
+    }
+    report
+}
+
+/// Every CLI copy but the newest of each runner, old npx entries and abandoned installs
+fn stale_installs(installs: Vec<CachedInstall>, now: SystemTime) -> Vec<CachedInstall> {
+    let newest_cli = |kind: CliCacheKind| {
+        installs
+            .iter()
+            .filter(|install| install.kind == kind && install.provides_cli())
+            .max_by_key(|install| install.modified)
+            .map(|install| install.path.clone())
+    };
+    let keep = [
+        newest_cli(CliCacheKind::Npx),
+        newest_cli(CliCacheKind::Bunx),
+    ];
+    let age = |install: &CachedInstall| now.duration_since(install.modified).unwrap_or_default();
+
+    installs
+        .iter()
+        .filter(|install| {
+            if keep.contains(&Some(install.path.clone())) {
+                false
+            } else if install.provides_cli() {
+                true
+            } else if install.packages.is_none() {
+                age(install) >= PARTIAL_AFTER
+            } else {
+                age(install) >= STALE_AFTER
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Entries of npx's `_npx` cache, one directory per package set
+fn npx_installs(cache_dir: &Path) -> Result<Vec<CachedInstall>, AppError> {
+    let npx_dir = cache_dir.join("_npx");
+    if !npx_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut installs = Vec::new();
+    for entry in fs::read_dir(&npx_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let packages = fs::read_to_string(path.join("package.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .map(|manifest| manifest_packages(&manifest));
+        installs.push(cached_install(CliCacheKind::Npx, path, packages)?);
+    }
+    Ok(installs)
+}
+
+/// Copies of the CLI in bun's install cache, one directory per version
+fn bunx_installs(cache_dir: &Path) -> Result<Vec<CachedInstall>, AppError> {
+    let (scope, name) = CLI_PACKAGE.split_once('/').unwrap_or(("", CLI_PACKAGE));
+    let scope_dir = cache_dir.join(scope);
+    if !scope_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut installs = Vec::new();
+    for entry in fs::read_dir(&scope_dir)? {
+        let path = entry?.path();
+        let dir_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Versions are cached as `cli@1.4.2@@@1`
+        let Some(version) = dir_name
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('@'))
+        else {
+            continue;
+        };
+        if !path.is_dir() {
+            continue;
+        }
+        let version = version.split("@@@").next().unwrap_or(version);
+        let packages = Some(vec![format!("{}@{}", CLI_PACKAGE, version)]);
+        installs.push(cached_install(CliCacheKind::Bunx, path, packages)?);
+    }
+    Ok(installs)
+}
+
+fn cached_install(
+    kind: CliCacheKind,
+    path: PathBuf,
+    packages: Option<Vec<String>>,
+) -> Result<CachedInstall, AppError> {
+    let modified = fs::metadata(&path)?.modified()?;
+    let (bytes, _) = dir_usage(&path)?;
+    Ok(CachedInstall {
+        kind,
+        path,
+        packages,
+        modified,
+        bytes,
+    })
+}
+
+/// `name@spec` for each dependency of an npx entry's generated manifest
+fn manifest_packages(manifest: &Value) -> Vec<String> {
+    manifest
+        .get("dependencies")
+        .and_then(Value::as_object)
+        .map(|dependencies| {
+            dependencies
+                .iter()
+                .map(|(name, spec)| format!("{}@{}", name, spec.as_str().unwrap_or("*")))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// npm's cache directory, honoring `npm_config_cache`
+fn npx_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_dir(&["npm_config_cache", "NPM_CONFIG_CACHE"]) {
+        return Some(dir);
+    }
+    if cfg!(windows) {
+        dirs::data_local_dir().map(|dir| dir.join("npm-cache"))
+    } else {
+        dirs::home_dir().map(|home| home.join(".npm"))
+    }
+}
+
+/// bun's global install cache, honoring `BUN_INSTALL_CACHE_DIR` and `BUN_INSTALL`
+fn bun_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env_dir(&["BUN_INSTALL_CACHE_DIR"]) {
+        return Some(dir);
+    }
+    env_dir(&["BUN_INSTALL"])
+        .or_else(|| dirs::home_dir().map(|home| home.join(".bun")))
+        .map(|dir| dir.join("install").join("cache"))
+}
+
+fn env_dir(names: &[&str]) -> Option<PathBuf> {
+    names
+        .iter()
+        .find_map(|name| std::env::var_os(name).filter(|value| !value.is_empty()))
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn install(
+        kind: CliCacheKind,
+        name: &str,
+        packages: Option<&[&str]>,
+        age_days: u32,
+        now: SystemTime,
+    ) -> CachedInstall {
+        CachedInstall {
+            kind,
+            path: PathBuf::from(name),
+            packages: packages.map(|p| p.iter().map(|s| s.to_string()).collect()),
+            modified: now - DAY * age_days,
+            bytes: 1000,
+        }
+    }
+
+    fn paths(installs: &[CachedInstall]) -> Vec<String> {
+        installs
+            .iter()
+            .map(|install| install.path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_stale_installs() {
+        let now = SystemTime::now();
+        let cli: &[&str] = &["@elizaos/cli@latest"];
+        let installs = vec![
+            install(CliCacheKind::Npx, "npx-old-cli", Some(cli), 3, now),
+            install(CliCacheKind::Npx, "npx-new-cli", Some(cli), 1, now),
+            install(
+                CliCacheKind::Npx,
+                "npx-recent-other",
+                Some(&["cowsay@1"]),
+                5,
+                now,
+            ),
+            install(
+                CliCacheKind::Npx,
+                "npx-old-other",
+                Some(&["cowsay@1"]),
+                40,
+                now,
+            ),
+            install(CliCacheKind::Npx, "npx-partial", None, 2, now),
+            install(
+                CliCacheKind::Bunx,
+                "bun-1.4.1",
+                Some(&["@elizaos/cli@1.4.1"]),
+                9,
+                now,
+            ),
+            install(
+                CliCacheKind::Bunx,
+                "bun-1.4.2",
+                Some(&["@elizaos/cli@1.4.2"]),
+                2,
+                now,
+            ),
+        ];
+
+        assert_eq!(
+            paths(&stale_installs(installs, now)),
+            vec!["npx-old-cli", "npx-old-other", "npx-partial", "bun-1.4.1"]
+        );
+    }
+
+    #[test]
+    fn test_provides_cli() {
+        let now = SystemTime::now();
+        let cli = install(
+            CliCacheKind::Npx,
+            "a",
+            Some(&["@elizaos/cli@^1.4.2"]),
+            0,
+            now,
+        );
+        let plugin = install(
+            CliCacheKind::Npx,
+            "b",
+            Some(&["@elizaos/cli-plugin@1"]),
+            0,
+            now,
+        );
+        assert!(cli.provides_cli());
+        assert!(!plugin.provides_cli());
+    }
+
+    #[test]
+    fn test_manifest_packages() {
+        let manifest = json!({ "dependencies": { "@elizaos/cli": "^1.4.2" } });
+        assert_eq!(manifest_packages(&manifest), vec!["@elizaos/cli@^1.4.2"]);
+        assert!(manifest_packages(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_removing() {
+        let dir = std::env::temp_dir().join(format!("cli-cache-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("_npx").join("abc123")).unwrap();
+        fs::write(
+            dir.join("_npx").join("abc123").join("package.json"),
+            r#"{"dependencies":{"cowsay":"1.6.0"}}"#,
+        )
+        .unwrap();
+
+        let installs = npx_installs(&dir).unwrap();
+        assert_eq!(installs.len(), 1);
+        assert_eq!(
+            installs[0].packages.as_deref(),
+            Some(&["cowsay@1.6.0".to_string()][..])
+        );
+
+        let report = clean(installs, SystemTime::now() + STALE_AFTER, true);
+        assert!(dir.join("_npx").join("abc123").exists());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.entries.len(), 1);
+        assert!(!report.entries[0].removed);
+        assert!(report.reclaimed_bytes > 0);
+    }
+}
@@ -0,0 +1,189 @@
+//! Clipboard copies
+//! Content for the frontend's copy buttons is assembled here rather than from what the page
+//! shows, so secrets are redacted before anything reaches the clipboard
+
+use crate::commands::config::{load_config_from_file, load_profile_config, resolve_profile};
+use crate::commands::network_capture::{is_sensitive_name, redact_text, REDACTED};
+use crate::commands::process::sanitize_args_for_logging;
+use crate::commands::reports::find_run;
+use crate::commands::support::redact_config;
+use crate::models::{ApiResponse, AppError, ClipboardContentKind, ClipboardCopy, RunResult};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Secret values shorter than this are left alone; replacing them would mangle ordinary text
+const MIN_SECRET_LEN: usize = 8;
+
+/// Copy redacted content to the clipboard: a run's logs (`id` is the run id) or a profile's
+/// configuration (`id` is the profile name, the active profile when omitted)
+#[tauri::command]
+pub async fn copy_to_clipboard(
+    app: AppHandle,
+    kind: ClipboardContentKind,
+    id: Option<String>,
+) -> Result<ApiResponse<ClipboardCopy>, String> {
+    let result = async {
+        let (id, text) = match kind {
+            ClipboardContentKind::RunLogs => {
+                let run_id = id.ok_or_else(|| {
+                    AppError::Process("A run id is required to copy run logs".to_string())
+                })?;
+                let text = run_logs_text(&app, &run_id).await?;
+                (run_id, text)
+            }
+            ClipboardContentKind::Config => {
+                let profile = resolve_profile(&app, id.as_deref())?;
+                let text = config_text(&app, &profile).await?;
+                (profile, text)
+            }
+        };
+
+        app.clipboard()
+            .write_text(text.clone())
+            .map_err(|e| AppError::Unknown(format!("Failed to write to the clipboard: {}", e)))?;
+
+        Ok::<_, AppError>(ClipboardCopy {
+            kind,
+            id,
+            length: text.chars().count(),
+            lines: text.lines().count(),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(copy) => {
+            log::info!("Copied {:?} {} to the clipboard", copy.kind, copy.id);
+            Ok(ApiResponse::success(copy))
+        }
+        Err(e) => {
+            log::error!("Failed to copy to the clipboard: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to copy to the clipboard: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+async fn run_logs_text(app: &AppHandle, run_id: &str) -> Result<String, AppError> {
+    let record = find_run(app, run_id).await?;
+    let result = record
+        .result
+        .ok_or_else(|| AppError::Process(format!("Run {} has no recorded result", run_id)))?;
+
+    let mut secrets = secret_env_values(&result);
+    // The key may appear in output even when it was never part of the run's env
+    if let Ok(Some(config)) = load_config_from_file(app).await {
+        secrets.push(config.api_key);
+    }
+    Ok(render_run_logs(&result, &secrets))
+}
+
+async fn config_text(app: &AppHandle, profile: &str) -> Result<String, AppError> {
+    let config = load_profile_config(app, profile).await?.ok_or_else(|| {
+        AppError::Config(format!("Profile {} has no saved configuration", profile))
+    })?;
+    Ok(serde_json::to_string_pretty(&redact_config(config))?)
+}
+
+/// Values of the run's env variables whose names mark them as secrets
+fn secret_env_values(result: &RunResult) -> Vec<String> {
+    result
+        .spec
+        .env
+        .iter()
+        .filter(|(name, _)| is_sensitive_name(name))
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+fn render_run_logs(result: &RunResult, secrets: &[String]) -> String {
+    let mut text = format!("Run {} ({})\n", result.id, result.status.as_str());
+    if let Some(exit_code) = result.exit_code {
+        text.push_str(&format!("Exit code: {}\n", exit_code));
+    }
+    text.push_str(&format!(
+        "$ elizaos {}\n",
+        sanitize_args_for_logging(&result.spec.args).join(" ")
+    ));
+
+    for (stream, lines) in [("stdout", &result.stdout), ("stderr", &result.stderr)] {
+        if lines.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\n[{}]\n", stream));
+        for line in lines {
+            text.push_str(&redact_line(line, secrets));
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Known secret values first, then anything else shaped like a token
+fn redact_line(line: &str, secrets: &[String]) -> String {
+    let line = secrets
+        .iter()
+        .filter(|secret| secret.len() >= MIN_SECRET_LEN)
+        .fold(line.to_string(), |line, secret| {
+            line.replace(secret.as_str(), REDACTED)
+        });
+    redact_text(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RunMode, RunSpec, RunStatus};
+
+    fn result() -> RunResult {
+        let mut spec = RunSpec::new("run_1".to_string(), RunMode::Run, vec!["start".to_string()]);
+        spec.env
+            .insert("OPENAI_API_KEY".to_string(), "sk-live-abcdefgh".to_string());
+        spec.env.insert(
+            "DATABASE_PASSWORD".to_string(),
+            "hunter2-hunter2".to_string(),
+        );
+        spec.env
+            .insert("LOG_LEVEL".to_string(), "debug".to_string());
+        RunResult {
+            stdout: vec![
+                "connecting with password hunter2-hunter2".to_string(),
+                "level is debug".to_string(),
+            ],
+            stderr: vec!["auth header Bearer abcdefghijklmnop".to_string()],
+            exit_code: Some(1),
+            status: RunStatus::Failed,
+            ..RunResult::new(spec, "run_1".to_string())
+        }
+    }
+
+    #[test]
+    fn test_secret_env_values() {
+        let mut secrets = secret_env_values(&result());
+        secrets.sort();
+        assert_eq!(secrets, vec!["hunter2-hunter2", "sk-live-abcdefgh"]);
+    }
+
+    #[test]
+    fn test_render_run_logs_redacts_secrets() {
+        let result = result();
+        let text = render_run_logs(&result, &secret_env_values(&result));
+        assert!(text.contains("$ elizaos start"));
+        assert!(text.contains("Exit code: 1"));
+        assert!(text.contains("connecting with password [REDACTED]"));
+        assert!(text.contains("level is debug"));
+        assert!(text.contains("auth header [REDACTED]"));
+        assert!(!text.contains("hunter2"));
+        assert!(!text.contains("abcdefghijklmnop"));
+    }
+
+    #[test]
+    fn test_short_secrets_are_not_replaced() {
+        assert_eq!(
+            redact_line("value 1234", &["1234".to_string()]),
+            "value 1234"
+        );
+    }
+}
@@ -0,0 +1,757 @@
+//! Sandbox cloud agents
+//! Lists and inspects agents hosted in the Sandbox cloud so they can be shown next to
+//! local runs in the same agent model, moves characters between the cloud and the
+//! managed character store, and reads the Sandbox model catalog and account usage
+
+use crate::commands::api_cache::{
+    cached_get, invalidate_cached, CLOUD_AGENTS_TTL, MODELS_TTL, USAGE_TTL,
+};
+use crate::commands::app_lock::require_unlocked;
+use crate::commands::audit::record_audit;
+use crate::commands::characters::{
+    character_exists, character_name, load_character, load_metadata, save_character, save_metadata,
+    slugify, validate_character,
+};
+use crate::commands::config::load_config_from_file;
+use crate::commands::sandbox_http::sandbox_http;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    Agent, AgentLocation, ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin,
+    CharacterProvenance, CloudDeployEvent, DeployStage, ManagedCharacter, SandboxConfig,
+    SandboxModel, UsageReport,
+};
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const CLOUD_TIMEOUT: Duration = Duration::from_secs(15);
+const DEPLOY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEPLOY_TIMEOUT: Duration = Duration::from_secs(300);
+/// Fields of an agent response that describe the deployment rather than the character
+const SERVER_FIELDS: &[&str] = &[
+    "id",
+    "status",
+    "url",
+    "createdAt",
+    "created_at",
+    "updatedAt",
+    "updated_at",
+    "deploymentId",
+    "ownerId",
+];
+
+/// Agent as returned by the Sandbox API; tolerant of camelCase and snake_case fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudAgentRecord {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default, alias = "created_at")]
+    pub created_at: Option<String>,
+    #[serde(default, alias = "updated_at")]
+    pub updated_at: Option<String>,
+    /// Character definition the agent was deployed with, when the API includes it
+    #[serde(default)]
+    pub character: Option<Value>,
+}
+
+impl From<CloudAgentRecord> for Agent {
+    fn from(record: CloudAgentRecord) -> Self {
+        Agent {
+            name: record.name.unwrap_or_else(|| record.id.clone()),
+            id: record.id,
+            location: AgentLocation::Cloud,
+            status: record.status.unwrap_or_else(|| "unknown".to_string()),
+            character_file: None,
+            url: record.url,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
+
+/// List the agents hosted in the Sandbox cloud for this API key
+///
+/// The list is cached briefly; pass `refresh` to fetch it again regardless.
+#[tauri::command]
+pub async fn list_cloud_agents(
+    app: AppHandle,
+    config: SandboxConfig,
+    refresh: Option<bool>,
+) -> Result<ApiResponse<Vec<Agent>>, String> {
+    log::info!("Listing cloud agents from {}", config.base_url);
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        let body = cached_get(
+            &app,
+            &config,
+            "agents",
+            CLOUD_AGENTS_TTL,
+            refresh.unwrap_or(false),
+        )
+        .await?;
+        parse_agent_list(body)
+    }
+    .await;
+
+    match result {
+        Ok(agents) => {
+            log::info!("Found {} cloud agents", agents.len());
+            Ok(ApiResponse::success(agents))
+        }
+        Err(e) => {
+            log::error!("Failed to list cloud agents: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to list cloud agents: {}", e),
+            ))
+        }
+    }
+}
+
+/// Models the Sandbox offers, cached for a few minutes unless `refresh` is set
+#[tauri::command]
+pub async fn list_sandbox_models(
+    app: AppHandle,
+    config: SandboxConfig,
+    refresh: Option<bool>,
+) -> Result<ApiResponse<Vec<SandboxModel>>, String> {
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        let body = cached_get(
+            &app,
+            &config,
+            "models",
+            MODELS_TTL,
+            refresh.unwrap_or(false),
+        )
+        .await?;
+        parse_model_list(body)
+    }
+    .await;
+
+    match result {
+        Ok(models) => {
+            if let Some(ref model) = config.default_model {
+                if !models.is_empty() && !models.iter().any(|m| m.matches(model)) {
+                    log::warn!("Default model {} is not in the Sandbox catalog", model);
+                }
+            }
+            Ok(ApiResponse::success(models))
+        }
+        Err(e) => {
+            log::error!("Failed to list Sandbox models: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to list Sandbox models: {}", e),
+            ))
+        }
+    }
+}
+
+/// Token and credit usage with the host's request quota, cached briefly unless `refresh`
+/// is set, so the remaining budget can be checked before a long run
+#[tauri::command]
+pub async fn get_sandbox_usage(
+    app: AppHandle,
+    config: SandboxConfig,
+    refresh: Option<bool>,
+) -> Result<ApiResponse<UsageReport>, String> {
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        let body = cached_get(&app, &config, "usage", USAGE_TTL, refresh.unwrap_or(false)).await?;
+        let mut report = parse_usage(body)?;
+        report.rate_limit = sandbox_http().status_for(&config.api_url("usage"));
+        Ok::<_, AppError>(report)
+    }
+    .await;
+
+    match result {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => {
+            log::error!("Failed to fetch Sandbox usage: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to fetch Sandbox usage: {}", e),
+            ))
+        }
+    }
+}
+
+/// Get one cloud agent using the saved Sandbox configuration
+#[tauri::command]
+pub async fn get_cloud_agent(app: AppHandle, id: String) -> Result<ApiResponse<Agent>, String> {
+    log::info!("Fetching cloud agent: {}", id);
+
+    let result = async {
+        let config = saved_config(&app).await?;
+        fetch_cloud_agent(&config, &id).await
+    }
+    .await;
+
+    match result {
+        Ok(agent) => Ok(ApiResponse::success(agent.into())),
+        Err(e) => {
+            log::error!("Failed to fetch cloud agent {}: {}", id, e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to fetch cloud agent: {}", e),
+            ))
+        }
+    }
+}
+
+/// Deploy a managed character as a cloud agent, emitting `cloud-deploy-status` events
+///
+/// Characters that were deployed before are updated in place; the resulting cloud agent
+/// ID is recorded in the character's metadata.
+#[tauri::command]
+pub async fn deploy_character_to_cloud(
+    app: AppHandle,
+    character_id: String,
+    config: SandboxConfig,
+) -> Result<ApiResponse<Agent>, String> {
+    log::info!("Deploying character {} to the Sandbox cloud", character_id);
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = deploy_character(&app, &character_id, &config).await;
+    invalidate_cached(&app, Some("agents")).await;
+
+    let entry = AuditEntry::new(
+        AuditAction::CloudDeploy,
+        AuditOrigin::Gui,
+        character_id.clone(),
+    )
+    .with_outcome(
+        result.is_ok(),
+        match &result {
+            Ok(agent) => Some(format!("Cloud agent {}", agent.id)),
+            Err(e) => Some(e.to_string()),
+        },
+    );
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok(agent) => {
+            log::info!(
+                "Character {} deployed as cloud agent {}",
+                character_id,
+                agent.id
+            );
+            Ok(ApiResponse::success(agent))
+        }
+        Err(e) => {
+            log::error!("Failed to deploy character {}: {}", character_id, e);
+            emit_deploy_status(
+                &app,
+                CloudDeployEvent::new(character_id, DeployStage::Failed, e.to_string()),
+            );
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to deploy character: {}", e),
+            ))
+        }
+    }
+}
+
+async fn deploy_character(
+    app: &AppHandle,
+    character_id: &str,
+    config: &SandboxConfig,
+) -> Result<Agent, AppError> {
+    let status = |stage: DeployStage, message: &str| {
+        CloudDeployEvent::new(character_id.to_string(), stage, message.to_string())
+    };
+
+    emit_deploy_status(app, status(DeployStage::Validating, "Validating character"));
+    let character = load_character(app, character_id)?;
+    validate_character(&character)?;
+    let mut metadata = load_metadata(app, character_id)?;
+
+    emit_deploy_status(app, status(DeployStage::Uploading, "Uploading character"));
+    let client = sandbox_http();
+    let payload = json!({
+        "name": character.get("name").cloned().unwrap_or_default(),
+        "character": character,
+    });
+    let request = match metadata.cloud_agent_id.as_deref() {
+        Some(agent_id) if validate_agent_id(agent_id).is_ok() => {
+            client.put(config.api_url(&format!("agents/{}", agent_id)))
+        }
+        _ => client.post(config.api_url("agents")),
+    };
+    let body = send_json_body(request, &payload, config).await?;
+    let record = parse_agent(unwrap_envelope(body, "agent"))?;
+    validate_agent_id(&record.id)?;
+
+    // Record the agent right away so a rollout that fails can still be managed
+    metadata.cloud_agent_id = Some(record.id.clone());
+    metadata.deployed_at = Some(crate::models::current_timestamp());
+    save_metadata(app, character_id, &metadata)?;
+
+    emit_deploy_status(
+        app,
+        status(DeployStage::Deploying, "Waiting for the agent to start")
+            .with_agent_id(record.id.clone()),
+    );
+    let record = wait_for_deployment(app, character_id, config, record).await?;
+
+    emit_deploy_status(
+        app,
+        status(DeployStage::Ready, "Agent deployed").with_agent_id(record.id.clone()),
+    );
+    Ok(record.into())
+}
+
+/// Poll the agent until the Sandbox reports it running or failed
+async fn wait_for_deployment(
+    app: &AppHandle,
+    character_id: &str,
+    config: &SandboxConfig,
+    mut record: CloudAgentRecord,
+) -> Result<CloudAgentRecord, AppError> {
+    let started = Instant::now();
+
+    loop {
+        match deploy_outcome(record.status.as_deref()) {
+            Some(true) => return Ok(record),
+            Some(false) => {
+                return Err(AppError::Network(format!(
+                    "Deployment of agent {} failed with status '{}'",
+                    record.id,
+                    record.status.unwrap_or_default()
+                )))
+            }
+            None => {}
+        }
+
+        if started.elapsed() >= DEPLOY_TIMEOUT {
+            return Err(AppError::Network(format!(
+                "Agent {} was not ready after {}s",
+                record.id,
+                DEPLOY_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(DEPLOY_POLL_INTERVAL).await;
+
+        let previous = record.status.clone();
+        record = fetch_cloud_agent(config, &record.id).await?;
+        if record.status != previous {
+            emit_deploy_status(
+                app,
+                CloudDeployEvent::new(
+                    character_id.to_string(),
+                    DeployStage::Deploying,
+                    format!("Status: {}", record.status.as_deref().unwrap_or("unknown")),
+                )
+                .with_agent_id(record.id.clone()),
+            );
+        }
+    }
+}
+
+/// Whether a reported agent status means the deployment succeeded, failed or is still going
+///
+/// Agents without a status are taken as ready, since there is nothing to wait for.
+fn deploy_outcome(status: Option<&str>) -> Option<bool> {
+    let status = match status {
+        Some(status) => status.to_ascii_lowercase(),
+        None => return Some(true),
+    };
+    match status.as_str() {
+        "running" | "active" | "ready" | "deployed" => Some(true),
+        "failed" | "error" | "crashed" | "stopped" => Some(false),
+        _ => None,
+    }
+}
+
+fn emit_deploy_status(app: &AppHandle, event: CloudDeployEvent) {
+    log::debug!(
+        "Deploy {}: {:?} {}",
+        event.character_id,
+        event.stage,
+        event.message
+    );
+    emit_event(app, "cloud-deploy-status", event);
+}
+
+/// Pull a cloud agent's definition into a managed local character
+///
+/// Importing the same agent again refreshes the character it was imported into.
+#[tauri::command]
+pub async fn import_cloud_agent(
+    app: AppHandle,
+    agent_id: String,
+) -> Result<ApiResponse<ManagedCharacter>, String> {
+    log::info!("Importing cloud agent {} as a local character", agent_id);
+
+    match import_agent(&app, &agent_id).await {
+        Ok(character) => {
+            log::info!(
+                "Cloud agent {} imported as character {}",
+                agent_id,
+                character.id
+            );
+            Ok(ApiResponse::success(character))
+        }
+        Err(e) => {
+            log::error!("Failed to import cloud agent {}: {}", agent_id, e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to import cloud agent: {}", e),
+            ))
+        }
+    }
+}
+
+async fn import_agent(app: &AppHandle, agent_id: &str) -> Result<ManagedCharacter, AppError> {
+    let config = saved_config(app).await?;
+    let definition = fetch_agent_definition(&config, agent_id).await?;
+    let character = character_from_agent(definition)?;
+    validate_character(&character)?;
+
+    let id = import_target_id(app, agent_id, &character)?;
+    let mut metadata = load_metadata(app, &id)?;
+    metadata.cloud_agent_id = Some(agent_id.to_string());
+    metadata.imported_from = Some(CharacterProvenance {
+        agent_id: agent_id.to_string(),
+        base_url: config.base_url.clone(),
+    });
+    metadata.synced_at = Some(crate::models::current_timestamp());
+
+    let message = format!("Imported from cloud agent {}", agent_id);
+    save_character(app, &id, &character, &metadata, Some(&message))
+}
+
+/// Character ID to import into: the one this agent was imported into before, or a free slug
+fn import_target_id(
+    app: &AppHandle,
+    agent_id: &str,
+    character: &Value,
+) -> Result<String, AppError> {
+    let slug = slugify(character_name(character).unwrap_or(agent_id));
+
+    for n in 1..=100 {
+        let id = if n == 1 {
+            slug.clone()
+        } else {
+            format!("{}-{}", slug, n)
+        };
+        if !character_exists(app, &id)? {
+            return Ok(id);
+        }
+        let metadata = load_metadata(app, &id)?;
+        if metadata
+            .imported_from
+            .is_some_and(|source| source.agent_id == agent_id)
+        {
+            return Ok(id);
+        }
+    }
+
+    Err(AppError::CharacterError(format!(
+        "Too many characters named '{}'",
+        slug
+    )))
+}
+
+/// Character definition of an agent: its `character` field, or the agent minus server fields
+fn character_from_agent(definition: Value) -> Result<Value, AppError> {
+    match definition {
+        Value::Object(mut object) => match object.remove("character") {
+            Some(character @ Value::Object(_)) => Ok(character),
+            _ => {
+                for field in SERVER_FIELDS {
+                    object.remove(*field);
+                }
+                Ok(Value::Object(object))
+            }
+        },
+        _ => Err(AppError::Network(
+            "Unexpected agent response: expected an object".to_string(),
+        )),
+    }
+}
+
+/// Saved Sandbox configuration, required for cloud commands that don't take one
+pub(crate) async fn saved_config(app: &AppHandle) -> Result<SandboxConfig, AppError> {
+    require_unlocked(app)?;
+    match load_config_from_file(app).await? {
+        Some(config) if config.is_valid() => Ok(config),
+        _ => Err(AppError::Config(
+            "No valid Sandbox configuration saved".to_string(),
+        )),
+    }
+}
+
+pub(crate) async fn fetch_cloud_agent(
+    config: &SandboxConfig,
+    id: &str,
+) -> Result<CloudAgentRecord, AppError> {
+    parse_agent(fetch_agent_definition(config, id).await?)
+}
+
+/// Raw agent definition as returned by the Sandbox API
+async fn fetch_agent_definition(config: &SandboxConfig, id: &str) -> Result<Value, AppError> {
+    validate_agent_id(id)?;
+    let client = sandbox_http();
+    let url = config.api_url(&format!("agents/{}", id));
+    let body = send_json(client.get(url), config).await?;
+    Ok(unwrap_envelope(body, "agent"))
+}
+
+/// Send an authenticated request built from `sandbox_http()` and parse the JSON response,
+/// mapping HTTP errors
+pub(crate) async fn send_json(
+    request: RequestBuilder,
+    config: &SandboxConfig,
+) -> Result<Value, AppError> {
+    send_request(request, None, config).await
+}
+
+/// Like `send_json`, with a JSON body that is compressed when it is large
+pub(crate) async fn send_json_body(
+    request: RequestBuilder,
+    body: &Value,
+    config: &SandboxConfig,
+) -> Result<Value, AppError> {
+    send_request(request, Some(serde_json::to_vec(body)?), config).await
+}
+
+async fn send_request(
+    request: RequestBuilder,
+    body: Option<Vec<u8>>,
+    config: &SandboxConfig,
+) -> Result<Value, AppError> {
+    let request = request
+        .timeout(CLOUD_TIMEOUT)
+        .header("Authorization", format!("Bearer {}", config.api_key));
+    let response = match body {
+        Some(body) => sandbox_http().send_json_body(request, body).await,
+        None => sandbox_http().send(request).await,
+    };
+    let response = response.map_err(|e| {
+        if e.is_timeout() {
+            AppError::Network("Sandbox request timed out".to_string())
+        } else if e.is_connect() {
+            AppError::Network("Failed to connect to the Sandbox API".to_string())
+        } else {
+            AppError::Network(format!("Sandbox request failed: {}", e))
+        }
+    })?;
+
+    let status = response.status();
+    if status.as_u16() == 401 {
+        return Err(AppError::Network(
+            "Authentication failed - please check your API key".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(AppError::Network(format!(
+            "Sandbox API returned {}: {}",
+            status, error_text
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to parse JSON response: {}", e)))
+}
+
+/// Agent IDs are interpolated into URLs, so reject anything that could change the path
+pub(crate) fn validate_agent_id(id: &str) -> Result<(), AppError> {
+    let valid = !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && id != "."
+        && id != "..";
+
+    if !valid {
+        return Err(AppError::Config(format!("Invalid cloud agent ID '{}'", id)));
+    }
+    Ok(())
+}
+
+/// Responses may wrap the payload as `{"<key>": ...}` or `{"data": ...}`
+pub(crate) fn unwrap_envelope(body: Value, key: &str) -> Value {
+    match body {
+        Value::Object(mut object) => match object.remove(key).or_else(|| object.remove("data")) {
+            Some(inner) => inner,
+            None => Value::Object(object),
+        },
+        other => other,
+    }
+}
+
+pub(crate) fn parse_agent(body: Value) -> Result<CloudAgentRecord, AppError> {
+    serde_json::from_value(body)
+        .map_err(|e| AppError::Network(format!("Unexpected agent response: {}", e)))
+}
+
+fn parse_agent_list(body: Value) -> Result<Vec<Agent>, AppError> {
+    let records: Vec<CloudAgentRecord> = serde_json::from_value(unwrap_envelope(body, "agents"))
+        .map_err(|e| AppError::Network(format!("Unexpected agent list response: {}", e)))?;
+    Ok(records.into_iter().map(Agent::from).collect())
+}
+
+fn parse_usage(body: Value) -> Result<UsageReport, AppError> {
+    serde_json::from_value::<UsageReport>(unwrap_envelope(body, "usage"))
+        .map(UsageReport::with_remaining)
+        .map_err(|e| AppError::Network(format!("Unexpected usage response: {}", e)))
+}
+
+fn parse_model_list(body: Value) -> Result<Vec<SandboxModel>, AppError> {
+    serde_json::from_value(unwrap_envelope(body, "models"))
+        .map_err(|e| AppError::Network(format!("Unexpected model list response: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_agent_list_envelopes() {
+        let agent =
+            json!({ "id": "a1", "name": "Ada", "status": "running", "created_at": "2024-01-01" });
+
+        for body in [
+            json!([agent.clone()]),
+            json!({ "agents": [agent.clone()] }),
+            json!({ "data": [agent.clone()] }),
+        ] {
+            let agents = parse_agent_list(body).unwrap();
+            assert_eq!(agents.len(), 1);
+            assert_eq!(agents[0].name, "Ada");
+            assert!(agents[0].is_cloud());
+            assert_eq!(agents[0].created_at.as_deref(), Some("2024-01-01"));
+        }
+
+        assert!(parse_agent_list(json!({ "agents": "nope" })).is_err());
+    }
+
+    #[test]
+    fn test_parse_model_list() {
+        let models = parse_model_list(json!({
+            "object": "list",
+            "data": [{ "id": "gpt-4o", "object": "model", "owned_by": "openai" }],
+        }))
+        .unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "gpt-4o");
+        assert_eq!(models[0].owned_by.as_deref(), Some("openai"));
+
+        let models =
+            parse_model_list(json!({ "models": [{ "id": "m1", "contextLength": 8192 }] })).unwrap();
+        assert_eq!(models[0].context_length, Some(8192));
+
+        let models = parse_model_list(json!({
+            "data": [{ "id": "gpt-4o", "context_window": 128000, "tier": "premium" }],
+        }))
+        .unwrap();
+        assert_eq!(models[0].context_length, Some(128000));
+        assert_eq!(models[0].pricing_tier.as_deref(), Some("premium"));
+        assert!(models[0].matches(" GPT-4o"));
+        assert!(!models[0].matches("gpt-4o-mini"));
+    }
+
+    #[test]
+    fn test_parse_usage() {
+        let report = parse_usage(json!({
+            "usage": {
+                "tokens_used": 1200,
+                "token_limit": 1000,
+                "credits_used": 2.5,
+                "credit_limit": 10.0,
+                "period_end": "2024-02-01T00:00:00Z",
+            }
+        }))
+        .unwrap();
+        assert_eq!(report.tokens_remaining, Some(0));
+        assert_eq!(report.credits_remaining, Some(7.5));
+        assert_eq!(report.period_end.as_deref(), Some("2024-02-01T00:00:00Z"));
+
+        let report = parse_usage(json!({ "creditsUsed": 1.0, "balance": 3.0 })).unwrap();
+        assert_eq!(report.credits_remaining, Some(3.0));
+        assert!(report.tokens_used.is_none());
+        assert!(report.rate_limit.is_none());
+
+        assert!(parse_usage(json!({ "tokens_used": "lots" })).is_err());
+    }
+
+    #[test]
+    fn test_agent_defaults() {
+        let agent: Agent = parse_agent(json!({ "id": "a2" })).unwrap().into();
+        assert_eq!(agent.name, "a2");
+        assert_eq!(agent.status, "unknown");
+    }
+
+    #[test]
+    fn test_character_from_agent() {
+        let nested = json!({ "id": "a1", "status": "running", "character": { "name": "Ada" } });
+        assert_eq!(
+            character_from_agent(nested).unwrap(),
+            json!({ "name": "Ada" })
+        );
+
+        let flat = json!({ "id": "a1", "status": "running", "name": "Ada", "bio": "hi" });
+        assert_eq!(
+            character_from_agent(flat).unwrap(),
+            json!({ "name": "Ada", "bio": "hi" })
+        );
+
+        assert!(character_from_agent(json!("nope")).is_err());
+    }
+
+    #[test]
+    fn test_deploy_outcome() {
+        assert_eq!(deploy_outcome(Some("Running")), Some(true));
+        assert_eq!(deploy_outcome(None), Some(true));
+        assert_eq!(deploy_outcome(Some("error")), Some(false));
+        assert_eq!(deploy_outcome(Some("provisioning")), None);
+    }
+
+    #[test]
+    fn test_validate_agent_id() {
+        assert!(validate_agent_id("agent_123-abc").is_ok());
+        assert!(validate_agent_id("").is_err());
+        assert!(validate_agent_id("..").is_err());
+        assert!(validate_agent_id("a/../b").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Request body compression
+//! Large JSON bodies sent to the Sandbox (telemetry, character uploads) are gzip or deflate
+//! encoded; a host that answers 415 is retried with a coding from its `Accept-Encoding`
+
+use flate2::read::{DeflateDecoder, GzDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Bodies smaller than this are sent as they are
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 8 * 1024;
+
+/// Content coding of a request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Deflate,
+    Identity,
+}
+
+impl ContentEncoding {
+    /// Coding named by a `Content-Encoding` header
+    pub fn from_header(value: &str) -> Option<ContentEncoding> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(ContentEncoding::Gzip),
+            "deflate" => Some(ContentEncoding::Deflate),
+            "identity" => Some(ContentEncoding::Identity),
+            _ => None,
+        }
+    }
+
+    pub fn header_value(&self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Deflate => Some("deflate"),
+            ContentEncoding::Identity => None,
+        }
+    }
+
+    pub fn encode(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentEncoding::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+            ContentEncoding::Identity => Ok(body.to_vec()),
+        }
+    }
+
+    pub fn decode(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        match self {
+            ContentEncoding::Gzip => GzDecoder::new(body).read_to_end(&mut decoded)?,
+            ContentEncoding::Deflate => DeflateDecoder::new(body).read_to_end(&mut decoded)?,
+            ContentEncoding::Identity => return Ok(body.to_vec()),
+        };
+        Ok(decoded)
+    }
+
+    /// Coding to retry with after a 415, from the response's `Accept-Encoding` (RFC 7694)
+    pub fn fallback(&self, accept_encoding: Option<&str>) -> ContentEncoding {
+        let accepted: Vec<String> = accept_encoding
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|coding| {
+                let mut parts = coding.split(';');
+                let name = parts.next()?.trim().to_lowercase();
+                let refused = parts.any(|param| {
+                    param
+                        .trim()
+                        .strip_prefix("q=")
+                        .and_then(|q| q.trim().parse::<f32>().ok())
+                        .is_some_and(|q| q == 0.0)
+                });
+                (!name.is_empty() && !refused).then_some(name)
+            })
+            .collect();
+
+        [ContentEncoding::Gzip, ContentEncoding::Deflate]
+            .into_iter()
+            .filter(|coding| coding != self)
+            .find(|coding| {
+                coding
+                    .header_value()
+                    .is_some_and(|value| accepted.iter().any(|a| a == value))
+            })
+            .unwrap_or(ContentEncoding::Identity)
+    }
+}
+
+/// Coding for a body of this size, given what the host last accepted
+pub fn encoding_for(len: usize, preferred: Option<ContentEncoding>) -> ContentEncoding {
+    if len < COMPRESSION_THRESHOLD_BYTES {
+        ContentEncoding::Identity
+    } else {
+        preferred.unwrap_or(ContentEncoding::Gzip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_round_trip() {
+        let body = br#"{"event":"run"}"#.repeat(1000);
+
+        for encoding in [
+            ContentEncoding::Gzip,
+            ContentEncoding::Deflate,
+            ContentEncoding::Identity,
+        ] {
+            let encoded = encoding.encode(&body).unwrap();
+            if encoding != ContentEncoding::Identity {
+                assert!(encoded.len() < body.len());
+            }
+            let name = encoding.header_value().unwrap_or("identity");
+            let decoded = ContentEncoding::from_header(name)
+                .unwrap()
+                .decode(&encoded)
+                .unwrap();
+            assert_eq!(decoded, body);
+        }
+        assert_eq!(ContentEncoding::from_header("br"), None);
+    }
+
+    #[test]
+    fn test_encoding_for_threshold() {
+        assert_eq!(encoding_for(100, None), ContentEncoding::Identity);
+        assert_eq!(
+            encoding_for(COMPRESSION_THRESHOLD_BYTES, None),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            encoding_for(COMPRESSION_THRESHOLD_BYTES, Some(ContentEncoding::Deflate)),
+            ContentEncoding::Deflate
+        );
+    }
+
+    #[test]
+    fn test_fallback_from_accept_encoding() {
+        let gzip = ContentEncoding::Gzip;
+        assert_eq!(gzip.fallback(Some("deflate")), ContentEncoding::Deflate);
+        assert_eq!(
+            gzip.fallback(Some("gzip, deflate;q=0")),
+            ContentEncoding::Identity
+        );
+        assert_eq!(gzip.fallback(Some("identity")), ContentEncoding::Identity);
+        assert_eq!(gzip.fallback(None), ContentEncoding::Identity);
+        assert_eq!(
+            ContentEncoding::Deflate.fallback(Some("br, GZIP")),
+            ContentEncoding::Gzip
+        );
+    }
+}
@@ -1,29 +1,82 @@
 //! Configuration management commands
-//! Handles saving, loading, and testing Sandbox configurations using JSON file storage
+//! Handles saving, loading, and testing Sandbox configurations using JSON file storage.
+//! Configurations are kept as named profiles: `default` in `sandbox_config.json`, others
+//! under `config-profiles/`, with the active one used by background tasks. A file that
+//! has to keep its API key (no OS keyring) is encrypted at rest with a key derived from
+//! the machine identifier, or from `ELIZA_CONFIG_PASSPHRASE` when that is set
 
+use crate::commands::app_lock::{require_unlocked, update_vault, with_vault};
+use crate::commands::audit::record_audit;
+use crate::commands::keyring::{delete_secret, get_secret, set_secret};
+use crate::commands::onboarding::complete_step;
+use crate::commands::sandbox_http::{build_client, sandbox_http};
+use crate::commands::stats::emit_event;
+use crate::commands::support::redact_config;
+use crate::crypto::{decode_hex, encode_hex, DerivedKey, KDF_ITERATIONS, SALT_LEN};
 use crate::models::{
-    ApiResponse, AppError, ConnectionMetadata, ConnectionTestResult, SandboxConfig,
+    current_timestamp, ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin, ConfigBackup,
+    ConfigBackupReason, ConfigChangeKind, ConfigChanged, ConfigImportResult, ConfigProfileSummary,
+    ConnectionMetadata, ConnectionTestResult, HealthCheckConfig, HealthCheckMethod, OnboardingStep,
+    PromptStreamResult, PromptTokenEvent, SandboxConfig, SandboxConfigExport,
 };
-use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json;
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 use tauri::Manager;
 use tokio::time::timeout;
 
 const CONFIG_FILE: &str = "sandbox_config.json";
+const PROFILES_DIR: &str = "config-profiles";
+const ACTIVE_PROFILE_FILE: &str = "active_config_profile.json";
+const BACKUPS_DIR: &str = "config-backups";
+const BACKUP_INDEX_FILE: &str = "index.json";
+/// Backups kept per profile; older ones are pruned
+const MAX_BACKUPS_PER_PROFILE: usize = 10;
+/// Profile stored in `sandbox_config.json`
+pub const DEFAULT_PROFILE: &str = "default";
+const MAX_PROFILE_NAME_LEN: usize = 64;
 const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(30);
+/// Covers the whole streamed response, not just the wait for headers
+const STREAMING_COMPLETION_TIMEOUT: Duration = Duration::from_secs(60);
+const LEGACY_CONFIG_KEYS: [(&str, &str); 3] = [
+    ("base_url", "baseUrl"),
+    ("api_key", "apiKey"),
+    ("default_model", "defaultModel"),
+];
+/// Key of the envelope wrapping an encrypted config file
+const ENCRYPTED_KEY: &str = "encrypted";
+const ENCRYPTED_CONFIG_VERSION: u32 = 1;
+/// Passphrase used instead of the machine identifier to encrypt config files
+const CONFIG_PASSPHRASE_ENV: &str = "ELIZA_CONFIG_PASSPHRASE";
+pub const CONFIG_EXPORT_FORMAT_VERSION: u32 = 1;
 
 /// Save Sandbox configuration to JSON file
+///
+/// Saves to `profile_name`, creating the profile if needed, or to the active profile.
 #[tauri::command]
 pub async fn save_sandbox_config(
     app: tauri::AppHandle,
     config: SandboxConfig,
+    profile_name: Option<String>,
 ) -> Result<ApiResponse<()>, String> {
     log::info!("Saving Sandbox configuration");
 
+    let profile =
+        match require_unlocked(&app).and_then(|_| resolve_profile(&app, profile_name.as_deref())) {
+            Ok(profile) => profile,
+            Err(e) => {
+                return Ok(ApiResponse::error(
+                    e.error_code().to_string(),
+                    e.localized_message(),
+                ))
+            }
+        };
+
     if !config.is_valid() {
         log::warn!(
             "Invalid configuration provided: {}",
@@ -34,14 +87,50 @@ pub async fn save_sandbox_config(
             "Configuration is invalid".to_string(),
         ));
     }
+    if let Some(ref http) = config.http {
+        if let Err(e) = build_client(http) {
+            log::warn!("Invalid HTTP settings provided: {}", e);
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.localized_message(),
+            ));
+        }
+    }
+    if let Some(ref health_check) = config.health_check {
+        if let Err(e) = validate_health_check(health_check) {
+            log::warn!("Invalid health check provided: {}", e);
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.localized_message(),
+            ));
+        }
+    }
 
-    match save_config_to_file(&app, &config).await {
+    match save_config_to_file(&app, &profile, &config).await {
         Ok(_) => {
-            log::info!("Configuration saved successfully");
+            log::info!("Configuration saved to profile {}", profile);
+            audit_config_change(
+                &app,
+                AuditAction::ConfigSaved,
+                &profile,
+                true,
+                Some(sanitize_config_for_log(&config)),
+            )
+            .await;
+            notify_config_changed(&app, ConfigChangeKind::Saved, &profile, Some(&config));
+            complete_step(&app, OnboardingStep::ConfigSaved).await;
             Ok(ApiResponse::success(()))
         }
         Err(e) => {
             log::error!("Failed to save configuration: {}", e);
+            audit_config_change(
+                &app,
+                AuditAction::ConfigSaved,
+                &profile,
+                false,
+                Some(e.to_string()),
+            )
+            .await;
             Ok(ApiResponse::error(
                 "SAVE_ERROR".to_string(),
                 format!("Failed to save configuration: {}", e),
@@ -50,14 +139,175 @@ pub async fn save_sandbox_config(
     }
 }
 
+/// Set the subcommands Custom runs may invoke, or restore the default allowlist with `None`
+///
+/// Run requests carry their own config, so runs read the allowlist from the saved profile
+/// and this admin-only command is the way to change it.
+#[tauri::command]
+pub async fn set_allowed_custom_subcommands(
+    app: tauri::AppHandle,
+    subcommands: Option<Vec<String>>,
+    profile_name: Option<String>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    let mut profile = profile_name.clone().unwrap_or_default();
+    let result = async {
+        require_unlocked(&app)?;
+        profile = resolve_profile(&app, profile_name.as_deref())?;
+        if subcommands
+            .iter()
+            .flatten()
+            .any(|subcommand| subcommand.trim().is_empty() || subcommand.trim() != subcommand)
+        {
+            return Err(AppError::Config(
+                "Allowed subcommands must be non-empty and without surrounding whitespace"
+                    .to_string(),
+            ));
+        }
+        let mut config = load_profile_config(&app, &profile).await?.ok_or_else(|| {
+            AppError::Config(format!("Configuration profile '{}' not found", profile))
+        })?;
+        config.allowed_custom_subcommands = subcommands.clone();
+        save_config_to_file(&app, &profile, &config).await?;
+        Ok::<_, AppError>(config)
+    }
+    .await;
+
+    audit_config_change(
+        &app,
+        AuditAction::ConfigSaved,
+        &profile,
+        result.is_ok(),
+        match result {
+            Ok(ref config) => Some(format!(
+                "allowed custom subcommands: {}",
+                config.allowed_custom_subcommands().join(", ")
+            )),
+            Err(ref e) => Some(e.to_string()),
+        },
+    )
+    .await;
+    match result {
+        Ok(config) => {
+            log::info!(
+                "Custom subcommand allowlist updated for profile {}",
+                profile
+            );
+            notify_config_changed(&app, ConfigChangeKind::Saved, &profile, Some(&config));
+            Ok(ApiResponse::success(config.allowed_custom_subcommands()))
+        }
+        Err(e) => {
+            log::error!("Failed to set allowed custom subcommands: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to set allowed custom subcommands: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Set the hosts, besides the base URL's, that a project's `.eliza-desktop.json` may point
+/// runs at with the API key; `None` trusts only the base URL's host
+///
+/// Runs read the list from the saved profile, so override files from cloned projects can't
+/// send the key elsewhere until a user approves their host here.
+#[tauri::command]
+pub async fn set_trusted_project_hosts(
+    app: tauri::AppHandle,
+    hosts: Option<Vec<String>>,
+    profile_name: Option<String>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    let mut profile = profile_name.clone().unwrap_or_default();
+    let result = async {
+        require_unlocked(&app)?;
+        profile = resolve_profile(&app, profile_name.as_deref())?;
+        let hosts = hosts
+            .map(|hosts| hosts.iter().map(|host| normalize_host(host)).collect())
+            .transpose()?;
+        let mut config = load_profile_config(&app, &profile).await?.ok_or_else(|| {
+            AppError::Config(format!("Configuration profile '{}' not found", profile))
+        })?;
+        config.trusted_project_hosts = hosts;
+        save_config_to_file(&app, &profile, &config).await?;
+        Ok::<_, AppError>(config)
+    }
+    .await;
+
+    audit_config_change(
+        &app,
+        AuditAction::ConfigSaved,
+        &profile,
+        result.is_ok(),
+        match result {
+            Ok(ref config) => Some(format!(
+                "trusted project hosts: {}",
+                config
+                    .trusted_project_hosts
+                    .clone()
+                    .unwrap_or_default()
+                    .join(", ")
+            )),
+            Err(ref e) => Some(e.to_string()),
+        },
+    )
+    .await;
+    match result {
+        Ok(config) => {
+            log::info!("Trusted project hosts updated for profile {}", profile);
+            notify_config_changed(&app, ConfigChangeKind::Saved, &profile, Some(&config));
+            Ok(ApiResponse::success(
+                config.trusted_project_hosts.unwrap_or_default(),
+            ))
+        }
+        Err(e) => {
+            log::error!("Failed to set trusted project hosts: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to set trusted project hosts: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// A bare host name, lowercased; URLs and values with a port or path are refused
+fn normalize_host(host: &str) -> Result<String, AppError> {
+    let parsed = reqwest::Url::parse(&format!("https://{}", host)).ok();
+    match parsed.as_ref().and_then(|url| url.host_str()) {
+        Some(parsed_host) if parsed_host.eq_ignore_ascii_case(host) => Ok(parsed_host.to_string()),
+        _ => Err(AppError::Config(format!(
+            "'{}' is not a host name; give hosts like staging.example.com",
+            host
+        ))),
+    }
+}
+
 /// Load Sandbox configuration from JSON file
+///
+/// Loads `profile_name`, or the active profile, so runs can be started against either.
 #[tauri::command]
 pub async fn load_sandbox_config(
     app: tauri::AppHandle,
+    profile_name: Option<String>,
 ) -> Result<ApiResponse<SandboxConfig>, String> {
     log::info!("Loading Sandbox configuration");
 
-    match load_config_from_file(&app).await {
+    let profile =
+        match require_unlocked(&app).and_then(|_| resolve_profile(&app, profile_name.as_deref())) {
+            Ok(profile) => profile,
+            Err(e) => {
+                return Ok(ApiResponse::error(
+                    e.error_code().to_string(),
+                    e.localized_message(),
+                ))
+            }
+        };
+
+    match load_profile_config(&app, &profile).await {
         Ok(Some(config)) => {
             log::info!("Configuration loaded successfully");
             Ok(ApiResponse::success(config))
@@ -79,154 +329,1332 @@ pub async fn load_sandbox_config(
     }
 }
 
-/// Clear saved Sandbox configuration
-#[tauri::command]
-pub async fn clear_sandbox_config(app: tauri::AppHandle) -> Result<ApiResponse<()>, String> {
-    log::info!("Clearing Sandbox configuration");
+/// Clear saved Sandbox configuration
+///
+/// Clears the active profile; clearing a named profile makes `default` active again.
+#[tauri::command]
+pub async fn clear_sandbox_config(app: tauri::AppHandle) -> Result<ApiResponse<()>, String> {
+    log::info!("Clearing Sandbox configuration");
+
+    let profile = active_profile(&app).unwrap_or_else(|_| DEFAULT_PROFILE.to_string());
+    match clear_config_file(&app, &profile).await {
+        Ok(_) => {
+            log::info!("Configuration cleared successfully");
+            audit_config_change(&app, AuditAction::ConfigCleared, &profile, true, None).await;
+            notify_config_changed(&app, ConfigChangeKind::Cleared, &profile, None);
+            Ok(ApiResponse::success(()))
+        }
+        Err(e) => {
+            log::error!("Failed to clear configuration: {}", e);
+            audit_config_change(
+                &app,
+                AuditAction::ConfigCleared,
+                &profile,
+                false,
+                Some(e.to_string()),
+            )
+            .await;
+            Ok(ApiResponse::error(
+                "CLEAR_ERROR".to_string(),
+                format!("Failed to clear configuration: {}", e),
+            ))
+        }
+    }
+}
+
+/// Saved configuration profiles, without their API keys
+#[tauri::command]
+pub async fn list_config_profiles(
+    app: tauri::AppHandle,
+) -> Result<ApiResponse<Vec<ConfigProfileSummary>>, String> {
+    match profile_summaries(&app) {
+        Ok(profiles) => Ok(ApiResponse::success(profiles)),
+        Err(e) => {
+            log::error!("Failed to list configuration profiles: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to list configuration profiles: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Make a saved profile the one background tasks and `load_sandbox_config` use
+#[tauri::command]
+pub async fn set_active_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<ApiResponse<ConfigProfileSummary>, String> {
+    let result = (|| {
+        if !profile_config_path(&app, &name)?.exists() {
+            return Err(AppError::Config(format!(
+                "Configuration profile '{}' not found",
+                name
+            )));
+        }
+        write_active_profile(&app, &name)?;
+        profile_summaries(&app)?
+            .into_iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| AppError::Config(format!("Configuration profile '{}' not found", name)))
+    })();
+
+    audit_config_change(
+        &app,
+        AuditAction::ConfigProfileActivated,
+        &name,
+        result.is_ok(),
+        result.as_ref().err().map(|e| e.to_string()),
+    )
+    .await;
+    match result {
+        Ok(profile) => {
+            log::info!("Active configuration profile set to {}", name);
+            match read_profile_file(&app, &name) {
+                Ok(config) => {
+                    notify_config_changed(&app, ConfigChangeKind::Activated, &name, Some(&config))
+                }
+                Err(e) => log::warn!("Failed to read activated profile {}: {}", name, e),
+            }
+            Ok(ApiResponse::success(profile))
+        }
+        Err(e) => {
+            log::error!("Failed to set active configuration profile: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to set active configuration profile: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Delete a profile other than the active one, returning whether it existed
+#[tauri::command]
+pub async fn delete_config_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<ApiResponse<bool>, String> {
+    let result = (|| {
+        if active_profile(&app)? == name {
+            return Err(AppError::Config(format!(
+                "Configuration profile '{}' is active; activate another profile first",
+                name
+            )));
+        }
+        let path = profile_config_path(&app, &name)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+        update_vault(&app, |vault| vault.set_api_key(&name, String::new()))?;
+        forget_stashed_api_key(&app, &name);
+        fs::remove_file(path)?;
+        Ok(true)
+    })();
+
+    audit_config_change(
+        &app,
+        AuditAction::ConfigProfileDeleted,
+        &name,
+        result.is_ok(),
+        result.as_ref().err().map(|e| e.to_string()),
+    )
+    .await;
+    match result {
+        Ok(existed) => {
+            log::info!(
+                "Deleted configuration profile {} (existed: {})",
+                name,
+                existed
+            );
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => {
+            log::error!("Failed to delete configuration profile: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to delete configuration profile: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Export a profile's configuration to `path` (a file, or a directory) for sharing
+///
+/// The API key is left out unless `include_secrets` is set.
+#[tauri::command]
+pub async fn export_sandbox_config(
+    app: tauri::AppHandle,
+    path: String,
+    include_secrets: bool,
+    profile_name: Option<String>,
+) -> Result<ApiResponse<String>, String> {
+    let result = async {
+        let profile = resolve_profile(&app, profile_name.as_deref())?;
+        if include_secrets {
+            require_unlocked(&app)?;
+        }
+        let config = load_profile_config(&app, &profile).await?.ok_or_else(|| {
+            AppError::Config(format!("Configuration profile '{}' not found", profile))
+        })?;
+
+        let export_path = export_file_path(Path::new(&path), &profile);
+        if let Some(parent) = export_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let export = config_export(config, include_secrets);
+        fs::write(&export_path, serde_json::to_string_pretty(&export)?)?;
+        Ok::<_, AppError>((profile, export_path.to_string_lossy().to_string()))
+    }
+    .await;
+
+    let subject = result
+        .as_ref()
+        .map_or_else(|_| path.clone(), |(_, path)| path.clone());
+    let detail = match &result {
+        Ok((profile, _)) => Some(format!(
+            "profile {}, {}",
+            profile,
+            if include_secrets {
+                "with API key"
+            } else {
+                "API key omitted"
+            }
+        )),
+        Err(e) => Some(e.to_string()),
+    };
+    let entry = AuditEntry::new(AuditAction::ConfigExported, AuditOrigin::Gui, subject)
+        .with_outcome(result.is_ok(), detail);
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok((profile, path)) => {
+            log::info!("Exported configuration profile {} to {}", profile, path);
+            Ok(ApiResponse::success(path))
+        }
+        Err(e) => {
+            log::error!("Failed to export configuration: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to export configuration: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Import a configuration written by `export_sandbox_config` into a profile
+///
+/// Imports into `profile_name`, or the active profile. Without an API key in the file the
+/// profile keeps the key it already has.
+#[tauri::command]
+pub async fn import_sandbox_config(
+    app: tauri::AppHandle,
+    path: String,
+    profile_name: Option<String>,
+) -> Result<ApiResponse<ConfigImportResult>, String> {
+    let result = async {
+        let profile = resolve_profile(&app, profile_name.as_deref())?;
+        require_unlocked(&app)?;
+        let json_data = fs::read_to_string(&path)
+            .map_err(|e| AppError::Config(format!("Failed to read '{}': {}", path, e)))?;
+        let mut config = parse_config_export(&json_data)?;
+
+        let api_key_imported = !config.api_key.is_empty();
+        if !api_key_imported {
+            if let Some(existing) = load_profile_config(&app, &profile).await? {
+                config.api_key = existing.api_key;
+            }
+        }
+        save_config_to_file(&app, &profile, &config).await?;
+        notify_config_changed(&app, ConfigChangeKind::Saved, &profile, Some(&config));
+
+        let summary = profile_summaries(&app)?
+            .into_iter()
+            .find(|summary| summary.name == profile)
+            .ok_or_else(|| {
+                AppError::Config(format!("Configuration profile '{}' not found", profile))
+            })?;
+        Ok::<_, AppError>(ConfigImportResult {
+            profile: summary,
+            api_key_imported,
+            has_api_key: !config.api_key.is_empty(),
+        })
+    }
+    .await;
+
+    let entry = AuditEntry::new(AuditAction::ConfigImported, AuditOrigin::Gui, path.clone())
+        .with_outcome(
+            result.is_ok(),
+            match &result {
+                Ok(import) => Some(format!("profile {}", import.profile.name)),
+                Err(e) => Some(e.to_string()),
+            },
+        );
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok(import) => {
+            log::info!(
+                "Imported configuration from {} into profile {}",
+                path,
+                import.profile.name
+            );
+            Ok(ApiResponse::success(import))
+        }
+        Err(e) => {
+            log::error!("Failed to import configuration: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to import configuration: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Configurations kept before they were overwritten or cleared, newest first; only those
+/// of `profile_name` when given
+#[tauri::command]
+pub async fn list_config_backups(
+    app: tauri::AppHandle,
+    profile_name: Option<String>,
+) -> Result<ApiResponse<Vec<ConfigBackup>>, String> {
+    let result = (|| {
+        if let Some(ref name) = profile_name {
+            validate_profile_name(name)?;
+        }
+        let mut backups = read_backup_index(&app)?;
+        backups.retain(|backup| {
+            profile_name
+                .as_ref()
+                .is_none_or(|name| backup.profile == *name)
+        });
+        backups.reverse();
+        Ok::<_, AppError>(backups)
+    })();
+
+    match result {
+        Ok(backups) => Ok(ApiResponse::success(backups)),
+        Err(e) => {
+            log::error!("Failed to list configuration backups: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to list configuration backups: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Put a backed-up configuration back in place of its profile's current one
+///
+/// The current configuration is backed up first, so a restore can itself be undone. A
+/// backup without an API key keeps the key the profile has now.
+#[tauri::command]
+pub async fn restore_config_backup(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<ApiResponse<ConfigProfileSummary>, String> {
+    let result = async {
+        require_unlocked(&app)?;
+        let backup = read_backup_index(&app)?
+            .into_iter()
+            .find(|backup| backup.id == id)
+            .ok_or_else(|| AppError::Config(format!("Configuration backup '{}' not found", id)))?;
+        let mut config = read_config_file(
+            &get_app_data_dir(&app)?
+                .join(BACKUPS_DIR)
+                .join(backup_file(&backup.id)),
+        )?;
+
+        if config.api_key.is_empty() {
+            if let Some(current) = load_profile_config(&app, &backup.profile).await? {
+                config.api_key = current.api_key;
+            }
+        }
+        save_config_to_file(&app, &backup.profile, &config).await?;
+        notify_config_changed(
+            &app,
+            ConfigChangeKind::Saved,
+            &backup.profile,
+            Some(&config),
+        );
+
+        profile_summaries(&app)?
+            .into_iter()
+            .find(|summary| summary.name == backup.profile)
+            .ok_or_else(|| {
+                AppError::Config(format!(
+                    "Configuration profile '{}' not found",
+                    backup.profile
+                ))
+            })
+    }
+    .await;
+
+    let entry = AuditEntry::new(AuditAction::ConfigRestored, AuditOrigin::Gui, id.clone())
+        .with_outcome(
+            result.is_ok(),
+            match &result {
+                Ok(profile) => Some(format!("profile {}", profile.name)),
+                Err(e) => Some(e.to_string()),
+            },
+        );
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok(profile) => {
+            log::info!(
+                "Restored configuration backup {} into profile {}",
+                id,
+                profile.name
+            );
+            Ok(ApiResponse::success(profile))
+        }
+        Err(e) => {
+            log::error!("Failed to restore configuration backup: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to restore configuration backup: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Test connection to Sandbox API
+#[tauri::command]
+pub async fn test_sandbox_connection(
+    app: tauri::AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<ConnectionTestResult>, String> {
+    log::info!("Testing connection to Sandbox API: {}", config.base_url);
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::success(ConnectionTestResult {
+            success: false,
+            latency_ms: None,
+            error: Some("Invalid configuration".to_string()),
+            metadata: None,
+        }));
+    }
+
+    match test_connection(&config).await {
+        Ok(result) => {
+            if result.success {
+                log::info!(
+                    "Connection test successful ({}ms)",
+                    result.latency_ms.unwrap_or(0)
+                );
+                complete_step(&app, OnboardingStep::ConnectionTested).await;
+            } else {
+                log::warn!("Connection test failed: {:?}", result.error);
+            }
+            Ok(ApiResponse::success(result))
+        }
+        Err(e) => {
+            log::error!("Connection test error: {}", e);
+            Ok(ApiResponse::success(ConnectionTestResult {
+                success: false,
+                latency_ms: None,
+                error: Some(e.to_string()),
+                metadata: None,
+            }))
+        }
+    }
+}
+
+/// Export file path: `path` itself, or `sandbox-config-<profile>.json` inside a directory
+fn export_file_path(path: &Path, profile: &str) -> PathBuf {
+    if path.is_dir() {
+        path.join(format!("sandbox-config-{}.json", profile))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Wrap a configuration for export; keyring references only make sense on this machine
+fn config_export(mut config: SandboxConfig, include_secrets: bool) -> SandboxConfigExport {
+    config.api_key_ref = None;
+    if !include_secrets {
+        config.api_key.clear();
+    }
+    SandboxConfigExport {
+        format_version: CONFIG_EXPORT_FORMAT_VERSION,
+        exported_at: current_timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        includes_secrets: include_secrets && !config.api_key.is_empty(),
+        config,
+    }
+}
+
+/// Parse and validate an exported configuration
+fn parse_config_export(json_data: &str) -> Result<SandboxConfig, AppError> {
+    let export: SandboxConfigExport = serde_json::from_str(json_data)
+        .map_err(|e| AppError::Config(format!("Not a valid configuration export: {}", e)))?;
+    if export.format_version != CONFIG_EXPORT_FORMAT_VERSION {
+        return Err(AppError::Config(format!(
+            "Unsupported configuration export version {}",
+            export.format_version
+        )));
+    }
+
+    let mut config = export.config;
+    config.api_key_ref = None;
+    if !validate_base_url(&config.base_url) {
+        return Err(AppError::Config(format!(
+            "Invalid base URL '{}'",
+            config.base_url
+        )));
+    }
+    if !config.api_key.is_empty() && !validate_api_key(&config.api_key) {
+        return Err(AppError::Config(
+            "Invalid API key: expected 'eliza_' followed by 64 characters".to_string(),
+        ));
+    }
+    Ok(config)
+}
+
+/// Save an API key obtained by a Sandbox login into a profile, keeping its other settings
+pub(crate) async fn save_login_api_key(
+    app: &tauri::AppHandle,
+    profile: &str,
+    base_url: &str,
+    api_key: String,
+) -> Result<(), AppError> {
+    require_unlocked(app)?;
+    let mut config = load_profile_config(app, profile)
+        .await?
+        .unwrap_or_else(|| SandboxConfig::new(base_url.to_string(), String::new()));
+    config.base_url = base_url.to_string();
+    config.api_key = api_key;
+    if !config.is_valid() {
+        return Err(AppError::Config(
+            "The Sandbox returned an API key in an unexpected format".to_string(),
+        ));
+    }
+
+    let result = save_config_to_file(app, profile, &config).await;
+    let detail = match &result {
+        Ok(_) => format!(
+            "API key from Sandbox login; {}",
+            sanitize_config_for_log(&config)
+        ),
+        Err(e) => e.to_string(),
+    };
+    audit_config_change(
+        app,
+        AuditAction::ConfigSaved,
+        profile,
+        result.is_ok(),
+        Some(detail),
+    )
+    .await;
+    if result.is_ok() {
+        notify_config_changed(app, ConfigChangeKind::Saved, profile, Some(&config));
+        complete_step(app, OnboardingStep::ConfigSaved).await;
+    }
+    result
+}
+
+/// Record a configuration change in the audit log
+async fn audit_config_change(
+    app: &tauri::AppHandle,
+    action: AuditAction,
+    profile: &str,
+    success: bool,
+    detail: Option<String>,
+) {
+    let subject = if profile == DEFAULT_PROFILE {
+        CONFIG_FILE.to_string()
+    } else {
+        format!("{}/{}.json", PROFILES_DIR, profile)
+    };
+    let entry = AuditEntry::new(action, AuditOrigin::Gui, subject).with_outcome(success, detail);
+    record_audit(app, entry).await;
+}
+
+/// Emit `config-changed` so open windows refresh without polling `load_sandbox_config`;
+/// the payload carries the configuration with its API key redacted. Changes to the active
+/// profile also reconfigure the shared Sandbox HTTP client
+fn notify_config_changed(
+    app: &tauri::AppHandle,
+    kind: ConfigChangeKind,
+    profile: &str,
+    config: Option<&SandboxConfig>,
+) {
+    // Only the active profile can be cleared, and clearing a named profile hands the active
+    // role back to `default`, so read it before it matters
+    let active = kind == ConfigChangeKind::Cleared
+        || active_profile(app).is_ok_and(|active| active == profile);
+    if active {
+        let http = config.and_then(|c| c.http.clone()).unwrap_or_default();
+        if let Err(e) = sandbox_http().configure(&http) {
+            log::warn!("Keeping the previous Sandbox HTTP client settings: {}", e);
+        }
+    }
+    emit_event(
+        app,
+        "config-changed",
+        ConfigChanged {
+            kind,
+            profile: profile.to_string(),
+            active,
+            config: config.cloned().map(redact_config),
+            changed_at: current_timestamp(),
+        },
+    );
+}
+
+/// Get the app data directory, creating it if needed
+fn get_app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    // Ensure the directory exists
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir)
+}
+
+/// Get the configuration file path of the default profile
+fn get_config_path(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    Ok(get_app_data_dir(app)?.join(CONFIG_FILE))
+}
+
+/// Get the configuration file path of a profile
+fn profile_config_path(app: &tauri::AppHandle, profile: &str) -> Result<PathBuf, AppError> {
+    validate_profile_name(profile)?;
+    if profile == DEFAULT_PROFILE {
+        return get_config_path(app);
+    }
+    let dir = get_app_data_dir(app)?.join(PROFILES_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.json", profile)))
+}
+
+fn validate_profile_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(AppError::Config(format!(
+            "Invalid profile name '{}': use 1-{} letters, digits, '-' or '_'",
+            name, MAX_PROFILE_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// The named profile, or the active one when no name is given
+pub(crate) fn resolve_profile(
+    app: &tauri::AppHandle,
+    name: Option<&str>,
+) -> Result<String, AppError> {
+    match name {
+        Some(name) => {
+            validate_profile_name(name)?;
+            Ok(name.to_string())
+        }
+        None => active_profile(app),
+    }
+}
+
+/// Profile in use; `default` unless another saved profile was activated
+pub(crate) fn active_profile(app: &tauri::AppHandle) -> Result<String, AppError> {
+    let path = get_app_data_dir(app)?.join(ACTIVE_PROFILE_FILE);
+    if !path.exists() {
+        return Ok(DEFAULT_PROFILE.to_string());
+    }
+    let name: String = serde_json::from_str(&fs::read_to_string(path)?)?;
+    // A profile deleted behind the app's back falls back to the default
+    match profile_config_path(app, &name) {
+        Ok(config_path) if config_path.exists() => Ok(name),
+        _ => Ok(DEFAULT_PROFILE.to_string()),
+    }
+}
+
+fn write_active_profile(app: &tauri::AppHandle, name: &str) -> Result<(), AppError> {
+    let path = get_app_data_dir(app)?.join(ACTIVE_PROFILE_FILE);
+    fs::write(path, serde_json::to_vec(name)?)?;
+    Ok(())
+}
+
+/// Names of the profiles with a saved configuration, `default` first
+fn profile_names(app: &tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    let mut names = Vec::new();
+    if get_config_path(app)?.exists() {
+        names.push(DEFAULT_PROFILE.to_string());
+    }
+
+    let dir = get_app_data_dir(app)?.join(PROFILES_DIR);
+    if dir.is_dir() {
+        let mut named: Vec<String> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .filter(|name| name != DEFAULT_PROFILE && validate_profile_name(name).is_ok())
+            .collect();
+        named.sort();
+        names.extend(named);
+    }
+    Ok(names)
+}
+
+fn profile_summaries(app: &tauri::AppHandle) -> Result<Vec<ConfigProfileSummary>, AppError> {
+    let active = active_profile(app)?;
+    profile_names(app)?
+        .into_iter()
+        .map(|name| {
+            let config = read_profile_file(app, &name)?;
+            Ok(ConfigProfileSummary {
+                active: name == active,
+                name,
+                base_url: config.base_url,
+                default_model: config.default_model,
+            })
+        })
+        .collect()
+}
+
+/// A profile's config file as stored, without filling in the API key from the vault
+fn read_profile_file(app: &tauri::AppHandle, profile: &str) -> Result<SandboxConfig, AppError> {
+    read_config_file(&profile_config_path(app, profile)?)
+}
+
+/// A config file (a profile's or a backup), decrypting it when it holds an API key
+fn read_config_file(config_path: &Path) -> Result<SandboxConfig, AppError> {
+    let json_data = fs::read_to_string(config_path)
+        .map_err(|e| AppError::Config(format!("Failed to read config file: {}", e)))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&json_data).map_err(AppError::Serialization)?;
+    match encrypted_envelope(&value)? {
+        Some(envelope) => {
+            serde_json::from_slice(&open_config(&envelope)?).map_err(AppError::Serialization)
+        }
+        None => serde_json::from_value(value).map_err(AppError::Serialization),
+    }
+}
+
+/// Write a profile's config file, encrypting it when it still holds the API key
+fn write_profile_file(
+    app: &tauri::AppHandle,
+    profile: &str,
+    config: &SandboxConfig,
+) -> Result<(), AppError> {
+    write_config_file(&profile_config_path(app, profile)?, config)
+}
+
+fn write_config_file(config_path: &Path, config: &SandboxConfig) -> Result<(), AppError> {
+    let json_data = if config.api_key.is_empty() {
+        serde_json::to_string_pretty(config)?
+    } else {
+        let plaintext = serde_json::to_vec(config)?;
+        let salt: [u8; SALT_LEN] = rand::random();
+        let key_source = ConfigKeySource::current();
+        let key = config_key(key_source, &salt, KDF_ITERATIONS)?;
+        let envelope = seal_config(&key, key_source, &salt, KDF_ITERATIONS, &plaintext);
+        serde_json::to_string_pretty(&json!({ ENCRYPTED_KEY: envelope }))?
+    };
+    fs::write(config_path, json_data)
+        .map_err(|e| AppError::Config(format!("Failed to write config file: {}", e)))
+}
+
+/// Save configuration to JSON file
+async fn save_config_to_file(
+    app: &tauri::AppHandle,
+    profile: &str,
+    config: &SandboxConfig,
+) -> Result<(), AppError> {
+    backup_profile(app, profile, ConfigBackupReason::Overwritten).await;
+
+    // With the app lock on, the API key is kept in the vault, otherwise in the OS keyring
+    let mut config = config.clone();
+    let api_key = config.api_key.clone();
+    if update_vault(app, |vault| vault.set_api_key(profile, api_key))?.is_some() {
+        config.api_key.clear();
+        config.api_key_ref = None;
+    } else {
+        stash_api_key(profile, &mut config);
+    }
+
+    write_profile_file(app, profile, &config)?;
+
+    log::debug!("Configuration saved to profile: {}", profile);
+    Ok(())
+}
+
+/// Load the active profile's configuration from JSON file
+pub(crate) async fn load_config_from_file(
+    app: &tauri::AppHandle,
+) -> Result<Option<SandboxConfig>, AppError> {
+    load_profile_config(app, &active_profile(app)?).await
+}
+
+/// The active profile's configuration as saved, without its API key; the defaults when
+/// nothing is saved. Run policy is read from here, not from configs sent with requests
+pub(crate) fn saved_config(app: &tauri::AppHandle) -> Result<SandboxConfig, AppError> {
+    let profile = active_profile(app)?;
+    if !profile_config_path(app, &profile)?.exists() {
+        return Ok(SandboxConfig::default());
+    }
+    read_profile_file(app, &profile)
+}
+
+/// Load a profile's configuration from JSON file
+pub(crate) async fn load_profile_config(
+    app: &tauri::AppHandle,
+    profile: &str,
+) -> Result<Option<SandboxConfig>, AppError> {
+    let config_path = profile_config_path(app, profile)?;
+
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let mut config = read_profile_file(app, profile)?;
+
+    // While locked the API key stays blank, which callers see as an invalid config
+    match with_vault(app, |vault| vault.api_key(profile).to_string()) {
+        Ok(Some(api_key)) => config.api_key = api_key,
+        Ok(None) => {
+            if let Err(e) = unstash_api_key(&mut config) {
+                log::warn!(
+                    "Failed to read the {} API key from the OS keyring: {}",
+                    profile,
+                    e
+                );
+            }
+        }
+        Err(_) => {}
+    }
+
+    log::debug!("Configuration loaded from: {:?}", config_path);
+    Ok(Some(config))
+}
+
+/// API keys kept outside the app lock (config files or the OS keyring) by profile
+pub(crate) fn read_plaintext_api_keys(
+    app: &tauri::AppHandle,
+) -> Result<BTreeMap<String, String>, AppError> {
+    profile_names(app)?
+        .into_iter()
+        .map(|name| {
+            let mut config = read_profile_file(app, &name)?;
+            unstash_api_key(&mut config)?;
+            Ok((name, config.api_key))
+        })
+        .collect()
+}
+
+/// Replace the API keys kept outside the app lock; profiles without a config file are
+/// skipped
+pub(crate) fn write_plaintext_api_keys(
+    app: &tauri::AppHandle,
+    api_keys: &BTreeMap<String, String>,
+) -> Result<(), AppError> {
+    for (profile, api_key) in api_keys {
+        let config_path = profile_config_path(app, profile)?;
+        if !config_path.exists() {
+            continue;
+        }
+        let mut config = read_profile_file(app, profile)?;
+        config.api_key = api_key.to_string();
+        stash_api_key(profile, &mut config);
+        write_profile_file(app, profile, &config)?;
+    }
+    Ok(())
+}
+
+/// Move API keys still stored in config files into the OS keyring, returning the
+/// migrations applied
+///
+/// Files keep their key when no keyring is available.
+pub(crate) fn migrate_api_keys_to_keyring(app: &tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    let mut migrations = Vec::new();
+    for profile in profile_names(app)? {
+        let mut config = read_profile_file(app, &profile)?;
+        if config.api_key.is_empty() || config.api_key_ref.is_some() {
+            continue;
+        }
+        stash_api_key(&profile, &mut config);
+        if config.api_key_ref.is_some() {
+            write_profile_file(app, &profile, &config)?;
+            migrations.push(format!("Moved the '{}' API key to the OS keyring", profile));
+        }
+    }
+    Ok(migrations)
+}
+
+/// OS keyring account holding a profile's API key
+fn api_key_account(profile: &str) -> String {
+    format!("sandbox-api-key:{}", profile)
+}
+
+/// Move `config`'s API key into the OS keyring, leaving a reference in its place
+///
+/// Without a usable keyring the key stays in the config; a blank key removes the entry.
+fn stash_api_key(profile: &str, config: &mut SandboxConfig) {
+    let account = api_key_account(profile);
+    config.api_key_ref = None;
+    let stored = if config.api_key.is_empty() {
+        delete_secret(&account)
+    } else {
+        set_secret(&account, &config.api_key)
+    };
+    match stored {
+        Ok(()) if !config.api_key.is_empty() => {
+            config.api_key.clear();
+            config.api_key_ref = Some(account);
+        }
+        Ok(()) => {}
+        Err(e) => log::warn!(
+            "OS keyring unavailable, keeping the {} API key in its config file: {}",
+            profile,
+            e
+        ),
+    }
+}
+
+/// Fill in `config`'s API key from the OS keyring entry it refers to
+fn unstash_api_key(config: &mut SandboxConfig) -> Result<(), AppError> {
+    if let Some(account) = &config.api_key_ref {
+        config.api_key = get_secret(account)?.unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Remove the OS keyring entry a profile's config file refers to
+fn forget_stashed_api_key(app: &tauri::AppHandle, profile: &str) {
+    let Ok(SandboxConfig {
+        api_key_ref: Some(account),
+        ..
+    }) = read_profile_file(app, profile)
+    else {
+        return;
+    };
+    if let Err(e) = delete_secret(&account) {
+        log::warn!(
+            "Failed to remove the {} API key from the OS keyring: {}",
+            profile,
+            e
+        );
+    }
+}
+
+/// Encrypt config files that still hold their API key in plain text, returning the
+/// migrations applied
+///
+/// These were written before encryption at rest, on machines without an OS keyring.
+pub(crate) fn migrate_plaintext_config(app: &tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    let mut migrations = Vec::new();
+    for profile in profile_names(app)? {
+        let json_data = fs::read_to_string(profile_config_path(app, &profile)?)?;
+        let value: serde_json::Value = serde_json::from_str(&json_data)?;
+        if encrypted_envelope(&value)?.is_some() {
+            continue;
+        }
+        let config: SandboxConfig = serde_json::from_value(value)?;
+        if config.api_key.is_empty() {
+            continue;
+        }
+        write_profile_file(app, &profile, &config)?;
+        migrations.push(format!("Encrypted the '{}' config file at rest", profile));
+    }
+    Ok(migrations)
+}
+
+// ============================================================================
+// Backups
+// ============================================================================
+
+/// Keep a copy of a profile's saved configuration before it is replaced; a failed backup
+/// is only logged, so it never blocks the change itself
+async fn backup_profile(app: &tauri::AppHandle, profile: &str, reason: ConfigBackupReason) {
+    if let Err(e) = write_backup(app, profile, reason).await {
+        log::warn!("Failed to back up the {} configuration: {}", profile, e);
+    }
+}
+
+async fn write_backup(
+    app: &tauri::AppHandle,
+    profile: &str,
+    reason: ConfigBackupReason,
+) -> Result<(), AppError> {
+    let Some(mut config) = load_profile_config(app, profile).await? else {
+        return Ok(());
+    };
+    // The backup carries the API key itself, so it survives the keyring entry being
+    // removed by a clear; a key held by the app lock stays in the vault only
+    if !matches!(with_vault(app, |_| ()), Ok(None)) {
+        config.api_key.clear();
+    }
+    config.api_key_ref = None;
+
+    let backup = ConfigBackup {
+        id: format!(
+            "{}-{}",
+            profile,
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ")
+        ),
+        profile: profile.to_string(),
+        created_at: current_timestamp(),
+        reason,
+        base_url: config.base_url.clone(),
+        default_model: config.default_model.clone(),
+        has_api_key: !config.api_key.is_empty(),
+    };
+    let dir = get_app_data_dir(app)?.join(BACKUPS_DIR);
+    fs::create_dir_all(&dir)?;
+    write_config_file(&dir.join(backup_file(&backup.id)), &config)?;
+
+    let mut backups = read_backup_index(app)?;
+    backups.push(backup);
+    for pruned in prune_backups(&mut backups, profile) {
+        if let Err(e) = fs::remove_file(dir.join(backup_file(&pruned.id))) {
+            log::warn!("Failed to remove configuration backup {}: {}", pruned.id, e);
+        }
+    }
+    write_backup_index(app, &backups)
+}
+
+/// Drop the oldest backups of `profile` beyond the limit, returning them
+fn prune_backups(backups: &mut Vec<ConfigBackup>, profile: &str) -> Vec<ConfigBackup> {
+    let count = backups.iter().filter(|b| b.profile == profile).count();
+    let mut excess = count.saturating_sub(MAX_BACKUPS_PER_PROFILE);
+    let mut pruned = Vec::new();
+    backups.retain(|backup| {
+        if excess > 0 && backup.profile == profile {
+            excess -= 1;
+            pruned.push(backup.clone());
+            return false;
+        }
+        true
+    });
+    pruned
+}
+
+fn backup_file(id: &str) -> String {
+    format!("{}.json", id)
+}
+
+/// Backups of every profile, oldest first
+fn read_backup_index(app: &tauri::AppHandle) -> Result<Vec<ConfigBackup>, AppError> {
+    let path = get_app_data_dir(app)?
+        .join(BACKUPS_DIR)
+        .join(BACKUP_INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_backup_index(app: &tauri::AppHandle, backups: &[ConfigBackup]) -> Result<(), AppError> {
+    let path = get_app_data_dir(app)?
+        .join(BACKUPS_DIR)
+        .join(BACKUP_INDEX_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(backups)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+// ============================================================================
+// Encryption at rest
+// ============================================================================
+
+/// Secret the config encryption key is derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ConfigKeySource {
+    Machine,
+    Passphrase,
+}
+
+impl ConfigKeySource {
+    /// Source for newly written files: the passphrase when one is set
+    fn current() -> Self {
+        if config_passphrase().is_some() {
+            ConfigKeySource::Passphrase
+        } else {
+            ConfigKeySource::Machine
+        }
+    }
+}
+
+/// Encrypted config file as stored under `encrypted`; byte fields are hex
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedConfig {
+    version: u32,
+    key_source: ConfigKeySource,
+    salt: String,
+    iterations: u32,
+    nonce: String,
+    /// Sealed config, ending with the authentication tag
+    ciphertext: String,
+}
 
-    match clear_config_file(&app).await {
-        Ok(_) => {
-            log::info!("Configuration cleared successfully");
-            Ok(ApiResponse::success(()))
-        }
-        Err(e) => {
-            log::error!("Failed to clear configuration: {}", e);
-            Ok(ApiResponse::error(
-                "CLEAR_ERROR".to_string(),
-                format!("Failed to clear configuration: {}", e),
+fn config_passphrase() -> Option<String> {
+    std::env::var(CONFIG_PASSPHRASE_ENV)
+        .ok()
+        .filter(|passphrase| !passphrase.is_empty())
+}
+
+/// Derive the key for a config file; an iteration count outside the accepted bounds means
+/// the file was altered
+fn config_key(
+    source: ConfigKeySource,
+    salt: &[u8],
+    iterations: u32,
+) -> Result<DerivedKey, AppError> {
+    let secret = match source {
+        ConfigKeySource::Machine => machine_id()?,
+        ConfigKeySource::Passphrase => config_passphrase().ok_or_else(|| {
+            AppError::Config(format!(
+                "Config file is encrypted with a passphrase; set {} to read it",
+                CONFIG_PASSPHRASE_ENV
             ))
-        }
+        })?,
+    };
+    DerivedKey::derive(secret.as_bytes(), salt, iterations)
+        .map_err(|e| AppError::Config(format!("Encrypted config file is corrupted: {}", e)))
+}
+
+/// The envelope of an encrypted config file; `None` for a plain one
+fn encrypted_envelope(value: &serde_json::Value) -> Result<Option<EncryptedConfig>, AppError> {
+    match value.get(ENCRYPTED_KEY) {
+        Some(envelope) => Ok(Some(serde_json::from_value(envelope.clone())?)),
+        None => Ok(None),
     }
 }
 
-/// Test connection to Sandbox API
-#[tauri::command]
-pub async fn test_sandbox_connection(
-    config: SandboxConfig,
-) -> Result<ApiResponse<ConnectionTestResult>, String> {
-    log::info!("Testing connection to Sandbox API: {}", config.base_url);
+fn seal_config(
+    key: &DerivedKey,
+    key_source: ConfigKeySource,
+    salt: &[u8],
+    iterations: u32,
+    plaintext: &[u8],
+) -> EncryptedConfig {
+    let sealed = key.seal(salt, plaintext);
 
-    if !config.is_valid() {
-        return Ok(ApiResponse::success(ConnectionTestResult {
-            success: false,
-            latency_ms: None,
-            error: Some("Invalid configuration".to_string()),
-            metadata: None,
-        }));
+    EncryptedConfig {
+        version: ENCRYPTED_CONFIG_VERSION,
+        key_source,
+        salt: encode_hex(salt),
+        iterations,
+        nonce: encode_hex(&sealed.nonce),
+        ciphertext: encode_hex(&sealed.ciphertext),
     }
+}
 
-    match test_connection(&config).await {
-        Ok(result) => {
-            if result.success {
-                log::info!(
-                    "Connection test successful ({}ms)",
-                    result.latency_ms.unwrap_or(0)
-                );
-            } else {
-                log::warn!("Connection test failed: {:?}", result.error);
-            }
-            Ok(ApiResponse::success(result))
-        }
-        Err(e) => {
-            log::error!("Connection test error: {}", e);
-            Ok(ApiResponse::success(ConnectionTestResult {
-                success: false,
-                latency_ms: None,
-                error: Some(e.to_string()),
-                metadata: None,
-            }))
-        }
-    }
+/// Decrypt a config file with the key its envelope names
+fn open_config(envelope: &EncryptedConfig) -> Result<Vec<u8>, AppError> {
+    check_config_version(envelope)?;
+    let salt = decode_hex(&envelope.salt).ok_or_else(corrupted_config)?;
+    let key = config_key(envelope.key_source, &salt, envelope.iterations)?;
+    open_config_with_key(envelope, &key)
 }
 
-/// Get the configuration file path
-fn get_config_path(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+fn open_config_with_key(envelope: &EncryptedConfig, key: &DerivedKey) -> Result<Vec<u8>, AppError> {
+    check_config_version(envelope)?;
+    let salt = decode_hex(&envelope.salt).ok_or_else(corrupted_config)?;
+    let nonce = decode_hex(&envelope.nonce).ok_or_else(corrupted_config)?;
+    let ciphertext = decode_hex(&envelope.ciphertext).ok_or_else(corrupted_config)?;
 
-    // Ensure the directory exists
-    fs::create_dir_all(&app_data_dir)
-        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+    key.open(&nonce, &salt, &ciphertext).ok_or_else(|| {
+        AppError::Config(
+            "Failed to decrypt config file; it was encrypted on another machine or with another passphrase"
+                .to_string(),
+        )
+    })
+}
 
-    Ok(app_data_dir.join(CONFIG_FILE))
+fn check_config_version(envelope: &EncryptedConfig) -> Result<(), AppError> {
+    if envelope.version != ENCRYPTED_CONFIG_VERSION {
+        return Err(AppError::Config(format!(
+            "Unsupported encrypted config version {}",
+            envelope.version
+        )));
+    }
+    Ok(())
 }
 
-/// Save configuration to JSON file
-async fn save_config_to_file(
-    app: &tauri::AppHandle,
-    config: &SandboxConfig,
-) -> Result<(), AppError> {
-    let config_path = get_config_path(app)?;
+fn corrupted_config() -> AppError {
+    AppError::Config("Encrypted config file is corrupted".to_string())
+}
+
+/// Stable identifier of this machine, falling back to the hostname
+fn machine_id() -> Result<String, AppError> {
+    platform_machine_id()
+        .or_else(|| hostname::get().ok()?.into_string().ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| {
+            AppError::EnvironmentError(format!(
+                "No machine identifier found; set {} to encrypt the config",
+                CONFIG_PASSPHRASE_ENV
+            ))
+        })
+}
 
-    let json_data = serde_json::to_string_pretty(config).map_err(|e| AppError::Serialization(e))?;
+#[cfg(target_os = "macos")]
+fn platform_machine_id() -> Option<String> {
+    // `"IOPlatformUUID" = "XXXXXXXX-XXXX-..."`
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("\"IOPlatformUUID\""))
+        .and_then(|line| line.rsplit('"').nth(1).map(str::to_string))
+}
 
-    fs::write(&config_path, json_data)
-        .map_err(|e| AppError::Config(format!("Failed to write config file: {}", e)))?;
+#[cfg(windows)]
+fn platform_machine_id() -> Option<String> {
+    // `    MachineGuid    REG_SZ    xxxxxxxx-xxxx-...`
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last().map(str::to_string))
+}
 
-    log::debug!("Configuration saved to: {:?}", config_path);
-    Ok(())
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
 }
 
-/// Load configuration from JSON file
-async fn load_config_from_file(app: &tauri::AppHandle) -> Result<Option<SandboxConfig>, AppError> {
-    let config_path = get_config_path(app)?;
+#[cfg(not(any(unix, windows)))]
+fn platform_machine_id() -> Option<String> {
+    None
+}
 
+/// Bring an older config file up to the current format, returning the migrations applied
+///
+/// The file is only rewritten when something changed.
+pub(crate) fn migrate_config_file(app: &tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    let config_path = get_config_path(app)?;
     if !config_path.exists() {
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
     let json_data = fs::read_to_string(&config_path)
         .map_err(|e| AppError::Config(format!("Failed to read config file: {}", e)))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&json_data).map_err(AppError::Serialization)?;
+    // Encrypted files are only ever written in the current format
+    if encrypted_envelope(&value)?.is_some() {
+        return Ok(Vec::new());
+    }
 
-    let config: SandboxConfig =
-        serde_json::from_str(&json_data).map_err(|e| AppError::Serialization(e))?;
+    let migrations = migrate_config_value(&mut value);
+    if !migrations.is_empty() {
+        let json_data = serde_json::to_string_pretty(&value).map_err(AppError::Serialization)?;
+        fs::write(&config_path, json_data)
+            .map_err(|e| AppError::Config(format!("Failed to write config file: {}", e)))?;
+        log::info!("Applied {} config migration(s)", migrations.len());
+    }
+    Ok(migrations)
+}
 
-    log::debug!("Configuration loaded from: {:?}", config_path);
-    Ok(Some(config))
+/// Rename keys written by older versions, which stored the config in snake_case
+fn migrate_config_value(value: &mut serde_json::Value) -> Vec<String> {
+    let mut migrations = Vec::new();
+    let Some(object) = value.as_object_mut() else {
+        return migrations;
+    };
+
+    for (legacy, current) in LEGACY_CONFIG_KEYS {
+        let Some(legacy_value) = object.remove(legacy) else {
+            continue;
+        };
+        if object.contains_key(current) {
+            migrations.push(format!("Removed '{}', superseded by '{}'", legacy, current));
+        } else {
+            object.insert(current.to_string(), legacy_value);
+            migrations.push(format!("Renamed '{}' to '{}'", legacy, current));
+        }
+    }
+    migrations
 }
 
 /// Clear configuration file
-async fn clear_config_file(app: &tauri::AppHandle) -> Result<(), AppError> {
-    let config_path = get_config_path(app)?;
+async fn clear_config_file(app: &tauri::AppHandle, profile: &str) -> Result<(), AppError> {
+    let config_path = profile_config_path(app, profile)?;
 
     if config_path.exists() {
+        backup_profile(app, profile, ConfigBackupReason::Cleared).await;
+        forget_stashed_api_key(app, profile);
         fs::remove_file(&config_path)
             .map_err(|e| AppError::Config(format!("Failed to delete config file: {}", e)))?;
         log::debug!("Configuration file deleted: {:?}", config_path);
     }
+    if profile != DEFAULT_PROFILE {
+        write_active_profile(app, DEFAULT_PROFILE)?;
+    }
 
     Ok(())
 }
 
 /// Perform actual connection test to Sandbox API
-async fn test_connection(config: &SandboxConfig) -> Result<ConnectionTestResult, AppError> {
-    let client = Client::builder()
-        .timeout(CONNECTION_TIMEOUT)
-        .user_agent("ElizaOS-Desktop/0.1.0")
-        .build()
-        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+pub(crate) async fn test_connection(
+    config: &SandboxConfig,
+) -> Result<ConnectionTestResult, AppError> {
+    let client = sandbox_http();
+    let health_check = config.health_check.clone().unwrap_or_default();
 
     // Construct test endpoint URL - health endpoint is at root, not under /api/v1
     let base_url = config.base_url.trim_end_matches('/');
-    let test_url = if base_url.ends_with("/api/v1") {
-        format!("{}/health", base_url.trim_end_matches("/api/v1"))
-    } else {
-        format!("{}/health", base_url)
-    };
+    let test_url = format!(
+        "{}{}",
+        base_url.trim_end_matches("/api/v1"),
+        health_check.path()
+    );
+    let method = health_check.method.unwrap_or_default();
 
-    log::debug!("Testing connection to: {}", test_url);
+    log::debug!("Testing connection to: {:?} {}", method, test_url);
 
     let start_time = Instant::now();
 
     // Perform the connection test with timeout
     let response_result = timeout(CONNECTION_TIMEOUT, async {
-        client
-            .get(&test_url)
-            .header("Authorization", format!("Bearer {}", config.api_key))
-            .send()
-            .await
+        let request = match method {
+            HealthCheckMethod::Get => client.get(&test_url),
+            HealthCheckMethod::Head => client.head(&test_url),
+            HealthCheckMethod::Post => client.post(&test_url),
+        };
+        let request = request
+            .timeout(CONNECTION_TIMEOUT)
+            .header("Authorization", format!("Bearer {}", config.api_key));
+        client.send(request).await
     })
     .await;
 
@@ -235,7 +1663,8 @@ async fn test_connection(config: &SandboxConfig) -> Result<ConnectionTestResult,
     match response_result {
         Ok(Ok(response)) => {
             let status = response.status();
-            let success = status.is_success() || status == 401; // 401 means auth issue but API is reachable
+            // 401 means auth issue but API is reachable
+            let success = health_check.is_expected_status(status.as_u16()) || status == 401;
 
             let metadata = ConnectionMetadata {
                 endpoint: test_url,
@@ -298,6 +1727,35 @@ async fn test_connection(config: &SandboxConfig) -> Result<ConnectionTestResult,
     }
 }
 
+/// Reject health check paths that aren't a plain path and statuses that aren't HTTP statuses
+fn validate_health_check(health_check: &HealthCheckConfig) -> Result<(), AppError> {
+    if let Some(path) = &health_check.path {
+        if path.contains("://") || path.contains(char::is_whitespace) {
+            return Err(AppError::Config(format!(
+                "Health check path must be a path like /health, not {}",
+                path
+            )));
+        }
+    }
+    if let Some(statuses) = &health_check.expected_statuses {
+        if statuses.is_empty() {
+            return Err(AppError::Config(
+                "Health check needs at least one expected status".to_string(),
+            ));
+        }
+        if let Some(status) = statuses
+            .iter()
+            .find(|status| !(100..=599).contains(*status))
+        {
+            return Err(AppError::Config(format!(
+                "Invalid expected health check status: {}",
+                status
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Validate API key format
 pub fn validate_api_key(api_key: &str) -> bool {
     api_key.starts_with("eliza_") && api_key.len() == 70
@@ -339,12 +1797,26 @@ pub async fn test_api_prompt(
 }
 
 /// Test API completion request
-async fn test_api_completion(config: &SandboxConfig, prompt: &str) -> Result<String, AppError> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .user_agent("ElizaOS-Desktop/0.1.0")
-        .build()
-        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+pub(crate) async fn test_api_completion(
+    config: &SandboxConfig,
+    prompt: &str,
+) -> Result<String, AppError> {
+    let response = send_completion(config, prompt, false).await?;
+    let response_json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to parse JSON response: {}", e)))?;
+
+    Ok(message_content(&response_json))
+}
+
+/// Post a one-message chat completion, failing on a non-success status
+async fn send_completion(
+    config: &SandboxConfig,
+    prompt: &str,
+    stream: bool,
+) -> Result<reqwest::Response, AppError> {
+    let client = sandbox_http();
 
     // Construct API endpoint URL
     let base_url_trimmed = config.base_url.trim_end_matches('/');
@@ -359,7 +1831,7 @@ async fn test_api_completion(config: &SandboxConfig, prompt: &str) -> Result<Str
     log::debug!("Constructed API URL: {}", api_url);
 
     // Prepare request payload
-    let payload = json!({
+    let mut payload = json!({
         "model": config.default_model.as_deref().unwrap_or("gpt-4o-mini"),
         "messages": [{
             "role": "user",
@@ -367,15 +1839,24 @@ async fn test_api_completion(config: &SandboxConfig, prompt: &str) -> Result<Str
         }],
         "max_tokens": 100
     });
+    if stream {
+        payload["stream"] = json!(true);
+    }
 
     log::debug!("Testing API at: {}", api_url);
 
-    let response = client
+    let request = client
         .post(&api_url)
+        .timeout(if stream {
+            STREAMING_COMPLETION_TIMEOUT
+        } else {
+            COMPLETION_TIMEOUT
+        })
         .header("Authorization", format!("Bearer {}", config.api_key))
         .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
+        .json(&payload);
+    let response = client
+        .send(request)
         .await
         .map_err(|e| AppError::Network(format!("API request failed: {}", e)))?;
 
@@ -388,22 +1869,195 @@ async fn test_api_completion(config: &SandboxConfig, prompt: &str) -> Result<Str
         )));
     }
 
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| AppError::Network(format!("Failed to parse JSON response: {}", e)))?;
+    Ok(response)
+}
 
-    // Extract the response content
-    let content = response_json
+/// The content of the first choice of a non-streamed completion
+fn message_content(response_json: &serde_json::Value) -> String {
+    response_json
         .get("choices")
         .and_then(|choices| choices.get(0))
         .and_then(|choice| choice.get("message"))
         .and_then(|message| message.get("content"))
         .and_then(|content| content.as_str())
         .unwrap_or("No response content")
-        .to_string();
+        .to_string()
+}
+
+/// Send a test prompt with streaming enabled, emitting a `prompt-token` event per content
+/// delta so the settings screen can show the output live along with time to first token
+#[tauri::command]
+pub async fn test_api_prompt_streaming(
+    app: tauri::AppHandle,
+    config: SandboxConfig,
+    prompt: String,
+    prompt_id: Option<String>,
+) -> Result<ApiResponse<PromptStreamResult>, String> {
+    log::info!("Testing streaming API prompt: {}", prompt);
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid configuration".to_string(),
+        ));
+    }
+
+    let prompt_id = prompt_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    match stream_api_completion(&app, &config, &prompt, &prompt_id).await {
+        Ok(result) => {
+            log::info!(
+                "Streaming API prompt test successful: {} tokens in {}ms",
+                result.token_count,
+                result.total_ms
+            );
+            Ok(ApiResponse::success(result))
+        }
+        Err(e) => {
+            log::error!("Streaming API prompt test failed: {}", e);
+            Ok(ApiResponse::error(
+                "API_TEST_ERROR".to_string(),
+                format!("API test failed: {}", e),
+            ))
+        }
+    }
+}
+
+async fn stream_api_completion(
+    app: &tauri::AppHandle,
+    config: &SandboxConfig,
+    prompt: &str,
+    prompt_id: &str,
+) -> Result<PromptStreamResult, AppError> {
+    let started = Instant::now();
+    let mut response = send_completion(config, prompt, true).await?;
+
+    let mut result = PromptStreamResult {
+        prompt_id: prompt_id.to_string(),
+        content: String::new(),
+        token_count: 0,
+        time_to_first_token_ms: None,
+        total_ms: 0,
+        finish_reason: None,
+        streamed: false,
+    };
+    let emit_token = |result: &mut PromptStreamResult, token: String| {
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        result.time_to_first_token_ms.get_or_insert(elapsed_ms);
+        result.content.push_str(&token);
+        emit_event(
+            app,
+            "prompt-token",
+            PromptTokenEvent {
+                prompt_id: prompt_id.to_string(),
+                index: result.token_count,
+                token,
+                elapsed_ms,
+            },
+        );
+        result.token_count += 1;
+    };
+
+    let is_event_stream = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+    if !is_event_stream {
+        // The endpoint ignored `stream`, so the whole completion arrives as one token
+        let response_json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Network(format!("Failed to parse JSON response: {}", e)))?;
+        result.finish_reason = response_json
+            .pointer("/choices/0/finish_reason")
+            .and_then(|reason| reason.as_str())
+            .map(str::to_string);
+        emit_token(&mut result, message_content(&response_json));
+        result.total_ms = started.elapsed().as_millis() as u64;
+        return Ok(result);
+    }
+
+    result.streamed = true;
+    let mut lines = SseDataLines::default();
+    'stream: while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read streamed response: {}", e)))?
+    {
+        for data in lines.push(&chunk) {
+            match parse_stream_delta(&data)? {
+                StreamDelta::Done => break 'stream,
+                StreamDelta::Chunk {
+                    content,
+                    finish_reason,
+                } => {
+                    if let Some(token) = content.filter(|token| !token.is_empty()) {
+                        emit_token(&mut result, token);
+                    }
+                    if finish_reason.is_some() {
+                        result.finish_reason = finish_reason;
+                    }
+                }
+            }
+        }
+    }
+
+    result.total_ms = started.elapsed().as_millis() as u64;
+    Ok(result)
+}
+
+/// Splits a server-sent event body into the payloads of its `data:` lines, buffering
+/// partial lines (and multi-byte characters) across chunks
+#[derive(Default)]
+struct SseDataLines {
+    buffer: Vec<u8>,
+}
+
+impl SseDataLines {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut data = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(payload) = line.trim_end_matches(['\r', '\n']).strip_prefix("data:") {
+                data.push(payload.trim_start().to_string());
+            }
+        }
+        data
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum StreamDelta {
+    Chunk {
+        content: Option<String>,
+        finish_reason: Option<String>,
+    },
+    Done,
+}
 
-    Ok(content)
+/// Parse the payload of one `data:` line of an OpenAI-style streamed completion
+fn parse_stream_delta(data: &str) -> Result<StreamDelta, AppError> {
+    if data == "[DONE]" {
+        return Ok(StreamDelta::Done);
+    }
+    let chunk: serde_json::Value = serde_json::from_str(data)
+        .map_err(|e| AppError::Network(format!("Failed to parse streamed chunk: {}", e)))?;
+    if let Some(error) = chunk.get("error") {
+        return Err(AppError::Network(format!("API stream error: {}", error)));
+    }
+    let choice = chunk.pointer("/choices/0");
+    let field = |pointer: &str| {
+        choice
+            .and_then(|choice| choice.pointer(pointer))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    };
+    Ok(StreamDelta::Chunk {
+        content: field("/delta/content"),
+        finish_reason: field("/finish_reason"),
+    })
 }
 
 /// Sanitize configuration for logging (redact API key)
@@ -441,6 +2095,159 @@ mod tests {
         assert!(!validate_base_url(""));
     }
 
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(
+            normalize_host("Staging.Example.com").unwrap(),
+            "staging.example.com"
+        );
+        assert_eq!(normalize_host("localhost").unwrap(), "localhost");
+        for host in [
+            "",
+            " staging.example.com",
+            "https://staging.example.com",
+            "staging.example.com:8443",
+            "staging.example.com/api",
+        ] {
+            assert!(normalize_host(host).is_err(), "{}", host);
+        }
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("default").is_ok());
+        assert!(validate_profile_name("staging-eu_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../production").is_err());
+        assert!(validate_profile_name("prod sandbox").is_err());
+        assert!(validate_profile_name(&"p".repeat(MAX_PROFILE_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_api_key_account() {
+        assert_eq!(api_key_account(DEFAULT_PROFILE), "sandbox-api-key:default");
+        assert_eq!(api_key_account("staging"), "sandbox-api-key:staging");
+    }
+
+    #[test]
+    fn test_config_encryption_round_trip() {
+        let salt = [3u8; SALT_LEN];
+        let key = DerivedKey::for_tests(b"machine-id", &salt);
+        let plaintext = br#"{"baseUrl":"https://api.example.com","apiKey":"eliza_secret"}"#;
+        let envelope = seal_config(
+            &key,
+            ConfigKeySource::Machine,
+            &salt,
+            KDF_ITERATIONS,
+            plaintext,
+        );
+        assert!(!envelope.ciphertext.contains(&encode_hex(b"eliza_secret")));
+        assert_eq!(
+            open_config_with_key(&envelope, &key).unwrap(),
+            plaintext.to_vec()
+        );
+
+        let other_machine = DerivedKey::for_tests(b"other-machine", &salt);
+        assert!(open_config_with_key(&envelope, &other_machine).is_err());
+    }
+
+    #[test]
+    fn test_config_iterations_are_bounded() {
+        let salt = [3u8; SALT_LEN];
+        let key = DerivedKey::for_tests(b"machine-id", &salt);
+        for iterations in [1, u32::MAX] {
+            let envelope = seal_config(&key, ConfigKeySource::Machine, &salt, iterations, b"{}");
+            let err = open_config(&envelope).unwrap_err();
+            assert_eq!(err.error_code(), "CONFIG_ERROR");
+        }
+    }
+
+    #[test]
+    fn test_encrypted_envelope() {
+        let salt = [0u8; SALT_LEN];
+        let key = DerivedKey::for_tests(b"machine-id", &salt);
+        let envelope = seal_config(
+            &key,
+            ConfigKeySource::Passphrase,
+            &salt,
+            KDF_ITERATIONS,
+            b"{}",
+        );
+        let value = json!({ ENCRYPTED_KEY: envelope });
+        let parsed = encrypted_envelope(&value).unwrap().unwrap();
+        assert_eq!(parsed.key_source, ConfigKeySource::Passphrase);
+        assert_eq!(value[ENCRYPTED_KEY]["keySource"], "passphrase");
+
+        let plain = json!({ "baseUrl": "https://api.example.com" });
+        assert!(encrypted_envelope(&plain).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_config_export_round_trip() {
+        let config = SandboxConfig {
+            base_url: "https://api.example.com".to_string(),
+            api_key: format!("eliza_{}", "a".repeat(64)),
+            default_model: Some("gpt-4o-mini".to_string()),
+            api_key_ref: Some("sandbox-api-key:default".to_string()),
+            ..SandboxConfig::default()
+        };
+
+        let redacted = config_export(config.clone(), false);
+        assert!(!redacted.includes_secrets);
+        let json_data = serde_json::to_string(&redacted).unwrap();
+        assert!(!json_data.contains("eliza_"));
+        assert!(!json_data.contains("sandbox-api-key"));
+        let imported = parse_config_export(&json_data).unwrap();
+        assert_eq!(imported.base_url, "https://api.example.com");
+        assert_eq!(imported.default_model.as_deref(), Some("gpt-4o-mini"));
+        assert!(imported.api_key.is_empty());
+
+        let full = config_export(config.clone(), true);
+        assert!(full.includes_secrets);
+        let imported = parse_config_export(&serde_json::to_string(&full).unwrap()).unwrap();
+        assert_eq!(imported.api_key, config.api_key);
+        assert!(imported.api_key_ref.is_none());
+    }
+
+    #[test]
+    fn test_parse_config_export_rejects_invalid_files() {
+        assert!(parse_config_export("{}").is_err());
+        assert!(parse_config_export(r#"{"baseUrl": "https://api.example.com"}"#).is_err());
+
+        let export = |version: u32, base_url: &str, api_key: &str| {
+            json!({
+                "formatVersion": version,
+                "exportedAt": "2026-01-01T00:00:00Z",
+                "appVersion": "0.1.0",
+                "includesSecrets": false,
+                "config": { "baseUrl": base_url, "apiKey": api_key },
+            })
+            .to_string()
+        };
+        assert!(parse_config_export(&export(1, "https://api.example.com", "")).is_ok());
+        assert!(parse_config_export(&export(2, "https://api.example.com", "")).is_err());
+        assert!(parse_config_export(&export(1, "api.example.com", "")).is_err());
+        assert!(parse_config_export(&export(1, "https://api.example.com", "eliza_short")).is_err());
+    }
+
+    #[test]
+    fn test_migrate_config_value() {
+        let mut value = json!({
+            "base_url": "https://api.example.com",
+            "api_key": "old",
+            "apiKey": "new",
+        });
+        let migrations = migrate_config_value(&mut value);
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(value["baseUrl"], "https://api.example.com");
+        assert_eq!(value["apiKey"], "new");
+        assert!(value.get("base_url").is_none());
+        assert!(value.get("api_key").is_none());
+
+        // Already migrated
+        assert!(migrate_config_value(&mut value).is_empty());
+    }
+
     #[test]
     fn test_sanitize_config_for_log() {
         let config = SandboxConfig {
@@ -448,6 +2255,7 @@ mod tests {
             api_key: "eliza_1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef"
                 .to_string(),
             default_model: Some("gpt-4".to_string()),
+            ..Default::default()
         };
 
         let sanitized = sanitize_config_for_log(&config);
@@ -458,4 +2266,96 @@ mod tests {
             !sanitized.contains("1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
         );
     }
+
+    #[test]
+    fn test_prune_backups_per_profile() {
+        let backup = |id: usize, profile: &str| ConfigBackup {
+            id: format!("{}-{}", profile, id),
+            profile: profile.to_string(),
+            created_at: current_timestamp(),
+            reason: ConfigBackupReason::Overwritten,
+            base_url: "https://api.example.com".to_string(),
+            default_model: None,
+            has_api_key: true,
+        };
+        let mut backups: Vec<ConfigBackup> = (0..MAX_BACKUPS_PER_PROFILE + 2)
+            .map(|id| backup(id, DEFAULT_PROFILE))
+            .collect();
+        backups.insert(1, backup(0, "staging"));
+
+        let pruned = prune_backups(&mut backups, DEFAULT_PROFILE);
+        assert_eq!(
+            pruned.iter().map(|b| b.id.as_str()).collect::<Vec<_>>(),
+            vec!["default-0", "default-1"]
+        );
+        assert_eq!(backups.len(), MAX_BACKUPS_PER_PROFILE + 1);
+        assert_eq!(backups[0].id, "staging-0");
+        assert!(prune_backups(&mut backups, "staging").is_empty());
+    }
+
+    #[test]
+    fn test_sse_data_lines_across_chunks() {
+        let mut lines = SseDataLines::default();
+        assert!(lines.push(b": keep-alive\n\ndata: {\"a\"").is_empty());
+        assert_eq!(
+            lines.push(b":1}\r\n\ndata:[DONE]\n"),
+            vec!["{\"a\":1}", "[DONE]"]
+        );
+
+        // A multi-byte character split between chunks
+        let token = "data: \u{e9}\n".as_bytes();
+        assert!(lines.push(&token[..7]).is_empty());
+        assert_eq!(lines.push(&token[7..]), vec!["\u{e9}"]);
+    }
+
+    #[test]
+    fn test_parse_stream_delta() {
+        assert_eq!(parse_stream_delta("[DONE]").unwrap(), StreamDelta::Done);
+        assert_eq!(
+            parse_stream_delta(r#"{"choices":[{"delta":{"content":"Hi"},"finish_reason":null}]}"#)
+                .unwrap(),
+            StreamDelta::Chunk {
+                content: Some("Hi".to_string()),
+                finish_reason: None,
+            }
+        );
+        assert_eq!(
+            parse_stream_delta(r#"{"choices":[{"delta":{},"finish_reason":"stop"}]}"#).unwrap(),
+            StreamDelta::Chunk {
+                content: None,
+                finish_reason: Some("stop".to_string()),
+            }
+        );
+        assert!(parse_stream_delta(r#"{"error":{"message":"rate limited"}}"#).is_err());
+        assert!(parse_stream_delta("not json").is_err());
+    }
+
+    #[test]
+    fn test_health_check_defaults_and_validation() {
+        let default = HealthCheckConfig::default();
+        assert_eq!(default.path(), "/health");
+        assert!(default.is_expected_status(204));
+        assert!(!default.is_expected_status(404));
+
+        let custom = HealthCheckConfig {
+            path: Some("v1/status".to_string()),
+            method: Some(HealthCheckMethod::Head),
+            expected_statuses: Some(vec![200, 404]),
+        };
+        assert_eq!(custom.path(), "/v1/status");
+        assert!(custom.is_expected_status(404));
+        assert!(!custom.is_expected_status(204));
+        assert!(validate_health_check(&custom).is_ok());
+
+        let url = HealthCheckConfig {
+            path: Some("https://sandbox.example/health".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_health_check(&url).is_err());
+        let status = HealthCheckConfig {
+            expected_statuses: Some(vec![200, 1000]),
+            ..Default::default()
+        };
+        assert!(validate_health_check(&status).is_err());
+    }
 }
@@ -0,0 +1,322 @@
+//! Sandbox connectivity monitor
+//! An opt-in background probe of the Sandbox health endpoint that keeps a rolling window of
+//! latency and availability and emits `connectivity-changed` when the state changes
+
+use crate::commands::config::test_connection;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, ConnectivityChangedEvent, ConnectivityState, ConnectivityStatus, SandboxConfig,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+const DEFAULT_INTERVAL_SECS: u64 = 30;
+const MIN_INTERVAL_SECS: u64 = 5;
+/// Probes kept in the rolling window
+const WINDOW_SIZE: usize = 20;
+/// Consecutive failed probes after which the Sandbox counts as offline
+const OFFLINE_AFTER_FAILURES: usize = 3;
+const DEGRADED_AVAILABILITY: f64 = 0.9;
+const DEGRADED_LATENCY_MS: u64 = 1500;
+
+#[derive(Debug, Clone)]
+struct Probe {
+    latency_ms: Option<u64>,
+    error: Option<String>,
+}
+
+/// The most recent probes and the state they add up to
+#[derive(Debug, Default)]
+struct ProbeWindow {
+    probes: VecDeque<Probe>,
+    state: Option<ConnectivityState>,
+    last_checked_at: Option<String>,
+}
+
+impl ProbeWindow {
+    /// Add a probe, returning the previous state when the state changed
+    fn record(&mut self, probe: Probe) -> Option<Option<ConnectivityState>> {
+        if self.probes.len() == WINDOW_SIZE {
+            self.probes.pop_front();
+        }
+        self.probes.push_back(probe);
+        self.last_checked_at = Some(crate::models::current_timestamp());
+
+        let state = self.classify();
+        let previous = self.state.replace(state);
+        (previous != Some(state)).then_some(previous)
+    }
+
+    fn classify(&self) -> ConnectivityState {
+        let recent_failures = self
+            .probes
+            .iter()
+            .rev()
+            .take_while(|probe| probe.latency_ms.is_none())
+            .count();
+        // A monitor that has only ever failed is offline without waiting for more probes
+        if recent_failures > 0 && recent_failures >= OFFLINE_AFTER_FAILURES.min(self.probes.len()) {
+            return ConnectivityState::Offline;
+        }
+
+        let slow = self
+            .average_latency_ms()
+            .is_some_and(|ms| ms > DEGRADED_LATENCY_MS);
+        if self.availability() < DEGRADED_AVAILABILITY || slow {
+            ConnectivityState::Degraded
+        } else {
+            ConnectivityState::Online
+        }
+    }
+
+    fn availability(&self) -> f64 {
+        if self.probes.is_empty() {
+            return 0.0;
+        }
+        let ok = self
+            .probes
+            .iter()
+            .filter(|p| p.latency_ms.is_some())
+            .count();
+        ok as f64 / self.probes.len() as f64
+    }
+
+    fn average_latency_ms(&self) -> Option<u64> {
+        let latencies: Vec<u64> = self.probes.iter().filter_map(|p| p.latency_ms).collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        Some(latencies.iter().sum::<u64>() / latencies.len() as u64)
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.probes.back().and_then(|p| p.error.clone())
+    }
+
+    fn status(&self, running: bool, interval_secs: u64) -> ConnectivityStatus {
+        ConnectivityStatus {
+            running,
+            state: self.state,
+            interval_secs,
+            samples: self.probes.len(),
+            availability: self.availability(),
+            average_latency_ms: self.average_latency_ms(),
+            last_latency_ms: self.probes.back().and_then(|p| p.latency_ms),
+            last_error: self.last_error(),
+            last_checked_at: self.last_checked_at.clone(),
+        }
+    }
+}
+
+pub struct ConnectivityMonitorHandle {
+    interval_secs: u64,
+    window: Arc<StdMutex<ProbeWindow>>,
+    task: JoinHandle<()>,
+}
+
+impl ConnectivityMonitorHandle {
+    fn status(&self) -> ConnectivityStatus {
+        lock_window(&self.window).status(true, self.interval_secs)
+    }
+}
+
+// The running monitor, if probing is enabled
+pub type ConnectivityMonitorState = Arc<Mutex<Option<ConnectivityMonitorHandle>>>;
+
+/// Initialize the connectivity monitor state (called from main)
+pub fn init_connectivity_monitor() -> ConnectivityMonitorState {
+    Arc::new(Mutex::new(None))
+}
+
+/// Start probing the Sandbox health endpoint, replacing a monitor already running
+#[tauri::command]
+pub async fn start_connectivity_monitor(
+    app: AppHandle,
+    config: SandboxConfig,
+    interval_secs: Option<u64>,
+) -> Result<ApiResponse<ConnectivityStatus>, String> {
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let interval_secs = interval_secs
+        .unwrap_or(DEFAULT_INTERVAL_SECS)
+        .max(MIN_INTERVAL_SECS);
+    let window = Arc::new(StdMutex::new(ProbeWindow::default()));
+    let task = tokio::spawn(probe_loop(
+        app.clone(),
+        config,
+        Duration::from_secs(interval_secs),
+        window.clone(),
+    ));
+    let handle = ConnectivityMonitorHandle {
+        interval_secs,
+        window,
+        task,
+    };
+    let status = handle.status();
+
+    let state = app.state::<ConnectivityMonitorState>().inner().clone();
+    if let Some(previous) = state.lock().await.replace(handle) {
+        previous.task.abort();
+    }
+
+    log::info!("Connectivity monitor probing every {}s", interval_secs);
+    Ok(ApiResponse::success(status))
+}
+
+/// Stop the connectivity monitor
+#[tauri::command]
+pub async fn stop_connectivity_monitor(
+    app: AppHandle,
+) -> Result<ApiResponse<ConnectivityStatus>, String> {
+    let state = app.state::<ConnectivityMonitorState>().inner().clone();
+
+    let status = match state.lock().await.take() {
+        Some(handle) => {
+            handle.task.abort();
+            log::info!("Connectivity monitor stopped");
+            lock_window(&handle.window).status(false, handle.interval_secs)
+        }
+        None => ProbeWindow::default().status(false, 0),
+    };
+    Ok(ApiResponse::success(status))
+}
+
+/// Get the monitor's rolling latency and availability
+#[tauri::command]
+pub async fn get_connectivity_status(
+    app: AppHandle,
+) -> Result<ApiResponse<ConnectivityStatus>, String> {
+    let state = app.state::<ConnectivityMonitorState>().inner().clone();
+    let guard = state.lock().await;
+
+    Ok(ApiResponse::success(match guard.as_ref() {
+        Some(handle) => handle.status(),
+        None => ProbeWindow::default().status(false, 0),
+    }))
+}
+
+async fn probe_loop(
+    app: AppHandle,
+    config: SandboxConfig,
+    interval: Duration,
+    window: Arc<StdMutex<ProbeWindow>>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        let probe = match test_connection(&config).await {
+            Ok(result) if result.success => Probe {
+                latency_ms: result.latency_ms,
+                error: None,
+            },
+            Ok(result) => Probe {
+                latency_ms: None,
+                error: result.error,
+            },
+            Err(e) => Probe {
+                latency_ms: None,
+                error: Some(e.to_string()),
+            },
+        };
+
+        let mut window = lock_window(&window);
+        if let Some(previous) = window.record(probe) {
+            let event = ConnectivityChangedEvent {
+                previous,
+                state: window.state.unwrap_or(ConnectivityState::Offline),
+                availability: window.availability(),
+                average_latency_ms: window.average_latency_ms(),
+                last_error: window.last_error(),
+                timestamp: chrono::Utc::now().timestamp(),
+            };
+            drop(window);
+            log::info!("Sandbox connectivity is now {:?}", event.state);
+            emit_event(&app, "connectivity-changed", event);
+        }
+    }
+}
+
+fn lock_window(window: &StdMutex<ProbeWindow>) -> std::sync::MutexGuard<'_, ProbeWindow> {
+    window.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(ms: u64) -> Probe {
+        Probe {
+            latency_ms: Some(ms),
+            error: None,
+        }
+    }
+
+    fn failed() -> Probe {
+        Probe {
+            latency_ms: None,
+            error: Some("Connection timed out".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_state_changes_are_reported_once() {
+        let mut window = ProbeWindow::default();
+        assert_eq!(window.record(ok(100)), Some(None));
+        assert_eq!(window.record(ok(120)), None);
+        assert_eq!(window.state, Some(ConnectivityState::Online));
+
+        // One failure in three probes is degraded, three in a row is offline
+        assert_eq!(
+            window.record(failed()),
+            Some(Some(ConnectivityState::Online))
+        );
+        assert_eq!(window.state, Some(ConnectivityState::Degraded));
+        window.record(failed());
+        assert_eq!(
+            window.record(failed()),
+            Some(Some(ConnectivityState::Degraded))
+        );
+        assert_eq!(window.state, Some(ConnectivityState::Offline));
+        assert_eq!(window.last_error().as_deref(), Some("Connection timed out"));
+    }
+
+    #[test]
+    fn test_first_failure_is_offline() {
+        let mut window = ProbeWindow::default();
+        window.record(failed());
+        assert_eq!(window.state, Some(ConnectivityState::Offline));
+    }
+
+    #[test]
+    fn test_slow_probes_are_degraded() {
+        let mut window = ProbeWindow::default();
+        window.record(ok(2000));
+        window.record(ok(1800));
+        assert_eq!(window.state, Some(ConnectivityState::Degraded));
+        assert_eq!(window.average_latency_ms(), Some(1900));
+    }
+
+    #[test]
+    fn test_window_is_bounded() {
+        let mut window = ProbeWindow::default();
+        window.record(failed());
+        for _ in 0..WINDOW_SIZE {
+            window.record(ok(50));
+        }
+        let status = window.status(true, 30);
+        assert_eq!(status.samples, WINDOW_SIZE);
+        assert_eq!(status.availability, 1.0);
+        assert_eq!(status.state, Some(ConnectivityState::Online));
+    }
+}
@@ -0,0 +1,388 @@
+//! Dependency audit
+//! Runs the project's package manager audit (`npm audit --json`, `pnpm audit --json` or
+//! `bun audit --json`, picked from the lockfile) and parses the report into typed
+//! vulnerability records, so risky plugin dependencies show up before an agent starts
+
+use crate::commands::path_jail::check_path_allowed;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, DependencyAuditReport, DependencyVulnerability,
+    PackageManager, VulnerabilityCounts, VulnerabilitySeverity,
+};
+use crate::path_env::{find_in_path, spawn_path_for_app};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::process::Command;
+
+/// Audits query the registry, which can be slow on a cold cache
+const AUDIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Audit the dependencies of the project in `project_dir`
+#[tauri::command]
+pub async fn audit_project_dependencies(
+    app: AppHandle,
+    project_dir: String,
+) -> Result<ApiResponse<DependencyAuditReport>, String> {
+    let result = async {
+        let dir = Path::new(&project_dir);
+        check_path_allowed(&app, dir, "Project directory")?;
+        if !dir.join("package.json").is_file() {
+            return Err(AppError::Config(format!(
+                "'{}' has no package.json",
+                project_dir
+            )));
+        }
+
+        let package_manager = detect_package_manager(dir);
+        let report = run_audit(&app, dir, package_manager).await?;
+        let mut vulnerabilities = match package_manager {
+            PackageManager::Bun => parse_bun_audit(&report),
+            PackageManager::Npm | PackageManager::Pnpm => parse_npm_audit(&report),
+        };
+        // Most severe first, then by package
+        vulnerabilities.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+
+        Ok::<_, AppError>(DependencyAuditReport {
+            project_dir: project_dir.clone(),
+            package_manager,
+            counts: count_by_severity(&vulnerabilities),
+            vulnerabilities,
+            audited_at: current_timestamp(),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            log::info!(
+                "Audited {} with {}: {} vulnerable package(s)",
+                project_dir,
+                package_manager_name(report.package_manager),
+                report.counts.total
+            );
+            Ok(ApiResponse::success(report))
+        }
+        Err(e) => {
+            log::error!("Failed to audit project dependencies: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to audit project dependencies: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// The package manager whose lockfile the project has; npm when there is none
+pub(crate) fn detect_package_manager(dir: &Path) -> PackageManager {
+    if dir.join("pnpm-lock.yaml").is_file() {
+        PackageManager::Pnpm
+    } else if dir.join("bun.lock").is_file() || dir.join("bun.lockb").is_file() {
+        PackageManager::Bun
+    } else {
+        PackageManager::Npm
+    }
+}
+
+pub(crate) fn package_manager_name(package_manager: PackageManager) -> &'static str {
+    match package_manager {
+        PackageManager::Npm => "npm",
+        PackageManager::Pnpm => "pnpm",
+        PackageManager::Bun => "bun",
+    }
+}
+
+/// Run the audit and return its JSON report
+///
+/// Audits exit non-zero when they find something, so the exit code alone is not a failure.
+async fn run_audit(
+    app: &AppHandle,
+    dir: &Path,
+    package_manager: PackageManager,
+) -> Result<Value, AppError> {
+    let program = package_manager_name(package_manager);
+    let path_env = spawn_path_for_app(app).await;
+    let executable = find_in_path(program, &path_env).ok_or_else(|| {
+        AppError::EnvironmentError(format!("{} is not installed or not on PATH", program))
+    })?;
+
+    let output = tokio::time::timeout(
+        AUDIT_TIMEOUT,
+        Command::new(executable)
+            .args(["audit", "--json"])
+            .current_dir(dir)
+            .env("PATH", path_env)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Process(format!(
+            "{} audit did not finish within {} seconds",
+            program,
+            AUDIT_TIMEOUT.as_secs()
+        ))
+    })?
+    .map_err(|e| AppError::Process(format!("Failed to run {} audit: {}", program, e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: Value = serde_json::from_str(stdout.trim()).map_err(|_| {
+        AppError::Process(format!(
+            "{} audit failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    })?;
+    if let Some(error) = report.get("error") {
+        // e.g. `{"error": {"code": "ENOLOCK", "summary": "...", "detail": "..."}}`
+        let summary = error
+            .get("summary")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(AppError::Process(format!(
+            "{} audit failed: {}",
+            program, summary
+        )));
+    }
+    Ok(report)
+}
+
+/// Parse `npm audit --json`, both the current report (`vulnerabilities` by package) and
+/// the older advisory report that pnpm still prints (`advisories` by id)
+fn parse_npm_audit(report: &Value) -> Vec<DependencyVulnerability> {
+    if let Some(vulnerabilities) = report.get("vulnerabilities").and_then(Value::as_object) {
+        return vulnerabilities
+            .iter()
+            .map(|(package, entry)| {
+                // Advisories are objects; plain strings name the vulnerable dependency
+                let via = entry
+                    .get("via")
+                    .and_then(Value::as_array)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let advisory = via.iter().find(|item| item.is_object());
+                DependencyVulnerability {
+                    package: package.clone(),
+                    severity: parse_severity(entry.get("severity")),
+                    title: advisory.and_then(|a| string_field(a, "title")),
+                    url: advisory.and_then(|a| string_field(a, "url")),
+                    vulnerable_versions: string_field(entry, "range"),
+                    direct: entry.get("isDirect").and_then(Value::as_bool),
+                    fix_available: entry.get("fixAvailable").map(|fix| match fix {
+                        Value::Bool(available) => *available,
+                        // An object describing the upgrade that fixes it
+                        _ => true,
+                    }),
+                    via: via
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect(),
+                }
+            })
+            .collect();
+    }
+
+    report
+        .get("advisories")
+        .and_then(Value::as_object)
+        .map(|advisories| {
+            advisories
+                .values()
+                .map(|advisory| DependencyVulnerability {
+                    package: string_field(advisory, "module_name").unwrap_or_default(),
+                    severity: parse_severity(advisory.get("severity")),
+                    title: string_field(advisory, "title"),
+                    url: string_field(advisory, "url"),
+                    vulnerable_versions: string_field(advisory, "vulnerable_versions"),
+                    direct: None,
+                    fix_available: string_field(advisory, "patched_versions")
+                        .map(|patched| patched != "<0.0.0"),
+                    via: Vec::new(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse `bun audit --json`: advisories grouped by package name
+fn parse_bun_audit(report: &Value) -> Vec<DependencyVulnerability> {
+    let Some(packages) = report.as_object() else {
+        return Vec::new();
+    };
+    packages
+        .iter()
+        .flat_map(|(package, advisories)| {
+            advisories
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(move |advisory| DependencyVulnerability {
+                    package: package.clone(),
+                    severity: parse_severity(advisory.get("severity")),
+                    title: string_field(advisory, "title"),
+                    url: string_field(advisory, "url"),
+                    vulnerable_versions: string_field(advisory, "vulnerable_versions"),
+                    direct: None,
+                    fix_available: None,
+                    via: Vec::new(),
+                })
+        })
+        .collect()
+}
+
+/// Unknown severities count as informational
+fn parse_severity(value: Option<&Value>) -> VulnerabilitySeverity {
+    match value.and_then(Value::as_str) {
+        Some("critical") => VulnerabilitySeverity::Critical,
+        Some("high") => VulnerabilitySeverity::High,
+        Some("moderate") | Some("medium") => VulnerabilitySeverity::Moderate,
+        Some("low") => VulnerabilitySeverity::Low,
+        _ => VulnerabilitySeverity::Info,
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn count_by_severity(vulnerabilities: &[DependencyVulnerability]) -> VulnerabilityCounts {
+    let mut counts = VulnerabilityCounts::default();
+    for vulnerability in vulnerabilities {
+        match vulnerability.severity {
+            VulnerabilitySeverity::Info => counts.info += 1,
+            VulnerabilitySeverity::Low => counts.low += 1,
+            VulnerabilitySeverity::Moderate => counts.moderate += 1,
+            VulnerabilitySeverity::High => counts.high += 1,
+            VulnerabilitySeverity::Critical => counts.critical += 1,
+        }
+        counts.total += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_npm_audit_v2() {
+        let report = json!({
+            "auditReportVersion": 2,
+            "vulnerabilities": {
+                "axios": {
+                    "name": "axios",
+                    "severity": "high",
+                    "isDirect": true,
+                    "via": [{
+                        "source": 1097679,
+                        "title": "Axios Cross-Site Request Forgery Vulnerability",
+                        "url": "https://github.com/advisories/GHSA-wf5p-g6vw-rhxx",
+                        "severity": "moderate",
+                        "range": ">=0.8.1 <0.28.0"
+                    }],
+                    "range": "0.8.1 - 0.27.2",
+                    "fixAvailable": { "name": "axios", "version": "1.7.4" }
+                },
+                "@elizaos/plugin-web": {
+                    "severity": "high",
+                    "isDirect": true,
+                    "via": ["axios"],
+                    "range": "*",
+                    "fixAvailable": false
+                }
+            },
+            "metadata": { "vulnerabilities": { "high": 2, "total": 2 } }
+        });
+        let vulnerabilities = parse_npm_audit(&report);
+        assert_eq!(vulnerabilities.len(), 2);
+
+        let axios = vulnerabilities
+            .iter()
+            .find(|v| v.package == "axios")
+            .unwrap();
+        assert_eq!(axios.severity, VulnerabilitySeverity::High);
+        assert_eq!(
+            axios.title.as_deref(),
+            Some("Axios Cross-Site Request Forgery Vulnerability")
+        );
+        assert_eq!(axios.vulnerable_versions.as_deref(), Some("0.8.1 - 0.27.2"));
+        assert_eq!(axios.fix_available, Some(true));
+
+        let plugin = vulnerabilities
+            .iter()
+            .find(|v| v.package == "@elizaos/plugin-web")
+            .unwrap();
+        assert!(plugin.title.is_none());
+        assert_eq!(plugin.via, vec!["axios".to_string()]);
+        assert_eq!(plugin.fix_available, Some(false));
+    }
+
+    #[test]
+    fn test_parse_advisory_audit() {
+        // pnpm prints the npm 6 report format
+        let report = json!({
+            "advisories": {
+                "1096727": {
+                    "module_name": "ws",
+                    "severity": "moderate",
+                    "title": "ws affected by a DoS when handling a request with many HTTP headers",
+                    "url": "https://github.com/advisories/GHSA-3h5v-q93c-6h6q",
+                    "vulnerable_versions": ">=8.0.0 <8.17.1",
+                    "patched_versions": ">=8.17.1"
+                }
+            },
+            "metadata": { "vulnerabilities": { "moderate": 1 } }
+        });
+        let vulnerabilities = parse_npm_audit(&report);
+        assert_eq!(vulnerabilities.len(), 1);
+        assert_eq!(vulnerabilities[0].package, "ws");
+        assert_eq!(vulnerabilities[0].severity, VulnerabilitySeverity::Moderate);
+        assert_eq!(vulnerabilities[0].fix_available, Some(true));
+    }
+
+    #[test]
+    fn test_parse_bun_audit() {
+        let report = json!({
+            "cookie": [{
+                "id": 1099846,
+                "title": "cookie accepts cookie name, path, and domain with out of bounds characters",
+                "url": "https://github.com/advisories/GHSA-pxg6-pf52-xh8x",
+                "severity": "low",
+                "vulnerable_versions": "<0.7.0"
+            }],
+            "tar": [
+                { "severity": "high", "title": "Arbitrary File Creation" },
+                { "severity": "critical", "title": "Arbitrary File Overwrite" }
+            ]
+        });
+        let vulnerabilities = parse_bun_audit(&report);
+        assert_eq!(vulnerabilities.len(), 3);
+
+        let counts = count_by_severity(&vulnerabilities);
+        assert_eq!((counts.low, counts.high, counts.critical), (1, 1, 1));
+        assert_eq!(counts.total, 3);
+    }
+
+    #[test]
+    fn test_parse_severity() {
+        assert_eq!(
+            parse_severity(Some(&json!("critical"))),
+            VulnerabilitySeverity::Critical
+        );
+        assert_eq!(
+            parse_severity(Some(&json!("medium"))),
+            VulnerabilitySeverity::Moderate
+        );
+        assert_eq!(parse_severity(None), VulnerabilitySeverity::Info);
+        assert!(VulnerabilitySeverity::Critical > VulnerabilitySeverity::Low);
+    }
+}
@@ -0,0 +1,292 @@
+//! Doctor diagnostics
+//! Runs a structured health check sequence and reports per-check progress
+
+use crate::commands::config::{
+    test_api_completion, test_connection, validate_api_key, validate_base_url,
+};
+use crate::commands::resolver::probe_runner_offline;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, DoctorCheck, DoctorCheckStatus, DoctorProgressEvent, DoctorReport, LogEvent,
+    RunResult, RunStatus, SandboxConfig,
+};
+use crate::path_env::{build_spawn_path, find_in_path};
+use std::time::Instant;
+use tauri::AppHandle;
+
+const DOCTOR_CHECK_COUNT: usize = 4;
+const DOCTOR_PROMPT: &str = "Reply with the single word OK.";
+
+/// Run the doctor diagnostics and return the structured report
+#[tauri::command]
+pub async fn run_doctor(
+    app: AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<DoctorReport>, String> {
+    log::info!("Running doctor diagnostics");
+
+    let run_id = crate::models::generate_safe_run_id();
+    let report = run_doctor_diagnostics(&app, &config, &run_id).await;
+
+    log::info!("Doctor diagnostics completed: {:?}", report.overall_status);
+    Ok(ApiResponse::success(report))
+}
+
+/// Execute a Doctor-mode run in place of spawning the CLI
+pub async fn execute_doctor_run(
+    app: &AppHandle,
+    config: &SandboxConfig,
+    mut run_result: RunResult,
+) -> RunResult {
+    let start_time = Instant::now();
+    let report = run_doctor_diagnostics(app, config, &run_result.id).await;
+
+    let failed = report.overall_status == DoctorCheckStatus::Fail;
+    run_result.stdout = report.checks.iter().map(format_check_line).collect();
+    run_result.exit_code = Some(if failed { 1 } else { 0 });
+    run_result.status = if failed {
+        RunStatus::Failed
+    } else {
+        RunStatus::Completed
+    };
+    run_result.ended_at = Some(crate::models::current_timestamp());
+    run_result.duration_ms = Some(start_time.elapsed().as_millis() as u64);
+    run_result.doctor_report = Some(report);
+
+    run_result
+}
+
+/// Run every diagnostic check in sequence, emitting progress after each one
+pub async fn run_doctor_diagnostics(
+    app: &AppHandle,
+    config: &SandboxConfig,
+    run_id: &str,
+) -> DoctorReport {
+    let started_at = crate::models::current_timestamp();
+    let start_time = Instant::now();
+    let mut checks: Vec<DoctorCheck> = Vec::new();
+
+    let cli_check = check_cli_version(config);
+    emit_progress(app, run_id, checks.len(), &cli_check);
+    checks.push(cli_check);
+
+    let env_check = check_environment(config);
+    emit_progress(app, run_id, checks.len(), &env_check);
+    let env_ok = env_check.status != DoctorCheckStatus::Fail;
+    checks.push(env_check);
+
+    let connection_check = if env_ok {
+        check_connection(config).await
+    } else {
+        skipped("connection", "Connection test", "Environment is invalid")
+    };
+    emit_progress(app, run_id, checks.len(), &connection_check);
+    let connection_ok = connection_check.status == DoctorCheckStatus::Pass;
+    checks.push(connection_check);
+
+    let prompt_check = if connection_ok {
+        check_prompt_round_trip(config).await
+    } else {
+        skipped(
+            "prompt",
+            "Prompt round-trip",
+            "Connection test did not pass",
+        )
+    };
+    emit_progress(app, run_id, checks.len(), &prompt_check);
+    checks.push(prompt_check);
+
+    DoctorReport::new(checks, started_at, start_time.elapsed().as_millis() as u64)
+}
+
+/// Check that an ElizaOS CLI runner is available and report its version
+fn check_cli_version(config: &SandboxConfig) -> DoctorCheck {
+    let start_time = Instant::now();
+    let path_env = build_spawn_path(config.extra_path_dirs());
+
+    // Probing stops at the first runner that works, so lower-priority runners are never started
+    let mut rejections = Vec::new();
+    let mut available = None;
+    for runner in config.runner_priority() {
+        let probe = probe_runner_offline(runner, &path_env);
+        if probe.available {
+            available = Some(probe);
+            break;
+        }
+        rejections.extend(probe.rejection_reason);
+    }
+
+    let check = match available {
+        Some(probe) => DoctorCheck::new(
+            "cli",
+            "CLI version",
+            DoctorCheckStatus::Pass,
+            format!(
+                "{} {}",
+                probe.runner,
+                probe.version.as_deref().unwrap_or("unknown version")
+            ),
+        ),
+        None => DoctorCheck::new(
+            "cli",
+            "CLI version",
+            DoctorCheckStatus::Fail,
+            format!("ElizaOS CLI not available ({})", rejections.join("; ")),
+        ),
+    };
+
+    check.with_duration(start_time.elapsed().as_millis() as u64)
+}
+
+/// Validate the configuration and the tools the CLI depends on
+fn check_environment(config: &SandboxConfig) -> DoctorCheck {
+    let start_time = Instant::now();
+    let mut problems = Vec::new();
+
+    if !validate_base_url(&config.base_url) {
+        problems.push("base URL must start with http:// or https://");
+    }
+    if !validate_api_key(&config.api_key) {
+        problems.push("API key must be eliza_ followed by 64 hex characters");
+    }
+
+    let status = if !problems.is_empty() {
+        DoctorCheckStatus::Fail
+    } else if find_in_path("node", &build_spawn_path(config.extra_path_dirs())).is_none() {
+        problems.push("node not found on PATH");
+        DoctorCheckStatus::Warn
+    } else {
+        DoctorCheckStatus::Pass
+    };
+
+    let message = if problems.is_empty() {
+        "Configuration and environment look good".to_string()
+    } else {
+        problems.join("; ")
+    };
+
+    DoctorCheck::new("environment", "Environment", status, message)
+        .with_duration(start_time.elapsed().as_millis() as u64)
+}
+
+/// Check the Sandbox API is reachable and accepts the API key
+async fn check_connection(config: &SandboxConfig) -> DoctorCheck {
+    let start_time = Instant::now();
+
+    let (status, message) = match test_connection(config).await {
+        Ok(result) if result.success && result.error.is_none() => (
+            DoctorCheckStatus::Pass,
+            format!("Healthy ({}ms)", result.latency_ms.unwrap_or(0)),
+        ),
+        Ok(result) => (
+            DoctorCheckStatus::Fail,
+            result
+                .error
+                .unwrap_or_else(|| "Connection test failed".to_string()),
+        ),
+        Err(e) => (DoctorCheckStatus::Fail, e.to_string()),
+    };
+
+    DoctorCheck::new("connection", "Connection test", status, message)
+        .with_duration(start_time.elapsed().as_millis() as u64)
+}
+
+/// Send a minimal prompt and make sure a completion comes back
+async fn check_prompt_round_trip(config: &SandboxConfig) -> DoctorCheck {
+    let start_time = Instant::now();
+
+    let (status, message) = match test_api_completion(config, DOCTOR_PROMPT).await {
+        Ok(response) if !response.trim().is_empty() => (
+            DoctorCheckStatus::Pass,
+            format!("Received {} characters", response.len()),
+        ),
+        Ok(_) => (
+            DoctorCheckStatus::Warn,
+            "Completion returned an empty response".to_string(),
+        ),
+        Err(e) => (DoctorCheckStatus::Fail, e.to_string()),
+    };
+
+    DoctorCheck::new("prompt", "Prompt round-trip", status, message)
+        .with_duration(start_time.elapsed().as_millis() as u64)
+}
+
+fn skipped(id: &str, name: &str, reason: &str) -> DoctorCheck {
+    DoctorCheck::new(id, name, DoctorCheckStatus::Skipped, reason.to_string())
+}
+
+/// Emit per-check progress plus a matching log line for the run's log stream
+fn emit_progress(app: &AppHandle, run_id: &str, index: usize, check: &DoctorCheck) {
+    emit_event(
+        app,
+        "doctor-progress",
+        DoctorProgressEvent {
+            run_id: run_id.to_string(),
+            index,
+            total: DOCTOR_CHECK_COUNT,
+            check: check.clone(),
+        },
+    );
+
+    let line = format_check_line(check);
+    let event = if check.status == DoctorCheckStatus::Fail {
+        LogEvent::error(run_id.to_string(), line)
+    } else {
+        LogEvent::info(run_id.to_string(), line)
+    };
+    emit_event(app, "log-event", event);
+}
+
+/// Human-readable one-line summary of a check
+pub fn format_check_line(check: &DoctorCheck) -> String {
+    let label = match check.status {
+        DoctorCheckStatus::Pass => "PASS",
+        DoctorCheckStatus::Warn => "WARN",
+        DoctorCheckStatus::Fail => "FAIL",
+        DoctorCheckStatus::Skipped => "SKIP",
+    };
+    format!(
+        "[{}] {}: {} ({}ms)",
+        label, check.name, check.message, check.duration_ms
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_environment_invalid_config() {
+        let config = SandboxConfig::new("localhost".to_string(), "bad".to_string());
+        let check = check_environment(&config);
+
+        assert_eq!(check.status, DoctorCheckStatus::Fail);
+        assert!(check.message.contains("base URL"));
+        assert!(check.message.contains("API key"));
+    }
+
+    #[test]
+    fn test_report_status_ignores_skipped() {
+        let checks = vec![
+            DoctorCheck::new("cli", "CLI version", DoctorCheckStatus::Pass, String::new()),
+            skipped("prompt", "Prompt round-trip", "skipped"),
+        ];
+        let report = DoctorReport::new(checks, crate::models::current_timestamp(), 0);
+        assert_eq!(report.overall_status, DoctorCheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_format_check_line() {
+        let check = DoctorCheck::new(
+            "connection",
+            "Connection test",
+            DoctorCheckStatus::Fail,
+            "timed out".to_string(),
+        )
+        .with_duration(42);
+        assert_eq!(
+            format_check_line(&check),
+            "[FAIL] Connection test: timed out (42ms)"
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! Network egress monitoring
+//! Samples the connections a streaming run and its child processes hold open (via `lsof`)
+//! and raises `egress-alert` when one reaches an address that is not on the allow list
+
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ConnectionSummary, EgressAlertEvent, EgressConfig, LogEvent, NetworkConnection,
+    DEFAULT_EGRESS_SAMPLE_SECS,
+};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::task::JoinHandle;
+
+/// Least time between lookups of the allowed host names
+const RESOLVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Remote end of one open socket
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Endpoint {
+    protocol: String,
+    address: String,
+    port: u16,
+}
+
+/// Addresses a run may connect to, with the host name each came from
+#[derive(Debug, Default)]
+struct Allowlist {
+    hosts: Vec<String>,
+    addresses: HashMap<IpAddr, Option<String>>,
+    resolved_at: Option<Instant>,
+}
+
+impl Allowlist {
+    fn new(config: &EgressConfig, base_url: &str) -> Self {
+        let mut hosts: Vec<String> = config
+            .allowed_hosts
+            .iter()
+            .map(|host| host.trim().to_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect();
+        if let Some(host) = reqwest::Url::parse(base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+        {
+            hosts.push(host);
+        }
+        Self {
+            hosts,
+            ..Default::default()
+        }
+    }
+
+    /// Look the host names up again, at most once per `RESOLVE_INTERVAL`
+    async fn refresh(&mut self) {
+        if self
+            .resolved_at
+            .is_some_and(|at| at.elapsed() < RESOLVE_INTERVAL)
+        {
+            return;
+        }
+        self.resolved_at = Some(Instant::now());
+
+        for host in &self.hosts {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            if let Ok(ip) = host.parse::<IpAddr>() {
+                self.addresses.insert(ip, None);
+                continue;
+            }
+            match tokio::net::lookup_host((host, 443)).await {
+                Ok(addrs) => {
+                    for addr in addrs {
+                        self.addresses.insert(addr.ip(), Some(host.to_string()));
+                    }
+                }
+                Err(e) => log::debug!("Failed to resolve allowed host {}: {}", host, e),
+            }
+        }
+    }
+
+    fn allows(&self, address: &str) -> bool {
+        match address.parse::<IpAddr>() {
+            Ok(ip) => ip.is_loopback() || ip.is_unspecified() || self.addresses.contains_key(&ip),
+            Err(_) => self.hosts.iter().any(|host| host == address),
+        }
+    }
+
+    fn host_name(&self, address: &str) -> Option<String> {
+        let ip = address.parse::<IpAddr>().ok()?;
+        self.addresses.get(&ip).cloned().flatten()
+    }
+}
+
+/// Connections seen so far for one run
+#[derive(Debug, Default)]
+struct EgressTracker {
+    connections: HashMap<Endpoint, NetworkConnection>,
+    samples: u32,
+}
+
+impl EgressTracker {
+    /// Record one sample and return the endpoints that are new and not allowed
+    fn record(&mut self, endpoints: Vec<Endpoint>, allowlist: &Allowlist) -> Vec<Endpoint> {
+        let now = chrono::Utc::now().timestamp();
+        self.samples += 1;
+
+        let mut unexpected = Vec::new();
+        for endpoint in endpoints.into_iter().collect::<HashSet<_>>() {
+            if let Some(connection) = self.connections.get_mut(&endpoint) {
+                connection.last_seen = now;
+                connection.samples += 1;
+                continue;
+            }
+            let allowed = allowlist.allows(&endpoint.address);
+            if !allowed {
+                unexpected.push(endpoint.clone());
+            }
+            self.connections.insert(
+                endpoint.clone(),
+                NetworkConnection {
+                    protocol: endpoint.protocol.clone(),
+                    remote_address: endpoint.address.clone(),
+                    remote_port: endpoint.port,
+                    host_name: allowlist.host_name(&endpoint.address),
+                    allowed,
+                    first_seen: now,
+                    last_seen: now,
+                    samples: 1,
+                },
+            );
+        }
+        unexpected
+    }
+
+    fn summary(&self) -> ConnectionSummary {
+        let mut connections: Vec<NetworkConnection> = self.connections.values().cloned().collect();
+        connections.sort_by_key(|c| (c.first_seen, c.remote_address.clone(), c.remote_port));
+
+        let mut unexpected_addresses: Vec<String> = connections
+            .iter()
+            .filter(|c| !c.allowed)
+            .map(|c| c.remote_address.clone())
+            .collect();
+        unexpected_addresses.sort();
+        unexpected_addresses.dedup();
+
+        ConnectionSummary {
+            connections,
+            unexpected_addresses,
+            samples: self.samples,
+        }
+    }
+}
+
+/// Egress sampling of one run; `finish` stops it and returns what was seen
+pub struct EgressMonitor {
+    task: JoinHandle<()>,
+    tracker: Arc<Mutex<EgressTracker>>,
+}
+
+impl EgressMonitor {
+    pub fn finish(self) -> ConnectionSummary {
+        self.task.abort();
+        self.tracker
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .summary()
+    }
+}
+
+/// Sample a run's connections until `finish` is called
+pub fn spawn_egress_monitor(
+    app: AppHandle,
+    run_id: String,
+    pid: u32,
+    config: EgressConfig,
+    base_url: &str,
+) -> EgressMonitor {
+    let tracker = Arc::new(Mutex::new(EgressTracker::default()));
+    let interval = Duration::from_secs(
+        config
+            .sample_interval_secs
+            .unwrap_or(DEFAULT_EGRESS_SAMPLE_SECS)
+            .max(1),
+    );
+    let mut allowlist = Allowlist::new(&config, base_url);
+
+    let sampled = tracker.clone();
+    let task = tokio::spawn(async move {
+        allowlist.refresh().await;
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let endpoints = match sample_connections(pid).await {
+                Ok(endpoints) => endpoints,
+                Err(e) => {
+                    log::warn!("Stopping egress monitoring for run {}: {}", run_id, e);
+                    return;
+                }
+            };
+            // An address may belong to an allowed host whose records changed
+            if endpoints.iter().any(|e| !allowlist.allows(&e.address)) {
+                allowlist.refresh().await;
+            }
+
+            let unexpected = sampled
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .record(endpoints, &allowlist);
+            for endpoint in unexpected {
+                alert(&app, &run_id, endpoint);
+            }
+        }
+    });
+
+    EgressMonitor { task, tracker }
+}
+
+fn alert(app: &AppHandle, run_id: &str, endpoint: Endpoint) {
+    log::warn!(
+        "Run {} connected to unexpected address {}:{} ({})",
+        run_id,
+        endpoint.address,
+        endpoint.port,
+        endpoint.protocol
+    );
+    emit_event(
+        app,
+        "log-event",
+        LogEvent::system(
+            run_id.to_string(),
+            format!(
+                "Unexpected network connection to {}:{}",
+                endpoint.address, endpoint.port
+            ),
+        ),
+    );
+    emit_event(
+        app,
+        "egress-alert",
+        EgressAlertEvent {
+            run_id: run_id.to_string(),
+            protocol: endpoint.protocol,
+            remote_address: endpoint.address,
+            remote_port: endpoint.port,
+            timestamp: chrono::Utc::now().timestamp(),
+        },
+    );
+}
+
+/// Remote endpoints of the sockets held by a process and its descendants
+#[cfg(unix)]
+async fn sample_connections(pid: u32) -> Result<Vec<Endpoint>, String> {
+    use tokio::process::Command;
+
+    let ps = Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid="])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list processes: {}", e))?;
+    let pids = descendants(pid, &String::from_utf8_lossy(&ps.stdout));
+    let pid_list: Vec<String> = pids.iter().map(u32::to_string).collect();
+
+    let lsof = Command::new("lsof")
+        .args(["-nP", "-a", "-i", "-p", &pid_list.join(",")])
+        .output()
+        .await
+        .map_err(|e| format!("lsof is not available: {}", e))?;
+    // lsof exits with 1 when none of the processes has a socket open
+    Ok(parse_lsof(&String::from_utf8_lossy(&lsof.stdout)))
+}
+
+#[cfg(not(unix))]
+async fn sample_connections(_pid: u32) -> Result<Vec<Endpoint>, String> {
+    Err("Egress monitoring is not supported on this platform".to_string())
+}
+
+/// `root` and every process below it, from `ps -o pid=,ppid=` output
+#[cfg_attr(not(unix), allow(dead_code))]
+fn descendants(root: u32, ps_output: &str) -> Vec<u32> {
+    let parents: Vec<(u32, u32)> = ps_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+        })
+        .collect();
+
+    let mut pids = vec![root];
+    let mut index = 0;
+    while index < pids.len() {
+        let parent = pids[index];
+        for (pid, ppid) in &parents {
+            if *ppid == parent && !pids.contains(pid) {
+                pids.push(*pid);
+            }
+        }
+        index += 1;
+    }
+    pids
+}
+
+/// Connected sockets from `lsof -nP -i` output; listening sockets have no remote end
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_lsof(output: &str) -> Vec<Endpoint> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let name = fields.iter().position(|field| field.contains("->"))?;
+            let protocol = fields.get(name.checked_sub(1)?)?.to_string();
+            let (_, remote) = fields[name].split_once("->")?;
+            let (address, port) = remote.rsplit_once(':')?;
+            Some(Endpoint {
+                protocol,
+                address: address
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string(),
+                port: port.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LSOF_OUTPUT: &str = "\
+COMMAND  PID USER   FD   TYPE             DEVICE SIZE/OFF NODE NAME
+node    4242 dev   21u  IPv4 0x8f3c2a1b      0t0  TCP 127.0.0.1:3000 (LISTEN)
+node    4242 dev   23u  IPv4 0x8f3c2a1c      0t0  TCP 192.168.1.5:52344->104.18.32.7:443 (ESTABLISHED)
+node    4243 dev   24u  IPv6 0x8f3c2a1d      0t0  TCP [::1]:52345->[::1]:5432 (ESTABLISHED)
+node    4243 dev   25u  IPv4 0x8f3c2a1e      0t0  UDP 192.168.1.5:61000->8.8.8.8:53
+";
+
+    fn endpoint(protocol: &str, address: &str, port: u16) -> Endpoint {
+        Endpoint {
+            protocol: protocol.to_string(),
+            address: address.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn test_parse_lsof() {
+        assert_eq!(
+            parse_lsof(LSOF_OUTPUT),
+            vec![
+                endpoint("TCP", "104.18.32.7", 443),
+                endpoint("TCP", "::1", 5432),
+                endpoint("UDP", "8.8.8.8", 53),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_descendants() {
+        let ps = "    1     0\n  100     1\n  101   100\n  102   101\n  200     1\n";
+        assert_eq!(descendants(100, ps), vec![100, 101, 102]);
+        assert_eq!(descendants(300, ps), vec![300]);
+    }
+
+    #[test]
+    fn test_tracker_alerts_once_per_unexpected_endpoint() {
+        let config = EgressConfig {
+            allowed_hosts: vec!["104.18.32.7".to_string()],
+            sample_interval_secs: None,
+        };
+        let mut allowlist = Allowlist::new(&config, "https://sandbox.example.com/api/v1");
+        allowlist
+            .addresses
+            .insert("104.18.32.7".parse().unwrap(), None);
+        assert_eq!(allowlist.hosts, vec!["104.18.32.7", "sandbox.example.com"]);
+
+        let mut tracker = EgressTracker::default();
+        let unexpected = tracker.record(parse_lsof(LSOF_OUTPUT), &allowlist);
+        assert_eq!(unexpected, vec![endpoint("UDP", "8.8.8.8", 53)]);
+        assert!(tracker
+            .record(parse_lsof(LSOF_OUTPUT), &allowlist)
+            .is_empty());
+
+        let summary = tracker.summary();
+        assert_eq!(summary.samples, 2);
+        assert_eq!(summary.connections.len(), 3);
+        assert!(summary.connections.iter().all(|c| c.samples == 2));
+        assert_eq!(summary.unexpected_addresses, vec!["8.8.8.8"]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Eval run support
+//! Parses the report produced by an eval run into an `EvalResult`
+
+use crate::models::{AppError, EvalCaseResult, EvalResult, RunMode, RunSpec};
+use serde_json::Value;
+use std::fs;
+
+/// Give an eval without an explicit report path one named after the run ID rather than the
+/// caller's spec ID, so two evals of the same spec never share a report
+pub fn with_run_report_path(mut spec: RunSpec, run_id: &str) -> RunSpec {
+    if let Some(eval) = spec.eval.as_mut() {
+        if eval.report_path.is_none() {
+            eval.report_path = Some(eval.report_path_for(run_id));
+        }
+    }
+    spec
+}
+
+/// Load the eval report for a finished run, if the run was an eval
+pub fn collect_eval_result(spec: &RunSpec) -> Option<EvalResult> {
+    if !matches!(spec.mode, RunMode::Eval) {
+        return None;
+    }
+
+    let eval = spec.eval.as_ref()?;
+    let report_path = eval.report_path_for(&spec.id);
+
+    match load_eval_report(&report_path) {
+        Ok(result) => {
+            log::info!(
+                "Eval report parsed: {}/{} passed ({})",
+                result.passed,
+                result.total,
+                report_path
+            );
+            Some(result)
+        }
+        Err(e) => {
+            log::warn!("Failed to load eval report {}: {}", report_path, e);
+            None
+        }
+    }
+}
+
+/// Read and parse an eval report file
+pub fn load_eval_report(report_path: &str) -> Result<EvalResult, AppError> {
+    let contents = fs::read_to_string(report_path)
+        .map_err(|e| AppError::Eval(format!("Failed to read eval report: {}", e)))?;
+
+    let report: Value = serde_json::from_str(&contents)?;
+
+    parse_eval_report(&report, report_path)
+}
+
+/// Parse an eval report, accepting either a `results` or `cases` array and an optional `summary`
+fn parse_eval_report(report: &Value, report_path: &str) -> Result<EvalResult, AppError> {
+    let entries = report
+        .get("results")
+        .or_else(|| report.get("cases"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| AppError::Eval("Eval report has no results".to_string()))?;
+
+    let cases: Vec<EvalCaseResult> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| parse_eval_case(entry, index))
+        .collect();
+
+    let summary = report.get("summary");
+    let summary_count = |key: &str| {
+        summary
+            .and_then(|s| s.get(key))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+    };
+
+    let passed = summary_count("passed")
+        .unwrap_or_else(|| cases.iter().filter(|case| case.passed).count() as u32);
+    let total = summary_count("total").unwrap_or(cases.len() as u32);
+    let failed = summary_count("failed").unwrap_or(total.saturating_sub(passed));
+
+    let pass_rate = if total > 0 {
+        passed as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    Ok(EvalResult {
+        report_path: report_path.to_string(),
+        total,
+        passed,
+        failed,
+        pass_rate,
+        cases,
+    })
+}
+
+/// Parse a single case entry, tolerating `passed: bool` or `status: "passed"` shapes
+fn parse_eval_case(entry: &Value, index: usize) -> EvalCaseResult {
+    let name = entry
+        .get("name")
+        .or_else(|| entry.get("scenario"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| format!("case {}", index + 1));
+
+    let passed = entry
+        .get("passed")
+        .and_then(|v| v.as_bool())
+        .or_else(|| {
+            entry
+                .get("status")
+                .and_then(|v| v.as_str())
+                .map(|status| matches!(status, "passed" | "pass" | "success"))
+        })
+        .unwrap_or(false);
+
+    EvalCaseResult {
+        name,
+        passed,
+        duration_ms: entry
+            .get("durationMs")
+            .or_else(|| entry.get("duration_ms"))
+            .and_then(|v| v.as_u64()),
+        error: entry
+            .get("error")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::EvalSpec;
+
+    #[test]
+    fn test_parse_eval_report() {
+        let report = serde_json::json!({
+            "results": [
+                { "name": "greets user", "passed": true, "durationMs": 120 },
+                { "name": "refuses secrets", "status": "failed", "error": "leaked key" },
+                { "status": "pass" }
+            ]
+        });
+
+        let result = parse_eval_report(&report, "/tmp/report.json").unwrap();
+        assert_eq!(result.total, 3);
+        assert_eq!(result.passed, 2);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.cases[1].error, Some("leaked key".to_string()));
+        assert_eq!(result.cases[2].name, "case 3");
+    }
+
+    #[test]
+    fn test_report_path_is_per_run() {
+        let mut spec = RunSpec::new("nightly".to_string(), RunMode::Eval, Vec::new());
+        spec.eval = Some(EvalSpec::new("scenario.yaml".to_string()));
+
+        let first = with_run_report_path(spec.clone(), "run_1");
+        let second = with_run_report_path(spec.clone(), "run_2");
+        let path = |spec: &RunSpec| spec.eval.as_ref().unwrap().report_path.clone().unwrap();
+        assert_ne!(path(&first), path(&second));
+        assert_eq!(
+            path(&with_run_report_path(first.clone(), "run_3")),
+            path(&first)
+        );
+
+        let crafted = EvalSpec::new("scenario.yaml".to_string()).report_path_for("../../etc/x");
+        assert_eq!(
+            std::path::Path::new(&crafted).parent(),
+            Some(std::env::temp_dir().as_path())
+        );
+        assert!(crafted.ends_with("eliza-eval-______etc_x.json"));
+    }
+
+    #[test]
+    fn test_parse_eval_report_without_results() {
+        let report = serde_json::json!({ "summary": { "total": 0 } });
+        assert!(parse_eval_report(&report, "/tmp/report.json").is_err());
+    }
+}
@@ -0,0 +1,611 @@
+//! Eval experiments
+//! Runs an eval scenario across every character/model combination, a few evals at a time,
+//! and stores the aggregated comparison as one experiment record; stored experiments can be
+//! listed and compared per character and per model for A/B testing
+
+use crate::commands::budget::check_run_budget;
+use crate::commands::characters::character_file;
+use crate::commands::process::{
+    audit_run, describe_run_for_audit, execute_eliza_run_streaming_with_id,
+};
+use crate::commands::stats::emit_event;
+use crate::commands::telemetry::estimate_token_usage;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, AuditAction, EvalMatrixCell, EvalMatrixRun,
+    EvalMatrixSummary, EvalSpec, Experiment, ExperimentResults, ExperimentSummary,
+    ExperimentVariant, LogEvent, RunMode, RunResult, RunSpec, RunStatus, SandboxConfig,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Semaphore;
+
+const EXPERIMENTS_DIR: &str = "experiments";
+/// Eval runs in flight at once
+pub const MAX_CONCURRENT_EVALS: usize = 3;
+/// Upper bound on runs in one matrix
+pub const MAX_MATRIX_RUNS: usize = 100;
+const MAX_NAME_LEN: usize = 128;
+/// Environment variable a run's seed is exported as
+const SEED_ENV: &str = "ELIZA_SEED";
+
+/// Run an eval scenario for every character/model pair `iterations` times and aggregate
+///
+/// With a `seed`, iteration `n` of every pair runs with `seed + n`, so arms are compared on
+/// the same sequence of seeds.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_eval_matrix(
+    app: AppHandle,
+    character_ids: Vec<String>,
+    models: Vec<String>,
+    iterations: u32,
+    scenario_path: String,
+    config: SandboxConfig,
+    name: Option<String>,
+    seed: Option<u64>,
+) -> Result<ApiResponse<Experiment>, String> {
+    log::info!(
+        "Starting eval matrix: {} characters x {} models x {} iterations",
+        character_ids.len(),
+        models.len(),
+        iterations
+    );
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        validate_matrix(&character_ids, &models, iterations)?;
+        let name = normalize_name(name)?;
+        check_run_budget(&app).await?;
+        let character_files = character_ids
+            .iter()
+            .map(|id| character_file(&app, id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let experiment_id = generate_experiment_id();
+        let started_at = current_timestamp();
+        let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_EVALS));
+        let mut tasks = Vec::new();
+
+        for (character_id, character_path) in character_ids.iter().zip(&character_files) {
+            for model in &models {
+                for iteration in 0..iterations {
+                    let run_seed = seed.map(|seed| seed.wrapping_add(u64::from(iteration)));
+                    let mut spec = eval_spec(&scenario_path, character_path);
+                    if let Some(run_seed) = run_seed {
+                        spec.env.insert(SEED_ENV.to_string(), run_seed.to_string());
+                    }
+                    let mut config = config.clone();
+                    config.default_model = Some(model.clone());
+                    tasks.push((
+                        character_id.clone(),
+                        model.clone(),
+                        tokio::spawn(run_eval(
+                            app.clone(),
+                            experiment_id.clone(),
+                            spec,
+                            config,
+                            run_seed,
+                            semaphore.clone(),
+                        )),
+                    ));
+                }
+            }
+        }
+
+        let mut runs = Vec::new();
+        for (character_id, model, task) in tasks {
+            let run = task
+                .await
+                .map_err(|e| AppError::Process(format!("Eval task failed: {}", e)))?;
+            runs.push((character_id, model, run));
+        }
+
+        let experiment = Experiment {
+            id: experiment_id,
+            name,
+            scenario_path: scenario_path.clone(),
+            character_ids: character_ids.clone(),
+            models: models.clone(),
+            iterations,
+            seed,
+            started_at,
+            ended_at: current_timestamp(),
+            cells: build_cells(&character_ids, &models, runs),
+        };
+        save_experiment(&app, &experiment)?;
+        Ok::<_, AppError>(experiment)
+    }
+    .await;
+
+    match result {
+        Ok(experiment) => {
+            log::info!(
+                "Eval matrix {} finished with {} cells",
+                experiment.id,
+                experiment.cells.len()
+            );
+            Ok(ApiResponse::success(experiment))
+        }
+        Err(e) => {
+            log::error!("Eval matrix failed: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Eval matrix failed: {}", e),
+            ))
+        }
+    }
+}
+
+/// Load a stored experiment record
+#[tauri::command]
+pub async fn get_experiment(
+    app: AppHandle,
+    experiment_id: String,
+) -> Result<ApiResponse<Experiment>, String> {
+    match load_experiment(&app, &experiment_id) {
+        Ok(experiment) => Ok(ApiResponse::success(experiment)),
+        Err(e) => Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            format!("Failed to load experiment: {}", e),
+        )),
+    }
+}
+
+/// List stored experiments, newest first
+#[tauri::command]
+pub async fn list_experiments(
+    app: AppHandle,
+) -> Result<ApiResponse<Vec<ExperimentSummary>>, String> {
+    let result = (|| {
+        let mut experiments = Vec::new();
+        for entry in fs::read_dir(experiments_dir(&app)?)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(AppError::from)
+                .and_then(|text| Ok(serde_json::from_str::<Experiment>(&text)?))
+            {
+                Ok(experiment) => experiments.push(summarize(&experiment)),
+                Err(e) => log::warn!("Skipping unreadable experiment {:?}: {}", path, e),
+            }
+        }
+        experiments.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        Ok::<_, AppError>(experiments)
+    })();
+
+    match result {
+        Ok(experiments) => Ok(ApiResponse::success(experiments)),
+        Err(e) => Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            format!("Failed to list experiments: {}", e),
+        )),
+    }
+}
+
+/// Aggregate an experiment's runs per character and per model
+#[tauri::command]
+pub async fn get_experiment_results(
+    app: AppHandle,
+    experiment_id: String,
+) -> Result<ApiResponse<ExperimentResults>, String> {
+    match load_experiment(&app, &experiment_id) {
+        Ok(experiment) => Ok(ApiResponse::success(experiment_results(&experiment))),
+        Err(e) => Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            format!("Failed to load experiment results: {}", e),
+        )),
+    }
+}
+
+fn summarize(experiment: &Experiment) -> ExperimentSummary {
+    let runs: Vec<EvalMatrixRun> = experiment
+        .cells
+        .iter()
+        .flat_map(|cell| cell.runs.iter().cloned())
+        .collect();
+
+    ExperimentSummary {
+        id: experiment.id.clone(),
+        name: experiment.name.clone(),
+        scenario_path: experiment.scenario_path.clone(),
+        character_ids: experiment.character_ids.clone(),
+        models: experiment.models.clone(),
+        iterations: experiment.iterations,
+        seed: experiment.seed,
+        started_at: experiment.started_at.clone(),
+        ended_at: experiment.ended_at.clone(),
+        overall: EvalMatrixSummary::from_runs(&runs),
+    }
+}
+
+fn experiment_results(experiment: &Experiment) -> ExperimentResults {
+    let by_character: Vec<ExperimentVariant> = experiment
+        .character_ids
+        .iter()
+        .map(|id| {
+            variant(
+                id,
+                experiment.cells.iter().filter(|c| &c.character_id == id),
+            )
+        })
+        .collect();
+    let by_model = experiment
+        .models
+        .iter()
+        .map(|model| variant(model, experiment.cells.iter().filter(|c| &c.model == model)))
+        .collect();
+
+    let best_character = by_character
+        .iter()
+        .filter(|arm| arm.summary.runs > 0)
+        .max_by(|a, b| {
+            let rate = |arm: &ExperimentVariant| arm.summary.mean_pass_rate.unwrap_or(-1.0);
+            rate(a)
+                .total_cmp(&rate(b))
+                .then(a.summary.success_rate.total_cmp(&b.summary.success_rate))
+        })
+        .map(|arm| arm.key.clone());
+
+    ExperimentResults {
+        experiment: summarize(experiment),
+        by_character,
+        by_model,
+        best_character,
+    }
+}
+
+fn variant<'a>(key: &str, cells: impl Iterator<Item = &'a EvalMatrixCell>) -> ExperimentVariant {
+    let runs: Vec<EvalMatrixRun> = cells.flat_map(|cell| cell.runs.iter().cloned()).collect();
+    let pass_rates: Vec<f64> = runs.iter().filter_map(|run| run.pass_rate).collect();
+
+    ExperimentVariant {
+        key: key.to_string(),
+        summary: EvalMatrixSummary::from_runs(&runs),
+        pass_rate_std_dev: std_dev(&pass_rates),
+    }
+}
+
+/// Sample standard deviation; needs at least two values
+fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Wait for a slot, then run one eval and reduce it to its matrix entry
+async fn run_eval(
+    app: AppHandle,
+    experiment_id: String,
+    spec: RunSpec,
+    config: SandboxConfig,
+    seed: Option<u64>,
+    semaphore: Arc<Semaphore>,
+) -> EvalMatrixRun {
+    let _permit = semaphore.acquire_owned().await;
+    let run_id = crate::models::generate_safe_run_id();
+
+    let detail = format!(
+        "experiment {}: {}",
+        experiment_id,
+        describe_run_for_audit(&spec)
+    );
+    audit_run(&app, AuditAction::RunStarted, &run_id, true, Some(detail)).await;
+
+    match execute_eliza_run_streaming_with_id(app.clone(), spec, config, run_id.clone()).await {
+        Ok(result) => matrix_run(&result, seed),
+        Err(e) => {
+            log::error!("Eval run {} failed to start: {}", run_id, e);
+            emit_event(
+                &app,
+                "log-event",
+                LogEvent::error(run_id.clone(), format!("Failed to start run: {}", e)),
+            );
+            EvalMatrixRun {
+                run_id,
+                status: RunStatus::Failed,
+                duration_ms: None,
+                pass_rate: None,
+                approx_tokens: 0,
+                error: Some(e.to_string()),
+                seed,
+            }
+        }
+    }
+}
+
+fn matrix_run(result: &RunResult, seed: Option<u64>) -> EvalMatrixRun {
+    let output: String = result
+        .stdout
+        .iter()
+        .chain(&result.stderr)
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    EvalMatrixRun {
+        run_id: result.id.clone(),
+        status: result.status,
+        duration_ms: result.duration_ms,
+        pass_rate: result.eval_result.as_ref().map(|eval| eval.pass_rate),
+        approx_tokens: estimate_token_usage(&output),
+        error: None,
+        seed,
+    }
+}
+
+fn eval_spec(scenario_path: &str, character_path: &Path) -> RunSpec {
+    let mut spec = RunSpec::new(
+        crate::models::generate_safe_run_id(),
+        RunMode::Eval,
+        Vec::new(),
+    );
+    spec.character_file = Some(character_path.to_string_lossy().to_string());
+    spec.eval = Some(EvalSpec::new(scenario_path.to_string()));
+    spec
+}
+
+/// Group runs into one cell per character/model pair, in matrix order
+fn build_cells(
+    character_ids: &[String],
+    models: &[String],
+    runs: Vec<(String, String, EvalMatrixRun)>,
+) -> Vec<EvalMatrixCell> {
+    let mut cells: Vec<EvalMatrixCell> = character_ids
+        .iter()
+        .flat_map(|character_id| {
+            models.iter().map(move |model| EvalMatrixCell {
+                character_id: character_id.clone(),
+                model: model.clone(),
+                runs: Vec::new(),
+                summary: EvalMatrixSummary::default(),
+            })
+        })
+        .collect();
+
+    for (character_id, model, run) in runs {
+        if let Some(cell) = cells
+            .iter_mut()
+            .find(|cell| cell.character_id == character_id && cell.model == model)
+        {
+            cell.runs.push(run);
+        }
+    }
+    for cell in &mut cells {
+        cell.summary = EvalMatrixSummary::from_runs(&cell.runs);
+    }
+    cells
+}
+
+fn validate_matrix(
+    character_ids: &[String],
+    models: &[String],
+    iterations: u32,
+) -> Result<(), AppError> {
+    if character_ids.is_empty() || models.is_empty() || iterations == 0 {
+        return Err(AppError::Eval(
+            "An eval matrix needs at least one character, one model and one iteration".to_string(),
+        ));
+    }
+    if models.iter().any(|model| model.trim().is_empty()) {
+        return Err(AppError::Eval("Model names must not be empty".to_string()));
+    }
+
+    let total = character_ids.len() * models.len() * iterations as usize;
+    if total > MAX_MATRIX_RUNS {
+        return Err(AppError::Quota(format!(
+            "Eval matrix would start {} runs; the limit is {}",
+            total, MAX_MATRIX_RUNS
+        )));
+    }
+    Ok(())
+}
+
+/// Trim the experiment name; a blank name is no name
+fn normalize_name(name: Option<String>) -> Result<Option<String>, AppError> {
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    if name
+        .as_ref()
+        .is_some_and(|name| name.chars().count() > MAX_NAME_LEN)
+    {
+        return Err(AppError::Eval(format!(
+            "Experiment names are limited to {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    Ok(name)
+}
+
+fn validate_experiment_id(id: &str) -> Result<(), AppError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(AppError::Eval(format!("Invalid experiment ID '{}'", id)));
+    }
+    Ok(())
+}
+
+fn generate_experiment_id() -> String {
+    format!(
+        "exp_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+fn experiments_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?
+        .join(EXPERIMENTS_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn load_experiment(app: &AppHandle, experiment_id: &str) -> Result<Experiment, AppError> {
+    validate_experiment_id(experiment_id)?;
+    let path = experiments_dir(app)?.join(format!("{}.json", experiment_id));
+    if !path.exists() {
+        return Err(AppError::Eval(format!(
+            "Experiment '{}' not found",
+            experiment_id
+        )));
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_experiment(app: &AppHandle, experiment: &Experiment) -> Result<(), AppError> {
+    let path = experiments_dir(app)?.join(format!("{}.json", experiment.id));
+    fs::write(path, serde_json::to_vec_pretty(experiment)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(status: RunStatus, duration_ms: u64, pass_rate: Option<f64>) -> EvalMatrixRun {
+        EvalMatrixRun {
+            run_id: "run_1".to_string(),
+            status,
+            duration_ms: Some(duration_ms),
+            pass_rate,
+            approx_tokens: 100,
+            error: None,
+            seed: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_from_runs() {
+        let summary = EvalMatrixSummary::from_runs(&[
+            run(RunStatus::Completed, 1000, Some(1.0)),
+            run(RunStatus::Completed, 3000, Some(0.5)),
+            run(RunStatus::Failed, 2000, None),
+        ]);
+        assert_eq!(summary.runs, 3);
+        assert_eq!(summary.succeeded, 1);
+        assert!((summary.success_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.mean_pass_rate, Some(0.75));
+        assert_eq!(summary.mean_duration_ms, Some(2000.0));
+        assert_eq!(summary.total_tokens, 300);
+
+        let empty = EvalMatrixSummary::from_runs(&[]);
+        assert_eq!(empty.success_rate, 0.0);
+        assert!(empty.mean_duration_ms.is_none());
+    }
+
+    #[test]
+    fn test_build_cells() {
+        let characters = vec!["ada".to_string(), "bob".to_string()];
+        let models = vec!["gpt-4".to_string()];
+        let runs = vec![
+            (
+                "bob".to_string(),
+                "gpt-4".to_string(),
+                run(RunStatus::Completed, 10, None),
+            ),
+            (
+                "ada".to_string(),
+                "gpt-4".to_string(),
+                run(RunStatus::Failed, 10, None),
+            ),
+        ];
+
+        let cells = build_cells(&characters, &models, runs);
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].character_id, "ada");
+        assert_eq!(cells[0].summary.succeeded, 0);
+        assert_eq!(cells[1].summary.succeeded, 1);
+    }
+
+    #[test]
+    fn test_experiment_results_compare_arms() {
+        let characters = vec!["ada".to_string(), "bob".to_string()];
+        let models = vec!["gpt-4".to_string(), "claude".to_string()];
+        let runs = vec![
+            (
+                "ada".to_string(),
+                "gpt-4".to_string(),
+                run(RunStatus::Completed, 10, Some(0.5)),
+            ),
+            (
+                "ada".to_string(),
+                "claude".to_string(),
+                run(RunStatus::Completed, 10, Some(0.7)),
+            ),
+            (
+                "bob".to_string(),
+                "gpt-4".to_string(),
+                run(RunStatus::Completed, 10, Some(1.0)),
+            ),
+            (
+                "bob".to_string(),
+                "claude".to_string(),
+                run(RunStatus::Failed, 10, None),
+            ),
+        ];
+        let experiment = Experiment {
+            id: "exp_1".to_string(),
+            name: Some("ada vs bob".to_string()),
+            scenario_path: "scenario.json".to_string(),
+            character_ids: characters.clone(),
+            models: models.clone(),
+            iterations: 1,
+            seed: Some(42),
+            started_at: current_timestamp(),
+            ended_at: current_timestamp(),
+            cells: build_cells(&characters, &models, runs),
+        };
+
+        let results = experiment_results(&experiment);
+        assert_eq!(results.experiment.overall.runs, 4);
+        assert_eq!(results.by_character[0].key, "ada");
+        let ada = &results.by_character[0];
+        assert!((ada.summary.mean_pass_rate.unwrap() - 0.6).abs() < 1e-9);
+        assert!((ada.pass_rate_std_dev.unwrap() - 0.1414).abs() < 1e-3);
+        assert_eq!(results.by_character[1].pass_rate_std_dev, None);
+        assert_eq!(results.by_model[1].summary.succeeded, 0);
+        assert_eq!(results.best_character.as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name(None).unwrap(), None);
+        assert_eq!(normalize_name(Some("  ".to_string())).unwrap(), None);
+        assert_eq!(
+            normalize_name(Some(" A/B ".to_string()))
+                .unwrap()
+                .as_deref(),
+            Some("A/B")
+        );
+        assert!(normalize_name(Some("x".repeat(MAX_NAME_LEN + 1))).is_err());
+    }
+
+    #[test]
+    fn test_validate_matrix() {
+        let one = vec!["a".to_string()];
+        assert!(validate_matrix(&one, &one, 1).is_ok());
+        assert!(validate_matrix(&[], &one, 1).is_err());
+        assert!(validate_matrix(&one, &one, 0).is_err());
+        let err = validate_matrix(&one, &one, MAX_MATRIX_RUNS as u32 + 1).unwrap_err();
+        assert_eq!(err.error_code(), "QUOTA_EXCEEDED");
+    }
+}
@@ -0,0 +1,348 @@
+//! Run failure diagnosis
+//! Matches a finished run's output and exit code against signatures of failures agents
+//! commonly hit (missing provider keys, native module builds, busy ports) and ranks the
+//! remediation steps of those that match
+
+use crate::commands::network_capture::redact_text;
+use crate::commands::ports::detect_port_conflict;
+use crate::commands::reports::find_run;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, FailureConfidence, FailureSuggestion,
+    RunFailureDiagnosis, RunResult, RunStatus,
+};
+use regex::Regex;
+use std::cmp::Reverse;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+/// Matched lines kept as evidence per suggestion
+const MAX_EVIDENCE_LINES: usize = 3;
+const MAX_EVIDENCE_LEN: usize = 200;
+
+/// A known failure: any pattern matching an output line, or the exit code, points at it
+struct FailureSignature {
+    id: &'static str,
+    title: &'static str,
+    /// Case-insensitive regexes matched against each stdout and stderr line
+    patterns: &'static [&'static str],
+    exit_codes: &'static [i32],
+    remediation: &'static [&'static str],
+}
+
+const SIGNATURES: &[FailureSignature] = &[
+    FailureSignature {
+        id: "missing_openai_key",
+        title: "OpenAI API key is missing or rejected",
+        patterns: &[
+            r"OPENAI_API_KEY.*(missing|not set|not defined|undefined|required)",
+            r"(missing|no) openai api key",
+            r"incorrect api key provided",
+            r"openai.*(401|unauthorized|invalid_api_key)",
+        ],
+        exit_codes: &[],
+        remediation: &[
+            "Add OPENAI_API_KEY as a secret, or to the project's .env and import it",
+            "Check that the key is active in the OpenAI dashboard",
+            "Or point the character at a Sandbox model so no OpenAI key is needed",
+        ],
+    },
+    FailureSignature {
+        id: "better_sqlite3_build",
+        title: "better-sqlite3 native module failed to build or load",
+        patterns: &[
+            r"better[-_]sqlite3",
+            r"was compiled against a different Node\.js version",
+            r"NODE_MODULE_VERSION \d+",
+        ],
+        exit_codes: &[],
+        remediation: &[
+            "Rebuild the module for the current Node.js: run `npm rebuild better-sqlite3` in the project",
+            "Make sure the Node.js version matches the one the project was installed with, then reinstall dependencies",
+            "If the build itself fails, install the native build tools (see the node-gyp suggestion)",
+        ],
+    },
+    FailureSignature {
+        id: "port_in_use",
+        title: "The agent's port is already in use",
+        // Matched with `detect_port_conflict`, which also extracts the port
+        patterns: &[],
+        exit_codes: &[],
+        remediation: &[
+            "Stop the other agent or process listening on the port",
+            "Start the agent on a free port (SERVER_PORT), or enable automatic port conflict retries in the configuration",
+        ],
+    },
+    FailureSignature {
+        id: "node_gyp",
+        title: "A native dependency failed to compile (node-gyp)",
+        patterns: &[
+            r"gyp ERR!",
+            r"node-gyp",
+            r"No Xcode or CLT version detected",
+            r"Could not find any Visual Studio installation",
+            r"make: \*\*\*",
+        ],
+        exit_codes: &[],
+        remediation: &[
+            "Install the native build tools: `xcode-select --install` on macOS, `build-essential` and python3 on Linux, or Visual Studio Build Tools (Desktop C++) on Windows",
+            "Delete node_modules and reinstall the project's dependencies",
+            "Use a Node.js LTS release; prebuilt binaries are often missing for newer ones",
+        ],
+    },
+    FailureSignature {
+        id: "command_not_found",
+        title: "A required command was not found",
+        patterns: &[
+            r"command not found",
+            r"is not recognized as an internal or external command",
+            r"spawn \S+ ENOENT",
+        ],
+        exit_codes: &[127],
+        remediation: &[
+            "Run the preflight check to see which tools are missing",
+            "Install Node.js and Bun, then add any custom install location to the configuration's extra PATH directories",
+        ],
+    },
+    FailureSignature {
+        id: "out_of_memory",
+        title: "The agent ran out of memory",
+        patterns: &[
+            r"JavaScript heap out of memory",
+            r"Allocation failed - process out of memory",
+        ],
+        exit_codes: &[134, 137],
+        remediation: &[
+            "Raise Node's heap limit by adding NODE_OPTIONS=--max-old-space-size=4096 to the run's env",
+            "Reduce the knowledge loaded by the character, or close other memory-heavy runs",
+        ],
+    },
+];
+
+/// Match a finished run's output against known failures and suggest fixes, most likely first
+#[tauri::command]
+pub async fn diagnose_run_failure(
+    app: AppHandle,
+    run_id: String,
+) -> Result<ApiResponse<RunFailureDiagnosis>, String> {
+    let result = async {
+        let result = find_run(&app, &run_id)
+            .await?
+            .result
+            .ok_or_else(|| AppError::Process(format!("Run {} has no recorded result", run_id)))?;
+        if result.status == RunStatus::Running {
+            return Err(AppError::Process(format!(
+                "Run {} is still running",
+                run_id
+            )));
+        }
+        Ok::<_, AppError>(diagnose(&result))
+    }
+    .await;
+
+    match result {
+        Ok(diagnosis) => {
+            log::info!(
+                "Diagnosed run {}: {}",
+                run_id,
+                match diagnosis.suggestions.first() {
+                    Some(suggestion) => suggestion.id.as_str(),
+                    None => "no known failure",
+                }
+            );
+            Ok(ApiResponse::success(diagnosis))
+        }
+        Err(e) => {
+            log::error!("Failed to diagnose run {}: {}", run_id, e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to diagnose run: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+fn diagnose(result: &RunResult) -> RunFailureDiagnosis {
+    let lines: Vec<&String> = result.stderr.iter().chain(result.stdout.iter()).collect();
+
+    let mut scored: Vec<(usize, FailureSuggestion)> = SIGNATURES
+        .iter()
+        .zip(compiled_signatures())
+        .filter_map(|(signature, patterns)| {
+            let evidence: Vec<&String> = if signature.id == "port_in_use" {
+                port_conflict_lines(result)
+            } else {
+                lines
+                    .iter()
+                    .filter(|line| patterns.iter().any(|pattern| pattern.is_match(line)))
+                    .copied()
+                    .collect()
+            };
+            let exit_code_matches = result
+                .exit_code
+                .is_some_and(|code| signature.exit_codes.contains(&code));
+            let score = evidence.len().min(MAX_EVIDENCE_LINES) * 2 + usize::from(exit_code_matches);
+            (score > 0).then(|| (score, suggestion(signature, result, &evidence, score)))
+        })
+        .collect();
+    // Highest score first; signature order breaks ties
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+
+    RunFailureDiagnosis {
+        run_id: result.id.clone(),
+        status: result.status,
+        exit_code: result.exit_code,
+        suggestions: scored
+            .into_iter()
+            .map(|(_, suggestion)| suggestion)
+            .collect(),
+        diagnosed_at: current_timestamp(),
+    }
+}
+
+fn suggestion(
+    signature: &FailureSignature,
+    result: &RunResult,
+    evidence: &[&String],
+    score: usize,
+) -> FailureSuggestion {
+    let mut remediation: Vec<String> = signature
+        .remediation
+        .iter()
+        .map(|step| step.to_string())
+        .collect();
+    if signature.id == "port_in_use" {
+        if let Some(Some(port)) = detect_port_conflict(&result.stderr) {
+            remediation[0] = format!("Stop the other agent or process listening on port {}", port);
+        }
+    }
+
+    FailureSuggestion {
+        id: signature.id.to_string(),
+        title: signature.title.to_string(),
+        confidence: match score {
+            0..=1 => FailureConfidence::Low,
+            2..=3 => FailureConfidence::Medium,
+            _ => FailureConfidence::High,
+        },
+        evidence: evidence
+            .iter()
+            .take(MAX_EVIDENCE_LINES)
+            .map(|line| {
+                let line = redact_text(line.trim());
+                match line.char_indices().nth(MAX_EVIDENCE_LEN) {
+                    Some((index, _)) => format!("{}...", &line[..index]),
+                    None => line,
+                }
+            })
+            .collect(),
+        remediation,
+    }
+}
+
+/// Stderr lines reporting a busy port
+fn port_conflict_lines(result: &RunResult) -> Vec<&String> {
+    result
+        .stderr
+        .iter()
+        .filter(|line| detect_port_conflict(std::slice::from_ref(*line)).is_some())
+        .collect()
+}
+
+/// The patterns of each signature, in `SIGNATURES` order
+fn compiled_signatures() -> &'static [Vec<Regex>] {
+    static COMPILED: OnceLock<Vec<Vec<Regex>>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        SIGNATURES
+            .iter()
+            .map(|signature| {
+                signature
+                    .patterns
+                    .iter()
+                    .map(|pattern| {
+                        Regex::new(&format!("(?i){}", pattern))
+                            .expect("valid failure signature pattern")
+                    })
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RunMode, RunSpec};
+
+    fn failed_run(stderr: &[&str], exit_code: i32) -> RunResult {
+        let spec = RunSpec::new("spec".to_string(), RunMode::Run, vec!["start".to_string()]);
+        RunResult {
+            stderr: stderr.iter().map(|line| line.to_string()).collect(),
+            exit_code: Some(exit_code),
+            status: RunStatus::Failed,
+            ..RunResult::new(spec, "run_1".to_string())
+        }
+    }
+
+    fn suggestion_ids(diagnosis: &RunFailureDiagnosis) -> Vec<&str> {
+        diagnosis
+            .suggestions
+            .iter()
+            .map(|suggestion| suggestion.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_signatures_compile() {
+        assert_eq!(compiled_signatures().len(), SIGNATURES.len());
+    }
+
+    #[test]
+    fn test_diagnose_port_in_use() {
+        let diagnosis = diagnose(&failed_run(
+            &["Error: listen EADDRINUSE: address already in use :::3000"],
+            1,
+        ));
+        assert_eq!(suggestion_ids(&diagnosis), vec!["port_in_use"]);
+        let suggestion = &diagnosis.suggestions[0];
+        assert!(suggestion.remediation[0].contains("port 3000"));
+        assert_eq!(suggestion.evidence.len(), 1);
+    }
+
+    #[test]
+    fn test_diagnose_ranks_by_evidence() {
+        let diagnosis = diagnose(&failed_run(
+            &[
+                "gyp ERR! build error",
+                "gyp ERR! stack Error: `make` failed with exit code: 2",
+                "npm ERR! better-sqlite3@9.4.0 install: `prebuild-install || node-gyp rebuild`",
+            ],
+            1,
+        ));
+        assert_eq!(
+            suggestion_ids(&diagnosis),
+            vec!["node_gyp", "better_sqlite3_build"]
+        );
+        assert_eq!(diagnosis.suggestions[0].confidence, FailureConfidence::High);
+    }
+
+    #[test]
+    fn test_diagnose_missing_openai_key_redacts_evidence() {
+        let diagnosis = diagnose(&failed_run(
+            &["Error: Incorrect API key provided: sk-proj-abcdefghijklmnopqrstuvwx"],
+            1,
+        ));
+        assert_eq!(suggestion_ids(&diagnosis), vec!["missing_openai_key"]);
+        assert!(!diagnosis.suggestions[0].evidence[0].contains("abcdefghijklmnop"));
+    }
+
+    #[test]
+    fn test_diagnose_exit_code_only() {
+        let diagnosis = diagnose(&failed_run(&[], 137));
+        assert_eq!(suggestion_ids(&diagnosis), vec!["out_of_memory"]);
+        assert_eq!(diagnosis.suggestions[0].confidence, FailureConfidence::Low);
+
+        assert!(diagnose(&failed_run(&["something else"], 1))
+            .suggestions
+            .is_empty());
+    }
+}
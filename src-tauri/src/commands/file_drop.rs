@@ -0,0 +1,129 @@
+//! File drops
+//! Paths dropped onto the window are handled in the backend: character JSON files are
+//! validated and imported, character package zips are unpacked, and each path's outcome
+//! is emitted as a `file-drop-result` event
+
+use crate::commands::characters::{import_character, read_character_file};
+use crate::commands::packages::import_package_file;
+use crate::commands::stats::emit_event;
+use crate::models::{AppError, DroppedItemKind, FileDropResult, ManagedCharacter};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Handle paths dropped onto a window off the event loop
+pub fn handle_file_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        for path in paths {
+            let result = handle_dropped_path(&app, &path);
+            emit_event(&app, "file-drop-result", result);
+        }
+    });
+}
+
+fn handle_dropped_path(app: &AppHandle, path: &Path) -> FileDropResult {
+    let kind = dropped_item_kind(path);
+    log::info!("Handling dropped {:?}: {}", kind, path.display());
+
+    let imported = match kind {
+        DroppedItemKind::Character => {
+            read_character_file(path).and_then(|character| import_character(app, &character))
+        }
+        DroppedItemKind::Package => import_package_file(app, path),
+        // Nothing in the app keeps a list of project folders to register one with
+        DroppedItemKind::Folder => Err(AppError::Config(
+            "Dropped folders are not supported; choose the folder as a run's working directory"
+                .to_string(),
+        )),
+        DroppedItemKind::Unsupported => Err(AppError::Config(
+            "Only character .json files and .zip packages can be dropped".to_string(),
+        )),
+    };
+    drop_result(path, kind, imported)
+}
+
+fn drop_result(
+    path: &Path,
+    kind: DroppedItemKind,
+    imported: Result<ManagedCharacter, AppError>,
+) -> FileDropResult {
+    let path = path.to_string_lossy().to_string();
+    match imported {
+        Ok(character) => {
+            log::info!("Imported dropped {} as {}", path, character.id);
+            FileDropResult {
+                path,
+                kind,
+                success: true,
+                character: Some(character),
+                error_code: None,
+                error: None,
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to handle dropped {}: {}", path, e);
+            FileDropResult {
+                path,
+                kind,
+                success: false,
+                character: None,
+                error_code: Some(e.error_code().to_string()),
+                error: Some(e.localized_message()),
+            }
+        }
+    }
+}
+
+fn dropped_item_kind(path: &Path) -> DroppedItemKind {
+    if path.is_dir() {
+        return DroppedItemKind::Folder;
+    }
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("json") => DroppedItemKind::Character,
+        Some("zip") => DroppedItemKind::Package,
+        _ => DroppedItemKind::Unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_item_kind() {
+        let dir = std::env::temp_dir().join(format!("file-drop-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(dropped_item_kind(&dir), DroppedItemKind::Folder);
+        assert_eq!(
+            dropped_item_kind(&dir.join("ada.json")),
+            DroppedItemKind::Character
+        );
+        assert_eq!(
+            dropped_item_kind(&dir.join("ada.ZIP")),
+            DroppedItemKind::Package
+        );
+        assert_eq!(
+            dropped_item_kind(&dir.join("notes.txt")),
+            DroppedItemKind::Unsupported
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_drop_result() {
+        let result = drop_result(
+            Path::new("/tmp/notes.txt"),
+            DroppedItemKind::Unsupported,
+            Err(AppError::Config("unsupported".to_string())),
+        );
+        assert!(!result.success);
+        assert_eq!(result.error_code.as_deref(), Some("CONFIG_ERROR"));
+        assert!(result.character.is_none());
+    }
+}
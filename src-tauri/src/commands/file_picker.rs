@@ -0,0 +1,179 @@
+//! Native character file picker
+//! Opens the platform file dialog (`osascript`, `zenity`/`kdialog` or a PowerShell
+//! `OpenFileDialog`), validates the chosen character and optionally imports it, so the
+//! frontend never handles a path to a file that failed validation
+
+use crate::commands::characters::{character_name, import_character, read_character_file};
+use crate::models::{ApiResponse, AppError, CharacterFilePick};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tokio::process::Command;
+
+const DIALOG_TITLE: &str = "Choose a character file";
+
+/// Let the user choose a character JSON file and validate it
+///
+/// With `import` (the default) the character is also copied into the managed characters
+/// directory under a new ID. Returns `None` when the dialog is cancelled.
+#[tauri::command]
+pub async fn pick_character_file(
+    app: AppHandle,
+    import: Option<bool>,
+) -> Result<ApiResponse<Option<CharacterFilePick>>, String> {
+    let result = async {
+        let Some(path) = open_file_dialog().await? else {
+            return Ok(None);
+        };
+        log::info!("Character file chosen: {}", path.display());
+
+        let character = read_character_file(&path)?;
+        let managed = if import.unwrap_or(true) {
+            Some(import_character(&app, &character)?)
+        } else {
+            None
+        };
+        Ok::<_, AppError>(Some(CharacterFilePick {
+            path: path.to_string_lossy().to_string(),
+            name: character_name(&character).unwrap_or_default().to_string(),
+            character: managed,
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(pick) => {
+            if let Some(managed) = pick.as_ref().and_then(|p| p.character.as_ref()) {
+                log::info!("Imported character file as {}", managed.id);
+            }
+            Ok(ApiResponse::success(pick))
+        }
+        Err(e) => {
+            log::error!("Failed to pick character file: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to pick character file: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Run the first available dialog; `None` when the user cancels
+async fn open_file_dialog() -> Result<Option<PathBuf>, AppError> {
+    for mut command in dialog_commands() {
+        let output = match command.output().await {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(AppError::Process(format!(
+                    "Failed to open file dialog: {}",
+                    e
+                )))
+            }
+        };
+
+        // Every supported dialog exits with 1 when cancelled
+        if output.status.code() == Some(1) {
+            return Ok(None);
+        }
+        if !output.status.success() {
+            return Err(AppError::Process(format!(
+                "File dialog failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return Ok(parse_dialog_output(&output.stdout));
+    }
+
+    Err(AppError::EnvironmentError(
+        "No native file dialog is available on this system".to_string(),
+    ))
+}
+
+/// The chosen path from a dialog's stdout; empty output means nothing was chosen
+fn parse_dialog_output(stdout: &[u8]) -> Option<PathBuf> {
+    let text = String::from_utf8_lossy(stdout);
+    let path = text.trim_end_matches(['\r', '\n']);
+    if path.trim().is_empty() {
+        None
+    } else {
+        Some(Path::new(path).to_path_buf())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn dialog_commands() -> Vec<Command> {
+    let mut command = Command::new("osascript");
+    command.args([
+        "-e",
+        &format!(
+            "POSIX path of (choose file with prompt \"{}\" of type {{\"public.json\"}})",
+            DIALOG_TITLE
+        ),
+    ]);
+    vec![command]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn dialog_commands() -> Vec<Command> {
+    let mut zenity = Command::new("zenity");
+    zenity.args([
+        "--file-selection",
+        &format!("--title={}", DIALOG_TITLE),
+        "--file-filter=Character JSON | *.json",
+    ]);
+
+    let mut kdialog = Command::new("kdialog");
+    kdialog.args([
+        "--title",
+        DIALOG_TITLE,
+        "--getopenfilename",
+        ".",
+        "Character JSON (*.json)",
+    ]);
+    vec![zenity, kdialog]
+}
+
+#[cfg(windows)]
+fn dialog_commands() -> Vec<Command> {
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // WinForms dialogs need a single-threaded apartment
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $d = New-Object System.Windows.Forms.OpenFileDialog; \
+         $d.Title = '{}'; \
+         $d.Filter = 'Character JSON (*.json)|*.json'; \
+         if ($d.ShowDialog() -eq 'OK') {{ [Console]::Out.Write($d.FileName) }}",
+        DIALOG_TITLE
+    );
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-STA", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW);
+    vec![command]
+}
+
+#[cfg(not(any(unix, windows)))]
+fn dialog_commands() -> Vec<Command> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dialog_output() {
+        assert_eq!(
+            parse_dialog_output(b"/home/ada/characters/ada.json\n"),
+            Some(PathBuf::from("/home/ada/characters/ada.json"))
+        );
+        assert_eq!(
+            parse_dialog_output(b"C:\\Users\\Ada\\ada.json\r\n"),
+            Some(PathBuf::from("C:\\Users\\Ada\\ada.json"))
+        );
+        assert_eq!(parse_dialog_output(b""), None);
+        assert_eq!(parse_dialog_output(b"\n"), None);
+    }
+}
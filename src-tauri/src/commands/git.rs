@@ -0,0 +1,397 @@
+//! Git integration
+//! Status, commits and per-file diffs for project directories, by running `git` and
+//! parsing its porcelain output, so edits made in the app can be committed from it
+
+use crate::commands::path_jail::check_path_allowed;
+use crate::models::{
+    ApiResponse, AppError, GitCommitResult, GitDiffHunk, GitDiffLine, GitDiffLineKind, GitFileDiff,
+    GitFileStatus, GitStatus,
+};
+use crate::path_env::spawn_path_for_app;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Output;
+use tauri::AppHandle;
+use tokio::process::Command;
+
+const MAX_MESSAGE_LEN: usize = 10_000;
+
+/// Branch, upstream and changed files of the repository containing `project_dir`
+#[tauri::command]
+pub async fn get_project_git_status(
+    app: AppHandle,
+    project_dir: String,
+) -> Result<ApiResponse<GitStatus>, String> {
+    let result = async {
+        let dir = Path::new(&project_dir);
+        check_path_allowed(&app, dir, "Project directory")?;
+        project_status(&app, dir).await
+    }
+    .await;
+
+    match result {
+        Ok(status) => Ok(ApiResponse::success(status)),
+        Err(e) => Ok(error_response("Failed to get git status", e)),
+    }
+}
+
+/// Stage every change under `project_dir` and commit it
+#[tauri::command]
+pub async fn git_commit_project(
+    app: AppHandle,
+    project_dir: String,
+    message: String,
+) -> Result<ApiResponse<GitCommitResult>, String> {
+    let result = async {
+        let dir = Path::new(&project_dir);
+        check_path_allowed(&app, dir, "Project directory")?;
+        let message = message.trim();
+        if message.is_empty() || message.len() > MAX_MESSAGE_LEN {
+            return Err(AppError::Config(format!(
+                "Commit message must be 1-{} characters",
+                MAX_MESSAGE_LEN
+            )));
+        }
+
+        // Only the project directory is staged and committed, even inside a larger repository
+        git(&app, dir, &["add", "--all", "--", "."]).await?;
+        let staged = git(
+            &app,
+            dir,
+            &["diff", "--cached", "--name-only", "-z", "--", "."],
+        )
+        .await?;
+        let files_changed = split_nul(&staged.stdout).count();
+        if files_changed == 0 {
+            return Err(AppError::Config("Nothing to commit".to_string()));
+        }
+        git(&app, dir, &["commit", "--quiet", "-m", message, "--", "."]).await?;
+
+        let commit = git(&app, dir, &["rev-parse", "HEAD"]).await?;
+        let status = project_status(&app, dir).await?;
+        Ok::<_, AppError>(GitCommitResult {
+            commit: String::from_utf8_lossy(&commit.stdout).trim().to_string(),
+            branch: status.branch,
+            files_changed,
+        })
+    }
+    .await;
+
+    match result {
+        Ok(commit) => {
+            log::info!(
+                "Committed {} file(s) in {} as {}",
+                commit.files_changed,
+                project_dir,
+                commit.commit
+            );
+            Ok(ApiResponse::success(commit))
+        }
+        Err(e) => Ok(error_response("Failed to commit project", e)),
+    }
+}
+
+/// Uncommitted changes to a file, staged or not, against `HEAD`
+#[tauri::command]
+pub async fn git_diff_file(
+    app: AppHandle,
+    path: String,
+) -> Result<ApiResponse<GitFileDiff>, String> {
+    let result = async {
+        let file = Path::new(&path);
+        check_path_allowed(&app, file, "File")?;
+        let (Some(dir), Some(name)) = (file.parent(), file.file_name().and_then(|n| n.to_str()))
+        else {
+            return Err(AppError::Config(format!("'{}' is not a file path", path)));
+        };
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+
+        let untracked = file.is_file()
+            && !run_git(&app, dir, &["ls-files", "--error-unmatch", "--", name])
+                .await?
+                .status
+                .success();
+        let diff = if untracked {
+            // Exits with 1 when the files differ, which they always do here
+            let output =
+                run_git(&app, dir, &["diff", "--no-index", "--", "/dev/null", name]).await?;
+            if output.status.code() != Some(1) {
+                return Err(git_error("diff", &output));
+            }
+            output
+        } else {
+            git(&app, dir, &["diff", "HEAD", "--", name]).await?
+        };
+
+        let mut file_diff = parse_diff(&String::from_utf8_lossy(&diff.stdout));
+        file_diff.path = path.clone();
+        file_diff.untracked = untracked;
+        Ok::<_, AppError>(file_diff)
+    }
+    .await;
+
+    match result {
+        Ok(diff) => Ok(ApiResponse::success(diff)),
+        Err(e) => Ok(error_response("Failed to diff file", e)),
+    }
+}
+
+async fn project_status(app: &AppHandle, dir: &Path) -> Result<GitStatus, AppError> {
+    let root = git(app, dir, &["rev-parse", "--show-toplevel"]).await?;
+    let status = git(
+        app,
+        dir,
+        &[
+            "status",
+            "--porcelain=v1",
+            "--branch",
+            "-z",
+            "--untracked-files=all",
+        ],
+    )
+    .await?;
+    Ok(parse_status(
+        String::from_utf8_lossy(&root.stdout).trim(),
+        &status.stdout,
+    ))
+}
+
+/// Run git and fail unless it exits successfully
+async fn git(app: &AppHandle, dir: &Path, args: &[&str]) -> Result<Output, AppError> {
+    let output = run_git(app, dir, args).await?;
+    if !output.status.success() {
+        return Err(git_error(args.first().copied().unwrap_or("git"), &output));
+    }
+    Ok(output)
+}
+
+async fn run_git(app: &AppHandle, dir: &Path, args: &[&str]) -> Result<Output, AppError> {
+    Command::new("git")
+        .args(["-c", "core.quotepath=false"])
+        .args(args)
+        .current_dir(dir)
+        .env("PATH", spawn_path_for_app(app).await)
+        // Never wait on a credential or editor prompt nobody can answer
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_EDITOR", "true")
+        .output()
+        .await
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                AppError::EnvironmentError("git is not installed or not on PATH".to_string())
+            }
+            _ => AppError::Process(format!("Failed to run git: {}", e)),
+        })
+}
+
+fn git_error(command: &str, output: &Output) -> AppError {
+    AppError::Process(format!(
+        "git {} failed: {}",
+        command,
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+fn split_nul(output: &[u8]) -> impl Iterator<Item = String> + '_ {
+    output
+        .split(|b| *b == 0)
+        .filter(|record| !record.is_empty())
+        .map(|record| String::from_utf8_lossy(record).to_string())
+}
+
+/// Parse `git status --porcelain=v1 --branch -z`
+fn parse_status(root: &str, output: &[u8]) -> GitStatus {
+    let mut status = GitStatus {
+        root: root.to_string(),
+        branch: None,
+        upstream: None,
+        ahead: 0,
+        behind: 0,
+        files: Vec::new(),
+    };
+
+    let mut records = split_nul(output);
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+        if record.len() < 4 || !record.is_char_boundary(3) {
+            continue;
+        }
+        let (code, path) = record.split_at(3);
+        let staged = code[..1].to_string();
+        // Renames and copies are followed by a record holding the original path
+        let original_path = if matches!(staged.as_str(), "R" | "C") {
+            records.next()
+        } else {
+            None
+        };
+        status.files.push(GitFileStatus {
+            path: path.to_string(),
+            original_path,
+            staged,
+            unstaged: code[1..2].to_string(),
+        });
+    }
+    status
+}
+
+/// `main...origin/main [ahead 1, behind 2]`, `No commits yet on main` or `HEAD (no branch)`
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    let (names, tracking) = match header.split_once(" [") {
+        Some((names, tracking)) => (names, tracking.trim_end_matches(']')),
+        None => (header, ""),
+    };
+    for part in tracking.split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
+
+    let names = names
+        .strip_prefix("No commits yet on ")
+        .or_else(|| names.strip_prefix("Initial commit on "))
+        .unwrap_or(names);
+    if names.starts_with("HEAD (no branch)") {
+        return;
+    }
+    match names.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(names.to_string()),
+    }
+}
+
+/// Parse a single-file unified diff into hunks
+fn parse_diff(diff: &str) -> GitFileDiff {
+    let mut file_diff = GitFileDiff {
+        path: String::new(),
+        untracked: false,
+        binary: false,
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+    };
+
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            file_diff.hunks.push(GitDiffHunk {
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = file_diff.hunks.last_mut() else {
+            // File headers before the first hunk
+            if line.starts_with("Binary files ") {
+                file_diff.binary = true;
+            }
+            continue;
+        };
+
+        let kind = match line.as_bytes().first() {
+            Some(b'+') => {
+                file_diff.additions += 1;
+                GitDiffLineKind::Added
+            }
+            Some(b'-') => {
+                file_diff.deletions += 1;
+                GitDiffLineKind::Removed
+            }
+            // `\ No newline at end of file`
+            Some(b'\\') => continue,
+            _ => GitDiffLineKind::Context,
+        };
+        // The marker is a single ASCII byte; blank context lines may have lost theirs
+        let content = line.get(1..).unwrap_or_default();
+        hunk.lines.push(GitDiffLine {
+            kind,
+            content: content.to_string(),
+        });
+    }
+    file_diff
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let output = b"## main...origin/main [ahead 2, behind 1]\0 M character.json\0R  new.json\0old.json\0?? notes/todo.md\0";
+        let status = parse_status("/work/agent", output);
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(status.files.len(), 3);
+        assert_eq!(status.files[0].path, "character.json");
+        assert_eq!(
+            (
+                status.files[0].staged.as_str(),
+                status.files[0].unstaged.as_str()
+            ),
+            (" ", "M")
+        );
+        assert_eq!(status.files[1].path, "new.json");
+        assert_eq!(status.files[1].original_path.as_deref(), Some("old.json"));
+        assert_eq!(status.files[2].staged, "?");
+    }
+
+    #[test]
+    fn test_parse_branch_header() {
+        let status = parse_status("/work", b"## No commits yet on main\0");
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert!(status.upstream.is_none());
+
+        let status = parse_status("/work", b"## HEAD (no branch)\0");
+        assert!(status.branch.is_none());
+    }
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "diff --git a/character.json b/character.json\n\
+                    index 1111111..2222222 100644\n\
+                    --- a/character.json\n\
+                    +++ b/character.json\n\
+                    @@ -1,3 +1,3 @@\n\
+                    \x20{\n\
+                    -  \"name\": \"Ada\"\n\
+                    +  \"name\": \"Ada Lovelace\"\n\
+                    \x20}\n\
+                    \\ No newline at end of file\n";
+        let parsed = parse_diff(diff);
+
+        assert!(!parsed.binary);
+        assert_eq!((parsed.additions, parsed.deletions), (1, 1));
+        assert_eq!(parsed.hunks.len(), 1);
+        assert_eq!(parsed.hunks[0].header, "@@ -1,3 +1,3 @@");
+        assert_eq!(parsed.hunks[0].lines.len(), 4);
+        assert_eq!(parsed.hunks[0].lines[1].kind, GitDiffLineKind::Removed);
+        assert_eq!(
+            parsed.hunks[0].lines[2].content,
+            "  \"name\": \"Ada Lovelace\""
+        );
+
+        let binary = parse_diff("diff --git a/avatar.png b/avatar.png\nBinary files a/avatar.png and b/avatar.png differ\n");
+        assert!(binary.binary);
+        assert!(binary.hunks.is_empty());
+    }
+}
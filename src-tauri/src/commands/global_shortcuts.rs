@@ -0,0 +1,323 @@
+//! Global keyboard shortcuts
+//! System-wide accelerators bound to app actions (stop all runs, open a window, toggle the
+//! main window) through the global-shortcut plugin. Bindings are kept in the key-value store
+//! under the `global_shortcuts` namespace and registered again on launch
+
+use crate::commands::kv::{load_value, update_value};
+use crate::commands::process::{running_run_ids, stop_eliza_run};
+use crate::commands::stats::emit_event;
+use crate::commands::windows::{open_window_kind, MAIN_WINDOW_LABEL};
+use crate::models::{
+    ApiResponse, AppError, GlobalShortcutAction, GlobalShortcutTriggered, ShortcutBinding,
+    WindowKind,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const SHORTCUTS_NAMESPACE: &str = "global_shortcuts";
+const BINDINGS_KEY: &str = "bindings";
+
+type ShortcutBindings = HashMap<GlobalShortcutAction, String>;
+
+// The plugin calls its handler on the event loop, so registrations sit behind a blocking mutex
+pub type GlobalShortcutState = Arc<Mutex<HashMap<GlobalShortcutAction, Shortcut>>>;
+
+/// Initialize the registered shortcut table (called from main)
+pub fn init_global_shortcuts() -> GlobalShortcutState {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Every action with its accelerator and whether the OS accepted the registration
+#[tauri::command]
+pub async fn list_shortcuts(app: AppHandle) -> Result<ApiResponse<Vec<ShortcutBinding>>, String> {
+    match read_bindings(&app).await {
+        Ok(bindings) => Ok(ApiResponse::success(
+            GlobalShortcutAction::ALL
+                .into_iter()
+                .map(|action| binding(&app, action, bindings.get(&action).cloned()))
+                .collect(),
+        )),
+        Err(e) => Ok(error_response("Failed to list shortcuts", e)),
+    }
+}
+
+/// Bind an action to an accelerator such as `CmdOrCtrl+Shift+T`; an empty or missing
+/// accelerator removes the binding
+#[tauri::command]
+pub async fn set_shortcut(
+    app: AppHandle,
+    action: GlobalShortcutAction,
+    accelerator: Option<String>,
+) -> Result<ApiResponse<ShortcutBinding>, String> {
+    let accelerator = accelerator
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+
+    let result = async {
+        let shortcut = accelerator.as_deref().map(parse_accelerator).transpose()?;
+        if let Some(ref shortcut) = shortcut {
+            if let Some(other) = bound_action(&app, shortcut).filter(|other| *other != action) {
+                return Err(AppError::Config(format!(
+                    "{} is already bound to {}",
+                    accelerator.as_deref().unwrap_or_default(),
+                    other.name()
+                )));
+            }
+        }
+
+        // Register first so a shortcut taken by another app leaves the old binding alone
+        let previous = registered(&app).get(&action).copied();
+        if shortcut != previous {
+            match shortcut {
+                Some(shortcut) => register_action(&app, action, shortcut)?,
+                None => {
+                    registered(&app).remove(&action);
+                }
+            }
+            if let Some(previous) = previous {
+                unregister_shortcut(&app, action, previous);
+            }
+        }
+
+        let stored = accelerator.clone();
+        update_value(
+            &app,
+            SHORTCUTS_NAMESPACE.to_string(),
+            BINDINGS_KEY.to_string(),
+            move |current| {
+                let mut bindings = parse_bindings(current)?;
+                match stored {
+                    Some(accelerator) => bindings.insert(action, accelerator),
+                    None => bindings.remove(&action),
+                };
+                Ok(serde_json::to_value(bindings)?)
+            },
+        )
+        .await?;
+
+        Ok::<_, AppError>(binding(&app, action, accelerator.clone()))
+    }
+    .await;
+
+    match result {
+        Ok(binding) => {
+            log::info!(
+                "Shortcut for {} set to {}",
+                action.name(),
+                binding.accelerator.as_deref().unwrap_or("nothing")
+            );
+            Ok(ApiResponse::success(binding))
+        }
+        Err(e) => Ok(error_response("Failed to set shortcut", e)),
+    }
+}
+
+/// Register the saved bindings; ones the OS refuses are logged and left unregistered
+pub fn spawn_shortcut_registration(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let bindings = match read_bindings(&app).await {
+            Ok(bindings) => bindings,
+            Err(e) => {
+                log::warn!("Failed to read saved shortcuts: {}", e);
+                return;
+            }
+        };
+
+        for (action, accelerator) in bindings {
+            let registered = parse_accelerator(&accelerator)
+                .and_then(|shortcut| register_action(&app, action, shortcut));
+            if let Err(e) = registered {
+                log::warn!(
+                    "Failed to register {} for {}: {}",
+                    accelerator,
+                    action.name(),
+                    e
+                );
+            }
+        }
+    });
+}
+
+/// Plugin handler: run the action bound to a pressed shortcut
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let Some(action) = bound_action(app, shortcut) else {
+        return;
+    };
+
+    log::info!("Global shortcut triggered: {}", action.name());
+    emit_event(app, "global-shortcut", GlobalShortcutTriggered { action });
+
+    let app = app.clone();
+    match action {
+        GlobalShortcutAction::StopAllRuns => {
+            tauri::async_runtime::spawn(async move { stop_all_runs(&app).await });
+        }
+        GlobalShortcutAction::OpenTerminalWindow => open_window_later(app, WindowKind::Terminal),
+        GlobalShortcutAction::OpenLogViewer => open_window_later(app, WindowKind::LogViewer),
+        GlobalShortcutAction::ToggleMainWindow => toggle_main_window(&app),
+    }
+}
+
+async fn stop_all_runs(app: &AppHandle) {
+    for run_id in running_run_ids(app).await {
+        match stop_eliza_run(app.clone(), run_id.clone()).await {
+            Ok(response) if response.success => {}
+            Ok(response) => log::warn!(
+                "Failed to stop run {}: {}",
+                run_id,
+                response.error.map(|e| e.message).unwrap_or_default()
+            ),
+            Err(e) => log::warn!("Failed to stop run {}: {}", run_id, e),
+        }
+    }
+}
+
+/// Windows are built off the event loop, which the builder waits on
+fn open_window_later(app: AppHandle, kind: WindowKind) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = open_window_kind(&app, kind) {
+            log::warn!("Failed to open {} window: {}", kind.label(), e);
+        }
+    });
+}
+
+/// Hide the main window when it is in front, otherwise bring it back
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+
+    let in_front = window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false);
+    let result = if in_front {
+        window.hide()
+    } else {
+        window
+            .show()
+            .and_then(|_| window.unminimize())
+            .and_then(|_| window.set_focus())
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to toggle the main window: {}", e);
+    }
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, AppError> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| AppError::Config(format!("Invalid accelerator '{}': {}", accelerator, e)))
+}
+
+fn register_action(
+    app: &AppHandle,
+    action: GlobalShortcutAction,
+    shortcut: Shortcut,
+) -> Result<(), AppError> {
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| AppError::Config(format!("The system refused the shortcut: {}", e)))?;
+    registered(app).insert(action, shortcut);
+    Ok(())
+}
+
+fn unregister_shortcut(app: &AppHandle, action: GlobalShortcutAction, shortcut: Shortcut) {
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        log::warn!("Failed to unregister shortcut for {}: {}", action.name(), e);
+    }
+}
+
+fn bound_action(app: &AppHandle, shortcut: &Shortcut) -> Option<GlobalShortcutAction> {
+    registered(app)
+        .iter()
+        .find(|(_, registered)| *registered == shortcut)
+        .map(|(action, _)| *action)
+}
+
+fn registered(
+    app: &AppHandle,
+) -> std::sync::MutexGuard<'_, HashMap<GlobalShortcutAction, Shortcut>> {
+    app.state::<GlobalShortcutState>()
+        .inner()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn binding(
+    app: &AppHandle,
+    action: GlobalShortcutAction,
+    accelerator: Option<String>,
+) -> ShortcutBinding {
+    ShortcutBinding {
+        action,
+        registered: registered(app).contains_key(&action),
+        accelerator,
+    }
+}
+
+async fn read_bindings(app: &AppHandle) -> Result<ShortcutBindings, AppError> {
+    parse_bindings(load_value(app, SHORTCUTS_NAMESPACE, BINDINGS_KEY).await?)
+}
+
+fn parse_bindings(value: Option<Value>) -> Result<ShortcutBindings, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| AppError::Config(format!("Saved shortcuts are corrupted: {}", e))),
+        None => Ok(ShortcutBindings::new()),
+    }
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_accelerator() {
+        assert!(parse_accelerator("CmdOrCtrl+Shift+T").is_ok());
+        assert!(parse_accelerator("Alt+F12").is_ok());
+        let err = parse_accelerator("Shift+Nope").unwrap_err();
+        assert_eq!(err.error_code(), "CONFIG_ERROR");
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        assert!(parse_bindings(None).unwrap().is_empty());
+
+        let bindings = parse_bindings(Some(json!({
+            "stop_all_runs": "CmdOrCtrl+Shift+.",
+            "toggle_main_window": "Alt+Space",
+        })))
+        .unwrap();
+        assert_eq!(
+            bindings
+                .get(&GlobalShortcutAction::StopAllRuns)
+                .map(String::as_str),
+            Some("CmdOrCtrl+Shift+.")
+        );
+        assert_eq!(bindings.len(), 2);
+
+        assert!(parse_bindings(Some(json!({ "launch_rockets": "F1" }))).is_err());
+    }
+
+    #[test]
+    fn test_action_names() {
+        for action in GlobalShortcutAction::ALL {
+            assert_eq!(serde_json::to_value(action).unwrap(), json!(action.name()));
+        }
+    }
+}
@@ -0,0 +1,383 @@
+//! Multi-agent run groups
+//! Launches several agents as one group with per-agent ports and group-level control
+
+use crate::commands::args::{custom_allowlist, validated_mode_args};
+use crate::commands::config::saved_config;
+use crate::commands::ports::{release_agent_ports, reserve_agent_port};
+use crate::commands::process::{
+    audit_run, describe_run_for_audit, execute_eliza_run_streaming_with_id, kill_eliza_run,
+    stop_eliza_run,
+};
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, AuditAction, RunGroup, RunGroupMember, RunMode, RunSpec, RunStatus, SandboxConfig,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, RwLock};
+
+/// Finished groups kept for status queries; older ones are dropped as new groups start
+const MAX_FINISHED_GROUPS: usize = 20;
+
+// Registry of run groups by group ID
+pub type RunGroupRegistry = Arc<RwLock<HashMap<String, Arc<Mutex<RunGroup>>>>>;
+
+/// Initialize the run group registry (called from main)
+pub fn init_run_group_registry() -> RunGroupRegistry {
+    Arc::new(RwLock::new(HashMap::new()))
+}
+
+/// Get the run group registry for the app
+pub fn get_run_group_registry(app: &AppHandle) -> RunGroupRegistry {
+    app.state::<RunGroupRegistry>().inner().clone()
+}
+
+/// Start several agent runs as a coordinated group
+#[tauri::command]
+pub async fn start_run_group(
+    app: AppHandle,
+    specs: Vec<RunSpec>,
+    config: SandboxConfig,
+) -> Result<ApiResponse<RunGroup>, String> {
+    log::info!("Starting run group with {} agents", specs.len());
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    if specs.is_empty() {
+        return Ok(ApiResponse::error(
+            "EMPTY_GROUP".to_string(),
+            "A run group needs at least one run spec".to_string(),
+        ));
+    }
+
+    let allowed = match saved_config(&app) {
+        Ok(saved) => custom_allowlist(&saved, &config),
+        Err(e) => {
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.to_string(),
+            ))
+        }
+    };
+    for spec in &specs {
+        if !matches!(spec.mode, RunMode::Run) {
+            return Ok(ApiResponse::error(
+                "INVALID_GROUP".to_string(),
+                format!("Run groups only support agent runs, got {} mode", spec.mode),
+            ));
+        }
+        if let Err(e) = validated_mode_args(spec, &allowed) {
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.to_string(),
+            ));
+        }
+    }
+
+    let group_id = generate_group_id();
+    let mut members = Vec::new();
+    let mut prepared: Vec<(String, RunSpec)> = Vec::new();
+
+    // Assign every agent its own port before anything is spawned
+    for mut spec in specs {
+        let run_id = crate::models::generate_safe_run_id();
+        let port = match reserve_agent_port(&app, &run_id).await {
+            Ok(port) => port,
+            Err(e) => {
+                for (reserved_run_id, _) in &prepared {
+                    release_agent_ports(&app, reserved_run_id).await;
+                }
+                log::error!("Failed to allocate port for run group: {}", e);
+                return Ok(ApiResponse::error(
+                    e.error_code().to_string(),
+                    e.to_string(),
+                ));
+            }
+        };
+        spec.port = Some(port);
+
+        members.push(RunGroupMember {
+            run_id: run_id.clone(),
+            character_file: member_character(&spec),
+            port: Some(port),
+            status: RunStatus::Running,
+            exit_code: None,
+        });
+        prepared.push((run_id, spec));
+    }
+
+    let group = RunGroup::new(group_id.clone(), members);
+    {
+        let registry = get_run_group_registry(&app);
+        let mut groups = registry.write().await;
+        prune_finished_groups(&mut groups).await;
+        groups.insert(group_id.clone(), Arc::new(Mutex::new(group.clone())));
+    }
+    emit_group_status(&app, &group);
+
+    for (run_id, spec) in prepared {
+        let detail = format!("group {}: {}", group_id, describe_run_for_audit(&spec));
+        audit_run(&app, AuditAction::RunStarted, &run_id, true, Some(detail)).await;
+        tokio::spawn(run_group_member(
+            app.clone(),
+            group_id.clone(),
+            run_id,
+            spec,
+            config.clone(),
+        ));
+    }
+
+    log::info!("Started run group: {}", group_id);
+    Ok(ApiResponse::success(group))
+}
+
+/// Gracefully stop every running agent in a group
+#[tauri::command]
+pub async fn stop_run_group(
+    app: AppHandle,
+    group_id: String,
+) -> Result<ApiResponse<RunGroup>, String> {
+    log::info!("Stopping run group: {}", group_id);
+    control_group(&app, &group_id, false).await
+}
+
+/// Force kill every running agent in a group
+#[tauri::command]
+pub async fn kill_run_group(
+    app: AppHandle,
+    group_id: String,
+) -> Result<ApiResponse<RunGroup>, String> {
+    log::info!("Killing run group: {}", group_id);
+    control_group(&app, &group_id, true).await
+}
+
+/// Get the current status of a run group
+#[tauri::command]
+pub async fn get_run_group_status(
+    app: AppHandle,
+    group_id: String,
+) -> Result<ApiResponse<RunGroup>, String> {
+    let registry = get_run_group_registry(&app);
+    let guard = registry.read().await;
+
+    match guard.get(&group_id) {
+        Some(group) => Ok(ApiResponse::success(group.lock().await.clone())),
+        None => Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Run group {} not found", group_id),
+        )),
+    }
+}
+
+/// Run one group member to completion, then record its outcome
+async fn run_group_member(
+    app: AppHandle,
+    group_id: String,
+    run_id: String,
+    spec: RunSpec,
+    config: SandboxConfig,
+) {
+    let result =
+        execute_eliza_run_streaming_with_id(app.clone(), spec, config, run_id.clone()).await;
+    release_agent_ports(&app, &run_id).await;
+
+    let (status, exit_code) = match result {
+        Ok(run_result) => (run_result.status, run_result.exit_code),
+        Err(e) => {
+            log::error!("Run group member {} failed to start: {}", run_id, e);
+            (RunStatus::Failed, None)
+        }
+    };
+
+    update_member(&app, &group_id, &run_id, status, exit_code).await;
+}
+
+/// Stop or kill the running members of a group
+async fn control_group(
+    app: &AppHandle,
+    group_id: &str,
+    force: bool,
+) -> Result<ApiResponse<RunGroup>, String> {
+    let group_arc = match get_run_group_registry(app).read().await.get(group_id) {
+        Some(group) => group.clone(),
+        None => {
+            return Ok(ApiResponse::error(
+                "NOT_FOUND".to_string(),
+                format!("Run group {} not found", group_id),
+            ))
+        }
+    };
+
+    let running: Vec<String> = group_arc
+        .lock()
+        .await
+        .members
+        .iter()
+        .filter(|member| member.status == RunStatus::Running)
+        .map(|member| member.run_id.clone())
+        .collect();
+
+    for run_id in running {
+        let response = if force {
+            kill_eliza_run(app.clone(), run_id.clone()).await
+        } else {
+            stop_eliza_run(app.clone(), run_id.clone()).await
+        };
+
+        match response {
+            Ok(response) if response.success => {
+                if let Some(run_result) = response.data {
+                    update_member(app, group_id, &run_id, run_result.status, None).await;
+                }
+            }
+            Ok(response) => log::warn!(
+                "Failed to control group member {}: {}",
+                run_id,
+                response.error.map(|e| e.message).unwrap_or_default()
+            ),
+            Err(e) => log::warn!("Failed to control group member {}: {}", run_id, e),
+        }
+    }
+
+    let group = group_arc.lock().await.clone();
+    Ok(ApiResponse::success(group))
+}
+
+/// Update one member's status and broadcast the aggregated group status
+async fn update_member(
+    app: &AppHandle,
+    group_id: &str,
+    run_id: &str,
+    status: RunStatus,
+    exit_code: Option<i32>,
+) {
+    let group_arc = match get_run_group_registry(app).read().await.get(group_id) {
+        Some(group) => group.clone(),
+        None => return,
+    };
+
+    let mut group = group_arc.lock().await;
+    if let Some(member) = group.members.iter_mut().find(|m| m.run_id == run_id) {
+        // A stopped/killed agent exits non-zero; keep it reported as killed
+        if member.status != RunStatus::Killed {
+            member.status = status;
+        }
+        if exit_code.is_some() {
+            member.exit_code = exit_code;
+        }
+    }
+    group.refresh_status();
+    emit_group_status(app, &group);
+}
+
+/// Drop the oldest groups whose members have all finished, keeping `MAX_FINISHED_GROUPS`
+async fn prune_finished_groups(groups: &mut HashMap<String, Arc<Mutex<RunGroup>>>) {
+    let mut finished = Vec::new();
+    for (id, group) in groups.iter() {
+        let group = group.lock().await;
+        if group.status != RunStatus::Running {
+            finished.push((group.started_at.clone(), id.clone()));
+        }
+    }
+    finished.sort();
+
+    let excess = finished.len().saturating_sub(MAX_FINISHED_GROUPS);
+    for (_, id) in finished.into_iter().take(excess) {
+        groups.remove(&id);
+    }
+}
+
+fn emit_group_status(app: &AppHandle, group: &RunGroup) {
+    emit_event(app, "run-group-status", group);
+}
+
+/// Character file for display, including legacy specs that pass it as the first arg
+fn member_character(spec: &RunSpec) -> Option<String> {
+    spec.character_file.clone().or_else(|| {
+        spec.args
+            .first()
+            .filter(|arg| !arg.starts_with('-'))
+            .cloned()
+    })
+}
+
+fn generate_group_id() -> String {
+    format!(
+        "group_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(status: RunStatus) -> RunGroupMember {
+        RunGroupMember {
+            run_id: "run_1".to_string(),
+            character_file: None,
+            port: Some(3000),
+            status,
+            exit_code: None,
+        }
+    }
+
+    #[test]
+    fn test_group_status_aggregation() {
+        let mut group = RunGroup::new(
+            "group_1".to_string(),
+            vec![member(RunStatus::Running), member(RunStatus::Completed)],
+        );
+        group.refresh_status();
+        assert_eq!(group.status, RunStatus::Running);
+
+        group.members[0].status = RunStatus::Failed;
+        group.refresh_status();
+        assert_eq!(group.status, RunStatus::Failed);
+
+        group.members[1].status = RunStatus::Killed;
+        group.refresh_status();
+        assert_eq!(group.status, RunStatus::Killed);
+    }
+
+    #[test]
+    fn test_member_character() {
+        let spec = RunSpec::new(
+            "a".to_string(),
+            RunMode::Run,
+            vec!["eliza.json".to_string()],
+        );
+        assert_eq!(member_character(&spec), Some("eliza.json".to_string()));
+
+        let spec = RunSpec::new("b".to_string(), RunMode::Run, vec!["--port".to_string()]);
+        assert_eq!(member_character(&spec), None);
+    }
+
+    #[tokio::test]
+    async fn test_prune_finished_groups() {
+        let mut groups = HashMap::new();
+        for i in 0..MAX_FINISHED_GROUPS + 3 {
+            let mut group =
+                RunGroup::new(format!("group_{}", i), vec![member(RunStatus::Completed)]);
+            group.started_at = format!("2026-01-01T00:00:{:02}Z", i);
+            group.refresh_status();
+            groups.insert(group.id.clone(), Arc::new(Mutex::new(group)));
+        }
+        let running = RunGroup::new("running".to_string(), vec![member(RunStatus::Running)]);
+        groups.insert(running.id.clone(), Arc::new(Mutex::new(running)));
+
+        prune_finished_groups(&mut groups).await;
+        assert_eq!(groups.len(), MAX_FINISHED_GROUPS + 1);
+        assert!(groups.contains_key("running"));
+        assert!(!groups.contains_key("group_0"));
+        assert!(!groups.contains_key("group_2"));
+        assert!(groups.contains_key("group_3"));
+    }
+}
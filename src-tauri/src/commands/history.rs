@@ -0,0 +1,260 @@
+//! Run history
+//! Finished runs persisted under the app data directory, one directory per run holding the
+//! final result alongside the user's notes and line annotations
+
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, RunAnnotation, RunNotes, RunRecord, RunResult,
+};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+pub(crate) const RUNS_DIR: &str = "runs";
+pub(crate) const RESULT_FILE: &str = "result.json";
+const NOTES_FILE: &str = "notes.json";
+const MAX_RUN_ID_LEN: usize = 128;
+const MAX_NOTE_LEN: usize = 16 * 1024;
+
+/// Serializes read-modify-write cycles on run notes
+pub type RunHistoryLock = Arc<Mutex<()>>;
+
+pub fn init_run_history() -> RunHistoryLock {
+    Arc::new(Mutex::new(()))
+}
+
+/// Persist a finished run; failures are logged and never fail the run
+pub async fn record_run(app: &AppHandle, result: &RunResult) {
+    let lock = app.state::<RunHistoryLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let written =
+        run_dir(app, &result.id).and_then(|dir| write_json(&dir.join(RESULT_FILE), result));
+    if let Err(e) = written {
+        log::warn!("Failed to record run {} in history: {}", result.id, e);
+    }
+}
+
+/// Set the free-form note of a run; empty text clears it
+#[tauri::command]
+pub async fn set_run_note(
+    app: AppHandle,
+    run_id: String,
+    text: String,
+) -> Result<ApiResponse<RunNotes>, String> {
+    let result = update_notes(&app, &run_id, |notes| {
+        let text = text.trim();
+        validate_note_text(text)?;
+        notes.note = (!text.is_empty()).then(|| text.to_string());
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(notes) => {
+            log::info!("Updated note of run {}", run_id);
+            Ok(ApiResponse::success(notes))
+        }
+        Err(e) => Ok(error_response("Failed to save run note", e)),
+    }
+}
+
+/// Attach a note to one (1-based) line of a run's log
+#[tauri::command]
+pub async fn add_run_annotation(
+    app: AppHandle,
+    run_id: String,
+    line_no: usize,
+    text: String,
+) -> Result<ApiResponse<RunAnnotation>, String> {
+    let result = async {
+        let annotation = new_annotation(line_no, &text)?;
+        update_notes(&app, &run_id, |notes| {
+            notes.annotations.push(annotation.clone());
+            notes.annotations.sort_by_key(|a| a.line_no);
+            Ok(())
+        })
+        .await?;
+        Ok::<_, AppError>(annotation)
+    }
+    .await;
+
+    match result {
+        Ok(annotation) => {
+            log::info!("Annotated line {} of run {}", line_no, run_id);
+            Ok(ApiResponse::success(annotation))
+        }
+        Err(e) => Ok(error_response("Failed to add annotation", e)),
+    }
+}
+
+/// A run's recorded result, if any, together with its notes
+#[tauri::command]
+pub async fn get_run_record(
+    app: AppHandle,
+    run_id: String,
+) -> Result<ApiResponse<RunRecord>, String> {
+    match load_record(&app, &run_id) {
+        Ok(record) => Ok(ApiResponse::success(record)),
+        Err(e) => Ok(error_response("Failed to load run", e)),
+    }
+}
+
+pub(crate) fn load_record(app: &AppHandle, run_id: &str) -> Result<RunRecord, AppError> {
+    let dir = run_dir(app, run_id)?;
+    let result: Option<RunResult> = read_json(&dir.join(RESULT_FILE))?;
+    if result.is_none() && !dir.join(NOTES_FILE).exists() {
+        return Err(AppError::Process(format!("Run '{}' not found", run_id)));
+    }
+    Ok(RunRecord {
+        result,
+        notes: load_notes(&dir, run_id)?,
+    })
+}
+
+async fn update_notes<F>(app: &AppHandle, run_id: &str, update: F) -> Result<RunNotes, AppError>
+where
+    F: FnOnce(&mut RunNotes) -> Result<(), AppError>,
+{
+    let lock = app.state::<RunHistoryLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let dir = run_dir(app, run_id)?;
+    let mut notes = load_notes(&dir, run_id)?;
+    update(&mut notes)?;
+    notes.updated_at = Some(current_timestamp());
+    write_json(&dir.join(NOTES_FILE), &notes)?;
+    Ok(notes)
+}
+
+fn load_notes(dir: &Path, run_id: &str) -> Result<RunNotes, AppError> {
+    Ok(
+        read_json(&dir.join(NOTES_FILE))?.unwrap_or_else(|| RunNotes {
+            run_id: run_id.to_string(),
+            ..RunNotes::default()
+        }),
+    )
+}
+
+fn new_annotation(line_no: usize, text: &str) -> Result<RunAnnotation, AppError> {
+    if line_no == 0 {
+        return Err(AppError::Config("Line numbers start at 1".to_string()));
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::Config("Annotation text is empty".to_string()));
+    }
+    validate_note_text(text)?;
+
+    Ok(RunAnnotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        line_no,
+        text: text.to_string(),
+        created_at: current_timestamp(),
+    })
+}
+
+fn validate_note_text(text: &str) -> Result<(), AppError> {
+    if text.len() > MAX_NOTE_LEN {
+        return Err(AppError::Config(format!(
+            "Note is {} bytes; the limit is {} bytes",
+            text.len(),
+            MAX_NOTE_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn validate_run_id(run_id: &str) -> Result<(), AppError> {
+    let valid = !run_id.is_empty()
+        && run_id.len() <= MAX_RUN_ID_LEN
+        && !run_id.starts_with('.')
+        && run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if !valid {
+        return Err(AppError::Config(format!("Invalid run ID '{}'", run_id)));
+    }
+    Ok(())
+}
+
+/// Directory of a run; run IDs double as directory names
+pub(crate) fn run_dir(app: &AppHandle, run_id: &str) -> Result<PathBuf, AppError> {
+    validate_run_id(run_id)?;
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?
+        .join(RUNS_DIR)
+        .join(run_id);
+    Ok(dir)
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(value)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_run_id() {
+        assert!(validate_run_id(&crate::models::generate_safe_run_id()).is_ok());
+        assert!(validate_run_id("run_1700000000000_ab12").is_ok());
+        assert!(validate_run_id("../secrets").is_err());
+        assert!(validate_run_id("").is_err());
+    }
+
+    #[test]
+    fn test_new_annotation() {
+        let annotation = new_annotation(3, "  flaky here ").unwrap();
+        assert_eq!(annotation.line_no, 3);
+        assert_eq!(annotation.text, "flaky here");
+
+        assert!(new_annotation(0, "text").is_err());
+        assert!(new_annotation(1, "   ").is_err());
+        assert!(new_annotation(1, &"x".repeat(MAX_NOTE_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_notes_round_trip() {
+        let dir = std::env::temp_dir().join(format!("run-notes-{}", uuid::Uuid::new_v4()));
+        let mut notes = load_notes(&dir, "run_1").unwrap();
+        assert_eq!(notes.run_id, "run_1");
+        assert!(notes.annotations.is_empty());
+
+        notes.note = Some("baseline".to_string());
+        notes.annotations.push(new_annotation(2, "here").unwrap());
+        write_json(&dir.join(NOTES_FILE), &notes).unwrap();
+
+        let loaded = load_notes(&dir, "run_1").unwrap();
+        assert_eq!(loaded.note.as_deref(), Some("baseline"));
+        assert_eq!(loaded.annotations.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
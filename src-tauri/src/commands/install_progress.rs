@@ -0,0 +1,220 @@
+//! npx install progress
+//! When the CLI runs through npx, npm logs each registry fetch (`npm_config_loglevel=http`);
+//! metadata fetches are counted as resolved packages and tarball fetches as downloads, and
+//! the running totals are emitted as throttled `cli-install-progress` events
+
+use crate::commands::stats::emit_event;
+use crate::models::{CliInstallPhase, CliInstallProgressEvent};
+use crate::severity::strip_ansi;
+use regex::Regex;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// Least time between progress events within one phase
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+/// Percent shown until npm confirms the install finished
+const MAX_PENDING_PERCENT: u8 = 99;
+
+/// Environment that makes npm log the registry fetches progress is counted from
+pub fn npx_progress_env() -> [(String, String); 2] {
+    [
+        ("npm_config_loglevel".to_string(), "http".to_string()),
+        ("npm_config_progress".to_string(), "false".to_string()),
+    ]
+}
+
+/// Package counts seen so far in one run's output
+#[derive(Debug, Default)]
+struct InstallTracker {
+    resolved: u32,
+    downloaded: u32,
+    total: Option<u32>,
+    phase: Option<CliInstallPhase>,
+    last_emit: Option<Instant>,
+}
+
+impl InstallTracker {
+    /// Count one output line, returning a snapshot when an event is due
+    fn observe(&mut self, line: &str, now: Instant) -> Option<(CliInstallPhase, Option<u8>)> {
+        if self.phase == Some(CliInstallPhase::Complete) {
+            return None;
+        }
+        let line = strip_ansi(line);
+        let line = line.trim();
+
+        let phase = if let Some(url) = fetched_url(line) {
+            if is_tarball(url) {
+                self.downloaded += 1;
+                CliInstallPhase::Downloading
+            } else {
+                self.resolved += 1;
+                self.phase.unwrap_or(CliInstallPhase::Resolving)
+            }
+        } else if let Some(added) = added_packages(line) {
+            self.total = Some(added);
+            CliInstallPhase::Complete
+        } else if self.phase.is_some() && !line.is_empty() && !is_npm_line(line) {
+            // The CLI itself has started talking, so the install is over
+            CliInstallPhase::Complete
+        } else {
+            return None;
+        };
+
+        let changed = self.phase != Some(phase);
+        self.phase = Some(phase);
+        if phase == CliInstallPhase::Downloading && self.total.is_none() {
+            self.total = Some(self.resolved.max(self.downloaded));
+        }
+
+        let due = self
+            .last_emit
+            .is_none_or(|at| now.duration_since(at) >= EMIT_INTERVAL);
+        if !changed && !due {
+            return None;
+        }
+        self.last_emit = Some(now);
+        Some((phase, self.percent()))
+    }
+
+    fn percent(&self) -> Option<u8> {
+        match self.phase? {
+            CliInstallPhase::Complete => Some(100),
+            CliInstallPhase::Resolving => None,
+            CliInstallPhase::Downloading => {
+                let total = self.total.filter(|&total| total > 0)?;
+                let percent = u64::from(self.downloaded) * 100 / u64::from(total);
+                Some((percent as u8).min(MAX_PENDING_PERCENT))
+            }
+        }
+    }
+}
+
+/// Install progress of one npx run, shared by its stdout and stderr readers
+pub struct InstallProgress {
+    run_id: String,
+    tracker: Mutex<InstallTracker>,
+}
+
+impl InstallProgress {
+    pub fn new(run_id: String) -> Self {
+        Self {
+            run_id,
+            tracker: Mutex::new(InstallTracker::default()),
+        }
+    }
+
+    pub fn observe(&self, app: &AppHandle, line: &str) {
+        let mut tracker = self.tracker.lock().unwrap_or_else(|e| e.into_inner());
+        let Some((phase, percent)) = tracker.observe(line, Instant::now()) else {
+            return;
+        };
+        let event = CliInstallProgressEvent {
+            run_id: self.run_id.clone(),
+            phase,
+            packages_resolved: tracker.resolved,
+            packages_downloaded: tracker.downloaded,
+            total_packages: tracker.total,
+            percent,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        drop(tracker);
+        emit_event(app, "cli-install-progress", event);
+    }
+}
+
+fn is_npm_line(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    lower.starts_with("npm ")
+        || lower.starts_with("need to install the following packages")
+        || lower.starts_with("ok to proceed?")
+        || lower.starts_with("@elizaos/")
+}
+
+/// URL of a successful registry fetch in `npm http fetch GET 200 <url> 12ms` lines
+fn fetched_url(line: &str) -> Option<&str> {
+    static FETCH: OnceLock<Regex> = OnceLock::new();
+    let pattern = FETCH.get_or_init(|| {
+        Regex::new(r"^npm http fetch GET (?:2\d\d|304) (\S+)").expect("valid npm fetch regex")
+    });
+    pattern
+        .captures(line)
+        .and_then(|captures| captures.get(1))
+        .map(|url| url.as_str())
+}
+
+fn is_tarball(url: &str) -> bool {
+    url.split('?')
+        .next()
+        .is_some_and(|path| path.ends_with(".tgz"))
+}
+
+/// Package count from npm's `added 523 packages in 45s` summary
+fn added_packages(line: &str) -> Option<u32> {
+    let rest = line.strip_prefix("added ")?;
+    let (count, rest) = rest.split_once(' ')?;
+    rest.starts_with("package").then(|| count.parse().ok())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKUMENT: &str =
+        "npm http fetch GET 200 https://registry.npmjs.org/@elizaos%2fcli 312ms (cache miss)";
+    const TARBALL: &str =
+        "npm http fetch GET 200 https://registry.npmjs.org/chalk/-/chalk-5.3.0.tgz 40ms (cache miss)";
+
+    #[test]
+    fn test_parse_npm_lines() {
+        assert_eq!(
+            fetched_url(PACKUMENT),
+            Some("https://registry.npmjs.org/@elizaos%2fcli")
+        );
+        assert!(is_tarball(fetched_url(TARBALL).unwrap()));
+        assert_eq!(
+            fetched_url("npm http fetch GET 404 https://registry.npmjs.org/nope 20ms"),
+            None
+        );
+        assert_eq!(added_packages("added 523 packages in 45s"), Some(523));
+        assert_eq!(
+            added_packages("added 1 package, and audited 2 packages"),
+            Some(1)
+        );
+        assert_eq!(added_packages("added support for x"), None);
+    }
+
+    #[test]
+    fn test_tracker_phases_and_percent() {
+        let start = Instant::now();
+        let mut tracker = InstallTracker::default();
+        assert_eq!(tracker.observe("Starting ElizaOS...", start), None);
+
+        assert_eq!(
+            tracker.observe(PACKUMENT, start),
+            Some((CliInstallPhase::Resolving, None))
+        );
+        for _ in 0..3 {
+            tracker.observe(PACKUMENT, start);
+        }
+        assert_eq!(tracker.resolved, 4);
+
+        // A phase change is emitted right away; later updates are throttled
+        assert_eq!(
+            tracker.observe(TARBALL, start),
+            Some((CliInstallPhase::Downloading, Some(25)))
+        );
+        assert_eq!(tracker.observe(TARBALL, start), None);
+        assert_eq!(
+            tracker.observe(TARBALL, start + EMIT_INTERVAL),
+            Some((CliInstallPhase::Downloading, Some(75)))
+        );
+        assert_eq!(tracker.total, Some(4));
+
+        assert_eq!(
+            tracker.observe("Starting ElizaOS CLI v1.4.2", start),
+            Some((CliInstallPhase::Complete, Some(100)))
+        );
+        assert_eq!(tracker.observe(TARBALL, start + EMIT_INTERVAL * 2), None);
+    }
+}
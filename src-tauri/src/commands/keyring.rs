@@ -0,0 +1,217 @@
+//! OS keyring
+//! Secrets kept in the platform credential store: the macOS Keychain, Windows Credential
+//! Manager, or the Secret Service through `secret-tool` on Linux. Callers fall back to
+//! their own storage when no keyring is available
+
+use crate::models::AppError;
+
+/// Service name the app's keyring entries are filed under
+const SERVICE: &str = "com.elizaos.desktop-cli";
+
+/// Store a secret, replacing any previous value for the account
+pub(crate) fn set_secret(account: &str, secret: &str) -> Result<(), AppError> {
+    platform::set_secret(account, secret)
+}
+
+/// A stored secret; `None` when the account has none
+pub(crate) fn get_secret(account: &str) -> Result<Option<String>, AppError> {
+    platform::get_secret(account)
+}
+
+/// Remove a secret; removing a missing one is not an error
+pub(crate) fn delete_secret(account: &str) -> Result<(), AppError> {
+    platform::delete_secret(account)
+}
+
+fn keyring_error(action: &str, detail: impl std::fmt::Display) -> AppError {
+    AppError::EnvironmentError(format!("Failed to {} keyring entry: {}", action, detail))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{keyring_error, SERVICE};
+    use crate::models::AppError;
+    use security_framework::passwords::{
+        delete_generic_password, get_generic_password, set_generic_password,
+    };
+
+    const ERR_SEC_ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn set_secret(account: &str, secret: &str) -> Result<(), AppError> {
+        set_generic_password(SERVICE, account, secret.as_bytes())
+            .map_err(|e| keyring_error("write", e))
+    }
+
+    pub fn get_secret(account: &str) -> Result<Option<String>, AppError> {
+        match get_generic_password(SERVICE, account) {
+            Ok(secret) => Ok(Some(String::from_utf8_lossy(&secret).to_string())),
+            Err(e) if e.code() == ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(keyring_error("read", e)),
+        }
+    }
+
+    pub fn delete_secret(account: &str) -> Result<(), AppError> {
+        match delete_generic_password(SERVICE, account) {
+            Err(e) if e.code() != ERR_SEC_ITEM_NOT_FOUND => Err(keyring_error("delete", e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{keyring_error, SERVICE};
+    use crate::models::AppError;
+    use windows_sys::Win32::Foundation::{GetLastError, ERROR_NOT_FOUND};
+    use windows_sys::Win32::Security::Credentials::{
+        CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+        CRED_TYPE_GENERIC,
+    };
+
+    fn target_name(account: &str) -> Vec<u16> {
+        format!("{}/{}", SERVICE, account)
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect()
+    }
+
+    pub fn set_secret(account: &str, secret: &str) -> Result<(), AppError> {
+        let mut target = target_name(account);
+        let mut blob = secret.as_bytes().to_vec();
+        // SAFETY: every pointer in the credential outlives the call, which copies them
+        unsafe {
+            let mut credential: CREDENTIALW = std::mem::zeroed();
+            credential.Type = CRED_TYPE_GENERIC;
+            credential.TargetName = target.as_mut_ptr();
+            credential.CredentialBlobSize = blob.len() as u32;
+            credential.CredentialBlob = blob.as_mut_ptr();
+            credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+            if CredWriteW(&credential, 0) == 0 {
+                return Err(keyring_error("write", format!("error {}", GetLastError())));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_secret(account: &str) -> Result<Option<String>, AppError> {
+        let target = target_name(account);
+        // SAFETY: the credential is only read while valid and freed with CredFree
+        unsafe {
+            let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                return match GetLastError() {
+                    ERROR_NOT_FOUND => Ok(None),
+                    code => Err(keyring_error("read", format!("error {}", code))),
+                };
+            }
+            let blob = std::slice::from_raw_parts(
+                (*credential).CredentialBlob,
+                (*credential).CredentialBlobSize as usize,
+            );
+            let secret = String::from_utf8_lossy(blob).to_string();
+            CredFree(credential as *const _);
+            Ok(Some(secret))
+        }
+    }
+
+    pub fn delete_secret(account: &str) -> Result<(), AppError> {
+        let target = target_name(account);
+        // SAFETY: plain Win32 call with a NUL-terminated target name
+        unsafe {
+            if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0 {
+                return match GetLastError() {
+                    ERROR_NOT_FOUND => Ok(()),
+                    code => Err(keyring_error("delete", format!("error {}", code))),
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::{keyring_error, SERVICE};
+    use crate::models::AppError;
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    /// Run `secret-tool`, writing `input` to its stdin
+    fn secret_tool(args: &[&str], input: Option<&str>) -> Result<Output, AppError> {
+        let mut child = Command::new("secret-tool")
+            .args(args)
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| keyring_error("access", format!("secret-tool: {}", e)))?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.as_bytes())?;
+        }
+        Ok(child.wait_with_output()?)
+    }
+
+    fn stderr(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stderr).trim().to_string()
+    }
+
+    pub fn set_secret(account: &str, secret: &str) -> Result<(), AppError> {
+        let label = format!("ElizaOS Desktop ({})", account);
+        // The secret goes through stdin so it never shows up in the process list
+        let output = secret_tool(
+            &[
+                "store", "--label", &label, "service", SERVICE, "account", account,
+            ],
+            Some(secret),
+        )?;
+        if !output.status.success() {
+            return Err(keyring_error("write", stderr(&output)));
+        }
+        Ok(())
+    }
+
+    pub fn get_secret(account: &str) -> Result<Option<String>, AppError> {
+        let output = secret_tool(&["lookup", "service", SERVICE, "account", account], None)?;
+        if output.status.success() {
+            return Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()));
+        }
+        // A missing entry exits with 1 and says nothing
+        if output.stderr.is_empty() {
+            return Ok(None);
+        }
+        Err(keyring_error("read", stderr(&output)))
+    }
+
+    pub fn delete_secret(account: &str) -> Result<(), AppError> {
+        let output = secret_tool(&["clear", "service", SERVICE, "account", account], None)?;
+        if !output.status.success() && !output.stderr.is_empty() {
+            return Err(keyring_error("delete", stderr(&output)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use crate::models::AppError;
+
+    fn unsupported() -> AppError {
+        AppError::EnvironmentError("No OS keyring on this platform".to_string())
+    }
+
+    pub fn set_secret(_account: &str, _secret: &str) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+
+    pub fn get_secret(_account: &str) -> Result<Option<String>, AppError> {
+        Err(unsupported())
+    }
+
+    pub fn delete_secret(_account: &str) -> Result<(), AppError> {
+        Err(unsupported())
+    }
+}
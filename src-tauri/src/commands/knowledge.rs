@@ -0,0 +1,287 @@
+//! Character knowledge
+//! Documents attached to managed characters, copied into a `knowledge` directory next to
+//! the character file and handed to the CLI's knowledge loader at run time
+
+use crate::commands::characters::character_dir;
+use crate::models::{ApiResponse, AppError, KnowledgeFile};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+pub const KNOWLEDGE_DIR: &str = "knowledge";
+/// Document types the knowledge loader understands
+pub const KNOWLEDGE_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "pdf"];
+/// Largest single knowledge document
+pub const MAX_KNOWLEDGE_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Largest total size of one character's knowledge
+pub const MAX_KNOWLEDGE_TOTAL_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Copy documents into a character's knowledge; a document with the same name is replaced
+#[tauri::command]
+pub async fn add_character_knowledge(
+    app: AppHandle,
+    id: String,
+    paths: Vec<String>,
+) -> Result<ApiResponse<Vec<KnowledgeFile>>, String> {
+    log::info!("Adding {} knowledge files to character {}", paths.len(), id);
+
+    let result = (|| {
+        let dir = character_dir(&app, &id)?;
+        if !dir.exists() {
+            return Err(AppError::CharacterError(format!(
+                "Character '{}' not found",
+                id
+            )));
+        }
+        let knowledge_dir = dir.join(KNOWLEDGE_DIR);
+
+        // Validate everything before copying anything
+        let existing = list_knowledge(&knowledge_dir)?;
+        let mut total: u64 = existing.iter().map(|file| file.size_bytes).sum();
+        let mut sources = Vec::new();
+        for path in &paths {
+            let (name, size) = validate_document(Path::new(path))?;
+            let replaced = existing
+                .iter()
+                .find(|file| file.name == name)
+                .map_or(0, |file| file.size_bytes);
+            total = total.saturating_sub(replaced) + size;
+            sources.push((path, name));
+        }
+        if total > MAX_KNOWLEDGE_TOTAL_BYTES {
+            return Err(AppError::Quota(format!(
+                "Knowledge would total {} bytes; the limit is {} bytes",
+                total, MAX_KNOWLEDGE_TOTAL_BYTES
+            )));
+        }
+
+        fs::create_dir_all(&knowledge_dir)?;
+        for (path, name) in sources {
+            fs::copy(path, knowledge_dir.join(name))?;
+        }
+        list_knowledge(&knowledge_dir)
+    })();
+
+    match result {
+        Ok(files) => Ok(ApiResponse::success(files)),
+        Err(e) => Ok(error_response("Failed to add knowledge", e)),
+    }
+}
+
+/// Knowledge documents of a character, sorted by name
+#[tauri::command]
+pub async fn list_character_knowledge(
+    app: AppHandle,
+    id: String,
+) -> Result<ApiResponse<Vec<KnowledgeFile>>, String> {
+    let result = character_dir(&app, &id).and_then(|dir| list_knowledge(&dir.join(KNOWLEDGE_DIR)));
+
+    match result {
+        Ok(files) => Ok(ApiResponse::success(files)),
+        Err(e) => Ok(error_response("Failed to list knowledge", e)),
+    }
+}
+
+/// Remove a knowledge document, returning whether it existed
+#[tauri::command]
+pub async fn remove_character_knowledge(
+    app: AppHandle,
+    id: String,
+    name: String,
+) -> Result<ApiResponse<bool>, String> {
+    let result = (|| {
+        validate_file_name(&name)?;
+        let path = character_dir(&app, &id)?.join(KNOWLEDGE_DIR).join(&name);
+        if !path.is_file() {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok::<_, AppError>(true)
+    })();
+
+    match result {
+        Ok(existed) => {
+            log::info!(
+                "Removed knowledge {} from character {} (existed: {})",
+                name,
+                id,
+                existed
+            );
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to remove knowledge", e)),
+    }
+}
+
+/// Environment pointing the CLI's knowledge loader at the character's documents, when the
+/// character file has a non-empty `knowledge` directory next to it
+pub fn knowledge_env(character_file: Option<&str>) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    let Some(knowledge_dir) = character_file
+        .and_then(|file| Path::new(file).parent())
+        .map(|dir| dir.join(KNOWLEDGE_DIR))
+    else {
+        return env;
+    };
+
+    let has_documents = list_knowledge(&knowledge_dir).is_ok_and(|files| !files.is_empty());
+    if has_documents {
+        env.insert(
+            "KNOWLEDGE_PATH".to_string(),
+            knowledge_dir.to_string_lossy().to_string(),
+        );
+        env.insert("LOAD_DOCS_ON_STARTUP".to_string(), "true".to_string());
+    }
+    env
+}
+
+pub(crate) fn list_knowledge(dir: &Path) -> Result<Vec<KnowledgeFile>, AppError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        files.push(KnowledgeFile {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: entry.path().to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// Check a source document's type and size, returning its file name and size
+fn validate_document(path: &Path) -> Result<(String, u64), AppError> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| AppError::CharacterError(format!("Cannot read {}: {}", path.display(), e)))?;
+    if !metadata.is_file() {
+        return Err(AppError::CharacterError(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    validate_file_name(&name)?;
+
+    if metadata.len() > MAX_KNOWLEDGE_FILE_BYTES {
+        return Err(AppError::Quota(format!(
+            "{} is {} bytes; the limit is {} bytes",
+            name,
+            metadata.len(),
+            MAX_KNOWLEDGE_FILE_BYTES
+        )));
+    }
+
+    validate_contents(&name, &fs::read(path)?)?;
+    Ok((name, metadata.len()))
+}
+
+/// Knowledge file names are plain file names with a supported extension
+fn validate_file_name(name: &str) -> Result<(), AppError> {
+    let path = Path::new(name);
+    if name.is_empty() || name.starts_with('.') || path.file_name() != Some(path.as_os_str()) {
+        return Err(AppError::CharacterError(format!(
+            "Invalid knowledge file name '{}'",
+            name
+        )));
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !KNOWLEDGE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AppError::CharacterError(format!(
+            "Unsupported knowledge file '{}': use {}",
+            name,
+            KNOWLEDGE_EXTENSIONS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Make sure contents match the extension: PDFs by signature, text as UTF-8
+fn validate_contents(name: &str, contents: &[u8]) -> Result<(), AppError> {
+    let is_pdf = name.to_lowercase().ends_with(".pdf");
+    let valid = if is_pdf {
+        contents.starts_with(b"%PDF-")
+    } else {
+        std::str::from_utf8(contents).is_ok()
+    };
+
+    if !valid {
+        return Err(AppError::CharacterError(format!(
+            "{} is not a valid {} file",
+            name,
+            if is_pdf { "PDF" } else { "text" }
+        )));
+    }
+    Ok(())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_file_name() {
+        assert!(validate_file_name("faq.md").is_ok());
+        assert!(validate_file_name("Manual.PDF").is_ok());
+        assert!(validate_file_name("notes.docx").is_err());
+        assert!(validate_file_name("../faq.md").is_err());
+        assert!(validate_file_name(".hidden.txt").is_err());
+        assert!(validate_file_name("").is_err());
+    }
+
+    #[test]
+    fn test_validate_contents() {
+        assert!(validate_contents("a.pdf", b"%PDF-1.7\n...").is_ok());
+        assert!(validate_contents("a.pdf", b"not a pdf").is_err());
+        assert!(validate_contents("a.md", "# Über".as_bytes()).is_ok());
+        assert!(validate_contents("a.txt", &[0xff, 0xfe, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_knowledge_env() {
+        let dir =
+            std::env::temp_dir().join(format!("character-knowledge-{}", uuid::Uuid::new_v4()));
+        let character = dir.join("character.json");
+        fs::create_dir_all(dir.join(KNOWLEDGE_DIR)).unwrap();
+        let character_file = character.to_string_lossy().to_string();
+
+        assert!(knowledge_env(Some(&character_file)).is_empty());
+        assert!(knowledge_env(None).is_empty());
+
+        fs::write(dir.join(KNOWLEDGE_DIR).join("faq.md"), "faq").unwrap();
+        let env = knowledge_env(Some(&character_file));
+        assert_eq!(
+            env.get("KNOWLEDGE_PATH").map(String::as_str),
+            Some(dir.join(KNOWLEDGE_DIR).to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            env.get("LOAD_DOCS_ON_STARTUP").map(String::as_str),
+            Some("true")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
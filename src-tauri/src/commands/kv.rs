@@ -0,0 +1,332 @@
+//! Key-value store
+//! Namespaced persistent storage for UI state (layouts, pinned runs, last-used character),
+//! one JSON file per namespace under the app data directory
+
+use crate::commands::stats::emit_event;
+use crate::models::{ApiResponse, AppError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+const KV_DIR: &str = "kv";
+const MAX_NAME_LEN: usize = 64;
+const MAX_KEY_LEN: usize = 256;
+/// Largest serialized value accepted for a single key
+pub const MAX_VALUE_BYTES: usize = 64 * 1024;
+/// Largest serialized size of all values in one namespace
+pub const MAX_NAMESPACE_BYTES: usize = 1024 * 1024;
+
+type Namespace = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KvEntry {
+    pub key: String,
+    pub value: Value,
+    pub size_bytes: usize,
+}
+
+/// Payload of the `kv-changed` event; `value` is absent when the key was deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KvChange {
+    pub namespace: String,
+    pub key: String,
+    pub value: Option<Value>,
+}
+
+// Serializes read-modify-write cycles on namespace files
+pub type KvStoreLock = Arc<Mutex<()>>;
+
+/// Initialize the key-value store lock (called from main)
+pub fn init_kv_store() -> KvStoreLock {
+    Arc::new(Mutex::new(()))
+}
+
+/// Get a value, or null when the key is not set
+#[tauri::command]
+pub async fn kv_get(
+    app: AppHandle,
+    namespace: String,
+    key: String,
+) -> Result<ApiResponse<Option<Value>>, String> {
+    Ok(to_response(load_value(&app, &namespace, &key).await))
+}
+
+/// Set a value, enforcing the per-value and per-namespace quotas
+#[tauri::command]
+pub async fn kv_set(
+    app: AppHandle,
+    namespace: String,
+    key: String,
+    value: Value,
+) -> Result<ApiResponse<KvEntry>, String> {
+    Ok(to_response(
+        update_value(&app, namespace, key, |_| Ok(value)).await,
+    ))
+}
+
+/// Delete a key, returning whether it existed
+#[tauri::command]
+pub async fn kv_delete(
+    app: AppHandle,
+    namespace: String,
+    key: String,
+) -> Result<ApiResponse<bool>, String> {
+    let result = async {
+        validate_namespace(&namespace)?;
+
+        let _guard = lock_store(&app).await;
+        let mut entries = read_namespace(&app, &namespace)?;
+        let existed = entries.remove(&key).is_some();
+        if existed {
+            write_namespace(&app, &namespace, &entries)?;
+        }
+        Ok::<_, AppError>(existed)
+    }
+    .await;
+
+    if let Ok(true) = result {
+        log::debug!("KV delete {}/{}", namespace, key);
+        emit_change(&app, namespace, key, None);
+    }
+    Ok(to_response(result))
+}
+
+/// List all entries in a namespace, sorted by key
+#[tauri::command]
+pub async fn kv_list(
+    app: AppHandle,
+    namespace: String,
+) -> Result<ApiResponse<Vec<KvEntry>>, String> {
+    let result = async {
+        validate_namespace(&namespace)?;
+        let _guard = lock_store(&app).await;
+
+        let entries = read_namespace(&app, &namespace)?
+            .into_iter()
+            .map(|(key, value)| KvEntry {
+                size_bytes: value_size(&value),
+                key,
+                value,
+            })
+            .collect();
+        Ok::<_, AppError>(entries)
+    }
+    .await;
+
+    Ok(to_response(result))
+}
+
+/// Read one value; shared with features that keep their state in the store
+pub(crate) async fn load_value(
+    app: &AppHandle,
+    namespace: &str,
+    key: &str,
+) -> Result<Option<Value>, AppError> {
+    validate_namespace(namespace)?;
+    let _guard = lock_store(app).await;
+    Ok(read_namespace(app, namespace)?.remove(key))
+}
+
+/// Replace one value with `update(current)` under the quotas and announce it with
+/// `kv-changed`; the store stays locked in between, so concurrent updates don't interleave
+pub(crate) async fn update_value<F>(
+    app: &AppHandle,
+    namespace: String,
+    key: String,
+    update: F,
+) -> Result<KvEntry, AppError>
+where
+    F: FnOnce(Option<Value>) -> Result<Value, AppError>,
+{
+    validate_namespace(&namespace)?;
+    validate_key(&key)?;
+
+    let (value, size_bytes) = {
+        let _guard = lock_store(app).await;
+        let mut entries = read_namespace(app, &namespace)?;
+        let value = update(entries.get(&key).cloned())?;
+        let size_bytes = check_quota(&entries, &key, &value)?;
+
+        entries.insert(key.clone(), value.clone());
+        write_namespace(app, &namespace, &entries)?;
+        (value, size_bytes)
+    };
+
+    log::debug!("KV set {}/{}", namespace, key);
+    emit_change(app, namespace, key.clone(), Some(value.clone()));
+    Ok(KvEntry {
+        key,
+        value,
+        size_bytes,
+    })
+}
+
+fn to_response<T>(result: Result<T, AppError>) -> ApiResponse<T> {
+    match result {
+        Ok(value) => ApiResponse::success(value),
+        Err(e) => {
+            log::warn!("KV store error: {}", e);
+            ApiResponse::error(e.error_code().to_string(), e.to_string())
+        }
+    }
+}
+
+fn emit_change(app: &AppHandle, namespace: String, key: String, value: Option<Value>) {
+    emit_event(
+        app,
+        "kv-changed",
+        KvChange {
+            namespace,
+            key,
+            value,
+        },
+    );
+}
+
+async fn lock_store(app: &AppHandle) -> tokio::sync::OwnedMutexGuard<()> {
+    app.state::<KvStoreLock>()
+        .inner()
+        .clone()
+        .lock_owned()
+        .await
+}
+
+/// Namespaces double as file names, so keep them to a safe character set
+fn validate_namespace(namespace: &str) -> Result<(), AppError> {
+    let valid = !namespace.is_empty()
+        && namespace.len() <= MAX_NAME_LEN
+        && !namespace.starts_with('.')
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if !valid {
+        return Err(AppError::Config(format!(
+            "Invalid KV namespace '{}': use 1-{} letters, digits, '-', '_' or '.'",
+            namespace, MAX_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn validate_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(AppError::Config(format!(
+            "Invalid KV key: use 1-{} characters",
+            MAX_KEY_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn value_size(value: &Value) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
+
+/// Check a write against the quotas, returning the value's serialized size
+fn check_quota(entries: &Namespace, key: &str, value: &Value) -> Result<usize, AppError> {
+    let size = value_size(value);
+    if size > MAX_VALUE_BYTES {
+        return Err(AppError::Quota(format!(
+            "Value for '{}' is {} bytes; the limit is {} bytes",
+            key, size, MAX_VALUE_BYTES
+        )));
+    }
+
+    let namespace_size: usize = entries
+        .iter()
+        .filter(|(existing, _)| existing.as_str() != key)
+        .map(|(existing, value)| existing.len() + value_size(value))
+        .sum::<usize>()
+        + key.len()
+        + size;
+    if namespace_size > MAX_NAMESPACE_BYTES {
+        return Err(AppError::Quota(format!(
+            "Namespace would grow to {} bytes; the limit is {} bytes",
+            namespace_size, MAX_NAMESPACE_BYTES
+        )));
+    }
+
+    Ok(size)
+}
+
+fn get_namespace_path(app: &AppHandle, namespace: &str) -> Result<PathBuf, AppError> {
+    let kv_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?
+        .join(KV_DIR);
+
+    fs::create_dir_all(&kv_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create KV directory: {}", e)))?;
+
+    Ok(kv_dir.join(format!("{}.json", namespace)))
+}
+
+fn read_namespace(app: &AppHandle, namespace: &str) -> Result<Namespace, AppError> {
+    let path = get_namespace_path(app, namespace)?;
+    if !path.exists() {
+        return Ok(Namespace::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_namespace(app: &AppHandle, namespace: &str, entries: &Namespace) -> Result<(), AppError> {
+    let path = get_namespace_path(app, namespace)?;
+    if entries.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    // Write then rename so a crash never leaves a truncated namespace behind
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(entries)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_names() {
+        assert!(validate_namespace("layout").is_ok());
+        assert!(validate_namespace("pinned-runs_v2").is_ok());
+        assert!(validate_namespace("").is_err());
+        assert!(validate_namespace("../config").is_err());
+        assert!(validate_namespace(".hidden").is_err());
+        assert!(validate_key("panel/left width").is_ok());
+        assert!(validate_key(&"k".repeat(MAX_KEY_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_check_quota() {
+        let mut entries = Namespace::new();
+        assert_eq!(check_quota(&entries, "a", &json!(true)).unwrap(), 4);
+
+        let big = json!("x".repeat(MAX_VALUE_BYTES));
+        let err = check_quota(&entries, "a", &big).unwrap_err();
+        assert_eq!(err.error_code(), "QUOTA_EXCEEDED");
+
+        let chunk = json!("x".repeat(MAX_VALUE_BYTES - 16));
+        for i in 0..(MAX_NAMESPACE_BYTES / MAX_VALUE_BYTES) {
+            entries.insert(format!("k{}", i), chunk.clone());
+        }
+        assert!(check_quota(&entries, "new", &chunk).is_err());
+        // Overwriting an existing key doesn't count its old value twice
+        assert!(check_quota(&entries, "k0", &chunk).is_ok());
+    }
+}
@@ -0,0 +1,267 @@
+//! Loopback HTTP servers
+//! The mock Sandbox, webhook listener, log stream server and network capture proxy all
+//! serve HTTP/1.1 on 127.0.0.1 through this module. Request lines and headers are bounded
+//! in length and count, bodies in size, and a client gets a fixed time to send its request,
+//! so a local process can neither hold a connection open nor make the app buffer without end
+
+use crate::models::AppError;
+use std::future::Future;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot};
+
+/// Longest request line or header line accepted, including the line break
+const MAX_LINE_BYTES: usize = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Time a client has to send its request line, headers and body
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed HTTP/1.1 request; header names are lowercased
+#[derive(Debug, Default)]
+pub(crate) struct HttpRequest {
+    pub method: String,
+    /// Request target: a path, or an absolute URL or `host:port` for proxy requests
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Bind a listener on a free loopback port
+pub(crate) async fn bind_loopback() -> Result<(TcpListener, u16), AppError> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    Ok((listener, port))
+}
+
+/// Accept connections until shutdown is requested, handling each on its own task;
+/// connections still open when the server stops are dropped with it
+pub(crate) async fn serve<F, Fut>(
+    name: &'static str,
+    listener: TcpListener,
+    mut shutdown: oneshot::Receiver<()>,
+    handle_connection: F,
+) where
+    F: Fn(TcpStream) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), AppError>> + Send + 'static,
+{
+    let (closed, _) = broadcast::channel::<()>(1);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let (connection, mut closed) = (handle_connection(stream), closed.subscribe());
+                    tokio::spawn(async move {
+                        tokio::select! {
+                            _ = closed.recv() => {}
+                            result = connection => {
+                                if let Err(e) = result {
+                                    log::debug!("{} connection error: {}", name, e);
+                                }
+                            }
+                        }
+                    });
+                }
+                Err(e) => log::warn!("{} accept failed: {}", name, e),
+            },
+        }
+    }
+    let _ = closed.send(());
+}
+
+/// Read a request line, headers and a `Content-Length` body
+pub(crate) async fn read_http_request<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> Result<HttpRequest, AppError> {
+    with_read_timeout(async {
+        let mut request = read_head(reader).await?;
+        let content_length: usize = match request.header("content-length") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| AppError::Network(format!("Invalid Content-Length '{}'", value)))?,
+            None => 0,
+        };
+        if content_length > MAX_BODY_BYTES {
+            return Err(AppError::Network(format!(
+                "Request body of {} bytes exceeds the {} byte limit",
+                content_length, MAX_BODY_BYTES
+            )));
+        }
+
+        request.body = vec![0u8; content_length];
+        reader.read_exact(&mut request.body).await?;
+        Ok(request)
+    })
+    .await
+}
+
+/// Read only the request line and headers, leaving the body to the caller
+pub(crate) async fn read_request_head<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> Result<HttpRequest, AppError> {
+    with_read_timeout(read_head(reader)).await
+}
+
+async fn with_read_timeout<T>(
+    read: impl Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    tokio::time::timeout(READ_TIMEOUT, read)
+        .await
+        .map_err(|_| {
+            AppError::Network(format!(
+                "Client did not send its request within {}s",
+                READ_TIMEOUT.as_secs()
+            ))
+        })?
+}
+
+async fn read_head<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<HttpRequest, AppError> {
+    let request_line = read_line(reader).await?;
+    let mut parts = request_line.split_whitespace();
+    let mut request = HttpRequest {
+        method: parts.next().unwrap_or_default().to_string(),
+        path: parts.next().unwrap_or("/").to_string(),
+        ..HttpRequest::default()
+    };
+
+    loop {
+        let header = read_line(reader).await?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if request.headers.len() == MAX_HEADERS {
+            return Err(AppError::Network(format!(
+                "Request has more than {} headers",
+                MAX_HEADERS
+            )));
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            request
+                .headers
+                .push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    Ok(request)
+}
+
+/// One line, or an empty string at the end of the stream
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<String, AppError> {
+    let mut line = String::new();
+    (&mut *reader)
+        .take(MAX_LINE_BYTES as u64)
+        .read_line(&mut line)
+        .await?;
+    if line.len() == MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Err(AppError::Network(format!(
+            "Request line or header is longer than {} bytes",
+            MAX_LINE_BYTES
+        )));
+    }
+    Ok(line)
+}
+
+/// Write a JSON response and close the connection
+pub(crate) async fn write_json_response(
+    mut stream: TcpStream,
+    status: u16,
+    payload: &serde_json::Value,
+) -> Result<(), AppError> {
+    write_response(
+        &mut stream,
+        status,
+        &[("Content-Type", "application/json")],
+        payload.to_string().as_bytes(),
+    )
+    .await
+}
+
+/// Write a complete response with the given extra headers and close the connection
+pub(crate) async fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<(), AppError> {
+    let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason_phrase(status));
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_http_request() {
+        let mut input: &[u8] =
+            b"POST /hooks?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello";
+        let request = read_http_request(&mut input).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/hooks?x=1");
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_read_http_request_limits() {
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_BYTES));
+        assert!(read_http_request(&mut long_line.as_bytes()).await.is_err());
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Filler: 1\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert!(read_http_request(&mut many_headers.as_bytes())
+            .await
+            .is_err());
+        let enough_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Filler: 1\r\n".repeat(MAX_HEADERS)
+        );
+        assert!(read_http_request(&mut enough_headers.as_bytes())
+            .await
+            .is_ok());
+
+        let large_body = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(read_http_request(&mut large_body.as_bytes()).await.is_err());
+    }
+}
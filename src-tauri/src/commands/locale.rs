@@ -0,0 +1,67 @@
+//! Locale selection
+//! Picks the language backend messages are rendered in and keeps it across launches
+
+use crate::i18n::{current_locale, set_current_locale, Locale};
+use crate::models::{ApiResponse, AppError};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const LOCALE_FILE: &str = "locale.json";
+
+/// Locale backend messages are currently rendered in
+#[tauri::command]
+pub async fn get_locale() -> Result<ApiResponse<Locale>, String> {
+    Ok(ApiResponse::success(current_locale()))
+}
+
+/// Render backend messages in the given locale, e.g. `es` or `fr-CA`
+///
+/// Only the language part of the tag is used.
+#[tauri::command]
+pub async fn set_locale(app: AppHandle, locale: String) -> Result<ApiResponse<Locale>, String> {
+    let Some(parsed) = Locale::parse(&locale) else {
+        return Ok(ApiResponse::error(
+            "UNSUPPORTED_LOCALE".to_string(),
+            format!("Unsupported locale: {}", locale),
+        ));
+    };
+
+    set_current_locale(parsed);
+    if let Err(e) = write_locale(&app, parsed) {
+        log::warn!("Failed to save locale: {}", e);
+    }
+    log::info!("Locale set to {}", parsed.tag());
+    Ok(ApiResponse::success(parsed))
+}
+
+/// Restore the locale chosen in a previous session
+pub fn load_saved_locale(app: &AppHandle) {
+    match read_locale(app) {
+        Ok(Some(locale)) => set_current_locale(locale),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to load saved locale: {}", e),
+    }
+}
+
+fn locale_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(LOCALE_FILE))
+}
+
+fn read_locale(app: &AppHandle) -> Result<Option<Locale>, AppError> {
+    let path = locale_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+fn write_locale(app: &AppHandle, locale: Locale) -> Result<(), AppError> {
+    fs::write(locale_path(app)?, serde_json::to_vec(&locale)?)?;
+    Ok(())
+}
@@ -0,0 +1,384 @@
+//! Run log streaming server
+//! An optional loopback HTTP endpoint that streams the logs of runs started in the app as
+//! Server-Sent Events, so external tools (editor tasks, terminal panes) can follow them.
+//! Every request must carry the server's token, as a bearer token or a `token` parameter:
+//!
+//! ```text
+//! curl -N -H "Authorization: Bearer <token>" http://127.0.0.1:<port>/runs/<run_id>/logs
+//! ```
+
+use crate::commands::local_http::{
+    bind_loopback, read_http_request, serve, write_json_response, HttpRequest,
+};
+use crate::commands::run_logs::{buffered_run_ids, run_log_buffer, RunLogBuffer};
+use crate::crypto::{constant_time_eq, random_hex};
+use crate::models::{ApiResponse, AppError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::{oneshot, Mutex};
+
+const RUNS_PATH: &str = "/runs";
+/// A comment line is sent after this long without output, so idle streams stay open
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStreamStatus {
+    pub running: bool,
+    /// Base URL; a run's events are at `<url>/<run_id>/logs`
+    pub url: Option<String>,
+    /// Token every request must present
+    pub token: Option<String>,
+    pub started_at: Option<String>,
+}
+
+pub struct LogStreamHandle {
+    port: u16,
+    token: String,
+    started_at: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl LogStreamHandle {
+    fn status(&self) -> LogStreamStatus {
+        LogStreamStatus {
+            running: true,
+            url: Some(format!("http://127.0.0.1:{}{}", self.port, RUNS_PATH)),
+            token: Some(self.token.clone()),
+            started_at: Some(self.started_at.clone()),
+        }
+    }
+}
+
+// The running log stream server, if external streaming is enabled
+pub type LogStreamState = Arc<Mutex<Option<LogStreamHandle>>>;
+
+/// Initialize the log stream server state (called from main)
+pub fn init_log_stream_state() -> LogStreamState {
+    Arc::new(Mutex::new(None))
+}
+
+/// Start the log stream server on a random loopback port with a fresh token
+#[tauri::command]
+pub async fn start_log_stream_server(
+    app: AppHandle,
+) -> Result<ApiResponse<LogStreamStatus>, String> {
+    let state = app.state::<LogStreamState>().inner().clone();
+    let mut guard = state.lock().await;
+
+    if let Some(handle) = guard.as_ref() {
+        log::info!("Log stream server already running on port {}", handle.port);
+        return Ok(ApiResponse::success(handle.status()));
+    }
+
+    let (listener, port) = match bind_loopback().await {
+        Ok(bound) => bound,
+        Err(e) => {
+            log::error!("Failed to bind log stream server: {}", e);
+            return Ok(ApiResponse::error(
+                "LOG_STREAM_ERROR".to_string(),
+                format!("Failed to start log stream server: {}", e),
+            ));
+        }
+    };
+
+    let token = random_hex(32);
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let (app_handle, server_token) = (app.clone(), token.clone());
+    tokio::spawn(serve(
+        "Log stream server",
+        listener,
+        shutdown_rx,
+        move |stream| {
+            let (app, token) = (app_handle.clone(), server_token.clone());
+            async move { handle_connection(&app, stream, &token).await }
+        },
+    ));
+
+    let handle = LogStreamHandle {
+        port,
+        token,
+        started_at: crate::models::current_timestamp(),
+        shutdown,
+    };
+    let status = handle.status();
+    *guard = Some(handle);
+
+    log::info!("Log stream server on port {}", port);
+    Ok(ApiResponse::success(status))
+}
+
+/// Stop the log stream server; open streams end with it
+#[tauri::command]
+pub async fn stop_log_stream_server(
+    app: AppHandle,
+) -> Result<ApiResponse<LogStreamStatus>, String> {
+    let state = app.state::<LogStreamState>().inner().clone();
+
+    if let Some(handle) = state.lock().await.take() {
+        let _ = handle.shutdown.send(());
+        log::info!("Log stream server on port {} stopped", handle.port);
+    }
+
+    Ok(ApiResponse::success(stopped_status()))
+}
+
+/// Get whether the log stream server is running, with its URL and token
+#[tauri::command]
+pub async fn get_log_stream_status(app: AppHandle) -> Result<ApiResponse<LogStreamStatus>, String> {
+    let state = app.state::<LogStreamState>().inner().clone();
+    let guard = state.lock().await;
+
+    Ok(ApiResponse::success(
+        guard
+            .as_ref()
+            .map(LogStreamHandle::status)
+            .unwrap_or_else(stopped_status),
+    ))
+}
+
+fn stopped_status() -> LogStreamStatus {
+    LogStreamStatus {
+        running: false,
+        url: None,
+        token: None,
+        started_at: None,
+    }
+}
+
+/// A request the server understood
+#[derive(Debug, PartialEq)]
+enum Route {
+    ListRuns,
+    RunLogs { run_id: String, from_offset: u64 },
+}
+
+async fn handle_connection(
+    app: &AppHandle,
+    stream: TcpStream,
+    token: &str,
+) -> Result<(), AppError> {
+    let mut reader = BufReader::new(stream);
+    let request = read_http_request(&mut reader).await?;
+    let stream = reader.into_inner();
+
+    let route = match route_request(&request, token) {
+        Ok(route) => route,
+        Err((status, message)) => {
+            log::warn!(
+                "Rejected log stream request {} {}: {}",
+                request.method,
+                request.path.split('?').next().unwrap_or_default(),
+                message
+            );
+            return write_json_response(stream, status, &json!({ "error": message })).await;
+        }
+    };
+
+    match route {
+        Route::ListRuns => {
+            let runs = buffered_run_ids(app).await;
+            write_json_response(stream, 200, &json!({ "runs": runs })).await
+        }
+        Route::RunLogs {
+            run_id,
+            from_offset,
+        } => match run_log_buffer(app, &run_id).await {
+            Some(buffer) => {
+                log::info!("Streaming logs of run {} to an external client", run_id);
+                stream_run_log(stream, &buffer, &run_id, from_offset).await
+            }
+            None => {
+                let message = format!("Run {} is not streaming logs", run_id);
+                write_json_response(stream, 404, &json!({ "error": message })).await
+            }
+        },
+    }
+}
+
+/// Check method and token, then match the path
+fn route_request(request: &HttpRequest, token: &str) -> Result<Route, (u16, String)> {
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", request.path))
+        .map_err(|_| (400, "Malformed request path".to_string()))?;
+
+    let presented = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| {
+            url.query_pairs()
+                .find(|(name, _)| name == "token")
+                .map(|(_, value)| value.to_string())
+        });
+    if !presented
+        .is_some_and(|presented| constant_time_eq(presented.trim().as_bytes(), token.as_bytes()))
+    {
+        return Err((401, "Missing or invalid token".to_string()));
+    }
+    if request.method != "GET" {
+        return Err((405, "Only GET is supported".to_string()));
+    }
+
+    let segments: Vec<&str> = url
+        .path()
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match segments.as_slice() {
+        ["runs"] => Ok(Route::ListRuns),
+        ["runs", run_id, "logs"] => {
+            // Reconnecting clients resume after the last event they saw
+            let from_offset = url
+                .query_pairs()
+                .find(|(name, _)| name == "from")
+                .and_then(|(_, value)| value.parse().ok())
+                .or_else(|| {
+                    request
+                        .header("last-event-id")
+                        .and_then(|id| id.trim().parse::<u64>().ok())
+                        .map(|id| id + 1)
+                })
+                .unwrap_or(0);
+            Ok(Route::RunLogs {
+                run_id: run_id.to_string(),
+                from_offset,
+            })
+        }
+        _ => Err((404, "Not found".to_string())),
+    }
+}
+
+/// Send the run's lines as events until it finishes or the client goes away
+async fn stream_run_log(
+    mut stream: TcpStream,
+    buffer: &RunLogBuffer,
+    run_id: &str,
+    mut offset: u64,
+) -> Result<(), AppError> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+
+    loop {
+        let deadline = tokio::time::Instant::now() + KEEP_ALIVE_INTERVAL;
+        let tail = buffer.follow(run_id, offset, deadline).await;
+
+        let mut chunk = String::new();
+        for line in &tail.lines {
+            chunk.push_str(&format!(
+                "id: {}\nevent: log\ndata: {}\n\n",
+                line.offset,
+                serde_json::to_string(line)?
+            ));
+        }
+        if tail.finished && tail.lines.is_empty() {
+            chunk.push_str(&format!(
+                "event: end\ndata: {}\n\n",
+                json!({ "runId": run_id, "nextOffset": tail.next_offset })
+            ));
+        } else if chunk.is_empty() {
+            chunk.push_str(": keep-alive\n\n");
+        }
+
+        stream.write_all(chunk.as_bytes()).await?;
+        stream.flush().await?;
+        if tail.finished && tail.lines.is_empty() {
+            break;
+        }
+        offset = tail.next_offset;
+    }
+
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "secret-token";
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> HttpRequest {
+        HttpRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_route_requires_token() {
+        let err = route_request(&request("/runs", &[]), TOKEN).unwrap_err();
+        assert_eq!(err.0, 401);
+        let err = route_request(&request("/runs?token=wrong", &[]), TOKEN).unwrap_err();
+        assert_eq!(err.0, 401);
+
+        assert_eq!(
+            route_request(&request("/runs?token=secret-token", &[]), TOKEN),
+            Ok(Route::ListRuns)
+        );
+        assert_eq!(
+            route_request(
+                &request("/runs/", &[("authorization", "Bearer secret-token")]),
+                TOKEN
+            ),
+            Ok(Route::ListRuns)
+        );
+    }
+
+    #[test]
+    fn test_route_run_logs() {
+        let auth = [("authorization", "Bearer secret-token")];
+        assert_eq!(
+            route_request(&request("/runs/run_1/logs", &auth), TOKEN),
+            Ok(Route::RunLogs {
+                run_id: "run_1".to_string(),
+                from_offset: 0
+            })
+        );
+        assert_eq!(
+            route_request(&request("/runs/run_1/logs?from=42", &auth), TOKEN),
+            Ok(Route::RunLogs {
+                run_id: "run_1".to_string(),
+                from_offset: 42
+            })
+        );
+
+        let resumed = request(
+            "/runs/run_1/logs",
+            &[
+                ("authorization", "Bearer secret-token"),
+                ("last-event-id", "9"),
+            ],
+        );
+        assert_eq!(
+            route_request(&resumed, TOKEN),
+            Ok(Route::RunLogs {
+                run_id: "run_1".to_string(),
+                from_offset: 10
+            })
+        );
+
+        assert_eq!(
+            route_request(&request("/runs/run_1/other", &auth), TOKEN)
+                .unwrap_err()
+                .0,
+            404
+        );
+        let mut post = request("/runs", &auth);
+        post.method = "POST".to_string();
+        assert_eq!(route_request(&post, TOKEN).unwrap_err().0, 405);
+    }
+}
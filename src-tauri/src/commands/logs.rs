@@ -0,0 +1,224 @@
+//! App log commands
+//! Lets the UI read the backend's structured log files and change the log level
+
+use crate::logging::{LoggingState, LOG_FILE_PREFIX};
+use crate::models::{ApiResponse, AppError, AppLogEntry, AppLogFilter};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_LOG_LIMIT: usize = 500;
+const MAX_LOG_LIMIT: usize = 5000;
+
+/// Get the most recent backend log entries matching the filter, oldest first
+#[tauri::command]
+pub async fn get_app_logs(
+    app: AppHandle,
+    filter: Option<AppLogFilter>,
+    limit: Option<usize>,
+) -> Result<ApiResponse<Vec<AppLogEntry>>, String> {
+    let logging = match app.try_state::<LoggingState>() {
+        Some(logging) => logging,
+        None => {
+            return Ok(ApiResponse::error(
+                "LOGGING_UNAVAILABLE".to_string(),
+                "File logging is not initialized".to_string(),
+            ))
+        }
+    };
+
+    let filter = filter.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).min(MAX_LOG_LIMIT);
+
+    match read_app_logs(&logging.log_dir, &filter, limit) {
+        Ok(entries) => Ok(ApiResponse::success(entries)),
+        Err(e) => {
+            log::error!("Failed to read app logs: {}", e);
+            Ok(ApiResponse::error(
+                "LOG_READ_ERROR".to_string(),
+                format!("Failed to read app logs: {}", e),
+            ))
+        }
+    }
+}
+
+/// Change the backend log level at runtime
+#[tauri::command]
+pub async fn set_log_level(app: AppHandle, level: String) -> Result<ApiResponse<String>, String> {
+    let logging = match app.try_state::<LoggingState>() {
+        Some(logging) => logging,
+        None => {
+            return Ok(ApiResponse::error(
+                "LOGGING_UNAVAILABLE".to_string(),
+                "Logging is not initialized".to_string(),
+            ))
+        }
+    };
+
+    match logging.set_level(&level) {
+        Ok(()) => {
+            log::info!("Log level set to {}", level);
+            Ok(ApiResponse::success(level))
+        }
+        Err(e) => Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+/// Read log files newest first until `limit` matching entries are collected
+pub fn read_app_logs(
+    log_dir: &Path,
+    filter: &AppLogFilter,
+    limit: usize,
+) -> Result<Vec<AppLogEntry>, AppError> {
+    let mut files: Vec<_> = fs::read_dir(log_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    // Daily suffixes sort chronologically
+    files.sort();
+
+    let mut entries = Vec::new();
+    for path in files.iter().rev() {
+        let contents = fs::read_to_string(path)?;
+        let mut matching: Vec<AppLogEntry> = contents
+            .lines()
+            .filter_map(parse_log_line)
+            .filter(|entry| matches_filter(entry, filter))
+            .collect();
+
+        let take = limit.saturating_sub(entries.len()).min(matching.len());
+        entries.extend(matching.drain(matching.len() - take..).rev());
+        if entries.len() >= limit {
+            break;
+        }
+    }
+
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Parse one JSON line written by the file layer
+fn parse_log_line(line: &str) -> Option<AppLogEntry> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let text = |value: Option<&Value>| value.and_then(|v| v.as_str()).map(|s| s.to_string());
+    let span_field = |key: &str| text(value.get("span").and_then(|span| span.get(key)));
+
+    Some(AppLogEntry {
+        timestamp: text(value.get("timestamp")).unwrap_or_default(),
+        level: text(value.get("level")).unwrap_or_default(),
+        target: text(value.get("target")).unwrap_or_default(),
+        message: text(value.get("fields").and_then(|f| f.get("message"))).unwrap_or_default(),
+        run_id: span_field("run_id"),
+        session_id: span_field("session_id"),
+    })
+}
+
+/// Severity rank where lower is more severe; unknown levels rank as trace
+fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_lowercase().as_str() {
+        "error" => 1,
+        "warn" | "warning" => 2,
+        "info" => 3,
+        "debug" => 4,
+        _ => 5,
+    }
+}
+
+fn matches_filter(entry: &AppLogEntry, filter: &AppLogFilter) -> bool {
+    filter
+        .level
+        .as_deref()
+        .is_none_or(|level| level_rank(&entry.level) <= level_rank(level))
+        && filter
+            .run_id
+            .as_deref()
+            .is_none_or(|run_id| entry.run_id.as_deref() == Some(run_id))
+        && filter
+            .target
+            .as_deref()
+            .is_none_or(|target| entry.target.starts_with(target))
+        && filter.contains.as_deref().is_none_or(|needle| {
+            entry
+                .message
+                .to_lowercase()
+                .contains(&needle.to_lowercase())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r#"{"timestamp":"2024-01-01T00:00:00.000000Z","level":"WARN","fields":{"message":"Process exited"},"target":"mvp_tauri_eliza_cli_lib::commands::process","span":{"run_id":"run_1","name":"run"}}"#;
+
+    #[test]
+    fn test_parse_log_line() {
+        let entry = parse_log_line(LINE).unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "Process exited");
+        assert_eq!(entry.run_id, Some("run_1".to_string()));
+        assert_eq!(entry.session_id, None);
+        assert!(parse_log_line("not json").is_none());
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let entry = parse_log_line(LINE).unwrap();
+
+        let by_level = |level: &str| AppLogFilter {
+            level: Some(level.to_string()),
+            ..AppLogFilter::default()
+        };
+        assert!(matches_filter(&entry, &by_level("info")));
+        assert!(!matches_filter(&entry, &by_level("error")));
+
+        let filter = AppLogFilter {
+            run_id: Some("run_2".to_string()),
+            ..AppLogFilter::default()
+        };
+        assert!(!matches_filter(&entry, &filter));
+
+        let filter = AppLogFilter {
+            contains: Some("EXITED".to_string()),
+            target: Some("mvp_tauri_eliza_cli_lib::commands".to_string()),
+            ..AppLogFilter::default()
+        };
+        assert!(matches_filter(&entry, &filter));
+    }
+
+    #[test]
+    fn test_read_app_logs_limit() {
+        let dir = std::env::temp_dir().join(format!("app_logs_{}", rand::random::<u32>()));
+        fs::create_dir_all(&dir).unwrap();
+        let line = |message: &str| LINE.replace("Process exited", message);
+
+        fs::write(
+            dir.join(format!("{}.2024-01-01", LOG_FILE_PREFIX)),
+            format!("{}\n{}\n", line("a"), line("b")),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(format!("{}.2024-01-02", LOG_FILE_PREFIX)),
+            format!("{}\n", line("c")),
+        )
+        .unwrap();
+
+        let messages: Vec<_> = read_app_logs(&dir, &AppLogFilter::default(), 2)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect();
+        assert_eq!(messages, vec!["b", "c"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+//! Offline development mode
+//! A tiny local HTTP stub of the Sandbox API (/health, chat completions, CLI telemetry,
+//! agents, secrets) so the app can be developed and demoed without real credentials
+
+use crate::commands::compression::ContentEncoding;
+use crate::commands::local_http::{bind_loopback, read_http_request, serve, write_response};
+use crate::models::{ApiResponse, AppError, SandboxConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::sync::{oneshot, Mutex};
+
+/// API key the stub hands out; well-formed so config validation passes
+pub const MOCK_API_KEY: &str =
+    "eliza_0000000000000000000000000000000000000000000000000000000000000000";
+const MOCK_MODEL: &str = "mock-model";
+const MOCK_AGENT_ID: &str = "mock-agent";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockSandboxStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub config: Option<SandboxConfig>,
+    pub started_at: Option<String>,
+}
+
+pub struct MockSandboxHandle {
+    port: u16,
+    started_at: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl MockSandboxHandle {
+    fn status(&self) -> MockSandboxStatus {
+        MockSandboxStatus {
+            running: true,
+            port: Some(self.port),
+            config: Some(mock_config(self.port)),
+            started_at: Some(self.started_at.clone()),
+        }
+    }
+}
+
+// The running stub server, if offline mode is enabled
+pub type MockSandboxState = Arc<Mutex<Option<MockSandboxHandle>>>;
+
+/// Initialize the mock sandbox state (called from main)
+pub fn init_mock_sandbox_state() -> MockSandboxState {
+    Arc::new(Mutex::new(None))
+}
+
+/// Get the mock sandbox state for the app
+pub fn get_mock_sandbox_state(app: &AppHandle) -> MockSandboxState {
+    app.state::<MockSandboxState>().inner().clone()
+}
+
+/// Start the local Sandbox stub and return a config pointing at it
+#[tauri::command]
+pub async fn start_mock_sandbox(app: AppHandle) -> Result<ApiResponse<MockSandboxStatus>, String> {
+    let state = get_mock_sandbox_state(&app);
+    let mut guard = state.lock().await;
+
+    if let Some(handle) = guard.as_ref() {
+        log::info!("Mock sandbox already running on port {}", handle.port);
+        return Ok(ApiResponse::success(handle.status()));
+    }
+
+    let (listener, port) = match bind_loopback().await {
+        Ok(bound) => bound,
+        Err(e) => {
+            log::error!("Failed to bind mock sandbox: {}", e);
+            return Ok(ApiResponse::error(
+                "MOCK_SERVER_ERROR".to_string(),
+                format!("Failed to start mock sandbox: {}", e),
+            ));
+        }
+    };
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    tokio::spawn(serve(
+        "Mock sandbox",
+        listener,
+        shutdown_rx,
+        handle_connection,
+    ));
+
+    let handle = MockSandboxHandle {
+        port,
+        started_at: crate::models::current_timestamp(),
+        shutdown,
+    };
+    let status = handle.status();
+    *guard = Some(handle);
+
+    log::info!("Mock sandbox listening on http://127.0.0.1:{}", port);
+    Ok(ApiResponse::success(status))
+}
+
+/// Stop the local Sandbox stub
+#[tauri::command]
+pub async fn stop_mock_sandbox(app: AppHandle) -> Result<ApiResponse<MockSandboxStatus>, String> {
+    let state = get_mock_sandbox_state(&app);
+
+    if let Some(handle) = state.lock().await.take() {
+        let _ = handle.shutdown.send(());
+        log::info!("Mock sandbox on port {} stopped", handle.port);
+    }
+
+    Ok(ApiResponse::success(stopped_status()))
+}
+
+/// Get whether the local Sandbox stub is running and where
+#[tauri::command]
+pub async fn get_mock_sandbox_status(
+    app: AppHandle,
+) -> Result<ApiResponse<MockSandboxStatus>, String> {
+    let state = get_mock_sandbox_state(&app);
+    let guard = state.lock().await;
+
+    Ok(ApiResponse::success(
+        guard
+            .as_ref()
+            .map(MockSandboxHandle::status)
+            .unwrap_or_else(stopped_status),
+    ))
+}
+
+/// Sandbox config that targets the stub on the given port
+pub fn mock_config(port: u16) -> SandboxConfig {
+    SandboxConfig::new(
+        format!("http://127.0.0.1:{}/api/v1", port),
+        MOCK_API_KEY.to_string(),
+    )
+    .with_default_model(MOCK_MODEL.to_string())
+}
+
+fn stopped_status() -> MockSandboxStatus {
+    MockSandboxStatus {
+        running: false,
+        port: None,
+        config: None,
+        started_at: None,
+    }
+}
+
+/// Read one HTTP/1.1 request and write the stub response
+async fn handle_connection(stream: TcpStream) -> Result<(), AppError> {
+    let mut reader = BufReader::new(stream);
+    let request = read_http_request(&mut reader).await?;
+    let body = match request
+        .header("content-encoding")
+        .and_then(ContentEncoding::from_header)
+    {
+        Some(encoding) => encoding.decode(&request.body)?,
+        None => request.body.clone(),
+    };
+
+    let (status, payload) = mock_response(&request.method, &request.path, &body);
+    log::debug!(
+        "Mock sandbox: {} {} -> {}",
+        request.method,
+        request.path,
+        status
+    );
+
+    write_response(
+        &mut reader.into_inner(),
+        status,
+        &[
+            ("Content-Type", "application/json"),
+            ("X-API-Version", "mock"),
+        ],
+        payload.to_string().as_bytes(),
+    )
+    .await
+}
+
+fn mock_response(method: &str, path: &str, body: &[u8]) -> (u16, serde_json::Value) {
+    let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+
+    match (method, path) {
+        ("GET", "/health") | ("GET", "/api/v1/health") => {
+            (200, json!({ "status": "ok", "mock": true }))
+        }
+        ("POST", "/api/v1/chat/completions") => {
+            let request: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+            let prompt = request
+                .get("messages")
+                .and_then(|messages| messages.as_array())
+                .and_then(|messages| messages.last())
+                .and_then(|message| message.get("content"))
+                .and_then(|content| content.as_str())
+                .unwrap_or_default();
+
+            (
+                200,
+                json!({
+                    "id": "mock-completion",
+                    "object": "chat.completion",
+                    "model": request.get("model").cloned().unwrap_or(json!(MOCK_MODEL)),
+                    "choices": [{
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": format!("OK (mock response to: {})", prompt)
+                        },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0 }
+                }),
+            )
+        }
+        ("POST", "/telemetry/cli") | ("POST", "/api/v1/telemetry/cli") => {
+            (202, json!({ "accepted": true }))
+        }
+        // Device logins are approved at once
+        ("POST", "/api/v1/auth/device/code") => (
+            200,
+            json!({
+                "device_code": "mock-device-code",
+                "user_code": "MOCK-CODE",
+                "verification_uri": "http://127.0.0.1/device",
+                "expires_in": 600,
+                "interval": 1
+            }),
+        ),
+        ("POST", "/api/v1/auth/device/token") => (200, json!({ "api_key": MOCK_API_KEY })),
+        ("GET", "/api/v1/models") => (200, json!({ "data": [{ "id": MOCK_MODEL }] })),
+        ("GET", "/api/v1/usage") => (
+            200,
+            json!({ "usage": { "tokens_used": 0, "credits_used": 0.0, "credits_remaining": 100.0 } }),
+        ),
+        ("GET", "/api/v1/agents") => (200, json!({ "agents": [mock_agent()] })),
+        ("GET", path) if path.strip_prefix("/api/v1/agents/") == Some(MOCK_AGENT_ID) => {
+            (200, json!({ "agent": mock_agent() }))
+        }
+        ("GET", "/api/v1/secrets") => (200, json!({ "secrets": ["OPENAI_API_KEY"] })),
+        ("PUT", "/api/v1/secrets") => (200, json!({ "updated": true })),
+        ("POST", "/api/v1/agents") | ("PUT", _) if path.starts_with("/api/v1/agents") => {
+            // Deployments come up immediately; echo the uploaded character's name
+            let request: serde_json::Value = serde_json::from_slice(body).unwrap_or_default();
+            let mut agent = mock_agent();
+            if let Some(name) = request.get("name").filter(|name| name.is_string()) {
+                agent["name"] = name.clone();
+            }
+            (200, json!({ "agent": agent }))
+        }
+        _ => (404, json!({ "error": "Not found", "path": path })),
+    }
+}
+
+fn mock_agent() -> serde_json::Value {
+    json!({
+        "id": MOCK_AGENT_ID,
+        "name": "Mock Agent",
+        "status": "running",
+        "url": "http://127.0.0.1/agents/mock-agent",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_config_is_valid() {
+        let config = mock_config(4321);
+        assert!(config.is_valid());
+        assert_eq!(config.base_url, "http://127.0.0.1:4321/api/v1");
+    }
+
+    #[test]
+    fn test_mock_response_routes() {
+        assert_eq!(mock_response("GET", "/health", b"").0, 200);
+        assert_eq!(mock_response("POST", "/api/v1/telemetry/cli", b"{}").0, 202);
+        assert_eq!(mock_response("GET", "/unknown", b"").0, 404);
+        assert_eq!(
+            mock_response("GET", "/api/v1/agents/mock-agent", b"").0,
+            200
+        );
+        assert_eq!(mock_response("GET", "/api/v1/agents/other", b"").0, 404);
+
+        let (status, payload) = mock_response("POST", "/api/v1/agents", br#"{"name":"Ada"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(payload["agent"]["name"], "Ada");
+
+        let body = br#"{"messages":[{"role":"user","content":"ping"}]}"#;
+        let (status, payload) = mock_response("POST", "/api/v1/chat/completions", body);
+        assert_eq!(status, 200);
+        assert_eq!(
+            payload["choices"][0]["message"]["content"],
+            "OK (mock response to: ping)"
+        );
+    }
+}
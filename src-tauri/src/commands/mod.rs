@@ -1,25 +1,213 @@
 //! Command modules for Tauri IPC
 //! Exports all command functions for the Tauri application
 
+pub mod activity;
+pub mod agents;
+pub mod api_cache;
+pub mod api_key_check;
+pub mod app_lock;
+pub mod approvals;
+pub mod args;
+pub mod audit;
+pub mod authorization;
+pub mod benchmark;
+pub mod budget;
+pub mod character_assets;
+pub mod character_lint;
+pub mod character_revisions;
+pub mod characters;
+pub mod chat;
+pub mod cli_cache;
+pub mod clipboard;
+pub mod cloud;
+pub mod compression;
 pub mod config;
+pub mod connectivity;
+pub mod dependency_audit;
+pub mod doctor;
+pub mod egress;
+pub mod eval;
+pub mod experiments;
+pub mod failure_diagnosis;
+pub mod file_drop;
+pub mod file_picker;
+pub mod git;
+pub mod global_shortcuts;
+pub mod groups;
+pub mod history;
+pub mod install_progress;
+pub mod keyring;
+pub mod knowledge;
+pub mod kv;
+pub mod local_http;
+pub mod locale;
+pub mod log_stream;
+pub mod logs;
+pub mod mock_server;
+pub mod network_capture;
+pub mod onboarding;
+pub mod packages;
+pub mod path_jail;
+pub mod pins;
+pub mod ports;
 pub mod preflight;
+pub mod preflight_history;
 pub mod process;
+pub mod project_config;
+pub mod project_env;
+pub mod project_watch;
+pub mod prompt_templates;
+pub mod quick_actions;
+pub mod reports;
+pub mod resolver;
+pub mod run_logs;
+pub mod run_queue;
+pub mod run_variables;
+pub mod sandbox_http;
+pub mod sandbox_login;
+pub mod scenarios;
+pub mod scheduler;
+pub mod secrets;
+pub mod session;
+pub mod shortcuts;
+pub mod simulation;
+pub mod sleep_inhibitor;
+pub mod stats;
+pub mod storage;
+pub mod support;
 pub mod telemetry;
 pub mod terminal;
+pub mod watchdog;
+pub mod webhooks;
+pub mod windows;
 
 // Re-export all command functions for easy access
+pub use activity::get_activity_feed;
+pub use agents::{list_agents, remove_agent, save_agent, set_agent_start_on_launch};
+pub use api_cache::invalidate_api_cache;
+pub use api_key_check::validate_api_key;
+pub use app_lock::{disable_app_lock, enable_app_lock, get_app_lock_status, lock_app, unlock_app};
+pub use approvals::{get_pending_approvals, resolve_approval};
+pub use audit::get_audit_log;
+pub use authorization::{get_permission_profile, set_permission_profile};
+pub use benchmark::{benchmark_pong, run_self_benchmark};
+pub use budget::get_budget_usage;
+pub use character_assets::{
+    add_character_asset, list_character_assets, remove_character_asset, set_character_avatar,
+};
+pub use character_lint::lint_character;
+pub use character_revisions::{
+    diff_character_revisions, list_character_revisions, rollback_character,
+};
+pub use chat::send_agent_message;
+pub use cli_cache::clean_cli_caches;
+pub use clipboard::copy_to_clipboard;
+pub use cloud::{
+    deploy_character_to_cloud, get_cloud_agent, get_sandbox_usage, import_cloud_agent,
+    list_cloud_agents, list_sandbox_models,
+};
 pub use config::{
-    clear_sandbox_config, load_sandbox_config, save_sandbox_config, test_api_prompt,
-    test_sandbox_connection,
+    clear_sandbox_config, delete_config_profile, export_sandbox_config, import_sandbox_config,
+    list_config_backups, list_config_profiles, load_sandbox_config, restore_config_backup,
+    save_sandbox_config, set_active_profile, set_allowed_custom_subcommands,
+    set_trusted_project_hosts, test_api_prompt, test_api_prompt_streaming, test_sandbox_connection,
+};
+pub use connectivity::{
+    get_connectivity_status, start_connectivity_monitor, stop_connectivity_monitor,
+};
+pub use dependency_audit::audit_project_dependencies;
+pub use doctor::run_doctor;
+pub use experiments::{
+    get_experiment, get_experiment_results, list_experiments, start_eval_matrix,
 };
+pub use failure_diagnosis::diagnose_run_failure;
+pub use file_picker::pick_character_file;
+pub use git::{get_project_git_status, git_commit_project, git_diff_file};
+pub use global_shortcuts::{list_shortcuts, set_shortcut};
+pub use groups::{get_run_group_status, kill_run_group, start_run_group, stop_run_group};
+pub use history::{add_run_annotation, get_run_record, set_run_note};
+pub use knowledge::{
+    add_character_knowledge, list_character_knowledge, remove_character_knowledge,
+};
+pub use kv::{kv_delete, kv_get, kv_list, kv_set};
+pub use locale::{get_locale, set_locale};
+pub use log_stream::{get_log_stream_status, start_log_stream_server, stop_log_stream_server};
+pub use logs::{get_app_logs, set_log_level};
+pub use mock_server::{get_mock_sandbox_status, start_mock_sandbox, stop_mock_sandbox};
+pub use network_capture::get_run_network_capture;
+pub use onboarding::{get_onboarding_state, reset_onboarding};
+pub use packages::{export_character_package, import_character_package};
+pub use path_jail::{get_allowed_roots, set_allowed_roots};
+pub use pins::{list_pinned, pin_item, unpin_item};
 pub use preflight::preflight_check;
-pub use process::{kill_eliza_run, start_eliza_run, start_eliza_run_streaming, stop_eliza_run};
+pub use preflight_history::{get_preflight_changes, get_preflight_history};
+pub use process::{
+    interrupt_eliza_run, kill_eliza_run, start_eliza_run, start_eliza_run_streaming, stop_eliza_run,
+};
+pub use project_config::inspect_project_config;
+pub use project_env::{forget_project_env, import_project_env, inspect_project_env};
+pub use project_watch::{list_project_watches, start_project_watch, stop_project_watch};
+pub use prompt_templates::{
+    insert_prompt_template, list_prompt_templates, remove_prompt_template, render_prompt_template,
+    save_prompt_template,
+};
+pub use quick_actions::{get_quick_actions, run_quick_action};
+pub use reports::generate_run_report;
+pub use resolver::{get_cli_resolution_report, refresh_cli_resolution};
+pub use run_logs::{set_run_log_filter, tail_run_log};
+pub use run_queue::get_run_queue;
+pub use sandbox_http::get_rate_limit_status;
+pub use sandbox_login::{cancel_sandbox_login, start_sandbox_login};
+pub use scenarios::{get_scenario_result, run_scenario};
+pub use scheduler::{get_run_schedule, schedule_runs};
+pub use secrets::{
+    delete_local_secret, list_cloud_secrets, list_local_secrets, push_secrets_to_cloud,
+    set_local_secret,
+};
+pub use session::get_startup_report;
+pub use shortcuts::create_agent_shortcut;
+pub use stats::get_backend_stats;
+pub use storage::{get_storage_usage, vacuum_storage};
+pub use support::create_support_bundle;
 pub use telemetry::{get_device_id, post_telemetry};
 pub use terminal::{
     cancel_terminal_command, change_terminal_cwd, cleanup_terminal_processes,
     execute_terminal_command, get_terminal_cwd, get_terminal_processes, initialize_terminal,
 };
+pub use webhooks::{
+    get_webhook_events, get_webhook_listener_status, start_webhook_listener, stop_webhook_listener,
+};
+pub use windows::{close_window, list_windows, open_window, reset_window_layout};
 
 // Registry initialization functions
+pub use activity::init_activity_log;
+pub use api_cache::init_api_cache;
+pub use app_lock::init_app_lock;
+pub use approvals::init_approval_registry;
+pub use audit::init_audit_log;
+pub use authorization::init_permission_state;
+pub use benchmark::init_benchmark_pings;
+pub use budget::init_budget_ledger;
+pub use connectivity::init_connectivity_monitor;
+pub use global_shortcuts::init_global_shortcuts;
+pub use groups::init_run_group_registry;
+pub use history::init_run_history;
+pub use kv::init_kv_store;
+pub use log_stream::init_log_stream_state;
+pub use mock_server::init_mock_sandbox_state;
+pub use network_capture::init_network_capture_store;
+pub use ports::init_port_registry;
+pub use preflight_history::init_preflight_history;
 pub use process::init_process_registry;
+pub use project_watch::init_watch_registry;
+pub use resolver::init_cli_resolution_cache;
+pub use run_logs::init_run_log_store;
+pub use run_queue::init_run_queue;
+pub use sandbox_login::init_sandbox_login_state;
+pub use scheduler::init_run_schedule_registry;
+pub use session::init_session_state;
+pub use sleep_inhibitor::init_sleep_inhibitor;
+pub use stats::init_backend_counters;
 pub use terminal::init_terminal_registry;
+pub use webhooks::init_webhook_listener_state;
+pub use windows::init_window_layout;
@@ -0,0 +1,210 @@
+//! Onboarding progress
+//! Tracks which setup steps of the first-run wizard are done so it can resume where the user
+//! left off. Steps are completed by the features themselves (preflight, config save,
+//! connection test, a finished run) and kept in the key-value store under `onboarding`
+
+use crate::commands::config::load_config_from_file;
+use crate::commands::kv::{load_value, update_value};
+use crate::commands::stats::emit_event;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, OnboardingState, OnboardingStep,
+    OnboardingStepCompleted, OnboardingStepStatus, RunResult, RunStatus,
+};
+use serde_json::Value;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+
+const ONBOARDING_NAMESPACE: &str = "onboarding";
+const STEPS_KEY: &str = "steps";
+
+/// Completion time of each finished step
+type CompletedSteps = BTreeMap<OnboardingStep, String>;
+
+/// Onboarding progress; a configuration that was saved before the progress was (for example
+/// by an earlier install) counts as the config step being done
+#[tauri::command]
+pub async fn get_onboarding_state(app: AppHandle) -> Result<ApiResponse<OnboardingState>, String> {
+    let result = async {
+        let completed = read_steps(&app).await?;
+        if !completed.contains_key(&OnboardingStep::ConfigSaved) {
+            let saved = load_config_from_file(&app)
+                .await
+                .ok()
+                .flatten()
+                .is_some_and(|config| !config.base_url.is_empty());
+            if saved {
+                complete_step(&app, OnboardingStep::ConfigSaved).await;
+                return read_steps(&app).await.map(|steps| onboarding_state(&steps));
+            }
+        }
+        Ok::<_, AppError>(onboarding_state(&completed))
+    }
+    .await;
+
+    match result {
+        Ok(state) => Ok(ApiResponse::success(state)),
+        Err(e) => {
+            log::error!("Failed to read onboarding state: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to read onboarding state: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Forget all completed steps so the wizard starts over
+#[tauri::command]
+pub async fn reset_onboarding(app: AppHandle) -> Result<ApiResponse<OnboardingState>, String> {
+    let result = update_value(
+        &app,
+        ONBOARDING_NAMESPACE.to_string(),
+        STEPS_KEY.to_string(),
+        |_| Ok(serde_json::to_value(CompletedSteps::new())?),
+    )
+    .await;
+
+    match result {
+        Ok(_) => {
+            log::info!("Onboarding progress reset");
+            Ok(ApiResponse::success(onboarding_state(
+                &CompletedSteps::new(),
+            )))
+        }
+        Err(e) => {
+            log::error!("Failed to reset onboarding: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to reset onboarding: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Mark a step done, emitting `onboarding-step-completed` the first time; failures are only
+/// logged since onboarding must never get in the way of the feature completing it
+pub(crate) async fn complete_step(app: &AppHandle, step: OnboardingStep) {
+    let mut newly_completed = false;
+    let result = update_value(
+        app,
+        ONBOARDING_NAMESPACE.to_string(),
+        STEPS_KEY.to_string(),
+        |current| {
+            let mut steps = parse_steps(current)?;
+            if let Entry::Vacant(entry) = steps.entry(step) {
+                entry.insert(current_timestamp());
+                newly_completed = true;
+            }
+            Ok(serde_json::to_value(steps)?)
+        },
+    )
+    .await;
+
+    match result {
+        Ok(entry) if newly_completed => {
+            log::info!("Onboarding step completed: {}", step.name());
+            let steps = parse_steps(Some(entry.value)).unwrap_or_default();
+            emit_event(
+                app,
+                "onboarding-step-completed",
+                OnboardingStepCompleted {
+                    step,
+                    state: onboarding_state(&steps),
+                },
+            );
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to record onboarding step {}: {}", step.name(), e),
+    }
+}
+
+/// The first run that finishes successfully completes onboarding's last step; simulated
+/// runs don't count
+pub(crate) async fn note_finished_run(app: &AppHandle, result: &RunResult) {
+    if result.status == RunStatus::Completed && !result.spec.simulate {
+        complete_step(app, OnboardingStep::FirstRunCompleted).await;
+    }
+}
+
+async fn read_steps(app: &AppHandle) -> Result<CompletedSteps, AppError> {
+    parse_steps(load_value(app, ONBOARDING_NAMESPACE, STEPS_KEY).await?)
+}
+
+fn parse_steps(value: Option<Value>) -> Result<CompletedSteps, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value).map_err(|e| {
+            AppError::Config(format!("Saved onboarding progress is corrupted: {}", e))
+        }),
+        None => Ok(CompletedSteps::new()),
+    }
+}
+
+fn onboarding_state(completed: &CompletedSteps) -> OnboardingState {
+    let steps: Vec<OnboardingStepStatus> = OnboardingStep::ALL
+        .into_iter()
+        .map(|step| OnboardingStepStatus {
+            step,
+            completed_at: completed.get(&step).cloned(),
+        })
+        .collect();
+    let current_step = steps
+        .iter()
+        .find(|status| status.completed_at.is_none())
+        .map(|status| status.step);
+
+    OnboardingState {
+        steps,
+        current_step,
+        complete: current_step.is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_onboarding_state() {
+        let state = onboarding_state(&CompletedSteps::new());
+        assert_eq!(state.steps.len(), OnboardingStep::ALL.len());
+        assert_eq!(state.current_step, Some(OnboardingStep::PreflightPassed));
+        assert!(!state.complete);
+
+        // Steps can be completed out of order; the wizard resumes at the first open one
+        let completed = parse_steps(Some(json!({
+            "config_saved": "2024-01-01T00:00:00Z",
+            "preflight_passed": "2024-01-01T00:00:00Z",
+            "first_run_completed": "2024-01-02T00:00:00Z",
+        })))
+        .unwrap();
+        let state = onboarding_state(&completed);
+        assert_eq!(state.current_step, Some(OnboardingStep::ConnectionTested));
+        assert_eq!(
+            state.steps[3].completed_at.as_deref(),
+            Some("2024-01-02T00:00:00Z")
+        );
+
+        let all = OnboardingStep::ALL
+            .into_iter()
+            .map(|step| (step, current_timestamp()))
+            .collect();
+        let state = onboarding_state(&all);
+        assert!(state.complete);
+        assert!(state.current_step.is_none());
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        assert!(parse_steps(None).unwrap().is_empty());
+        assert!(parse_steps(Some(json!({ "made_coffee": "2024-01-01" }))).is_err());
+    }
+
+    #[test]
+    fn test_step_names() {
+        for step in OnboardingStep::ALL {
+            assert_eq!(serde_json::to_value(step).unwrap(), json!(step.name()));
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! Character packages
+//! Zip archives bundling a managed character with its assets (avatars, knowledge files)
+//! and a manifest of SHA-256 checksums that is verified on import
+
+use crate::commands::character_assets::{validate_asset, ASSETS_DIR};
+use crate::commands::character_revisions::REVISIONS_DIR;
+use crate::commands::characters::{
+    character_dir, character_name, load_character, save_character, unique_character_id,
+    validate_character, CHARACTER_FILE, METADATA_FILE,
+};
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, CharacterMetadata, CharacterPackage,
+    CharacterPackageFile, CharacterPackageManifest, ManagedCharacter,
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const MANIFEST_FILE: &str = "manifest.json";
+pub const PACKAGE_FORMAT_VERSION: u32 = 1;
+/// Largest total uncompressed size of a package
+pub const MAX_PACKAGE_BYTES: u64 = 100 * 1024 * 1024;
+/// Character fields that may point at a local image to bundle
+const ASSET_FIELDS: &[&str] = &["avatar", "image"];
+
+/// Package contents keyed by path inside the archive
+type PackageFiles = BTreeMap<String, Vec<u8>>;
+
+/// Export a managed character and its assets to a zip at `path` (a file, or a directory)
+#[tauri::command]
+pub async fn export_character_package(
+    app: AppHandle,
+    id: String,
+    path: String,
+) -> Result<ApiResponse<CharacterPackage>, String> {
+    log::info!("Exporting character {} to {}", id, path);
+
+    let result = (|| {
+        let mut character = load_character(&app, &id)?;
+        let files = collect_package_files(&character_dir(&app, &id)?, &mut character)?;
+        let name = character_name(&character).unwrap_or(&id).to_string();
+
+        let package_path = package_file_path(Path::new(&path), &id);
+        if let Some(parent) = package_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let manifest = write_package(File::create(&package_path)?, &id, &name, &files)?;
+
+        Ok::<_, AppError>(CharacterPackage {
+            path: package_path.to_string_lossy().to_string(),
+            size_bytes: fs::metadata(&package_path)?.len(),
+            manifest,
+        })
+    })();
+
+    match result {
+        Ok(package) => {
+            log::info!(
+                "Character package written: {} ({} files)",
+                package.path,
+                package.manifest.files.len()
+            );
+            Ok(ApiResponse::success(package))
+        }
+        Err(e) => {
+            log::error!("Failed to export character package: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to export character package: {}", e),
+            ))
+        }
+    }
+}
+
+/// Import a character package as a new managed character after verifying its checksums
+#[tauri::command]
+pub async fn import_character_package(
+    app: AppHandle,
+    path: String,
+) -> Result<ApiResponse<ManagedCharacter>, String> {
+    log::info!("Importing character package {}", path);
+
+    match import_package_file(&app, Path::new(&path)) {
+        Ok(character) => {
+            log::info!("Imported character package as {}", character.id);
+            Ok(ApiResponse::success(character))
+        }
+        Err(e) => {
+            log::error!("Failed to import character package: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to import character package: {}", e),
+            ))
+        }
+    }
+}
+
+/// Import a package file as a new managed character
+pub(crate) fn import_package_file(
+    app: &AppHandle,
+    path: &Path,
+) -> Result<ManagedCharacter, AppError> {
+    let (_, mut files) = read_package(File::open(path)?)?;
+    let character: Value = files
+        .remove(CHARACTER_FILE)
+        .map(|bytes| serde_json::from_slice(&bytes))
+        .transpose()?
+        .ok_or_else(|| AppError::CharacterError(format!("Package has no {}", CHARACTER_FILE)))?;
+    validate_character(&character)?;
+    for (name, contents) in &files {
+        if let Some(asset) = name.strip_prefix(&format!("{}/", ASSETS_DIR)) {
+            validate_asset(asset, contents)?;
+        }
+    }
+
+    let id = unique_character_id(app, character_name(&character).unwrap_or("character"))?;
+    let managed = save_character(
+        app,
+        &id,
+        &character,
+        &CharacterMetadata::default(),
+        Some("Imported from a character package"),
+    )?;
+
+    let dir = character_dir(app, &id)?;
+    for (name, contents) in files {
+        let target = dir.join(&name);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, contents)?;
+    }
+    Ok(managed)
+}
+
+/// Use the path as-is, or place `<id>.zip` inside it when it is a directory
+fn package_file_path(path: &Path, id: &str) -> PathBuf {
+    if path.is_dir() {
+        path.join(format!("{}.zip", id))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// The character JSON plus every asset in its directory; local images referenced by
+/// absolute path are pulled into `assets/` and the character is pointed at the copy
+fn collect_package_files(dir: &Path, character: &mut Value) -> Result<PackageFiles, AppError> {
+    let mut files = PackageFiles::new();
+    collect_dir(dir, dir, &mut files)?;
+
+    if let Some(object) = character.as_object_mut() {
+        for field in ASSET_FIELDS {
+            let Some(source) = object
+                .get(*field)
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+            else {
+                continue;
+            };
+            if !source.is_absolute() || !source.is_file() {
+                continue;
+            }
+            let Some(file_name) = source.file_name().map(|n| n.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+
+            let name = format!("{}/{}", ASSETS_DIR, file_name);
+            files.insert(name.clone(), fs::read(&source)?);
+            object.insert(field.to_string(), Value::String(name));
+        }
+    }
+
+    files.insert(
+        CHARACTER_FILE.to_string(),
+        serde_json::to_vec_pretty(character)?,
+    );
+
+    let total: u64 = files.values().map(|contents| contents.len() as u64).sum();
+    if total > MAX_PACKAGE_BYTES {
+        return Err(AppError::Quota(format!(
+            "Package would be {} bytes; the limit is {} bytes",
+            total, MAX_PACKAGE_BYTES
+        )));
+    }
+    Ok(files)
+}
+
+fn collect_dir(root: &Path, dir: &Path, files: &mut PackageFiles) -> Result<(), AppError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_dir(root, &path, files)?;
+            continue;
+        }
+
+        let name = package_name(path.strip_prefix(root).unwrap_or(&path));
+        // Revision history stays with the local copy
+        let skipped = name == CHARACTER_FILE
+            || name == METADATA_FILE
+            || name.ends_with(".tmp")
+            || name.starts_with(&format!("{}/", REVISIONS_DIR));
+        if !skipped {
+            files.insert(name, fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// Archive paths always use `/`, whatever the platform
+fn package_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn write_package<W: Write + Seek>(
+    writer: W,
+    id: &str,
+    name: &str,
+    files: &PackageFiles,
+) -> Result<CharacterPackageManifest, AppError> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_error = |e: zip::result::ZipError| AppError::Io(std::io::Error::other(e));
+
+    let mut manifest = CharacterPackageManifest {
+        format_version: PACKAGE_FORMAT_VERSION,
+        character_id: id.to_string(),
+        name: name.to_string(),
+        exported_at: current_timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: Vec::new(),
+    };
+
+    for (path, contents) in files {
+        zip.start_file(path.as_str(), options).map_err(zip_error)?;
+        zip.write_all(contents)?;
+        manifest.files.push(CharacterPackageFile {
+            path: path.clone(),
+            sha256: sha256_hex(contents),
+            size_bytes: contents.len() as u64,
+        });
+    }
+
+    zip.start_file(MANIFEST_FILE, options).map_err(zip_error)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    zip.finish().map_err(zip_error)?;
+    Ok(manifest)
+}
+
+/// Read a package, rejecting unsafe paths, unlisted files and checksum mismatches
+fn read_package<R: Read + Seek>(
+    reader: R,
+) -> Result<(CharacterPackageManifest, PackageFiles), AppError> {
+    let invalid =
+        |message: String| AppError::CharacterError(format!("Invalid package: {}", message));
+    let mut archive = ZipArchive::new(reader).map_err(|e| invalid(e.to_string()))?;
+
+    let manifest: CharacterPackageManifest = {
+        let entry = archive
+            .by_name(MANIFEST_FILE)
+            .map_err(|_| invalid(format!("missing {}", MANIFEST_FILE)))?;
+        serde_json::from_slice(&read_limited(entry, MAX_PACKAGE_BYTES)?)?
+    };
+    if manifest.format_version > PACKAGE_FORMAT_VERSION {
+        return Err(invalid(format!(
+            "format version {} is newer than supported version {}",
+            manifest.format_version, PACKAGE_FORMAT_VERSION
+        )));
+    }
+
+    let total: u64 = manifest.files.iter().map(|file| file.size_bytes).sum();
+    if total > MAX_PACKAGE_BYTES {
+        return Err(AppError::Quota(format!(
+            "Package holds {} bytes; the limit is {} bytes",
+            total, MAX_PACKAGE_BYTES
+        )));
+    }
+
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| invalid(e.to_string()))?;
+        let name = entry.name();
+        if entry.is_dir() || name == MANIFEST_FILE {
+            continue;
+        }
+        if !manifest.files.iter().any(|file| file.path == name) {
+            return Err(invalid(format!("'{}' is not listed in the manifest", name)));
+        }
+    }
+
+    let mut files = PackageFiles::new();
+    for file in &manifest.files {
+        validate_package_path(&file.path).map_err(invalid)?;
+        let entry = archive
+            .by_name(&file.path)
+            .map_err(|_| invalid(format!("'{}' is listed but missing", file.path)))?;
+        let contents = read_limited(entry, file.size_bytes)?;
+
+        if contents.len() as u64 != file.size_bytes || sha256_hex(&contents) != file.sha256 {
+            return Err(invalid(format!("checksum mismatch for '{}'", file.path)));
+        }
+        files.insert(file.path.clone(), contents);
+    }
+
+    Ok((manifest, files))
+}
+
+/// Read at most one byte past `limit`, so oversized entries fail the size check
+fn read_limited<R: Read>(reader: R, limit: u64) -> Result<Vec<u8>, AppError> {
+    let mut contents = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut contents)?;
+    Ok(contents)
+}
+
+/// Paths must stay inside the character directory and not replace its bookkeeping
+fn validate_package_path(path: &str) -> Result<(), String> {
+    let relative = Path::new(path);
+    let safe = !path.is_empty()
+        && !path.contains('\\')
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    if !safe {
+        return Err(format!("unsafe path '{}'", path));
+    }
+    if path == METADATA_FILE || path.starts_with(&format!("{}/", REVISIONS_DIR)) {
+        return Err(format!("'{}' may not be packaged", path));
+    }
+    Ok(())
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn package(files: &PackageFiles) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        write_package(&mut buffer, "ada", "Ada", files).unwrap();
+        buffer.into_inner()
+    }
+
+    fn sample_files() -> PackageFiles {
+        PackageFiles::from([
+            (
+                CHARACTER_FILE.to_string(),
+                serde_json::to_vec(&json!({ "name": "Ada" })).unwrap(),
+            ),
+            ("knowledge/notes.md".to_string(), b"# Notes".to_vec()),
+        ])
+    }
+
+    #[test]
+    fn test_package_round_trip() {
+        let files = sample_files();
+        let (manifest, read) = read_package(Cursor::new(package(&files))).unwrap();
+        assert_eq!(manifest.character_id, "ada");
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(read, files);
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_rejected() {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut manifest = write_package(&mut buffer, "ada", "Ada", &sample_files()).unwrap();
+
+        // Rewrite the package with a manifest that no longer matches the contents
+        manifest.files[1].sha256 = sha256_hex(b"something else");
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, contents) in sample_files() {
+            zip.start_file(path, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&contents).unwrap();
+        }
+        zip.start_file(MANIFEST_FILE, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        let tampered = zip.finish().unwrap().into_inner();
+
+        let err = read_package(Cursor::new(tampered)).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_validate_package_path() {
+        assert!(validate_package_path("assets/avatar.png").is_ok());
+        assert!(validate_package_path("../evil").is_err());
+        assert!(validate_package_path("/etc/passwd").is_err());
+        assert!(validate_package_path("a\\..\\b").is_err());
+        assert!(validate_package_path(METADATA_FILE).is_err());
+    }
+
+    #[test]
+    fn test_collect_package_files() {
+        let dir = std::env::temp_dir().join(format!("character-package-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("knowledge")).unwrap();
+        fs::write(dir.join(CHARACTER_FILE), "{}").unwrap();
+        fs::write(dir.join(METADATA_FILE), "{}").unwrap();
+        fs::write(dir.join("knowledge").join("faq.md"), "faq").unwrap();
+        let avatar = dir.join("avatar.png");
+        fs::write(&avatar, [0x89, b'P', b'N', b'G']).unwrap();
+
+        let mut character = json!({ "name": "Ada", "avatar": avatar.to_string_lossy() });
+        let files = collect_package_files(&dir, &mut character).unwrap();
+
+        assert_eq!(character["avatar"], "assets/avatar.png");
+        let names: Vec<&str> = files.keys().map(|name| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "assets/avatar.png",
+                "avatar.png",
+                CHARACTER_FILE,
+                "knowledge/faq.md"
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! Path jail
+//! An optional set of allowed root directories: run working directories, character files
+//! and terminal cwds outside them are rejected with a policy error. The app data directory
+//! is always allowed, and no configured roots means no restriction
+
+use crate::commands::audit::record_audit;
+use crate::models::{ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin, RunSpec};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const ALLOWED_ROOTS_FILE: &str = "allowed-roots.json";
+const MAX_ROOTS: usize = 64;
+
+/// Configured allowed roots; empty when paths are unrestricted
+#[tauri::command]
+pub async fn get_allowed_roots(app: AppHandle) -> Result<ApiResponse<Vec<String>>, String> {
+    match read_roots(&app) {
+        Ok(roots) => Ok(ApiResponse::success(roots)),
+        Err(e) => Ok(error_response("Failed to load allowed roots", e)),
+    }
+}
+
+/// Replace the allowed roots; an empty list lifts the restriction
+///
+/// Roots must be existing directories and are stored canonicalized.
+#[tauri::command]
+pub async fn set_allowed_roots(
+    app: AppHandle,
+    roots: Vec<String>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    let result = (|| {
+        let roots = canonical_roots(&roots)?;
+        write_roots(&app, &roots)?;
+        Ok::<_, AppError>(roots)
+    })();
+
+    let entry = AuditEntry::new(
+        AuditAction::ConfigSaved,
+        AuditOrigin::Gui,
+        ALLOWED_ROOTS_FILE.to_string(),
+    );
+    match result {
+        Ok(roots) => {
+            log::info!("Allowed roots set to {:?}", roots);
+            let detail = format!("{} allowed root(s)", roots.len());
+            record_audit(&app, entry.with_outcome(true, Some(detail))).await;
+            Ok(ApiResponse::success(roots))
+        }
+        Err(e) => {
+            record_audit(&app, entry.with_outcome(false, Some(e.to_string()))).await;
+            Ok(error_response("Failed to save allowed roots", e))
+        }
+    }
+}
+
+/// Reject a run whose working directory or character file is outside the allowed roots
+pub(crate) fn check_run_paths(app: &AppHandle, spec: &RunSpec) -> Result<(), AppError> {
+    if let Some(ref dir) = spec.working_dir {
+        check_path_allowed(app, Path::new(dir), "Working directory")?;
+    }
+    if let Some(ref file) = spec.character_file {
+        let path = match spec.working_dir {
+            Some(ref dir) => Path::new(dir).join(file),
+            None => PathBuf::from(file),
+        };
+        check_path_allowed(app, &path, "Character file")?;
+    }
+    Ok(())
+}
+
+/// Reject a path outside the allowed roots; `what` names it in the error
+pub(crate) fn check_path_allowed(app: &AppHandle, path: &Path, what: &str) -> Result<(), AppError> {
+    let roots = read_roots(app)?;
+    if roots.is_empty() {
+        return Ok(());
+    }
+
+    let mut roots: Vec<PathBuf> = roots.into_iter().map(PathBuf::from).collect();
+    if let Ok(app_data) = app.path().app_data_dir() {
+        roots.push(normalize(&app_data)?);
+    }
+
+    let path = normalize(&expand_home(path))?;
+    if roots.iter().any(|root| path.starts_with(root)) {
+        return Ok(());
+    }
+    log::warn!("{} {:?} is outside the allowed roots", what, path);
+    Err(AppError::Policy(format!(
+        "{} '{}' is outside the allowed root directories",
+        what,
+        path.display()
+    )))
+}
+
+fn canonical_roots(roots: &[String]) -> Result<Vec<String>, AppError> {
+    if roots.len() > MAX_ROOTS {
+        return Err(AppError::Config(format!(
+            "At most {} allowed roots can be configured",
+            MAX_ROOTS
+        )));
+    }
+
+    let mut canonical: Vec<String> = Vec::new();
+    for root in roots {
+        let path = expand_home(Path::new(root.trim()));
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(AppError::Config(format!(
+                "Allowed root '{}' must be an existing absolute directory",
+                root
+            )));
+        }
+        let path = fs::canonicalize(&path)?.to_string_lossy().to_string();
+        if !canonical.contains(&path) {
+            canonical.push(path);
+        }
+    }
+    Ok(canonical)
+}
+
+/// Absolute path with symlinks and `..` resolved as far as the path exists
+///
+/// The missing tail is appended as written, so it may not step back out with `..`.
+fn normalize(path: &Path) -> Result<PathBuf, AppError> {
+    let absolute = std::path::absolute(path)?;
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+
+    loop {
+        if let Ok(canonical) = fs::canonicalize(existing) {
+            let mut normalized = canonical;
+            for component in missing.iter().rev() {
+                match component {
+                    Component::Normal(part) => normalized.push(part),
+                    Component::CurDir => {}
+                    _ => {
+                        return Err(AppError::Policy(format!(
+                            "Path '{}' cannot be checked against the allowed roots",
+                            path.display()
+                        )))
+                    }
+                }
+            }
+            return Ok(normalized);
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(component)) => {
+                missing.push(component);
+                existing = parent;
+            }
+            _ => return Ok(absolute),
+        }
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn roots_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(ALLOWED_ROOTS_FILE))
+}
+
+fn read_roots(app: &AppHandle) -> Result<Vec<String>, AppError> {
+    let path = roots_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_roots(app: &AppHandle, roots: &[String]) -> Result<(), AppError> {
+    fs::write(roots_path(app)?, serde_json::to_vec_pretty(roots)?)?;
+    Ok(())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_resolves_dot_dot() {
+        let dir = std::env::temp_dir().join(format!("path-jail-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("project")).unwrap();
+        let root = fs::canonicalize(&dir).unwrap();
+
+        let inside = normalize(&root.join("project/../project/new/file.json")).unwrap();
+        assert_eq!(inside, root.join("project/new/file.json"));
+
+        let escaped = normalize(&root.join("project/../..")).unwrap();
+        assert!(!escaped.starts_with(&root));
+
+        // A missing directory can't be used to step back out of the root
+        assert!(normalize(&root.join("missing/../../etc")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_canonical_roots() {
+        let dir = std::env::temp_dir().join(format!("path-jail-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let root = dir.to_string_lossy().to_string();
+
+        let roots = canonical_roots(&[root.clone(), format!("{}/.", root)]).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert!(canonical_roots(&["relative/dir".to_string()]).is_err());
+        assert!(canonical_roots(&[format!("{}/missing", root)]).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
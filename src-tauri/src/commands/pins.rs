@@ -0,0 +1,215 @@
+//! Pinned items
+//! Runs, templates and characters the user pinned so they stay at the top of listings,
+//! kept in the key-value store under the `pins` namespace, one key per kind
+
+use crate::commands::kv::{load_value, update_value};
+use crate::models::{current_timestamp, ApiResponse, AppError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+const PINS_NAMESPACE: &str = "pins";
+const MAX_ID_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PinKind {
+    Run,
+    Template,
+    Character,
+}
+
+impl PinKind {
+    const ALL: [PinKind; 3] = [PinKind::Run, PinKind::Template, PinKind::Character];
+
+    fn key(self) -> &'static str {
+        match self {
+            PinKind::Run => "run",
+            PinKind::Template => "template",
+            PinKind::Character => "character",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedItem {
+    pub kind: PinKind,
+    pub id: String,
+    /// Display name captured when pinned, so listings don't need to load the item
+    #[serde(default)]
+    pub label: Option<String>,
+    pub pinned_at: String,
+}
+
+/// Pin an item; pinning it again moves it to the top and updates its label
+#[tauri::command]
+pub async fn pin_item(
+    app: AppHandle,
+    kind: PinKind,
+    id: String,
+    label: Option<String>,
+) -> Result<ApiResponse<PinnedItem>, String> {
+    let result = async {
+        validate_id(&id)?;
+        let item = PinnedItem {
+            kind,
+            id: id.clone(),
+            label: label
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty()),
+            pinned_at: current_timestamp(),
+        };
+        let pinned = item.clone();
+        update_pins(&app, kind, move |pins| {
+            pins.retain(|pin| pin.id != pinned.id);
+            pins.insert(0, pinned);
+        })
+        .await?;
+        Ok::<_, AppError>(item)
+    }
+    .await;
+
+    match result {
+        Ok(item) => {
+            log::info!("Pinned {} {}", kind.key(), id);
+            Ok(ApiResponse::success(item))
+        }
+        Err(e) => Ok(error_response("Failed to pin item", e)),
+    }
+}
+
+/// Unpin an item, returning whether it was pinned
+#[tauri::command]
+pub async fn unpin_item(
+    app: AppHandle,
+    kind: PinKind,
+    id: String,
+) -> Result<ApiResponse<bool>, String> {
+    let mut existed = false;
+    let result = update_pins(&app, kind, |pins| {
+        let before = pins.len();
+        pins.retain(|pin| pin.id != id);
+        existed = pins.len() != before;
+    })
+    .await;
+
+    match result {
+        Ok(()) => {
+            log::info!("Unpinned {} {} (was pinned: {})", kind.key(), id, existed);
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to unpin item", e)),
+    }
+}
+
+/// Pinned items, most recently pinned first within each kind; all kinds unless `kind` is given
+#[tauri::command]
+pub async fn list_pinned(
+    app: AppHandle,
+    kind: Option<PinKind>,
+) -> Result<ApiResponse<Vec<PinnedItem>>, String> {
+    let result = async {
+        let mut items = Vec::new();
+        for each in PinKind::ALL
+            .into_iter()
+            .filter(|each| kind.is_none_or(|wanted| wanted == *each))
+        {
+            items.extend(read_pins(&app, each).await?);
+        }
+        Ok::<_, AppError>(items)
+    }
+    .await;
+
+    match result {
+        Ok(items) => Ok(ApiResponse::success(items)),
+        Err(e) => Ok(error_response("Failed to list pinned items", e)),
+    }
+}
+
+pub(crate) async fn read_pins(app: &AppHandle, kind: PinKind) -> Result<Vec<PinnedItem>, AppError> {
+    parse_pins(load_value(app, PINS_NAMESPACE, kind.key()).await?)
+}
+
+async fn update_pins<F>(app: &AppHandle, kind: PinKind, update: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut Vec<PinnedItem>),
+{
+    update_value(
+        app,
+        PINS_NAMESPACE.to_string(),
+        kind.key().to_string(),
+        |current| {
+            let mut pins = parse_pins(current)?;
+            update(&mut pins);
+            Ok(serde_json::to_value(pins)?)
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+fn parse_pins(value: Option<Value>) -> Result<Vec<PinnedItem>, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| AppError::Config(format!("Pinned items are corrupted: {}", e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn validate_id(id: &str) -> Result<(), AppError> {
+    if id.trim().is_empty() || id.len() > MAX_ID_LEN {
+        return Err(AppError::Config(format!(
+            "Invalid item ID: use 1-{} characters",
+            MAX_ID_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pin_kind_serialization() {
+        assert_eq!(
+            serde_json::to_value(PinKind::Template).unwrap(),
+            json!("template")
+        );
+        for kind in PinKind::ALL {
+            assert_eq!(serde_json::to_value(kind).unwrap(), json!(kind.key()));
+        }
+    }
+
+    #[test]
+    fn test_parse_pins() {
+        assert!(parse_pins(None).unwrap().is_empty());
+
+        let pins = parse_pins(Some(json!([
+            { "kind": "run", "id": "run_1", "pinnedAt": "2024-01-01T00:00:00Z" }
+        ])))
+        .unwrap();
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].kind, PinKind::Run);
+        assert!(pins[0].label.is_none());
+
+        assert!(parse_pins(Some(json!({ "id": "run_1" }))).is_err());
+    }
+
+    #[test]
+    fn test_validate_id() {
+        assert!(validate_id("run_1").is_ok());
+        assert!(validate_id("  ").is_err());
+        assert!(validate_id(&"x".repeat(MAX_ID_LEN + 1)).is_err());
+    }
+}
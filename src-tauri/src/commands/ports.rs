@@ -0,0 +1,199 @@
+//! Agent port management
+//! Hands out free local ports to agent runs so concurrent agents don't collide, and
+//! recognizes agents that failed because something else already held their port
+
+use crate::models::{AppError, RunSpec};
+use regex::Regex;
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+/// First port handed out; matches the ElizaOS server default
+pub const AGENT_PORT_BASE: u16 = 3000;
+const AGENT_PORT_RANGE: u16 = 100;
+
+// Reserved ports mapped to the run that owns them
+pub type PortRegistry = Arc<Mutex<HashMap<u16, String>>>;
+
+/// Initialize the port registry (called from main)
+pub fn init_port_registry() -> PortRegistry {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Get the port registry for the app
+pub fn get_port_registry(app: &AppHandle) -> PortRegistry {
+    app.state::<PortRegistry>().inner().clone()
+}
+
+/// Reserve the first free agent port for the given owner
+pub async fn reserve_agent_port(app: &AppHandle, owner: &str) -> Result<u16, AppError> {
+    let registry = get_port_registry(app);
+    let mut reserved = registry.lock().await;
+
+    let port = find_free_port(&reserved).ok_or_else(|| {
+        AppError::Process(format!(
+            "No free agent port in range {}-{}",
+            AGENT_PORT_BASE,
+            AGENT_PORT_BASE + AGENT_PORT_RANGE - 1
+        ))
+    })?;
+
+    reserved.insert(port, owner.to_string());
+    log::debug!("Reserved agent port {} for {}", port, owner);
+    Ok(port)
+}
+
+/// A free agent port, without reserving it
+pub async fn suggest_agent_port(app: &AppHandle) -> Result<u16, AppError> {
+    let registry = get_port_registry(app);
+    let reserved = registry.lock().await;
+    find_free_port(&reserved).ok_or_else(|| {
+        AppError::Process(format!(
+            "No free agent port in range {}-{}",
+            AGENT_PORT_BASE,
+            AGENT_PORT_BASE + AGENT_PORT_RANGE - 1
+        ))
+    })
+}
+
+/// Release every port held by the given owner
+pub async fn release_agent_ports(app: &AppHandle, owner: &str) {
+    let registry = get_port_registry(app);
+    let mut reserved = registry.lock().await;
+    reserved.retain(|port, port_owner| {
+        let keep = port_owner != owner;
+        if !keep {
+            log::debug!("Released agent port {} from {}", port, owner);
+        }
+        keep
+    });
+}
+
+/// First port in range that is neither reserved nor bound by another process
+fn find_free_port(reserved: &HashMap<u16, String>) -> Option<u16> {
+    (AGENT_PORT_BASE..AGENT_PORT_BASE + AGENT_PORT_RANGE)
+        .filter(|port| !reserved.contains_key(port))
+        .find(|port| is_port_available(*port))
+}
+
+/// Whether a local port can currently be bound
+pub fn is_port_available(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Whether stderr shows the server failed to bind its port; holds the port when named
+///
+/// Matches Node's `listen EADDRINUSE: address already in use :::3000` and the CLI's own
+/// `Port 3000 is already in use`.
+pub(crate) fn detect_port_conflict(stderr: &[String]) -> Option<Option<u16>> {
+    static CONFLICT: OnceLock<Regex> = OnceLock::new();
+    let conflict = CONFLICT.get_or_init(|| {
+        Regex::new(
+            r"(?i)EADDRINUSE|address already in use|port\s+(\d{1,5})\s+is\s+(?:already\s+)?in\s+use",
+        )
+        .expect("valid port conflict regex")
+    });
+    static ADDRESS_PORT: OnceLock<Regex> = OnceLock::new();
+    let address_port = ADDRESS_PORT
+        .get_or_init(|| Regex::new(r":(\d{1,5})\s*$").expect("valid address port regex"));
+
+    stderr.iter().find_map(|line| {
+        let captures = conflict.captures(line)?;
+        let port = captures
+            .get(1)
+            .or_else(|| {
+                address_port
+                    .captures(line.trim_end())
+                    .and_then(|c| c.get(1))
+            })
+            .and_then(|port| port.as_str().parse().ok());
+        Some(port)
+    })
+}
+
+/// The spec moved to `port`, dropping any `--port` it passed as a raw argument
+pub(crate) fn with_agent_port(spec: &RunSpec, port: u16) -> RunSpec {
+    let mut spec = spec.clone();
+    let mut args = Vec::with_capacity(spec.args.len());
+    let mut skip_value = false;
+    for arg in spec.args.drain(..) {
+        if std::mem::take(&mut skip_value) {
+            continue;
+        }
+        if arg == "--port" {
+            skip_value = true;
+        } else if !arg.starts_with("--port=") {
+            args.push(arg);
+        }
+    }
+    spec.args = args;
+    spec.port = Some(port);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_free_port_skips_reserved() {
+        let mut reserved = HashMap::new();
+        let first = find_free_port(&reserved).unwrap();
+
+        reserved.insert(first, "run_1".to_string());
+        let second = find_free_port(&reserved).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_detect_port_conflict() {
+        let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            detect_port_conflict(&lines(&[
+                "Starting server...",
+                "Error: listen EADDRINUSE: address already in use :::3000",
+            ])),
+            Some(Some(3000))
+        );
+        assert_eq!(
+            detect_port_conflict(&lines(&["[ERROR] Port 3001 is already in use"])),
+            Some(Some(3001))
+        );
+        assert_eq!(
+            detect_port_conflict(&lines(&["code: 'EADDRINUSE',"])),
+            Some(None)
+        );
+        assert_eq!(
+            detect_port_conflict(&lines(&["Error: Cannot find module 'x'"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_agent_port() {
+        let spec = RunSpec::new(
+            "run".to_string(),
+            crate::models::RunMode::Run,
+            vec![
+                "eliza.json".to_string(),
+                "--port".to_string(),
+                "3000".to_string(),
+                "--port=3000".to_string(),
+                "--verbose".to_string(),
+            ],
+        );
+        let moved = with_agent_port(&spec, 3004);
+        assert_eq!(moved.args, vec!["eliza.json", "--verbose"]);
+        assert_eq!(moved.port, Some(3004));
+    }
+
+    #[test]
+    fn test_bound_port_is_unavailable() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(!is_port_available(port));
+    }
+}
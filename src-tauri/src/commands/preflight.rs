@@ -1,18 +1,36 @@
 //! Preflight checks for system requirements
 //! Verifies Node.js, npm, and ElizaOS CLI availability
 
-use crate::models::{ApiResponse, AppError, PreflightResult, ToolCheck};
+use crate::commands::onboarding::complete_step;
+use crate::commands::preflight_history::record_preflight_snapshot;
+use crate::commands::resolver::invalidate_cli_resolution;
+use crate::i18n::{t, MessageId};
+use crate::models::{
+    ApiResponse, AppError, CliRunner, OnboardingStep, PreflightResult, PreflightStatus,
+    RunEnvironment, ToolCheck,
+};
+use crate::path_env::spawn_path_for_app;
 use std::process::Command;
+use tauri::AppHandle;
 use tauri_plugin_os::platform;
 
 /// Run comprehensive preflight checks
 #[tauri::command]
-pub async fn preflight_check() -> Result<ApiResponse<PreflightResult>, String> {
+pub async fn preflight_check(app: AppHandle) -> Result<ApiResponse<PreflightResult>, String> {
     log::info!("Running preflight checks");
 
-    match run_preflight_checks().await {
+    // A preflight refresh means the environment may have changed
+    invalidate_cli_resolution(&app).await;
+
+    let path_env = spawn_path_for_app(&app).await;
+
+    match run_preflight_checks(&path_env).await {
         Ok(result) => {
             log::info!("Preflight checks completed: {:?}", result.overall_status);
+            record_preflight_snapshot(&app, &result).await;
+            if matches!(result.overall_status, PreflightStatus::Ready) {
+                complete_step(&app, OnboardingStep::PreflightPassed).await;
+            }
             Ok(ApiResponse::success(result))
         }
         Err(e) => {
@@ -26,26 +44,26 @@ pub async fn preflight_check() -> Result<ApiResponse<PreflightResult>, String> {
 }
 
 /// Internal function to run all preflight checks
-async fn run_preflight_checks() -> Result<PreflightResult, AppError> {
+pub(crate) async fn run_preflight_checks(path_env: &str) -> Result<PreflightResult, AppError> {
     log::debug!("Checking Node.js installation");
-    let node_check = check_nodejs().await?;
+    let node_check = check_nodejs(path_env).await?;
 
     log::debug!("Checking npm installation");
-    let npm_check = check_npm().await?;
+    let npm_check = check_npm(path_env).await?;
 
     log::debug!("Checking ElizaOS CLI installation");
-    let eliza_check = check_eliza_cli().await?;
+    let eliza_check = check_eliza_cli(path_env).await?;
 
     Ok(PreflightResult::new(node_check, npm_check, eliza_check))
 }
 
 /// Check Node.js installation and version
-async fn check_nodejs() -> Result<ToolCheck, AppError> {
+async fn check_nodejs(path_env: &str) -> Result<ToolCheck, AppError> {
     // Try different possible Node.js commands
     let node_commands = ["node", "nodejs"];
 
     for cmd in &node_commands {
-        match check_tool_version(cmd, "--version").await {
+        match check_tool_version(cmd, "--version", path_env).await {
             Ok(Some((version, path))) => {
                 log::debug!("Found Node.js {} at {}", version, path);
                 return Ok(ToolCheck::found(version, path));
@@ -63,7 +81,7 @@ async fn check_nodejs() -> Result<ToolCheck, AppError> {
 }
 
 /// Check npm installation and version
-async fn check_npm() -> Result<ToolCheck, AppError> {
+async fn check_npm(path_env: &str) -> Result<ToolCheck, AppError> {
     // Try npm and pnpm
     let package_managers = [
         ("npm", "--version"),
@@ -72,7 +90,7 @@ async fn check_npm() -> Result<ToolCheck, AppError> {
     ];
 
     for (cmd, version_flag) in &package_managers {
-        match check_tool_version(cmd, version_flag).await {
+        match check_tool_version(cmd, version_flag, path_env).await {
             Ok(Some((version, path))) => {
                 log::debug!("Found package manager {} {} at {}", cmd, version, path);
                 return Ok(ToolCheck::found(version, path));
@@ -90,9 +108,9 @@ async fn check_npm() -> Result<ToolCheck, AppError> {
 }
 
 /// Check ElizaOS CLI installation
-async fn check_eliza_cli() -> Result<ToolCheck, AppError> {
+async fn check_eliza_cli(path_env: &str) -> Result<ToolCheck, AppError> {
     // First try to find elizaos CLI directly (updated from eliza)
-    match check_tool_version("elizaos", "--version").await {
+    match check_tool_version("elizaos", "--version", path_env).await {
         Ok(Some((version, path))) => {
             log::debug!("Found ElizaOS CLI {} at {}", version, path);
             return Ok(ToolCheck::found(version, path));
@@ -103,7 +121,7 @@ async fn check_eliza_cli() -> Result<ToolCheck, AppError> {
     }
 
     // Try to check if it's available via npx
-    match check_npx_eliza().await {
+    match check_npx_eliza(path_env).await {
         Ok(true) => {
             log::debug!("ElizaOS CLI available via npx");
             Ok(ToolCheck::found(
@@ -123,9 +141,10 @@ async fn check_eliza_cli() -> Result<ToolCheck, AppError> {
 }
 
 /// Check if ElizaOS CLI is available via npx
-async fn check_npx_eliza() -> Result<bool, AppError> {
+async fn check_npx_eliza(path_env: &str) -> Result<bool, AppError> {
     let output = Command::new("npx")
         .args(["-y", "@elizaos/cli@latest", "--version"])
+        .env("PATH", path_env)
         .output()
         .map_err(|e| AppError::Process(format!("Failed to run npx: {}", e)))?;
 
@@ -142,10 +161,12 @@ async fn check_npx_eliza() -> Result<bool, AppError> {
 async fn check_tool_version(
     command: &str,
     version_flag: &str,
+    path_env: &str,
 ) -> Result<Option<(String, String)>, AppError> {
     // First check if command exists
     let which_output = Command::new(get_which_command())
         .arg(command)
+        .env("PATH", path_env)
         .output()
         .map_err(|e| AppError::Process(format!("Failed to check if {} exists: {}", command, e)))?;
 
@@ -163,6 +184,7 @@ async fn check_tool_version(
     // Get version information
     let version_output = Command::new(command)
         .arg(version_flag)
+        .env("PATH", path_env)
         .output()
         .map_err(|e| AppError::Process(format!("Failed to get {} version: {}", command, e)))?;
 
@@ -178,6 +200,36 @@ async fn check_tool_version(
     }
 }
 
+/// Capture the toolchain a run is about to execute on
+///
+/// Missing tools are recorded as `None` rather than failing the run.
+pub(crate) async fn capture_run_environment(runner: CliRunner, path_env: &str) -> RunEnvironment {
+    let version = |result: Result<Option<(String, String)>, AppError>| {
+        result.ok().flatten().map(|(version, _)| version)
+    };
+
+    let node_version = version(check_tool_version("node", "--version", path_env).await);
+    let npm_version = version(check_tool_version("npm", "--version", path_env).await);
+    // Asking a package runner for its version could trigger a download
+    let cli_version = if runner == CliRunner::Elizaos {
+        version(check_tool_version("elizaos", "--version", path_env).await)
+    } else {
+        None
+    };
+
+    RunEnvironment {
+        node_version,
+        npm_version,
+        cli_runner: runner,
+        cli_package: runner.package_spec().map(str::to_string),
+        cli_version,
+        os: platform().to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
 /// Get the appropriate "which" command for the current platform
 fn get_which_command() -> &'static str {
     if platform().to_string().to_lowercase().contains("windows") {
@@ -239,24 +291,21 @@ pub fn get_installation_recommendations() -> Vec<String> {
     let platform_str = platform().to_string().to_lowercase();
 
     if platform_str.contains("windows") {
-        recommendations
-            .push("Install Node.js from https://nodejs.org/ (choose LTS version)".to_string());
-        recommendations.push("npm comes bundled with Node.js".to_string());
-        recommendations.push("ElizaOS CLI will be installed automatically when needed".to_string());
+        recommendations.push(t(MessageId::RecommendInstallNodeLts));
+        recommendations.push(t(MessageId::NpmBundledWithNode));
+        recommendations.push(t(MessageId::CliInstalledWhenNeeded));
     } else if platform_str.contains("darwin") || platform_str.contains("macos") {
-        recommendations.push("Install Node.js via Homebrew: brew install node".to_string());
-        recommendations
-            .push("Or download from https://nodejs.org/ (choose LTS version)".to_string());
-        recommendations.push("ElizaOS CLI will be installed automatically when needed".to_string());
+        recommendations.push(t(MessageId::RecommendInstallNodeHomebrew));
+        recommendations.push(t(MessageId::RecommendDownloadNodeLts));
+        recommendations.push(t(MessageId::CliInstalledWhenNeeded));
     } else if platform_str.contains("linux") {
-        recommendations
-            .push("Install Node.js via package manager or from https://nodejs.org/".to_string());
-        recommendations.push("Ubuntu/Debian: sudo apt install nodejs npm".to_string());
-        recommendations.push("CentOS/RHEL: sudo yum install nodejs npm".to_string());
-        recommendations.push("ElizaOS CLI will be installed automatically when needed".to_string());
+        recommendations.push(t(MessageId::RecommendInstallNodePackageManager));
+        recommendations.push(t(MessageId::RecommendInstallNodeApt));
+        recommendations.push(t(MessageId::RecommendInstallNodeYum));
+        recommendations.push(t(MessageId::CliInstalledWhenNeeded));
     } else {
-        recommendations.push("Install Node.js 18+ from https://nodejs.org/".to_string());
-        recommendations.push("Ensure npm is available".to_string());
+        recommendations.push(t(MessageId::RecommendInstallNode));
+        recommendations.push(t(MessageId::RecommendEnsureNpm));
     }
 
     recommendations
@@ -295,8 +344,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_preflight_check_structure() {
-        // This test just ensures the function can be called
-        let result = preflight_check().await;
+        // This test just ensures the checks can be run
+        let path_env = crate::path_env::build_spawn_path(&[]);
+        let result = run_preflight_checks(&path_env).await;
         assert!(result.is_ok());
     }
 }
@@ -0,0 +1,427 @@
+//! Preflight history
+//! Keeps a timestamped snapshot of every preflight check and diffs them to surface environment drift
+
+use crate::models::{
+    ApiResponse, AppError, PreflightChange, PreflightChangeKind, PreflightDiff, PreflightResult,
+    PreflightSnapshot, ToolCheck,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+const PREFLIGHT_HISTORY_FILE: &str = "preflight_history.jsonl";
+
+/// Snapshots kept on disk; the oldest are dropped first
+const MAX_SNAPSHOTS: usize = 500;
+
+/// Default comparison window for `get_preflight_changes`
+const DEFAULT_DIFF_WINDOW_HOURS: i64 = 24;
+
+// Serializes writers so concurrent checks never interleave or lose snapshots
+pub type PreflightHistoryLock = Arc<Mutex<()>>;
+
+/// Initialize the preflight history lock (called from main)
+pub fn init_preflight_history() -> PreflightHistoryLock {
+    Arc::new(Mutex::new(()))
+}
+
+/// Get recorded preflight snapshots, newest first
+#[tauri::command]
+pub async fn get_preflight_history(
+    app: AppHandle,
+    limit: Option<usize>,
+) -> Result<ApiResponse<Vec<PreflightSnapshot>>, String> {
+    match read_snapshots(&app).await {
+        Ok(mut snapshots) => {
+            snapshots.reverse();
+            if let Some(limit) = limit {
+                snapshots.truncate(limit);
+            }
+            Ok(ApiResponse::success(snapshots))
+        }
+        Err(e) => {
+            log::error!("Failed to read preflight history: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to read preflight history: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Diff the latest snapshot against the one in effect at `since` (RFC 3339, default 24 hours ago)
+#[tauri::command]
+pub async fn get_preflight_changes(
+    app: AppHandle,
+    since: Option<String>,
+) -> Result<ApiResponse<PreflightDiff>, String> {
+    let result = async {
+        let since = match since.as_deref() {
+            Some(value) => DateTime::parse_from_rfc3339(value)
+                .map_err(|e| AppError::Config(format!("Invalid timestamp '{}': {}", value, e)))?
+                .with_timezone(&Utc),
+            None => Utc::now() - Duration::hours(DEFAULT_DIFF_WINDOW_HOURS),
+        };
+
+        let snapshots = read_snapshots(&app).await?;
+        Ok::<_, AppError>(diff_since(&snapshots, since))
+    }
+    .await;
+
+    match result {
+        Ok(diff) => Ok(ApiResponse::success(diff)),
+        Err(e) => {
+            log::error!("Failed to diff preflight history: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to diff preflight history: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// Persist a preflight result; failures are logged and never fail the check
+pub async fn record_preflight_snapshot(app: &AppHandle, result: &PreflightResult) {
+    let snapshot = PreflightSnapshot {
+        timestamp: Utc::now().to_rfc3339(),
+        result: result.clone(),
+    };
+
+    if let Err(e) = append_snapshot(app, &snapshot).await {
+        log::warn!("Failed to record preflight snapshot: {}", e);
+    }
+}
+
+fn get_history_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(PREFLIGHT_HISTORY_FILE))
+}
+
+async fn append_snapshot(app: &AppHandle, snapshot: &PreflightSnapshot) -> Result<(), AppError> {
+    let lock = app.state::<PreflightHistoryLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let path = get_history_path(app)?;
+    let mut snapshots = if path.exists() {
+        parse_snapshots(&fs::read_to_string(&path)?)
+    } else {
+        Vec::new()
+    };
+    snapshots.push(snapshot.clone());
+
+    let skip = snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+    let mut contents = String::new();
+    for snapshot in &snapshots[skip..] {
+        contents.push_str(&serde_json::to_string(snapshot)?);
+        contents.push('\n');
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// All snapshots, oldest first
+async fn read_snapshots(app: &AppHandle) -> Result<Vec<PreflightSnapshot>, AppError> {
+    let lock = app.state::<PreflightHistoryLock>().inner().clone();
+    let _guard = lock.lock().await;
+
+    let path = get_history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(parse_snapshots(&fs::read_to_string(path)?))
+}
+
+/// Parse JSON lines, skipping any line that is not a valid snapshot
+fn parse_snapshots(contents: &str) -> Vec<PreflightSnapshot> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                log::warn!("Skipping malformed preflight history line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn snapshot_time(snapshot: &PreflightSnapshot) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&snapshot.timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Compare the latest snapshot with the last one taken at or before `since`,
+/// falling back to the oldest snapshot when history does not reach that far back
+fn diff_since(snapshots: &[PreflightSnapshot], since: DateTime<Utc>) -> PreflightDiff {
+    let Some(latest) = snapshots.last() else {
+        return PreflightDiff {
+            from: None,
+            to: None,
+            changes: Vec::new(),
+        };
+    };
+
+    let baseline = snapshots
+        .iter()
+        .rev()
+        .find(|snapshot| snapshot_time(snapshot).is_some_and(|time| time <= since))
+        .or_else(|| snapshots.first())
+        .unwrap_or(latest);
+
+    PreflightDiff {
+        from: Some(baseline.timestamp.clone()),
+        to: Some(latest.timestamp.clone()),
+        changes: diff_results(&baseline.result, &latest.result),
+    }
+}
+
+fn diff_results(before: &PreflightResult, after: &PreflightResult) -> Vec<PreflightChange> {
+    let mut changes = Vec::new();
+
+    for (tool, label, old, new) in [
+        ("node", "Node.js", &before.node, &after.node),
+        ("npm", "Package manager", &before.npm, &after.npm),
+        ("eliza", "ElizaOS CLI", &before.eliza, &after.eliza),
+    ] {
+        changes.extend(diff_tool(tool, label, old, new));
+    }
+
+    let old_status = status_label(before);
+    let new_status = status_label(after);
+    if old_status != new_status {
+        changes.push(PreflightChange {
+            tool: "overall".to_string(),
+            kind: PreflightChangeKind::StatusChanged,
+            message: format!(
+                "Overall status changed from {} to {}",
+                old_status, new_status
+            ),
+            before: Some(old_status),
+            after: Some(new_status),
+        });
+    }
+
+    changes
+}
+
+fn status_label(result: &PreflightResult) -> String {
+    serde_json::to_value(&result.overall_status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", result.overall_status))
+}
+
+fn diff_tool(tool: &str, label: &str, old: &ToolCheck, new: &ToolCheck) -> Vec<PreflightChange> {
+    let change =
+        |kind, message: String, before: &Option<String>, after: &Option<String>| PreflightChange {
+            tool: tool.to_string(),
+            kind,
+            before: before.clone(),
+            after: after.clone(),
+            message,
+        };
+
+    match (old.installed, new.installed) {
+        (false, false) => return Vec::new(),
+        (true, false) => {
+            return vec![change(
+                PreflightChangeKind::Disappeared,
+                format!("{} is no longer found", label),
+                &old.version,
+                &None,
+            )]
+        }
+        (false, true) => {
+            return vec![change(
+                PreflightChangeKind::Appeared,
+                format!("{} is now installed", label),
+                &None,
+                &new.version,
+            )]
+        }
+        (true, true) => {}
+    }
+
+    let mut changes = Vec::new();
+
+    if old.version != new.version {
+        let old_version = old.version.as_deref().unwrap_or("unknown");
+        let new_version = new.version.as_deref().unwrap_or("unknown");
+        let (kind, verb) = match compare_versions(old_version, new_version) {
+            Some(Ordering::Less) => (PreflightChangeKind::Upgraded, "upgraded"),
+            Some(Ordering::Greater) => (PreflightChangeKind::Downgraded, "downgraded"),
+            _ => (PreflightChangeKind::VersionChanged, "changed"),
+        };
+        changes.push(change(
+            kind,
+            format!("{} {} from {} to {}", label, verb, old_version, new_version),
+            &old.version,
+            &new.version,
+        ));
+    }
+
+    if old.path != new.path {
+        changes.push(change(
+            PreflightChangeKind::Moved,
+            format!(
+                "{} now resolves to {}",
+                label,
+                new.path.as_deref().unwrap_or("an unknown path")
+            ),
+            &old.path,
+            &new.path,
+        ));
+    }
+
+    changes
+}
+
+/// Numeric dotted comparison tolerant of a leading `v` and trailing tags; None when unparseable
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        let core = version.trim().trim_start_matches('v');
+        let core = core.split(['-', '+', ' ']).next()?;
+        core.split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect()
+    };
+
+    let (mut a, mut b) = (parse(a)?, parse(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(node: ToolCheck, eliza: ToolCheck) -> PreflightResult {
+        PreflightResult::new(
+            node,
+            ToolCheck::found("10.2.0".to_string(), "/usr/bin/npm".to_string()),
+            eliza,
+        )
+    }
+
+    fn snapshot(timestamp: &str, result: PreflightResult) -> PreflightSnapshot {
+        PreflightSnapshot {
+            timestamp: timestamp.to_string(),
+            result,
+        }
+    }
+
+    fn node(version: &str) -> ToolCheck {
+        ToolCheck::found(version.to_string(), "/usr/bin/node".to_string())
+    }
+
+    fn eliza() -> ToolCheck {
+        ToolCheck::found("1.0.0".to_string(), "/usr/bin/elizaos".to_string())
+    }
+
+    #[test]
+    fn compares_dotted_versions_numerically() {
+        assert_eq!(
+            compare_versions("v18.9.0", "v18.10.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(compare_versions("20.1", "20.1.0"), Some(Ordering::Equal));
+        assert_eq!(
+            compare_versions("1.2.0-beta.1", "1.1.9"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_versions("latest", "1.0.0"), None);
+    }
+
+    #[test]
+    fn reports_upgrades_and_disappearances() {
+        let before = result(node("v18.19.0"), eliza());
+        let after = result(node("v20.11.0"), ToolCheck::not_found());
+
+        let changes = diff_results(&before, &after);
+        let kinds: Vec<_> = changes
+            .iter()
+            .map(|change| (change.tool.as_str(), change.kind.clone()))
+            .collect();
+
+        assert!(kinds.contains(&("node", PreflightChangeKind::Upgraded)));
+        assert!(kinds.contains(&("eliza", PreflightChangeKind::Disappeared)));
+        assert!(kinds.contains(&("overall", PreflightChangeKind::StatusChanged)));
+    }
+
+    #[test]
+    fn identical_results_have_no_changes() {
+        let before = result(node("v20.11.0"), eliza());
+        assert!(diff_results(&before, &before.clone()).is_empty());
+    }
+
+    #[test]
+    fn diffs_against_last_snapshot_before_since() {
+        let snapshots = vec![
+            snapshot("2026-10-01T00:00:00Z", result(node("v18.0.0"), eliza())),
+            snapshot("2026-10-10T00:00:00Z", result(node("v20.0.0"), eliza())),
+            snapshot("2026-10-15T00:00:00Z", result(node("v22.0.0"), eliza())),
+        ];
+        let since = DateTime::parse_from_rfc3339("2026-10-12T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let diff = diff_since(&snapshots, since);
+        assert_eq!(diff.from.as_deref(), Some("2026-10-10T00:00:00Z"));
+        assert_eq!(diff.to.as_deref(), Some("2026-10-15T00:00:00Z"));
+        assert_eq!(diff.changes[0].before.as_deref(), Some("v20.0.0"));
+    }
+
+    #[test]
+    fn falls_back_to_oldest_snapshot() {
+        let snapshots = vec![
+            snapshot("2026-10-10T00:00:00Z", result(node("v20.0.0"), eliza())),
+            snapshot("2026-10-15T00:00:00Z", result(node("v22.0.0"), eliza())),
+        ];
+        let since = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let diff = diff_since(&snapshots, since);
+        assert_eq!(diff.from.as_deref(), Some("2026-10-10T00:00:00Z"));
+        assert_eq!(diff.changes.len(), 1);
+    }
+
+    #[test]
+    fn empty_history_has_no_diff() {
+        let diff = diff_since(&[], Utc::now());
+        assert!(diff.from.is_none() && diff.changes.is_empty());
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let line = serde_json::to_string(&snapshot(
+            "2026-10-10T00:00:00Z",
+            result(node("v20.0.0"), eliza()),
+        ))
+        .unwrap();
+        let parsed = parse_snapshots(&format!("{}\nnot json\n\n", line));
+        assert_eq!(parsed.len(), 1);
+    }
+}
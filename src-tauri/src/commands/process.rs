@@ -1,16 +1,57 @@
 //! Process management for ElizaOS CLI execution
 //! Handles spawning, monitoring, and controlling ElizaOS CLI processes
 
+use crate::commands::app_lock::require_unlocked;
+use crate::commands::args::{custom_allowlist, validated_mode_args, ToCliArgs};
+use crate::commands::audit::record_audit;
+use crate::commands::budget::check_run_budget;
+use crate::commands::config::saved_config;
+use crate::commands::doctor::execute_doctor_run;
+use crate::commands::egress::{spawn_egress_monitor, EgressMonitor};
+use crate::commands::eval::{collect_eval_result, with_run_report_path};
+use crate::commands::history::record_run;
+use crate::commands::install_progress::{npx_progress_env, InstallProgress};
+use crate::commands::knowledge::knowledge_env;
+use crate::commands::network_capture::start_capture_proxy;
+use crate::commands::onboarding::note_finished_run;
+use crate::commands::path_jail::check_run_paths;
+use crate::commands::ports::{
+    detect_port_conflict, release_agent_ports, reserve_agent_port, suggest_agent_port,
+    with_agent_port,
+};
+use crate::commands::preflight::capture_run_environment;
+use crate::commands::project_config::project_config;
+use crate::commands::project_env::apply_project_env;
+use crate::commands::resolver::resolve_eliza_command_cached;
+use crate::commands::run_logs::{open_run_log, RunLogBuffer};
+use crate::commands::run_queue::{acquire_run_slot, continue_paused_run, set_run_pid};
+use crate::commands::run_variables::resolve_run_spec;
+use crate::commands::session::JournaledRunGuard;
+use crate::commands::simulation::execute_simulated_run;
+use crate::commands::sleep_inhibitor::SleepGuard;
+use crate::commands::stats::emit_event;
+use crate::commands::watchdog::{spawn_watchdog, OutputActivity};
+use crate::executor::{terminate, ExecRequest, Executor, LocalExecutor};
 use crate::models::{
-    ApiResponse, AppError, LogEvent, RunMode, RunResult, RunSpec, RunStatus, SandboxConfig,
+    ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin, CliRunner, LogEvent, LogSeverity,
+    LogType, ProgressLineEvent, RunEnvironment, RunMode, RunResult, RunRetrySuggestedEvent,
+    RunSpec, RunStatus, SandboxConfig,
 };
+use crate::path_env::build_spawn_path;
+use crate::severity::{is_benign_stderr, parse_severity};
+use crate::stream::{CapturedOutput, StreamItem, StreamReader};
 use std::collections::HashMap;
-use std::process::Command;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncRead;
 use tokio::sync::{Mutex, RwLock};
+use tracing::Instrument;
+
+/// Minimum gap between `run-progress-line` updates for one stream
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+/// Automatic retries of an agent run whose port keeps turning out to be taken
+const MAX_PORT_CONFLICT_RETRIES: u32 = 3;
 
 // Structure to track running processes
 #[derive(Debug, Clone)]
@@ -34,6 +75,15 @@ impl ProcessHandle {
     pub fn mark_completed(&mut self) {
         self.can_control = false;
     }
+
+    /// End a simulated run, which has no process; playback stops once the handle is ended
+    fn end_simulated(&mut self) -> RunResult {
+        log::info!("Ending simulated run: {}", self.run_result.id);
+        self.run_result.status = RunStatus::Killed;
+        self.run_result.ended_at = Some(crate::models::current_timestamp());
+        self.mark_completed();
+        self.run_result.clone()
+    }
 }
 
 // Global process registry to track running processes
@@ -52,22 +102,63 @@ pub async fn start_eliza_run_streaming(
         spec.args
     );
 
-    if !config.is_valid() {
+    // Simulated runs never reach the Sandbox, so demos work without credentials
+    if !spec.simulate && !config.is_valid() {
         return Ok(ApiResponse::error(
             "INVALID_CONFIG".to_string(),
             "Invalid Sandbox configuration".to_string(),
         ));
     }
 
-    match execute_eliza_run_streaming(app, spec, config).await {
+    if spec.after.is_some() {
+        return Ok(ApiResponse::error(
+            "INVALID_SPEC".to_string(),
+            "Runs with dependencies must be started through schedule_runs".to_string(),
+        ));
+    }
+
+    if let Err(e) = saved_config(&app)
+        .and_then(|saved| validated_mode_args(&spec, &custom_allowlist(&saved, &config)))
+    {
+        log::warn!("Rejected run spec: {}", e);
+        return Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            e.to_string(),
+        ));
+    }
+
+    let audit_detail = describe_run_for_audit(&spec);
+    match execute_eliza_run_streaming(app.clone(), spec, config.clone()).await {
         Ok(result) => {
             log::info!("Started streaming ElizaOS CLI run: {}", result.id);
+            audit_run(
+                &app,
+                AuditAction::RunStarted,
+                &result.id,
+                true,
+                Some(audit_detail),
+            )
+            .await;
+            let result = retry_on_port_conflict(&app, result, &config).await;
             Ok(ApiResponse::success(result))
         }
         Err(e) => {
             log::error!("Failed to start streaming ElizaOS CLI run: {}", e);
+            audit_run(
+                &app,
+                AuditAction::RunStarted,
+                "",
+                false,
+                Some(e.to_string()),
+            )
+            .await;
+            // A blocked budget keeps its own code, so it reads differently from a failed start
+            let code = match e {
+                AppError::Quota(_) => e.error_code(),
+                _ => "START_ERROR",
+            };
             Ok(ApiResponse::error(
-                "START_ERROR".to_string(),
+                code.to_string(),
                 format!("Failed to start streaming run: {}", e),
             ))
         }
@@ -83,22 +174,62 @@ pub async fn start_eliza_run(
 ) -> Result<ApiResponse<RunResult>, String> {
     log::info!("Starting ElizaOS CLI run: {} {:?}", spec.mode, spec.args);
 
-    if !config.is_valid() {
+    // Simulated runs never reach the Sandbox, so demos work without credentials
+    if !spec.simulate && !config.is_valid() {
         return Ok(ApiResponse::error(
             "INVALID_CONFIG".to_string(),
             "Invalid Sandbox configuration".to_string(),
         ));
     }
 
-    match execute_eliza_run_simple(app, spec, config).await {
+    if spec.after.is_some() {
+        return Ok(ApiResponse::error(
+            "INVALID_SPEC".to_string(),
+            "Runs with dependencies must be started through schedule_runs".to_string(),
+        ));
+    }
+
+    if let Err(e) = saved_config(&app)
+        .and_then(|saved| validated_mode_args(&spec, &custom_allowlist(&saved, &config)))
+    {
+        log::warn!("Rejected run spec: {}", e);
+        return Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            e.to_string(),
+        ));
+    }
+
+    let audit_detail = describe_run_for_audit(&spec);
+    match execute_eliza_run_simple(app.clone(), spec, config).await {
         Ok(result) => {
             log::info!("Started ElizaOS CLI run: {}", result.id);
+            audit_run(
+                &app,
+                AuditAction::RunStarted,
+                &result.id,
+                true,
+                Some(audit_detail),
+            )
+            .await;
             Ok(ApiResponse::success(result))
         }
         Err(e) => {
             log::error!("Failed to start ElizaOS CLI run: {}", e);
+            audit_run(
+                &app,
+                AuditAction::RunStarted,
+                "",
+                false,
+                Some(e.to_string()),
+            )
+            .await;
+            // A blocked budget keeps its own code, so it reads differently from a failed start
+            let code = match e {
+                AppError::Quota(_) => e.error_code(),
+                _ => "START_ERROR",
+            };
             Ok(ApiResponse::error(
-                "START_ERROR".to_string(),
+                code.to_string(),
                 format!("Failed to start run: {}", e),
             ))
         }
@@ -116,72 +247,38 @@ pub async fn stop_eliza_run(
     let registry = get_process_registry(&app);
     let mut guard = registry.write().await;
 
-    match guard.get_mut(&run_id) {
+    let response = match guard.get_mut(&run_id) {
         Some(process_handle_arc) => {
             let mut process_handle = process_handle_arc.lock().await;
 
             if process_handle.can_control {
                 if let Some(pid) = process_handle.run_result.pid {
-                    // Use system command to send SIGTERM
-                    log::info!("Sending SIGTERM to process: PID={}, run_id={}", pid, run_id);
-
-                    #[cfg(unix)]
-                    {
-                        use nix::sys::signal::{kill, Signal};
-                        use nix::unistd::Pid;
-
-                        match kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
-                            Ok(_) => {
-                                log::info!("Successfully sent SIGTERM to PID: {}", pid);
-                                process_handle.run_result.status = RunStatus::Killed;
-                                process_handle.run_result.ended_at =
-                                    Some(crate::models::current_timestamp());
-                                process_handle.mark_completed();
-
-                                let result = process_handle.run_result.clone();
-                                Ok(ApiResponse::success(result))
-                            }
-                            Err(e) => {
-                                log::error!("Failed to send SIGTERM to PID {}: {}", pid, e);
-                                Ok(ApiResponse::error(
-                                    "STOP_ERROR".to_string(),
-                                    format!("Failed to stop process (PID: {}): {}", pid, e),
-                                ))
-                            }
+                    // SIGTERM on Unix, taskkill elsewhere
+                    log::info!("Stopping process: PID={}, run_id={}", pid, run_id);
+
+                    match terminate(pid, false) {
+                        Ok(()) => {
+                            // A paused run only acts on the signal once continued
+                            continue_paused_run(&app, &run_id);
+                            log::info!("Successfully stopped PID: {}", pid);
+                            process_handle.run_result.status = RunStatus::Killed;
+                            process_handle.run_result.ended_at =
+                                Some(crate::models::current_timestamp());
+                            process_handle.mark_completed();
+
+                            let result = process_handle.run_result.clone();
+                            Ok(ApiResponse::success(result))
                         }
-                    }
-
-                    #[cfg(not(unix))]
-                    {
-                        // On non-Unix systems, use std::process to terminate
-                        match std::process::Command::new("taskkill")
-                            .args(["/PID", &pid.to_string(), "/T", "/F"])
-                            .output()
-                        {
-                            Ok(output) => {
-                                if output.status.success() {
-                                    log::info!("Successfully terminated process PID: {}", pid);
-                                    process_handle.run_result.status = RunStatus::Killed;
-                                    process_handle.run_result.ended_at =
-                                        Some(crate::models::current_timestamp());
-                                    process_handle.mark_completed();
-
-                                    let result = process_handle.run_result.clone();
-                                    Ok(ApiResponse::success(result))
-                                } else {
-                                    let error = String::from_utf8_lossy(&output.stderr);
-                                    Ok(ApiResponse::error(
-                                        "STOP_ERROR".to_string(),
-                                        format!("Failed to stop process: {}", error),
-                                    ))
-                                }
-                            }
-                            Err(e) => Ok(ApiResponse::error(
+                        Err(e) => {
+                            log::error!("Failed to stop PID {}: {}", pid, e);
+                            Ok(ApiResponse::error(
                                 "STOP_ERROR".to_string(),
-                                format!("Failed to stop process: {}", e),
-                            )),
+                                format!("Failed to stop process (PID: {}): {}", pid, e),
+                            ))
                         }
                     }
+                } else if process_handle.run_result.spec.simulate {
+                    Ok(ApiResponse::success(process_handle.end_simulated()))
                 } else {
                     Ok(ApiResponse::error(
                         "NO_PID".to_string(),
@@ -198,7 +295,22 @@ pub async fn stop_eliza_run(
             "NOT_FOUND".to_string(),
             format!("Process {} not found or already completed", run_id),
         )),
+    };
+    drop(guard);
+
+    if let Ok(ref response) = response {
+        let detail = response.error.as_ref().map(|error| error.message.clone());
+        audit_run(
+            &app,
+            AuditAction::RunStopped,
+            &run_id,
+            response.success,
+            detail,
+        )
+        .await;
     }
+
+    response
 }
 
 /// Kill a running ElizaOS CLI process forcefully
@@ -212,75 +324,36 @@ pub async fn kill_eliza_run(
     let registry = get_process_registry(&app);
     let mut guard = registry.write().await;
 
-    match guard.get_mut(&run_id) {
+    let response = match guard.get_mut(&run_id) {
         Some(process_handle_arc) => {
             let mut process_handle = process_handle_arc.lock().await;
 
             if process_handle.can_control {
                 if let Some(pid) = process_handle.run_result.pid {
-                    // Force kill the process (SIGKILL)
+                    // SIGKILL on Unix, taskkill /F elsewhere
                     log::info!("Force killing process: PID={}, run_id={}", pid, run_id);
 
-                    #[cfg(unix)]
-                    {
-                        use nix::sys::signal::{kill, Signal};
-                        use nix::unistd::Pid;
-
-                        match kill(Pid::from_raw(pid as i32), Signal::SIGKILL) {
-                            Ok(_) => {
-                                log::info!("Successfully sent SIGKILL to PID: {}", pid);
-                                process_handle.run_result.status = RunStatus::Killed;
-                                process_handle.run_result.ended_at =
-                                    Some(crate::models::current_timestamp());
-                                process_handle.mark_completed();
-
-                                let result = process_handle.run_result.clone();
-                                Ok(ApiResponse::success(result))
-                            }
-                            Err(e) => {
-                                log::error!("Failed to send SIGKILL to PID {}: {}", pid, e);
-                                Ok(ApiResponse::error(
-                                    "KILL_ERROR".to_string(),
-                                    format!("Failed to kill process (PID: {}): {}", pid, e),
-                                ))
-                            }
-                        }
-                    }
+                    match terminate(pid, true) {
+                        Ok(()) => {
+                            log::info!("Successfully killed PID: {}", pid);
+                            process_handle.run_result.status = RunStatus::Killed;
+                            process_handle.run_result.ended_at =
+                                Some(crate::models::current_timestamp());
+                            process_handle.mark_completed();
 
-                    #[cfg(not(unix))]
-                    {
-                        // On non-Unix systems, use taskkill with /F (force)
-                        match std::process::Command::new("taskkill")
-                            .args(["/PID", &pid.to_string(), "/T", "/F"])
-                            .output()
-                        {
-                            Ok(output) => {
-                                if output.status.success() {
-                                    log::info!(
-                                        "Successfully force-terminated process PID: {}",
-                                        pid
-                                    );
-                                    process_handle.run_result.status = RunStatus::Killed;
-                                    process_handle.run_result.ended_at =
-                                        Some(crate::models::current_timestamp());
-                                    process_handle.mark_completed();
-
-                                    let result = process_handle.run_result.clone();
-                                    Ok(ApiResponse::success(result))
-                                } else {
-                                    let error = String::from_utf8_lossy(&output.stderr);
-                                    Ok(ApiResponse::error(
-                                        "KILL_ERROR".to_string(),
-                                        format!("Failed to kill process: {}", error),
-                                    ))
-                                }
-                            }
-                            Err(e) => Ok(ApiResponse::error(
+                            let result = process_handle.run_result.clone();
+                            Ok(ApiResponse::success(result))
+                        }
+                        Err(e) => {
+                            log::error!("Failed to kill PID {}: {}", pid, e);
+                            Ok(ApiResponse::error(
                                 "KILL_ERROR".to_string(),
-                                format!("Failed to kill process: {}", e),
-                            )),
+                                format!("Failed to kill process (PID: {}): {}", pid, e),
+                            ))
                         }
                     }
+                } else if process_handle.run_result.spec.simulate {
+                    Ok(ApiResponse::success(process_handle.end_simulated()))
                 } else {
                     Ok(ApiResponse::error(
                         "NO_PID".to_string(),
@@ -297,40 +370,202 @@ pub async fn kill_eliza_run(
             "NOT_FOUND".to_string(),
             format!("Process {} not found or already completed", run_id),
         )),
+    };
+    drop(guard);
+
+    if let Ok(ref response) = response {
+        let detail = response.error.as_ref().map(|error| error.message.clone());
+        audit_run(
+            &app,
+            AuditAction::RunKilled,
+            &run_id,
+            response.success,
+            detail,
+        )
+        .await;
+    }
+
+    response
+}
+
+/// Interrupt a running ElizaOS CLI process as Ctrl-C would, letting the CLI run its own
+/// interrupt handlers; the run keeps its handle until the process actually exits
+#[tauri::command]
+pub async fn interrupt_eliza_run(
+    app: AppHandle,
+    run_id: String,
+) -> Result<ApiResponse<RunResult>, String> {
+    log::info!("Interrupting ElizaOS CLI run: {}", run_id);
+
+    let registry = get_process_registry(&app);
+    let guard = registry.read().await;
+
+    let response = match guard.get(&run_id) {
+        Some(process_handle_arc) => {
+            let mut process_handle = process_handle_arc.lock().await;
+
+            if !process_handle.can_control {
+                // Process already finished
+                Ok(ApiResponse::success(process_handle.run_result.clone()))
+            } else if let Some(pid) = process_handle.run_result.pid {
+                match send_interrupt(pid) {
+                    Ok(()) => {
+                        continue_paused_run(&app, &run_id);
+                        log::info!("Successfully sent interrupt to PID: {}", pid);
+                        Ok(ApiResponse::success(process_handle.run_result.clone()))
+                    }
+                    Err(e) => {
+                        log::error!("Failed to interrupt PID {}: {}", pid, e);
+                        Ok(ApiResponse::error(
+                            "INTERRUPT_ERROR".to_string(),
+                            format!("Failed to interrupt process (PID: {}): {}", pid, e),
+                        ))
+                    }
+                }
+            } else if process_handle.run_result.spec.simulate {
+                Ok(ApiResponse::success(process_handle.end_simulated()))
+            } else {
+                Ok(ApiResponse::error(
+                    "NO_PID".to_string(),
+                    "Process has no PID available for control".to_string(),
+                ))
+            }
+        }
+        None => Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Process {} not found or already completed", run_id),
+        )),
+    };
+    drop(guard);
+
+    if let Ok(ref response) = response {
+        let detail = response.error.as_ref().map(|error| error.message.clone());
+        audit_run(
+            &app,
+            AuditAction::RunInterrupted,
+            &run_id,
+            response.success,
+            detail,
+        )
+        .await;
+    }
+
+    response
+}
+
+/// Send SIGINT to the process group a run was started in
+#[cfg(unix)]
+pub(crate) fn send_interrupt(pid: u32) -> Result<(), String> {
+    crate::executor::signal_group(pid, nix::sys::signal::Signal::SIGINT)
+}
+
+/// Send CTRL_C_EVENT to a process by briefly attaching to its console
+#[cfg(windows)]
+pub(crate) fn send_interrupt(pid: u32) -> Result<(), String> {
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler, CTRL_C_EVENT,
+    };
+
+    // Only one console can be attached at a time, so interrupts must not overlap
+    static CONSOLE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    // SAFETY: plain Win32 calls; this process ignores Ctrl-C while attached so the event
+    // only reaches the target's console group
+    unsafe {
+        FreeConsole();
+        if AttachConsole(pid) == 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        SetConsoleCtrlHandler(None, 1);
+        let sent = GenerateConsoleCtrlEvent(CTRL_C_EVENT, 0) != 0;
+        let error = std::io::Error::last_os_error();
+        FreeConsole();
+        // Restore Ctrl-C handling once the event has been delivered
+        std::thread::sleep(Duration::from_millis(50));
+        SetConsoleCtrlHandler(None, 0);
+
+        if sent {
+            Ok(())
+        } else {
+            Err(error.to_string())
+        }
     }
 }
 
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn send_interrupt(_pid: u32) -> Result<(), String> {
+    Err("Interrupting processes is not supported on this platform".to_string())
+}
+
 /// Execute ElizaOS CLI run with simplified process management
 async fn execute_eliza_run_simple(
-    _app: AppHandle,
+    app: AppHandle,
     spec: RunSpec,
     config: SandboxConfig,
 ) -> Result<RunResult, AppError> {
     // Generate unique run ID using safe format
     let run_id = crate::models::generate_safe_run_id();
 
+    let config = with_saved_policy(config, &saved_config(&app)?);
+    let span = run_span(&run_id, &spec);
+    let result = run_simple(app.clone(), spec, config, run_id)
+        .instrument(span)
+        .await?;
+    record_run(&app, &result).await;
+    note_finished_run(&app, &result).await;
+    Ok(result)
+}
+
+async fn run_simple(
+    app: AppHandle,
+    spec: RunSpec,
+    config: SandboxConfig,
+    run_id: String,
+) -> Result<RunResult, AppError> {
+    // Runs need the API key and secrets, so the app lock must be open, and paths outside the
+    // allowed roots are refused; both apply to every mode, simulated and Doctor runs included
+    require_unlocked(&app)?;
+    check_run_paths(&app, &spec)?;
+
     // Create initial run result
     let mut run_result = RunResult::new(spec.clone(), run_id.clone());
 
+    if spec.simulate {
+        return Ok(execute_simulated_run(&app, run_result).await);
+    }
+
+    // Every real run is held to the saved budget, however it was started
+    check_run_budget(&app).await?;
+
+    // Doctor mode runs structured diagnostics instead of spawning the CLI
+    if matches!(spec.mode, RunMode::Doctor) {
+        return Ok(execute_doctor_run(&app, &config, run_result).await);
+    }
+
     // Determine ElizaOS CLI command
-    let (eliza_cmd, use_npx) = resolve_eliza_command().await?;
+    let runner = resolve_eliza_command_cached(&app, &config).await?;
+    let eliza_cmd = runner.program().to_string();
 
-    log::debug!("Using ElizaOS command: {} (npx: {})", eliza_cmd, use_npx);
+    log::debug!(
+        "Using ElizaOS command: {} (runner: {:?})",
+        eliza_cmd,
+        runner
+    );
 
-    // Build command arguments based on mode
-    let args = build_eliza_args(&spec, &config, use_npx)?;
+    // Resolve template variables and add the project's imported .env variables, the run
+    // result keeping the templated spec
+    let spec = resolve_run_spec(&app, &spec)?;
+    let spec = apply_project_env(&app, spec).await;
+    let spec = with_run_report_path(spec, &run_id);
+
+    // Build command arguments based on mode, held to the saved Custom allowlist
+    let saved = saved_config(&app)?;
+    let allowed = custom_allowlist(&saved, &config);
+    let args = build_eliza_args(&spec, &allowed, runner)?;
 
     // Sanitize arguments for logging (remove sensitive information)
-    let safe_args: Vec<String> = args
-        .iter()
-        .map(|arg| {
-            if arg.starts_with("eliza_") {
-                format!("{}***", &arg[..12])
-            } else {
-                arg.clone()
-            }
-        })
-        .collect();
+    let safe_args = sanitize_args_for_logging(&args);
 
     log::info!(
         "Executing: {} {} (working_dir: {:?})",
@@ -339,61 +574,59 @@ async fn execute_eliza_run_simple(
         spec.working_dir
     );
 
-    // Build environment variables for ElizaOS CLI execution
-    let env = build_eliza_env(&config);
-
-    // Spawn the real ElizaOS CLI process
-    let mut command = Command::new(&eliza_cmd);
-    command.args(&args);
-    command.envs(&env);
-
-    if let Some(ref wd) = spec.working_dir {
-        command.current_dir(wd);
-    }
-
-    // Configure for stdout/stderr capture
-    command.stdout(std::process::Stdio::piped());
-    command.stderr(std::process::Stdio::piped());
+    // Build environment variables for ElizaOS CLI execution; the app's own variables win
+    let mut env = spec.env.clone();
+    env.extend(build_eliza_env(
+        &config,
+        &saved,
+        spec.working_dir.as_deref(),
+    ));
+    env.extend(knowledge_env(spec.character_file.as_deref()));
+    run_result.environment = Some(capture_environment(runner, &env).await);
+
+    // Spawn the real ElizaOS CLI process, capturing stdout and stderr
+    let executor = run_executor();
+    let request = ExecRequest::new(&eliza_cmd, args)
+        .envs(env)
+        .working_dir(spec.working_dir.clone());
 
     let start_time = std::time::Instant::now();
     run_result.status = RunStatus::Running;
 
     log::info!(
-        "Spawning real ElizaOS CLI process: {} {:?}",
+        "Spawning real ElizaOS CLI process ({}): {} {:?}",
+        executor.name(),
         eliza_cmd,
         safe_args
     );
 
     // Execute and capture output
-    match command.spawn() {
-        Ok(child) => {
+    match executor.spawn(&request) {
+        Ok(process) => {
+            let _sleep_guard = sleep_guard(&app, &spec, &config, &run_id);
+            let _journal = JournaledRunGuard::start(&app, &run_id, spec.mode.clone(), process.pid);
+
             // Wait for completion and capture output
-            match child.wait_with_output() {
-                Ok(output) => {
+            match process.wait_with_output().await {
+                Ok((stdout, stderr, status)) => {
                     // Update run result with real data
-                    run_result.status = if output.status.success() {
+                    run_result.status = if status.success() {
                         RunStatus::Completed
                     } else {
                         RunStatus::Failed
                     };
 
-                    run_result.stdout = String::from_utf8_lossy(&output.stdout)
-                        .lines()
-                        .map(|s| s.to_string())
-                        .collect();
+                    run_result.binary_output = stdout.binary || stderr.binary;
+                    run_result.stdout = stdout.lines;
+                    run_result.stderr = stderr.lines;
 
-                    run_result.stderr = String::from_utf8_lossy(&output.stderr)
-                        .lines()
-                        .map(|s| s.to_string())
-                        .collect();
-
-                    run_result.exit_code = output.status.code();
+                    run_result.exit_code = status.code();
                     run_result.ended_at = Some(crate::models::current_timestamp());
                     run_result.duration_ms = Some(start_time.elapsed().as_millis() as u64);
 
                     log::info!(
                         "ElizaOS CLI process completed: exit_code={:?}, duration={}ms",
-                        output.status.code(),
+                        status.code(),
                         start_time.elapsed().as_millis()
                     );
                 }
@@ -419,6 +652,8 @@ async fn execute_eliza_run_simple(
         }
     }
 
+    run_result.eval_result = collect_eval_result(&spec);
+
     Ok(run_result)
 }
 
@@ -431,11 +666,178 @@ async fn execute_eliza_run_streaming(
     // Generate unique run ID using safe format
     let run_id = crate::models::generate_safe_run_id();
 
+    execute_eliza_run_streaming_with_id(app, spec, config, run_id).await
+}
+
+/// Execute a streaming run under a caller-chosen run ID (used by run groups)
+pub(crate) async fn execute_eliza_run_streaming_with_id(
+    app: AppHandle,
+    spec: RunSpec,
+    config: SandboxConfig,
+    run_id: String,
+) -> Result<RunResult, AppError> {
+    let config = with_saved_policy(config, &saved_config(&app)?);
+    let span = run_span(&run_id, &spec);
+    // Held until the run finishes; waits here while the queue is full
+    let _slot = acquire_run_slot(&app, &run_id, spec.effective_priority())
+        .instrument(span.clone())
+        .await;
+    let result = run_streaming(app.clone(), spec, config, run_id)
+        .instrument(span)
+        .await?;
+    record_run(&app, &result).await;
+    note_finished_run(&app, &result).await;
+    Ok(result)
+}
+
+/// The request's connection settings under the saved profile's run policy: queue, watchdog,
+/// egress, sleep, PATH and runner settings come from the profile, so a run request can't
+/// loosen them. The request's Custom allowlist is kept, `custom_allowlist` only letting it
+/// narrow the saved one
+fn with_saved_policy(requested: SandboxConfig, saved: &SandboxConfig) -> SandboxConfig {
+    SandboxConfig {
+        base_url: requested.base_url,
+        api_key: requested.api_key,
+        default_model: requested.default_model,
+        api_key_ref: requested.api_key_ref,
+        allowed_custom_subcommands: requested.allowed_custom_subcommands,
+        ..saved.clone()
+    }
+}
+
+/// Suggest a free port when an agent run failed because its port was taken, emitting
+/// `run-retry-suggested`; with `autoRetryPortConflicts` set the retry starts right away
+///
+/// Returns the last run, which is the original one unless a retry was started.
+async fn retry_on_port_conflict(
+    app: &AppHandle,
+    mut result: RunResult,
+    config: &SandboxConfig,
+) -> RunResult {
+    for attempt in 1..=MAX_PORT_CONFLICT_RETRIES {
+        if !matches!(result.spec.mode, RunMode::Run) || result.status != RunStatus::Failed {
+            break;
+        }
+        let Some(conflicting_port) = detect_port_conflict(&result.stderr) else {
+            break;
+        };
+
+        let automatic = saved_config(app).is_ok_and(|saved| saved.auto_retry_port_conflicts);
+        let retry_run_id = crate::models::generate_safe_run_id();
+        // Only an automatic retry holds its port; a suggestion may never be taken up
+        let port = if automatic {
+            reserve_agent_port(app, &retry_run_id).await
+        } else {
+            suggest_agent_port(app).await
+        };
+        let port = match port {
+            Ok(port) => port,
+            Err(e) => {
+                log::warn!("No port to suggest for run {}: {}", result.id, e);
+                break;
+            }
+        };
+
+        let spec = with_agent_port(&result.spec, port);
+        let action = if automatic {
+            "retrying on"
+        } else {
+            "suggesting"
+        };
+        match conflicting_port {
+            Some(taken) => log::warn!(
+                "Run {} failed because port {} is taken; {} port {}",
+                result.id,
+                taken,
+                action,
+                port
+            ),
+            None => log::warn!(
+                "Run {} failed because its port is taken; {} port {}",
+                result.id,
+                action,
+                port
+            ),
+        }
+        emit_event(
+            app,
+            "run-retry-suggested",
+            RunRetrySuggestedEvent {
+                run_id: result.id.clone(),
+                conflicting_port,
+                suggested_port: port,
+                spec: spec.clone(),
+                automatic,
+                retry_run_id: automatic.then(|| retry_run_id.clone()),
+                attempt,
+                timestamp: chrono::Utc::now().timestamp(),
+            },
+        );
+        if !automatic {
+            break;
+        }
+
+        let detail = format!(
+            "port conflict retry of {}: {}",
+            result.id,
+            describe_run_for_audit(&spec)
+        );
+        let retried = execute_eliza_run_streaming_with_id(
+            app.clone(),
+            spec,
+            config.clone(),
+            retry_run_id.clone(),
+        )
+        .await;
+        release_agent_ports(app, &retry_run_id).await;
+        audit_run(
+            app,
+            AuditAction::RunStarted,
+            &retry_run_id,
+            retried.is_ok(),
+            Some(match &retried {
+                Ok(_) => detail,
+                Err(e) => format!("{} ({})", detail, e),
+            }),
+        )
+        .await;
+        match retried {
+            Ok(retried) => result = retried,
+            Err(e) => {
+                log::error!(
+                    "Port conflict retry of {} failed to start: {}",
+                    result.id,
+                    e
+                );
+                break;
+            }
+        }
+    }
+    result
+}
+
+/// Span carrying the run ID so every log line of a run can be filtered by it
+fn run_span(run_id: &str, spec: &RunSpec) -> tracing::Span {
+    tracing::info_span!("run", run_id = %run_id, mode = %spec.mode)
+}
+
+async fn run_streaming(
+    app: AppHandle,
+    spec: RunSpec,
+    config: SandboxConfig,
+    run_id: String,
+) -> Result<RunResult, AppError> {
+    // Runs need the API key and secrets, so the app lock must be open, and paths outside the
+    // allowed roots are refused; both apply to every mode, simulated and Doctor runs included
+    require_unlocked(&app)?;
+    check_run_paths(&app, &spec)?;
+
     // Create initial run result
     let mut run_result = RunResult::new(spec.clone(), run_id.clone());
 
     // Emit system log about starting
-    let _ = app.emit(
+    emit_event(
+        &app,
         "log-event",
         LogEvent::system(
             run_id.clone(),
@@ -443,26 +845,77 @@ async fn execute_eliza_run_streaming(
         ),
     );
 
+    if spec.simulate {
+        return Ok(execute_simulated_run(&app, run_result).await);
+    }
+
+    // Every real run is held to the saved budget, however it was started
+    check_run_budget(&app).await?;
+
+    // Doctor mode runs structured diagnostics instead of spawning the CLI
+    if matches!(spec.mode, RunMode::Doctor) {
+        let run_result = execute_doctor_run(&app, &config, run_result).await;
+        emit_event(
+            &app,
+            "log-event",
+            LogEvent::system(
+                run_id.clone(),
+                format!("Doctor finished (exit code: {:?})", run_result.exit_code),
+            ),
+        );
+        return Ok(run_result);
+    }
+
     // Determine ElizaOS CLI command
-    let (eliza_cmd, use_npx) = resolve_eliza_command().await?;
+    let runner = resolve_eliza_command_cached(&app, &config).await?;
+    let eliza_cmd = runner.program().to_string();
 
-    log::debug!("Using ElizaOS command: {} (npx: {})", eliza_cmd, use_npx);
+    log::debug!(
+        "Using ElizaOS command: {} (runner: {:?})",
+        eliza_cmd,
+        runner
+    );
 
-    // Build command arguments and environment
-    let args = build_eliza_args(&spec, &config, use_npx)?;
-    let env = build_eliza_env(&config);
+    // Resolve template variables and add the project's imported .env variables, the run
+    // result keeping the templated spec
+    let spec = resolve_run_spec(&app, &spec)?;
+    let spec = apply_project_env(&app, spec).await;
+    let spec = with_run_report_path(spec, &run_id);
+
+    // Build command arguments, held to the saved Custom allowlist, and environment; the
+    // app's own variables win
+    let saved = saved_config(&app)?;
+    let allowed = custom_allowlist(&saved, &config);
+    let args = build_eliza_args(&spec, &allowed, runner)?;
+    let mut env = spec.env.clone();
+    env.extend(build_eliza_env(
+        &config,
+        &saved,
+        spec.working_dir.as_deref(),
+    ));
+    env.extend(knowledge_env(spec.character_file.as_deref()));
+
+    run_result.environment = Some(capture_environment(runner, &env).await);
+
+    // npx downloads the CLI on first use; its fetch log drives `cli-install-progress`
+    let install_progress = if runner == CliRunner::Npx {
+        env.extend(npx_progress_env());
+        Some(Arc::new(InstallProgress::new(run_id.clone())))
+    } else {
+        None
+    };
+
+    // Route the run's HTTP traffic through a recording proxy; it stops when dropped
+    let capture_proxy = if spec.capture_network {
+        let proxy = start_capture_proxy(&app, &run_id).await?;
+        env.extend(proxy.env());
+        Some(proxy)
+    } else {
+        None
+    };
 
     // Sanitize arguments for logging
-    let safe_args: Vec<String> = args
-        .iter()
-        .map(|arg| {
-            if arg.starts_with("eliza_") {
-                format!("{}***", &arg[..12])
-            } else {
-                arg.clone()
-            }
-        })
-        .collect();
+    let safe_args = sanitize_args_for_logging(&args);
 
     log::info!(
         "Executing with streaming: {} {} (working_dir: {:?})",
@@ -472,7 +925,8 @@ async fn execute_eliza_run_streaming(
     );
 
     // Emit command info
-    let _ = app.emit(
+    emit_event(
+        &app,
         "log-event",
         LogEvent::info(
             run_id.clone(),
@@ -480,29 +934,25 @@ async fn execute_eliza_run_streaming(
         ),
     );
 
-    // Use tokio::process::Command for async execution
-    let mut command = TokioCommand::new(&eliza_cmd);
-    command.args(&args);
-    command.envs(&env);
-
-    if let Some(ref wd) = spec.working_dir {
-        command.current_dir(wd);
-    }
-
-    // Configure for stdout/stderr capture
-    command.stdout(std::process::Stdio::piped());
-    command.stderr(std::process::Stdio::piped());
+    let executor = run_executor();
+    let request = ExecRequest::new(&eliza_cmd, args)
+        .envs(env)
+        .working_dir(spec.working_dir.clone());
 
     let start_time = std::time::Instant::now();
     run_result.status = RunStatus::Running;
 
     // Spawn the process
-    match command.spawn() {
-        Ok(mut child) => {
+    match executor.spawn(&request) {
+        Ok(mut process) => {
+            let _sleep_guard = sleep_guard(&app, &spec, &config, &run_id);
+            let _journal = JournaledRunGuard::start(&app, &run_id, spec.mode.clone(), process.pid);
+
             // Capture process ID and create initial process handle entry
-            if let Some(pid) = child.id() {
+            if let Some(pid) = process.pid {
                 run_result.pid = Some(pid);
                 log::info!("Started ElizaOS CLI process: PID={}", pid);
+                set_run_pid(&app, &run_id, pid);
 
                 // Register process in registry for control operations
                 let registry = get_process_registry(&app);
@@ -515,53 +965,68 @@ async fn execute_eliza_run_streaming(
             }
 
             // Get stdout and stderr handles
-            let stdout = child
-                .stdout
-                .take()
+            let stdout = process
+                .take_stdout()
                 .ok_or_else(|| AppError::Process("Failed to get stdout handle".to_string()))?;
 
-            let stderr = child
-                .stderr
-                .take()
+            let stderr = process
+                .take_stderr()
                 .ok_or_else(|| AppError::Process("Failed to get stderr handle".to_string()))?;
 
             // Spawn tasks for streaming logs
-            let app_stdout = app.clone();
-            let run_id_stdout = run_id.clone();
-            let stdout_task = tokio::spawn(async move {
-                let reader = BufReader::new(stdout);
-                let mut lines = reader.lines();
-                let mut stdout_lines = Vec::new();
-
-                while let Ok(Some(line)) = lines.next_line().await {
-                    stdout_lines.push(line.clone());
-                    let _ =
-                        app_stdout.emit("log-event", LogEvent::stdout(run_id_stdout.clone(), line));
-                }
-                stdout_lines
+            let activity = OutputActivity::new();
+            let run_log = open_run_log(&app, &run_id, spec.min_event_severity).await;
+            let stdout_task = tokio::spawn(stream_output(
+                app.clone(),
+                run_id.clone(),
+                stdout,
+                LogType::Stdout,
+                activity.clone(),
+                run_log.clone(),
+                install_progress.clone(),
+            ));
+            let stderr_task = tokio::spawn(stream_output(
+                app.clone(),
+                run_id.clone(),
+                stderr,
+                LogType::Stderr,
+                activity.clone(),
+                run_log.clone(),
+                install_progress,
+            ));
+            let watchdog = config.watchdog.clone().map(|watchdog| {
+                spawn_watchdog(
+                    app.clone(),
+                    run_id.clone(),
+                    run_result.pid,
+                    activity,
+                    watchdog,
+                )
             });
 
-            let app_stderr = app.clone();
-            let run_id_stderr = run_id.clone();
-            let stderr_task = tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                let mut lines = reader.lines();
-                let mut stderr_lines = Vec::new();
-
-                while let Ok(Some(line)) = lines.next_line().await {
-                    stderr_lines.push(line.clone());
-                    let _ =
-                        app_stderr.emit("log-event", LogEvent::stderr(run_id_stderr.clone(), line));
-                }
-                stderr_lines
-            });
+            let egress = config
+                .egress
+                .clone()
+                .zip(run_result.pid)
+                .map(|(egress, pid)| {
+                    spawn_egress_monitor(app.clone(), run_id.clone(), pid, egress, &config.base_url)
+                });
 
             // Wait for process completion
-            let status_result = child.wait().await;
+            let status_result = process.wait().await;
+            if let Some(watchdog) = watchdog {
+                watchdog.abort();
+            }
+            run_result.network = egress.map(EgressMonitor::finish);
+            drop(capture_proxy);
 
             // Wait for log streaming tasks to complete
-            let stdout_lines = stdout_task.await.unwrap_or_default();
-            let mut stderr_lines = stderr_task.await.unwrap_or_default();
+            let stdout_output = stdout_task.await.unwrap_or_default();
+            let stderr_output = stderr_task.await.unwrap_or_default();
+            run_log.finish();
+            run_result.binary_output = stdout_output.binary || stderr_output.binary;
+            let stdout_lines = stdout_output.lines;
+            let mut stderr_lines = stderr_output.lines;
 
             // Update run result
             match status_result {
@@ -584,6 +1049,21 @@ async fn execute_eliza_run_streaming(
             run_result.stderr = stderr_lines;
             run_result.ended_at = Some(crate::models::current_timestamp());
             run_result.duration_ms = Some(start_time.elapsed().as_millis() as u64);
+            run_result.eval_result = collect_eval_result(&spec);
+
+            if let Some(ref eval_result) = run_result.eval_result {
+                emit_event(
+                    &app,
+                    "log-event",
+                    LogEvent::info(
+                        run_id.clone(),
+                        format!(
+                            "Eval completed: {}/{} passed",
+                            eval_result.passed, eval_result.total
+                        ),
+                    ),
+                );
+            }
 
             // Update the process handle in the registry with the final result
             let registry = get_process_registry(&app);
@@ -622,7 +1102,11 @@ async fn execute_eliza_run_streaming(
                 _ => "Process ended".to_string(),
             };
 
-            let _ = app.emit("log-event", LogEvent::system(run_id.clone(), status_msg));
+            emit_event(
+                &app,
+                "log-event",
+                LogEvent::system(run_id.clone(), status_msg),
+            );
 
             log::info!(
                 "Streaming ElizaOS CLI process completed: exit_code={:?}, duration={}ms, stdout_lines={}, stderr_lines={}",
@@ -642,7 +1126,8 @@ async fn execute_eliza_run_streaming(
             run_result.ended_at = Some(crate::models::current_timestamp());
             run_result.duration_ms = Some(start_time.elapsed().as_millis() as u64);
 
-            let _ = app.emit(
+            emit_event(
+                &app,
                 "log-event",
                 LogEvent::error(run_id.clone(), format!("Failed to spawn process: {}", e)),
             );
@@ -653,104 +1138,158 @@ async fn execute_eliza_run_streaming(
     }
 }
 
-/// Resolve the ElizaOS CLI command to use
-async fn resolve_eliza_command() -> Result<(String, bool), AppError> {
-    // Try elizaos command (from @elizaos/cli package)
-    if let Ok(output) = Command::new("elizaos").arg("--version").output() {
-        if output.status.success() {
-            log::debug!("Found elizaos CLI locally installed");
-            return Ok(("elizaos".to_string(), false));
-        }
-    }
-
-    // Try npx approach with correct package
-    if let Ok(output) = Command::new("npx")
-        .args(["-y", "@elizaos/cli@latest", "--version"])
-        .output()
-    {
-        if output.status.success() {
-            log::debug!("ElizaOS CLI available via npx");
-            return Ok(("npx".to_string(), true));
+/// Forward a child pipe as log events, returning the lines to persist
+///
+/// Carriage-return rewrites are sent as throttled `run-progress-line` updates and only
+/// their final state is kept; binary output is replaced with a summarized placeholder.
+async fn stream_output<R: AsyncRead + Unpin>(
+    app: AppHandle,
+    run_id: String,
+    pipe: R,
+    log_type: LogType,
+    activity: Arc<OutputActivity>,
+    run_log: Arc<RunLogBuffer>,
+    install_progress: Option<Arc<InstallProgress>>,
+) -> CapturedOutput {
+    let mut reader = StreamReader::new(pipe);
+    let mut output = CapturedOutput::default();
+    let mut in_progress = false;
+    let mut last_progress_emit: Option<Instant> = None;
+    let mut last_severity = None;
+
+    while let Some(item) = reader.next_item().await {
+        activity.touch();
+        match item {
+            StreamItem::Progress(ref text) => {
+                in_progress = true;
+                if last_progress_emit.is_none_or(|at| at.elapsed() >= PROGRESS_EMIT_INTERVAL) {
+                    last_progress_emit = Some(Instant::now());
+                    emit_event(
+                        &app,
+                        "run-progress-line",
+                        ProgressLineEvent::new(
+                            run_id.clone(),
+                            log_type.clone(),
+                            text.clone(),
+                            false,
+                        ),
+                    );
+                }
+            }
+            StreamItem::Line(ref text) => {
+                if in_progress {
+                    in_progress = false;
+                    last_progress_emit = None;
+                    emit_event(
+                        &app,
+                        "run-progress-line",
+                        ProgressLineEvent::new(
+                            run_id.clone(),
+                            log_type.clone(),
+                            text.clone(),
+                            true,
+                        ),
+                    );
+                }
+                if let Some(ref install_progress) = install_progress {
+                    install_progress.observe(&app, text);
+                }
+                // Package-manager chatter on stderr is shown as info rather than an error
+                let (event_type, severity) =
+                    if matches!(log_type, LogType::Stderr) && is_benign_stderr(text) {
+                        (LogType::Info, Some(LogSeverity::Info))
+                    } else {
+                        // Unmarked lines (stack traces, wrapped output) inherit the previous level
+                        last_severity = parse_severity(text).or(last_severity);
+                        (log_type.clone(), last_severity)
+                    };
+                let offset = run_log.push(event_type.clone(), severity, text.clone());
+                if run_log.should_emit(severity, text) {
+                    emit_event(
+                        &app,
+                        "log-event",
+                        LogEvent::new(run_id.clone(), text.clone(), event_type)
+                            .with_offset(offset)
+                            .with_severity(severity),
+                    );
+                }
+            }
+            StreamItem::Binary(ref chunk) => {
+                log::warn!(
+                    "Run {} emitted {} bytes of binary output on {:?}",
+                    run_id,
+                    chunk.bytes,
+                    log_type
+                );
+                emit_event(
+                    &app,
+                    "log-event",
+                    LogEvent::new(run_id.clone(), chunk.placeholder(), log_type.clone()),
+                );
+            }
         }
+        output.push(item);
     }
 
-    Err(AppError::CliNotFound(
-        "ElizaOS CLI not available. Please install with: npm install -g @elizaos/cli@latest"
-            .to_string(),
-    ))
+    output
 }
 
 /// Build ElizaOS CLI arguments based on run specification
 fn build_eliza_args(
     spec: &RunSpec,
-    _config: &SandboxConfig,
-    use_npx: bool,
+    allowed_subcommands: &[String],
+    runner: CliRunner,
 ) -> Result<Vec<String>, AppError> {
-    let mut args = Vec::new();
-
-    // If using npx, add the package specification
-    if use_npx {
-        args.push("-y".to_string());
-        args.push("@elizaos/cli@latest".to_string());
-    }
-
-    // Actual ElizaOS CLI commands based on real CLI capabilities
-    match spec.mode {
-        RunMode::Doctor => {
-            // Doctor mode: run system tests to check ElizaOS capabilities
-            args.push("test".to_string());
-            args.push("--type".to_string());
-            args.push("component".to_string());
-            args.push("--skip-build".to_string());
-        }
-        RunMode::Run => {
-            // Run mode: Start ElizaOS agent server
-            args.push("start".to_string());
-            if !spec.args.is_empty() {
-                // Add character file if specified in args
-                args.push("--character".to_string());
-                args.push(spec.args[0].clone());
-            }
-        }
-        RunMode::Eval => {
-            // Eval mode: Development mode
-            args.push("dev".to_string());
-        }
-        RunMode::Custom => {
-            // Custom command from spec.args[0] if available
-            if !spec.args.is_empty() {
-                args.push(spec.args[0].clone());
-            } else {
-                // Default to showing help
-                args.push("--help".to_string());
-            }
-        }
-    }
+    // Package runners (npx/bunx) need the package specification first
+    let mut args = runner.package_args();
 
-    // Add character file if specified
-    if let Some(ref character_file) = spec.character_file {
-        args.push("--character".to_string());
-        args.push(character_file.clone());
-    }
-
-    // Add additional arguments (skip first for Custom mode since it's the command)
-    let skip_count = if matches!(spec.mode, RunMode::Custom) && !spec.args.is_empty() {
-        1
-    } else {
-        0
-    };
-    args.extend(spec.args.iter().skip(skip_count).cloned());
+    // Mode-specific subcommand and flags
+    args.extend(validated_mode_args(spec, allowed_subcommands)?.to_cli_args());
 
     Ok(args)
 }
 
-/// Build environment variables for ElizaOS CLI execution
-fn build_eliza_env(config: &SandboxConfig) -> HashMap<String, String> {
+/// Where run processes start; the one place a containerized or remote run mode picks a
+/// different executor
+fn run_executor() -> &'static dyn Executor {
+    &LocalExecutor
+}
+
+/// Keep the system awake for agent and eval runs when the config asks for it
+fn sleep_guard(
+    app: &AppHandle,
+    spec: &RunSpec,
+    config: &SandboxConfig,
+    run_id: &str,
+) -> Option<SleepGuard> {
+    let long_running = matches!(spec.mode, RunMode::Run | RunMode::Eval);
+    (config.prevent_sleep && long_running).then(|| SleepGuard::acquire(app, run_id))
+}
+
+/// Record tool versions as the spawned CLI will see them
+async fn capture_environment(runner: CliRunner, env: &HashMap<String, String>) -> RunEnvironment {
+    let path_env = env.get("PATH").map(String::as_str).unwrap_or_default();
+    let environment = capture_run_environment(runner, path_env).await;
+    log::debug!("Run environment: {:?}", environment);
+    environment
+}
+
+/// Build environment variables for ElizaOS CLI execution; the `.eliza-desktop.json` of
+/// the working directory may override the base URL and model, and the API key is left out
+/// when its base URL is not trusted by the `saved` profile
+fn build_eliza_env(
+    config: &SandboxConfig,
+    saved: &SandboxConfig,
+    working_dir: Option<&str>,
+) -> HashMap<String, String> {
+    let config = &project_config(config, saved, working_dir);
     let mut env = HashMap::new();
 
     // ElizaOS Cloud API environment variables (matching real ElizaOS structure)
     env.insert("ELIZAOS_BASE_URL".to_string(), config.base_url.clone());
-    env.insert("ELIZAOS_API_KEY".to_string(), config.api_key.clone());
+    if !config.api_key.is_empty() {
+        env.insert("ELIZAOS_API_KEY".to_string(), config.api_key.clone());
+    }
 
     if let Some(ref model) = config.default_model {
         env.insert("ELIZAOS_LARGE_MODEL".to_string(), model.clone());
@@ -761,6 +1300,12 @@ fn build_eliza_env(config: &SandboxConfig) -> HashMap<String, String> {
     env.insert("NODE_ENV".to_string(), "production".to_string());
     env.insert("ELIZA_DESKTOP".to_string(), "true".to_string());
 
+    // Make version-manager installs of node/bun visible to the CLI
+    env.insert(
+        "PATH".to_string(),
+        build_spawn_path(config.extra_path_dirs()),
+    );
+
     log::debug!("Built environment variables for ElizaOS CLI (API keys redacted)");
 
     env
@@ -771,6 +1316,31 @@ pub fn get_process_registry(app: &AppHandle) -> ProcessRegistry {
     app.state::<ProcessRegistry>().inner().clone()
 }
 
+/// Whether any run in the registry is still running
+pub(crate) async fn has_running_runs(app: &AppHandle) -> bool {
+    let registry = get_process_registry(app);
+    let guard = registry.read().await;
+    for handle in guard.values() {
+        if handle.lock().await.run_result.status == RunStatus::Running {
+            return true;
+        }
+    }
+    false
+}
+
+/// IDs of the runs in the registry that are still running
+pub(crate) async fn running_run_ids(app: &AppHandle) -> Vec<String> {
+    let registry = get_process_registry(app);
+    let guard = registry.read().await;
+    let mut run_ids = Vec::new();
+    for (run_id, handle) in guard.iter() {
+        if handle.lock().await.run_result.status == RunStatus::Running {
+            run_ids.push(run_id.clone());
+        }
+    }
+    run_ids
+}
+
 /// Initialize the process registry (called from main)
 pub fn init_process_registry() -> ProcessRegistry {
     Arc::new(RwLock::new(HashMap::new()))
@@ -800,8 +1370,31 @@ pub async fn get_run_result(
     }
 }
 
+/// Record a run lifecycle action in the audit log
+pub(crate) async fn audit_run(
+    app: &AppHandle,
+    action: AuditAction,
+    run_id: &str,
+    success: bool,
+    detail: Option<String>,
+) {
+    let entry =
+        AuditEntry::new(action, AuditOrigin::Gui, run_id.to_string()).with_outcome(success, detail);
+    record_audit(app, entry).await;
+}
+
+/// Mode and sanitized arguments of a run, for the audit log
+pub(crate) fn describe_run_for_audit(spec: &RunSpec) -> String {
+    let args = sanitize_args_for_logging(&spec.args);
+    if args.is_empty() {
+        spec.mode.to_string()
+    } else {
+        format!("{} {}", spec.mode, args.join(" "))
+    }
+}
+
 /// Sanitize command arguments for logging (remove API keys)
-fn sanitize_args_for_logging(args: &[String]) -> Vec<String> {
+pub(crate) fn sanitize_args_for_logging(args: &[String]) -> Vec<String> {
     args.iter()
         .map(|arg| {
             if arg.starts_with("eliza_") && arg.len() > 20 {
@@ -842,19 +1435,40 @@ mod tests {
             working_dir: None,
             character_file: None,
             env: std::collections::HashMap::new(),
+            eval: None,
+            port: None,
+            after: None,
+            simulate: false,
+            min_event_severity: None,
+            priority: None,
+            capture_network: false,
+            variables: std::collections::HashMap::new(),
         };
 
         let config = SandboxConfig {
             base_url: "https://api.example.com".to_string(),
             api_key: "eliza_test_key".to_string(),
             default_model: Some("gpt-4".to_string()),
+            ..Default::default()
         };
 
-        let args = build_eliza_args(&spec, &config, true).unwrap();
-        assert!(args.contains(&"start".to_string()));
-        assert!(args.contains(&"--mode".to_string()));
-        assert!(args.contains(&"diagnostic".to_string()));
-        assert!(args.contains(&"--verbose".to_string()));
+        let args =
+            build_eliza_args(&spec, &config.allowed_custom_subcommands(), CliRunner::Npx).unwrap();
+        assert_eq!(args, vec!["-y", "@elizaos/cli@latest", "--version"]);
+    }
+
+    #[test]
+    fn test_build_eliza_args_bunx() {
+        let spec = RunSpec::new("test".to_string(), RunMode::Run, vec![]);
+        let allowed = SandboxConfig::default().allowed_custom_subcommands();
+
+        let direct = build_eliza_args(&spec, &allowed, CliRunner::Elizaos).unwrap();
+        let bunx = build_eliza_args(&spec, &allowed, CliRunner::Bunx).unwrap();
+        let npx = build_eliza_args(&spec, &allowed, CliRunner::Npx).unwrap();
+        assert_eq!(direct, vec!["start"]);
+        // bunx takes the package without npx's `-y`, ahead of the same subcommand
+        assert_eq!(bunx, vec!["@elizaos/cli@latest", "start"]);
+        assert_eq!(npx, vec!["-y", "@elizaos/cli@latest", "start"]);
     }
 
     #[test]
@@ -863,9 +1477,10 @@ mod tests {
             base_url: "https://api.example.com".to_string(),
             api_key: "eliza_test_key".to_string(),
             default_model: Some("gpt-4".to_string()),
+            ..Default::default()
         };
 
-        let env = build_eliza_env(&config);
+        let env = build_eliza_env(&config, &config, None);
         assert_eq!(
             env.get("ELIZAOS_BASE_URL"),
             Some(&"https://api.example.com".to_string())
@@ -877,4 +1492,49 @@ mod tests {
         assert_eq!(env.get("ELIZAOS_LARGE_MODEL"), Some(&"gpt-4".to_string()));
         assert_eq!(env.get("ELIZAOS_SMALL_MODEL"), Some(&"gpt-4".to_string()));
     }
+
+    #[test]
+    fn test_with_saved_policy() {
+        let saved = SandboxConfig {
+            base_url: "https://api.example.com".to_string(),
+            api_key: "eliza_saved_key".to_string(),
+            extra_path_dirs: Some(vec!["/opt/eliza/bin".to_string()]),
+            prevent_sleep: true,
+            ..Default::default()
+        };
+        let requested = SandboxConfig {
+            base_url: "https://staging.example.com".to_string(),
+            api_key: "eliza_request_key".to_string(),
+            runner_priority: Some(vec![CliRunner::Npx]),
+            extra_path_dirs: Some(vec!["/tmp/evil".to_string()]),
+            auto_retry_port_conflicts: true,
+            ..Default::default()
+        };
+
+        let config = with_saved_policy(requested, &saved);
+        assert_eq!(config.base_url, "https://staging.example.com");
+        assert_eq!(config.api_key, "eliza_request_key");
+        assert_eq!(config.runner_priority, None);
+        assert_eq!(config.extra_path_dirs, saved.extra_path_dirs);
+        assert!(config.prevent_sleep);
+        assert!(!config.auto_retry_port_conflicts);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_interrupt() {
+        use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        send_interrupt(child.id()).unwrap();
+        let status = child.wait().unwrap();
+        assert_eq!(
+            status.signal(),
+            Some(nix::sys::signal::Signal::SIGINT as i32)
+        );
+    }
 }
@@ -0,0 +1,285 @@
+//! Per-project Sandbox configuration
+//! A project directory may hold a `.eliza-desktop.json` overriding the base URL and model of
+//! runs started in it, so agent projects can target different sandboxes without switching
+//! the global configuration. The API key always comes from the app's configuration:
+//!
+//! ```json
+//! { "baseUrl": "https://staging.example.com", "defaultModel": "gpt-4o-mini" }
+//! ```
+//!
+//! Override files come with cloned repositories, so the key only goes to a base URL whose
+//! host is the saved profile's or one the user trusted with `set_trusted_project_hosts`;
+//! runs against any other host start without it
+
+use crate::commands::config::{load_config_from_file, validate_base_url};
+use crate::commands::path_jail::check_path_allowed;
+use crate::commands::terminal::resolve_working_directory;
+use crate::models::{
+    ApiResponse, AppError, ProjectConfigOverride, ProjectConfigReport, SandboxConfig,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+pub(crate) const PROJECT_CONFIG_FILE: &str = ".eliza-desktop.json";
+
+/// Show a project's overrides and the settings its runs would use
+#[tauri::command]
+pub async fn inspect_project_config(
+    app: AppHandle,
+    project_dir: String,
+) -> Result<ApiResponse<ProjectConfigReport>, String> {
+    let result = async {
+        let dir = PathBuf::from(resolve_working_directory(project_dir.clone()));
+        check_path_allowed(&app, &dir, "Project directory")?;
+        if !dir.is_dir() {
+            return Err(AppError::Config(format!(
+                "Project directory '{}' does not exist",
+                project_dir
+            )));
+        }
+
+        let overrides = load_project_config(&dir)?;
+        let config = load_config_from_file(&app)
+            .await?
+            .unwrap_or_else(|| SandboxConfig::new(String::new(), String::new()));
+        let effective = match overrides {
+            Some(ref overrides) => apply_overrides(&config, &config, overrides),
+            None => config.clone(),
+        };
+        let needs_approval = overrides
+            .as_ref()
+            .and_then(|overrides| overrides.base_url.as_deref())
+            .is_some_and(|base_url| !is_trusted_base_url(&config, base_url));
+
+        Ok::<_, AppError>(ProjectConfigReport {
+            config_path: dir.join(PROJECT_CONFIG_FILE).to_string_lossy().to_string(),
+            overrides,
+            base_url: effective.base_url,
+            default_model: effective.default_model,
+            needs_approval,
+        })
+    }
+    .await;
+
+    match result {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => {
+            log::error!("Failed to read project configuration: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!(
+                    "Failed to read project configuration: {}",
+                    e.localized_message()
+                ),
+            ))
+        }
+    }
+}
+
+/// `config` with the overrides of the run's working directory applied, trusting base URLs
+/// by the `saved` profile; a broken override file is logged and ignored, so the run still
+/// gets a working configuration
+pub(crate) fn project_config(
+    config: &SandboxConfig,
+    saved: &SandboxConfig,
+    working_dir: Option<&str>,
+) -> SandboxConfig {
+    let Some(working_dir) = working_dir else {
+        return config.clone();
+    };
+    let dir = PathBuf::from(resolve_working_directory(working_dir.to_string()));
+
+    match load_project_config(&dir) {
+        Ok(Some(overrides)) => {
+            log::info!(
+                "Applying Sandbox overrides from {} (base URL: {}, model: {})",
+                dir.join(PROJECT_CONFIG_FILE).display(),
+                overrides.base_url.as_deref().unwrap_or("unchanged"),
+                overrides.default_model.as_deref().unwrap_or("unchanged")
+            );
+            apply_overrides(config, saved, &overrides)
+        }
+        Ok(None) => config.clone(),
+        Err(e) => {
+            log::warn!(
+                "Ignoring {} of {}: {}",
+                PROJECT_CONFIG_FILE,
+                dir.display(),
+                e
+            );
+            config.clone()
+        }
+    }
+}
+
+/// The overrides in `dir`, None when it has no override file
+fn load_project_config(dir: &Path) -> Result<Option<ProjectConfigOverride>, AppError> {
+    let path = dir.join(PROJECT_CONFIG_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    parse_project_config(&fs::read_to_string(&path)?).map(Some)
+}
+
+fn parse_project_config(contents: &str) -> Result<ProjectConfigOverride, AppError> {
+    let overrides: ProjectConfigOverride = serde_json::from_str(contents)
+        .map_err(|e| AppError::Config(format!("{} is not valid: {}", PROJECT_CONFIG_FILE, e)))?;
+
+    if let Some(ref base_url) = overrides.base_url {
+        if !validate_base_url(base_url.trim()) {
+            return Err(AppError::Config(format!(
+                "Invalid base URL in {}: '{}' must start with http:// or https://",
+                PROJECT_CONFIG_FILE, base_url
+            )));
+        }
+    }
+    if overrides
+        .default_model
+        .as_ref()
+        .is_some_and(|model| model.trim().is_empty())
+    {
+        return Err(AppError::Config(format!(
+            "The model in {} is empty",
+            PROJECT_CONFIG_FILE
+        )));
+    }
+    Ok(overrides)
+}
+
+/// Whether runs may send the API key to `base_url`: its host is the saved base URL's or a
+/// trusted project host
+pub(crate) fn is_trusted_base_url(saved: &SandboxConfig, base_url: &str) -> bool {
+    let Some(host) = url_host(base_url) else {
+        return false;
+    };
+    url_host(&saved.base_url).as_deref() == Some(host.as_str())
+        || saved
+            .trusted_project_hosts
+            .iter()
+            .flatten()
+            .any(|trusted| trusted.eq_ignore_ascii_case(&host))
+}
+
+fn url_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url.trim())
+        .ok()?
+        .host_str()
+        .map(|host| host.to_ascii_lowercase())
+}
+
+/// Apply the overrides; a base URL on an untrusted host drops the API key
+fn apply_overrides(
+    config: &SandboxConfig,
+    saved: &SandboxConfig,
+    overrides: &ProjectConfigOverride,
+) -> SandboxConfig {
+    let mut config = config.clone();
+    if let Some(ref base_url) = overrides.base_url {
+        if !is_trusted_base_url(saved, base_url) {
+            log::warn!(
+                "{} points runs at untrusted {}; starting them without the API key until the \
+                 host is added with set_trusted_project_hosts",
+                PROJECT_CONFIG_FILE,
+                base_url.trim()
+            );
+            config.api_key.clear();
+        }
+        config.base_url = base_url.trim().to_string();
+    }
+    if let Some(ref model) = overrides.default_model {
+        config.default_model = Some(model.trim().to_string());
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project_config() {
+        let overrides =
+            parse_project_config(r#"{ "baseUrl": "https://staging.example.com" }"#).unwrap();
+        assert_eq!(
+            overrides.base_url.as_deref(),
+            Some("https://staging.example.com")
+        );
+        assert!(overrides.default_model.is_none());
+
+        let overrides = parse_project_config(r#"{ "model": "gpt-4o-mini" }"#).unwrap();
+        assert_eq!(overrides.default_model.as_deref(), Some("gpt-4o-mini"));
+
+        assert!(parse_project_config(r#"{ "baseUrl": "staging.example.com" }"#).is_err());
+        assert!(parse_project_config(r#"{ "defaultModel": " " }"#).is_err());
+        // The API key can't be overridden per project
+        assert!(parse_project_config(r#"{ "apiKey": "eliza_x" }"#).is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut config = SandboxConfig::new(
+            "https://api.example.com".to_string(),
+            "eliza_key".to_string(),
+        );
+        config.default_model = Some("gpt-4".to_string());
+
+        let saved = SandboxConfig {
+            trusted_project_hosts: Some(vec!["localhost".to_string()]),
+            ..config.clone()
+        };
+
+        let applied = apply_overrides(
+            &config,
+            &saved,
+            &ProjectConfigOverride {
+                base_url: Some("http://localhost:4000 ".to_string()),
+                default_model: None,
+            },
+        );
+        assert_eq!(applied.base_url, "http://localhost:4000");
+        assert_eq!(applied.default_model.as_deref(), Some("gpt-4"));
+        assert_eq!(applied.api_key, "eliza_key");
+    }
+
+    #[test]
+    fn test_untrusted_base_url_gets_no_api_key() {
+        let config = SandboxConfig::new(
+            "https://api.example.com".to_string(),
+            "eliza_key".to_string(),
+        );
+        let overrides = |base_url: &str| ProjectConfigOverride {
+            base_url: Some(base_url.to_string()),
+            default_model: None,
+        };
+
+        let applied = apply_overrides(&config, &config, &overrides("https://attacker.test"));
+        assert_eq!(applied.base_url, "https://attacker.test");
+        assert!(applied.api_key.is_empty());
+
+        // The saved profile's host, on another path, is trusted
+        let applied = apply_overrides(&config, &config, &overrides("https://API.example.com/v2"));
+        assert_eq!(applied.api_key, "eliza_key");
+
+        // A request config can't vouch for its own host; only the saved profile can
+        let requested = SandboxConfig {
+            trusted_project_hosts: Some(vec!["attacker.test".to_string()]),
+            ..config.clone()
+        };
+        assert!(!is_trusted_base_url(&config, "https://attacker.test"));
+        let applied = apply_overrides(&requested, &config, &overrides("https://attacker.test"));
+        assert!(applied.api_key.is_empty());
+    }
+
+    #[test]
+    fn test_project_config_without_working_dir() {
+        let config = SandboxConfig::new(
+            "https://api.example.com".to_string(),
+            "eliza_key".to_string(),
+        );
+        assert_eq!(
+            project_config(&config, &config, None).base_url,
+            config.base_url
+        );
+    }
+}
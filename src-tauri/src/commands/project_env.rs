@@ -0,0 +1,438 @@
+//! Project environment files
+//! Reads an ElizaOS project's `.env`, shows which keys belong to the Sandbox configuration
+//! and which are plain run variables, and remembers imported projects so their run
+//! variables are merged into the env of later runs in that directory. The file is re-read
+//! at spawn time, so values are never copied into app storage and never logged
+
+use crate::commands::kv::{load_value, update_value};
+use crate::commands::network_capture::is_sensitive_name;
+use crate::commands::path_jail::check_path_allowed;
+use crate::commands::terminal::resolve_working_directory;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, ProjectEnvEntry, ProjectEnvReport, ProjectEnvTarget,
+    RunSpec,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PROJECT_ENV_NAMESPACE: &str = "project_env";
+const IMPORTS_KEY: &str = "imports";
+const ENV_FILE: &str = ".env";
+const REDACTED: &str = "[REDACTED]";
+/// Characters of a non-secret value shown in previews
+const MAX_PREVIEW_LEN: usize = 80;
+
+/// `.env` keys that configure the Sandbox connection, with the `SandboxConfig` field each
+/// maps to; the app sets these itself for every run, so they are not merged into run env
+const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("ELIZAOS_API_KEY", "apiKey"),
+    ("ELIZAOS_BASE_URL", "baseUrl"),
+    ("ELIZAOS_LARGE_MODEL", "defaultModel"),
+    ("ELIZAOS_SMALL_MODEL", "defaultModel"),
+];
+
+/// Keys the app always overrides when spawning the CLI
+const APP_MANAGED_KEYS: &[&str] = &["PATH", "NODE_ENV", "ELIZA_DESKTOP"];
+
+/// A project whose `.env` run variables are merged into its runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectEnvImport {
+    /// Imported keys; every run variable in the file when None
+    keys: Option<Vec<String>>,
+    imported_at: String,
+}
+
+type ProjectEnvImports = HashMap<String, ProjectEnvImport>;
+
+/// Show the keys of a project's `.env` and where each one would go, without importing
+#[tauri::command]
+pub async fn inspect_project_env(
+    app: AppHandle,
+    project_dir: String,
+) -> Result<ApiResponse<ProjectEnvReport>, String> {
+    let result = async {
+        let dir = project_path(&app, &project_dir)?;
+        let import = read_imports(&app).await?.remove(&import_key(&dir));
+        build_report(&dir, import.as_ref())
+    }
+    .await;
+
+    match result {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => Ok(error_response("Failed to read project .env", e)),
+    }
+}
+
+/// Merge a project's `.env` run variables into later runs started in that directory;
+/// only `keys` are merged when given. Values set in a run spec still win
+#[tauri::command]
+pub async fn import_project_env(
+    app: AppHandle,
+    project_dir: String,
+    keys: Option<Vec<String>>,
+) -> Result<ApiResponse<ProjectEnvReport>, String> {
+    let result = async {
+        let dir = project_path(&app, &project_dir)?;
+        let entries = parse_dotenv(&fs::read_to_string(dir.join(ENV_FILE))?);
+        if let Some(ref keys) = keys {
+            if let Some(missing) = keys
+                .iter()
+                .find(|key| !entries.iter().any(|(name, _)| name == *key))
+            {
+                return Err(AppError::Config(format!(
+                    "'{}' is not set in the project's .env",
+                    missing
+                )));
+            }
+        }
+
+        let import = ProjectEnvImport {
+            keys,
+            imported_at: current_timestamp(),
+        };
+        let stored = import.clone();
+        let key = import_key(&dir);
+        update_imports(&app, move |imports| {
+            imports.insert(key, stored);
+        })
+        .await?;
+
+        build_report(&dir, Some(&import))
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            log::info!(
+                "Imported .env of {} ({} run variables)",
+                report.env_path,
+                report.entries.iter().filter(|entry| entry.imported).count()
+            );
+            Ok(ApiResponse::success(report))
+        }
+        Err(e) => Ok(error_response("Failed to import project .env", e)),
+    }
+}
+
+/// Stop merging a project's `.env` into its runs, returning whether it was imported
+#[tauri::command]
+pub async fn forget_project_env(
+    app: AppHandle,
+    project_dir: String,
+) -> Result<ApiResponse<bool>, String> {
+    let mut existed = false;
+    let key = import_key(Path::new(&resolve_working_directory(project_dir)));
+    let result = update_imports(&app, |imports| {
+        existed = imports.remove(&key).is_some();
+    })
+    .await;
+
+    match result {
+        Ok(()) => Ok(ApiResponse::success(existed)),
+        Err(e) => Ok(error_response("Failed to forget project .env", e)),
+    }
+}
+
+/// Copy of the spec with the imported `.env` run variables of its working directory added;
+/// the spec's own env wins. Problems reading the file are logged and never fail the run
+pub(crate) async fn apply_project_env(app: &AppHandle, spec: RunSpec) -> RunSpec {
+    let Some(working_dir) = spec.working_dir.clone() else {
+        return spec;
+    };
+    let dir = PathBuf::from(resolve_working_directory(working_dir));
+
+    let import = match read_imports(app).await {
+        Ok(mut imports) => imports.remove(&import_key(&dir)),
+        Err(e) => {
+            log::warn!("Failed to read project .env imports: {}", e);
+            return spec;
+        }
+    };
+    let Some(import) = import else {
+        return spec;
+    };
+
+    let contents = match fs::read_to_string(dir.join(ENV_FILE)) {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::warn!("Skipping imported .env of {}: {}", dir.display(), e);
+            return spec;
+        }
+    };
+
+    let mut spec = spec;
+    let mut merged = Vec::new();
+    for (key, value) in parse_dotenv(&contents) {
+        if is_imported(&import, &key) && !spec.env.contains_key(&key) {
+            merged.push(key.clone());
+            spec.env.insert(key, value);
+        }
+    }
+
+    if !merged.is_empty() {
+        log::info!(
+            "Merged {} variables from {}: {}",
+            merged.len(),
+            dir.join(ENV_FILE).display(),
+            merged.join(", ")
+        );
+    }
+    spec
+}
+
+/// Key/value pairs of a `.env` file in file order; a repeated key keeps its last value
+fn parse_dotenv(contents: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let key = key.trim();
+        let valid_key = !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_key {
+            continue;
+        }
+
+        let value = parse_value(value.trim());
+        entries.retain(|(existing, _)| existing != key);
+        entries.push((key.to_string(), value));
+    }
+
+    entries
+}
+
+/// Unquote a value: double quotes understand `\n`, `\t`, `\"` and `\\`, single quotes are
+/// literal, and unquoted values end at a ` #` comment
+fn parse_value(raw: &str) -> String {
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return value,
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => value.push('\\'),
+                },
+                c => value.push(c),
+            }
+        }
+        return value;
+    }
+
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.split('\'').next().unwrap_or_default().to_string();
+    }
+
+    match raw.find(" #") {
+        Some(index) => raw[..index].trim_end().to_string(),
+        None => raw.to_string(),
+    }
+}
+
+fn classify_key(key: &str) -> (ProjectEnvTarget, Option<String>) {
+    if let Some((_, field)) = CONFIG_KEYS.iter().find(|(name, _)| *name == key) {
+        return (ProjectEnvTarget::Config, Some(field.to_string()));
+    }
+    if APP_MANAGED_KEYS.contains(&key) {
+        return (ProjectEnvTarget::AppManaged, None);
+    }
+    (ProjectEnvTarget::Run, None)
+}
+
+fn is_imported(import: &ProjectEnvImport, key: &str) -> bool {
+    classify_key(key).0 == ProjectEnvTarget::Run
+        && import
+            .keys
+            .as_ref()
+            .is_none_or(|keys| keys.iter().any(|k| k == key))
+}
+
+/// Value shown to the user; secrets are replaced entirely
+fn preview_value(key: &str, value: &str) -> String {
+    if is_sensitive_name(key) {
+        return REDACTED.to_string();
+    }
+    if value.chars().count() > MAX_PREVIEW_LEN {
+        let truncated: String = value.chars().take(MAX_PREVIEW_LEN).collect();
+        return format!("{}...", truncated);
+    }
+    value.to_string()
+}
+
+fn build_report(
+    dir: &Path,
+    import: Option<&ProjectEnvImport>,
+) -> Result<ProjectEnvReport, AppError> {
+    let env_path = dir.join(ENV_FILE);
+    if !env_path.is_file() {
+        return Err(AppError::Config(format!(
+            "'{}' has no .env file",
+            dir.display()
+        )));
+    }
+
+    let entries = parse_dotenv(&fs::read_to_string(&env_path)?)
+        .into_iter()
+        .map(|(key, value)| {
+            let (target, config_field) = classify_key(&key);
+            ProjectEnvEntry {
+                imported: import.is_some_and(|import| is_imported(import, &key)),
+                value_preview: preview_value(&key, &value),
+                secret: is_sensitive_name(&key),
+                key,
+                target,
+                config_field,
+            }
+        })
+        .collect();
+
+    Ok(ProjectEnvReport {
+        env_path: env_path.to_string_lossy().to_string(),
+        entries,
+        imported_at: import.map(|import| import.imported_at.clone()),
+    })
+}
+
+fn project_path(app: &AppHandle, project_dir: &str) -> Result<PathBuf, AppError> {
+    let dir = PathBuf::from(resolve_working_directory(project_dir.to_string()));
+    check_path_allowed(app, &dir, "Project directory")?;
+    if !dir.is_dir() {
+        return Err(AppError::Config(format!(
+            "Project directory '{}' does not exist",
+            project_dir
+        )));
+    }
+    Ok(dir)
+}
+
+/// Imports are keyed by canonical path so `~/agent` and `/home/me/agent` match
+fn import_key(dir: &Path) -> String {
+    fs::canonicalize(dir)
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+async fn read_imports(app: &AppHandle) -> Result<ProjectEnvImports, AppError> {
+    parse_imports(load_value(app, PROJECT_ENV_NAMESPACE, IMPORTS_KEY).await?)
+}
+
+async fn update_imports<F>(app: &AppHandle, update: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut ProjectEnvImports),
+{
+    update_value(
+        app,
+        PROJECT_ENV_NAMESPACE.to_string(),
+        IMPORTS_KEY.to_string(),
+        |current| {
+            let mut imports = parse_imports(current)?;
+            update(&mut imports);
+            Ok(serde_json::to_value(imports)?)
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+fn parse_imports(value: Option<Value>) -> Result<ProjectEnvImports, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| AppError::Config(format!("Project .env imports are corrupted: {}", e))),
+        None => Ok(ProjectEnvImports::new()),
+    }
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let entries = parse_dotenv(
+            "# comment\n\
+             OPENAI_API_KEY=sk-test\n\
+             export ELIZAOS_BASE_URL=\"https://example.com\"\n\
+             GREETING='hello # not a comment'\n\
+             PORT=3000 # trailing comment\n\
+             MULTI=\"a\\nb\"\n\
+             1BAD=x\n\
+             no equals sign\n\
+             PORT=3001\n",
+        );
+
+        let map: HashMap<_, _> = entries.iter().cloned().collect();
+        assert_eq!(map["OPENAI_API_KEY"], "sk-test");
+        assert_eq!(map["ELIZAOS_BASE_URL"], "https://example.com");
+        assert_eq!(map["GREETING"], "hello # not a comment");
+        assert_eq!(map["MULTI"], "a\nb");
+        assert_eq!(map["PORT"], "3001");
+        assert!(!map.contains_key("1BAD"));
+        assert_eq!(entries.last().unwrap().0, "PORT");
+        assert_eq!(entries.len(), 5);
+    }
+
+    #[test]
+    fn test_classify_key() {
+        assert_eq!(
+            classify_key("ELIZAOS_API_KEY"),
+            (ProjectEnvTarget::Config, Some("apiKey".to_string()))
+        );
+        assert_eq!(classify_key("PATH"), (ProjectEnvTarget::AppManaged, None));
+        assert_eq!(
+            classify_key("OPENAI_API_KEY"),
+            (ProjectEnvTarget::Run, None)
+        );
+    }
+
+    #[test]
+    fn test_is_imported() {
+        let all = ProjectEnvImport {
+            keys: None,
+            imported_at: current_timestamp(),
+        };
+        assert!(is_imported(&all, "OPENAI_API_KEY"));
+        assert!(!is_imported(&all, "ELIZAOS_API_KEY"));
+        assert!(!is_imported(&all, "PATH"));
+
+        let some = ProjectEnvImport {
+            keys: Some(vec!["DISCORD_TOKEN".to_string()]),
+            ..all
+        };
+        assert!(is_imported(&some, "DISCORD_TOKEN"));
+        assert!(!is_imported(&some, "OPENAI_API_KEY"));
+    }
+
+    #[test]
+    fn test_preview_value_redacts_secrets() {
+        assert_eq!(preview_value("OPENAI_API_KEY", "sk-secret"), REDACTED);
+        assert_eq!(preview_value("DISCORD_TOKEN", "abc"), REDACTED);
+        assert_eq!(preview_value("LOG_LEVEL", "debug"), "debug");
+        assert!(preview_value("NOTE", &"x".repeat(200)).ends_with("..."));
+    }
+}
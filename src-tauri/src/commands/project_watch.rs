@@ -0,0 +1,634 @@
+//! Project watch mode
+//! Polls a project's source directory and, once changes settle for the debounce time,
+//! restarts the watch's agent run or runs the project's build script. Every decision is
+//! emitted as `watch-event` and kept in the session's feed. Stopping a watch leaves its
+//! agent running
+
+use crate::commands::args::{custom_allowlist, validated_mode_args};
+use crate::commands::config::saved_config;
+use crate::commands::dependency_audit::{detect_package_manager, package_manager_name};
+use crate::commands::path_jail::check_path_allowed;
+use crate::commands::process::{
+    audit_run, describe_run_for_audit, execute_eliza_run_streaming_with_id, running_run_ids,
+    stop_eliza_run,
+};
+use crate::commands::stats::emit_event;
+use crate::commands::terminal::resolve_working_directory;
+use crate::models::{
+    current_timestamp, generate_safe_run_id, ApiResponse, AppError, AuditAction, LogEvent, RunSpec,
+    SandboxConfig, WatchAction, WatchConfig, WatchEvent, WatchOutcome, WatchSession,
+};
+use crate::path_env::{find_in_path, spawn_path_for_app};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+use tokio::process::Command;
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+const DEFAULT_WATCH_DIR: &str = "src";
+const DEFAULT_DEBOUNCE_MS: u64 = 500;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Generated and vendored directories never trigger an action
+const IGNORED_DIRS: &[&str] = &["node_modules", "dist", "build", "coverage"];
+/// Files beyond this are not watched, so a misconfigured watch can't walk a whole disk
+const MAX_WATCHED_FILES: usize = 20_000;
+const MAX_CHANGED_PATHS: usize = 20;
+const MAX_WATCH_EVENTS: usize = 50;
+const BUILD_TIMEOUT: Duration = Duration::from_secs(600);
+/// How long a stopped agent gets to exit before its replacement starts
+const AGENT_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct WatchHandle {
+    session: Arc<Mutex<WatchSession>>,
+    shutdown: oneshot::Sender<()>,
+}
+
+// Registry of active project watches by watch ID
+pub type WatchRegistry = Arc<RwLock<HashMap<String, WatchHandle>>>;
+
+/// Initialize the watch registry (called from main)
+pub fn init_watch_registry() -> WatchRegistry {
+    Arc::new(RwLock::new(HashMap::new()))
+}
+
+/// Modification time and size of each watched file, by path relative to the project
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Start watching a project; `config` is the Sandbox configuration of the agent run and
+/// is required for `RestartAgent`, which also starts the agent right away
+#[tauri::command]
+pub async fn start_project_watch(
+    app: AppHandle,
+    watch: WatchConfig,
+    config: Option<SandboxConfig>,
+) -> Result<ApiResponse<WatchSession>, String> {
+    let registry = app.state::<WatchRegistry>().inner().clone();
+
+    let result = async {
+        let (project, root) = watch_paths(&app, &watch)?;
+        let agent = match watch.action {
+            WatchAction::RestartAgent => {
+                Some(agent_run(&watch, &project, config, &saved_config(&app)?)?)
+            }
+            WatchAction::Rebuild => {
+                require_build_script(&project)?;
+                None
+            }
+        };
+
+        let mut guard = registry.write().await;
+        for handle in guard.values() {
+            let session = handle.session.lock().await;
+            if Path::new(&session.config.project_dir) == project.as_path() {
+                return Err(AppError::Config(format!(
+                    "'{}' is already watched by {}",
+                    project.display(),
+                    session.id
+                )));
+            }
+        }
+
+        let session = WatchSession {
+            id: generate_watch_id(),
+            config: WatchConfig {
+                project_dir: project.to_string_lossy().to_string(),
+                ..watch
+            },
+            started_at: current_timestamp(),
+            run_id: None,
+            events: Vec::new(),
+        };
+        let watch_id = session.id.clone();
+        let session = Arc::new(Mutex::new(session));
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        tokio::spawn(watch_loop(
+            app.clone(),
+            session.clone(),
+            root,
+            agent,
+            shutdown_rx,
+        ));
+        guard.insert(
+            watch_id,
+            WatchHandle {
+                session: session.clone(),
+                shutdown,
+            },
+        );
+
+        let session = session.lock().await.clone();
+        Ok::<_, AppError>(session)
+    }
+    .await;
+
+    match result {
+        Ok(session) => {
+            log::info!(
+                "Watching {} ({:?}) as {}",
+                session.config.project_dir,
+                session.config.action,
+                session.id
+            );
+            Ok(ApiResponse::success(session))
+        }
+        Err(e) => {
+            log::error!("Failed to start project watch: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to start project watch: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Stop a project watch, returning its final session
+#[tauri::command]
+pub async fn stop_project_watch(
+    app: AppHandle,
+    watch_id: String,
+) -> Result<ApiResponse<WatchSession>, String> {
+    let registry = app.state::<WatchRegistry>().inner().clone();
+    let Some(handle) = registry.write().await.remove(&watch_id) else {
+        return Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Project watch {} not found", watch_id),
+        ));
+    };
+
+    let _ = handle.shutdown.send(());
+    let session = handle.session.lock().await.clone();
+    log::info!("Stopped project watch {}", watch_id);
+    Ok(ApiResponse::success(session))
+}
+
+/// Active project watches with their recent events
+#[tauri::command]
+pub async fn list_project_watches(
+    app: AppHandle,
+) -> Result<ApiResponse<Vec<WatchSession>>, String> {
+    let registry = app.state::<WatchRegistry>().inner().clone();
+    let guard = registry.read().await;
+
+    let mut sessions = Vec::with_capacity(guard.len());
+    for handle in guard.values() {
+        sessions.push(handle.session.lock().await.clone());
+    }
+    sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    Ok(ApiResponse::success(sessions))
+}
+
+/// The project directory and the watched directory inside it
+fn watch_paths(app: &AppHandle, watch: &WatchConfig) -> Result<(PathBuf, PathBuf), AppError> {
+    let project = PathBuf::from(resolve_working_directory(watch.project_dir.clone()));
+    check_path_allowed(app, &project, "Project directory")?;
+    let project = fs::canonicalize(&project).map_err(|_| {
+        AppError::Config(format!(
+            "Project directory '{}' does not exist",
+            watch.project_dir
+        ))
+    })?;
+
+    let watch_dir = watch.watch_dir.as_deref().unwrap_or(DEFAULT_WATCH_DIR);
+    if !is_relative_inside(Path::new(watch_dir)) {
+        return Err(AppError::Config(format!(
+            "Watched directory '{}' must be a relative path inside the project",
+            watch_dir
+        )));
+    }
+    let root = project.join(watch_dir);
+    if !root.is_dir() {
+        return Err(AppError::Config(format!(
+            "'{}' does not exist",
+            root.display()
+        )));
+    }
+    Ok((project, root))
+}
+
+fn is_relative_inside(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// The agent spec and its Sandbox configuration, checked before anything starts
+fn agent_run(
+    watch: &WatchConfig,
+    project: &Path,
+    config: Option<SandboxConfig>,
+    saved: &SandboxConfig,
+) -> Result<(RunSpec, SandboxConfig), AppError> {
+    let mut spec = watch.spec.clone().ok_or_else(|| {
+        AppError::Config("Restarting an agent requires the spec of its run".to_string())
+    })?;
+    let config = config
+        .filter(SandboxConfig::is_valid)
+        .ok_or_else(|| AppError::Config("Invalid Sandbox configuration".to_string()))?;
+    validated_mode_args(&spec, &custom_allowlist(saved, &config))?;
+
+    if spec.working_dir.is_none() {
+        spec.working_dir = Some(project.to_string_lossy().to_string());
+    }
+    Ok((spec, config))
+}
+
+fn require_build_script(project: &Path) -> Result<(), AppError> {
+    let manifest = fs::read_to_string(project.join("package.json"))
+        .map_err(|_| AppError::Config(format!("'{}' has no package.json", project.display())))?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)?;
+    if manifest.pointer("/scripts/build").is_none() {
+        return Err(AppError::Config(
+            "The project's package.json has no build script".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Poll the watched directory until shutdown, acting on each settled batch of changes
+async fn watch_loop(
+    app: AppHandle,
+    session: Arc<Mutex<WatchSession>>,
+    root: PathBuf,
+    agent: Option<(RunSpec, SandboxConfig)>,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let (watch_id, config) = {
+        let session = session.lock().await;
+        (session.id.clone(), session.config.clone())
+    };
+    let project = PathBuf::from(&config.project_dir);
+    let debounce = Duration::from_millis(config.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS));
+
+    let mut agent_task = None;
+    if let Some((ref spec, ref sandbox)) = agent {
+        let event = restart_agent(&app, &session, &mut agent_task, spec, sandbox, Vec::new()).await;
+        record_event(&app, &session, event).await;
+    }
+
+    let mut snapshot = scan(&root, &project).await;
+    let mut pending = BTreeSet::new();
+    let mut last_change = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+
+        let current = scan(&root, &project).await;
+        let changed = changed_paths(&snapshot, &current);
+        snapshot = current;
+        if !changed.is_empty() {
+            pending.extend(changed);
+            last_change = Instant::now();
+        }
+        if pending.is_empty() || last_change.elapsed() < debounce {
+            continue;
+        }
+
+        let changed: Vec<PathBuf> = std::mem::take(&mut pending).into_iter().collect();
+        log::info!(
+            "Project watch {}: {} file(s) changed",
+            watch_id,
+            changed.len()
+        );
+        let event = match agent {
+            Some((ref spec, ref sandbox)) => {
+                restart_agent(&app, &session, &mut agent_task, spec, sandbox, changed).await
+            }
+            None => tokio::select! {
+                _ = &mut shutdown => break,
+                event = rebuild(&app, &watch_id, &project, changed) => event,
+            },
+        };
+        record_event(&app, &session, event).await;
+    }
+
+    log::debug!("Project watch {} ended", watch_id);
+}
+
+/// Stop the watch's agent if it is still running, wait for it to exit and start it again
+async fn restart_agent(
+    app: &AppHandle,
+    session: &Arc<Mutex<WatchSession>>,
+    agent_task: &mut Option<JoinHandle<()>>,
+    spec: &RunSpec,
+    config: &SandboxConfig,
+    changed: Vec<PathBuf>,
+) -> WatchEvent {
+    let (watch_id, previous) = {
+        let session = session.lock().await;
+        (session.id.clone(), session.run_id.clone())
+    };
+
+    if let Some(previous) = previous {
+        if running_run_ids(app).await.contains(&previous) {
+            match stop_eliza_run(app.clone(), previous.clone()).await {
+                Ok(response) if response.success => {}
+                Ok(response) => log::warn!(
+                    "Failed to stop agent run {}: {}",
+                    previous,
+                    response.error.map(|e| e.message).unwrap_or_default()
+                ),
+                Err(e) => log::warn!("Failed to stop agent run {}: {}", previous, e),
+            }
+        }
+        if let Some(task) = agent_task.take() {
+            if tokio::time::timeout(AGENT_EXIT_TIMEOUT, task)
+                .await
+                .is_err()
+            {
+                log::warn!(
+                    "Agent run {} did not exit in time; starting anyway",
+                    previous
+                );
+            }
+        }
+    }
+
+    let run_id = generate_safe_run_id();
+    let detail = format!("watch {}: {}", watch_id, describe_run_for_audit(spec));
+    audit_run(app, AuditAction::RunStarted, &run_id, true, Some(detail)).await;
+    *agent_task = Some(tokio::spawn(run_agent(
+        app.clone(),
+        spec.clone(),
+        config.clone(),
+        run_id.clone(),
+    )));
+    session.lock().await.run_id = Some(run_id.clone());
+
+    let message = if changed.is_empty() {
+        format!("Started agent run {}", run_id)
+    } else {
+        format!("Restarted agent as run {}", run_id)
+    };
+    watch_event(
+        &watch_id,
+        WatchAction::RestartAgent,
+        changed,
+        WatchOutcome::Restarted,
+        Some(run_id),
+        message,
+    )
+}
+
+async fn run_agent(app: AppHandle, spec: RunSpec, config: SandboxConfig, run_id: String) {
+    if let Err(e) =
+        execute_eliza_run_streaming_with_id(app.clone(), spec, config, run_id.clone()).await
+    {
+        log::error!("Watched agent run {} failed to start: {}", run_id, e);
+        emit_event(
+            &app,
+            "log-event",
+            LogEvent::error(run_id, format!("Failed to start run: {}", e)),
+        );
+    }
+}
+
+/// Run the project's build script with the package manager its lockfile names
+async fn rebuild(
+    app: &AppHandle,
+    watch_id: &str,
+    project: &Path,
+    changed: Vec<PathBuf>,
+) -> WatchEvent {
+    let (outcome, message) = match run_build(app, project).await {
+        Ok(message) => (WatchOutcome::Rebuilt, message),
+        Err(AppError::Process(message)) => (WatchOutcome::BuildFailed, message),
+        Err(e) => (WatchOutcome::Failed, e.to_string()),
+    };
+    watch_event(
+        watch_id,
+        WatchAction::Rebuild,
+        changed,
+        outcome,
+        None,
+        message,
+    )
+}
+
+async fn run_build(app: &AppHandle, project: &Path) -> Result<String, AppError> {
+    let program = package_manager_name(detect_package_manager(project));
+    let path_env = spawn_path_for_app(app).await;
+    let executable = find_in_path(program, &path_env).ok_or_else(|| {
+        AppError::EnvironmentError(format!("{} is not installed or not on PATH", program))
+    })?;
+
+    let start = Instant::now();
+    let output = tokio::time::timeout(
+        BUILD_TIMEOUT,
+        Command::new(executable)
+            .args(["run", "build"])
+            .current_dir(project)
+            .env("PATH", path_env)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Process(format!(
+            "{} run build did not finish within {} seconds",
+            program,
+            BUILD_TIMEOUT.as_secs()
+        ))
+    })?
+    .map_err(|e| AppError::EnvironmentError(format!("Failed to run {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let last_line = [stderr, stdout]
+            .iter()
+            .find_map(|text| text.lines().rev().find(|line| !line.trim().is_empty()))
+            .map(|line| line.trim().to_string())
+            .unwrap_or_default();
+        return Err(AppError::Process(format!(
+            "{} run build exited with {}: {}",
+            program, output.status, last_line
+        )));
+    }
+    Ok(format!(
+        "Built with {} in {:.1}s",
+        program,
+        start.elapsed().as_secs_f64()
+    ))
+}
+
+fn watch_event(
+    watch_id: &str,
+    action: WatchAction,
+    changed: Vec<PathBuf>,
+    outcome: WatchOutcome,
+    run_id: Option<String>,
+    message: String,
+) -> WatchEvent {
+    WatchEvent {
+        watch_id: watch_id.to_string(),
+        total_changes: changed.len(),
+        changed_paths: changed
+            .iter()
+            .take(MAX_CHANGED_PATHS)
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        action,
+        outcome,
+        run_id,
+        message,
+        timestamp: current_timestamp(),
+    }
+}
+
+async fn record_event(app: &AppHandle, session: &Arc<Mutex<WatchSession>>, event: WatchEvent) {
+    log::info!(
+        "Project watch {}: {:?} - {}",
+        event.watch_id,
+        event.outcome,
+        event.message
+    );
+    {
+        let mut session = session.lock().await;
+        session.events.push(event.clone());
+        let excess = session.events.len().saturating_sub(MAX_WATCH_EVENTS);
+        session.events.drain(..excess);
+    }
+    emit_event(app, "watch-event", event);
+}
+
+/// Walk the watched directory off the async runtime
+async fn scan(root: &Path, project: &Path) -> Snapshot {
+    let (root, project) = (root.to_path_buf(), project.to_path_buf());
+    tokio::task::spawn_blocking(move || {
+        let mut snapshot = Snapshot::new();
+        scan_dir(&root, &project, &mut snapshot);
+        snapshot
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn scan_dir(dir: &Path, project: &Path, snapshot: &mut Snapshot) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if snapshot.len() >= MAX_WATCHED_FILES {
+            return;
+        }
+        let name = entry.file_name();
+        if is_ignored(&name.to_string_lossy()) {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            scan_dir(&path, project, snapshot);
+        } else if file_type.is_file() {
+            let metadata = entry.metadata().ok();
+            let relative = path.strip_prefix(project).unwrap_or(&path).to_path_buf();
+            snapshot.insert(
+                relative,
+                (
+                    metadata.as_ref().and_then(|m| m.modified().ok()),
+                    metadata.map_or(0, |m| m.len()),
+                ),
+            );
+        }
+    }
+}
+
+/// Hidden entries (editor swap files, `.git`) and build output are not sources
+fn is_ignored(name: &str) -> bool {
+    name.starts_with('.') || name.ends_with('~') || IGNORED_DIRS.contains(&name)
+}
+
+/// Files added, removed or modified between two snapshots
+fn changed_paths(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let removed = before.keys().filter(|path| !after.contains_key(*path));
+    let added_or_modified = after
+        .iter()
+        .filter(|(path, stamp)| before.get(*path) != Some(stamp))
+        .map(|(path, _)| path);
+    removed.chain(added_or_modified).cloned().collect()
+}
+
+fn generate_watch_id() -> String {
+    format!(
+        "watch_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_paths() {
+        let at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        let before: Snapshot = [
+            (PathBuf::from("src/a.ts"), (at(1), 10)),
+            (PathBuf::from("src/b.ts"), (at(1), 10)),
+            (PathBuf::from("src/c.ts"), (at(1), 10)),
+        ]
+        .into_iter()
+        .collect();
+        let after: Snapshot = [
+            (PathBuf::from("src/a.ts"), (at(1), 10)),
+            (PathBuf::from("src/b.ts"), (at(2), 12)),
+            (PathBuf::from("src/d.ts"), (at(2), 1)),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut changed = changed_paths(&before, &after);
+        changed.sort();
+        assert_eq!(
+            changed,
+            vec![
+                PathBuf::from("src/b.ts"),
+                PathBuf::from("src/c.ts"),
+                PathBuf::from("src/d.ts"),
+            ]
+        );
+        assert!(changed_paths(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_scan_skips_ignored_entries() {
+        let project = std::env::temp_dir().join(format!("project-watch-{}", uuid::Uuid::new_v4()));
+        let src = project.join("src");
+        fs::create_dir_all(src.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(src.join("plugins")).unwrap();
+        fs::write(src.join("index.ts"), "export {}").unwrap();
+        fs::write(src.join("plugins/greet.ts"), "export {}").unwrap();
+        fs::write(src.join(".index.ts.swp"), "").unwrap();
+        fs::write(src.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let mut snapshot = Snapshot::new();
+        scan_dir(&src, &project, &mut snapshot);
+        let paths: Vec<PathBuf> = snapshot.into_keys().collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("src/index.ts"),
+                PathBuf::from("src/plugins/greet.ts"),
+            ]
+        );
+
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_watch_dir_must_stay_inside_project() {
+        assert!(is_relative_inside(Path::new("src")));
+        assert!(is_relative_inside(Path::new("./packages/agent/src")));
+        assert!(!is_relative_inside(Path::new("../other")));
+        assert!(!is_relative_inside(Path::new("/etc")));
+    }
+}
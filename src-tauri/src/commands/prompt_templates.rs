@@ -0,0 +1,381 @@
+//! Prompt template library
+//! Reusable prompt snippets kept in the key-value store under the `prompt-templates`
+//! namespace, rendered for the prompt playground or inserted into managed characters
+
+use crate::commands::characters::{
+    load_character, load_metadata, save_character, validate_character,
+};
+use crate::commands::kv::{load_value, update_value};
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, ManagedCharacter, PromptTemplate,
+    PromptTemplateTarget,
+};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+const TEMPLATES_NAMESPACE: &str = "prompt-templates";
+const TEMPLATES_KEY: &str = "templates";
+const MAX_NAME_LEN: usize = 128;
+const MAX_BODY_LEN: usize = 8 * 1024;
+
+/// Saved prompt templates, in the order they were created
+#[tauri::command]
+pub async fn list_prompt_templates(
+    app: AppHandle,
+) -> Result<ApiResponse<Vec<PromptTemplate>>, String> {
+    match read_templates(&app).await {
+        Ok(templates) => Ok(ApiResponse::success(templates)),
+        Err(e) => Ok(error_response("Failed to list prompt templates", e)),
+    }
+}
+
+/// Create a template, or replace the one with the same ID
+#[tauri::command]
+pub async fn save_prompt_template(
+    app: AppHandle,
+    template: PromptTemplate,
+) -> Result<ApiResponse<PromptTemplate>, String> {
+    let result = async {
+        let mut template = template;
+        validate_template(&template)?;
+        if template.id.is_empty() {
+            template.id = generate_template_id();
+        }
+        template.name = template.name.trim().to_string();
+        template.description = template
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        template.variables = template_variables(&template.body);
+        template.updated_at = Some(current_timestamp());
+
+        let saved = template.clone();
+        update_templates(&app, move |templates| {
+            match templates.iter_mut().find(|t| t.id == saved.id) {
+                Some(existing) => *existing = saved,
+                None => templates.push(saved),
+            }
+            Ok(())
+        })
+        .await?;
+        Ok::<_, AppError>(template)
+    }
+    .await;
+
+    match result {
+        Ok(template) => {
+            log::info!("Saved prompt template {} ({})", template.id, template.name);
+            Ok(ApiResponse::success(template))
+        }
+        Err(e) => Ok(error_response("Failed to save prompt template", e)),
+    }
+}
+
+/// Delete a template, returning whether it existed
+#[tauri::command]
+pub async fn remove_prompt_template(
+    app: AppHandle,
+    template_id: String,
+) -> Result<ApiResponse<bool>, String> {
+    let mut existed = false;
+    let result = update_templates(&app, |templates| {
+        let before = templates.len();
+        templates.retain(|t| t.id != template_id);
+        existed = templates.len() != before;
+        Ok(())
+    })
+    .await;
+
+    match result {
+        Ok(()) => {
+            log::info!(
+                "Removed prompt template {} (existed: {})",
+                template_id,
+                existed
+            );
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to remove prompt template", e)),
+    }
+}
+
+/// Fill in a template's placeholders, e.g. for the prompt playground
+#[tauri::command]
+pub async fn render_prompt_template(
+    app: AppHandle,
+    template_id: String,
+    variables: HashMap<String, String>,
+) -> Result<ApiResponse<String>, String> {
+    let result = async {
+        let template = find_template(&app, &template_id).await?;
+        render(&template.body, &variables)
+    }
+    .await;
+
+    match result {
+        Ok(prompt) => Ok(ApiResponse::success(prompt)),
+        Err(e) => Ok(error_response("Failed to render prompt template", e)),
+    }
+}
+
+/// Render a template into a field of a managed character and save the character
+#[tauri::command]
+pub async fn insert_prompt_template(
+    app: AppHandle,
+    character_id: String,
+    template_id: String,
+    target: PromptTemplateTarget,
+    variables: HashMap<String, String>,
+) -> Result<ApiResponse<ManagedCharacter>, String> {
+    let result = async {
+        let template = find_template(&app, &template_id).await?;
+        let text = render(&template.body, &variables)?;
+
+        let mut character = load_character(&app, &character_id)?;
+        insert_into_character(&mut character, target, text)?;
+        validate_character(&character)?;
+        let metadata = load_metadata(&app, &character_id)?;
+        let message = format!("Inserted prompt template {}", template.name);
+        save_character(&app, &character_id, &character, &metadata, Some(&message))
+    }
+    .await;
+
+    match result {
+        Ok(managed) => {
+            log::info!(
+                "Inserted prompt template {} into {} of character {}",
+                template_id,
+                target.field(),
+                character_id
+            );
+            Ok(ApiResponse::success(managed))
+        }
+        Err(e) => Ok(error_response("Failed to insert prompt template", e)),
+    }
+}
+
+fn placeholder_pattern() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_.-]*)\s*\}\}").expect("valid placeholder regex")
+    })
+}
+
+/// Placeholder names in a template body, in order of first use
+pub(crate) fn template_variables(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for captures in placeholder_pattern().captures_iter(body) {
+        let name = &captures[1];
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Substitute every placeholder, failing when any of them has no value
+pub(crate) fn render(body: &str, variables: &HashMap<String, String>) -> Result<String, AppError> {
+    let missing: Vec<String> = template_variables(body)
+        .into_iter()
+        .filter(|name| !variables.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::Config(format!(
+            "Missing values for template variables: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(placeholder_pattern()
+        .replace_all(body, |captures: &regex::Captures| {
+            variables[&captures[1]].clone()
+        })
+        .into_owned())
+}
+
+/// Set or append the rendered text in the target field; a string bio becomes a list
+fn insert_into_character(
+    character: &mut Value,
+    target: PromptTemplateTarget,
+    text: String,
+) -> Result<(), AppError> {
+    let object = character
+        .as_object_mut()
+        .ok_or_else(|| AppError::CharacterError("Character must be a JSON object".to_string()))?;
+    let field = target.field();
+
+    if target == PromptTemplateTarget::System {
+        object.insert(field.to_string(), Value::String(text));
+        return Ok(());
+    }
+
+    let entry = object
+        .entry(field.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::String(existing) = entry {
+        *entry = Value::Array(vec![Value::String(std::mem::take(existing))]);
+    }
+    entry
+        .as_array_mut()
+        .ok_or_else(|| {
+            AppError::CharacterError(format!("\"{}\" must be a list of strings", field))
+        })?
+        .push(Value::String(text));
+    Ok(())
+}
+
+async fn find_template(app: &AppHandle, template_id: &str) -> Result<PromptTemplate, AppError> {
+    read_templates(app)
+        .await?
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| AppError::Config(format!("Prompt template '{}' not found", template_id)))
+}
+
+async fn read_templates(app: &AppHandle) -> Result<Vec<PromptTemplate>, AppError> {
+    parse_templates(load_value(app, TEMPLATES_NAMESPACE, TEMPLATES_KEY).await?)
+}
+
+async fn update_templates<F>(app: &AppHandle, update: F) -> Result<(), AppError>
+where
+    F: FnOnce(&mut Vec<PromptTemplate>) -> Result<(), AppError>,
+{
+    update_value(
+        app,
+        TEMPLATES_NAMESPACE.to_string(),
+        TEMPLATES_KEY.to_string(),
+        |current| {
+            let mut templates = parse_templates(current)?;
+            update(&mut templates)?;
+            Ok(serde_json::to_value(templates)?)
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+fn parse_templates(value: Option<Value>) -> Result<Vec<PromptTemplate>, AppError> {
+    match value {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| AppError::Config(format!("Saved prompt templates are corrupted: {}", e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn validate_template(template: &PromptTemplate) -> Result<(), AppError> {
+    let name = template.name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(AppError::Config(format!(
+            "Invalid template name: use 1-{} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if template.body.trim().is_empty() {
+        return Err(AppError::Config("Template body is empty".to_string()));
+    }
+    if template.body.len() > MAX_BODY_LEN {
+        return Err(AppError::Config(format!(
+            "Template body is {} bytes; the limit is {} bytes",
+            template.body.len(),
+            MAX_BODY_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn generate_template_id() -> String {
+    format!(
+        "template_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_template_variables() {
+        assert_eq!(
+            template_variables("Hi {{name}}, meet {{ other }} and {{name}} {{1bad}}"),
+            vec!["name", "other"]
+        );
+        assert!(template_variables("no placeholders").is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let body = "You are {{ name }}, an expert in {{topic}}.";
+        assert_eq!(
+            render(
+                body,
+                &vars(&[("name", "Ada"), ("topic", "math"), ("x", "y")])
+            )
+            .unwrap(),
+            "You are Ada, an expert in math."
+        );
+
+        let err = render(body, &vars(&[("name", "Ada")])).unwrap_err();
+        assert!(err.to_string().contains("topic"));
+    }
+
+    #[test]
+    fn test_insert_into_character() {
+        let mut character = json!({ "name": "Ada", "bio": "Mathematician" });
+        insert_into_character(
+            &mut character,
+            PromptTemplateTarget::Bio,
+            "Poet".to_string(),
+        )
+        .unwrap();
+        insert_into_character(
+            &mut character,
+            PromptTemplateTarget::System,
+            "Be precise".to_string(),
+        )
+        .unwrap();
+        insert_into_character(
+            &mut character,
+            PromptTemplateTarget::PostExamples,
+            "Numbers are poems".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            character,
+            json!({
+                "name": "Ada",
+                "bio": ["Mathematician", "Poet"],
+                "system": "Be precise",
+                "postExamples": ["Numbers are poems"],
+            })
+        );
+
+        let mut character = json!({ "name": "Ada", "postExamples": 3 });
+        assert!(insert_into_character(
+            &mut character,
+            PromptTemplateTarget::PostExamples,
+            "x".to_string()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Terminal quick actions
+//! Curated, policy-approved commands that run through the terminal execution path
+//! without exposing free-form input
+
+use crate::commands::terminal::{
+    is_safe_command, resolve_working_directory, run_terminal_command, TerminalCommandResult,
+    TerminalRegistry,
+};
+use crate::models::{ApiResponse, AppError};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAction {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    pub category: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl QuickAction {
+    fn new(id: &str, label: &str, description: &str, category: &str, command_line: &str) -> Self {
+        let mut parts = command_line.split_whitespace().map(|s| s.to_string());
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            description: description.to_string(),
+            category: category.to_string(),
+            command: parts.next().unwrap_or_default(),
+            args: parts.collect(),
+        }
+    }
+
+    /// Full command line for display
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(|s| s.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// List the quick actions that apply to a project directory
+#[tauri::command]
+pub async fn get_quick_actions(
+    project_dir: Option<String>,
+) -> Result<ApiResponse<Vec<QuickAction>>, AppError> {
+    let dir = project_dir.map(resolve_working_directory);
+    let actions = quick_actions_for(dir.as_deref());
+    log::debug!("Offering {} quick actions", actions.len());
+    Ok(ApiResponse::success(actions))
+}
+
+/// Run a quick action by ID through the terminal execution path
+#[tauri::command]
+pub async fn run_quick_action(
+    id: String,
+    project_dir: Option<String>,
+    app: AppHandle,
+    registry: State<'_, TerminalRegistry>,
+) -> Result<ApiResponse<TerminalCommandResult>, AppError> {
+    let dir = project_dir.map(resolve_working_directory);
+
+    let action = match quick_actions_for(dir.as_deref())
+        .into_iter()
+        .find(|action| action.id == id)
+    {
+        Some(action) => action,
+        None => {
+            log::warn!("Unknown or unavailable quick action: {}", id);
+            return Ok(ApiResponse::error(
+                "NOT_FOUND".to_string(),
+                format!("Quick action '{}' is not available", id),
+            ));
+        }
+    };
+
+    log::info!(
+        "Running quick action {}: {}",
+        action.id,
+        action.command_line()
+    );
+    let result = run_terminal_command(&app, &registry, action.command, action.args, dir).await?;
+    Ok(ApiResponse::success(result))
+}
+
+/// Curated actions, filtered by what the project contains and by terminal policy
+fn quick_actions_for(project_dir: Option<&str>) -> Vec<QuickAction> {
+    let has = |name: &str| project_dir.is_some_and(|dir| Path::new(dir).join(name).exists());
+
+    let mut actions = vec![
+        QuickAction::new(
+            "elizaos-version",
+            "ElizaOS version",
+            "Show the installed ElizaOS CLI version",
+            "elizaos",
+            "elizaos --version",
+        ),
+        QuickAction::new(
+            "node-version",
+            "Node.js version",
+            "Show the Node.js version used for runs",
+            "environment",
+            "node --version",
+        ),
+    ];
+
+    if has("package.json") {
+        actions.push(QuickAction::new(
+            "npm-install",
+            "Install dependencies",
+            "Install the project's npm dependencies",
+            "project",
+            "npm install",
+        ));
+    }
+
+    if has(".git") {
+        actions.push(QuickAction::new(
+            "git-status",
+            "Git status",
+            "Show changed files in the project",
+            "git",
+            "git status",
+        ));
+        actions.push(QuickAction::new(
+            "git-log",
+            "Recent commits",
+            "Show the last ten commits",
+            "git",
+            "git log --oneline -10",
+        ));
+    }
+
+    actions.retain(|action| is_safe_command(&action.command));
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quick_actions_depend_on_project() {
+        let ids = |dir: Option<&str>| -> Vec<String> {
+            quick_actions_for(dir).into_iter().map(|a| a.id).collect()
+        };
+
+        let base = ids(None);
+        assert!(base.contains(&"elizaos-version".to_string()));
+        assert!(!base.contains(&"npm-install".to_string()));
+
+        let dir = std::env::temp_dir().join(format!("quick_actions_{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("package.json"), "{}").unwrap();
+
+        let project = ids(dir.to_str());
+        assert!(project.contains(&"npm-install".to_string()));
+        assert!(!project.contains(&"git-status".to_string()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quick_action_command_line() {
+        let action = QuickAction::new("git-log", "Log", "", "git", "git log --oneline -10");
+        assert_eq!(action.command, "git");
+        assert_eq!(action.args, vec!["log", "--oneline", "-10"]);
+        assert_eq!(action.command_line(), "git log --oneline -10");
+    }
+}
@@ -0,0 +1,499 @@
+//! Run reports
+//! Renders a finished run as a shareable Markdown or HTML document: the redacted spec,
+//! timings, exit status, key log excerpts, chart-ready metrics and a telemetry summary
+
+use crate::commands::history::load_record;
+use crate::commands::process::{get_process_registry, sanitize_args_for_logging};
+use crate::commands::telemetry::estimate_token_usage;
+use crate::models::{
+    ApiResponse, AppError, ReportFormat, RunNotes, RunRecord, RunReport, RunResult,
+};
+use crate::severity::is_benign_stderr;
+use serde_json::json;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Lines kept from the start and end of each stream
+const HEAD_LINES: usize = 10;
+const TAIL_LINES: usize = 20;
+/// Most error/warning lines quoted in a report
+const MAX_PROBLEM_LINES: usize = 30;
+const PROBLEM_MARKERS: &[&str] = &["error", "fail", "panic", "exception", "warn"];
+
+/// Render a report for a run; written to `path` (a file, or a directory to place it in)
+/// when given, otherwise only returned
+#[tauri::command]
+pub async fn generate_run_report(
+    app: AppHandle,
+    run_id: String,
+    format: ReportFormat,
+    path: Option<String>,
+) -> Result<ApiResponse<RunReport>, String> {
+    log::info!("Generating {:?} report for run {}", format, run_id);
+
+    let result = async {
+        let record = find_run(&app, &run_id).await?;
+        let content = render_report(&record, format)?;
+
+        let path = match path {
+            Some(path) => {
+                let path = report_file_path(Path::new(&path), &run_id, format);
+                fs::write(&path, &content)?;
+                Some(path.to_string_lossy().to_string())
+            }
+            None => None,
+        };
+
+        Ok::<_, AppError>(RunReport {
+            run_id: run_id.clone(),
+            format,
+            content,
+            path,
+        })
+    }
+    .await;
+
+    match result {
+        Ok(report) => {
+            if let Some(ref path) = report.path {
+                log::info!("Run report written to {}", path);
+            }
+            Ok(ApiResponse::success(report))
+        }
+        Err(e) => {
+            log::error!("Failed to generate run report: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to generate run report: {}", e),
+            ))
+        }
+    }
+}
+
+/// The run from history, or from the process registry while it is still live
+pub(crate) async fn find_run(app: &AppHandle, run_id: &str) -> Result<RunRecord, AppError> {
+    let stored = load_record(app, run_id).ok();
+    if let Some(record) = stored.as_ref().filter(|record| record.result.is_some()) {
+        return Ok(record.clone());
+    }
+
+    let registry = get_process_registry(app);
+    let handle = registry.read().await.get(run_id).cloned();
+    match handle {
+        Some(handle) => Ok(RunRecord {
+            result: Some(handle.lock().await.run_result.clone()),
+            notes: stored
+                .map(|record| record.notes)
+                .unwrap_or_else(|| RunNotes {
+                    run_id: run_id.to_string(),
+                    ..RunNotes::default()
+                }),
+        }),
+        None => Err(AppError::Process(format!(
+            "Run {} has no recorded result",
+            run_id
+        ))),
+    }
+}
+
+/// Reports go to `path` itself unless it is an existing directory
+fn report_file_path(path: &Path, run_id: &str, format: ReportFormat) -> PathBuf {
+    if path.is_dir() {
+        path.join(format!("run-report-{}.{}", run_id, format.extension()))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// A quoted log line with its 1-based position in its stream
+#[derive(Debug, Clone)]
+struct Excerpt {
+    stream: &'static str,
+    line_no: usize,
+    text: String,
+}
+
+fn render_report(record: &RunRecord, format: ReportFormat) -> Result<String, AppError> {
+    let result = record
+        .result
+        .as_ref()
+        .ok_or_else(|| AppError::Process("Run has no recorded result".to_string()))?;
+
+    Ok(match format {
+        ReportFormat::Markdown => render_markdown(result, &record.notes),
+        ReportFormat::Html => render_html(result, &record.notes),
+    })
+}
+
+/// Spec fields safe to share: arguments sanitized, environment values dropped
+fn redacted_spec(result: &RunResult) -> Vec<(&'static str, String)> {
+    let spec = &result.spec;
+    let mut env_keys: Vec<&str> = spec.env.keys().map(|key| key.as_str()).collect();
+    env_keys.sort_unstable();
+
+    let mut fields = vec![
+        ("Mode", spec.mode.to_string()),
+        ("Arguments", sanitize_args_for_logging(&spec.args).join(" ")),
+    ];
+    if let Some(ref character) = spec.character_file {
+        fields.push(("Character", character.clone()));
+    }
+    if let Some(ref dir) = spec.working_dir {
+        fields.push(("Working directory", dir.clone()));
+    }
+    if let Some(port) = spec.port {
+        fields.push(("Port", port.to_string()));
+    }
+    if let Some(ref eval) = spec.eval {
+        fields.push(("Scenario", eval.scenario_path.clone()));
+    }
+    if !env_keys.is_empty() {
+        fields.push(("Environment (values redacted)", env_keys.join(", ")));
+    }
+    if spec.simulate {
+        fields.push(("Simulated", "yes".to_string()));
+    }
+    fields
+}
+
+fn timing_fields(result: &RunResult) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("Status", result.status.as_str().to_string()),
+        (
+            "Exit code",
+            result
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        ),
+        ("Started", result.started_at.clone()),
+    ];
+    if let Some(ref ended) = result.ended_at {
+        fields.push(("Ended", ended.clone()));
+    }
+    if let Some(ms) = result.duration_ms {
+        fields.push(("Duration", format!("{:.1}s", ms as f64 / 1000.0)));
+    }
+    fields
+}
+
+fn telemetry_fields(result: &RunResult) -> Vec<(&'static str, String)> {
+    let output: String = result
+        .stdout
+        .iter()
+        .chain(&result.stderr)
+        .map(|line| format!("{}\n", line))
+        .collect();
+
+    let mut fields = vec![
+        ("Stdout lines", result.stdout.len().to_string()),
+        ("Stderr lines", result.stderr.len().to_string()),
+        (
+            "Approximate tokens",
+            estimate_token_usage(&output).to_string(),
+        ),
+    ];
+    if let Some(ref eval) = result.eval_result {
+        fields.push((
+            "Eval",
+            format!(
+                "{}/{} passed ({:.0}%)",
+                eval.passed,
+                eval.total,
+                eval.pass_rate * 100.0
+            ),
+        ));
+    }
+    if result.binary_output {
+        fields.push(("Binary output", "replaced with placeholders".to_string()));
+    }
+    fields
+}
+
+/// Data for charts: output volume per stream and per-case eval durations
+fn metrics(result: &RunResult) -> serde_json::Value {
+    let cases: Vec<_> = result
+        .eval_result
+        .iter()
+        .flat_map(|eval| &eval.cases)
+        .map(|case| {
+            json!({
+                "name": case.name,
+                "passed": case.passed,
+                "durationMs": case.duration_ms,
+            })
+        })
+        .collect();
+
+    json!({
+        "durationMs": result.duration_ms,
+        "outputLines": { "stdout": result.stdout.len(), "stderr": result.stderr.len() },
+        "evalCases": cases,
+    })
+}
+
+fn numbered<'a>(stream: &'static str, lines: &'a [String]) -> impl Iterator<Item = Excerpt> + 'a {
+    lines.iter().enumerate().map(move |(index, text)| Excerpt {
+        stream,
+        line_no: index + 1,
+        text: text.clone(),
+    })
+}
+
+/// First and last lines of stdout, plus every line that looks like a problem
+fn excerpts(result: &RunResult) -> (Vec<Excerpt>, Vec<Excerpt>, Vec<Excerpt>) {
+    let head: Vec<Excerpt> = numbered("stdout", &result.stdout)
+        .take(HEAD_LINES)
+        .collect();
+    let skip = result.stdout.len().max(HEAD_LINES + TAIL_LINES) - TAIL_LINES;
+    let tail: Vec<Excerpt> = numbered("stdout", &result.stdout)
+        .skip(skip.max(head.len()))
+        .collect();
+    let problems = numbered("stdout", &result.stdout)
+        .chain(numbered("stderr", &result.stderr))
+        .filter(|excerpt| is_problem_line(&excerpt.text))
+        .take(MAX_PROBLEM_LINES)
+        .collect();
+    (head, tail, problems)
+}
+
+fn is_problem_line(line: &str) -> bool {
+    if is_benign_stderr(line) {
+        return false;
+    }
+    let line = line.to_lowercase();
+    PROBLEM_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+fn render_markdown(result: &RunResult, notes: &RunNotes) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Run report: {}\n", result.id);
+
+    for (title, fields) in [
+        ("Summary", timing_fields(result)),
+        ("Spec", redacted_spec(result)),
+        ("Telemetry", telemetry_fields(result)),
+    ] {
+        let _ = writeln!(out, "## {}\n\n| Field | Value |\n| --- | --- |", title);
+        for (name, value) in fields {
+            let _ = writeln!(out, "| {} | {} |", name, value.replace('|', "\\|"));
+        }
+        out.push('\n');
+    }
+
+    if let Some(ref note) = notes.note {
+        let _ = writeln!(out, "## Notes\n\n{}\n", note);
+    }
+    if !notes.annotations.is_empty() {
+        let _ = writeln!(out, "## Annotations\n");
+        for annotation in &notes.annotations {
+            let _ = writeln!(out, "- Line {}: {}", annotation.line_no, annotation.text);
+        }
+        out.push('\n');
+    }
+
+    let (head, tail, problems) = excerpts(result);
+    for (title, lines) in [
+        ("Errors and warnings", problems),
+        ("Output start", head),
+        ("Output end", tail),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "## {}\n\n```text", title);
+        for excerpt in lines {
+            let _ = writeln!(
+                out,
+                "{}:{:>5} | {}",
+                excerpt.stream, excerpt.line_no, excerpt.text
+            );
+        }
+        let _ = writeln!(out, "```\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "## Metrics\n\n```json\n{}\n```",
+        serde_json::to_string_pretty(&metrics(result)).unwrap_or_default()
+    );
+    out
+}
+
+fn render_html(result: &RunResult, notes: &RunNotes) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Run report: {id}</title>\n<style>\n\
+         body {{ font-family: system-ui, sans-serif; max-width: 960px; margin: 2rem auto; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         td, th {{ border: 1px solid #ccc; padding: 0.25rem 0.5rem; text-align: left; }}\n\
+         pre {{ background: #f5f5f5; padding: 0.75rem; overflow-x: auto; }}\n\
+         </style>\n</head>\n<body>\n<h1>Run report: {id}</h1>\n",
+        id = escape_html(&result.id)
+    );
+
+    for (title, fields) in [
+        ("Summary", timing_fields(result)),
+        ("Spec", redacted_spec(result)),
+        ("Telemetry", telemetry_fields(result)),
+    ] {
+        let _ = writeln!(out, "<h2>{}</h2>\n<table>", title);
+        for (name, value) in fields {
+            let _ = writeln!(
+                out,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                name,
+                escape_html(&value)
+            );
+        }
+        out.push_str("</table>\n");
+    }
+
+    if let Some(ref note) = notes.note {
+        let _ = writeln!(out, "<h2>Notes</h2>\n<p>{}</p>", escape_html(note));
+    }
+    if !notes.annotations.is_empty() {
+        out.push_str("<h2>Annotations</h2>\n<ul>\n");
+        for annotation in &notes.annotations {
+            let _ = writeln!(
+                out,
+                "<li>Line {}: {}</li>",
+                annotation.line_no,
+                escape_html(&annotation.text)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+
+    let (head, tail, problems) = excerpts(result);
+    for (title, lines) in [
+        ("Errors and warnings", problems),
+        ("Output start", head),
+        ("Output end", tail),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "<h2>{}</h2>\n<pre>", title);
+        for excerpt in lines {
+            let _ = writeln!(
+                out,
+                "{}:{:>5} | {}",
+                excerpt.stream,
+                excerpt.line_no,
+                escape_html(&excerpt.text)
+            );
+        }
+        out.push_str("</pre>\n");
+    }
+
+    // Chart data for viewers that want to plot it
+    let _ = write!(
+        out,
+        "<h2>Metrics</h2>\n<script type=\"application/json\" id=\"run-metrics\">{}</script>\n\
+         </body>\n</html>\n",
+        serde_json::to_string(&metrics(result))
+            .unwrap_or_default()
+            .replace("</", "<\\/")
+    );
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RunMode, RunSpec, RunStatus};
+    use std::collections::HashMap;
+
+    fn record() -> RunRecord {
+        let mut spec = RunSpec::new(
+            "spec_1".to_string(),
+            RunMode::Run,
+            vec![
+                "start".to_string(),
+                "eliza_1234567890abcdef1234567890abcdef".to_string(),
+            ],
+        );
+        spec.env = HashMap::from([("OPENAI_API_KEY".to_string(), "sk-secret".to_string())]);
+
+        let mut result = RunResult::new(spec, "run_1".to_string());
+        result.status = RunStatus::Failed;
+        result.exit_code = Some(1);
+        result.stdout = (1..=40).map(|i| format!("line {}", i)).collect();
+        result.stderr = vec!["Error: <boom>".to_string()];
+
+        RunRecord {
+            result: Some(result),
+            notes: RunNotes {
+                run_id: "run_1".to_string(),
+                note: Some("flaky".to_string()),
+                ..RunNotes::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_is_redacted() {
+        let report = render_report(&record(), ReportFormat::Markdown).unwrap();
+        assert!(report.starts_with("# Run report: run_1"));
+        assert!(report.contains("| Status | failed |"));
+        assert!(report.contains("OPENAI_API_KEY"));
+        assert!(!report.contains("sk-secret"));
+        assert!(!report.contains("eliza_1234567890abcdef"));
+        assert!(report.contains("stderr:    1 | Error: <boom>"));
+        assert!(report.contains("flaky"));
+    }
+
+    #[test]
+    fn test_html_report_escapes_output() {
+        let report = render_report(&record(), ReportFormat::Html).unwrap();
+        assert!(report.contains("Error: &lt;boom&gt;"));
+        assert!(!report.contains("<boom>"));
+        assert!(report.contains("id=\"run-metrics\""));
+    }
+
+    #[test]
+    fn test_excerpts() {
+        let record = record();
+        let (head, tail, problems) = excerpts(record.result.as_ref().unwrap());
+        assert_eq!(head.len(), HEAD_LINES);
+        assert_eq!(tail.first().unwrap().line_no, 21);
+        assert_eq!(tail.last().unwrap().line_no, 40);
+        assert_eq!(problems.len(), 1);
+
+        let mut short = record.result.unwrap();
+        short.stdout.truncate(12);
+        let (head, tail, _) = excerpts(&short);
+        assert_eq!(head.len(), 10);
+        // The tail never repeats lines already in the head
+        assert_eq!(tail.first().unwrap().line_no, 11);
+    }
+
+    #[test]
+    fn test_report_requires_result() {
+        let record = RunRecord {
+            result: None,
+            notes: RunNotes::default(),
+        };
+        assert!(render_report(&record, ReportFormat::Html).is_err());
+    }
+}
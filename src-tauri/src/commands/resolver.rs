@@ -0,0 +1,294 @@
+//! ElizaOS CLI resolution
+//! Determines which runner (elizaos, bunx, npx) launches the CLI and caches the result
+
+use crate::models::{
+    ApiResponse, AppError, CliResolutionReport, CliRunner, RunnerProbe, SandboxConfig,
+};
+use crate::path_env::{build_spawn_path, find_in_path, path_entries};
+use std::process::Command;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+/// A cached runner resolution, keyed on the inputs that can change its outcome
+#[derive(Debug, Clone)]
+pub struct CliResolution {
+    pub runner: CliRunner,
+    pub priority: Vec<CliRunner>,
+    pub path_env: String,
+    pub resolved_at: String,
+}
+
+impl CliResolution {
+    /// Whether this resolution is still valid for the given priority and PATH
+    fn matches(&self, priority: &[CliRunner], path_env: &str) -> bool {
+        self.priority == priority && self.path_env == path_env
+    }
+}
+
+// Managed cache of the last successful resolution
+pub type CliResolutionCache = Arc<RwLock<Option<CliResolution>>>;
+
+/// Initialize the resolution cache (called from main)
+pub fn init_cli_resolution_cache() -> CliResolutionCache {
+    Arc::new(RwLock::new(None))
+}
+
+/// Get the resolution cache for the app
+pub fn get_cli_resolution_cache(app: &AppHandle) -> CliResolutionCache {
+    app.state::<CliResolutionCache>().inner().clone()
+}
+
+/// Drop any cached resolution so the next run re-probes the runners
+pub async fn invalidate_cli_resolution(app: &AppHandle) {
+    let cache = get_cli_resolution_cache(app);
+    if cache.write().await.take().is_some() {
+        log::debug!("Invalidated cached ElizaOS CLI resolution");
+    }
+}
+
+/// Clear the cached CLI resolution and resolve again
+#[tauri::command]
+pub async fn refresh_cli_resolution(
+    app: AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<CliRunner>, String> {
+    log::info!("Refreshing ElizaOS CLI resolution");
+
+    invalidate_cli_resolution(&app).await;
+
+    match resolve_eliza_command_cached(&app, &config).await {
+        Ok(runner) => Ok(ApiResponse::success(runner)),
+        Err(e) => {
+            log::warn!("ElizaOS CLI resolution failed: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.to_string(),
+            ))
+        }
+    }
+}
+
+/// Report which ElizaOS CLI will be used, where it lives, and why alternatives were rejected
+#[tauri::command]
+pub async fn get_cli_resolution_report(
+    app: AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<CliResolutionReport>, String> {
+    log::info!("Building ElizaOS CLI resolution report");
+
+    let priority = config.runner_priority();
+    let path_env = build_spawn_path(config.extra_path_dirs());
+
+    let cached = get_cli_resolution_cache(&app)
+        .read()
+        .await
+        .as_ref()
+        .is_some_and(|cached| cached.matches(&priority, &path_env));
+
+    let mut probes: Vec<RunnerProbe> = Vec::new();
+    let mut selected: Option<usize> = None;
+
+    for runner in effective_priority(&priority) {
+        match selected {
+            // Only the first available runner is used, so don't spawn the rest
+            Some(index) => {
+                let winner = probes[index].runner;
+                probes.push(RunnerProbe {
+                    runner: *runner,
+                    path: find_in_path(runner.program(), &path_env)
+                        .map(|p| p.to_string_lossy().to_string()),
+                    version: None,
+                    available: false,
+                    rejection_reason: Some(format!("Lower priority than {}", winner)),
+                });
+            }
+            None => {
+                let probe = probe_runner(*runner, &path_env);
+                if probe.available {
+                    selected = Some(probes.len());
+                }
+                probes.push(probe);
+            }
+        }
+    }
+
+    let winner = selected.map(|index| probes[index].clone());
+
+    Ok(ApiResponse::success(CliResolutionReport {
+        selected: winner.as_ref().map(|probe| probe.runner),
+        selected_path: winner.as_ref().and_then(|probe| probe.path.clone()),
+        selected_version: winner.and_then(|probe| probe.version),
+        path_entries: path_entries(&path_env),
+        probes,
+        cached,
+    }))
+}
+
+/// Resolve the ElizaOS CLI runner, reusing the cached result when PATH and priority are unchanged
+pub async fn resolve_eliza_command_cached(
+    app: &AppHandle,
+    config: &SandboxConfig,
+) -> Result<CliRunner, AppError> {
+    let cache = get_cli_resolution_cache(app);
+    let priority = config.runner_priority();
+    let path_env = build_spawn_path(config.extra_path_dirs());
+
+    if let Some(ref cached) = *cache.read().await {
+        if cached.matches(&priority, &path_env) {
+            log::debug!(
+                "Using cached ElizaOS CLI resolution: {} (resolved at {})",
+                cached.runner,
+                cached.resolved_at
+            );
+            return Ok(cached.runner);
+        }
+    }
+
+    let runner = resolve_eliza_command(&priority, &path_env).await?;
+
+    *cache.write().await = Some(CliResolution {
+        runner,
+        priority,
+        path_env,
+        resolved_at: crate::models::current_timestamp(),
+    });
+
+    Ok(runner)
+}
+
+/// Resolve the ElizaOS CLI runner to use, trying each runner in priority order
+pub async fn resolve_eliza_command(
+    priority: &[CliRunner],
+    path_env: &str,
+) -> Result<CliRunner, AppError> {
+    for runner in effective_priority(priority) {
+        let probe = probe_runner(*runner, path_env);
+        if probe.available {
+            log::debug!("ElizaOS CLI available via {}", runner);
+            return Ok(*runner);
+        }
+        log::debug!(
+            "ElizaOS CLI not available via {}: {}",
+            runner,
+            probe.rejection_reason.unwrap_or_default()
+        );
+    }
+
+    Err(AppError::CliNotFound(
+        "ElizaOS CLI not available. Please install with: npm install -g @elizaos/cli@latest"
+            .to_string(),
+    ))
+}
+
+/// Test harness builds always resolve to the scripted mock CLI
+#[cfg(feature = "test-harness")]
+fn effective_priority(_priority: &[CliRunner]) -> &[CliRunner] {
+    &[CliRunner::Mock]
+}
+
+#[cfg(not(feature = "test-harness"))]
+fn effective_priority(priority: &[CliRunner]) -> &[CliRunner] {
+    priority
+}
+
+/// Check whether a runner can launch the ElizaOS CLI on the given PATH
+pub(crate) fn probe_runner(runner: CliRunner, path_env: &str) -> RunnerProbe {
+    probe_with_args(runner, path_env, runner.package_args())
+}
+
+/// `probe_runner` for diagnostics: npx reports the CLI only if it is already installed
+/// or cached, rather than downloading it
+pub(crate) fn probe_runner_offline(runner: CliRunner, path_env: &str) -> RunnerProbe {
+    probe_with_args(runner, path_env, runner.offline_package_args())
+}
+
+fn probe_with_args(runner: CliRunner, path_env: &str, mut args: Vec<String>) -> RunnerProbe {
+    let mut probe = RunnerProbe {
+        runner,
+        path: None,
+        version: None,
+        available: false,
+        rejection_reason: None,
+    };
+
+    match find_in_path(runner.program(), path_env) {
+        Some(path) => probe.path = Some(path.to_string_lossy().to_string()),
+        None => {
+            probe.rejection_reason = Some(format!("{} not found on PATH", runner.program()));
+            return probe;
+        }
+    }
+
+    args.push("--version".to_string());
+
+    match Command::new(runner.program())
+        .args(&args)
+        .env("PATH", path_env)
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            probe.version = stdout
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| line.trim().to_string());
+            probe.available = true;
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            probe.rejection_reason = Some(format!(
+                "Version check failed (exit code {:?}): {}",
+                output.status.code(),
+                stderr.lines().next().unwrap_or("no output").trim()
+            ));
+        }
+        Err(e) => {
+            probe.rejection_reason = Some(format!("Failed to run {}: {}", runner.program(), e));
+        }
+    }
+
+    probe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolution_matches() {
+        let resolution = CliResolution {
+            runner: CliRunner::Bunx,
+            priority: CliRunner::default_priority(),
+            path_env: "/usr/bin:/bin".to_string(),
+            resolved_at: crate::models::current_timestamp(),
+        };
+
+        assert!(resolution.matches(&CliRunner::default_priority(), "/usr/bin:/bin"));
+        assert!(!resolution.matches(&CliRunner::default_priority(), "/usr/local/bin"));
+        assert!(!resolution.matches(&[CliRunner::Npx], "/usr/bin:/bin"));
+    }
+
+    #[test]
+    fn test_probe_runner_not_on_path() {
+        let probe = probe_runner(CliRunner::Bunx, "");
+        assert!(!probe.available);
+        assert!(probe.path.is_none());
+        assert_eq!(
+            probe.rejection_reason,
+            Some("bunx not found on PATH".to_string())
+        );
+    }
+
+    #[test]
+    fn test_offline_probe_never_installs() {
+        let args = CliRunner::Npx.offline_package_args();
+        assert_eq!(args.first().map(String::as_str), Some("--no-install"));
+        assert!(!args.contains(&"-y".to_string()));
+        assert_eq!(
+            CliRunner::Elizaos.offline_package_args(),
+            CliRunner::Elizaos.package_args()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! Run log tailing
+//! Buffers the output lines of streaming runs so a reopened window can catch up with
+//! `tail_run_log` from the last offset it saw instead of replaying the whole history
+
+use crate::commands::process::get_process_registry;
+use crate::models::{
+    ApiResponse, LogSeverity, LogType, RunLogLine, RunLogTail, RunResult, RunStatus,
+};
+use crate::severity::strip_ansi;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Notify, RwLock};
+
+/// Lines kept per run; older lines are dropped first
+pub const MAX_BUFFERED_LINES: usize = 10_000;
+/// Lines returned by one `tail_run_log` call
+pub const MAX_TAIL_LINES: usize = 1_000;
+/// Finished runs whose buffers are kept
+const MAX_FINISHED_LOGS: usize = 20;
+/// Longest a follow-mode call waits for new lines
+const FOLLOW_WAIT: Duration = Duration::from_secs(15);
+/// Longest accepted filter pattern
+pub const MAX_FILTER_PATTERN_LEN: usize = 1024;
+/// Compiled size limit for filter patterns
+const FILTER_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Default)]
+struct RunLog {
+    lines: VecDeque<RunLogLine>,
+    next_offset: u64,
+    finished: bool,
+}
+
+/// Which buffered lines are also emitted as `log-event`s
+#[derive(Debug, Clone, Default)]
+struct EventFilter {
+    min_severity: Option<LogSeverity>,
+    pattern: Option<Regex>,
+}
+
+/// Output buffer of one streaming run; waiters are woken whenever it changes
+#[derive(Default)]
+pub struct RunLogBuffer {
+    log: Mutex<RunLog>,
+    filter: Mutex<EventFilter>,
+    changed: Notify,
+}
+
+impl RunLogBuffer {
+    /// Whether a line should be emitted as well as buffered; lines without a known
+    /// severity pass the severity check
+    pub fn should_emit(&self, severity: Option<LogSeverity>, text: &str) -> bool {
+        let filter = self.filter.lock().unwrap_or_else(|e| e.into_inner());
+        let severity_passes = match (filter.min_severity, severity) {
+            (Some(min), Some(severity)) => severity >= min,
+            _ => true,
+        };
+        severity_passes
+            && filter
+                .pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(&strip_ansi(text)))
+    }
+
+    fn set_pattern(&self, pattern: Option<Regex>) {
+        self.filter
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pattern = pattern;
+    }
+
+    /// Append a line and return its offset
+    pub fn push(&self, log_type: LogType, severity: Option<LogSeverity>, text: String) -> u64 {
+        let offset = {
+            let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+            let offset = log.next_offset;
+            log.next_offset += 1;
+            log.lines.push_back(RunLogLine {
+                offset,
+                log_type,
+                severity,
+                text,
+                timestamp: chrono::Utc::now().timestamp(),
+            });
+            if log.lines.len() > MAX_BUFFERED_LINES {
+                log.lines.pop_front();
+            }
+            offset
+        };
+        self.changed.notify_waiters();
+        offset
+    }
+
+    /// Mark the run as exited
+    pub fn finish(&self) {
+        self.log.lock().unwrap_or_else(|e| e.into_inner()).finished = true;
+        self.changed.notify_waiters();
+    }
+
+    fn is_finished(&self) -> bool {
+        self.log.lock().unwrap_or_else(|e| e.into_inner()).finished
+    }
+
+    /// Lines from `from_offset` on, waiting until `deadline` for new ones when there are
+    /// none yet and the run is still going
+    pub(crate) async fn follow(
+        &self,
+        run_id: &str,
+        from_offset: u64,
+        deadline: tokio::time::Instant,
+    ) -> RunLogTail {
+        loop {
+            // Register for wakeups before reading, so a line pushed in between isn't missed
+            let changed = self.changed.notified();
+            let tail = self.tail(run_id, from_offset);
+            if !tail.lines.is_empty() || tail.finished {
+                return tail;
+            }
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return self.tail(run_id, from_offset);
+            }
+        }
+    }
+
+    fn tail(&self, run_id: &str, from_offset: u64) -> RunLogTail {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        let first_offset = log
+            .lines
+            .front()
+            .map_or(log.next_offset, |line| line.offset);
+        let lines: Vec<RunLogLine> = log
+            .lines
+            .iter()
+            .skip(from_offset.saturating_sub(first_offset) as usize)
+            .take(MAX_TAIL_LINES)
+            .cloned()
+            .collect();
+
+        RunLogTail {
+            run_id: run_id.to_string(),
+            next_offset: lines
+                .last()
+                .map_or(from_offset.max(first_offset), |line| line.offset + 1),
+            first_offset,
+            finished: log.finished,
+            lines,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RunLogs {
+    buffers: HashMap<String, Arc<RunLogBuffer>>,
+    /// Insertion order, used to evict the oldest finished runs
+    order: VecDeque<String>,
+}
+
+// Buffered run logs by run ID
+pub type RunLogStore = Arc<RwLock<RunLogs>>;
+
+pub fn init_run_log_store() -> RunLogStore {
+    Arc::new(RwLock::new(RunLogs::default()))
+}
+
+/// Create the log buffer of a run that is about to stream output
+pub async fn open_run_log(
+    app: &AppHandle,
+    run_id: &str,
+    min_severity: Option<LogSeverity>,
+) -> Arc<RunLogBuffer> {
+    let store = app.state::<RunLogStore>().inner().clone();
+    let mut logs = store.write().await;
+
+    let finished: Vec<String> = logs
+        .order
+        .iter()
+        .filter(|id| {
+            logs.buffers
+                .get(*id)
+                .is_some_and(|buffer| buffer.is_finished())
+        })
+        .cloned()
+        .collect();
+    for id in finished
+        .iter()
+        .take(finished.len().saturating_sub(MAX_FINISHED_LOGS))
+    {
+        logs.buffers.remove(id);
+    }
+    let RunLogs { buffers, order } = &mut *logs;
+    order.retain(|id| buffers.contains_key(id));
+
+    let buffer = Arc::new(RunLogBuffer {
+        filter: Mutex::new(EventFilter {
+            min_severity,
+            pattern: None,
+        }),
+        ..Default::default()
+    });
+    logs.buffers.insert(run_id.to_string(), buffer.clone());
+    logs.order.push_back(run_id.to_string());
+    buffer
+}
+
+/// Log buffer of a streaming run, while it is kept
+pub(crate) async fn run_log_buffer(app: &AppHandle, run_id: &str) -> Option<Arc<RunLogBuffer>> {
+    app.state::<RunLogStore>()
+        .read()
+        .await
+        .buffers
+        .get(run_id)
+        .cloned()
+}
+
+/// IDs of the runs whose logs are buffered, oldest first
+pub(crate) async fn buffered_run_ids(app: &AppHandle) -> Vec<String> {
+    app.state::<RunLogStore>()
+        .read()
+        .await
+        .order
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Lines of a run's log from `from_offset` on; with `follow`, waits briefly for new lines
+/// when there are none yet and the run is still going
+#[tauri::command]
+pub async fn tail_run_log(
+    app: AppHandle,
+    run_id: String,
+    from_offset: u64,
+    follow: Option<bool>,
+) -> Result<ApiResponse<RunLogTail>, String> {
+    let Some(buffer) = run_log_buffer(&app, &run_id).await else {
+        // Runs that were not streamed (or were evicted) are served from their final result
+        let registry = get_process_registry(&app);
+        let handle = registry.read().await.get(&run_id).cloned();
+        return match handle {
+            Some(handle) => {
+                let result = handle.lock().await.run_result.clone();
+                Ok(ApiResponse::success(tail_from_result(&result, from_offset)))
+            }
+            None => Ok(ApiResponse::error(
+                "NOT_FOUND".to_string(),
+                format!("Run {} not found", run_id),
+            )),
+        };
+    };
+
+    let tail = if follow.unwrap_or(false) {
+        let deadline = tokio::time::Instant::now() + FOLLOW_WAIT;
+        buffer.follow(&run_id, from_offset, deadline).await
+    } else {
+        buffer.tail(&run_id, from_offset)
+    };
+    Ok(ApiResponse::success(tail))
+}
+
+/// Only emit a running run's lines that match a regular expression (case-insensitive);
+/// an empty or missing pattern emits everything again. All lines are still buffered
+#[tauri::command]
+pub async fn set_run_log_filter(
+    app: AppHandle,
+    run_id: String,
+    pattern: Option<String>,
+) -> Result<ApiResponse<Option<String>>, String> {
+    let pattern = pattern.filter(|p| !p.is_empty());
+    let regex = match pattern.as_deref().map(compile_filter).transpose() {
+        Ok(regex) => regex,
+        Err(e) => return Ok(ApiResponse::error("INVALID_PATTERN".to_string(), e)),
+    };
+
+    match run_log_buffer(&app, &run_id).await {
+        Some(buffer) => {
+            buffer.set_pattern(regex);
+            log::info!("Log filter for run {} set to {:?}", run_id, pattern);
+            Ok(ApiResponse::success(pattern))
+        }
+        None => Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Run {} is not streaming logs", run_id),
+        )),
+    }
+}
+
+fn compile_filter(pattern: &str) -> Result<Regex, String> {
+    if pattern.len() > MAX_FILTER_PATTERN_LEN {
+        return Err(format!(
+            "Filter pattern is longer than {} characters",
+            MAX_FILTER_PATTERN_LEN
+        ));
+    }
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(FILTER_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid filter pattern: {}", e))
+}
+
+/// Stdout followed by stderr of a finished run, numbered from zero
+fn tail_from_result(result: &RunResult, from_offset: u64) -> RunLogTail {
+    let buffer = RunLogBuffer::default();
+    for line in &result.stdout {
+        buffer.push(LogType::Stdout, None, line.clone());
+    }
+    for line in &result.stderr {
+        buffer.push(LogType::Stderr, None, line.clone());
+    }
+    if result.status != RunStatus::Running {
+        buffer.finish();
+    }
+    buffer.tail(&result.id, from_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_from_offset() {
+        let buffer = RunLogBuffer::default();
+        for i in 0..5 {
+            assert_eq!(buffer.push(LogType::Stdout, None, format!("line {}", i)), i);
+        }
+
+        let tail = buffer.tail("run_1", 3);
+        assert_eq!(tail.lines.len(), 2);
+        assert_eq!(tail.lines[0].text, "line 3");
+        assert_eq!(tail.next_offset, 5);
+        assert!(!tail.finished);
+
+        let caught_up = buffer.tail("run_1", 5);
+        assert!(caught_up.lines.is_empty());
+        assert_eq!(caught_up.next_offset, 5);
+
+        buffer.finish();
+        assert!(buffer.tail("run_1", 5).finished);
+    }
+
+    #[test]
+    fn test_tail_after_lines_were_dropped() {
+        let buffer = RunLogBuffer::default();
+        for i in 0..MAX_BUFFERED_LINES + 10 {
+            buffer.push(LogType::Stderr, None, format!("line {}", i));
+        }
+
+        let tail = buffer.tail("run_1", 0);
+        assert_eq!(tail.first_offset, 10);
+        assert_eq!(tail.lines[0].offset, 10);
+        assert_eq!(tail.lines.len(), MAX_TAIL_LINES);
+        assert_eq!(tail.next_offset, 10 + MAX_TAIL_LINES as u64);
+    }
+
+    #[test]
+    fn test_min_severity_filter() {
+        let buffer = RunLogBuffer {
+            filter: Mutex::new(EventFilter {
+                min_severity: Some(LogSeverity::Warn),
+                pattern: None,
+            }),
+            ..Default::default()
+        };
+        assert!(!buffer.should_emit(Some(LogSeverity::Info), "info"));
+        assert!(buffer.should_emit(Some(LogSeverity::Warn), "warn"));
+        assert!(buffer.should_emit(Some(LogSeverity::Error), "error"));
+        assert!(buffer.should_emit(None, "plain"));
+        assert!(RunLogBuffer::default().should_emit(Some(LogSeverity::Trace), "trace"));
+    }
+
+    #[test]
+    fn test_pattern_filter() {
+        let buffer = RunLogBuffer::default();
+        buffer.set_pattern(Some(compile_filter("timeout|refused").unwrap()));
+        assert!(buffer.should_emit(None, "Connection REFUSED by peer"));
+        assert!(buffer.should_emit(None, "\x1b[31mtimeout\x1b[0m after 5s"));
+        assert!(!buffer.should_emit(None, "Agent started"));
+
+        buffer.set_pattern(None);
+        assert!(buffer.should_emit(None, "Agent started"));
+
+        assert!(compile_filter("(unclosed").is_err());
+        assert!(compile_filter(&"a".repeat(MAX_FILTER_PATTERN_LEN + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_follow_wakes_on_push() {
+        let buffer = Arc::new(RunLogBuffer::default());
+        let changed = buffer.changed.notified();
+
+        let writer = buffer.clone();
+        tokio::spawn(async move {
+            writer.push(LogType::Stdout, None, "hello".to_string());
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), changed)
+            .await
+            .unwrap();
+        assert_eq!(buffer.tail("run_1", 0).lines[0].text, "hello");
+    }
+}
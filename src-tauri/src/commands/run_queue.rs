@@ -0,0 +1,519 @@
+//! Run queue
+//! Caps how many streaming runs execute at once when the saved profile sets a limit. Waiting runs
+//! start by priority, and with preemption a higher-priority run pauses a lower-priority one
+//! until a slot frees up
+
+use crate::commands::config::saved_config;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, LogEvent, QueuedRun, RunPriority, RunQueueEvent, RunQueueSnapshot, RunQueueState,
+};
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+struct RunningRun {
+    run_id: String,
+    priority: RunPriority,
+    pid: Option<u32>,
+    paused: bool,
+}
+
+struct WaitingRun {
+    run_id: String,
+    priority: RunPriority,
+    seq: u64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    limit: Option<usize>,
+    /// Runs holding a slot, in start order
+    running: Vec<RunningRun>,
+    waiting: Vec<WaitingRun>,
+    next_seq: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Admission {
+    Start,
+    /// Pause this run to make room, then try again
+    Preempt {
+        run_id: String,
+        priority: RunPriority,
+        pid: u32,
+    },
+    Wait {
+        position: usize,
+    },
+}
+
+impl QueueState {
+    fn enqueue(&mut self, run_id: &str, priority: RunPriority) {
+        self.waiting.push(WaitingRun {
+            run_id: run_id.to_string(),
+            priority,
+            seq: self.next_seq,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Runs holding a slot that are not paused
+    fn active(&self) -> usize {
+        self.running.iter().filter(|run| !run.paused).count()
+    }
+
+    /// Waiting runs in start order: highest priority first, then first come
+    fn waiting_order(&self) -> Vec<&WaitingRun> {
+        let mut waiting: Vec<&WaitingRun> = self.waiting.iter().collect();
+        waiting.sort_by_key(|run| (Reverse(run.priority), run.seq));
+        waiting
+    }
+
+    /// Start the run if it is next in line and a slot is free, or pick a run to pause for it
+    fn admit(&mut self, run_id: &str, preempt: bool) -> Admission {
+        let order = self.waiting_order();
+        let Some(position) = order.iter().position(|run| run.run_id == run_id) else {
+            return Admission::Start;
+        };
+        if position > 0 {
+            return Admission::Wait {
+                position: position + 1,
+            };
+        }
+        let priority = order[0].priority;
+
+        if self.active() < self.limit.unwrap_or(usize::MAX) {
+            self.waiting.retain(|run| run.run_id != run_id);
+            self.running.push(RunningRun {
+                run_id: run_id.to_string(),
+                priority,
+                pid: None,
+                paused: false,
+            });
+            return Admission::Start;
+        }
+
+        // The lowest-priority run that can be paused, latest started first
+        let victim = self
+            .running
+            .iter_mut()
+            .rev()
+            .filter(|run| !run.paused && run.priority < priority && run.pid.is_some())
+            .min_by_key(|run| run.priority);
+        match victim {
+            Some(victim) if preempt => {
+                victim.paused = true;
+                Admission::Preempt {
+                    run_id: victim.run_id.clone(),
+                    priority: victim.priority,
+                    pid: victim.pid.unwrap_or_default(),
+                }
+            }
+            _ => Admission::Wait { position: 1 },
+        }
+    }
+
+    /// Undo a pause that could not be delivered; the run is not picked again
+    fn cancel_pause(&mut self, run_id: &str) {
+        if let Some(run) = self.running.iter_mut().find(|run| run.run_id == run_id) {
+            run.paused = false;
+            run.pid = None;
+        }
+    }
+
+    fn remove(&mut self, run_id: &str) {
+        self.running.retain(|run| run.run_id != run_id);
+        self.waiting.retain(|run| run.run_id != run_id);
+    }
+
+    /// Unpause runs while slots are free, unless a waiting run outranks them
+    fn take_resumable(&mut self) -> Vec<(String, RunPriority, Option<u32>)> {
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let mut resumed = Vec::new();
+        while self.active() < limit {
+            let best_waiting = self.waiting.iter().map(|run| run.priority).max();
+            let next = self
+                .running
+                .iter_mut()
+                .rev()
+                .filter(|run| run.paused && best_waiting.is_none_or(|p| run.priority >= p))
+                .max_by_key(|run| run.priority);
+            let Some(run) = next else {
+                break;
+            };
+            run.paused = false;
+            resumed.push((run.run_id.clone(), run.priority, run.pid));
+        }
+        resumed
+    }
+
+    /// Process of a paused run, which stays paused as far as the queue is concerned
+    fn paused_pid(&self, run_id: &str) -> Option<u32> {
+        self.running
+            .iter()
+            .find(|run| run.run_id == run_id && run.paused)
+            .and_then(|run| run.pid)
+    }
+
+    fn is_paused(&self, run_id: &str) -> bool {
+        self.running
+            .iter()
+            .any(|run| run.run_id == run_id && run.paused)
+    }
+
+    fn snapshot(&self) -> RunQueueSnapshot {
+        RunQueueSnapshot {
+            max_concurrent_runs: self.limit,
+            running: self
+                .running
+                .iter()
+                .map(|run| QueuedRun {
+                    run_id: run.run_id.clone(),
+                    priority: run.priority,
+                    paused: run.paused,
+                })
+                .collect(),
+            waiting: self
+                .waiting_order()
+                .into_iter()
+                .map(|run| QueuedRun {
+                    run_id: run.run_id.clone(),
+                    priority: run.priority,
+                    paused: false,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Shared queue state; waiters are woken whenever a slot is released
+#[derive(Default)]
+pub struct RunQueue {
+    state: Mutex<QueueState>,
+    changed: Notify,
+}
+
+impl RunQueue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// Run queue shared by all streaming runs
+pub type RunQueueStore = Arc<RunQueue>;
+
+pub fn init_run_queue() -> RunQueueStore {
+    Arc::new(RunQueue::default())
+}
+
+fn get_run_queue_store(app: &AppHandle) -> RunQueueStore {
+    app.state::<RunQueueStore>().inner().clone()
+}
+
+/// A run's place in the queue; dropping it frees the slot (or leaves the line)
+pub struct QueueSlot {
+    app: AppHandle,
+    run_id: String,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        let queue = get_run_queue_store(&self.app);
+        let resumed = {
+            let mut state = queue.lock();
+            state.remove(&self.run_id);
+            state.take_resumable()
+        };
+        for (run_id, priority, pid) in resumed {
+            resume_paused(&self.app, &run_id, priority, pid);
+        }
+        queue.changed.notify_waiters();
+    }
+}
+
+/// Wait until the run may start; returns immediately when the saved profile sets no queue
+pub async fn acquire_run_slot(
+    app: &AppHandle,
+    run_id: &str,
+    priority: RunPriority,
+) -> Option<QueueSlot> {
+    // The limit is shared by every run, so only the saved profile may set it
+    let config = saved_config(app).ok()?.queue?;
+    let queue = get_run_queue_store(app);
+    {
+        let mut state = queue.lock();
+        state.limit = Some(config.max_concurrent_runs.max(1));
+        state.enqueue(run_id, priority);
+    }
+    let slot = QueueSlot {
+        app: app.clone(),
+        run_id: run_id.to_string(),
+    };
+
+    let mut reported_position = None;
+    loop {
+        // Register for wakeups before checking, so a release in between isn't missed
+        let changed = queue.changed.notified();
+        let admission = queue.lock().admit(run_id, config.preempt);
+        match admission {
+            Admission::Start => {
+                emit_queue_event(app, run_id, RunQueueState::Started, priority, None);
+                return Some(slot);
+            }
+            Admission::Preempt {
+                run_id: victim,
+                priority: victim_priority,
+                pid,
+            } => match pause_process(pid) {
+                Ok(()) => {
+                    log::info!("Paused run {} to make room for run {}", victim, run_id);
+                    emit_event(
+                        app,
+                        "log-event",
+                        LogEvent::system(
+                            victim.clone(),
+                            format!("Paused to make room for higher-priority run {}", run_id),
+                        ),
+                    );
+                    emit_queue_event(app, &victim, RunQueueState::Paused, victim_priority, None);
+                }
+                Err(e) => {
+                    log::warn!("Failed to pause run {}: {}", victim, e);
+                    queue.lock().cancel_pause(&victim);
+                }
+            },
+            Admission::Wait { position } => {
+                if reported_position != Some(position) {
+                    if reported_position.is_none() {
+                        emit_event(
+                            app,
+                            "log-event",
+                            LogEvent::system(
+                                run_id.to_string(),
+                                "Waiting for a free run slot...".to_string(),
+                            ),
+                        );
+                    }
+                    emit_queue_event(app, run_id, RunQueueState::Queued, priority, Some(position));
+                    reported_position = Some(position);
+                }
+                changed.await;
+            }
+        }
+    }
+}
+
+/// Record the process of a run holding a slot, making it eligible for preemption
+pub fn set_run_pid(app: &AppHandle, run_id: &str, pid: u32) {
+    let queue = get_run_queue_store(app);
+    let mut state = queue.lock();
+    if let Some(run) = state.running.iter_mut().find(|run| run.run_id == run_id) {
+        run.pid = Some(pid);
+    }
+}
+
+/// Whether a run is currently paused by the queue
+pub fn is_run_paused(app: &AppHandle, run_id: &str) -> bool {
+    get_run_queue_store(app).lock().is_paused(run_id)
+}
+
+/// Continue a paused run's process group so it acts on a stop or interrupt signal just
+/// sent to it. It takes no slot: the queue still counts it as paused until it exits or is
+/// resumed
+pub fn continue_paused_run(app: &AppHandle, run_id: &str) {
+    let pid = get_run_queue_store(app).lock().paused_pid(run_id);
+    if let Some(pid) = pid {
+        match resume_process(pid) {
+            Ok(()) => log::info!("Continued paused run {} to deliver its signal", run_id),
+            Err(e) => log::warn!("Failed to continue paused run {}: {}", run_id, e),
+        }
+    }
+}
+
+/// Runs holding or waiting for a slot
+#[tauri::command]
+pub async fn get_run_queue(app: AppHandle) -> Result<ApiResponse<RunQueueSnapshot>, String> {
+    Ok(ApiResponse::success(
+        get_run_queue_store(&app).lock().snapshot(),
+    ))
+}
+
+fn resume_paused(app: &AppHandle, run_id: &str, priority: RunPriority, pid: Option<u32>) {
+    if let Some(pid) = pid {
+        if let Err(e) = resume_process(pid) {
+            log::warn!("Failed to resume run {}: {}", run_id, e);
+            return;
+        }
+    }
+    log::info!("Resumed run {}", run_id);
+    emit_event(
+        app,
+        "log-event",
+        LogEvent::system(run_id.to_string(), "Resumed".to_string()),
+    );
+    emit_queue_event(app, run_id, RunQueueState::Resumed, priority, None);
+}
+
+fn emit_queue_event(
+    app: &AppHandle,
+    run_id: &str,
+    state: RunQueueState,
+    priority: RunPriority,
+    position: Option<usize>,
+) {
+    emit_event(
+        app,
+        "run-queue",
+        RunQueueEvent {
+            run_id: run_id.to_string(),
+            state,
+            priority,
+            position,
+            timestamp: chrono::Utc::now().timestamp(),
+        },
+    );
+}
+
+/// Stop the run's whole process group, so an agent started through npx or bunx is paused
+/// along with the wrapper
+#[cfg(unix)]
+fn pause_process(pid: u32) -> Result<(), String> {
+    crate::executor::signal_group(pid, nix::sys::signal::Signal::SIGSTOP)
+}
+
+#[cfg(unix)]
+fn resume_process(pid: u32) -> Result<(), String> {
+    crate::executor::signal_group(pid, nix::sys::signal::Signal::SIGCONT)
+}
+
+#[cfg(not(unix))]
+fn pause_process(_pid: u32) -> Result<(), String> {
+    Err("Pausing runs is not supported on this platform".to_string())
+}
+
+#[cfg(not(unix))]
+fn resume_process(_pid: u32) -> Result<(), String> {
+    Err("Resuming runs is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(limit: usize) -> QueueState {
+        QueueState {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    fn start(state: &mut QueueState, run_id: &str, priority: RunPriority, pid: u32) {
+        state.enqueue(run_id, priority);
+        assert_eq!(state.admit(run_id, false), Admission::Start);
+        state.running.last_mut().unwrap().pid = Some(pid);
+    }
+
+    #[test]
+    fn test_high_priority_jumps_the_line() {
+        let mut state = queue(1);
+        start(&mut state, "running", RunPriority::Normal, 10);
+        state.enqueue("eval", RunPriority::Low);
+        state.enqueue("doctor", RunPriority::High);
+
+        assert_eq!(state.admit("eval", false), Admission::Wait { position: 2 });
+        assert_eq!(
+            state.admit("doctor", false),
+            Admission::Wait { position: 1 }
+        );
+
+        state.remove("running");
+        assert_eq!(state.admit("eval", false), Admission::Wait { position: 2 });
+        assert_eq!(state.admit("doctor", false), Admission::Start);
+    }
+
+    #[test]
+    fn test_preempt_pauses_lowest_priority() {
+        let mut state = queue(2);
+        start(&mut state, "eval", RunPriority::Low, 10);
+        start(&mut state, "agent", RunPriority::Normal, 11);
+        state.enqueue("doctor", RunPriority::High);
+
+        assert_eq!(
+            state.admit("doctor", false),
+            Admission::Wait { position: 1 }
+        );
+        assert_eq!(
+            state.admit("doctor", true),
+            Admission::Preempt {
+                run_id: "eval".to_string(),
+                priority: RunPriority::Low,
+                pid: 10,
+            }
+        );
+        assert!(state.is_paused("eval"));
+        assert_eq!(state.admit("doctor", true), Admission::Start);
+
+        // Equal priority never preempts
+        state.enqueue("agent2", RunPriority::Normal);
+        assert_eq!(state.admit("agent2", true), Admission::Wait { position: 1 });
+    }
+
+    #[test]
+    fn test_paused_runs_resume_when_slots_free() {
+        let mut state = queue(1);
+        start(&mut state, "eval", RunPriority::Low, 10);
+        state.enqueue("doctor", RunPriority::High);
+        assert!(matches!(
+            state.admit("doctor", true),
+            Admission::Preempt { .. }
+        ));
+        assert_eq!(state.admit("doctor", true), Admission::Start);
+        state.enqueue("agent", RunPriority::Normal);
+
+        // A waiting run that outranks the paused one goes first
+        state.remove("doctor");
+        assert!(state.take_resumable().is_empty());
+        assert_eq!(state.admit("agent", true), Admission::Start);
+
+        state.remove("agent");
+        assert_eq!(
+            state.take_resumable(),
+            vec![("eval".to_string(), RunPriority::Low, Some(10))]
+        );
+        assert!(!state.is_paused("eval"));
+    }
+
+    #[test]
+    fn test_paused_run_keeps_its_pause_when_signalled() {
+        let mut state = queue(1);
+        start(&mut state, "eval", RunPriority::Low, 10);
+        state.enqueue("doctor", RunPriority::High);
+        assert!(matches!(
+            state.admit("doctor", true),
+            Admission::Preempt { .. }
+        ));
+        assert_eq!(state.admit("doctor", true), Admission::Start);
+
+        // Continuing the paused group for a signal leaves the slot to the doctor run
+        assert_eq!(state.paused_pid("eval"), Some(10));
+        assert_eq!(state.paused_pid("doctor"), None);
+        assert!(state.is_paused("eval"));
+        assert_eq!(state.active(), 1);
+    }
+
+    #[test]
+    fn test_failed_pause_is_not_retried() {
+        let mut state = queue(1);
+        start(&mut state, "eval", RunPriority::Low, 10);
+        state.enqueue("doctor", RunPriority::High);
+        assert!(matches!(
+            state.admit("doctor", true),
+            Admission::Preempt { .. }
+        ));
+
+        state.cancel_pause("eval");
+        assert_eq!(state.admit("doctor", true), Admission::Wait { position: 1 });
+    }
+}
@@ -0,0 +1,137 @@
+//! Run template variables
+//! `{{character}}`, `{{port}}`, `{{project_dir}}` and a spec's own variables are substituted
+//! into its args and env values at spawn time; env values may also read `{{secret.NAME}}`
+//! from the local secret store, which is never substituted into the command line
+
+use crate::commands::prompt_templates::{render, template_variables};
+use crate::commands::secrets::read_secrets;
+use crate::commands::terminal::resolve_working_directory;
+use crate::models::{AppError, RunSpec};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+const BUILTIN_VARIABLES: [&str; 3] = ["character", "port", "project_dir"];
+const SECRET_PREFIX: &str = "secret.";
+
+/// Copy of the spec with every placeholder in its args and env values resolved
+pub(crate) fn resolve_run_spec(app: &AppHandle, spec: &RunSpec) -> Result<RunSpec, AppError> {
+    let mut variables = run_variables(spec)?;
+    let mut resolved = spec.clone();
+    resolved.args = spec
+        .args
+        .iter()
+        .map(|arg| render(arg, &variables))
+        .collect::<Result<_, _>>()?;
+
+    let secret_names = secret_references(spec.env.values());
+    if !secret_names.is_empty() {
+        let secrets = read_secrets(app)?;
+        for name in secret_names {
+            if let Some(value) = secrets.get(&name) {
+                variables.insert(format!("{}{}", SECRET_PREFIX, name), value.clone());
+            }
+        }
+    }
+    resolved.env = spec
+        .env
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), render(value, &variables)?)))
+        .collect::<Result<_, AppError>>()?;
+
+    Ok(resolved)
+}
+
+/// Built-in values from the spec, plus its user-defined variables
+fn run_variables(spec: &RunSpec) -> Result<HashMap<String, String>, AppError> {
+    if let Some(name) = spec
+        .variables
+        .keys()
+        .find(|name| BUILTIN_VARIABLES.contains(&name.as_str()) || name.starts_with(SECRET_PREFIX))
+    {
+        return Err(AppError::Config(format!(
+            "Run variable '{}' is reserved",
+            name
+        )));
+    }
+
+    let mut variables = spec.variables.clone();
+    if let Some(ref character) = spec.character_file {
+        variables.insert("character".to_string(), character.clone());
+    }
+    if let Some(port) = spec.port {
+        variables.insert("port".to_string(), port.to_string());
+    }
+    if let Some(ref dir) = spec.working_dir {
+        variables.insert(
+            "project_dir".to_string(),
+            resolve_working_directory(dir.clone()),
+        );
+    }
+    Ok(variables)
+}
+
+/// Secret names referenced by `{{secret.NAME}}` placeholders
+fn secret_references<'a>(values: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for value in values {
+        for variable in template_variables(value) {
+            if let Some(name) = variable.strip_prefix(SECRET_PREFIX) {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RunMode;
+
+    #[test]
+    fn test_builtin_and_user_variables() {
+        let mut spec = RunSpec::new(
+            "run_1".to_string(),
+            RunMode::Run,
+            vec!["--character".to_string(), "{{character}}".to_string()],
+        );
+        spec.character_file = Some("/chars/ada.json".to_string());
+        spec.port = Some(3001);
+        spec.variables = HashMap::from([("model".to_string(), "gpt-4".to_string())]);
+
+        let variables = run_variables(&spec).unwrap();
+        assert_eq!(variables["character"], "/chars/ada.json");
+        assert_eq!(variables["port"], "3001");
+        assert_eq!(variables["model"], "gpt-4");
+        assert!(!variables.contains_key("project_dir"));
+        assert_eq!(
+            render("--port={{port}} {{ model }}", &variables).unwrap(),
+            "--port=3001 gpt-4"
+        );
+        assert!(render("{{unknown}}", &variables).is_err());
+    }
+
+    #[test]
+    fn test_reserved_variable_names() {
+        let mut spec = RunSpec::new("run_1".to_string(), RunMode::Run, Vec::new());
+        spec.variables = HashMap::from([("port".to_string(), "80".to_string())]);
+        assert!(run_variables(&spec).is_err());
+
+        spec.variables = HashMap::from([("secret.KEY".to_string(), "x".to_string())]);
+        assert!(run_variables(&spec).is_err());
+    }
+
+    #[test]
+    fn test_secret_references() {
+        let values = [
+            "Bearer {{secret.OPENAI_API_KEY}}".to_string(),
+            "{{secret.OPENAI_API_KEY}}:{{secret.ORG}} {{port}}".to_string(),
+        ];
+        assert_eq!(
+            secret_references(values.iter()),
+            vec!["OPENAI_API_KEY", "ORG"]
+        );
+    }
+}
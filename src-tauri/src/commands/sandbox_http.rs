@@ -0,0 +1,472 @@
+//! Shared Sandbox HTTP client
+//! Every Sandbox API call goes through one pooled client that tracks the rate-limit headers
+//! of each host and holds requests back while a host's remaining budget is nearly spent.
+//! The client uses the proxy and root certificate of the active configuration profile
+
+use crate::commands::compression::{encoding_for, ContentEncoding};
+use crate::models::{ApiResponse, AppError, HttpClientConfig, RateLimitStatus};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{
+    Certificate, Client, IntoUrl, Proxy, Request, RequestBuilder, Response, StatusCode, Url,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+const USER_AGENT: &str = "ElizaOS-Desktop/0.1.0";
+/// Requests left in a window below which further calls wait for the window to reset
+const RESERVE_REQUESTS: u64 = 2;
+/// Longest a request is held back waiting for a window to reset
+const MAX_QUEUE_WAIT: Duration = Duration::from_secs(60);
+/// Reset values above this are Unix timestamps rather than seconds from now
+const EPOCH_RESET_THRESHOLD: f64 = 1_000_000_000.0;
+
+/// Rate-limit budget last reported by one host
+#[derive(Debug, Default)]
+struct HostLimit {
+    limit: Option<u64>,
+    remaining: Option<u64>,
+    reset_at: Option<DateTime<Utc>>,
+    queued: u64,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl HostLimit {
+    /// Take one request from the budget, returning how long it must wait first
+    fn reserve(&mut self, now: DateTime<Utc>) -> Option<Duration> {
+        let reset_at = self.reset_at?;
+        if reset_at <= now {
+            // The window is over; the next response reports the new budget
+            self.remaining = None;
+            self.reset_at = None;
+            return None;
+        }
+
+        let remaining = self.remaining?;
+        if remaining > RESERVE_REQUESTS {
+            self.remaining = Some(remaining - 1);
+            return None;
+        }
+        self.queued += 1;
+        Some(
+            (reset_at - now)
+                .to_std()
+                .unwrap_or_default()
+                .min(MAX_QUEUE_WAIT),
+        )
+    }
+
+    /// Update the budget from a response's headers
+    fn record(&mut self, status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) {
+        let limit = header_number(headers, &["x-ratelimit-limit", "ratelimit-limit"]);
+        let remaining = header_number(headers, &["x-ratelimit-remaining", "ratelimit-remaining"]);
+        let reset = header_number(headers, &["x-ratelimit-reset", "ratelimit-reset"]);
+
+        if limit.is_some() {
+            self.limit = limit.map(|n| n as u64);
+        }
+        if remaining.is_some() {
+            self.remaining = remaining.map(|n| n as u64);
+        }
+        if let Some(reset) = reset {
+            self.reset_at = Some(reset_time(reset, now));
+        }
+
+        let throttled = status == StatusCode::TOO_MANY_REQUESTS;
+        if throttled {
+            let retry_after = header_number(headers, &["retry-after"]).unwrap_or(1.0);
+            let retry_at = reset_time(retry_after, now);
+            self.remaining = Some(0);
+            self.reset_at = Some(self.reset_at.map_or(retry_at, |at| at.max(retry_at)));
+        }
+
+        if throttled || limit.is_some() || remaining.is_some() || reset.is_some() {
+            self.updated_at = Some(now);
+        }
+    }
+
+    fn status(&self, host: &str, now: DateTime<Utc>) -> RateLimitStatus {
+        let window_open = self.reset_at.is_some_and(|at| at > now);
+        RateLimitStatus {
+            host: host.to_string(),
+            limit: self.limit,
+            remaining: self.remaining,
+            reset_at: self.reset_at.map(|at| at.to_rfc3339()),
+            throttled: window_open && self.remaining.is_some_and(|r| r <= RESERVE_REQUESTS),
+            queued_requests: self.queued,
+            updated_at: self.updated_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+/// HTTP client shared by all Sandbox calls, with per-host rate-limit tracking
+pub struct SandboxHttp {
+    client: RwLock<Client>,
+    /// Settings `client` was built with
+    settings: Mutex<HttpClientConfig>,
+    limits: Mutex<HashMap<String, HostLimit>>,
+    /// Body coding each host settled on after refusing one with 415
+    encodings: Mutex<HashMap<String, ContentEncoding>>,
+}
+
+/// The process-wide Sandbox client; callers without an `AppHandle` (telemetry, connection
+/// tests) need it too, so it lives in a static rather than managed state
+pub fn sandbox_http() -> &'static SandboxHttp {
+    static CLIENT: OnceLock<SandboxHttp> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let client = build_client(&HttpClientConfig::default()).unwrap_or_else(|e| {
+            log::error!("{}, using defaults", e);
+            Client::new()
+        });
+        SandboxHttp {
+            client: RwLock::new(client),
+            settings: Mutex::new(HttpClientConfig::default()),
+            limits: Mutex::new(HashMap::new()),
+            encodings: Mutex::new(HashMap::new()),
+        }
+    })
+}
+
+impl SandboxHttp {
+    /// Rebuild the client when the proxy or certificate settings changed; the connections
+    /// pooled by the previous client close once its in-flight requests finish
+    pub fn configure(&self, settings: &HttpClientConfig) -> Result<(), AppError> {
+        let mut current = self.settings.lock().unwrap_or_else(|e| e.into_inner());
+        if *current == *settings {
+            return Ok(());
+        }
+
+        let client = build_client(settings)?;
+        *self.client.write().unwrap_or_else(|e| e.into_inner()) = client;
+        *current = settings.clone();
+        log::info!(
+            "Sandbox HTTP client reconfigured (proxy: {}, extra CA certificate: {})",
+            if configured(&settings.proxy_url).is_some() {
+                "set"
+            } else {
+                "system"
+            },
+            configured(&settings.ca_cert_path).unwrap_or("none")
+        );
+        Ok(())
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client().get(url)
+    }
+
+    pub fn head<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client().head(url)
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client().post(url)
+    }
+
+    pub fn put<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client().put(url)
+    }
+
+    /// Send a request built from this client, first waiting out an exhausted budget
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let request = request.build()?;
+        let host = host_key(request.url());
+        self.execute(host, request).await
+    }
+
+    /// Send a JSON body, compressed once it passes the size threshold; a 415 is retried
+    /// with the next coding the host accepts, or uncompressed
+    pub async fn send_json_body(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response, reqwest::Error> {
+        let template = request.header(CONTENT_TYPE, "application/json").build()?;
+        let host = host_key(template.url());
+        let preferred = self.encodings().get(&host).copied();
+        let mut encoding = encoding_for(body.len(), preferred);
+        let mut tried = Vec::new();
+
+        loop {
+            let mut request = template
+                .try_clone()
+                .expect("a request without a body can be cloned");
+            let encoded = encoding.encode(&body).unwrap_or_else(|e| {
+                log::warn!("Failed to compress request body, sending it as is: {}", e);
+                encoding = ContentEncoding::Identity;
+                body.clone()
+            });
+            if let Some(value) = encoding.header_value() {
+                request
+                    .headers_mut()
+                    .insert(CONTENT_ENCODING, HeaderValue::from_static(value));
+            }
+            *request.body_mut() = Some(encoded.into());
+
+            let response = self.execute(host.clone(), request).await?;
+            if response.status() != StatusCode::UNSUPPORTED_MEDIA_TYPE
+                || encoding == ContentEncoding::Identity
+            {
+                return Ok(response);
+            }
+
+            tried.push(encoding);
+            let accept_encoding = response
+                .headers()
+                .get(ACCEPT_ENCODING)
+                .and_then(|value| value.to_str().ok());
+            let next = encoding.fallback(accept_encoding);
+            encoding = if tried.contains(&next) {
+                ContentEncoding::Identity
+            } else {
+                next
+            };
+            log::info!(
+                "{} refused a compressed body; retrying with {}",
+                host,
+                encoding.header_value().unwrap_or("no compression")
+            );
+            self.encodings().insert(host.clone(), encoding);
+        }
+    }
+
+    async fn execute(&self, host: String, request: Request) -> Result<Response, reqwest::Error> {
+        let wait = self
+            .limits()
+            .entry(host.clone())
+            .or_default()
+            .reserve(Utc::now());
+        if let Some(wait) = wait {
+            log::info!(
+                "Sandbox rate limit nearly reached for {}; waiting {:?}",
+                host,
+                wait
+            );
+            tokio::time::sleep(wait).await;
+        }
+
+        let response = self.client().execute(request).await?;
+        self.limits().entry(host).or_default().record(
+            response.status(),
+            response.headers(),
+            Utc::now(),
+        );
+        Ok(response)
+    }
+
+    /// Last known budget of every host called so far
+    pub fn status(&self) -> Vec<RateLimitStatus> {
+        let now = Utc::now();
+        let mut statuses: Vec<RateLimitStatus> = self
+            .limits()
+            .iter()
+            .map(|(host, limit)| limit.status(host, now))
+            .collect();
+        statuses.sort_by(|a, b| a.host.cmp(&b.host));
+        statuses
+    }
+
+    /// Budget of the host serving `url`, once it has sent rate-limit headers
+    pub fn status_for(&self, url: &str) -> Option<RateLimitStatus> {
+        let host = host_key(&Url::parse(url).ok()?);
+        let limits = self.limits();
+        let limit = limits.get(&host)?;
+        limit
+            .updated_at
+            .is_some()
+            .then(|| limit.status(&host, Utc::now()))
+    }
+
+    /// The current client; cloning it shares its connection pool
+    fn client(&self) -> Client {
+        self.client
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn limits(&self) -> std::sync::MutexGuard<'_, HashMap<String, HostLimit>> {
+        self.limits.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn encodings(&self) -> std::sync::MutexGuard<'_, HashMap<String, ContentEncoding>> {
+        self.encodings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Rate-limit budget of each Sandbox host called this session
+#[tauri::command]
+pub async fn get_rate_limit_status() -> Result<ApiResponse<Vec<RateLimitStatus>>, String> {
+    Ok(ApiResponse::success(sandbox_http().status()))
+}
+
+/// A client with the given proxy and extra root certificate; also used to validate the
+/// settings before a configuration is saved
+pub(crate) fn build_client(settings: &HttpClientConfig) -> Result<Client, AppError> {
+    let mut builder = Client::builder().user_agent(USER_AGENT);
+
+    if let Some(proxy_url) = configured(&settings.proxy_url) {
+        // The URL may carry proxy credentials, so it is left out of the error
+        let proxy = Proxy::all(proxy_url)
+            .map_err(|e| AppError::Config(format!("Invalid proxy URL: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = configured(&settings.ca_cert_path) {
+        let pem = fs::read(path).map_err(|e| {
+            AppError::Config(format!("Failed to read CA certificate '{}': {}", path, e))
+        })?;
+        let certificate = Certificate::from_pem(&pem)
+            .map_err(|e| AppError::Config(format!("Invalid CA certificate '{}': {}", path, e)))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))
+}
+
+fn configured(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn host_key(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// First number of the first header present; tolerates values like `100, 100;w=60`
+fn header_number(headers: &HeaderMap, names: &[&str]) -> Option<f64> {
+    names.iter().find_map(|name| {
+        let value = headers.get(*name)?.to_str().ok()?;
+        value
+            .split([',', ';'])
+            .next()?
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n >= 0.0)
+    })
+}
+
+fn reset_time(value: f64, now: DateTime<Utc>) -> DateTime<Utc> {
+    if value > EPOCH_RESET_THRESHOLD {
+        DateTime::from_timestamp(value as i64, 0).unwrap_or(now)
+    } else {
+        now + chrono::Duration::milliseconds((value * 1000.0) as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        map
+    }
+
+    #[test]
+    fn test_record_rate_limit_headers() {
+        let now = Utc::now();
+        let mut limit = HostLimit::default();
+        limit.record(
+            StatusCode::OK,
+            &headers(&[
+                ("x-ratelimit-limit", "100"),
+                ("x-ratelimit-remaining", "42"),
+                ("x-ratelimit-reset", "30"),
+            ]),
+            now,
+        );
+        assert_eq!(limit.limit, Some(100));
+        assert_eq!(limit.remaining, Some(42));
+        assert_eq!(limit.reset_at, Some(now + chrono::Duration::seconds(30)));
+
+        let epoch = now.timestamp() + 90;
+        limit.record(
+            StatusCode::OK,
+            &headers(&[
+                ("ratelimit-remaining", "41, 100;w=60"),
+                ("ratelimit-reset", &epoch.to_string()),
+            ]),
+            now,
+        );
+        assert_eq!(limit.remaining, Some(41));
+        assert_eq!(limit.reset_at.unwrap().timestamp(), epoch);
+    }
+
+    #[test]
+    fn test_reserve_queues_near_the_limit() {
+        let now = Utc::now();
+        let mut limit = HostLimit::default();
+        assert_eq!(limit.reserve(now), None);
+
+        limit.record(
+            StatusCode::OK,
+            &headers(&[("x-ratelimit-remaining", "3"), ("x-ratelimit-reset", "10")]),
+            now,
+        );
+        assert_eq!(limit.reserve(now), None);
+        assert_eq!(limit.remaining, Some(2));
+        assert_eq!(limit.reserve(now), Some(Duration::from_secs(10)));
+        assert_eq!(limit.queued, 1);
+        assert!(limit.status("sandbox", now).throttled);
+
+        // Once the window resets the budget is unknown until the next response
+        assert_eq!(limit.reserve(now + chrono::Duration::seconds(11)), None);
+        assert_eq!(limit.remaining, None);
+    }
+
+    #[test]
+    fn test_too_many_requests_uses_retry_after() {
+        let now = Utc::now();
+        let mut limit = HostLimit::default();
+        limit.record(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("retry-after", "5")]),
+            now,
+        );
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(limit.reserve(now), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_build_client_settings() {
+        assert!(build_client(&HttpClientConfig::default()).is_ok());
+        assert!(build_client(&HttpClientConfig {
+            proxy_url: Some("http://proxy.example.com:8080".to_string()),
+            ca_cert_path: Some(" ".to_string()),
+        })
+        .is_ok());
+
+        let err = build_client(&HttpClientConfig {
+            proxy_url: None,
+            ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+        })
+        .unwrap_err();
+        assert_eq!(err.error_code(), "CONFIG_ERROR");
+    }
+
+    #[test]
+    fn test_host_key() {
+        let url = Url::parse("https://sandbox.example.com/api/v1/agents").unwrap();
+        assert_eq!(host_key(&url), "sandbox.example.com");
+        let url = Url::parse("http://localhost:3000/health").unwrap();
+        assert_eq!(host_key(&url), "localhost:3000");
+    }
+}
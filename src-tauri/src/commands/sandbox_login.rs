@@ -0,0 +1,442 @@
+//! Sandbox login
+//! Signs in through the Sandbox's OAuth device flow: the user approves a short code in the
+//! browser while the app polls for the token, and the API key it returns is written into the
+//! saved config without the user ever copying it
+
+use crate::commands::app_lock::require_unlocked;
+use crate::commands::config::{resolve_profile, save_login_api_key, validate_base_url};
+use crate::commands::sandbox_http::sandbox_http;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, AppError, SandboxConfig, SandboxLoginEvent, SandboxLoginSession,
+    SandboxLoginStatus,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+const CLIENT_ID: &str = "elizaos-desktop";
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+/// Longest polling interval, however often the Sandbox asks to slow down
+const MAX_INTERVAL_SECS: u64 = 60;
+/// Added to the polling interval each time the Sandbox answers `slow_down`
+const SLOW_DOWN_SECS: u64 = 5;
+const DEFAULT_EXPIRES_SECS: u64 = 900;
+/// Longest a login polls, whatever `expires_in` the Sandbox sends
+const MAX_EXPIRES_SECS: u64 = 30 * 60;
+/// Consecutive failed polls after which the login is given up
+const MAX_POLL_ERRORS: u32 = 3;
+
+/// Cancel flags of the logins still polling, by login id
+pub type SandboxLoginState = Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>;
+
+/// Initialize the pending login table (called from main)
+pub fn init_sandbox_login_state() -> SandboxLoginState {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: Option<u64>,
+    interval: Option<u64>,
+}
+
+/// What one poll of the token endpoint said
+#[derive(Debug, PartialEq)]
+enum PollOutcome {
+    ApiKey(String),
+    Pending,
+    SlowDown,
+    Finished(SandboxLoginStatus, String),
+}
+
+/// Start a device-code login against `base_url`. The verification page is opened in the
+/// browser unless `open_browser` is false; the result arrives as a `sandbox-login` event
+#[tauri::command]
+pub async fn start_sandbox_login(
+    app: AppHandle,
+    base_url: String,
+    profile_name: Option<String>,
+    open_browser: Option<bool>,
+) -> Result<ApiResponse<SandboxLoginSession>, String> {
+    let base_url = base_url.trim().trim_end_matches('/').to_string();
+
+    let result = async {
+        require_unlocked(&app)?;
+        if !validate_base_url(&base_url) {
+            return Err(AppError::Config(format!("Invalid base URL '{}'", base_url)));
+        }
+        let profile = resolve_profile(&app, profile_name.as_deref())?;
+        let device = request_device_code(&base_url).await?;
+        Ok::<_, AppError>((profile, device))
+    }
+    .await;
+
+    let (profile, device) = match result {
+        Ok(started) => started,
+        Err(e) => return Ok(error_response("Failed to start Sandbox login", e)),
+    };
+
+    let login_id = uuid::Uuid::new_v4().to_string();
+    let expires_in = Duration::from_secs(
+        device
+            .expires_in
+            .unwrap_or(DEFAULT_EXPIRES_SECS)
+            .min(MAX_EXPIRES_SECS),
+    );
+    let session = SandboxLoginSession {
+        login_id: login_id.clone(),
+        user_code: device.user_code,
+        verification_uri: device.verification_uri,
+        verification_uri_complete: device.verification_uri_complete,
+        expires_at: (chrono::Utc::now()
+            + chrono::Duration::from_std(expires_in).unwrap_or_default())
+        .to_rfc3339(),
+        profile: profile.clone(),
+    };
+
+    if open_browser.unwrap_or(true) {
+        let url = session
+            .verification_uri_complete
+            .as_deref()
+            .unwrap_or(&session.verification_uri);
+        if !is_safe_verification_url(url) {
+            log::warn!("Not opening login page '{}': it is not an https URL", url);
+        } else if let Err(e) = app.opener().open_url(url, None::<&str>) {
+            log::warn!("Failed to open the login page in the browser: {}", e);
+        }
+    }
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    pending_logins(&app).insert(login_id.clone(), cancelled.clone());
+
+    let poll = LoginPoll {
+        base_url,
+        device_code: device.device_code,
+        interval: Duration::from_secs(
+            device
+                .interval
+                .unwrap_or(DEFAULT_INTERVAL_SECS)
+                .clamp(1, MAX_INTERVAL_SECS),
+        ),
+        deadline: Instant::now() + expires_in,
+        cancelled,
+    };
+    tauri::async_runtime::spawn(finish_login(app, login_id, profile, poll));
+
+    log::info!("Sandbox login {} started", session.login_id);
+    Ok(ApiResponse::success(session))
+}
+
+/// Stop polling for a login; its `sandbox-login` event reports it as cancelled
+#[tauri::command]
+pub async fn cancel_sandbox_login(
+    app: AppHandle,
+    login_id: String,
+) -> Result<ApiResponse<()>, String> {
+    match pending_logins(&app).get(&login_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::SeqCst);
+            log::info!("Sandbox login {} cancelled", login_id);
+            Ok(ApiResponse::success(()))
+        }
+        None => Ok(ApiResponse::error(
+            "LOGIN_NOT_FOUND".to_string(),
+            format!("No Sandbox login {} is in progress", login_id),
+        )),
+    }
+}
+
+async fn request_device_code(base_url: &str) -> Result<DeviceCodeResponse, AppError> {
+    let url = login_url(base_url, "auth/device/code");
+    let response = sandbox_http()
+        .send(
+            sandbox_http()
+                .post(&url)
+                .json(&json!({ "client_id": CLIENT_ID })),
+        )
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Network(format!(
+            "The Sandbox refused the login request ({})",
+            status
+        )));
+    }
+    response.json::<DeviceCodeResponse>().await.map_err(|e| {
+        AppError::Network(format!(
+            "The Sandbox sent an unexpected login response: {}",
+            e
+        ))
+    })
+}
+
+struct LoginPoll {
+    base_url: String,
+    device_code: String,
+    interval: Duration,
+    deadline: Instant,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Poll until the login ends, save the key on success and report the outcome
+async fn finish_login(app: AppHandle, login_id: String, profile: String, poll: LoginPoll) {
+    let base_url = poll.base_url.clone();
+    let (status, message) = match poll_for_api_key(poll).await {
+        Ok(api_key) => match save_login_api_key(&app, &profile, &base_url, api_key).await {
+            Ok(_) => (SandboxLoginStatus::Succeeded, None),
+            Err(e) => (SandboxLoginStatus::Failed, Some(e.localized_message())),
+        },
+        Err((status, message)) => (status, Some(message)),
+    };
+    pending_logins(&app).remove(&login_id);
+
+    match &message {
+        Some(message) => log::warn!("Sandbox login {} ended: {}", login_id, message),
+        None => log::info!("Sandbox login {} saved an API key to {}", login_id, profile),
+    }
+    emit_event(
+        &app,
+        "sandbox-login",
+        SandboxLoginEvent {
+            login_id,
+            status,
+            profile,
+            message,
+        },
+    );
+}
+
+async fn poll_for_api_key(mut poll: LoginPoll) -> Result<String, (SandboxLoginStatus, String)> {
+    let url = login_url(&poll.base_url, "auth/device/token");
+    let mut errors = 0;
+
+    loop {
+        tokio::time::sleep(poll.interval).await;
+        if poll.cancelled.load(Ordering::SeqCst) {
+            return Err((
+                SandboxLoginStatus::Cancelled,
+                "Login was cancelled".to_string(),
+            ));
+        }
+        if Instant::now() >= poll.deadline {
+            return Err((
+                SandboxLoginStatus::Expired,
+                "The login code expired before it was approved".to_string(),
+            ));
+        }
+
+        let body = json!({
+            "client_id": CLIENT_ID,
+            "device_code": poll.device_code,
+            "grant_type": DEVICE_GRANT_TYPE,
+        });
+        let response = match sandbox_http()
+            .send(sandbox_http().post(&url).json(&body))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                errors += 1;
+                if errors >= MAX_POLL_ERRORS {
+                    return Err((
+                        SandboxLoginStatus::Failed,
+                        format!("Could not reach the Sandbox: {}", e),
+                    ));
+                }
+                continue;
+            }
+        };
+        errors = 0;
+
+        let status = response.status().as_u16();
+        let body = response.json::<Value>().await.unwrap_or_default();
+        match parse_token_response(status, &body) {
+            PollOutcome::ApiKey(api_key) => return Ok(api_key),
+            PollOutcome::Pending => {}
+            PollOutcome::SlowDown => {
+                poll.interval = (poll.interval + Duration::from_secs(SLOW_DOWN_SECS))
+                    .min(Duration::from_secs(MAX_INTERVAL_SECS))
+            }
+            PollOutcome::Finished(status, message) => return Err((status, message)),
+        }
+    }
+}
+
+/// Whether a verification URL the Sandbox sent may be opened in the browser: https, or
+/// http on a loopback host for a local Sandbox
+fn is_safe_verification_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    match url.scheme() {
+        "https" => true,
+        "http" => url.host_str().is_some_and(|host| {
+            host.eq_ignore_ascii_case("localhost")
+                || host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse::<std::net::IpAddr>()
+                    .is_ok_and(|ip| ip.is_loopback())
+        }),
+        _ => false,
+    }
+}
+
+/// Read a token endpoint response; the key may come back as `api_key`, `apiKey` or the
+/// standard `access_token`
+fn parse_token_response(status: u16, body: &Value) -> PollOutcome {
+    if (200..300).contains(&status) {
+        let api_key = ["api_key", "apiKey", "access_token"]
+            .iter()
+            .find_map(|field| body.get(field).and_then(Value::as_str));
+        return match api_key {
+            Some(api_key) => PollOutcome::ApiKey(api_key.to_string()),
+            None => PollOutcome::Finished(
+                SandboxLoginStatus::Failed,
+                "The Sandbox approved the login but sent no API key".to_string(),
+            ),
+        };
+    }
+
+    let description = body
+        .get("error_description")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    match body.get("error").and_then(Value::as_str) {
+        Some("authorization_pending") => PollOutcome::Pending,
+        Some("slow_down") => PollOutcome::SlowDown,
+        Some("expired_token") => PollOutcome::Finished(
+            SandboxLoginStatus::Expired,
+            description.unwrap_or_else(|| "The login code expired".to_string()),
+        ),
+        Some("access_denied") => PollOutcome::Finished(
+            SandboxLoginStatus::Denied,
+            description.unwrap_or_else(|| "The login was denied".to_string()),
+        ),
+        error => PollOutcome::Finished(
+            SandboxLoginStatus::Failed,
+            description.unwrap_or_else(|| {
+                format!(
+                    "The Sandbox returned {} ({})",
+                    status,
+                    error.unwrap_or("no error code")
+                )
+            }),
+        ),
+    }
+}
+
+fn login_url(base_url: &str, path: &str) -> String {
+    SandboxConfig::new(base_url.to_string(), String::new()).api_url(path)
+}
+
+fn pending_logins(app: &AppHandle) -> std::sync::MutexGuard<'_, HashMap<String, Arc<AtomicBool>>> {
+    app.state::<SandboxLoginState>()
+        .inner()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_response_success() {
+        let key = format!("eliza_{}", "a".repeat(64));
+        for field in ["api_key", "apiKey", "access_token"] {
+            assert_eq!(
+                parse_token_response(200, &json!({ field: key })),
+                PollOutcome::ApiKey(key.clone())
+            );
+        }
+        assert!(matches!(
+            parse_token_response(200, &json!({ "token_type": "bearer" })),
+            PollOutcome::Finished(SandboxLoginStatus::Failed, _)
+        ));
+    }
+
+    #[test]
+    fn test_parse_token_response_errors() {
+        assert_eq!(
+            parse_token_response(400, &json!({ "error": "authorization_pending" })),
+            PollOutcome::Pending
+        );
+        assert_eq!(
+            parse_token_response(400, &json!({ "error": "slow_down" })),
+            PollOutcome::SlowDown
+        );
+        assert!(matches!(
+            parse_token_response(400, &json!({ "error": "expired_token" })),
+            PollOutcome::Finished(SandboxLoginStatus::Expired, _)
+        ));
+        assert_eq!(
+            parse_token_response(
+                400,
+                &json!({ "error": "access_denied", "error_description": "User said no" })
+            ),
+            PollOutcome::Finished(SandboxLoginStatus::Denied, "User said no".to_string())
+        );
+        assert!(matches!(
+            parse_token_response(500, &Value::Null),
+            PollOutcome::Finished(SandboxLoginStatus::Failed, _)
+        ));
+    }
+
+    #[test]
+    fn test_device_code_response_aliases() {
+        let device: DeviceCodeResponse = serde_json::from_value(json!({
+            "device_code": "dev",
+            "user_code": "ABCD-EFGH",
+            "verification_url": "https://sandbox.example/device",
+        }))
+        .unwrap();
+        assert_eq!(device.verification_uri, "https://sandbox.example/device");
+        assert!(device.interval.is_none());
+    }
+
+    #[test]
+    fn test_is_safe_verification_url() {
+        assert!(is_safe_verification_url("https://sandbox.example/device"));
+        assert!(is_safe_verification_url("http://localhost:3000/device"));
+        assert!(is_safe_verification_url("http://127.0.0.1:3000/device"));
+        assert!(is_safe_verification_url("http://[::1]/device"));
+        assert!(!is_safe_verification_url("http://sandbox.example/device"));
+        assert!(!is_safe_verification_url("file:///etc/passwd"));
+        assert!(!is_safe_verification_url("javascript:alert(1)"));
+        assert!(!is_safe_verification_url("not a url"));
+    }
+
+    #[test]
+    fn test_login_url() {
+        assert_eq!(
+            login_url("https://sandbox.example/api/v1", "auth/device/code"),
+            "https://sandbox.example/api/v1/auth/device/code"
+        );
+        assert_eq!(
+            login_url("https://sandbox.example", "auth/device/token"),
+            "https://sandbox.example/api/v1/auth/device/token"
+        );
+    }
+}
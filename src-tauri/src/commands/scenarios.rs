@@ -0,0 +1,339 @@
+//! Scenario runner
+//! Replays scripted conversations from YAML or JSON files against a locally running agent
+//! through the chat bridge, checking each response and storing the result
+
+use crate::commands::chat::{agent_base_url, ChatBridge};
+use crate::commands::stats::emit_event;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, Scenario, ScenarioAssertion, ScenarioResult,
+    ScenarioStepResult,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const SCENARIO_RESULTS_DIR: &str = "scenario-results";
+/// Upper bound on steps in one scenario
+pub const MAX_SCENARIO_STEPS: usize = 200;
+
+/// Replay a scenario file against the agent server on `port` and store the result
+#[tauri::command]
+pub async fn run_scenario(
+    app: AppHandle,
+    scenario_path: String,
+    port: u16,
+) -> Result<ApiResponse<ScenarioResult>, String> {
+    log::info!("Running scenario {} against port {}", scenario_path, port);
+
+    let result = async {
+        let scenario = load_scenario(Path::new(&scenario_path))?;
+        let bridge = ChatBridge::new(agent_base_url(port))?;
+        let result = replay(&app, &bridge, &scenario, &scenario_path).await;
+        save_result(&app, &result)?;
+        Ok::<_, AppError>(result)
+    }
+    .await;
+
+    match result {
+        Ok(result) => {
+            log::info!(
+                "Scenario {} finished: {}",
+                result.id,
+                if result.passed { "passed" } else { "failed" }
+            );
+            Ok(ApiResponse::success(result))
+        }
+        Err(e) => {
+            log::error!("Scenario run failed: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Scenario run failed: {}", e),
+            ))
+        }
+    }
+}
+
+/// Load a stored scenario result
+#[tauri::command]
+pub async fn get_scenario_result(
+    app: AppHandle,
+    result_id: String,
+) -> Result<ApiResponse<ScenarioResult>, String> {
+    let result = (|| {
+        validate_result_id(&result_id)?;
+        let path = results_dir(&app)?.join(format!("{}.json", result_id));
+        if !path.exists() {
+            return Err(AppError::Eval(format!(
+                "Scenario result '{}' not found",
+                result_id
+            )));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    })();
+
+    match result {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(
+            e.error_code().to_string(),
+            format!("Failed to load scenario result: {}", e),
+        )),
+    }
+}
+
+/// Send every step in order; once the agent can't be reached the rest are not run
+async fn replay(
+    app: &AppHandle,
+    bridge: &ChatBridge,
+    scenario: &Scenario,
+    scenario_path: &str,
+) -> ScenarioResult {
+    let id = generate_result_id();
+    let started_at = current_timestamp();
+    let mut agent = bridge.resolve_agent(scenario.agent.as_deref()).await;
+    let mut steps = Vec::new();
+
+    for (index, step) in scenario.steps.iter().enumerate() {
+        let started = Instant::now();
+        let (response, failures) = match &agent {
+            Ok(agent_id) => match bridge.send_message(agent_id, &step.message).await {
+                Ok(response) => {
+                    let latency_ms = started.elapsed().as_millis() as u64;
+                    let failures = check_assertions(&step.expect, &response, latency_ms);
+                    (Some(response), failures)
+                }
+                Err(e) => {
+                    let failure = e.to_string();
+                    agent = Err(e);
+                    (None, vec![failure])
+                }
+            },
+            Err(e) if index == 0 => (None, vec![e.to_string()]),
+            Err(_) => (
+                None,
+                vec!["Not run: an earlier step could not reach the agent".to_string()],
+            ),
+        };
+
+        let step_result = ScenarioStepResult {
+            scenario_id: id.clone(),
+            index,
+            message: step.message.clone(),
+            response,
+            latency_ms: started.elapsed().as_millis() as u64,
+            passed: failures.is_empty(),
+            failures,
+        };
+        emit_event(app, "scenario-step", step_result.clone());
+        steps.push(step_result);
+    }
+
+    ScenarioResult {
+        id,
+        scenario_name: scenario.name.clone(),
+        scenario_path: scenario_path.to_string(),
+        agent_url: bridge.base_url().to_string(),
+        started_at,
+        ended_at: current_timestamp(),
+        passed: steps.iter().all(|step| step.passed),
+        steps,
+    }
+}
+
+/// Read a scenario as JSON or YAML, chosen by file extension
+fn load_scenario(path: &Path) -> Result<Scenario, AppError> {
+    let contents = fs::read_to_string(path)?;
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let invalid = |e: String| AppError::Eval(format!("Invalid scenario {}: {}", path.display(), e));
+
+    let scenario: Scenario = match extension.as_str() {
+        "json" => serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()))?,
+        "yaml" | "yml" => serde_yaml::from_str(&contents).map_err(|e| invalid(e.to_string()))?,
+        _ => {
+            return Err(AppError::Eval(format!(
+                "Unsupported scenario file {}: use .json, .yaml or .yml",
+                path.display()
+            )))
+        }
+    };
+    validate_scenario(&scenario).map_err(invalid)?;
+    Ok(scenario)
+}
+
+fn validate_scenario(scenario: &Scenario) -> Result<(), String> {
+    if scenario.steps.is_empty() {
+        return Err("a scenario needs at least one step".to_string());
+    }
+    if scenario.steps.len() > MAX_SCENARIO_STEPS {
+        return Err(format!(
+            "{} steps; the limit is {}",
+            scenario.steps.len(),
+            MAX_SCENARIO_STEPS
+        ));
+    }
+    if let Some(index) = scenario
+        .steps
+        .iter()
+        .position(|step| step.message.trim().is_empty())
+    {
+        return Err(format!("step {} has an empty message", index + 1));
+    }
+    Ok(())
+}
+
+/// Describe every assertion the response fails
+fn check_assertions(
+    assertions: &[ScenarioAssertion],
+    response: &str,
+    latency_ms: u64,
+) -> Vec<String> {
+    let lower = response.to_lowercase();
+    assertions
+        .iter()
+        .filter_map(|assertion| {
+            let passed = match assertion {
+                ScenarioAssertion::Contains { value } => lower.contains(&value.to_lowercase()),
+                ScenarioAssertion::NotContains { value } => !lower.contains(&value.to_lowercase()),
+                ScenarioAssertion::Equals { value } => response.trim() == value.trim(),
+                ScenarioAssertion::NonEmpty => !response.trim().is_empty(),
+                ScenarioAssertion::MaxLatencyMs { value } => latency_ms <= *value,
+            };
+            (!passed).then(|| describe_failure(assertion, latency_ms))
+        })
+        .collect()
+}
+
+fn describe_failure(assertion: &ScenarioAssertion, latency_ms: u64) -> String {
+    match assertion {
+        ScenarioAssertion::Contains { value } => {
+            format!("Expected response to contain '{}'", value)
+        }
+        ScenarioAssertion::NotContains { value } => {
+            format!("Expected response not to contain '{}'", value)
+        }
+        ScenarioAssertion::Equals { value } => format!("Expected response to equal '{}'", value),
+        ScenarioAssertion::NonEmpty => "Expected a non-empty response".to_string(),
+        ScenarioAssertion::MaxLatencyMs { value } => {
+            format!("Response took {}ms; the limit is {}ms", latency_ms, value)
+        }
+    }
+}
+
+fn validate_result_id(id: &str) -> Result<(), AppError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(AppError::Eval(format!(
+            "Invalid scenario result ID '{}'",
+            id
+        )));
+    }
+    Ok(())
+}
+
+fn generate_result_id() -> String {
+    format!(
+        "scn_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+fn results_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?
+        .join(SCENARIO_RESULTS_DIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn save_result(app: &AppHandle, result: &ScenarioResult) -> Result<(), AppError> {
+    let path = results_dir(app)?.join(format!("{}.json", result.id));
+    fs::write(path, serde_json::to_vec_pretty(result)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREETING_YAML: &str = r#"
+name: Greeting
+steps:
+  - message: Hello
+    expect:
+      - type: contains
+        value: hi
+      - type: nonEmpty
+      - type: maxLatencyMs
+        value: 5000
+  - message: Bye
+"#;
+
+    #[test]
+    fn test_load_yaml_scenario() {
+        let path = std::env::temp_dir().join(format!("scenario-{}.yaml", uuid::Uuid::new_v4()));
+        fs::write(&path, GREETING_YAML).unwrap();
+
+        let scenario = load_scenario(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(scenario.name, "Greeting");
+        assert!(scenario.agent.is_none());
+        assert_eq!(scenario.steps.len(), 2);
+        assert_eq!(
+            scenario.steps[0].expect,
+            vec![
+                ScenarioAssertion::Contains {
+                    value: "hi".to_string()
+                },
+                ScenarioAssertion::NonEmpty,
+                ScenarioAssertion::MaxLatencyMs { value: 5000 },
+            ]
+        );
+        assert!(scenario.steps[1].expect.is_empty());
+    }
+
+    #[test]
+    fn test_load_scenario_rejects_bad_files() {
+        let path = std::env::temp_dir().join(format!("scenario-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, r#"{ "name": "Empty", "steps": [] }"#).unwrap();
+        assert!(load_scenario(&path).is_err());
+        fs::remove_file(&path).unwrap();
+
+        let path = std::env::temp_dir().join(format!("scenario-{}.txt", uuid::Uuid::new_v4()));
+        fs::write(&path, "name: x").unwrap();
+        assert!(load_scenario(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_assertions() {
+        let assertions = vec![
+            ScenarioAssertion::Contains {
+                value: "HELLO".to_string(),
+            },
+            ScenarioAssertion::NotContains {
+                value: "error".to_string(),
+            },
+            ScenarioAssertion::MaxLatencyMs { value: 100 },
+        ];
+        assert!(check_assertions(&assertions, "Hello there", 50).is_empty());
+
+        let failures = check_assertions(&assertions, "An error occurred", 250);
+        assert_eq!(failures.len(), 3);
+        assert!(failures[2].contains("250ms"));
+
+        let equals = [ScenarioAssertion::Equals {
+            value: "pong".to_string(),
+        }];
+        assert!(check_assertions(&equals, " pong\n", 0).is_empty());
+        assert!(!check_assertions(&[ScenarioAssertion::NonEmpty], "  ", 0).is_empty());
+    }
+}
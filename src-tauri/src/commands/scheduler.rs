@@ -0,0 +1,462 @@
+//! Run scheduling
+//! Starts a batch of runs in dependency order, so e.g. a plugin install can
+//! gate an agent start, and skips dependents whose condition is not met.
+//! A dependency outside the batch must name a known run (one in this session or in
+//! the run history); its dependents wait until it finishes
+
+use crate::commands::args::{custom_allowlist, validated_mode_args};
+use crate::commands::config::saved_config;
+use crate::commands::history::load_record;
+use crate::commands::process::{
+    audit_run, describe_run_for_audit, execute_eliza_run_streaming_with_id, get_process_registry,
+};
+use crate::commands::session::journal_schedule;
+use crate::commands::stats::emit_event;
+use crate::models::{
+    ApiResponse, AppError, AuditAction, DependencyCondition, LogEvent, RunSchedule, RunSpec,
+    SandboxConfig, ScheduledRun, ScheduledRunStatus,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{watch, Mutex, RwLock};
+
+/// How often a dependency on a run outside the batch is checked for completion
+const EXTERNAL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Registry of run schedules by schedule ID
+pub type RunScheduleRegistry = Arc<RwLock<HashMap<String, Arc<Mutex<RunSchedule>>>>>;
+
+/// Initialize the run schedule registry (called from main)
+pub fn init_run_schedule_registry() -> RunScheduleRegistry {
+    Arc::new(RwLock::new(HashMap::new()))
+}
+
+/// Get the run schedule registry for the app
+pub fn get_run_schedule_registry(app: &AppHandle) -> RunScheduleRegistry {
+    app.state::<RunScheduleRegistry>().inner().clone()
+}
+
+/// Schedule a batch of runs; runs declaring `after` wait for that spec to finish
+#[tauri::command]
+pub async fn schedule_runs(
+    app: AppHandle,
+    specs: Vec<RunSpec>,
+    config: SandboxConfig,
+) -> Result<ApiResponse<RunSchedule>, String> {
+    log::info!("Scheduling {} runs", specs.len());
+
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    if specs.is_empty() {
+        return Ok(ApiResponse::error(
+            "EMPTY_SCHEDULE".to_string(),
+            "A schedule needs at least one run spec".to_string(),
+        ));
+    }
+
+    let allowed = match saved_config(&app) {
+        Ok(saved) => custom_allowlist(&saved, &config),
+        Err(e) => {
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.to_string(),
+            ))
+        }
+    };
+    for spec in &specs {
+        if let Err(e) = validated_mode_args(spec, &allowed) {
+            return Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                e.to_string(),
+            ));
+        }
+    }
+
+    let batch: HashSet<&str> = specs.iter().map(|spec| spec.id.as_str()).collect();
+    let mut external = HashSet::new();
+    for after in specs.iter().filter_map(|spec| spec.after.as_ref()) {
+        if !batch.contains(after.run_id.as_str())
+            && external_run_status(&app, &after.run_id).await.is_some()
+        {
+            external.insert(after.run_id.clone());
+        }
+    }
+
+    if let Err(e) = dependency_order(&specs, &external) {
+        log::warn!("Rejected run schedule: {}", e);
+        return Ok(ApiResponse::error(
+            "INVALID_SCHEDULE".to_string(),
+            e.to_string(),
+        ));
+    }
+
+    let schedule = RunSchedule {
+        id: generate_schedule_id(),
+        runs: specs
+            .iter()
+            .map(|spec| ScheduledRun {
+                spec_id: spec.id.clone(),
+                run_id: None,
+                after: spec.after.clone(),
+                status: ScheduledRunStatus::Pending,
+                reason: None,
+            })
+            .collect(),
+        started_at: crate::models::current_timestamp(),
+        finished: false,
+    };
+
+    let schedule_id = schedule.id.clone();
+    get_run_schedule_registry(&app)
+        .write()
+        .await
+        .insert(schedule_id.clone(), Arc::new(Mutex::new(schedule.clone())));
+    journal_schedule(&app, &schedule);
+    emit_schedule_status(&app, &schedule);
+
+    // Each spec publishes its final status; dependents wait on it
+    let channels: HashMap<String, watch::Sender<Option<ScheduledRunStatus>>> = specs
+        .iter()
+        .map(|spec| (spec.id.clone(), watch::channel(None).0))
+        .collect();
+    let external: HashMap<String, watch::Receiver<Option<ScheduledRunStatus>>> = external
+        .into_iter()
+        .map(|run_id| (run_id.clone(), watch_external_run(app.clone(), run_id)))
+        .collect();
+
+    for spec in specs {
+        let done = channels[&spec.id].clone();
+        let dependency = spec.after.as_ref().map(|after| {
+            let receiver = match channels.get(&after.run_id) {
+                Some(sender) => sender.subscribe(),
+                None => external[&after.run_id].clone(),
+            };
+            (after.condition, receiver)
+        });
+
+        tokio::spawn(run_scheduled(
+            app.clone(),
+            schedule_id.clone(),
+            spec,
+            config.clone(),
+            dependency,
+            done,
+        ));
+    }
+
+    log::info!("Started run schedule: {}", schedule_id);
+    Ok(ApiResponse::success(schedule))
+}
+
+/// Get the current status of a run schedule
+#[tauri::command]
+pub async fn get_run_schedule(
+    app: AppHandle,
+    schedule_id: String,
+) -> Result<ApiResponse<RunSchedule>, String> {
+    let registry = get_run_schedule_registry(&app);
+    let guard = registry.read().await;
+
+    match guard.get(&schedule_id) {
+        Some(schedule) => Ok(ApiResponse::success(schedule.lock().await.clone())),
+        None => Ok(ApiResponse::error(
+            "NOT_FOUND".to_string(),
+            format!("Run schedule {} not found", schedule_id),
+        )),
+    }
+}
+
+/// Wait for the dependency (if any), then run the spec or skip it
+async fn run_scheduled(
+    app: AppHandle,
+    schedule_id: String,
+    spec: RunSpec,
+    config: SandboxConfig,
+    dependency: Option<(
+        DependencyCondition,
+        watch::Receiver<Option<ScheduledRunStatus>>,
+    )>,
+    done: watch::Sender<Option<ScheduledRunStatus>>,
+) {
+    let spec_id = spec.id.clone();
+
+    if let Some((condition, mut receiver)) = dependency {
+        let dependency_status = match receiver.wait_for(|status| status.is_some()).await {
+            Ok(status) => status.unwrap_or(ScheduledRunStatus::Skipped),
+            Err(_) => ScheduledRunStatus::Skipped,
+        };
+
+        if !condition.is_met(dependency_status) {
+            let reason = format!(
+                "Dependency {} finished as {:?}, required {:?}",
+                spec.after
+                    .as_ref()
+                    .map(|after| after.run_id.as_str())
+                    .unwrap_or_default(),
+                dependency_status,
+                condition
+            );
+            log::info!("Skipping scheduled run {}: {}", spec_id, reason);
+            update_run(
+                &app,
+                &schedule_id,
+                &spec_id,
+                None,
+                ScheduledRunStatus::Skipped,
+                Some(reason),
+            )
+            .await;
+            let _ = done.send(Some(ScheduledRunStatus::Skipped));
+            return;
+        }
+    }
+
+    let run_id = crate::models::generate_safe_run_id();
+    update_run(
+        &app,
+        &schedule_id,
+        &spec_id,
+        Some(run_id.clone()),
+        ScheduledRunStatus::Running,
+        None,
+    )
+    .await;
+
+    let detail = format!(
+        "schedule {}: {}",
+        schedule_id,
+        describe_run_for_audit(&spec)
+    );
+    audit_run(&app, AuditAction::RunStarted, &run_id, true, Some(detail)).await;
+
+    let (status, reason) = match execute_eliza_run_streaming_with_id(
+        app.clone(),
+        spec,
+        config,
+        run_id.clone(),
+    )
+    .await
+    {
+        Ok(run_result) => (ScheduledRunStatus::from(run_result.status), None),
+        Err(e) => {
+            log::error!("Scheduled run {} failed to start: {}", spec_id, e);
+            emit_event(
+                &app,
+                "log-event",
+                LogEvent::error(run_id.clone(), format!("Failed to start run: {}", e)),
+            );
+            (ScheduledRunStatus::Failed, Some(e.to_string()))
+        }
+    };
+
+    update_run(&app, &schedule_id, &spec_id, Some(run_id), status, reason).await;
+    let _ = done.send(Some(status));
+}
+
+/// Record a scheduled run's status and broadcast the schedule
+async fn update_run(
+    app: &AppHandle,
+    schedule_id: &str,
+    spec_id: &str,
+    run_id: Option<String>,
+    status: ScheduledRunStatus,
+    reason: Option<String>,
+) {
+    let schedule_arc = match get_run_schedule_registry(app).read().await.get(schedule_id) {
+        Some(schedule) => schedule.clone(),
+        None => return,
+    };
+
+    let mut schedule = schedule_arc.lock().await;
+    if let Some(run) = schedule.runs.iter_mut().find(|run| run.spec_id == spec_id) {
+        if run_id.is_some() {
+            run.run_id = run_id;
+        }
+        run.status = status;
+        run.reason = reason;
+    }
+    schedule.finished = schedule.runs.iter().all(|run| run.status.is_finished());
+    journal_schedule(app, &schedule);
+    emit_event(app, "run-schedule-status", &*schedule);
+}
+
+fn emit_schedule_status(app: &AppHandle, schedule: &RunSchedule) {
+    emit_event(app, "run-schedule-status", schedule);
+}
+
+/// Status of a run started outside the batch, from the process registry or the run history;
+/// `None` when no such run is known
+async fn external_run_status(app: &AppHandle, run_id: &str) -> Option<ScheduledRunStatus> {
+    if let Some(handle) = get_process_registry(app).read().await.get(run_id) {
+        return Some(handle.lock().await.run_result.status.into());
+    }
+    load_record(app, run_id)
+        .ok()?
+        .result
+        .map(|result| result.status.into())
+}
+
+/// Publish the final status of a run outside the batch once it finishes
+fn watch_external_run(
+    app: AppHandle,
+    run_id: String,
+) -> watch::Receiver<Option<ScheduledRunStatus>> {
+    let (done, receiver) = watch::channel(None);
+    tokio::spawn(async move {
+        loop {
+            match external_run_status(&app, &run_id).await {
+                Some(status) if !status.is_finished() => {}
+                status => {
+                    let _ = done.send(Some(status.unwrap_or(ScheduledRunStatus::Skipped)));
+                    return;
+                }
+            }
+            tokio::time::sleep(EXTERNAL_POLL_INTERVAL).await;
+        }
+    });
+    receiver
+}
+
+/// Order specs so every dependency comes before its dependents; dependencies on the
+/// `external` runs are waited on separately and impose no order
+///
+/// Rejects duplicate IDs, dependencies that are neither in the batch nor external, and cycles.
+pub fn dependency_order(
+    specs: &[RunSpec],
+    external: &HashSet<String>,
+) -> Result<Vec<String>, AppError> {
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut pending: HashMap<&str, usize> = HashMap::new();
+
+    for spec in specs {
+        if pending.insert(spec.id.as_str(), 0).is_some() {
+            return Err(AppError::Process(format!(
+                "Duplicate run spec ID in schedule: {}",
+                spec.id
+            )));
+        }
+    }
+
+    for spec in specs {
+        if let Some(after) = &spec.after {
+            if !pending.contains_key(after.run_id.as_str()) {
+                if external.contains(&after.run_id) {
+                    continue;
+                }
+                return Err(AppError::Process(format!(
+                    "Run {} depends on {}, which is neither part of this schedule nor a known run",
+                    spec.id, after.run_id
+                )));
+            }
+            dependents
+                .entry(after.run_id.as_str())
+                .or_default()
+                .push(spec.id.as_str());
+            *pending.entry(spec.id.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut ready: VecDeque<&str> = specs
+        .iter()
+        .map(|spec| spec.id.as_str())
+        .filter(|id| pending[id] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(specs.len());
+
+    while let Some(id) = ready.pop_front() {
+        order.push(id.to_string());
+        for dependent in dependents.get(id).into_iter().flatten() {
+            let count = pending.entry(dependent).or_default();
+            *count -= 1;
+            if *count == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    }
+
+    if order.len() != specs.len() {
+        return Err(AppError::Process(
+            "Run dependencies form a cycle".to_string(),
+        ));
+    }
+
+    Ok(order)
+}
+
+fn generate_schedule_id() -> String {
+    format!(
+        "schedule_{}_{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u16>()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RunMode;
+
+    fn spec(id: &str) -> RunSpec {
+        RunSpec::new(id.to_string(), RunMode::Run, Vec::new())
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let specs = vec![
+            spec("start").after("install".to_string(), DependencyCondition::Success),
+            spec("install"),
+            spec("doctor"),
+        ];
+
+        let order = dependency_order(&specs, &HashSet::new()).unwrap();
+        let position = |id: &str| order.iter().position(|o| o == id).unwrap();
+        assert_eq!(order.len(), 3);
+        assert!(position("install") < position("start"));
+    }
+
+    #[test]
+    fn test_dependency_order_rejects_invalid() {
+        let cycle = vec![
+            spec("a").after("b".to_string(), DependencyCondition::Success),
+            spec("b").after("a".to_string(), DependencyCondition::Success),
+        ];
+        assert!(dependency_order(&cycle, &HashSet::new()).is_err());
+
+        let missing = vec![spec("a").after("x".to_string(), DependencyCondition::Success)];
+        assert!(dependency_order(&missing, &HashSet::new()).is_err());
+
+        assert!(dependency_order(&[spec("a"), spec("a")], &HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_dependency_order_across_batches() {
+        let specs = vec![
+            spec("start").after("run_earlier".to_string(), DependencyCondition::Success),
+            spec("report").after("start".to_string(), DependencyCondition::Completion),
+        ];
+        assert!(dependency_order(&specs, &HashSet::new()).is_err());
+
+        let external = HashSet::from(["run_earlier".to_string()]);
+        assert_eq!(
+            dependency_order(&specs, &external).unwrap(),
+            vec!["start".to_string(), "report".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_dependency_condition() {
+        assert!(DependencyCondition::Success.is_met(ScheduledRunStatus::Completed));
+        assert!(!DependencyCondition::Success.is_met(ScheduledRunStatus::Failed));
+        assert!(DependencyCondition::Completion.is_met(ScheduledRunStatus::Failed));
+        assert!(!DependencyCondition::Completion.is_met(ScheduledRunStatus::Skipped));
+        assert!(DependencyCondition::Failure.is_met(ScheduledRunStatus::Killed));
+        assert!(!DependencyCondition::Failure.is_met(ScheduledRunStatus::Skipped));
+    }
+}
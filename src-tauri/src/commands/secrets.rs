@@ -0,0 +1,377 @@
+//! Secret synchronization
+//! Keeps agent secrets (model provider keys and the like) in a local store and pushes them
+//! to the Sandbox secrets endpoint so deployed agents have the credentials they need.
+//! Secret values are never logged or returned to the webview.
+
+use crate::commands::app_lock::{update_vault, with_vault};
+use crate::commands::audit::record_audit;
+use crate::commands::cloud::{send_json, unwrap_envelope};
+use crate::commands::sandbox_http::sandbox_http;
+use crate::models::{ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin, SandboxConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const SECRETS_FILE: &str = "secrets.json";
+const MAX_NAME_LEN: usize = 128;
+
+pub(crate) type SecretStore = BTreeMap<String, String>;
+
+/// A secret the cloud project expects, and where it is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStatus {
+    pub name: String,
+    pub required: bool,
+    pub configured_in_cloud: bool,
+    pub available_locally: bool,
+}
+
+/// Entry of the Sandbox secrets listing; names only, never values
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudSecret {
+    name: String,
+    #[serde(default = "default_required")]
+    required: bool,
+    #[serde(default, alias = "set")]
+    configured: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// Store a secret locally, replacing any previous value
+#[tauri::command]
+pub async fn set_local_secret(
+    app: AppHandle,
+    name: String,
+    value: String,
+) -> Result<ApiResponse<()>, String> {
+    let result = (|| {
+        validate_secret_name(&name)?;
+        let mut secrets = read_secrets(&app)?;
+        secrets.insert(name.clone(), value);
+        write_secrets(&app, &secrets)
+    })();
+
+    match result {
+        Ok(()) => {
+            log::info!("Local secret {} saved", name);
+            Ok(ApiResponse::success(()))
+        }
+        Err(e) => Ok(error_response("Failed to save secret", e)),
+    }
+}
+
+/// Names of the locally stored secrets
+#[tauri::command]
+pub async fn list_local_secrets(app: AppHandle) -> Result<ApiResponse<Vec<String>>, String> {
+    match read_secrets(&app) {
+        Ok(secrets) => Ok(ApiResponse::success(secrets.into_keys().collect())),
+        Err(e) => Ok(error_response("Failed to read secrets", e)),
+    }
+}
+
+/// Remove a local secret, returning whether it existed
+#[tauri::command]
+pub async fn delete_local_secret(
+    app: AppHandle,
+    name: String,
+) -> Result<ApiResponse<bool>, String> {
+    let result = (|| {
+        let mut secrets = read_secrets(&app)?;
+        let existed = secrets.remove(&name).is_some();
+        if existed {
+            write_secrets(&app, &secrets)?;
+        }
+        Ok::<_, AppError>(existed)
+    })();
+
+    match result {
+        Ok(existed) => {
+            log::info!("Local secret {} deleted (existed: {})", name, existed);
+            Ok(ApiResponse::success(existed))
+        }
+        Err(e) => Ok(error_response("Failed to delete secret", e)),
+    }
+}
+
+/// List the secrets the cloud project expects, merged with the names stored locally
+#[tauri::command]
+pub async fn list_cloud_secrets(
+    app: AppHandle,
+    config: SandboxConfig,
+) -> Result<ApiResponse<Vec<SecretStatus>>, String> {
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        let local = read_secrets(&app)?;
+        let client = sandbox_http();
+        let body = send_json(client.get(config.api_url("secrets")), &config).await?;
+        Ok::<_, AppError>(merge_statuses(parse_cloud_secrets(body)?, &local))
+    }
+    .await;
+
+    match result {
+        Ok(statuses) => {
+            log::info!("Cloud project expects {} secrets", statuses.len());
+            Ok(ApiResponse::success(statuses))
+        }
+        Err(e) => Ok(error_response("Failed to list cloud secrets", e)),
+    }
+}
+
+/// Push local secrets to the Sandbox, all of them or just `names`; returns the names pushed
+#[tauri::command]
+pub async fn push_secrets_to_cloud(
+    app: AppHandle,
+    config: SandboxConfig,
+    names: Option<Vec<String>>,
+) -> Result<ApiResponse<Vec<String>>, String> {
+    if !config.is_valid() {
+        return Ok(ApiResponse::error(
+            "INVALID_CONFIG".to_string(),
+            "Invalid Sandbox configuration".to_string(),
+        ));
+    }
+
+    let result = async {
+        let selected = select_secrets(read_secrets(&app)?, names.as_deref())?;
+        if selected.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = sandbox_http();
+        let payload = json!({ "secrets": selected });
+        send_json(
+            client.put(config.api_url("secrets")).json(&payload),
+            &config,
+        )
+        .await?;
+        Ok::<_, AppError>(selected.into_keys().collect::<Vec<_>>())
+    }
+    .await;
+
+    let subject = match (&result, &names) {
+        (Ok(pushed), _) => pushed.join(", "),
+        (Err(_), Some(names)) => names.join(", "),
+        (Err(_), None) => "all".to_string(),
+    };
+    let entry = AuditEntry::new(AuditAction::SecretsPushed, AuditOrigin::Gui, subject)
+        .with_outcome(result.is_ok(), result.as_ref().err().map(|e| e.to_string()));
+    record_audit(&app, entry).await;
+
+    match result {
+        Ok(pushed) => {
+            log::info!("Pushed {} secrets to the Sandbox", pushed.len());
+            Ok(ApiResponse::success(pushed))
+        }
+        Err(e) => Ok(error_response("Failed to push secrets", e)),
+    }
+}
+
+fn error_response<T>(context: &str, e: AppError) -> ApiResponse<T> {
+    log::error!("{}: {}", context, e);
+    ApiResponse::error(
+        e.error_code().to_string(),
+        format!("{}: {}", context, e.localized_message()),
+    )
+}
+
+/// Secret names are environment variable names
+fn validate_secret_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid {
+        return Err(AppError::Config(format!(
+            "Invalid secret name '{}': use letters, digits and '_', not starting with a digit",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Pick the secrets to push; every requested name must be stored locally
+fn select_secrets(
+    mut secrets: SecretStore,
+    names: Option<&[String]>,
+) -> Result<SecretStore, AppError> {
+    let Some(names) = names else {
+        return Ok(secrets);
+    };
+
+    let missing: Vec<&str> = names
+        .iter()
+        .filter(|name| !secrets.contains_key(name.as_str()))
+        .map(|name| name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::Config(format!(
+            "Secrets not stored locally: {}",
+            missing.join(", ")
+        )));
+    }
+
+    secrets.retain(|name, _| names.contains(name));
+    Ok(secrets)
+}
+
+/// Listings may be plain names or objects with `name`, `required` and `configured`
+fn parse_cloud_secrets(body: Value) -> Result<Vec<CloudSecret>, AppError> {
+    let entries = match unwrap_envelope(body, "secrets") {
+        Value::Array(entries) => entries,
+        _ => {
+            return Err(AppError::Network(
+                "Unexpected secrets response: expected a list".to_string(),
+            ))
+        }
+    };
+
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            Value::String(name) => Ok(CloudSecret {
+                name,
+                required: true,
+                configured: false,
+            }),
+            other => serde_json::from_value(other)
+                .map_err(|e| AppError::Network(format!("Unexpected secrets response: {}", e))),
+        })
+        .collect()
+}
+
+fn merge_statuses(cloud: Vec<CloudSecret>, local: &SecretStore) -> Vec<SecretStatus> {
+    cloud
+        .into_iter()
+        .map(|secret| SecretStatus {
+            available_locally: local.contains_key(&secret.name),
+            name: secret.name,
+            required: secret.required,
+            configured_in_cloud: secret.configured,
+        })
+        .collect()
+}
+
+fn get_secrets_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(SECRETS_FILE))
+}
+
+/// Stored secrets, from the vault while the app lock is enabled
+pub(crate) fn read_secrets(app: &AppHandle) -> Result<SecretStore, AppError> {
+    match with_vault(app, |vault| vault.secrets.clone())? {
+        Some(secrets) => Ok(secrets),
+        None => read_plaintext_secrets(app),
+    }
+}
+
+fn write_secrets(app: &AppHandle, secrets: &SecretStore) -> Result<(), AppError> {
+    match update_vault(app, |vault| vault.secrets = secrets.clone())? {
+        Some(()) => Ok(()),
+        None => write_plaintext_secrets(app, secrets),
+    }
+}
+
+/// Secrets in the plain secrets file, ignoring the app lock
+pub(crate) fn read_plaintext_secrets(app: &AppHandle) -> Result<SecretStore, AppError> {
+    let path = get_secrets_path(app)?;
+    if !path.exists() {
+        return Ok(SecretStore::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+pub(crate) fn write_plaintext_secrets(
+    app: &AppHandle,
+    secrets: &SecretStore,
+) -> Result<(), AppError> {
+    let path = get_secrets_path(app)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(secrets)?)?;
+
+    // Only the current user may read the values
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+pub(crate) fn remove_plaintext_secrets(app: &AppHandle) -> Result<(), AppError> {
+    let path = get_secrets_path(app)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_secret_name() {
+        assert!(validate_secret_name("OPENAI_API_KEY").is_ok());
+        assert!(validate_secret_name("_PRIVATE").is_ok());
+        assert!(validate_secret_name("1KEY").is_err());
+        assert!(validate_secret_name("BAD-NAME").is_err());
+        assert!(validate_secret_name("").is_err());
+    }
+
+    #[test]
+    fn test_parse_and_merge() {
+        let body = json!({ "secrets": [
+            "OPENAI_API_KEY",
+            { "name": "DISCORD_TOKEN", "required": false, "set": true },
+        ]});
+        let local = SecretStore::from([("OPENAI_API_KEY".to_string(), "sk".to_string())]);
+
+        let statuses = merge_statuses(parse_cloud_secrets(body).unwrap(), &local);
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses[0].required && statuses[0].available_locally);
+        assert!(!statuses[0].configured_in_cloud);
+        assert!(!statuses[1].required && statuses[1].configured_in_cloud);
+        assert!(!statuses[1].available_locally);
+
+        assert!(parse_cloud_secrets(json!({ "secrets": 3 })).is_err());
+    }
+
+    #[test]
+    fn test_select_secrets() {
+        let secrets = SecretStore::from([
+            ("A".to_string(), "1".to_string()),
+            ("B".to_string(), "2".to_string()),
+        ]);
+
+        assert_eq!(select_secrets(secrets.clone(), None).unwrap().len(), 2);
+        let selected = select_secrets(secrets.clone(), Some(&["B".to_string()])).unwrap();
+        assert_eq!(selected.keys().collect::<Vec<_>>(), ["B"]);
+
+        let err = select_secrets(secrets, Some(&["C".to_string()])).unwrap_err();
+        assert!(err.to_string().contains("C"));
+    }
+}
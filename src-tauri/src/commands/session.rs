@@ -0,0 +1,355 @@
+//! Session journal and startup report
+//! Runs and schedules in flight are journaled to `session.json` as they start and finish;
+//! whatever is still journaled at the next launch was cut off by the shutdown, and is
+//! summarized with any config migrations in a report emitted as `startup-report`
+
+use crate::commands::config::{
+    migrate_api_keys_to_keyring, migrate_config_file, migrate_plaintext_config,
+};
+use crate::commands::stats::emit_event;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, InterruptedRun, MissedSchedule, RunMode, RunSchedule,
+    ScheduledRunStatus, StartupReport,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+const SESSION_FILE: &str = "session.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournaledRun {
+    run_id: String,
+    mode: RunMode,
+    pid: Option<u32>,
+    started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JournaledSchedule {
+    schedule_id: String,
+    started_at: String,
+    /// Spec IDs that have not started yet
+    pending: Vec<String>,
+}
+
+/// What is in flight in the current session
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionJournal {
+    session_started_at: Option<String>,
+    #[serde(default)]
+    runs: Vec<JournaledRun>,
+    #[serde(default)]
+    schedules: Vec<JournaledSchedule>,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionState {
+    report: Option<StartupReport>,
+}
+
+// Also serializes journal updates
+pub type SessionStateLock = Arc<Mutex<SessionState>>;
+
+/// Initialize the session state (called from main)
+pub fn init_session_state() -> SessionStateLock {
+    Arc::new(Mutex::new(SessionState::default()))
+}
+
+/// What happened since the last session, as found at launch
+#[tauri::command]
+pub async fn get_startup_report(app: AppHandle) -> Result<ApiResponse<StartupReport>, String> {
+    let state = app.state::<SessionStateLock>();
+    let report = lock(&state).report.clone();
+    Ok(ApiResponse::success(report.unwrap_or_default()))
+}
+
+/// Build the startup report from the previous session's journal and start a new journal
+///
+/// Runs before anything can start a run, so the journal only holds the previous session.
+pub fn recover_session(app: &AppHandle) {
+    let report = match build_startup_report(app) {
+        Ok(report) => report,
+        Err(e) => {
+            log::warn!("Failed to build startup report: {}", e);
+            return;
+        }
+    };
+
+    if !report.interrupted_runs.is_empty() || !report.missed_schedules.is_empty() {
+        log::warn!(
+            "Previous session ended with {} run(s) active and {} schedule(s) pending",
+            report.interrupted_runs.len(),
+            report.missed_schedules.len()
+        );
+    }
+    lock(&app.state::<SessionStateLock>()).report = Some(report.clone());
+    emit_event(app, "startup-report", report);
+}
+
+fn build_startup_report(app: &AppHandle) -> Result<StartupReport, AppError> {
+    let previous = read_journal(app)?;
+    write_journal(
+        app,
+        &SessionJournal {
+            session_started_at: Some(current_timestamp()),
+            ..SessionJournal::default()
+        },
+    )?;
+
+    let mut config_migrations = migrate_config_file(app).unwrap_or_else(|e| {
+        log::warn!("Failed to migrate config file: {}", e);
+        Vec::new()
+    });
+    config_migrations.extend(migrate_api_keys_to_keyring(app).unwrap_or_else(|e| {
+        log::warn!("Failed to move API keys to the OS keyring: {}", e);
+        Vec::new()
+    }));
+    // Whatever could not move to the keyring is encrypted in place
+    config_migrations.extend(migrate_plaintext_config(app).unwrap_or_else(|e| {
+        log::warn!("Failed to encrypt plaintext config files: {}", e);
+        Vec::new()
+    }));
+
+    Ok(startup_report(
+        previous,
+        config_migrations,
+        is_process_running,
+    ))
+}
+
+fn startup_report(
+    previous: SessionJournal,
+    config_migrations: Vec<String>,
+    is_running: impl Fn(u32) -> bool,
+) -> StartupReport {
+    StartupReport {
+        previous_session_started_at: previous.session_started_at,
+        interrupted_runs: previous
+            .runs
+            .into_iter()
+            .map(|run| InterruptedRun {
+                orphaned: run.pid.is_some_and(&is_running),
+                run_id: run.run_id,
+                mode: run.mode,
+                pid: run.pid,
+                started_at: run.started_at,
+            })
+            .collect(),
+        missed_schedules: previous
+            .schedules
+            .into_iter()
+            .filter(|schedule| !schedule.pending.is_empty())
+            .map(|schedule| MissedSchedule {
+                schedule_id: schedule.schedule_id,
+                started_at: schedule.started_at,
+                pending_runs: schedule.pending,
+            })
+            .collect(),
+        config_migrations,
+        generated_at: current_timestamp(),
+    }
+}
+
+/// Journal entry of a running run; removed from the journal when dropped
+pub struct JournaledRunGuard {
+    app: AppHandle,
+    run_id: String,
+}
+
+impl JournaledRunGuard {
+    pub fn start(app: &AppHandle, run_id: &str, mode: RunMode, pid: Option<u32>) -> Self {
+        let run = JournaledRun {
+            run_id: run_id.to_string(),
+            mode,
+            pid,
+            started_at: current_timestamp(),
+        };
+        update_journal(app, |journal| {
+            journal.runs.retain(|r| r.run_id != run.run_id);
+            journal.runs.push(run);
+        });
+        Self {
+            app: app.clone(),
+            run_id: run_id.to_string(),
+        }
+    }
+}
+
+impl Drop for JournaledRunGuard {
+    fn drop(&mut self) {
+        let run_id = &self.run_id;
+        update_journal(&self.app, |journal| {
+            journal.runs.retain(|r| &r.run_id != run_id)
+        });
+    }
+}
+
+/// Journal the runs of a schedule that have not started; finished schedules are dropped
+pub fn journal_schedule(app: &AppHandle, schedule: &RunSchedule) {
+    let pending: Vec<String> = schedule
+        .runs
+        .iter()
+        .filter(|run| run.status == ScheduledRunStatus::Pending)
+        .map(|run| run.spec_id.clone())
+        .collect();
+
+    update_journal(app, |journal| {
+        journal.schedules.retain(|s| s.schedule_id != schedule.id);
+        if !schedule.finished && !pending.is_empty() {
+            journal.schedules.push(JournaledSchedule {
+                schedule_id: schedule.id.clone(),
+                started_at: schedule.started_at.clone(),
+                pending,
+            });
+        }
+    });
+}
+
+/// Apply a change to the journal; failures are logged and never fail the caller
+fn update_journal(app: &AppHandle, update: impl FnOnce(&mut SessionJournal)) {
+    let state = app.state::<SessionStateLock>();
+    let _guard = lock(&state);
+
+    let result = read_journal(app).and_then(|mut journal| {
+        update(&mut journal);
+        write_journal(app, &journal)
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to update session journal: {}", e);
+    }
+}
+
+fn lock(state: &Mutex<SessionState>) -> std::sync::MutexGuard<'_, SessionState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn journal_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(SESSION_FILE))
+}
+
+fn read_journal(app: &AppHandle) -> Result<SessionJournal, AppError> {
+    let path = journal_path(app)?;
+    if !path.exists() {
+        return Ok(SessionJournal::default());
+    }
+    Ok(
+        serde_json::from_str(&fs::read_to_string(path)?).unwrap_or_else(|e| {
+            log::warn!("Discarding unreadable session journal: {}", e);
+            SessionJournal::default()
+        }),
+    )
+}
+
+fn write_journal(app: &AppHandle, journal: &SessionJournal) -> Result<(), AppError> {
+    let path = journal_path(app)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(journal)?)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
+
+/// Whether a process with this PID still exists
+#[cfg(unix)]
+fn is_process_running(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    // Signal 0 only checks the process exists; EPERM means it does but isn't ours
+    matches!(
+        kill(Pid::from_raw(pid as i32), None),
+        Ok(()) | Err(Errno::EPERM)
+    )
+}
+
+#[cfg(windows)]
+fn is_process_running(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: the handle is checked before use and closed before returning
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut code = 0u32;
+        let running = GetExitCodeProcess(handle, &mut code) != 0 && code == STILL_ACTIVE as u32;
+        CloseHandle(handle);
+        running
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_process_running(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_report_from_journal() {
+        let journal = SessionJournal {
+            session_started_at: Some("2026-01-01T00:00:00Z".to_string()),
+            runs: vec![
+                JournaledRun {
+                    run_id: "run_agent".to_string(),
+                    mode: RunMode::Run,
+                    pid: Some(4242),
+                    started_at: "2026-01-01T00:01:00Z".to_string(),
+                },
+                JournaledRun {
+                    run_id: "run_eval".to_string(),
+                    mode: RunMode::Eval,
+                    pid: Some(4343),
+                    started_at: "2026-01-01T00:02:00Z".to_string(),
+                },
+            ],
+            schedules: vec![
+                JournaledSchedule {
+                    schedule_id: "schedule_1".to_string(),
+                    started_at: "2026-01-01T00:00:30Z".to_string(),
+                    pending: vec!["spec_b".to_string()],
+                },
+                JournaledSchedule {
+                    schedule_id: "schedule_2".to_string(),
+                    started_at: "2026-01-01T00:00:40Z".to_string(),
+                    pending: Vec::new(),
+                },
+            ],
+        };
+
+        let report = startup_report(journal, vec!["migrated".to_string()], |pid| pid == 4242);
+        assert_eq!(
+            report.previous_session_started_at.as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(report.interrupted_runs.len(), 2);
+        assert!(report.interrupted_runs[0].orphaned);
+        assert!(!report.interrupted_runs[1].orphaned);
+        assert_eq!(report.missed_schedules.len(), 1);
+        assert_eq!(report.missed_schedules[0].pending_runs, vec!["spec_b"]);
+        assert_eq!(report.config_migrations, vec!["migrated"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_process_running() {
+        assert!(is_process_running(std::process::id()));
+    }
+}
@@ -0,0 +1,266 @@
+//! Agent shortcuts
+//! OS shortcuts (`.desktop` entries, `.lnk` files, `.command` scripts) that launch the app
+//! with `--run-agent <id>`, which starts the saved agent as soon as preflight checks pass
+
+use crate::commands::agents::find_agent;
+use crate::models::{AgentProfile, AgentShortcut, ApiResponse, AppError, ShortcutLocation};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Command-line flag that starts a saved agent on launch
+pub const RUN_AGENT_ARG: &str = "run-agent";
+const MAX_FILE_NAME_LEN: usize = 64;
+
+/// Create a shortcut that opens the app and starts a saved agent
+///
+/// An existing shortcut for an agent with the same name is replaced.
+#[tauri::command]
+pub async fn create_agent_shortcut(
+    app: AppHandle,
+    agent_id: String,
+    location: ShortcutLocation,
+) -> Result<ApiResponse<AgentShortcut>, String> {
+    let result = async {
+        let agent = find_agent(&app, &agent_id).await?;
+        validate_shortcut_id(&agent.id)?;
+
+        let exe = std::env::current_exe()?;
+        let dir = shortcut_dir(location)?;
+        fs::create_dir_all(&dir)?;
+        let path = write_shortcut(&dir, &exe, &agent).await?;
+
+        Ok::<_, AppError>(AgentShortcut {
+            agent_id: agent.id.clone(),
+            location,
+            path: path.to_string_lossy().to_string(),
+            launch_args: launch_args(&agent.id),
+        })
+    }
+    .await;
+
+    match result {
+        Ok(shortcut) => {
+            log::info!(
+                "Created shortcut for agent {}: {}",
+                shortcut.agent_id,
+                shortcut.path
+            );
+            Ok(ApiResponse::success(shortcut))
+        }
+        Err(e) => {
+            log::error!("Failed to create agent shortcut: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to create agent shortcut: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+fn launch_args(agent_id: &str) -> Vec<String> {
+    vec![format!("--{}", RUN_AGENT_ARG), agent_id.to_string()]
+}
+
+/// Shortcuts pass the ID through shells and launchers unquoted
+fn validate_shortcut_id(agent_id: &str) -> Result<(), AppError> {
+    if agent_id.is_empty()
+        || !agent_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(AppError::Config(format!(
+            "Agent ID '{}' can't be used in a shortcut; use letters, digits, '-' and '_'",
+            agent_id
+        )));
+    }
+    Ok(())
+}
+
+/// Shortcut file name (without extension) from the agent name
+fn shortcut_file_stem(agent: &AgentProfile) -> String {
+    let stem: String = agent
+        .name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .take(MAX_FILE_NAME_LEN)
+        .collect();
+    match stem.trim() {
+        "" => agent.id.clone(),
+        stem => stem.to_string(),
+    }
+}
+
+fn shortcut_dir(location: ShortcutLocation) -> Result<PathBuf, AppError> {
+    let dir = match location {
+        ShortcutLocation::Desktop => dirs::desktop_dir(),
+        ShortcutLocation::StartMenu => start_menu_dir(),
+    };
+    dir.ok_or_else(|| {
+        AppError::EnvironmentError(format!("No {:?} directory on this system", location))
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn start_menu_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Applications"))
+}
+
+#[cfg(windows)]
+fn start_menu_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(r"Microsoft\Windows\Start Menu\Programs"))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn start_menu_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("applications"))
+}
+
+#[cfg(target_os = "macos")]
+async fn write_shortcut(dir: &Path, exe: &Path, agent: &AgentProfile) -> Result<PathBuf, AppError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Detached so the Terminal window opening the script can close
+    let script = format!(
+        "#!/bin/sh\nnohup {} {} >/dev/null 2>&1 &\n",
+        shell_quote(&exe.to_string_lossy()),
+        launch_args(&agent.id).join(" ")
+    );
+    let path = dir.join(format!("{}.command", shortcut_file_stem(agent)));
+    fs::write(&path, script)?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(windows)]
+async fn write_shortcut(dir: &Path, exe: &Path, agent: &AgentProfile) -> Result<PathBuf, AppError> {
+    use tokio::process::Command;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let path = dir.join(format!("{}.lnk", shortcut_file_stem(agent)));
+    let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+    let script = format!(
+        "$s = (New-Object -ComObject WScript.Shell).CreateShortcut({}); \
+         $s.TargetPath = {}; $s.Arguments = {}; $s.Description = {}; $s.Save()",
+        quote(&path.to_string_lossy()),
+        quote(&exe.to_string_lossy()),
+        quote(&launch_args(&agent.id).join(" ")),
+        quote(&format!("Start the {} agent", agent.name)),
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(AppError::Process(format!(
+            "Failed to create shortcut: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(path)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+async fn write_shortcut(dir: &Path, exe: &Path, agent: &AgentProfile) -> Result<PathBuf, AppError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(format!("{}.desktop", shortcut_file_stem(agent)));
+    fs::write(&path, desktop_entry(exe, agent))?;
+    // Desktop environments only launch executable entries placed on the desktop
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    Ok(path)
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn write_shortcut(
+    _dir: &Path,
+    _exe: &Path,
+    _agent: &AgentProfile,
+) -> Result<PathBuf, AppError> {
+    Err(AppError::EnvironmentError(
+        "Shortcuts are not supported on this platform".to_string(),
+    ))
+}
+
+/// Freedesktop desktop entry launching the app with the agent's launch arguments
+#[cfg(all(unix, not(target_os = "macos")))]
+fn desktop_entry(exe: &Path, agent: &AgentProfile) -> String {
+    // Quoted per the Exec key rules, then escaped again as a string value
+    let exe = exe
+        .to_string_lossy()
+        .chars()
+        .flat_map(|c| match c {
+            '"' | '`' | '$' | '\\' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect::<String>()
+        .replace('\\', r"\\");
+    let name = agent.name.replace(['\n', '\r'], " ");
+
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Version=1.0\n\
+         Name={}\n\
+         Comment=Start the {} agent in ElizaOS Desktop\n\
+         Exec=\"{}\" {}\n\
+         Terminal=false\n\
+         Categories=Development;\n",
+        name,
+        name,
+        exe,
+        launch_args(&agent.id).join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RunMode, RunSpec};
+
+    fn agent(id: &str, name: &str) -> AgentProfile {
+        AgentProfile {
+            id: id.to_string(),
+            name: name.to_string(),
+            spec: RunSpec::new("spec_1".to_string(), RunMode::Run, vec![]),
+            start_on_launch: false,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_shortcut_id() {
+        assert!(validate_shortcut_id("agent_1700000000000_42").is_ok());
+        assert!(validate_shortcut_id("").is_err());
+        assert!(validate_shortcut_id("agent 1").is_err());
+        assert!(validate_shortcut_id("agent;rm").is_err());
+    }
+
+    #[test]
+    fn test_shortcut_file_stem() {
+        assert_eq!(
+            shortcut_file_stem(&agent("agent_1", "Ada / Eval")),
+            "Ada  Eval"
+        );
+        assert_eq!(shortcut_file_stem(&agent("agent_1", "../..")), "agent_1");
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(
+            Path::new("/opt/Eliza OS/eliza$desktop"),
+            &agent("agent_1", "Ada"),
+        );
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Name=Ada\n"));
+        assert!(entry.contains("Exec=\"/opt/Eliza OS/eliza\\\\$desktop\" --run-agent agent_1\n"));
+    }
+}
@@ -0,0 +1,295 @@
+//! Simulated runs
+//! Plays back realistic agent output through the normal log-event pipeline without
+//! spawning node or the ElizaOS CLI, so the app can be demoed on machines without a toolchain
+
+use crate::commands::process::{get_process_registry, ProcessHandle};
+use crate::commands::stats::emit_event;
+use crate::models::{LogEvent, RunMode, RunResult, RunSpec, RunStatus};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+const DEFAULT_CHARACTER: &str = "Eliza";
+const DEFAULT_PORT: u16 = 3000;
+const SIMULATED_MESSAGES: usize = 8;
+
+/// One line of simulated output and the pause before it
+#[derive(Debug, Clone, PartialEq)]
+struct SimulatedLine {
+    stderr: bool,
+    text: String,
+    delay_ms: u64,
+}
+
+impl SimulatedLine {
+    fn out(delay_ms: u64, text: impl Into<String>) -> Self {
+        Self {
+            stderr: false,
+            text: text.into(),
+            delay_ms,
+        }
+    }
+
+    fn err(delay_ms: u64, text: impl Into<String>) -> Self {
+        Self {
+            stderr: true,
+            text: text.into(),
+            delay_ms,
+        }
+    }
+}
+
+/// Play back a simulated run, registering it so it can be stopped like a real one
+pub async fn execute_simulated_run(app: &AppHandle, mut run_result: RunResult) -> RunResult {
+    let run_id = run_result.id.clone();
+    let start_time = Instant::now();
+    run_result.status = RunStatus::Running;
+
+    let registry = get_process_registry(app);
+    let handle = Arc::new(Mutex::new(ProcessHandle::new(run_result.clone())));
+    registry
+        .write()
+        .await
+        .insert(run_id.clone(), handle.clone());
+
+    log::info!(
+        "Starting simulated {} run: {}",
+        run_result.spec.mode,
+        run_id
+    );
+    emit_event(
+        app,
+        "log-event",
+        LogEvent::system(
+            run_id.clone(),
+            "Simulation mode: no CLI process is spawned".to_string(),
+        ),
+    );
+
+    let (script, exit_code) = simulated_script(&run_result.spec);
+    let mut stopped = false;
+
+    for line in script {
+        tokio::time::sleep(jittered(line.delay_ms)).await;
+        if !handle.lock().await.can_control {
+            stopped = true;
+            break;
+        }
+
+        let event = if line.stderr {
+            run_result.stderr.push(line.text.clone());
+            LogEvent::stderr(run_id.clone(), line.text)
+        } else {
+            run_result.stdout.push(line.text.clone());
+            LogEvent::stdout(run_id.clone(), line.text)
+        };
+        emit_event(app, "log-event", event);
+    }
+
+    {
+        let mut handle = handle.lock().await;
+        if stopped || !handle.can_control {
+            run_result.status = handle.run_result.status;
+            run_result.ended_at = handle.run_result.ended_at.clone();
+        } else {
+            run_result.status = if exit_code == 0 {
+                RunStatus::Completed
+            } else {
+                RunStatus::Failed
+            };
+            run_result.exit_code = Some(exit_code);
+            run_result.ended_at = Some(crate::models::current_timestamp());
+        }
+        run_result.duration_ms = Some(start_time.elapsed().as_millis() as u64);
+        handle.update_result(run_result.clone());
+        handle.mark_completed();
+    }
+
+    // Clean up from the registry after a short delay, like real runs
+    let cleanup_run_id = run_id.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        registry.write().await.remove(&cleanup_run_id);
+    });
+
+    let status_msg = match run_result.status {
+        RunStatus::Killed => "Simulated run stopped".to_string(),
+        _ => format!(
+            "Simulated run finished (exit code: {:?})",
+            run_result.exit_code
+        ),
+    };
+    emit_event(app, "log-event", LogEvent::system(run_id, status_msg));
+
+    run_result
+}
+
+/// Vary delays by up to half so the output doesn't look mechanical
+fn jittered(delay_ms: u64) -> Duration {
+    Duration::from_millis(delay_ms + rand::random::<u64>() % (delay_ms / 2 + 1))
+}
+
+/// Output and exit code to play back for a run spec
+fn simulated_script(spec: &RunSpec) -> (Vec<SimulatedLine>, i32) {
+    match spec.mode {
+        RunMode::Run => (agent_script(spec), 0),
+        RunMode::Eval => (eval_script(spec), 0),
+        RunMode::Doctor => (doctor_script(), 0),
+        RunMode::Custom => (custom_script(spec), 0),
+    }
+}
+
+fn character_name(spec: &RunSpec) -> String {
+    spec.character_file
+        .as_deref()
+        .or_else(|| {
+            spec.args
+                .first()
+                .filter(|arg| !arg.starts_with('-'))
+                .map(|s| s.as_str())
+        })
+        .and_then(|file| Path::new(file).file_stem())
+        .and_then(|stem| stem.to_str())
+        .map(|stem| {
+            let mut chars = stem.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_else(|| DEFAULT_CHARACTER.to_string())
+}
+
+fn agent_script(spec: &RunSpec) -> Vec<SimulatedLine> {
+    let name = character_name(spec);
+    let port = spec.port.unwrap_or(DEFAULT_PORT);
+    let prompts = [
+        ("What can you help me with?", 212, 1.4),
+        ("Summarize today's news", 486, 2.9),
+        ("Tell me a joke", 98, 0.8),
+        ("Schedule a reminder for 5pm", 154, 1.1),
+    ];
+
+    let mut lines = vec![
+        SimulatedLine::out(300, "Info  Starting ElizaOS agent runtime"),
+        SimulatedLine::out(400, format!("Info  Loading character: {}", name)),
+        SimulatedLine::out(350, "Info  Initializing database adapter (pglite)"),
+        SimulatedLine::out(250, "Info  Plugin loaded: @elizaos/plugin-sql"),
+        SimulatedLine::out(200, "Info  Plugin loaded: @elizaos/plugin-bootstrap"),
+        SimulatedLine::err(
+            150,
+            "Warn  No local model configured; using Sandbox provider",
+        ),
+        SimulatedLine::out(300, format!("Info  Agent {} registered", name)),
+        SimulatedLine::out(
+            250,
+            format!("Info  Server listening on http://localhost:{}", port),
+        ),
+    ];
+
+    for i in 0..SIMULATED_MESSAGES {
+        let (prompt, tokens, seconds) = prompts[i % prompts.len()];
+        lines.push(SimulatedLine::out(
+            1200,
+            format!("Info  [{}] Received message: \"{}\"", name, prompt),
+        ));
+        lines.push(SimulatedLine::out(
+            (seconds * 1000.0) as u64,
+            format!(
+                "Info  [{}] Responded ({} tokens, {:.1}s)",
+                name, tokens, seconds
+            ),
+        ));
+    }
+
+    lines.push(SimulatedLine::out(800, "Info  Shutting down agent runtime"));
+    lines
+}
+
+fn eval_script(spec: &RunSpec) -> Vec<SimulatedLine> {
+    let scenario = spec
+        .eval
+        .as_ref()
+        .map(|eval| eval.scenario_path.clone())
+        .unwrap_or_else(|| "scenarios/demo.yaml".to_string());
+    let cases = [
+        "greets the user by name",
+        "answers a factual question",
+        "refuses an unsafe request",
+        "keeps conversation context",
+    ];
+
+    let mut lines = vec![SimulatedLine::out(
+        300,
+        format!("Running scenario: {}", scenario),
+    )];
+    for (i, case) in cases.iter().enumerate() {
+        lines.push(SimulatedLine::out(
+            700,
+            format!("  ✓ [{}/{}] {}", i + 1, cases.len(), case),
+        ));
+    }
+    lines.push(SimulatedLine::out(
+        300,
+        format!("Results: {}/{} passed", cases.len(), cases.len()),
+    ));
+    lines
+}
+
+fn doctor_script() -> Vec<SimulatedLine> {
+    vec![
+        SimulatedLine::out(300, "✓ ElizaOS CLI: 1.0.0 (simulated)"),
+        SimulatedLine::out(300, "✓ Environment: base URL and API key look valid"),
+        SimulatedLine::out(500, "✓ Connection: Sandbox reachable (42ms)"),
+        SimulatedLine::out(700, "✓ Prompt round-trip: OK"),
+    ]
+}
+
+fn custom_script(spec: &RunSpec) -> Vec<SimulatedLine> {
+    vec![
+        SimulatedLine::out(300, format!("$ elizaos {}", spec.args.join(" "))),
+        SimulatedLine::out(500, "Done."),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_name() {
+        let spec = RunSpec::new("a".to_string(), RunMode::Run, vec![]);
+        assert_eq!(character_name(&spec), "Eliza");
+
+        let spec = RunSpec::new(
+            "b".to_string(),
+            RunMode::Run,
+            vec!["characters/ada.json".to_string()],
+        );
+        assert_eq!(character_name(&spec), "Ada");
+    }
+
+    #[test]
+    fn test_agent_script_uses_port() {
+        let mut spec = RunSpec::new("a".to_string(), RunMode::Run, vec![]).simulated();
+        spec.port = Some(3005);
+
+        let (lines, exit_code) = simulated_script(&spec);
+        assert_eq!(exit_code, 0);
+        assert!(lines
+            .iter()
+            .any(|line| line.text.contains("http://localhost:3005")));
+        assert!(lines.iter().any(|line| line.stderr));
+    }
+
+    #[test]
+    fn test_jittered_bounds() {
+        for _ in 0..20 {
+            let delay = jittered(100);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(150));
+        }
+    }
+}
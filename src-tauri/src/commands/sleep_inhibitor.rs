@@ -0,0 +1,203 @@
+//! Sleep prevention during runs
+//! With `preventSleep` set, agent and eval runs hold a platform sleep inhibitor
+//! (`caffeinate`, `systemd-inhibit` or `SetThreadExecutionState`) that is released when
+//! the last of them finishes
+
+use std::collections::HashSet;
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+/// An acquired platform inhibitor; dropping it lets the system sleep again
+enum PlatformInhibitor {
+    /// Helper process that blocks sleep for as long as it runs
+    #[cfg_attr(windows, allow(dead_code))]
+    Process(Child),
+    /// Thread holding `ES_SYSTEM_REQUIRED` until the sender is dropped
+    #[cfg(windows)]
+    Thread(std::sync::mpsc::Sender<()>),
+}
+
+impl Drop for PlatformInhibitor {
+    fn drop(&mut self) {
+        match self {
+            PlatformInhibitor::Process(child) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            #[cfg(windows)]
+            PlatformInhibitor::Thread(_) => {}
+        }
+    }
+}
+
+/// Runs currently holding the inhibitor
+#[derive(Default)]
+pub struct SleepInhibitor {
+    holders: HashSet<String>,
+    inhibitor: Option<PlatformInhibitor>,
+}
+
+impl SleepInhibitor {
+    fn acquire<F>(&mut self, run_id: &str, start: F)
+    where
+        F: FnOnce() -> Option<PlatformInhibitor>,
+    {
+        self.holders.insert(run_id.to_string());
+        if self.inhibitor.is_none() {
+            self.inhibitor = start();
+        }
+    }
+
+    fn release(&mut self, run_id: &str) {
+        self.holders.remove(run_id);
+        if self.holders.is_empty() && self.inhibitor.take().is_some() {
+            log::info!("Sleep inhibitor released");
+        }
+    }
+}
+
+pub type SleepInhibitorState = Arc<Mutex<SleepInhibitor>>;
+
+/// Initialize the sleep inhibitor state (called from main)
+pub fn init_sleep_inhibitor() -> SleepInhibitorState {
+    Arc::new(Mutex::new(SleepInhibitor::default()))
+}
+
+/// Keeps the system awake for one run; released when dropped
+pub struct SleepGuard {
+    state: SleepInhibitorState,
+    run_id: String,
+}
+
+impl SleepGuard {
+    pub fn acquire(app: &AppHandle, run_id: &str) -> Self {
+        let state = app.state::<SleepInhibitorState>().inner().clone();
+        lock(&state).acquire(run_id, start_inhibitor);
+        Self {
+            state,
+            run_id: run_id.to_string(),
+        }
+    }
+}
+
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        lock(&self.state).release(&self.run_id);
+    }
+}
+
+fn lock(state: &Mutex<SleepInhibitor>) -> std::sync::MutexGuard<'_, SleepInhibitor> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start the platform inhibitor; failures are logged and the run goes on without one
+fn start_inhibitor() -> Option<PlatformInhibitor> {
+    match platform_inhibitor() {
+        Ok(inhibitor) => {
+            log::info!("Sleep inhibitor acquired");
+            Some(inhibitor)
+        }
+        Err(e) => {
+            log::warn!("Failed to prevent system sleep: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_inhibitor() -> std::io::Result<PlatformInhibitor> {
+    use std::process::{Command, Stdio};
+
+    // -w ends the assertion if the app exits without releasing it
+    let child = Command::new("caffeinate")
+        .args(["-i", "-w", &std::process::id().to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(PlatformInhibitor::Process(child))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_inhibitor() -> std::io::Result<PlatformInhibitor> {
+    use std::process::{Command, Stdio};
+
+    // The lock is held by `tail`, which also exits if the app dies without releasing it
+    let child = Command::new("systemd-inhibit")
+        .args([
+            "--what=sleep:idle",
+            "--who=ElizaOS Desktop",
+            "--why=An ElizaOS agent or eval run is active",
+            "--mode=block",
+            "tail",
+            "--pid",
+            &std::process::id().to_string(),
+            "-f",
+            "/dev/null",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(PlatformInhibitor::Process(child))
+}
+
+#[cfg(windows)]
+fn platform_inhibitor() -> std::io::Result<PlatformInhibitor> {
+    use windows_sys::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED,
+    };
+
+    // The execution state belongs to the calling thread, so one thread holds it throughout
+    let (sender, receiver) = std::sync::mpsc::channel::<()>();
+    std::thread::Builder::new()
+        .name("sleep-inhibitor".to_string())
+        .spawn(move || {
+            // SAFETY: plain Win32 calls that only change this thread's execution state
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+            let _ = receiver.recv();
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        })?;
+    Ok(PlatformInhibitor::Thread(sender))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_inhibitor() -> std::io::Result<PlatformInhibitor> {
+    Err(std::io::Error::other(
+        "Sleep prevention is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inhibitor_held_until_last_run_finishes() {
+        let mut inhibitor = SleepInhibitor::default();
+        let mut starts = 0;
+
+        inhibitor.acquire("run_a", || {
+            starts += 1;
+            Some(PlatformInhibitor::Process(
+                std::process::Command::new("sleep")
+                    .arg("30")
+                    .spawn()
+                    .unwrap(),
+            ))
+        });
+        inhibitor.acquire("run_b", || {
+            starts += 1;
+            None
+        });
+        assert_eq!(starts, 1);
+
+        inhibitor.release("run_a");
+        assert!(inhibitor.inhibitor.is_some());
+        inhibitor.release("run_b");
+        assert!(inhibitor.inhibitor.is_none());
+        assert!(inhibitor.holders.is_empty());
+    }
+}
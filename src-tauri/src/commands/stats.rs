@@ -0,0 +1,265 @@
+//! Backend stats
+//! Reports registry sizes, in-flight telemetry, log files, event emit rates and tokio
+//! task counts so leaks in long-running sessions are observable from the UI
+
+use crate::commands::approvals::get_approval_registry;
+use crate::commands::groups::get_run_group_registry;
+use crate::commands::ports::get_port_registry;
+use crate::commands::process::get_process_registry;
+use crate::commands::scheduler::get_run_schedule_registry;
+use crate::commands::terminal::TerminalRegistry;
+use crate::logging::{LoggingState, LOG_FILE_PREFIX};
+use crate::models::{ApiResponse, RunStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySizes {
+    pub processes: usize,
+    pub finished_processes: usize,
+    pub terminal_processes: usize,
+    pub run_groups: usize,
+    pub run_schedules: usize,
+    pub reserved_ports: usize,
+    pub pending_approvals: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventEmitStats {
+    pub event: String,
+    pub count: u64,
+    pub per_second: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStats {
+    pub workers: usize,
+    pub alive_tasks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendStats {
+    pub uptime_secs: u64,
+    pub registries: RegistrySizes,
+    pub telemetry_in_flight: usize,
+    pub log_files: usize,
+    pub log_bytes: u64,
+    pub events: Vec<EventEmitStats>,
+    pub runtime: Option<RuntimeStats>,
+}
+
+/// Counters updated from hot paths; read by `get_backend_stats`
+pub struct BackendCounters {
+    started: Instant,
+    events: Mutex<HashMap<String, u64>>,
+    telemetry_in_flight: AtomicUsize,
+}
+
+impl BackendCounters {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            events: Mutex::new(HashMap::new()),
+            telemetry_in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    fn record_event(&self, event: &str) {
+        if let Ok(mut events) = self.events.lock() {
+            *events.entry(event.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// Per-event totals and average rate since startup, busiest first
+    fn event_stats(&self) -> Vec<EventEmitStats> {
+        let elapsed = self.started.elapsed().as_secs_f64().max(1.0);
+        let mut stats: Vec<_> = self
+            .events
+            .lock()
+            .map(|events| {
+                events
+                    .iter()
+                    .map(|(event, count)| EventEmitStats {
+                        event: event.clone(),
+                        count: *count,
+                        per_second: *count as f64 / elapsed,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.event.cmp(&b.event)));
+        stats
+    }
+}
+
+// Shared backend counters
+pub type BackendCountersState = Arc<BackendCounters>;
+
+/// Initialize the backend counters (called from main)
+pub fn init_backend_counters() -> BackendCountersState {
+    Arc::new(BackendCounters::new())
+}
+
+/// Emit an event to the webview and count it
+pub(crate) fn emit_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Some(counters) = app.try_state::<BackendCountersState>() {
+        counters.record_event(event);
+    }
+    crate::commands::activity::observe_event(app, event, &payload);
+    if let Err(e) = app.emit(event, payload) {
+        log::debug!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// Marks a telemetry post as in flight until dropped
+pub(crate) struct TelemetryInFlight(Option<BackendCountersState>);
+
+impl TelemetryInFlight {
+    pub(crate) fn start(app: &AppHandle) -> Self {
+        let counters = app
+            .try_state::<BackendCountersState>()
+            .map(|state| state.inner().clone());
+        if let Some(counters) = &counters {
+            counters.telemetry_in_flight.fetch_add(1, Ordering::Relaxed);
+        }
+        Self(counters)
+    }
+}
+
+impl Drop for TelemetryInFlight {
+    fn drop(&mut self) {
+        if let Some(counters) = &self.0 {
+            counters.telemetry_in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Get a snapshot of backend resource usage
+#[tauri::command]
+pub async fn get_backend_stats(app: AppHandle) -> Result<ApiResponse<BackendStats>, String> {
+    let counters = app
+        .try_state::<BackendCountersState>()
+        .map(|state| state.inner().clone())
+        .unwrap_or_else(init_backend_counters);
+
+    let (log_files, log_bytes) = app
+        .try_state::<LoggingState>()
+        .map(|logging| log_file_usage(&logging.log_dir))
+        .unwrap_or_default();
+
+    let stats = BackendStats {
+        uptime_secs: counters.started.elapsed().as_secs(),
+        registries: registry_sizes(&app).await,
+        telemetry_in_flight: counters.telemetry_in_flight.load(Ordering::Relaxed),
+        log_files,
+        log_bytes,
+        events: counters.event_stats(),
+        runtime: runtime_stats(),
+    };
+
+    log::debug!(
+        "Backend stats: {} processes, {} terminal processes, {} alive tasks",
+        stats.registries.processes,
+        stats.registries.terminal_processes,
+        stats
+            .runtime
+            .as_ref()
+            .map_or(0, |runtime| runtime.alive_tasks)
+    );
+
+    Ok(ApiResponse::success(stats))
+}
+
+async fn registry_sizes(app: &AppHandle) -> RegistrySizes {
+    let process_registry = get_process_registry(app);
+    let processes = process_registry.read().await;
+    let mut finished_processes = 0;
+    for handle in processes.values() {
+        if handle.lock().await.run_result.status != RunStatus::Running {
+            finished_processes += 1;
+        }
+    }
+
+    let terminal_processes = app
+        .try_state::<TerminalRegistry>()
+        .and_then(|registry| registry.lock().ok().map(|registry| registry.len()))
+        .unwrap_or(0);
+
+    RegistrySizes {
+        processes: processes.len(),
+        finished_processes,
+        terminal_processes,
+        run_groups: get_run_group_registry(app).read().await.len(),
+        run_schedules: get_run_schedule_registry(app).read().await.len(),
+        reserved_ports: get_port_registry(app).lock().await.len(),
+        pending_approvals: get_approval_registry(app).lock().await.len(),
+    }
+}
+
+/// Number and total size of the rolling log files
+fn log_file_usage(log_dir: &std::path::Path) -> (usize, u64) {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return (0, 0);
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .fold((0, 0), |(count, bytes), entry| {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            (count + 1, bytes + size)
+        })
+}
+
+fn runtime_stats() -> Option<RuntimeStats> {
+    let metrics = tokio::runtime::Handle::try_current().ok()?.metrics();
+    Some(RuntimeStats {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stats_sorted_by_count() {
+        let counters = BackendCounters::new();
+        counters.record_event("run-group-status");
+        counters.record_event("log-event");
+        counters.record_event("log-event");
+
+        let stats = counters.event_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].event, "log-event");
+        assert_eq!(stats[0].count, 2);
+        assert!(stats[0].per_second > 0.0);
+    }
+
+    #[test]
+    fn test_log_file_usage() {
+        let dir = std::env::temp_dir().join(format!("backend_stats_{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("{}.2024-01-01", LOG_FILE_PREFIX)), "abc").unwrap();
+        std::fs::write(dir.join("other.txt"), "ignored").unwrap();
+
+        assert_eq!(log_file_usage(&dir), (1, 3));
+        assert_eq!(log_file_usage(&dir.join("missing")), (0, 0));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,390 @@
+//! Storage usage and retention
+//! Reports how much disk the app data directory uses and vacuums run history and log
+//! files down to the configured retention limits, on demand and from a background task
+
+use crate::commands::config::load_config_from_file;
+use crate::commands::history::{RunHistoryLock, RESULT_FILE, RUNS_DIR};
+use crate::commands::pins::{read_pins, PinKind};
+use crate::logging::{LoggingState, LOG_DIR_NAME, LOG_FILE_PREFIX};
+use crate::models::{
+    ApiResponse, AppError, RetentionConfig, StorageCategoryUsage, StorageUsage, VacuumReport,
+};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+/// Time between background vacuum passes
+const VACUUM_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Category of loose files directly in the app data directory
+const OTHER_CATEGORY: &str = "other";
+
+/// Disk used by the app data directory, per top-level entry
+#[tauri::command]
+pub async fn get_storage_usage(app: AppHandle) -> Result<ApiResponse<StorageUsage>, String> {
+    let result = app_data_dir(&app).and_then(|dir| storage_usage(&dir));
+
+    match result {
+        Ok(usage) => Ok(ApiResponse::success(usage)),
+        Err(e) => {
+            log::error!("Failed to measure storage usage: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to measure storage usage: {}", e),
+            ))
+        }
+    }
+}
+
+/// Apply the saved retention limits now instead of waiting for the background pass
+#[tauri::command]
+pub async fn vacuum_storage(app: AppHandle) -> Result<ApiResponse<VacuumReport>, String> {
+    match vacuum(&app).await {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => {
+            log::error!("Storage vacuum failed: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Storage vacuum failed: {}", e),
+            ))
+        }
+    }
+}
+
+/// Vacuum at startup and then every `VACUUM_INTERVAL`, re-reading the config each pass
+pub fn spawn_vacuum_task(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = vacuum(&app).await {
+                log::warn!("Storage vacuum failed: {}", e);
+            }
+            tokio::time::sleep(VACUUM_INTERVAL).await;
+        }
+    });
+}
+
+/// A run directory or log file considered by the vacuum
+#[derive(Debug, Clone)]
+struct StoredEntry {
+    path: PathBuf,
+    name: String,
+    modified: SystemTime,
+    bytes: u64,
+}
+
+async fn vacuum(app: &AppHandle) -> Result<VacuumReport, AppError> {
+    let retention = load_config_from_file(app)
+        .await?
+        .and_then(|config| config.retention)
+        .unwrap_or_default();
+    if retention == RetentionConfig::default() {
+        return Ok(VacuumReport::default());
+    }
+
+    let data_dir = app_data_dir(app)?;
+    let now = SystemTime::now();
+    let mut report = VacuumReport::default();
+
+    // Pinned runs are never removed
+    let pinned: HashSet<String> = read_pins(app, PinKind::Run)
+        .await?
+        .into_iter()
+        .map(|pin| pin.id)
+        .collect();
+    {
+        let lock = app.state::<RunHistoryLock>().inner().clone();
+        let _guard = lock.lock().await;
+
+        let runs = stored_runs(&data_dir.join(RUNS_DIR))?;
+        for run in expired_runs(runs, &retention, &pinned, now) {
+            fs::remove_dir_all(&run.path)?;
+            report.runs_removed += 1;
+            report.bytes_freed += run.bytes;
+        }
+    }
+
+    let log_dir = app
+        .try_state::<LoggingState>()
+        .map(|logging| logging.log_dir.clone())
+        .unwrap_or_else(|| data_dir.join(LOG_DIR_NAME));
+    for file in expired_logs(stored_logs(&log_dir)?, &retention, now) {
+        fs::remove_file(&file.path)?;
+        report.log_files_removed += 1;
+        report.bytes_freed += file.bytes;
+    }
+
+    if report.runs_removed > 0 || report.log_files_removed > 0 {
+        log::info!(
+            "Storage vacuum removed {} runs and {} log files ({} bytes)",
+            report.runs_removed,
+            report.log_files_removed,
+            report.bytes_freed
+        );
+    }
+    Ok(report)
+}
+
+/// Runs past `max_runs` (newest kept first) or past the age limit, skipping pinned runs
+fn expired_runs(
+    mut runs: Vec<StoredEntry>,
+    retention: &RetentionConfig,
+    pinned: &HashSet<String>,
+    now: SystemTime,
+) -> Vec<StoredEntry> {
+    runs.retain(|run| !pinned.contains(&run.name));
+    runs.sort_by_key(|run| Reverse(run.modified));
+
+    runs.into_iter()
+        .enumerate()
+        .filter(|(index, run)| {
+            retention.max_runs.is_some_and(|max| *index >= max)
+                || is_expired(run, retention.max_age_days, now)
+        })
+        .map(|(_, run)| run)
+        .collect()
+}
+
+/// Oldest log files past the size or age limit; the newest file is being written to and stays
+fn expired_logs(
+    mut files: Vec<StoredEntry>,
+    retention: &RetentionConfig,
+    now: SystemTime,
+) -> Vec<StoredEntry> {
+    files.sort_by_key(|file| Reverse(file.modified));
+
+    let mut total = 0;
+    let mut expired = Vec::new();
+    for (index, file) in files.into_iter().enumerate() {
+        total += file.bytes;
+        let over_size = retention.max_log_bytes.is_some_and(|max| total > max);
+        if index > 0 && (over_size || is_expired(&file, retention.max_age_days, now)) {
+            expired.push(file);
+        }
+    }
+    expired
+}
+
+fn is_expired(entry: &StoredEntry, max_age_days: Option<u32>, now: SystemTime) -> bool {
+    max_age_days.is_some_and(|days| {
+        let age = now.duration_since(entry.modified).unwrap_or_default();
+        age > Duration::from_secs(u64::from(days) * 24 * 60 * 60)
+    })
+}
+
+/// Run directories, dated by when their result was recorded
+fn stored_runs(runs_dir: &Path) -> Result<Vec<StoredEntry>, AppError> {
+    if !runs_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut runs = Vec::new();
+    for entry in fs::read_dir(runs_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let result_file = path.join(RESULT_FILE);
+        let dated = if result_file.exists() {
+            &result_file
+        } else {
+            &path
+        };
+        runs.push(StoredEntry {
+            name: file_name(&path),
+            modified: fs::metadata(dated)?.modified()?,
+            bytes: dir_usage(&path)?.0,
+            path,
+        });
+    }
+    Ok(runs)
+}
+
+fn stored_logs(log_dir: &Path) -> Result<Vec<StoredEntry>, AppError> {
+    if !log_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(log_dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !metadata.is_file() || !name.starts_with(LOG_FILE_PREFIX) {
+            continue;
+        }
+        files.push(StoredEntry {
+            path: entry.path(),
+            name,
+            modified: metadata.modified()?,
+            bytes: metadata.len(),
+        });
+    }
+    Ok(files)
+}
+
+fn storage_usage(data_dir: &Path) -> Result<StorageUsage, AppError> {
+    let mut categories: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    if data_dir.exists() {
+        for entry in fs::read_dir(data_dir)? {
+            let path = entry?.path();
+            let metadata = fs::symlink_metadata(&path)?;
+            let (category, usage) = if metadata.is_dir() {
+                (file_name(&path), dir_usage(&path)?)
+            } else {
+                (OTHER_CATEGORY.to_string(), (metadata.len(), 1))
+            };
+            let totals = categories.entry(category).or_default();
+            totals.0 += usage.0;
+            totals.1 += usage.1;
+        }
+    }
+
+    let mut categories: Vec<StorageCategoryUsage> = categories
+        .into_iter()
+        .map(|(category, (bytes, files))| StorageCategoryUsage {
+            category,
+            bytes,
+            files,
+        })
+        .collect();
+    categories.sort_by_key(|category| Reverse(category.bytes));
+
+    Ok(StorageUsage {
+        path: data_dir.to_string_lossy().to_string(),
+        total_bytes: categories.iter().map(|category| category.bytes).sum(),
+        categories,
+    })
+}
+
+/// Total bytes and file count below a directory; symlinks are not followed
+pub(crate) fn dir_usage(dir: &Path) -> Result<(u64, u64), AppError> {
+    let mut bytes = 0;
+    let mut files = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if metadata.is_dir() {
+            let (dir_bytes, dir_files) = dir_usage(&path)?;
+            bytes += dir_bytes;
+            files += dir_files;
+        } else {
+            bytes += metadata.len();
+            files += 1;
+        }
+    }
+    Ok((bytes, files))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn entry(name: &str, age_days: u32, bytes: u64, now: SystemTime) -> StoredEntry {
+        StoredEntry {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            modified: now - DAY * age_days,
+            bytes,
+        }
+    }
+
+    fn names(entries: &[StoredEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_expired_runs() {
+        let now = SystemTime::now();
+        let runs = || {
+            vec![
+                entry("old", 40, 10, now),
+                entry("new", 0, 10, now),
+                entry("mid", 5, 10, now),
+                entry("pinned", 90, 10, now),
+            ]
+        };
+        let pinned = HashSet::from(["pinned".to_string()]);
+
+        let by_count = RetentionConfig {
+            max_runs: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&expired_runs(runs(), &by_count, &pinned, now)),
+            ["old"]
+        );
+
+        let by_age = RetentionConfig {
+            max_age_days: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&expired_runs(runs(), &by_age, &pinned, now)),
+            ["mid", "old"]
+        );
+
+        let unlimited = RetentionConfig::default();
+        assert!(expired_runs(runs(), &unlimited, &pinned, now).is_empty());
+    }
+
+    #[test]
+    fn test_expired_logs_keep_newest() {
+        let now = SystemTime::now();
+        let files = vec![
+            entry("app.log.1", 3, 100, now),
+            entry("app.log.3", 1, 100, now),
+            entry("app.log.4", 0, 500, now),
+            entry("app.log.2", 2, 100, now),
+        ];
+        let retention = RetentionConfig {
+            max_log_bytes: Some(650),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&expired_logs(files.clone(), &retention, now)),
+            ["app.log.2", "app.log.1"]
+        );
+
+        // The file being written to survives even when it alone is over the limit
+        let tiny = RetentionConfig {
+            max_log_bytes: Some(1),
+            max_age_days: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(expired_logs(files, &tiny, now).len(), 3);
+    }
+
+    #[test]
+    fn test_storage_usage() {
+        let dir = std::env::temp_dir().join(format!("storage-usage-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("runs").join("run_1")).unwrap();
+        fs::write(dir.join("runs").join("run_1").join(RESULT_FILE), [0; 300]).unwrap();
+        fs::write(dir.join("runs").join("run_1").join("notes.json"), [0; 50]).unwrap();
+        fs::write(dir.join("config.json"), [0; 20]).unwrap();
+
+        let usage = storage_usage(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(usage.total_bytes, 370);
+        assert_eq!(usage.categories[0].category, "runs");
+        assert_eq!(usage.categories[0].files, 2);
+        assert_eq!(usage.categories[1].category, OTHER_CATEGORY);
+        assert_eq!(usage.categories[1].bytes, 20);
+    }
+}
@@ -0,0 +1,290 @@
+//! Support bundle
+//! Packs redacted config, preflight results, recent logs, recent runs and system info
+//! into one zip archive with a manifest of what was included
+
+use crate::commands::audit::read_audit_log;
+use crate::commands::config::load_config_from_file;
+use crate::commands::logs::read_app_logs;
+use crate::commands::network_capture::redact_url;
+use crate::commands::preflight::{get_system_info, run_preflight_checks};
+use crate::commands::process::get_process_registry;
+use crate::logging::LoggingState;
+use crate::models::{
+    ApiResponse, AppError, AppLogFilter, AuditAction, AuditEntry, RunSummary, SandboxConfig,
+    SupportBundle, SupportBundleFile, SupportBundleManifest,
+};
+use crate::path_env::spawn_path_for_app;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const BUNDLE_LOG_LIMIT: usize = 2000;
+const BUNDLE_AUDIT_LIMIT: usize = 200;
+
+/// Create a support bundle at `path` (a file, or a directory to place it in)
+#[tauri::command]
+pub async fn create_support_bundle(
+    app: AppHandle,
+    path: String,
+) -> Result<ApiResponse<SupportBundle>, String> {
+    log::info!("Creating support bundle at {}", path);
+
+    let bundle_path = bundle_file_path(Path::new(&path));
+    let files = collect_bundle_files(&app).await;
+
+    match write_bundle(&bundle_path, files) {
+        Ok(bundle) => {
+            log::info!(
+                "Support bundle written: {} ({} bytes)",
+                bundle.path,
+                bundle.size_bytes
+            );
+            Ok(ApiResponse::success(bundle))
+        }
+        Err(e) => {
+            log::error!("Failed to create support bundle: {}", e);
+            Ok(ApiResponse::error(
+                "BUNDLE_ERROR".to_string(),
+                format!("Failed to create support bundle: {}", e),
+            ))
+        }
+    }
+}
+
+/// A file destined for the archive, or the reason it could not be produced
+struct BundleEntry {
+    name: &'static str,
+    description: &'static str,
+    contents: Result<Vec<u8>, String>,
+}
+
+impl BundleEntry {
+    fn json<T: Serialize>(
+        name: &'static str,
+        description: &'static str,
+        value: Result<T, AppError>,
+    ) -> Self {
+        let contents = value
+            .and_then(|value| Ok(serde_json::to_vec_pretty(&value)?))
+            .map_err(|e| e.to_string());
+        Self {
+            name,
+            description,
+            contents,
+        }
+    }
+}
+
+async fn collect_bundle_files(app: &AppHandle) -> Vec<BundleEntry> {
+    let config = load_config_from_file(app).await.and_then(|config| {
+        config
+            .map(redact_config)
+            .ok_or_else(|| AppError::Config("No configuration saved".to_string()))
+    });
+
+    let path_env = spawn_path_for_app(app).await;
+    let preflight = run_preflight_checks(&path_env).await;
+
+    let logs = match app.try_state::<LoggingState>() {
+        Some(logging) => {
+            read_app_logs(&logging.log_dir, &AppLogFilter::default(), BUNDLE_LOG_LIMIT)
+        }
+        None => Err(AppError::Config(
+            "File logging is not initialized".to_string(),
+        )),
+    };
+    let logs = logs.and_then(|entries| {
+        let mut lines = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut lines, &entry)?;
+            lines.push(b'\n');
+        }
+        Ok(lines)
+    });
+
+    vec![
+        BundleEntry::json(
+            "config.json",
+            "Sandbox configuration with the API key redacted",
+            config,
+        ),
+        BundleEntry::json(
+            "preflight.json",
+            "Node.js, npm and ElizaOS CLI preflight report",
+            preflight,
+        ),
+        BundleEntry {
+            name: "logs.jsonl",
+            description: "Most recent backend log entries",
+            contents: logs.map_err(|e| e.to_string()),
+        },
+        BundleEntry::json(
+            "runs.json",
+            "Recent runs and run audit entries",
+            Ok(recent_runs(app).await),
+        ),
+        BundleEntry::json(
+            "system.json",
+            "Platform, architecture and app version",
+            Ok(system_info()),
+        ),
+    ]
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentRuns {
+    active: Vec<RunSummary>,
+    history: Vec<AuditEntry>,
+}
+
+/// Runs still in the process registry plus recent run lifecycle audit entries
+async fn recent_runs(app: &AppHandle) -> RecentRuns {
+    let mut active = Vec::new();
+    for handle in get_process_registry(app).read().await.values() {
+        active.push(RunSummary::from(&handle.lock().await.run_result));
+    }
+
+    let mut history: Vec<_> = read_audit_log(app)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| {
+            matches!(
+                entry.action,
+                AuditAction::RunStarted | AuditAction::RunStopped | AuditAction::RunKilled
+            )
+        })
+        .collect();
+    let skip = history.len().saturating_sub(BUNDLE_AUDIT_LIMIT);
+    history.drain(..skip);
+
+    RecentRuns { active, history }
+}
+
+fn system_info() -> serde_json::Value {
+    serde_json::json!({
+        "summary": get_system_info(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "family": std::env::consts::FAMILY,
+        "appVersion": env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// Keep enough of the API key to tell keys apart, never the secret itself; proxy
+/// credentials are redacted too
+pub(crate) fn redact_config(mut config: SandboxConfig) -> SandboxConfig {
+    let prefix: String = config.api_key.chars().take(12).collect();
+    config.api_key = format!("{}***", prefix);
+    if let Some(proxy_url) = config
+        .http
+        .as_mut()
+        .and_then(|http| http.proxy_url.as_mut())
+    {
+        *proxy_url = redact_url(proxy_url);
+    }
+    config
+}
+
+/// Use the path as-is, or place a timestamped bundle inside it when it is a directory
+fn bundle_file_path(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(format!(
+            "eliza-support-{}.zip",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn write_bundle(path: &Path, files: Vec<BundleEntry>) -> Result<SupportBundle, AppError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_error = |e: zip::result::ZipError| AppError::Io(std::io::Error::other(e));
+
+    let mut manifest = SupportBundleManifest {
+        created_at: crate::models::current_timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: Vec::new(),
+    };
+
+    for file in files {
+        let (included, error) = match file.contents {
+            Ok(contents) => {
+                zip.start_file(file.name, options).map_err(zip_error)?;
+                zip.write_all(&contents)?;
+                (true, None)
+            }
+            Err(e) => (false, Some(e)),
+        };
+
+        manifest.files.push(SupportBundleFile {
+            name: file.name.to_string(),
+            description: file.description.to_string(),
+            included,
+            error,
+        });
+    }
+
+    zip.start_file("manifest.json", options)
+        .map_err(zip_error)?;
+    zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    zip.finish().map_err(zip_error)?;
+
+    Ok(SupportBundle {
+        path: path.to_string_lossy().to_string(),
+        size_bytes: fs::metadata(path)?.len(),
+        manifest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_config() {
+        let config = SandboxConfig::new(
+            "https://api.example.com".to_string(),
+            format!("eliza_{}", "a".repeat(64)),
+        );
+        let redacted = redact_config(config);
+        assert_eq!(redacted.api_key, "eliza_aaaaaa***");
+        assert_eq!(redact_config(SandboxConfig::default()).api_key, "***");
+    }
+
+    #[test]
+    fn test_write_bundle_manifest() {
+        let dir = std::env::temp_dir().join(format!("support_bundle_{}", rand::random::<u32>()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = bundle_file_path(&dir);
+        assert!(path.starts_with(&dir));
+
+        let files = vec![
+            BundleEntry::json("system.json", "System info", Ok(system_info())),
+            BundleEntry {
+                name: "logs.jsonl",
+                description: "Logs",
+                contents: Err("File logging is not initialized".to_string()),
+            },
+        ];
+        let bundle = write_bundle(&path, files).unwrap();
+
+        assert!(bundle.size_bytes > 0);
+        assert!(bundle.manifest.files[0].included);
+        assert!(!bundle.manifest.files[1].included);
+        assert!(bundle.manifest.files[1].error.is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
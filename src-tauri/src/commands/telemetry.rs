@@ -1,17 +1,82 @@
 //! Telemetry management for usage analytics
-//! Handles posting telemetry data to Sandbox API
-
-use crate::models::{ApiResponse, AppError, SandboxConfig, TelemetryEvent};
-use reqwest::Client;
+//! Handles posting telemetry data to Sandbox API, or to the webhook or local file picked as
+//! the config's telemetry sink. Payloads are validated before sending; events that fail
+//! validation go to a dead-letter file in the app data directory
+
+use crate::commands::budget::record_usage;
+use crate::commands::sandbox_http::{sandbox_http, SandboxHttp};
+use crate::commands::stats::TelemetryInFlight;
+use crate::models::{
+    current_timestamp, ApiResponse, AppError, SandboxConfig, TelemetryEvent, TelemetrySink,
+    DEFAULT_TELEMETRY_PATH,
+};
+use crate::severity::is_benign_stderr;
+use chrono::DateTime;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
+use tauri::{AppHandle, Manager};
 
 const TELEMETRY_TIMEOUT: Duration = Duration::from_secs(10);
 const MAX_RETRY_ATTEMPTS: usize = 3;
 const RETRY_DELAY: Duration = Duration::from_millis(1000);
 
+const PAYLOAD_SOURCE: &str = "desktop_client";
+const PAYLOAD_VERSION: &str = "0.1.0";
+/// Largest serialized payload the endpoint accepts
+const MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+/// Largest serialized `metadata` object
+const MAX_METADATA_BYTES: usize = 16 * 1024;
+const MAX_ARGS: usize = 100;
+const MAX_FIELD_LEN: usize = 256;
+
+const DEAD_LETTER_FILE: &str = "telemetry_dead_letter.jsonl";
+/// Once the dead-letter file grows past this, its older half is dropped
+const MAX_DEAD_LETTER_BYTES: u64 = 1024 * 1024;
+
+/// Body posted to the telemetry endpoint
+#[derive(Debug, Clone, Serialize)]
+struct TelemetryPayload {
+    source: &'static str,
+    version: &'static str,
+    timestamp: String,
+    event: TelemetryPayloadEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TelemetryPayloadEvent {
+    device_id: String,
+    command: String,
+    args: Vec<String>,
+    started_at: String,
+    duration_ms: u64,
+    exit_code: i32,
+    bytes_out: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    approx_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<HashMap<String, Value>>,
+}
+
+/// A payload that failed validation, kept instead of being sent
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeadLetter<'a> {
+    recorded_at: String,
+    problems: &'a [String],
+    payload: &'a TelemetryPayload,
+}
+
 /// Post telemetry event to Sandbox API
 #[tauri::command]
 pub async fn post_telemetry(
+    app: AppHandle,
     config: SandboxConfig,
     event: TelemetryEvent,
 ) -> Result<ApiResponse<()>, String> {
@@ -22,7 +87,14 @@ pub async fn post_telemetry(
         event.duration_ms
     );
 
-    if !config.is_valid() {
+    // Usage counts toward the budget even when it can't be reported
+    if let Some(tokens) = event.approx_tokens {
+        record_usage(&app, &config, tokens).await;
+    }
+
+    // Only the Sandbox sink needs the API key
+    let sink = config.telemetry_sink.clone().unwrap_or_default();
+    if matches!(sink, TelemetrySink::Sandbox { .. }) && !config.is_valid() {
         log::warn!("Invalid configuration for telemetry");
         return Ok(ApiResponse::error(
             "INVALID_CONFIG".to_string(),
@@ -30,9 +102,26 @@ pub async fn post_telemetry(
         ));
     }
 
-    match post_telemetry_event(&config, &event).await {
+    let payload = prepare_telemetry_payload(&event);
+    let body = match validate_payload(&payload) {
+        Ok(body) => body,
+        Err(problems) => {
+            log::warn!(
+                "Rejected malformed telemetry event: {}",
+                problems.join("; ")
+            );
+            write_dead_letter(&app, &payload, &problems);
+            return Ok(ApiResponse::error(
+                "INVALID_TELEMETRY".to_string(),
+                format!("Telemetry event is malformed: {}", problems.join("; ")),
+            ));
+        }
+    };
+
+    let _in_flight = TelemetryInFlight::start(&app);
+    match deliver_telemetry(&config, &sink, body).await {
         Ok(_) => {
-            log::info!("Telemetry event posted successfully");
+            log::info!("Telemetry event delivered to the {} sink", sink.kind());
             Ok(ApiResponse::success(()))
         }
         Err(e) => {
@@ -54,25 +143,68 @@ pub async fn get_device_id() -> Result<ApiResponse<String>, String> {
     Ok(ApiResponse::success(device_id))
 }
 
-/// Post telemetry event with retry logic
-async fn post_telemetry_event(
+/// Hand a validated payload to the configured sink
+async fn deliver_telemetry(
     config: &SandboxConfig,
-    event: &TelemetryEvent,
+    sink: &TelemetrySink,
+    body: Vec<u8>,
 ) -> Result<(), AppError> {
-    let client = Client::builder()
-        .timeout(TELEMETRY_TIMEOUT)
-        .user_agent("ElizaOS-Desktop/0.1.0")
-        .build()
-        .map_err(|e| AppError::Network(format!("Failed to create HTTP client: {}", e)))?;
+    match sink {
+        TelemetrySink::Sandbox { path } => {
+            let url = sandbox_telemetry_url(config, path.as_deref());
+            post_telemetry_event(&url, Some(&config.api_key), body).await
+        }
+        TelemetrySink::Webhook { url } => {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(AppError::Config(format!(
+                    "Telemetry webhook URL '{}' must start with http:// or https://",
+                    url
+                )));
+            }
+            post_telemetry_event(url, None, body).await
+        }
+        TelemetrySink::File { path } => append_telemetry_file(Path::new(path), &body),
+    }
+}
+
+/// Telemetry endpoint on the Sandbox, `/telemetry/cli` unless the sink overrides the path
+fn sandbox_telemetry_url(config: &SandboxConfig, path: Option<&str>) -> String {
+    let path = path
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(DEFAULT_TELEMETRY_PATH);
+    format!(
+        "{}/{}",
+        config.base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
 
-    let telemetry_url = format!("{}/telemetry/cli", config.base_url.trim_end_matches('/'));
+/// Append the payload as one JSON line, for self-hosted collectors that tail a file
+fn append_telemetry_file(path: &Path, body: &[u8]) -> Result<(), AppError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(body)?;
+    file.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Post telemetry event with retry logic; `api_key` is sent as a bearer token when given
+async fn post_telemetry_event(
+    telemetry_url: &str,
+    api_key: Option<&str>,
+    body: Vec<u8>,
+) -> Result<(), AppError> {
+    let client = sandbox_http();
 
     let mut last_error = None;
 
     for attempt in 1..=MAX_RETRY_ATTEMPTS {
         log::debug!("Telemetry attempt {} to {}", attempt, telemetry_url);
 
-        match send_telemetry_request(&client, &telemetry_url, config, event).await {
+        match send_telemetry_request(client, telemetry_url, api_key, body.clone()).await {
             Ok(_) => {
                 if attempt > 1 {
                     log::info!("Telemetry succeeded on attempt {}", attempt);
@@ -95,30 +227,24 @@ async fn post_telemetry_event(
 
 /// Send telemetry HTTP request
 async fn send_telemetry_request(
-    client: &Client,
+    client: &SandboxHttp,
     url: &str,
-    config: &SandboxConfig,
-    event: &TelemetryEvent,
+    api_key: Option<&str>,
+    body: Vec<u8>,
 ) -> Result<(), AppError> {
-    // Prepare the telemetry payload
-    let payload = prepare_telemetry_payload(event);
-
-    let response = client
-        .post(url)
-        .header("Authorization", format!("Bearer {}", config.api_key))
-        .header("Content-Type", "application/json")
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                AppError::Network("Telemetry request timed out".to_string())
-            } else if e.is_connect() {
-                AppError::Network("Failed to connect to telemetry endpoint".to_string())
-            } else {
-                AppError::Network(format!("Telemetry request failed: {}", e))
-            }
-        })?;
+    let mut request = client.post(url).timeout(TELEMETRY_TIMEOUT);
+    if let Some(api_key) = api_key {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let response = client.send_json_body(request, body).await.map_err(|e| {
+        if e.is_timeout() {
+            AppError::Network("Telemetry request timed out".to_string())
+        } else if e.is_connect() {
+            AppError::Network("Failed to connect to telemetry endpoint".to_string())
+        } else {
+            AppError::Network(format!("Telemetry request failed: {}", e))
+        }
+    })?;
 
     let status = response.status();
 
@@ -146,38 +272,130 @@ async fn send_telemetry_request(
 }
 
 /// Prepare telemetry payload for transmission
-fn prepare_telemetry_payload(event: &TelemetryEvent) -> serde_json::Value {
-    let mut payload = serde_json::json!({
-        "source": "desktop_client",
-        "version": "0.1.0",
-        "timestamp": event.started_at,
-        "event": {
-            "device_id": event.device_id,
-            "command": event.command,
-            "args": sanitize_args_for_telemetry(&event.args),
-            "started_at": event.started_at,
-            "duration_ms": event.duration_ms,
-            "exit_code": event.exit_code,
-            "bytes_out": event.bytes_out,
+fn prepare_telemetry_payload(event: &TelemetryEvent) -> TelemetryPayload {
+    TelemetryPayload {
+        source: PAYLOAD_SOURCE,
+        version: PAYLOAD_VERSION,
+        timestamp: event.started_at.clone(),
+        event: TelemetryPayloadEvent {
+            device_id: event.device_id.clone(),
+            command: event.command.clone(),
+            args: sanitize_args_for_telemetry(&event.args),
+            started_at: event.started_at.clone(),
+            duration_ms: event.duration_ms,
+            exit_code: event.exit_code,
+            bytes_out: event.bytes_out,
+            approx_tokens: event.approx_tokens,
+            error: event.error.as_deref().map(sanitize_error_for_telemetry),
+            metadata: event.metadata.clone(),
+        },
+    }
+}
+
+/// Check required fields and size limits, returning the serialized body or every problem found
+fn validate_payload(payload: &TelemetryPayload) -> Result<Vec<u8>, Vec<String>> {
+    let event = &payload.event;
+    let mut problems = Vec::new();
+
+    for (name, value) in [("device_id", &event.device_id), ("command", &event.command)] {
+        if value.trim().is_empty() {
+            problems.push(format!("{} is required", name));
+        } else if value.len() > MAX_FIELD_LEN {
+            problems.push(format!("{} exceeds {} characters", name, MAX_FIELD_LEN));
         }
-    });
+    }
 
-    // Add optional fields if present
-    if let Some(tokens) = event.approx_tokens {
-        payload["event"]["approx_tokens"] =
-            serde_json::Value::Number(serde_json::Number::from(tokens));
+    if DateTime::parse_from_rfc3339(&event.started_at).is_err() {
+        problems.push(format!(
+            "started_at '{}' is not an RFC 3339 timestamp",
+            event.started_at
+        ));
     }
 
-    if let Some(ref error) = event.error {
-        payload["event"]["error"] = serde_json::Value::String(sanitize_error_for_telemetry(error));
+    if event.args.len() > MAX_ARGS {
+        problems.push(format!("args has more than {} entries", MAX_ARGS));
     }
 
     if let Some(ref metadata) = event.metadata {
-        payload["event"]["metadata"] =
-            serde_json::to_value(metadata).unwrap_or(serde_json::Value::Null);
+        let size = serde_json::to_vec(metadata).map_or(0, |bytes| bytes.len());
+        if size > MAX_METADATA_BYTES {
+            problems.push(format!(
+                "metadata is {} bytes, limit is {}",
+                size, MAX_METADATA_BYTES
+            ));
+        }
     }
 
-    payload
+    let body = match serde_json::to_vec(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            problems.push(format!("payload does not serialize: {}", e));
+            return Err(problems);
+        }
+    };
+    if body.len() > MAX_PAYLOAD_BYTES {
+        problems.push(format!(
+            "payload is {} bytes, limit is {}",
+            body.len(),
+            MAX_PAYLOAD_BYTES
+        ));
+    }
+
+    if problems.is_empty() {
+        Ok(body)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Keep a rejected payload for inspection; failures are logged and never surface
+fn write_dead_letter(app: &AppHandle, payload: &TelemetryPayload, problems: &[String]) {
+    // Serializes writers so trimming never races an append
+    static DEAD_LETTER_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = DEAD_LETTER_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let entry = DeadLetter {
+        recorded_at: current_timestamp(),
+        problems,
+        payload,
+    };
+    if let Err(e) = append_dead_letter(app, &entry) {
+        log::warn!("Failed to write telemetry dead letter: {}", e);
+    }
+}
+
+fn append_dead_letter(app: &AppHandle, entry: &DeadLetter) -> Result<(), AppError> {
+    let path = get_dead_letter_path(app)?;
+    trim_dead_letters(&path)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+fn get_dead_letter_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(DEAD_LETTER_FILE))
+}
+
+/// Drop the older half of the dead-letter file once it outgrows its limit
+fn trim_dead_letters(path: &Path) -> Result<(), AppError> {
+    let too_large = fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_DEAD_LETTER_BYTES);
+    if too_large {
+        let contents = fs::read_to_string(path)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n");
+        fs::write(path, format!("{}\n", kept))?;
+    }
+    Ok(())
 }
 
 /// Sanitize command arguments for telemetry (remove sensitive data)
@@ -217,6 +435,7 @@ pub fn estimate_token_usage(text: &str) -> u64 {
 }
 
 /// Create telemetry event from run result
+#[allow(clippy::too_many_arguments)]
 pub fn create_telemetry_event_from_run(
     device_id: String,
     command: &str,
@@ -231,8 +450,18 @@ pub fn create_telemetry_event_from_run(
     let bytes_out = combined_output.len() as u64;
     let approx_tokens = estimate_token_usage(&combined_output);
 
+    // Report the lines that look like real errors, not npm/npx install chatter
     let error = if exit_code != 0 && !stderr.is_empty() {
-        Some(stderr.join("\n"))
+        let errors: Vec<&str> = stderr
+            .iter()
+            .map(String::as_str)
+            .filter(|line| !is_benign_stderr(line))
+            .collect();
+        if errors.is_empty() {
+            Some(stderr.join("\n"))
+        } else {
+            Some(errors.join("\n"))
+        }
     } else {
         None
     };
@@ -286,6 +515,90 @@ mod tests {
         assert!(sanitized.len() <= 500);
     }
 
+    fn valid_event() -> TelemetryEvent {
+        TelemetryEvent::new(
+            "device123".to_string(),
+            "start".to_string(),
+            vec!["start".to_string()],
+            "2024-01-01T00:00:00Z".to_string(),
+            1200,
+            0,
+            64,
+        )
+    }
+
+    #[test]
+    fn test_payload_shape() {
+        let event = valid_event().with_tokens(16);
+        let value = serde_json::to_value(prepare_telemetry_payload(&event)).unwrap();
+
+        assert_eq!(value["source"], "desktop_client");
+        assert_eq!(value["timestamp"], "2024-01-01T00:00:00Z");
+        assert_eq!(value["event"]["device_id"], "device123");
+        assert_eq!(value["event"]["approx_tokens"], 16);
+        assert!(value["event"].get("metadata").is_none());
+    }
+
+    #[test]
+    fn test_validate_payload_accepts_valid_event() {
+        let body = validate_payload(&prepare_telemetry_payload(&valid_event())).unwrap();
+        assert!(!body.is_empty());
+    }
+
+    #[test]
+    fn test_validate_payload_reports_every_problem() {
+        let mut event = valid_event();
+        event.device_id = String::new();
+        event.started_at = "yesterday".to_string();
+        event.args = vec!["x".to_string(); MAX_ARGS + 1];
+
+        let problems = validate_payload(&prepare_telemetry_payload(&event)).unwrap_err();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("device_id"));
+    }
+
+    #[test]
+    fn test_validate_payload_limits_size() {
+        let mut event = valid_event();
+        event.metadata = Some(HashMap::from([(
+            "blob".to_string(),
+            Value::String("x".repeat(MAX_PAYLOAD_BYTES)),
+        )]));
+
+        let problems = validate_payload(&prepare_telemetry_payload(&event)).unwrap_err();
+        assert!(problems.iter().any(|p| p.starts_with("metadata")));
+        assert!(problems.iter().any(|p| p.starts_with("payload")));
+    }
+
+    #[test]
+    fn test_sandbox_telemetry_url() {
+        let config = SandboxConfig::new("https://sandbox.example.com/".to_string(), String::new());
+        assert_eq!(
+            sandbox_telemetry_url(&config, None),
+            "https://sandbox.example.com/telemetry/cli"
+        );
+        assert_eq!(
+            sandbox_telemetry_url(&config, Some("v2/usage")),
+            "https://sandbox.example.com/v2/usage"
+        );
+        assert_eq!(
+            sandbox_telemetry_url(&config, Some(" ")),
+            "https://sandbox.example.com/telemetry/cli"
+        );
+    }
+
+    #[test]
+    fn test_append_telemetry_file() {
+        let dir = std::env::temp_dir().join(format!("telemetry-sink-{}", std::process::id()));
+        let path = dir.join("events.jsonl");
+
+        append_telemetry_file(&path, b"{\"a\":1}").unwrap();
+        append_telemetry_file(&path, b"{\"a\":2}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":1}\n{\"a\":2}\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_estimate_token_usage() {
         let text = "This is a test message with some content";
@@ -320,4 +633,29 @@ mod tests {
         assert!(event.bytes_out > 0);
         assert!(event.approx_tokens.is_some());
     }
+
+    #[test]
+    fn test_telemetry_error_skips_install_noise() {
+        let stderr = vec![
+            "npm warn deprecated glob@7.2.3: Glob versions prior to v9 are no longer supported"
+                .to_string(),
+            "Error: Cannot find module '@elizaos/core'".to_string(),
+        ];
+
+        let event = create_telemetry_event_from_run(
+            "device123".to_string(),
+            "run",
+            &[],
+            "2023-01-01T00:00:00Z",
+            100,
+            1,
+            &[],
+            &stderr,
+        );
+
+        assert_eq!(
+            event.error.as_deref(),
+            Some("Error: Cannot find module '@elizaos/core'")
+        );
+    }
 }
@@ -1,14 +1,20 @@
 //! Terminal Commands - Handles terminal execution and process management
 //! Provides safe terminal command execution with output streaming
 
-use std::process::Stdio;
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
-use tokio::process::Command;
-use tokio::io::{AsyncBufReadExt, BufReader};
 use serde::{Deserialize, Serialize};
-use tauri::State;
-use crate::models::{ApiResponse, AppError};
+use tauri::{AppHandle, State};
+use tracing::Instrument;
+use crate::commands::activity::record_activity;
+use crate::commands::approvals::request_approval;
+use crate::commands::audit::record_audit;
+use crate::commands::path_jail::check_path_allowed;
+use crate::commands::process::sanitize_args_for_logging;
+use crate::executor::{ExecRequest, Executor, LocalExecutor};
+use crate::models::{ActivityKind, ApiResponse, AppError, AuditAction, AuditEntry, AuditOrigin};
+use crate::path_env::spawn_path_for_app;
+use crate::stream::CapturedOutput;
 
 // ============================================================================
 // Terminal Types
@@ -22,6 +28,9 @@ pub struct TerminalCommandResult {
     pub error: Option<String>,
     pub exit_code: Option<i32>,
     pub duration_ms: u64,
+    /// Whether binary output was replaced with a placeholder
+    #[serde(default)]
+    pub binary_output: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,10 +102,22 @@ pub async fn execute_terminal_command(
     command: String,
     args: Vec<String>,
     working_dir: Option<String>,
+    app: AppHandle,
     registry: State<'_, TerminalRegistry>,
 ) -> Result<TerminalCommandResult, AppError> {
     log::info!("Executing terminal command: {} with args: {:?}", command, args);
 
+    run_terminal_command(&app, &registry, command, args, working_dir).await
+}
+
+/// Shared terminal execution path: security check, registry tracking and output capture
+pub(crate) async fn run_terminal_command(
+    app: &AppHandle,
+    registry: &TerminalRegistry,
+    command: String,
+    args: Vec<String>,
+    working_dir: Option<String>,
+) -> Result<TerminalCommandResult, AppError> {
     let start_time = std::time::Instant::now();
 
     // Resolve working directory properly
@@ -106,20 +127,29 @@ pub async fn execute_terminal_command(
     };
 
     log::debug!("Working directory: {}", work_dir);
+    check_path_allowed(app, std::path::Path::new(&work_dir), "Working directory")?;
 
     // Validate command for security
     let security_check = is_safe_command(&command);
     log::debug!("Security check for command '{}': {}", command, security_check);
 
+    // Commands outside the policy need an explicit one-off approval from the user
     if !security_check {
-        log::warn!("Command '{}' blocked for security reasons", command);
-        return Ok(TerminalCommandResult {
-            success: false,
-            output: vec![],
-            error: Some(format!("Command '{}' is not allowed for security reasons", command)),
-            exit_code: Some(1),
-            duration_ms: start_time.elapsed().as_millis() as u64,
-        });
+        log::warn!("Command '{}' requires approval", command);
+        let approved = request_approval(app, &command, &args, &work_dir, "Command is not allowed by the security policy").await;
+
+        if !approved {
+            log::warn!("Command '{}' blocked: approval denied", command);
+            audit_terminal_command(app, &command, &args, false, Some("Blocked: approval denied".to_string())).await;
+            return Ok(TerminalCommandResult {
+                success: false,
+                output: vec![],
+                error: Some(format!("Command '{}' was not approved", command)),
+                exit_code: Some(1),
+                duration_ms: start_time.elapsed().as_millis() as u64,
+                binary_output: false,
+            });
+        }
     }
 
     let process_id = format!("term_{}_{}",
@@ -149,17 +179,23 @@ pub async fn execute_terminal_command(
 
     log::debug!("About to execute command: {} with args: {:?} in dir: {}", command, args, work_dir);
 
+    // Spawn with the augmented PATH so version-manager installs are found
+    let path_env = spawn_path_for_app(app).await;
+
+    // Tag execution logs with the terminal session so they can be filtered later
+    let session_span = tracing::info_span!("terminal", session_id = %process_id, command = %command);
+
     // Execute command using appropriate method (shell vs binary)
     let execution_result = if should_use_shell(&command) {
         log::debug!("Using shell execution for command: {}", command);
-        execute_shell_command(&command, &args, &work_dir).await
+        execute_shell_command(&command, &args, &work_dir, &path_env).instrument(session_span.clone()).await
     } else {
         log::debug!("Using binary execution for command: {}", command);
-        match execute_binary_command(&command, &args, &work_dir).await {
+        match execute_binary_command(&command, &args, &work_dir, &path_env).instrument(session_span.clone()).await {
             Ok(result) => Ok(result),
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 log::debug!("Binary '{}' not found, falling back to shell execution", command);
-                execute_shell_command(&command, &args, &work_dir).await
+                execute_shell_command(&command, &args, &work_dir, &path_env).instrument(session_span.clone()).await
             }
             Err(e) => Err(e),
         }
@@ -168,6 +204,8 @@ pub async fn execute_terminal_command(
     // Process execution result
     match execution_result {
         Ok((stdout_output, stderr_output, exit_code)) => {
+            let binary_output = stdout_output.binary || stderr_output.binary;
+            let (stdout_output, stderr_output) = (stdout_output.lines, stderr_output.lines);
             let success = exit_code == Some(0) || exit_code.is_none();
             log::debug!("Command completed. Exit code: {:?}, Success: {}", exit_code, success);
             log::debug!("Output - stdout lines: {}, stderr lines: {}", stdout_output.len(), stderr_output.len());
@@ -186,6 +224,8 @@ pub async fn execute_terminal_command(
                 combined_output.push(format!("... ({} more lines truncated to prevent memory issues)", truncated_count));
             }
 
+            audit_terminal_command(app, &command, &args, success, exit_code.map(|code| format!("Exit code {}", code))).await;
+
             // Update registry and cleanup old processes
             {
                 let mut reg = registry.lock().unwrap();
@@ -203,10 +243,12 @@ pub async fn execute_terminal_command(
                 error: if stderr_output.is_empty() { None } else { Some(stderr_output.join("\n")) },
                 exit_code,
                 duration_ms: start_time.elapsed().as_millis() as u64,
+                binary_output,
             })
         }
         Err(e) => {
             log::error!("Command execution failed: {}", e);
+            audit_terminal_command(app, &command, &args, false, Some(format!("Failed to spawn: {}", e))).await;
 
             // Update registry and cleanup old processes
             {
@@ -225,6 +267,7 @@ pub async fn execute_terminal_command(
                 error: Some(format!("Failed to spawn command: {}", e)),
                 exit_code: Some(1),
                 duration_ms: start_time.elapsed().as_millis() as u64,
+                binary_output: false,
             })
         }
     }
@@ -251,14 +294,14 @@ pub async fn cancel_terminal_command(
                     Ok(_) => {
                         process.status = "killed".to_string();
                         log::info!("Successfully sent SIGTERM to process {}", pid);
-                        return Ok(ApiResponse::success(true));
+                        Ok(ApiResponse::success(true))
                     }
                     Err(e) => {
                         log::error!("Failed to kill process {}: {}", pid, e);
-                        return Ok(ApiResponse::error(
+                        Ok(ApiResponse::error(
                             "KILL_FAILED".to_string(),
                             format!("Failed to kill process: {}", e)
-                        ));
+                        ))
                     }
                 }
             }
@@ -267,22 +310,22 @@ pub async fn cancel_terminal_command(
             {
                 // On Windows, we would use different approach
                 log::warn!("Process termination on Windows not yet implemented");
-                return Ok(ApiResponse::error(
+                Ok(ApiResponse::error(
                     "NOT_IMPLEMENTED".to_string(),
                     "Process termination on Windows not yet implemented".to_string()
-                ));
+                ))
             }
         } else {
-            return Ok(ApiResponse::error(
+            Ok(ApiResponse::error(
                 "NO_PID".to_string(),
                 "Process has no PID available".to_string()
-            ));
+            ))
         }
     } else {
-        return Ok(ApiResponse::error(
+        Ok(ApiResponse::error(
             "NOT_FOUND".to_string(),
             "Command not found in registry".to_string()
-        ));
+        ))
     }
 }
 
@@ -306,9 +349,10 @@ pub async fn get_terminal_cwd() -> Result<ApiResponse<String>, AppError> {
 
 /// Change working directory
 #[tauri::command]
-pub async fn change_terminal_cwd(path: String) -> Result<ApiResponse<String>, AppError> {
+pub async fn change_terminal_cwd(app: AppHandle, path: String) -> Result<ApiResponse<String>, AppError> {
     let resolved_path = resolve_working_directory(path.clone());
     log::debug!("Changing directory from '{}' to '{}'", path, resolved_path);
+    check_path_allowed(&app, std::path::Path::new(&resolved_path), "Working directory")?;
 
     match std::env::set_current_dir(&resolved_path) {
         Ok(_) => {
@@ -326,12 +370,37 @@ pub async fn change_terminal_cwd(path: String) -> Result<ApiResponse<String>, Ap
     }
 }
 
+/// Record an executed (or blocked) terminal command in the audit log
+async fn audit_terminal_command(
+    app: &AppHandle,
+    command: &str,
+    args: &[String],
+    success: bool,
+    detail: Option<String>,
+) {
+    let subject = std::iter::once(command.to_string())
+        .chain(sanitize_args_for_logging(args))
+        .collect::<Vec<_>>()
+        .join(" ");
+    record_activity(
+        app,
+        ActivityKind::Terminal,
+        "terminal-command",
+        format!("{} ({})", subject, if success { "succeeded" } else { "failed" }),
+        None,
+        serde_json::json!({ "success": success, "detail": detail }),
+    );
+    let entry = AuditEntry::new(AuditAction::TerminalCommand, AuditOrigin::Gui, subject)
+        .with_outcome(success, detail);
+    record_audit(app, entry).await;
+}
+
 // ============================================================================
 // Security and Validation
 // ============================================================================
 
 /// Check if a command is safe to execute
-fn is_safe_command(command: &str) -> bool {
+pub(crate) fn is_safe_command(command: &str) -> bool {
     log::debug!("Checking security for command: '{}'", command);
 
     // Allow common safe commands
@@ -407,7 +476,7 @@ fn is_safe_command(command: &str) -> bool {
 // ============================================================================
 
 /// Resolve working directory with proper ~ expansion and validation
-fn resolve_working_directory(dir: String) -> String {
+pub(crate) fn resolve_working_directory(dir: String) -> String {
     log::debug!("Resolving working directory: '{}'", dir);
 
     let expanded_dir = if dir.starts_with('~') {
@@ -415,8 +484,8 @@ fn resolve_working_directory(dir: String) -> String {
             Some(home) => {
                 let path = if dir == "~" {
                     home
-                } else if dir.starts_with("~/") {
-                    home.join(&dir[2..])
+                } else if let Some(rest) = dir.strip_prefix("~/") {
+                    home.join(rest)
                 } else {
                     // Handle cases like ~username (not supported, fallback to current dir)
                     log::warn!("Unsupported path format: '{}', using current directory", dir);
@@ -479,7 +548,8 @@ async fn execute_shell_command(
     command: &str,
     args: &[String],
     work_dir: &str,
-) -> Result<(Vec<String>, Vec<String>, Option<i32>), std::io::Error> {
+    path_env: &str,
+) -> Result<(CapturedOutput, CapturedOutput, Option<i32>), std::io::Error> {
     log::debug!("Executing shell command: {} {:?}", command, args);
 
     // Construct the full command string
@@ -492,40 +562,8 @@ async fn execute_shell_command(
     log::debug!("Full shell command: '{}'", full_command);
 
     // Use bash to execute the command
-    let mut cmd = Command::new("bash");
-    cmd.arg("-c")
-        .arg(&full_command)
-        .current_dir(work_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    let mut child = cmd.spawn()?;
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
-
-    let mut stdout_output = Vec::new();
-    let mut stderr_output = Vec::new();
-
-    // Read stdout
-    let mut stdout_reader = BufReader::new(stdout);
-    let mut stdout_line = String::new();
-    while stdout_reader.read_line(&mut stdout_line).await? > 0 {
-        stdout_output.push(stdout_line.trim_end().to_string());
-        stdout_line.clear();
-    }
-
-    // Read stderr
-    let mut stderr_reader = BufReader::new(stderr);
-    let mut stderr_line = String::new();
-    while stderr_reader.read_line(&mut stderr_line).await? > 0 {
-        stderr_output.push(stderr_line.trim_end().to_string());
-        stderr_line.clear();
-    }
-
-    let status = child.wait().await?;
-    let exit_code = status.code();
-
-    Ok((stdout_output, stderr_output, exit_code))
+    let request = ExecRequest::new("bash", vec!["-c".to_string(), full_command]);
+    run_local(request, work_dir, path_env).await
 }
 
 /// Execute command directly as binary
@@ -533,42 +571,29 @@ async fn execute_binary_command(
     command: &str,
     args: &[String],
     work_dir: &str,
-) -> Result<(Vec<String>, Vec<String>, Option<i32>), std::io::Error> {
+    path_env: &str,
+) -> Result<(CapturedOutput, CapturedOutput, Option<i32>), std::io::Error> {
     log::debug!("Executing binary command: {} {:?}", command, args);
 
-    let mut cmd = Command::new(command);
-    cmd.args(args)
-        .current_dir(work_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    let mut child = cmd.spawn()?;
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
-
-    let mut stdout_output = Vec::new();
-    let mut stderr_output = Vec::new();
-
-    // Read stdout
-    let mut stdout_reader = BufReader::new(stdout);
-    let mut stdout_line = String::new();
-    while stdout_reader.read_line(&mut stdout_line).await? > 0 {
-        stdout_output.push(stdout_line.trim_end().to_string());
-        stdout_line.clear();
-    }
+    let request = ExecRequest::new(command, args.to_vec());
+    run_local(request, work_dir, path_env).await
+}
 
-    // Read stderr
-    let mut stderr_reader = BufReader::new(stderr);
-    let mut stderr_line = String::new();
-    while stderr_reader.read_line(&mut stderr_line).await? > 0 {
-        stderr_output.push(stderr_line.trim_end().to_string());
-        stderr_line.clear();
-    }
+/// Run a terminal command on this machine with the spawn PATH, capturing its output
+async fn run_local(
+    request: ExecRequest,
+    work_dir: &str,
+    path_env: &str,
+) -> Result<(CapturedOutput, CapturedOutput, Option<i32>), std::io::Error> {
+    let request = request
+        .working_dir(Some(work_dir.to_string()))
+        .env("PATH", path_env);
 
-    let status = child.wait().await?;
-    let exit_code = status.code();
+    // Read both pipes; binary output is summarized rather than failing the read
+    let (stdout_output, stderr_output, status) =
+        LocalExecutor.spawn(&request)?.wait_with_output().await?;
 
-    Ok((stdout_output, stderr_output, exit_code))
+    Ok((stdout_output, stderr_output, status.code()))
 }
 
 // ============================================================================
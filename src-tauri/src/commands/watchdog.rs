@@ -0,0 +1,156 @@
+//! Hung-run watchdog
+//! Tracks when each streaming run last wrote to stdout or stderr and raises `run-stalled`
+//! once a run stays silent past the configured threshold, optionally interrupting it
+
+use crate::commands::process::send_interrupt;
+use crate::commands::run_queue::is_run_paused;
+use crate::commands::stats::emit_event;
+use crate::models::{LogEvent, RunStalledEvent, WatchdogConfig};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::task::JoinHandle;
+
+/// Longest gap between inactivity checks
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time of a run's latest output, shared between its pipe readers and the watchdog
+#[derive(Debug)]
+pub struct OutputActivity {
+    started: Instant,
+    last_output_ms: AtomicU64,
+}
+
+impl OutputActivity {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            last_output_ms: AtomicU64::new(0),
+        })
+    }
+
+    /// Record that the run just produced output
+    pub fn touch(&self) {
+        let now = self.started.elapsed().as_millis() as u64;
+        self.last_output_ms.fetch_max(now, Ordering::Relaxed);
+    }
+
+    /// How long the run has been silent
+    pub fn silent_for(&self) -> Duration {
+        let last = Duration::from_millis(self.last_output_ms.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last)
+    }
+}
+
+/// Watch a run until the returned task is aborted; raises one warning per silent stretch
+pub fn spawn_watchdog(
+    app: AppHandle,
+    run_id: String,
+    pid: Option<u32>,
+    activity: Arc<OutputActivity>,
+    config: WatchdogConfig,
+) -> JoinHandle<()> {
+    let threshold = Duration::from_secs(config.stall_after_secs.max(1));
+    let interval = check_interval(threshold);
+
+    tokio::spawn(async move {
+        let mut warned = false;
+        loop {
+            tokio::time::sleep(interval).await;
+
+            // A run paused by the queue is silent on purpose; count from when it resumes
+            if is_run_paused(&app, &run_id) {
+                activity.touch();
+                warned = false;
+                continue;
+            }
+
+            let silent = activity.silent_for();
+            if silent < threshold {
+                warned = false;
+                continue;
+            }
+            if warned {
+                continue;
+            }
+            warned = true;
+
+            let interrupted = config.auto_interrupt && interrupt(&run_id, pid);
+            log::warn!(
+                "Run {} has produced no output for {}s (interrupted: {})",
+                run_id,
+                silent.as_secs(),
+                interrupted
+            );
+
+            let message = if interrupted {
+                format!(
+                    "No output for {}s; the watchdog interrupted the run",
+                    silent.as_secs()
+                )
+            } else {
+                format!("No output for {}s; the run may be stuck", silent.as_secs())
+            };
+            emit_event(&app, "log-event", LogEvent::system(run_id.clone(), message));
+            emit_event(
+                &app,
+                "run-stalled",
+                RunStalledEvent {
+                    run_id: run_id.clone(),
+                    silent_secs: silent.as_secs(),
+                    stall_after_secs: threshold.as_secs(),
+                    interrupted,
+                    timestamp: chrono::Utc::now().timestamp(),
+                },
+            );
+        }
+    })
+}
+
+fn interrupt(run_id: &str, pid: Option<u32>) -> bool {
+    let Some(pid) = pid else {
+        log::warn!("Cannot interrupt stalled run {}: no PID", run_id);
+        return false;
+    };
+    match send_interrupt(pid) {
+        Ok(()) => true,
+        Err(e) => {
+            log::error!("Failed to interrupt stalled run {}: {}", run_id, e);
+            false
+        }
+    }
+}
+
+/// Check often enough to notice a stall within a quarter of the threshold
+fn check_interval(threshold: Duration) -> Duration {
+    (threshold / 4).clamp(Duration::from_millis(250), MAX_CHECK_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_activity() {
+        let activity = OutputActivity::new();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(activity.silent_for() >= Duration::from_millis(20));
+
+        activity.touch();
+        assert!(activity.silent_for() < Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_check_interval() {
+        assert_eq!(
+            check_interval(Duration::from_secs(1)),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            check_interval(Duration::from_secs(8)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(check_interval(Duration::from_secs(600)), MAX_CHECK_INTERVAL);
+    }
+}
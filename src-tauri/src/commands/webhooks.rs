@@ -0,0 +1,378 @@
+//! Sandbox webhook listener
+//! An optional local HTTP endpoint the Sandbox calls back when long-running operations
+//! finish (deployments, evals). Payloads must carry an HMAC-SHA256 signature made with the
+//! listener's secret; accepted events are re-emitted to the webview and appended to a
+//! JSON lines file in the app data directory.
+
+use crate::commands::local_http::{
+    bind_loopback, read_http_request, serve, write_json_response, HttpRequest,
+};
+use crate::commands::stats::emit_event;
+use crate::crypto::{decode_hex, random_hex};
+use crate::models::{ApiResponse, AppError};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::sync::{oneshot, Mutex};
+
+const WEBHOOK_PATH: &str = "/webhooks/sandbox";
+const WEBHOOK_EVENTS_FILE: &str = "webhook_events.jsonl";
+const SIGNATURE_HEADER: &str = "x-sandbox-signature";
+const TIMESTAMP_HEADER: &str = "x-sandbox-timestamp";
+/// Signed requests older than this are rejected as replays
+const MAX_SIGNATURE_AGE_SECS: i64 = 300;
+const DEFAULT_EVENT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookListenerStatus {
+    pub running: bool,
+    pub url: Option<String>,
+    /// Shared secret the Sandbox signs callbacks with
+    pub secret: Option<String>,
+    pub started_at: Option<String>,
+}
+
+/// A verified callback from the Sandbox; payload of the `sandbox-webhook` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent {
+    pub id: String,
+    /// Event type reported by the Sandbox, e.g. `deploy.finished`
+    pub event_type: String,
+    pub received_at: String,
+    pub payload: Value,
+}
+
+pub struct WebhookListenerHandle {
+    port: u16,
+    secret: String,
+    started_at: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl WebhookListenerHandle {
+    fn status(&self) -> WebhookListenerStatus {
+        WebhookListenerStatus {
+            running: true,
+            url: Some(format!("http://127.0.0.1:{}{}", self.port, WEBHOOK_PATH)),
+            secret: Some(self.secret.clone()),
+            started_at: Some(self.started_at.clone()),
+        }
+    }
+}
+
+// The running listener, if callbacks are enabled
+pub type WebhookListenerState = Arc<Mutex<Option<WebhookListenerHandle>>>;
+
+/// Initialize the webhook listener state (called from main)
+pub fn init_webhook_listener_state() -> WebhookListenerState {
+    Arc::new(Mutex::new(None))
+}
+
+/// Start the webhook listener on a random local port
+#[tauri::command]
+pub async fn start_webhook_listener(
+    app: AppHandle,
+) -> Result<ApiResponse<WebhookListenerStatus>, String> {
+    let state = app.state::<WebhookListenerState>().inner().clone();
+    let mut guard = state.lock().await;
+
+    if let Some(handle) = guard.as_ref() {
+        log::info!("Webhook listener already running on port {}", handle.port);
+        return Ok(ApiResponse::success(handle.status()));
+    }
+
+    let (listener, port) = match bind_loopback().await {
+        Ok(bound) => bound,
+        Err(e) => {
+            log::error!("Failed to bind webhook listener: {}", e);
+            return Ok(ApiResponse::error(
+                "WEBHOOK_ERROR".to_string(),
+                format!("Failed to start webhook listener: {}", e),
+            ));
+        }
+    };
+
+    let secret = random_hex(32);
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let (app_handle, listener_secret) = (app.clone(), secret.clone());
+    tokio::spawn(serve(
+        "Webhook listener",
+        listener,
+        shutdown_rx,
+        move |stream| {
+            let (app, secret) = (app_handle.clone(), listener_secret.clone());
+            async move { handle_connection(&app, stream, &secret).await }
+        },
+    ));
+
+    let handle = WebhookListenerHandle {
+        port,
+        secret,
+        started_at: crate::models::current_timestamp(),
+        shutdown,
+    };
+    let status = handle.status();
+    *guard = Some(handle);
+
+    log::info!("Webhook listener on port {}", port);
+    Ok(ApiResponse::success(status))
+}
+
+/// Stop the webhook listener
+#[tauri::command]
+pub async fn stop_webhook_listener(
+    app: AppHandle,
+) -> Result<ApiResponse<WebhookListenerStatus>, String> {
+    let state = app.state::<WebhookListenerState>().inner().clone();
+
+    if let Some(handle) = state.lock().await.take() {
+        let _ = handle.shutdown.send(());
+        log::info!("Webhook listener on port {} stopped", handle.port);
+    }
+
+    Ok(ApiResponse::success(stopped_status()))
+}
+
+/// Get whether the webhook listener is running, with its URL and secret
+#[tauri::command]
+pub async fn get_webhook_listener_status(
+    app: AppHandle,
+) -> Result<ApiResponse<WebhookListenerStatus>, String> {
+    let state = app.state::<WebhookListenerState>().inner().clone();
+    let guard = state.lock().await;
+
+    Ok(ApiResponse::success(
+        guard
+            .as_ref()
+            .map(WebhookListenerHandle::status)
+            .unwrap_or_else(stopped_status),
+    ))
+}
+
+/// Get the most recent persisted webhook events, oldest first
+#[tauri::command]
+pub async fn get_webhook_events(
+    app: AppHandle,
+    limit: Option<usize>,
+) -> Result<ApiResponse<Vec<WebhookEvent>>, String> {
+    match read_events(&app, limit.unwrap_or(DEFAULT_EVENT_LIMIT)) {
+        Ok(events) => Ok(ApiResponse::success(events)),
+        Err(e) => {
+            log::error!("Failed to read webhook events: {}", e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to read webhook events: {}", e),
+            ))
+        }
+    }
+}
+
+fn stopped_status() -> WebhookListenerStatus {
+    WebhookListenerStatus {
+        running: false,
+        url: None,
+        secret: None,
+        started_at: None,
+    }
+}
+
+async fn handle_connection(
+    app: &AppHandle,
+    stream: TcpStream,
+    secret: &str,
+) -> Result<(), AppError> {
+    let mut reader = BufReader::new(stream);
+    let request = read_http_request(&mut reader).await?;
+
+    let (status, payload) = match accept_webhook(&request, secret, chrono::Utc::now().timestamp()) {
+        Ok(event) => {
+            log::info!("Webhook received: {} ({})", event.event_type, event.id);
+            if let Err(e) = append_event(app, &event) {
+                log::warn!("Failed to persist webhook event: {}", e);
+            }
+            emit_event(app, "sandbox-webhook", event);
+            (200, json!({ "received": true }))
+        }
+        Err((status, message)) => {
+            log::warn!(
+                "Rejected webhook {} {}: {}",
+                request.method,
+                request.path,
+                message
+            );
+            (status, json!({ "error": message }))
+        }
+    };
+
+    write_json_response(reader.into_inner(), status, &payload).await
+}
+
+/// Check route, signature and freshness, then parse the payload into an event
+fn accept_webhook(
+    request: &HttpRequest,
+    secret: &str,
+    now: i64,
+) -> Result<WebhookEvent, (u16, String)> {
+    let path = request.path.split('?').next().unwrap_or_default();
+    if request.method != "POST" || path != WEBHOOK_PATH {
+        return Err((404, "Not found".to_string()));
+    }
+
+    let timestamp = request
+        .header(TIMESTAMP_HEADER)
+        .and_then(|value| value.parse::<i64>().ok())
+        .ok_or_else(|| (401, format!("Missing {} header", TIMESTAMP_HEADER)))?;
+    if (now - timestamp).abs() > MAX_SIGNATURE_AGE_SECS {
+        return Err((401, "Signature timestamp is too old".to_string()));
+    }
+
+    let signature = request
+        .header(SIGNATURE_HEADER)
+        .ok_or_else(|| (401, format!("Missing {} header", SIGNATURE_HEADER)))?;
+    if !verify_signature(secret, timestamp, &request.body, signature) {
+        return Err((401, "Invalid signature".to_string()));
+    }
+
+    let payload: Value =
+        serde_json::from_slice(&request.body).map_err(|e| (400, format!("Invalid JSON: {}", e)))?;
+    let event_type = payload
+        .get("type")
+        .and_then(|value| value.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let id = payload
+        .get("id")
+        .and_then(|value| value.as_str())
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| format!("webhook_{}", uuid::Uuid::new_v4()));
+
+    Ok(WebhookEvent {
+        id,
+        event_type,
+        received_at: crate::models::current_timestamp(),
+        payload,
+    })
+}
+
+/// Signature is `sha256=<hex>` of HMAC-SHA256 over `<timestamp>.<body>`
+fn verify_signature(secret: &str, timestamp: i64, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(decode_hex) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn get_events_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(WEBHOOK_EVENTS_FILE))
+}
+
+fn append_event(app: &AppHandle, event: &WebhookEvent) -> Result<(), AppError> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_events_path(app)?)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+fn read_events(app: &AppHandle, limit: usize) -> Result<Vec<WebhookEvent>, AppError> {
+    let path = get_events_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let events: Vec<WebhookEvent> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let skip = events.len().saturating_sub(limit);
+    Ok(events.into_iter().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::encode_hex;
+
+    fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(body);
+        format!("sha256={}", encode_hex(&mac.finalize().into_bytes()))
+    }
+
+    fn request(body: &[u8], timestamp: i64, signature: String) -> HttpRequest {
+        HttpRequest {
+            method: "POST".to_string(),
+            path: WEBHOOK_PATH.to_string(),
+            headers: vec![
+                (TIMESTAMP_HEADER.to_string(), timestamp.to_string()),
+                (SIGNATURE_HEADER.to_string(), signature),
+            ],
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_accept_signed_webhook() {
+        let body = br#"{"id":"evt_1","type":"deploy.finished","agentId":"a1"}"#;
+        let signed = request(body, 1000, sign("s3cret", 1000, body));
+
+        let event = accept_webhook(&signed, "s3cret", 1010).unwrap();
+        assert_eq!(event.id, "evt_1");
+        assert_eq!(event.event_type, "deploy.finished");
+        assert_eq!(event.payload["agentId"], "a1");
+    }
+
+    #[test]
+    fn test_reject_bad_webhooks() {
+        let body = br#"{"type":"eval.complete"}"#;
+
+        let wrong_secret = request(body, 1000, sign("other", 1000, body));
+        assert_eq!(
+            accept_webhook(&wrong_secret, "s3cret", 1000).unwrap_err().0,
+            401
+        );
+
+        let stale = request(body, 1000, sign("s3cret", 1000, body));
+        assert_eq!(accept_webhook(&stale, "s3cret", 2000).unwrap_err().0, 401);
+
+        let mut tampered = request(body, 1000, sign("s3cret", 1000, body));
+        tampered.body = br#"{"type":"eval.failed"}"#.to_vec();
+        assert_eq!(
+            accept_webhook(&tampered, "s3cret", 1000).unwrap_err().0,
+            401
+        );
+
+        let mut wrong_path = request(body, 1000, sign("s3cret", 1000, body));
+        wrong_path.path = "/other".to_string();
+        assert_eq!(
+            accept_webhook(&wrong_path, "s3cret", 1000).unwrap_err().0,
+            404
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Window management
+//! Opens the named secondary windows (log viewer, terminal, playground) from the backend so
+//! commands, CLI arguments and shortcuts can all reach them. Each window's size and position
+//! is remembered per label, and windows left open are brought back on the next launch
+
+use crate::models::{ApiResponse, AppError, WindowGeometry, WindowInfo, WindowKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{
+    AppHandle, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, Window, WindowEvent,
+};
+
+const WINDOW_LAYOUT_FILE: &str = "window_layout.json";
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Last known state of one window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowRecord {
+    #[serde(default)]
+    geometry: Option<WindowGeometry>,
+    /// Whether the window was open when the app last quit
+    #[serde(default)]
+    open: bool,
+}
+
+type WindowLayout = HashMap<String, WindowRecord>;
+
+// Window events arrive on the event loop, so the layout sits behind a blocking mutex
+pub type WindowLayoutState = Arc<Mutex<WindowLayout>>;
+
+/// Initialize the window layout state (called from main)
+pub fn init_window_layout() -> WindowLayoutState {
+    Arc::new(Mutex::new(WindowLayout::new()))
+}
+
+/// Open a secondary window, or focus it if it is already open
+#[tauri::command]
+pub async fn open_window(
+    app: AppHandle,
+    kind: WindowKind,
+) -> Result<ApiResponse<WindowInfo>, String> {
+    match open_window_kind(&app, kind) {
+        Ok(info) => Ok(ApiResponse::success(info)),
+        Err(e) => {
+            log::error!("Failed to open {} window: {}", kind.label(), e);
+            Ok(ApiResponse::error(
+                e.error_code().to_string(),
+                format!("Failed to open window: {}", e.localized_message()),
+            ))
+        }
+    }
+}
+
+/// Close a secondary window, returning whether it was open
+#[tauri::command]
+pub async fn close_window(app: AppHandle, kind: WindowKind) -> Result<ApiResponse<bool>, String> {
+    let Some(window) = app.get_webview_window(kind.label()) else {
+        return Ok(ApiResponse::success(false));
+    };
+
+    // Goes through CloseRequested like a user close, which records the layout
+    match window.close() {
+        Ok(()) => Ok(ApiResponse::success(true)),
+        Err(e) => {
+            log::error!("Failed to close {} window: {}", kind.label(), e);
+            Ok(ApiResponse::error(
+                "WINDOW_ERROR".to_string(),
+                format!("Failed to close window: {}", e),
+            ))
+        }
+    }
+}
+
+/// Every secondary window with whether it is open and its remembered geometry
+#[tauri::command]
+pub async fn list_windows(app: AppHandle) -> Result<ApiResponse<Vec<WindowInfo>>, String> {
+    let windows = WindowKind::ALL
+        .into_iter()
+        .map(|kind| window_info(&app, kind))
+        .collect();
+    Ok(ApiResponse::success(windows))
+}
+
+/// Forget remembered window sizes and positions; open windows keep their current layout
+#[tauri::command]
+pub async fn reset_window_layout(app: AppHandle) -> Result<ApiResponse<()>, String> {
+    {
+        let state = app.state::<WindowLayoutState>();
+        let mut layout = state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for record in layout.values_mut() {
+            record.geometry = None;
+        }
+    }
+    persist_layout(&app);
+    log::info!("Window layout reset");
+    Ok(ApiResponse::success(()))
+}
+
+/// Open or focus a window; shared with the CLI handler
+pub(crate) fn open_window_kind(app: &AppHandle, kind: WindowKind) -> Result<WindowInfo, AppError> {
+    if let Some(window) = app.get_webview_window(kind.label()) {
+        let _ = window.unminimize();
+        window
+            .set_focus()
+            .map_err(|e| AppError::Unknown(format!("Failed to focus window: {}", e)))?;
+        return Ok(window_info(app, kind));
+    }
+
+    let geometry = stored_geometry(app, kind.label()).filter(|g| fits_on_screen(app, g));
+    let (width, height) = geometry
+        .as_ref()
+        .map(|g| (g.width, g.height))
+        .unwrap_or_else(|| kind.default_size());
+
+    let url = WebviewUrl::App(format!("index.html?window={}", kind.label()).into());
+    let mut builder = WebviewWindowBuilder::new(app, kind.label(), url)
+        .title(kind.title())
+        .inner_size(width, height)
+        .min_inner_size(480.0, 320.0);
+    builder = match geometry {
+        Some(ref g) => builder.position(g.x, g.y),
+        None => builder.center(),
+    };
+
+    let window = builder
+        .build()
+        .map_err(|e| AppError::Unknown(format!("Failed to create window: {}", e)))?;
+    if geometry.as_ref().is_some_and(|g| g.maximized) {
+        let _ = window.maximize();
+    }
+
+    set_open(app, kind.label(), true);
+    persist_layout(app);
+    log::info!("Opened {} window", kind.label());
+    Ok(window_info(app, kind))
+}
+
+/// Load the saved layout, put the main window back where it was and reopen the windows
+/// that were open when the app quit
+pub fn restore_window_layout(app: &AppHandle) {
+    let layout = match read_layout(app) {
+        Ok(layout) => layout,
+        Err(e) => {
+            log::warn!("Failed to read window layout: {}", e);
+            WindowLayout::new()
+        }
+    };
+    *app.state::<WindowLayoutState>()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = layout.clone();
+
+    if let (Some(window), Some(geometry)) = (
+        app.get_webview_window(MAIN_WINDOW_LABEL),
+        layout
+            .get(MAIN_WINDOW_LABEL)
+            .and_then(|record| record.geometry.clone()),
+    ) {
+        apply_geometry(app, &window, &geometry);
+    }
+
+    for kind in WindowKind::ALL {
+        if layout.get(kind.label()).is_some_and(|record| record.open) {
+            if let Err(e) = open_window_kind(app, kind) {
+                log::warn!("Failed to reopen {} window: {}", kind.label(), e);
+            }
+        }
+    }
+}
+
+/// Track geometry as windows move, and persist the layout when one is closed
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    let app = window.app_handle();
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            record_geometry(app, window.label(), window);
+        }
+        WindowEvent::CloseRequested { .. } => {
+            record_geometry(app, window.label(), window);
+            // Secondary windows closed by the user stay closed next launch; closing the
+            // main window quits, leaving the others marked open so they come back
+            if window.label() != MAIN_WINDOW_LABEL {
+                set_open(app, window.label(), false);
+                persist_layout(app);
+            } else {
+                persist_layout(app);
+                app.exit(0);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn window_info(app: &AppHandle, kind: WindowKind) -> WindowInfo {
+    WindowInfo {
+        kind,
+        label: kind.label().to_string(),
+        open: app.get_webview_window(kind.label()).is_some(),
+        geometry: stored_geometry(app, kind.label()),
+    }
+}
+
+fn stored_geometry(app: &AppHandle, label: &str) -> Option<WindowGeometry> {
+    let state = app.state::<WindowLayoutState>();
+    let layout = state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    layout.get(label).and_then(|record| record.geometry.clone())
+}
+
+fn set_open(app: &AppHandle, label: &str, open: bool) {
+    let state = app.state::<WindowLayoutState>();
+    let mut layout = state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    layout.entry(label.to_string()).or_default().open = open;
+}
+
+/// Remember a window's logical size and position; a maximized window keeps the geometry
+/// it had before so un-maximizing after a restore still works
+fn record_geometry(app: &AppHandle, label: &str, window: &Window) {
+    let maximized = window.is_maximized().unwrap_or(false);
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let (Ok(scale), Ok(position), Ok(size)) = (
+        window.scale_factor(),
+        window.outer_position(),
+        window.inner_size(),
+    ) else {
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    let size = size.to_logical::<f64>(scale);
+
+    let state = app.state::<WindowLayoutState>();
+    let mut layout = state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let record = layout.entry(label.to_string()).or_default();
+    match (maximized, record.geometry.as_mut()) {
+        (true, Some(geometry)) => geometry.maximized = true,
+        _ => {
+            record.geometry = Some(WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+            })
+        }
+    }
+}
+
+fn apply_geometry(app: &AppHandle, window: &WebviewWindow, geometry: &WindowGeometry) {
+    let _ = window.set_size(LogicalSize::new(geometry.width, geometry.height));
+    if fits_on_screen(app, geometry) {
+        let _ = window.set_position(LogicalPosition::new(geometry.x, geometry.y));
+    }
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Whether the window's top-left corner lands on a connected monitor, so a layout saved
+/// with a second screen attached doesn't open windows off-screen
+fn fits_on_screen(app: &AppHandle, geometry: &WindowGeometry) -> bool {
+    let Ok(monitors) = app.available_monitors() else {
+        return true;
+    };
+    monitors.iter().any(|monitor| {
+        let scale = monitor.scale_factor();
+        let origin = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        point_in_rect(
+            (geometry.x, geometry.y),
+            (origin.x, origin.y, size.width, size.height),
+        )
+    })
+}
+
+fn point_in_rect((x, y): (f64, f64), (left, top, width, height): (f64, f64, f64, f64)) -> bool {
+    x >= left && x < left + width && y >= top && y < top + height
+}
+
+fn get_layout_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to get app data directory: {}", e)))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create app data directory: {}", e)))?;
+
+    Ok(app_data_dir.join(WINDOW_LAYOUT_FILE))
+}
+
+fn read_layout(app: &AppHandle) -> Result<WindowLayout, AppError> {
+    let path = get_layout_path(app)?;
+    if !path.exists() {
+        return Ok(WindowLayout::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Write the in-memory layout; failures are logged and never surface
+fn persist_layout(app: &AppHandle) {
+    let layout = app
+        .state::<WindowLayoutState>()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+
+    let result = get_layout_path(app).and_then(|path| {
+        fs::write(path, serde_json::to_string_pretty(&layout)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to save window layout: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_kind_labels() {
+        for kind in WindowKind::ALL {
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.label())
+            );
+            assert_ne!(kind.label(), MAIN_WINDOW_LABEL);
+            assert_eq!(WindowKind::from_label(kind.label()), Some(kind));
+        }
+        assert_eq!(WindowKind::from_label("main"), None);
+    }
+
+    #[test]
+    fn test_point_in_rect() {
+        let screen = (0.0, 0.0, 1920.0, 1080.0);
+        assert!(point_in_rect((100.0, 100.0), screen));
+        assert!(!point_in_rect((2500.0, 100.0), screen));
+        assert!(!point_in_rect((-10.0, 100.0), screen));
+        assert!(point_in_rect(
+            (-1000.0, 0.0),
+            (-1920.0, 0.0, 1920.0, 1080.0)
+        ));
+    }
+
+    #[test]
+    fn test_layout_round_trip() {
+        let mut layout = WindowLayout::new();
+        layout.insert(
+            "terminal".to_string(),
+            WindowRecord {
+                geometry: Some(WindowGeometry {
+                    x: 10.0,
+                    y: 20.0,
+                    width: 900.0,
+                    height: 600.0,
+                    maximized: false,
+                }),
+                open: true,
+            },
+        );
+
+        let json = serde_json::to_string(&layout).unwrap();
+        let parsed: WindowLayout = serde_json::from_str(&json).unwrap();
+        assert!(parsed["terminal"].open);
+        assert_eq!(parsed["terminal"].geometry.as_ref().unwrap().width, 900.0);
+
+        let legacy: WindowLayout = serde_json::from_str(r#"{"main":{}}"#).unwrap();
+        assert!(!legacy["main"].open && legacy["main"].geometry.is_none());
+    }
+}
@@ -0,0 +1,214 @@
+//! Passphrase-based encryption
+//! Keys for the app lock vault, encrypted config files and the admin password hash are
+//! derived with PBKDF2-HMAC-SHA256 (`pbkdf2`), and data is sealed with XChaCha20-Poly1305
+//! (`chacha20poly1305`). Iteration counts read back from disk must fall within fixed
+//! bounds, so an edited file can neither weaken derivation nor stall it. Hex encoding, random
+//! tokens and constant-time comparison of secrets live here too
+
+use crate::models::AppError;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::Sha256;
+
+/// PBKDF2 iterations for newly derived keys
+pub const KDF_ITERATIONS: u32 = 210_000;
+/// Fewest iterations accepted from a stored file
+const MIN_KDF_ITERATIONS: u32 = 100_000;
+/// Most iterations accepted from a stored file
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+pub const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// A 256-bit key derived from a passphrase or machine secret
+#[derive(Clone)]
+pub struct DerivedKey([u8; 32]);
+
+/// Output of `DerivedKey::seal`; the ciphertext ends with the Poly1305 tag
+pub struct Sealed {
+    pub nonce: [u8; NONCE_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+impl DerivedKey {
+    /// Derive a key with PBKDF2-HMAC-SHA256, refusing iteration counts outside the bounds
+    pub fn derive(secret: &[u8], salt: &[u8], iterations: u32) -> Result<Self, AppError> {
+        if !(MIN_KDF_ITERATIONS..=MAX_KDF_ITERATIONS).contains(&iterations) {
+            return Err(AppError::Config(format!(
+                "Key derivation iterations must be between {} and {}, got {}",
+                MIN_KDF_ITERATIONS, MAX_KDF_ITERATIONS, iterations
+            )));
+        }
+        Ok(Self::pbkdf2(secret, salt, iterations))
+    }
+
+    /// `derive` on the blocking thread pool, so the async runtime keeps serving other work
+    pub async fn derive_blocking(
+        secret: Vec<u8>,
+        salt: Vec<u8>,
+        iterations: u32,
+    ) -> Result<Self, AppError> {
+        tokio::task::spawn_blocking(move || Self::derive(&secret, &salt, iterations))
+            .await
+            .map_err(|e| AppError::Process(format!("Key derivation task failed: {}", e)))?
+    }
+
+    /// A key derived with a single iteration, keeping tests fast
+    #[cfg(test)]
+    pub fn for_tests(secret: &[u8], salt: &[u8]) -> Self {
+        Self::pbkdf2(secret, salt, 1)
+    }
+
+    fn pbkdf2(secret: &[u8], salt: &[u8], iterations: u32) -> Self {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(secret, salt, iterations, &mut key);
+        Self(key)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Whether `expected` holds this key, compared without stopping at the first difference
+    pub fn matches(&self, expected: &[u8]) -> bool {
+        constant_time_eq(&self.0, expected)
+    }
+
+    /// Encrypt and authenticate `plaintext` under a fresh random nonce; `associated` is
+    /// authenticated but not encrypted and must be passed to `open` unchanged
+    pub fn seal(&self, associated: &[u8], plaintext: &[u8]) -> Sealed {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: associated,
+                },
+            )
+            .expect("XChaCha20-Poly1305 encrypts any payload that fits in memory");
+        Sealed { nonce, ciphertext }
+    }
+
+    /// Decrypt what `seal` produced; `None` when the key is wrong or anything was altered
+    pub fn open(&self, nonce: &[u8], associated: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+        if nonce.len() != NONCE_LEN {
+            return None;
+        }
+        self.cipher()
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: associated,
+                },
+            )
+            .ok()
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+/// Compare two secrets without stopping at the first differing byte
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Lowercase hex of `len` random bytes, for tokens and shared secrets
+pub fn random_hex(len: usize) -> String {
+    encode_hex(&(0..len).map(|_| rand::random::<u8>()).collect::<Vec<_>>())
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_round_trip() {
+        let key = DerivedKey::for_tests(b"passphrase", &[3u8; SALT_LEN]);
+        let sealed = key.seal(b"header", b"secret data");
+        assert!(!sealed
+            .ciphertext
+            .windows(b"secret".len())
+            .any(|w| w == b"secret"));
+
+        assert_eq!(
+            key.open(&sealed.nonce, b"header", &sealed.ciphertext)
+                .as_deref(),
+            Some(&b"secret data"[..])
+        );
+        assert!(key
+            .open(&sealed.nonce, b"other header", &sealed.ciphertext)
+            .is_none());
+
+        let other = DerivedKey::for_tests(b"other passphrase", &[3u8; SALT_LEN]);
+        assert!(other
+            .open(&sealed.nonce, b"header", &sealed.ciphertext)
+            .is_none());
+
+        let mut tampered = sealed.ciphertext.clone();
+        tampered[0] ^= 1;
+        assert!(key.open(&sealed.nonce, b"header", &tampered).is_none());
+    }
+
+    #[test]
+    fn test_pbkdf2_vector_and_matches() {
+        // RFC 7914 section 11 test vector, first 32 bytes
+        let key = DerivedKey::pbkdf2(b"passwd", b"salt", 1);
+        assert_eq!(
+            encode_hex(key.as_bytes()),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+        assert!(key.matches(key.as_bytes()));
+        assert!(!key.matches(&[0u8; 32]));
+        assert!(!key.matches(&key.as_bytes()[..16]));
+    }
+
+    #[test]
+    fn test_hex_and_compare_helpers() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0, 255, 16]));
+        assert_eq!(
+            decode_hex(&encode_hex(b"secret")).as_deref(),
+            Some(&b"secret"[..])
+        );
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+
+        let token = random_hex(32);
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, random_hex(32));
+
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abd", b"abc"));
+        assert!(!constant_time_eq(b"ab", b"abc"));
+    }
+
+    #[test]
+    fn test_iterations_are_bounded() {
+        for iterations in [
+            0,
+            1,
+            MIN_KDF_ITERATIONS - 1,
+            MAX_KDF_ITERATIONS + 1,
+            u32::MAX,
+        ] {
+            assert!(DerivedKey::derive(b"passphrase", b"salt", iterations).is_err());
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Process executors
+//! Runs and terminal commands start their processes through an [`Executor`], so a new
+//! execution target (a container, a remote host) only decides how a command is launched;
+//! streaming the output, waiting for the exit and stopping by PID stay with the callers
+
+use crate::stream::{read_output, CapturedOutput};
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::process::Stdio;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio::process::{Child, Command};
+
+/// Working directory of commands run by [`DockerExecutor`], where the request's working
+/// directory is mounted
+pub const CONTAINER_WORKDIR: &str = "/workspace";
+
+pub type OutputPipe = Box<dyn AsyncRead + Send + Unpin>;
+type ExitFuture = Pin<Box<dyn Future<Output = io::Result<ExitOutcome>> + Send>>;
+
+/// A command to start; the environment is added to the app's own
+#[derive(Debug, Clone, Default)]
+pub struct ExecRequest {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub working_dir: Option<String>,
+}
+
+impl ExecRequest {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            ..Self::default()
+        }
+    }
+
+    pub fn envs(mut self, env: HashMap<String, String>) -> Self {
+        self.env.extend(env);
+        self
+    }
+
+    pub fn env(mut self, name: &str, value: &str) -> Self {
+        self.env.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn working_dir(mut self, dir: Option<String>) -> Self {
+        self.working_dir = dir;
+        self
+    }
+}
+
+/// How a process ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitOutcome {
+    code: Option<i32>,
+}
+
+impl ExitOutcome {
+    /// Exit code; None when the process was ended by a signal
+    pub fn code(&self) -> Option<i32> {
+        self.code
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// A started process with its stdout and stderr piped
+pub struct SpawnedProcess {
+    /// PID of the local process; for docker and ssh that is the client relaying the command.
+    /// On unix it leads its own process group, see [`signal_group`]
+    pub pid: Option<u32>,
+    stdout: Option<OutputPipe>,
+    stderr: Option<OutputPipe>,
+    exit: ExitFuture,
+}
+
+impl SpawnedProcess {
+    fn from_child(mut child: Child) -> io::Result<Self> {
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("stdout was not piped"))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| io::Error::other("stderr was not piped"))?;
+        Ok(Self {
+            pid: child.id(),
+            stdout: Some(Box::new(stdout)),
+            stderr: Some(Box::new(stderr)),
+            exit: Box::pin(async move {
+                let status = child.wait().await?;
+                Ok(ExitOutcome {
+                    code: status.code(),
+                })
+            }),
+        })
+    }
+
+    pub fn take_stdout(&mut self) -> Option<OutputPipe> {
+        self.stdout.take()
+    }
+
+    pub fn take_stderr(&mut self) -> Option<OutputPipe> {
+        self.stderr.take()
+    }
+
+    /// Wait for the process to exit; pipes not taken are closed first
+    pub async fn wait(mut self) -> io::Result<ExitOutcome> {
+        self.stdout = None;
+        self.stderr = None;
+        self.exit.await
+    }
+
+    /// Read both pipes to the end, then wait for the exit
+    pub async fn wait_with_output(
+        mut self,
+    ) -> io::Result<(CapturedOutput, CapturedOutput, ExitOutcome)> {
+        let (stdout, stderr) =
+            tokio::join!(read_pipe(self.take_stdout()), read_pipe(self.take_stderr()));
+        Ok((stdout, stderr, self.wait().await?))
+    }
+}
+
+async fn read_pipe(pipe: Option<OutputPipe>) -> CapturedOutput {
+    match pipe {
+        Some(pipe) => read_output(pipe).await,
+        None => CapturedOutput::default(),
+    }
+}
+
+/// Where processes are started
+pub trait Executor: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    fn spawn(&self, request: &ExecRequest) -> io::Result<SpawnedProcess>;
+}
+
+/// Start processes on this machine
+pub struct LocalExecutor;
+
+impl Executor for LocalExecutor {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn spawn(&self, request: &ExecRequest) -> io::Result<SpawnedProcess> {
+        let mut command = Command::new(&request.program);
+        command.args(&request.args).envs(&request.env);
+        if let Some(ref dir) = request.working_dir {
+            command.current_dir(dir);
+        }
+        spawn_piped(command)
+    }
+}
+
+/// Run commands in a fresh container of `image`, with the working directory mounted at
+/// [`CONTAINER_WORKDIR`]
+pub struct DockerExecutor {
+    pub image: String,
+}
+
+impl DockerExecutor {
+    pub fn new(image: impl Into<String>) -> Self {
+        Self {
+            image: image.into(),
+        }
+    }
+
+    fn docker_args(&self, request: &ExecRequest) -> Vec<String> {
+        let mut args: Vec<String> = ["run", "--rm", "-i", "--init"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        if let Some(ref dir) = request.working_dir {
+            args.push("-v".to_string());
+            args.push(format!("{}:{}", dir, CONTAINER_WORKDIR));
+            args.push("-w".to_string());
+            args.push(CONTAINER_WORKDIR.to_string());
+        }
+        // Only the names go on the command line; docker copies the values from its own
+        // environment, so secrets don't show up in process listings
+        for name in forwarded_env_names(request) {
+            args.push("-e".to_string());
+            args.push(name.to_string());
+        }
+        args.push(self.image.clone());
+        args.push(request.program.clone());
+        args.extend(request.args.iter().cloned());
+        args
+    }
+}
+
+impl Executor for DockerExecutor {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    fn spawn(&self, request: &ExecRequest) -> io::Result<SpawnedProcess> {
+        let mut command = Command::new("docker");
+        command.args(self.docker_args(request)).envs(&request.env);
+        spawn_piped(command)
+    }
+}
+
+/// Run commands on another machine over ssh; the working directory, environment and
+/// command are sent as a script on stdin, keeping secrets off both command lines
+pub struct SshExecutor {
+    /// `host` or `user@host`
+    pub destination: String,
+    pub port: Option<u16>,
+}
+
+impl SshExecutor {
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = ["-T", "-o", "BatchMode=yes"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        if let Some(port) = self.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        args.push(self.destination.clone());
+        args.push("sh".to_string());
+        args.push("-s".to_string());
+        args
+    }
+}
+
+impl Executor for SshExecutor {
+    fn name(&self) -> &'static str {
+        "ssh"
+    }
+
+    fn spawn(&self, request: &ExecRequest) -> io::Result<SpawnedProcess> {
+        let mut command = Command::new("ssh");
+        command.args(self.ssh_args()).stdin(Stdio::piped());
+        own_process_group(&mut command);
+        // The local PATH is only used to find ssh itself
+        if let Some(path) = request.env.get("PATH") {
+            command.env("PATH", path);
+        }
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let mut child = command.spawn()?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("stdin was not piped"))?;
+        let script = ssh_script(request);
+        // Written from a task so callers can read output while it is sent; dropping stdin
+        // ends the script
+        tokio::spawn(async move {
+            if let Err(e) = stdin.write_all(script.as_bytes()).await {
+                log::warn!("Failed to send the command to the ssh host: {}", e);
+            }
+        });
+        SpawnedProcess::from_child(child)
+    }
+}
+
+/// Plays back fixed output without starting a process, for exercising code built on
+/// executors
+#[derive(Debug, Clone, Default)]
+pub struct MockExecutor {
+    pub stdout: Vec<String>,
+    pub stderr: Vec<String>,
+    pub exit_code: i32,
+}
+
+impl Executor for MockExecutor {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn spawn(&self, _request: &ExecRequest) -> io::Result<SpawnedProcess> {
+        let pipe = |lines: &[String]| -> OutputPipe {
+            let text: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+            Box::new(io::Cursor::new(text.into_bytes()))
+        };
+        let exit_code = self.exit_code;
+        Ok(SpawnedProcess {
+            pid: None,
+            stdout: Some(pipe(&self.stdout)),
+            stderr: Some(pipe(&self.stderr)),
+            exit: Box::pin(async move {
+                Ok(ExitOutcome {
+                    code: Some(exit_code),
+                })
+            }),
+        })
+    }
+}
+
+fn spawn_piped(mut command: Command) -> io::Result<SpawnedProcess> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    own_process_group(&mut command);
+    SpawnedProcess::from_child(command.spawn()?)
+}
+
+/// Start the process as the leader of a new process group, so signals sent to the group
+/// also reach what a wrapper such as npx or bunx starts
+fn own_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    command.process_group(0);
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Variables passed on to a remote target, sorted; PATH stays behind since it describes
+/// this machine
+fn forwarded_env_names(request: &ExecRequest) -> Vec<&str> {
+    let mut names: Vec<&str> = request
+        .env
+        .keys()
+        .map(String::as_str)
+        .filter(|name| *name != "PATH" && is_env_name(name))
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn ssh_script(request: &ExecRequest) -> String {
+    let mut script = String::new();
+    if let Some(ref dir) = request.working_dir {
+        script.push_str(&format!("cd {} || exit 1\n", shell_quote(dir)));
+    }
+    for name in forwarded_env_names(request) {
+        script.push_str(&format!(
+            "export {}={}\n",
+            name,
+            shell_quote(&request.env[name])
+        ));
+    }
+    let command: Vec<String> = std::iter::once(&request.program)
+        .chain(&request.args)
+        .map(|part| shell_quote(part))
+        .collect();
+    script.push_str(&format!("exec {}\n", command.join(" ")));
+    script
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Send `signal` to the process group led by `pid`, reaching the process an executor
+/// started and everything it started in turn
+#[cfg(unix)]
+pub fn signal_group(pid: u32, signal: nix::sys::signal::Signal) -> Result<(), String> {
+    use nix::sys::signal::killpg;
+    use nix::unistd::Pid;
+
+    killpg(Pid::from_raw(pid as i32), signal).map_err(|e| e.to_string())
+}
+
+/// Stop a process started by an executor and its children: SIGTERM, or SIGKILL when
+/// `force` is set
+#[cfg(unix)]
+pub fn terminate(pid: u32, force: bool) -> Result<(), String> {
+    use nix::sys::signal::Signal;
+
+    let signal = if force {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    };
+    signal_group(pid, signal)
+}
+
+/// Stop a process started by an executor and its children
+#[cfg(not(unix))]
+pub fn terminate(pid: u32, _force: bool) -> Result<(), String> {
+    let output = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> ExecRequest {
+        ExecRequest::new("elizaos", vec!["start".to_string(), "--port".to_string()])
+            .env("PATH", "/usr/local/bin")
+            .env("ELIZAOS_API_KEY", "it's secret")
+            .working_dir(Some("/home/me/agent".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_mock_executor_output() {
+        let executor = MockExecutor {
+            stdout: vec!["ready".to_string(), "listening".to_string()],
+            stderr: vec!["warning".to_string()],
+            exit_code: 2,
+        };
+        let (stdout, stderr, status) = executor
+            .spawn(&request())
+            .unwrap()
+            .wait_with_output()
+            .await
+            .unwrap();
+        assert_eq!(stdout.lines, vec!["ready", "listening"]);
+        assert_eq!(stderr.lines, vec!["warning"]);
+        assert_eq!(status.code(), Some(2));
+        assert!(!status.success());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_local_executor() {
+        let request = ExecRequest::new(
+            "sh",
+            vec!["-c".to_string(), "echo $GREETING; exit 3".to_string()],
+        )
+        .env("GREETING", "hello");
+        let (stdout, _, status) = LocalExecutor
+            .spawn(&request)
+            .unwrap()
+            .wait_with_output()
+            .await
+            .unwrap();
+        assert_eq!(stdout.lines, vec!["hello"]);
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_local_process_leads_its_group() {
+        use nix::sys::signal::killpg;
+        use nix::unistd::Pid;
+
+        let process = LocalExecutor
+            .spawn(&ExecRequest::new("sleep", vec!["30".to_string()]))
+            .unwrap();
+        let pid = process.pid.unwrap();
+        // Signal 0 only checks the group exists
+        assert!(killpg(Pid::from_raw(pid as i32), None).is_ok());
+
+        terminate(pid, false).unwrap();
+        assert_eq!(process.wait().await.unwrap().code(), None);
+    }
+
+    #[test]
+    fn test_docker_args_forward_env_names_only() {
+        let args = DockerExecutor::new("node:20").docker_args(&request());
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--rm",
+                "-i",
+                "--init",
+                "-v",
+                "/home/me/agent:/workspace",
+                "-w",
+                "/workspace",
+                "-e",
+                "ELIZAOS_API_KEY",
+                "node:20",
+                "elizaos",
+                "start",
+                "--port",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ssh_script() {
+        assert_eq!(
+            ssh_script(&request()),
+            "cd '/home/me/agent' || exit 1\n\
+             export ELIZAOS_API_KEY='it'\\''s secret'\n\
+             exec 'elizaos' 'start' '--port'\n"
+        );
+        let executor = SshExecutor {
+            destination: "me@build-box".to_string(),
+            port: Some(2222),
+        };
+        assert!(!executor.ssh_args().join(" ").contains("secret"));
+    }
+}
@@ -0,0 +1,442 @@
+//! Localized backend messages
+//! User-facing strings generated here (error categories, preflight recommendations,
+//! notification text) are looked up by message id in the active locale, which the
+//! frontend picks with `set_locale`; text missing from a locale falls back to English
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Es, Locale::Fr];
+
+    /// Parse a language tag such as `es`, `fr-CA` or `en_US`; unsupported languages are `None`
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.tag() == language)
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+        }
+    }
+}
+
+static CURRENT_LOCALE: RwLock<Locale> = RwLock::new(Locale::En);
+
+/// Locale backend messages are rendered in
+pub fn current_locale() -> Locale {
+    *CURRENT_LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_current_locale(locale: Locale) {
+    *CURRENT_LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+/// Identifies a user-facing message independently of its language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageId {
+    ErrorConfig,
+    ErrorProcess,
+    ErrorCliNotFound,
+    ErrorEnvironment,
+    ErrorCharacter,
+    ErrorNetwork,
+    ErrorEval,
+    ErrorPolicy,
+    ErrorQuota,
+    ErrorLocked,
+    ErrorIo,
+    ErrorSerialization,
+    ErrorRequest,
+    ErrorUnknown,
+    RecommendInstallNode,
+    RecommendInstallNodeLts,
+    RecommendInstallNodeHomebrew,
+    RecommendDownloadNodeLts,
+    RecommendInstallNodePackageManager,
+    RecommendInstallNodeApt,
+    RecommendInstallNodeYum,
+    RecommendUpdateNode,
+    RecommendInstallNpm,
+    RecommendEnsureNpm,
+    NpmBundledWithNode,
+    CliInstalledViaNpx,
+    CliInstalledWhenNeeded,
+    BudgetNotificationTitle,
+    BudgetDailyTokensUsed,
+    BudgetMonthlyTokensUsed,
+    BudgetDailyCostUsed,
+    BudgetMonthlyCostUsed,
+}
+
+impl MessageId {
+    pub const ALL: [MessageId; 32] = [
+        MessageId::ErrorConfig,
+        MessageId::ErrorProcess,
+        MessageId::ErrorCliNotFound,
+        MessageId::ErrorEnvironment,
+        MessageId::ErrorCharacter,
+        MessageId::ErrorNetwork,
+        MessageId::ErrorEval,
+        MessageId::ErrorPolicy,
+        MessageId::ErrorQuota,
+        MessageId::ErrorLocked,
+        MessageId::ErrorIo,
+        MessageId::ErrorSerialization,
+        MessageId::ErrorRequest,
+        MessageId::ErrorUnknown,
+        MessageId::RecommendInstallNode,
+        MessageId::RecommendInstallNodeLts,
+        MessageId::RecommendInstallNodeHomebrew,
+        MessageId::RecommendDownloadNodeLts,
+        MessageId::RecommendInstallNodePackageManager,
+        MessageId::RecommendInstallNodeApt,
+        MessageId::RecommendInstallNodeYum,
+        MessageId::RecommendUpdateNode,
+        MessageId::RecommendInstallNpm,
+        MessageId::RecommendEnsureNpm,
+        MessageId::NpmBundledWithNode,
+        MessageId::CliInstalledViaNpx,
+        MessageId::CliInstalledWhenNeeded,
+        MessageId::BudgetNotificationTitle,
+        MessageId::BudgetDailyTokensUsed,
+        MessageId::BudgetMonthlyTokensUsed,
+        MessageId::BudgetDailyCostUsed,
+        MessageId::BudgetMonthlyCostUsed,
+    ];
+
+    /// Stable id the frontend can key on
+    pub fn id(self) -> &'static str {
+        match self {
+            MessageId::ErrorConfig => "error.config",
+            MessageId::ErrorProcess => "error.process",
+            MessageId::ErrorCliNotFound => "error.cliNotFound",
+            MessageId::ErrorEnvironment => "error.environment",
+            MessageId::ErrorCharacter => "error.character",
+            MessageId::ErrorNetwork => "error.network",
+            MessageId::ErrorEval => "error.eval",
+            MessageId::ErrorPolicy => "error.policy",
+            MessageId::ErrorQuota => "error.quota",
+            MessageId::ErrorLocked => "error.locked",
+            MessageId::ErrorIo => "error.io",
+            MessageId::ErrorSerialization => "error.serialization",
+            MessageId::ErrorRequest => "error.request",
+            MessageId::ErrorUnknown => "error.unknown",
+            MessageId::RecommendInstallNode => "preflight.installNode",
+            MessageId::RecommendInstallNodeLts => "preflight.installNodeLts",
+            MessageId::RecommendInstallNodeHomebrew => "preflight.installNodeHomebrew",
+            MessageId::RecommendDownloadNodeLts => "preflight.downloadNodeLts",
+            MessageId::RecommendInstallNodePackageManager => "preflight.installNodePackageManager",
+            MessageId::RecommendInstallNodeApt => "preflight.installNodeApt",
+            MessageId::RecommendInstallNodeYum => "preflight.installNodeYum",
+            MessageId::RecommendUpdateNode => "preflight.updateNode",
+            MessageId::RecommendInstallNpm => "preflight.installNpm",
+            MessageId::RecommendEnsureNpm => "preflight.ensureNpm",
+            MessageId::NpmBundledWithNode => "preflight.npmBundled",
+            MessageId::CliInstalledViaNpx => "preflight.cliViaNpx",
+            MessageId::CliInstalledWhenNeeded => "preflight.cliWhenNeeded",
+            MessageId::BudgetNotificationTitle => "budget.notificationTitle",
+            MessageId::BudgetDailyTokensUsed => "budget.dailyTokensUsed",
+            MessageId::BudgetMonthlyTokensUsed => "budget.monthlyTokensUsed",
+            MessageId::BudgetDailyCostUsed => "budget.dailyCostUsed",
+            MessageId::BudgetMonthlyCostUsed => "budget.monthlyCostUsed",
+        }
+    }
+
+    /// Message for an `ApiError` code, when the code is one of the `AppError` categories
+    pub fn for_error_code(code: &str) -> Option<Self> {
+        let id = match code {
+            "CONFIG_ERROR" => MessageId::ErrorConfig,
+            "PROCESS_ERROR" => MessageId::ErrorProcess,
+            "CLI_NOT_FOUND" => MessageId::ErrorCliNotFound,
+            "ENVIRONMENT_ERROR" => MessageId::ErrorEnvironment,
+            "CHARACTER_ERROR" => MessageId::ErrorCharacter,
+            "NETWORK_ERROR" => MessageId::ErrorNetwork,
+            "EVAL_ERROR" => MessageId::ErrorEval,
+            "POLICY_VIOLATION" => MessageId::ErrorPolicy,
+            "QUOTA_EXCEEDED" => MessageId::ErrorQuota,
+            "APP_LOCKED" => MessageId::ErrorLocked,
+            "IO_ERROR" => MessageId::ErrorIo,
+            "SERIALIZATION_ERROR" => MessageId::ErrorSerialization,
+            "REQUEST_ERROR" => MessageId::ErrorRequest,
+            "UNKNOWN_ERROR" => MessageId::ErrorUnknown,
+            _ => return None,
+        };
+        Some(id)
+    }
+
+    /// English, Spanish and French text; `{name}` marks a parameter
+    fn translations(self) -> [&'static str; 3] {
+        match self {
+            MessageId::ErrorConfig => [
+                "Configuration error: {detail}",
+                "Error de configuración: {detail}",
+                "Erreur de configuration : {detail}",
+            ],
+            MessageId::ErrorProcess => [
+                "Process error: {detail}",
+                "Error del proceso: {detail}",
+                "Erreur de processus : {detail}",
+            ],
+            MessageId::ErrorCliNotFound => [
+                "CLI not found: {detail}",
+                "CLI no encontrada: {detail}",
+                "CLI introuvable : {detail}",
+            ],
+            MessageId::ErrorEnvironment => [
+                "Environment setup failed: {detail}",
+                "Falló la preparación del entorno: {detail}",
+                "Échec de la préparation de l'environnement : {detail}",
+            ],
+            MessageId::ErrorCharacter => [
+                "Character file error: {detail}",
+                "Error en el archivo de personaje: {detail}",
+                "Erreur du fichier de personnage : {detail}",
+            ],
+            MessageId::ErrorNetwork => [
+                "Network error: {detail}",
+                "Error de red: {detail}",
+                "Erreur réseau : {detail}",
+            ],
+            MessageId::ErrorEval => [
+                "Eval error: {detail}",
+                "Error de evaluación: {detail}",
+                "Erreur d'évaluation : {detail}",
+            ],
+            MessageId::ErrorPolicy => [
+                "Policy violation: {detail}",
+                "Infracción de política: {detail}",
+                "Violation de politique : {detail}",
+            ],
+            MessageId::ErrorQuota => [
+                "Quota exceeded: {detail}",
+                "Cuota superada: {detail}",
+                "Quota dépassé : {detail}",
+            ],
+            MessageId::ErrorLocked => [
+                "App is locked: {detail}",
+                "La aplicación está bloqueada: {detail}",
+                "L'application est verrouillée : {detail}",
+            ],
+            MessageId::ErrorIo => [
+                "IO error: {detail}",
+                "Error de E/S: {detail}",
+                "Erreur d'E/S : {detail}",
+            ],
+            MessageId::ErrorSerialization => [
+                "Serialization error: {detail}",
+                "Error de serialización: {detail}",
+                "Erreur de sérialisation : {detail}",
+            ],
+            MessageId::ErrorRequest => [
+                "Request error: {detail}",
+                "Error en la solicitud: {detail}",
+                "Erreur de requête : {detail}",
+            ],
+            MessageId::ErrorUnknown => [
+                "Unknown error: {detail}",
+                "Error desconocido: {detail}",
+                "Erreur inconnue : {detail}",
+            ],
+            MessageId::RecommendInstallNode => [
+                "Install Node.js 18+ from https://nodejs.org/",
+                "Instala Node.js 18+ desde https://nodejs.org/",
+                "Installez Node.js 18+ depuis https://nodejs.org/",
+            ],
+            MessageId::RecommendInstallNodeLts => [
+                "Install Node.js from https://nodejs.org/ (choose LTS version)",
+                "Instala Node.js desde https://nodejs.org/ (elige la versión LTS)",
+                "Installez Node.js depuis https://nodejs.org/ (choisissez la version LTS)",
+            ],
+            MessageId::RecommendInstallNodeHomebrew => [
+                "Install Node.js via Homebrew: brew install node",
+                "Instala Node.js con Homebrew: brew install node",
+                "Installez Node.js avec Homebrew : brew install node",
+            ],
+            MessageId::RecommendDownloadNodeLts => [
+                "Or download from https://nodejs.org/ (choose LTS version)",
+                "O descárgalo desde https://nodejs.org/ (elige la versión LTS)",
+                "Ou téléchargez-le depuis https://nodejs.org/ (choisissez la version LTS)",
+            ],
+            MessageId::RecommendInstallNodePackageManager => [
+                "Install Node.js via package manager or from https://nodejs.org/",
+                "Instala Node.js con tu gestor de paquetes o desde https://nodejs.org/",
+                "Installez Node.js avec votre gestionnaire de paquets ou depuis https://nodejs.org/",
+            ],
+            MessageId::RecommendInstallNodeApt => [
+                "Ubuntu/Debian: sudo apt install nodejs npm",
+                "Ubuntu/Debian: sudo apt install nodejs npm",
+                "Ubuntu/Debian : sudo apt install nodejs npm",
+            ],
+            MessageId::RecommendInstallNodeYum => [
+                "CentOS/RHEL: sudo yum install nodejs npm",
+                "CentOS/RHEL: sudo yum install nodejs npm",
+                "CentOS/RHEL : sudo yum install nodejs npm",
+            ],
+            MessageId::RecommendUpdateNode => [
+                "Update Node.js to version 18 or higher",
+                "Actualiza Node.js a la versión 18 o superior",
+                "Mettez à jour Node.js vers la version 18 ou supérieure",
+            ],
+            MessageId::RecommendInstallNpm => [
+                "Install npm (usually comes with Node.js)",
+                "Instala npm (normalmente viene con Node.js)",
+                "Installez npm (généralement fourni avec Node.js)",
+            ],
+            MessageId::RecommendEnsureNpm => [
+                "Ensure npm is available",
+                "Asegúrate de que npm esté disponible",
+                "Vérifiez que npm est disponible",
+            ],
+            MessageId::NpmBundledWithNode => [
+                "npm comes bundled with Node.js",
+                "npm viene incluido con Node.js",
+                "npm est fourni avec Node.js",
+            ],
+            MessageId::CliInstalledViaNpx => [
+                "ElizaOS CLI will be installed automatically via npx",
+                "La CLI de ElizaOS se instalará automáticamente mediante npx",
+                "La CLI ElizaOS sera installée automatiquement via npx",
+            ],
+            MessageId::CliInstalledWhenNeeded => [
+                "ElizaOS CLI will be installed automatically when needed",
+                "La CLI de ElizaOS se instalará automáticamente cuando haga falta",
+                "La CLI ElizaOS sera installée automatiquement si nécessaire",
+            ],
+            MessageId::BudgetNotificationTitle => [
+                "ElizaOS budget",
+                "Presupuesto de ElizaOS",
+                "Budget ElizaOS",
+            ],
+            MessageId::BudgetDailyTokensUsed => [
+                "{percent}% of the daily token budget used ({used} of {limit} tokens)",
+                "{percent}% del presupuesto diario de tokens usado ({used} de {limit} tokens)",
+                "{percent} % du budget quotidien de jetons utilisé ({used} sur {limit} jetons)",
+            ],
+            MessageId::BudgetMonthlyTokensUsed => [
+                "{percent}% of the monthly token budget used ({used} of {limit} tokens)",
+                "{percent}% del presupuesto mensual de tokens usado ({used} de {limit} tokens)",
+                "{percent} % du budget mensuel de jetons utilisé ({used} sur {limit} jetons)",
+            ],
+            MessageId::BudgetDailyCostUsed => [
+                "{percent}% of the daily cost budget used (${used} of ${limit})",
+                "{percent}% del presupuesto diario de coste usado (${used} de ${limit})",
+                "{percent} % du budget quotidien de coût utilisé ({used} $ sur {limit} $)",
+            ],
+            MessageId::BudgetMonthlyCostUsed => [
+                "{percent}% of the monthly cost budget used (${used} of ${limit})",
+                "{percent}% del presupuesto mensual de coste usado (${used} de ${limit})",
+                "{percent} % du budget mensuel de coût utilisé ({used} $ sur {limit} $)",
+            ],
+        }
+    }
+
+    /// Text in the given locale, falling back to English
+    fn text(self, locale: Locale) -> &'static str {
+        let [en, es, fr] = self.translations();
+        let text = match locale {
+            Locale::En => en,
+            Locale::Es => es,
+            Locale::Fr => fr,
+        };
+        if text.is_empty() {
+            en
+        } else {
+            text
+        }
+    }
+}
+
+/// A message in the current locale
+pub fn t(id: MessageId) -> String {
+    id.text(current_locale()).to_string()
+}
+
+/// A message in the current locale with its `{name}` parameters filled in
+pub fn t_args(id: MessageId, args: &[(&str, &str)]) -> String {
+    render(id.text(current_locale()), args)
+}
+
+fn render(text: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_translations_keep_parameters() {
+        for id in MessageId::ALL {
+            let english = parameters(id.text(Locale::En));
+            for locale in Locale::ALL {
+                assert!(!id.text(locale).is_empty(), "{} missing", id.id());
+                assert_eq!(parameters(id.text(locale)), english, "{}", id.id());
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_ids_are_unique() {
+        let mut ids: Vec<&str> = MessageId::ALL.iter().map(|id| id.id()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), MessageId::ALL.len());
+    }
+
+    #[test]
+    fn test_locale_parse() {
+        assert_eq!(Locale::parse("es"), Some(Locale::Es));
+        assert_eq!(Locale::parse("fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::parse("EN_us"), Some(Locale::En));
+        assert_eq!(Locale::parse("de"), None);
+        assert_eq!(Locale::parse(""), None);
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(
+                MessageId::ErrorConfig.text(Locale::Es),
+                &[("detail", "falta la clave")]
+            ),
+            "Error de configuración: falta la clave"
+        );
+        assert_eq!(
+            MessageId::for_error_code("POLICY_VIOLATION"),
+            Some(MessageId::ErrorPolicy)
+        );
+        assert_eq!(MessageId::for_error_code("VALIDATION_ERROR"), None);
+    }
+}
@@ -1,13 +1,23 @@
 //! MVP Tauri ElizaOS CLI - Main application entry point
 //! Desktop client for running ElizaOS CLI with Sandbox integration
 
+pub mod cli_handler;
 pub mod commands;
+pub mod crypto;
+pub mod executor;
+pub mod i18n;
+pub mod logging;
 pub mod models;
-pub mod cli_handler;
+pub mod path_env;
+pub mod severity;
+pub mod stream;
+#[cfg(feature = "test-harness")]
+pub mod test_harness;
 
 use commands::process::get_run_result;
 use commands::*;
 use log::info;
+use tauri::Manager;
 
 /// Basic greet command for IPC testing
 #[tauri::command]
@@ -17,64 +27,374 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logging
-    env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Debug) // Enable debug logging
-        .format_timestamp_secs()
-        .init();
-
-    info!(
-        "Starting MVP Tauri ElizaOS CLI v{}",
-        env!("CARGO_PKG_VERSION")
-    );
-
     // Initialize process registry
     let process_registry = init_process_registry();
 
     // Initialize terminal registry
     let terminal_registry = init_terminal_registry();
 
+    // Initialize CLI resolution cache
+    let cli_resolution_cache = init_cli_resolution_cache();
+
+    // Initialize run group and agent port registries
+    let run_group_registry = init_run_group_registry();
+    let port_registry = init_port_registry();
+
+    // Initialize run schedule registry
+    let run_schedule_registry = init_run_schedule_registry();
+
+    // Initialize audit log and command approval queue
+    let audit_log = init_audit_log();
+    let approval_registry = init_approval_registry();
+
+    // Initialize the activity feed writer
+    let activity_log = init_activity_log();
+
+    // Initialize the preflight snapshot history lock
+    let preflight_history = init_preflight_history();
+
+    // Initialize offline mode (mock sandbox) state
+    let mock_sandbox_state = init_mock_sandbox_state();
+
+    // Initialize the Sandbox webhook listener state
+    let webhook_listener_state = init_webhook_listener_state();
+
+    // Initialize the frontend key-value store
+    let kv_store = init_kv_store();
+
+    // Initialize backend counters for stats and the self benchmark ping registry
+    let backend_counters = init_backend_counters();
+    let benchmark_pings = init_benchmark_pings();
+
+    // Initialize the token usage ledger lock for budget alerts
+    let budget_ledger = init_budget_ledger();
+
+    // Initialize the run history lock guarding notes and annotations
+    let run_history = init_run_history();
+
+    // Initialize the buffered output of streaming runs for log tailing
+    let run_log_store = init_run_log_store();
+
+    // Initialize the queue limiting concurrent streaming runs
+    let run_queue = init_run_queue();
+
+    // Initialize the recorded outbound calls of runs using the capture proxy
+    let network_capture_store = init_network_capture_store();
+
+    // Initialize the short-lived cache of Sandbox GET responses
+    let api_cache = init_api_cache();
+
+    // Initialize the opt-in Sandbox connectivity monitor
+    let connectivity_monitor = init_connectivity_monitor();
+
+    // Initialize the sleep inhibitor held during agent and eval runs
+    let sleep_inhibitor = init_sleep_inhibitor();
+
+    // Initialize the session journal and the startup report built from it
+    let session_state = init_session_state();
+
+    // Initialize the app lock; the vault starts locked
+    let app_lock = init_app_lock();
+
+    // Initialize the permission profile checked before every command
+    let permission_state = init_permission_state();
+
+    // Initialize the remembered layout of the main and secondary windows
+    let window_layout = init_window_layout();
+
+    // Initialize the table of registered global shortcuts
+    let global_shortcuts = init_global_shortcuts();
+
+    // Initialize the table of Sandbox logins still polling for a token
+    let sandbox_logins = init_sandbox_login_state();
+
+    // Initialize the run log streaming server state
+    let log_stream_state = init_log_stream_state();
+
+    // Initialize the registry of project watches
+    let watch_registry = init_watch_registry();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_cli::init())
         // Initialize plugins
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(commands::global_shortcuts::handle_shortcut)
+                .build(),
+        )
         // Register global state
         .manage(process_registry)
         .manage(terminal_registry)
-        // Register command handlers
-        .invoke_handler(tauri::generate_handler![
-            // Basic IPC commands
-            greet,
-            // Configuration commands
-            save_sandbox_config,
-            load_sandbox_config,
-            clear_sandbox_config,
-            test_sandbox_connection,
-            test_api_prompt,
-            // Preflight commands
-            preflight_check,
-            // Process management commands
-            start_eliza_run,
-            start_eliza_run_streaming,
-            stop_eliza_run,
-            kill_eliza_run,
-            get_run_result,
-            // Telemetry commands
-            post_telemetry,
-            get_device_id,
-            // Terminal commands
-            initialize_terminal,
-            execute_terminal_command,
-            cancel_terminal_command,
-            get_terminal_processes,
-            get_terminal_cwd,
-            change_terminal_cwd,
-            cleanup_terminal_processes,
-        ])
+        .manage(cli_resolution_cache)
+        .manage(run_group_registry)
+        .manage(port_registry)
+        .manage(run_schedule_registry)
+        .manage(audit_log)
+        .manage(approval_registry)
+        .manage(activity_log)
+        .manage(preflight_history)
+        .manage(mock_sandbox_state)
+        .manage(kv_store)
+        .manage(backend_counters)
+        .manage(benchmark_pings)
+        .manage(webhook_listener_state)
+        .manage(budget_ledger)
+        .manage(run_history)
+        .manage(run_log_store)
+        .manage(run_queue)
+        .manage(network_capture_store)
+        .manage(api_cache)
+        .manage(connectivity_monitor)
+        .manage(sleep_inhibitor)
+        .manage(session_state)
+        .manage(app_lock)
+        .manage(permission_state)
+        .manage(window_layout)
+        .manage(global_shortcuts)
+        .manage(sandbox_logins)
+        .manage(log_stream_state)
+        .manage(watch_registry)
+        // Register command handlers; each call is checked against the permission profile first
+        .invoke_handler(commands::authorization::authorized(
+            tauri::generate_handler![
+                // Basic IPC commands
+                greet,
+                // Configuration commands
+                save_sandbox_config,
+                load_sandbox_config,
+                clear_sandbox_config,
+                list_config_profiles,
+                set_active_profile,
+                delete_config_profile,
+                export_sandbox_config,
+                import_sandbox_config,
+                list_config_backups,
+                restore_config_backup,
+                set_allowed_custom_subcommands,
+                set_trusted_project_hosts,
+                test_sandbox_connection,
+                validate_api_key,
+                test_api_prompt,
+                test_api_prompt_streaming,
+                get_allowed_roots,
+                set_allowed_roots,
+                // Offline mode commands
+                start_mock_sandbox,
+                stop_mock_sandbox,
+                get_mock_sandbox_status,
+                // Cloud agent commands
+                list_cloud_agents,
+                get_cloud_agent,
+                deploy_character_to_cloud,
+                import_cloud_agent,
+                // Sandbox API commands
+                list_sandbox_models,
+                get_sandbox_usage,
+                invalidate_api_cache,
+                get_rate_limit_status,
+                // Connectivity monitor commands
+                start_connectivity_monitor,
+                stop_connectivity_monitor,
+                get_connectivity_status,
+                // Character package commands
+                export_character_package,
+                import_character_package,
+                // Character asset commands
+                list_character_assets,
+                add_character_asset,
+                set_character_avatar,
+                remove_character_asset,
+                // Git commands
+                get_project_git_status,
+                git_commit_project,
+                git_diff_file,
+                // Dependency audit commands
+                audit_project_dependencies,
+                // Project .env commands
+                inspect_project_env,
+                import_project_env,
+                forget_project_env,
+                inspect_project_config,
+                // Project watch commands
+                start_project_watch,
+                stop_project_watch,
+                list_project_watches,
+                // Character file picker commands
+                pick_character_file,
+                // Character knowledge commands
+                add_character_knowledge,
+                list_character_knowledge,
+                remove_character_knowledge,
+                lint_character,
+                // Character revision commands
+                list_character_revisions,
+                diff_character_revisions,
+                rollback_character,
+                // Secret commands
+                set_local_secret,
+                list_local_secrets,
+                delete_local_secret,
+                list_cloud_secrets,
+                push_secrets_to_cloud,
+                // Webhook commands
+                start_webhook_listener,
+                stop_webhook_listener,
+                get_webhook_listener_status,
+                get_webhook_events,
+                // Run log streaming commands
+                start_log_stream_server,
+                stop_log_stream_server,
+                get_log_stream_status,
+                // Preflight commands
+                preflight_check,
+                get_preflight_history,
+                get_preflight_changes,
+                run_doctor,
+                diagnose_run_failure,
+                // Process management commands
+                start_eliza_run,
+                start_eliza_run_streaming,
+                stop_eliza_run,
+                interrupt_eliza_run,
+                kill_eliza_run,
+                get_run_result,
+                tail_run_log,
+                set_run_log_filter,
+                get_run_queue,
+                get_run_network_capture,
+                // Run history commands
+                get_run_record,
+                set_run_note,
+                add_run_annotation,
+                generate_run_report,
+                // Storage commands
+                get_storage_usage,
+                vacuum_storage,
+                clean_cli_caches,
+                // Session commands
+                get_startup_report,
+                // Locale commands
+                get_locale,
+                set_locale,
+                // App lock commands
+                get_app_lock_status,
+                enable_app_lock,
+                disable_app_lock,
+                unlock_app,
+                lock_app,
+                // Permission commands
+                get_permission_profile,
+                set_permission_profile,
+                // Run group commands
+                start_run_group,
+                stop_run_group,
+                kill_run_group,
+                get_run_group_status,
+                // Eval experiment commands
+                start_eval_matrix,
+                get_experiment,
+                list_experiments,
+                get_experiment_results,
+                // Scenario commands
+                run_scenario,
+                get_scenario_result,
+                send_agent_message,
+                // Run scheduling commands
+                schedule_runs,
+                get_run_schedule,
+                refresh_cli_resolution,
+                get_cli_resolution_report,
+                // Audit commands
+                get_audit_log,
+                // Activity feed commands
+                get_activity_feed,
+                // App log commands
+                get_app_logs,
+                set_log_level,
+                // Key-value store commands
+                kv_get,
+                kv_set,
+                kv_delete,
+                kv_list,
+                // Pinned item commands
+                pin_item,
+                unpin_item,
+                list_pinned,
+                // Support commands
+                create_support_bundle,
+                get_backend_stats,
+                // Benchmark commands
+                run_self_benchmark,
+                benchmark_pong,
+                // Telemetry commands
+                post_telemetry,
+                get_device_id,
+                get_budget_usage,
+                // Terminal commands
+                initialize_terminal,
+                execute_terminal_command,
+                cancel_terminal_command,
+                get_terminal_processes,
+                get_terminal_cwd,
+                change_terminal_cwd,
+                cleanup_terminal_processes,
+                get_quick_actions,
+                run_quick_action,
+                // Agent manager commands
+                list_agents,
+                save_agent,
+                remove_agent,
+                set_agent_start_on_launch,
+                create_agent_shortcut,
+                // Prompt template commands
+                list_prompt_templates,
+                save_prompt_template,
+                remove_prompt_template,
+                render_prompt_template,
+                insert_prompt_template,
+                // Approval commands
+                get_pending_approvals,
+                resolve_approval,
+                // Window commands
+                open_window,
+                close_window,
+                list_windows,
+                reset_window_layout,
+                // Global shortcut commands
+                list_shortcuts,
+                set_shortcut,
+                // Sandbox login commands
+                start_sandbox_login,
+                cancel_sandbox_login,
+                // Clipboard commands
+                copy_to_clipboard,
+                // Onboarding commands
+                get_onboarding_state,
+                reset_onboarding,
+            ],
+        ))
         // Set up window configuration
         .setup(|app| {
+            // Initialize logging; files live under the app data directory
+            match app.path().app_data_dir() {
+                Ok(app_data_dir) => match logging::init_logging(&app_data_dir) {
+                    Ok(logging_state) => {
+                        app.manage(logging_state);
+                    }
+                    Err(e) => eprintln!("Failed to initialize logging: {}", e),
+                },
+                Err(e) => eprintln!("Failed to resolve app data directory for logs: {}", e),
+            }
+
+            info!(
+                "Starting MVP Tauri ElizaOS CLI v{}",
+                env!("CARGO_PKG_VERSION")
+            );
             info!("Application setup complete");
 
             // Log system information
@@ -84,6 +404,60 @@ pub fn run() {
                 std::env::consts::ARCH
             );
 
+            // Restore the permission profile before any command can run
+            commands::authorization::load_permission_settings(app.handle());
+
+            // Render backend messages in the locale picked last session
+            commands::locale::load_saved_locale(app.handle());
+
+            // Report what the previous session left behind; nothing has run yet
+            commands::session::recover_session(app.handle());
+
+            // Route Sandbox requests through the active profile's proxy and certificates
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let http = commands::config::load_config_from_file(&app_handle)
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|config| config.http)
+                    .unwrap_or_default();
+                if let Err(e) = commands::sandbox_http::sandbox_http().configure(&http) {
+                    log::warn!("Failed to apply the Sandbox HTTP settings: {}", e);
+                }
+            });
+
+            // Prune audit entries past the configured retention
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let retention_days = commands::config::load_config_from_file(&app_handle)
+                    .await
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default()
+                    .audit_retention_days();
+                if let Err(e) =
+                    commands::audit::apply_audit_retention(&app_handle, retention_days).await
+                {
+                    log::warn!("Failed to apply audit log retention: {}", e);
+                }
+            });
+
+            // Lock the app again after a period without use
+            commands::app_lock::spawn_auto_lock_task(app.handle().clone());
+
+            // Keep run history and log files within the configured retention
+            commands::storage::spawn_vacuum_task(app.handle().clone());
+
+            // Bring up agents flagged to start on launch once preflight checks pass
+            commands::agents::spawn_agent_autostart(app.handle().clone());
+
+            // Put windows back where they were last session
+            commands::windows::restore_window_layout(app.handle());
+
+            // Register the saved global keyboard shortcuts
+            commands::global_shortcuts::spawn_shortcut_registration(app.handle().clone());
+
             // Handle CLI arguments
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -94,6 +468,13 @@ pub fn run() {
 
             Ok(())
         })
+        // Import characters and packages dropped onto a window, and remember window layout
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                commands::file_drop::handle_file_drop(window.app_handle(), paths.clone());
+            }
+            commands::windows::handle_window_event(window, event);
+        })
         // Run the application
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,73 @@
+//! Structured logging
+//! Sets up `tracing` with console output and a daily rolling JSON log file under the
+//! app data directory; existing `log` macros are bridged into the same subscriber
+
+use crate::models::AppError;
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Directory under app data that holds the rolling log files
+pub const LOG_DIR_NAME: &str = "logs";
+/// Log file prefix; the appender adds a `.YYYY-MM-DD` suffix
+pub const LOG_FILE_PREFIX: &str = "app.log";
+const DEFAULT_LOG_LEVEL: &str = "debug";
+
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Managed logging state: where logs are written and how to change the level at runtime
+pub struct LoggingState {
+    pub log_dir: PathBuf,
+    filter: FilterHandle,
+    _guard: WorkerGuard,
+}
+
+impl LoggingState {
+    /// Replace the active level filter, e.g. `info` or `mvp_tauri_eliza_cli_lib=trace`
+    pub fn set_level(&self, level: &str) -> Result<(), AppError> {
+        let filter = EnvFilter::try_new(level)
+            .map_err(|e| AppError::Config(format!("Invalid log level '{}': {}", level, e)))?;
+
+        self.filter
+            .reload(filter)
+            .map_err(|e| AppError::Unknown(format!("Failed to update log level: {}", e)))
+    }
+}
+
+/// Install the global subscriber, honoring `RUST_LOG` when set
+pub fn init_logging(app_data_dir: &Path) -> Result<LoggingState, AppError> {
+    let log_dir = app_data_dir.join(LOG_DIR_NAME);
+    std::fs::create_dir_all(&log_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create log directory: {}", e)))?;
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+    let (filter, filter_handle) = reload::Layer::new(filter);
+
+    let file_appender = tracing_appender::rolling::daily(&log_dir, LOG_FILE_PREFIX);
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(true))
+        .with(
+            fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(file_writer),
+        )
+        .try_init()
+        .map_err(|e| AppError::Unknown(format!("Failed to initialize logging: {}", e)))?;
+
+    // Let the reloadable filter decide; the `log` bridge would otherwise pin the init-time level
+    log::set_max_level(log::LevelFilter::Trace);
+
+    Ok(LoggingState {
+        log_dir,
+        filter: filter_handle,
+        _guard: guard,
+    })
+}
@@ -1,6 +1,7 @@
 //! Core data models for MVP Tauri ElizaOS CLI
 //! These structs match the TypeScript interfaces for proper IPC serialization
 
+use crate::i18n::{t, t_args, MessageId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -14,14 +15,354 @@ pub struct SandboxConfig {
     pub base_url: String,
     pub api_key: String,
     pub default_model: Option<String>,
+    /// Runner lookup order for the ElizaOS CLI; defaults to elizaos > bunx > npx
+    #[serde(default)]
+    pub runner_priority: Option<Vec<CliRunner>>,
+    /// Extra directories appended to PATH for spawned processes
+    #[serde(default)]
+    pub extra_path_dirs: Option<Vec<String>>,
+    /// ElizaOS subcommands permitted in Custom runs; defaults to `DEFAULT_CUSTOM_SUBCOMMANDS`.
+    /// Runs read it from the saved profile, where `set_allowed_custom_subcommands` sets it
+    #[serde(default)]
+    pub allowed_custom_subcommands: Option<Vec<String>>,
+    /// Days to keep audit log entries; defaults to `DEFAULT_AUDIT_RETENTION_DAYS`
+    #[serde(default)]
+    pub audit_retention_days: Option<u32>,
+    /// Token and cost limits checked against run telemetry
+    #[serde(default)]
+    pub budget: Option<BudgetConfig>,
+    /// Warn about (and optionally interrupt) runs that stop producing output
+    #[serde(default)]
+    pub watchdog: Option<WatchdogConfig>,
+    /// Limits enforced on run history and log files by the storage vacuum
+    #[serde(default)]
+    pub retention: Option<RetentionConfig>,
+    /// Concurrency limit for streaming runs; unlimited when unset
+    #[serde(default)]
+    pub queue: Option<RunQueueConfig>,
+    /// Sample and check the network endpoints streaming runs connect to
+    #[serde(default)]
+    pub egress: Option<EgressConfig>,
+    /// Keep the system awake while agent or eval runs are active
+    #[serde(default)]
+    pub prevent_sleep: bool,
+    /// Retry agent runs that failed because their port was taken on a free port, instead
+    /// of only suggesting it
+    #[serde(default)]
+    pub auto_retry_port_conflicts: bool,
+    /// OS keyring entry holding the API key when it is not stored in the file
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
+    /// Where telemetry events go; the Sandbox's `/telemetry/cli` endpoint when unset
+    #[serde(default)]
+    pub telemetry_sink: Option<TelemetrySink>,
+    /// Proxy and extra root certificate used by the shared Sandbox HTTP client
+    #[serde(default)]
+    pub http: Option<HttpClientConfig>,
+    /// Endpoint probed by connection tests and the connectivity monitor
+    #[serde(default)]
+    pub health_check: Option<HealthCheckConfig>,
+    /// Hosts besides the base URL's that a project's `.eliza-desktop.json` may send runs
+    /// and their API key to. Runs read it from the saved profile, where
+    /// `set_trusted_project_hosts` sets it
+    #[serde(default)]
+    pub trusted_project_hosts: Option<Vec<String>>,
 }
 
+/// Shareable copy of a configuration written by `export_sandbox_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxConfigExport {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub app_version: String,
+    /// Whether `config.apiKey` holds the API key; it is blank otherwise
+    pub includes_secrets: bool,
+    pub config: SandboxConfig,
+}
+
+/// Outcome of `import_sandbox_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImportResult {
+    pub profile: ConfigProfileSummary,
+    /// The file carried an API key, which replaced the profile's
+    pub api_key_imported: bool,
+    /// Whether the profile has an API key after the import
+    pub has_api_key: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeKind {
+    Saved,
+    Cleared,
+    /// Another profile became the active one
+    Activated,
+}
+
+/// Payload of the `config-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChanged {
+    pub kind: ConfigChangeKind,
+    pub profile: String,
+    /// Whether the changed profile is the one `load_sandbox_config` uses
+    pub active: bool,
+    /// The profile's configuration with the API key redacted; None once cleared
+    pub config: Option<SandboxConfig>,
+    pub changed_at: String,
+}
+
+/// A named Sandbox configuration, without its API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfileSummary {
+    pub name: String,
+    /// Whether `load_sandbox_config` and background tasks use this profile
+    pub active: bool,
+    pub base_url: String,
+    #[serde(default)]
+    pub default_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigBackupReason {
+    /// Taken before a save, import, login or restore replaced the configuration
+    Overwritten,
+    Cleared,
+}
+
+/// A saved configuration kept before it was replaced, restorable with
+/// `restore_config_backup`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackup {
+    pub id: String,
+    pub profile: String,
+    pub created_at: String,
+    pub reason: ConfigBackupReason,
+    pub base_url: String,
+    #[serde(default)]
+    pub default_model: Option<String>,
+    /// Whether the backup holds the API key; backups taken while the app lock keeps the
+    /// key don't, and restoring them keeps the profile's current key
+    pub has_api_key: bool,
+}
+
+/// Daily and monthly usage limits; unset limits are not checked
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub daily_tokens: Option<u64>,
+    #[serde(default)]
+    pub monthly_tokens: Option<u64>,
+    #[serde(default)]
+    pub daily_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub monthly_cost_usd: Option<f64>,
+    /// Price used to turn token counts into cost; cost limits need it
+    #[serde(default)]
+    pub usd_per_1k_tokens: Option<f64>,
+    /// Show an OS notification when a threshold is crossed
+    #[serde(default)]
+    pub notify: bool,
+    /// Refuse to start new runs while any limit is exceeded
+    #[serde(default)]
+    pub block_when_exceeded: bool,
+}
+
+/// Output-inactivity watchdog for streaming runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchdogConfig {
+    /// Seconds without stdout/stderr output before a run counts as stalled
+    pub stall_after_secs: u64,
+    /// Send the run an interrupt (Ctrl-C) when it stalls
+    #[serde(default)]
+    pub auto_interrupt: bool,
+}
+
+/// Storage retention limits; unset limits are not enforced
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionConfig {
+    /// Most recent runs kept in history
+    #[serde(default)]
+    pub max_runs: Option<usize>,
+    /// Total size of the app log files
+    #[serde(default)]
+    pub max_log_bytes: Option<u64>,
+    /// Runs and log files older than this are removed
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+/// Limits how many streaming runs execute at once
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunQueueConfig {
+    /// Runs allowed to execute at the same time; further runs wait in priority order
+    pub max_concurrent_runs: usize,
+    /// Pause lower-priority runs to make room for a higher-priority one (Unix only)
+    #[serde(default)]
+    pub preempt: bool,
+}
+
+/// Network egress monitoring for streaming runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressConfig {
+    /// Host names or IP addresses runs are expected to reach; the Sandbox host and
+    /// loopback addresses are always allowed
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Seconds between connection samples; defaults to `DEFAULT_EGRESS_SAMPLE_SECS`
+    #[serde(default)]
+    pub sample_interval_secs: Option<u64>,
+}
+
+/// Network settings of the shared Sandbox HTTP client
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpClientConfig {
+    /// Proxy for all Sandbox requests, e.g. `http://proxy.corp:8080`; the system proxy
+    /// settings apply when unset
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    /// PEM file with a root certificate to trust in addition to the system ones, for
+    /// proxies or self-hosted Sandboxes using a private CA
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+}
+
+/// Endpoint probed to check that the Sandbox is up
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckConfig {
+    /// Path at the Sandbox root (without any `/api/v1` suffix of the base URL); defaults to
+    /// `DEFAULT_HEALTH_CHECK_PATH`
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub method: Option<HealthCheckMethod>,
+    /// Statuses that count as healthy; any 2xx when unset. A 401 always counts as reachable
+    /// with a rejected API key
+    #[serde(default)]
+    pub expected_statuses: Option<Vec<u16>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthCheckMethod {
+    #[default]
+    Get,
+    Head,
+    Post,
+}
+
+/// Sandbox health endpoint unless the profile overrides it
+pub const DEFAULT_HEALTH_CHECK_PATH: &str = "/health";
+
+impl HealthCheckConfig {
+    /// Configured path with a leading slash, or the default
+    pub fn path(&self) -> String {
+        match self.path.as_deref().map(str::trim) {
+            Some(path) if !path.is_empty() => format!("/{}", path.trim_start_matches('/')),
+            _ => DEFAULT_HEALTH_CHECK_PATH.to_string(),
+        }
+    }
+
+    pub fn is_expected_status(&self, status: u16) -> bool {
+        match &self.expected_statuses {
+            Some(statuses) => statuses.contains(&status),
+            None => (200..300).contains(&status),
+        }
+    }
+}
+
+/// Destination for telemetry events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TelemetrySink {
+    /// POST to the Sandbox with its API key; `path` is relative to the base URL and
+    /// defaults to `DEFAULT_TELEMETRY_PATH`
+    Sandbox {
+        #[serde(default)]
+        path: Option<String>,
+    },
+    /// POST to a self-hosted collector, without the Sandbox API key
+    Webhook { url: String },
+    /// Append events as JSON lines to a local file
+    File { path: String },
+}
+
+impl Default for TelemetrySink {
+    fn default() -> Self {
+        TelemetrySink::Sandbox { path: None }
+    }
+}
+
+impl TelemetrySink {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TelemetrySink::Sandbox { .. } => "sandbox",
+            TelemetrySink::Webhook { .. } => "webhook",
+            TelemetrySink::File { .. } => "file",
+        }
+    }
+}
+
+/// Sandbox telemetry endpoint unless the sink overrides it
+pub const DEFAULT_TELEMETRY_PATH: &str = "/telemetry/cli";
+
+/// Connection sampling interval unless the config overrides it
+pub const DEFAULT_EGRESS_SAMPLE_SECS: u64 = 2;
+
+/// Audit log retention unless the config overrides it
+pub const DEFAULT_AUDIT_RETENTION_DAYS: u32 = 90;
+
+/// Subcommands Custom runs may invoke unless the config overrides the allowlist
+pub const DEFAULT_CUSTOM_SUBCOMMANDS: &[&str] = &[
+    "--help",
+    "-h",
+    "--version",
+    "-v",
+    "agent",
+    "create",
+    "dev",
+    "env",
+    "plugins",
+    "scenario",
+    "start",
+    "test",
+    "update",
+];
+
 impl SandboxConfig {
     pub fn new(base_url: String, api_key: String) -> Self {
         Self {
             base_url,
             api_key,
             default_model: None,
+            runner_priority: None,
+            extra_path_dirs: None,
+            allowed_custom_subcommands: None,
+            audit_retention_days: None,
+            budget: None,
+            watchdog: None,
+            retention: None,
+            queue: None,
+            egress: None,
+            prevent_sleep: false,
+            auto_retry_port_conflicts: false,
+            api_key_ref: None,
+            telemetry_sink: None,
+            http: None,
+            health_check: None,
+            trusted_project_hosts: None,
         }
     }
 
@@ -30,6 +371,57 @@ impl SandboxConfig {
         self
     }
 
+    pub fn with_runner_priority(mut self, priority: Vec<CliRunner>) -> Self {
+        self.runner_priority = Some(priority);
+        self
+    }
+
+    pub fn with_extra_path_dirs(mut self, dirs: Vec<String>) -> Self {
+        self.extra_path_dirs = Some(dirs);
+        self
+    }
+
+    /// Extra PATH directories, empty when none are configured
+    pub fn extra_path_dirs(&self) -> &[String] {
+        self.extra_path_dirs.as_deref().unwrap_or_default()
+    }
+
+    /// Audit log retention in days, falling back to the default
+    pub fn audit_retention_days(&self) -> u32 {
+        self.audit_retention_days
+            .unwrap_or(DEFAULT_AUDIT_RETENTION_DAYS)
+    }
+
+    /// Subcommands permitted in Custom runs, falling back to the default allowlist
+    pub fn allowed_custom_subcommands(&self) -> Vec<String> {
+        match self.allowed_custom_subcommands {
+            Some(ref allowed) => allowed.clone(),
+            None => DEFAULT_CUSTOM_SUBCOMMANDS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+
+    /// Runner priority to use, falling back to the default order
+    pub fn runner_priority(&self) -> Vec<CliRunner> {
+        match self.runner_priority {
+            Some(ref priority) if !priority.is_empty() => priority.clone(),
+            _ => CliRunner::default_priority(),
+        }
+    }
+
+    /// URL of a Sandbox API endpoint, whether or not the base URL already ends in `/api/v1`
+    pub fn api_url(&self, path: &str) -> String {
+        let base_url = self.base_url.trim_end_matches('/');
+        let path = path.trim_start_matches('/');
+        if base_url.ends_with("/api/v1") {
+            format!("{}/{}", base_url, path)
+        } else {
+            format!("{}/api/v1/{}", base_url, path)
+        }
+    }
+
     pub fn is_valid(&self) -> bool {
         !self.base_url.is_empty()
             && !self.api_key.is_empty()
@@ -63,6 +455,99 @@ impl std::fmt::Display for RunMode {
     }
 }
 
+/// CLI package spec that `bunx` and `npx` runs pin
+pub const ELIZAOS_CLI_PACKAGE: &str = "@elizaos/cli@latest";
+
+/// How the ElizaOS CLI is launched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CliRunner {
+    /// Globally installed `elizaos` binary
+    Elizaos,
+    /// `bunx @elizaos/cli` - much faster startup than npx
+    Bunx,
+    /// `npx -y @elizaos/cli@latest`
+    Npx,
+    /// Scripted `mock-eliza` stand-in used by end-to-end tests
+    #[cfg(feature = "test-harness")]
+    Mock,
+}
+
+impl CliRunner {
+    pub fn default_priority() -> Vec<CliRunner> {
+        vec![CliRunner::Elizaos, CliRunner::Bunx, CliRunner::Npx]
+    }
+
+    /// Executable to spawn for this runner
+    pub fn program(&self) -> &'static str {
+        match self {
+            CliRunner::Elizaos => "elizaos",
+            CliRunner::Bunx => "bunx",
+            CliRunner::Npx => "npx",
+            #[cfg(feature = "test-harness")]
+            CliRunner::Mock => crate::test_harness::MOCK_CLI_PROGRAM,
+        }
+    }
+
+    /// Arguments that must precede the ElizaOS subcommand
+    pub fn package_args(&self) -> Vec<String> {
+        match self {
+            CliRunner::Elizaos => Vec::new(),
+            #[cfg(feature = "test-harness")]
+            CliRunner::Mock => Vec::new(),
+            CliRunner::Bunx => vec![ELIZAOS_CLI_PACKAGE.to_string()],
+            CliRunner::Npx => vec!["-y".to_string(), ELIZAOS_CLI_PACKAGE.to_string()],
+        }
+    }
+
+    /// `package_args` for a version probe, which must not download the package
+    pub fn offline_package_args(&self) -> Vec<String> {
+        match self {
+            CliRunner::Npx => vec!["--no-install".to_string(), ELIZAOS_CLI_PACKAGE.to_string()],
+            _ => self.package_args(),
+        }
+    }
+
+    /// Package spec fetched by a package runner
+    pub fn package_spec(&self) -> Option<&'static str> {
+        match self {
+            CliRunner::Elizaos => None,
+            #[cfg(feature = "test-harness")]
+            CliRunner::Mock => None,
+            CliRunner::Bunx | CliRunner::Npx => Some(ELIZAOS_CLI_PACKAGE),
+        }
+    }
+}
+
+impl std::fmt::Display for CliRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program())
+    }
+}
+
+/// Outcome of probing a single runner during CLI resolution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerProbe {
+    pub runner: CliRunner,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub available: bool,
+    pub rejection_reason: Option<String>,
+}
+
+/// Explains which ElizaOS CLI will be used and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliResolutionReport {
+    pub selected: Option<CliRunner>,
+    pub selected_path: Option<String>,
+    pub selected_version: Option<String>,
+    pub path_entries: Vec<String>,
+    pub probes: Vec<RunnerProbe>,
+    pub cached: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RunSpec {
@@ -72,6 +557,40 @@ pub struct RunSpec {
     pub env: HashMap<String, String>,
     pub working_dir: Option<String>,
     pub character_file: Option<String>,
+    /// Evaluation parameters, required for `RunMode::Eval`
+    #[serde(default)]
+    pub eval: Option<EvalSpec>,
+    /// Server port for agent runs; assigned automatically for run groups
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Run that must finish first; only honored by `schedule_runs`
+    #[serde(default)]
+    pub after: Option<RunDependency>,
+    /// Generate fake agent output instead of spawning the CLI (demo mode)
+    #[serde(default)]
+    pub simulate: bool,
+    /// Streamed lines below this severity are kept in the run's output but not emitted
+    /// as `log-event`s
+    #[serde(default)]
+    pub min_event_severity: Option<LogSeverity>,
+    /// Queue priority; defaults to high for doctor checks, low for evals, normal otherwise
+    #[serde(default)]
+    pub priority: Option<RunPriority>,
+    /// Route the run's HTTP(S) traffic through a local recording proxy
+    #[serde(default)]
+    pub capture_network: bool,
+    /// User-defined `{{name}}` values for args and env, resolved at spawn time
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+/// Order in which queued runs start, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunPriority {
+    Low,
+    Normal,
+    High,
 }
 
 impl RunSpec {
@@ -83,6 +602,14 @@ impl RunSpec {
             env: HashMap::new(),
             working_dir: None,
             character_file: None,
+            eval: None,
+            port: None,
+            after: None,
+            simulate: false,
+            min_event_severity: None,
+            priority: None,
+            capture_network: false,
+            variables: HashMap::new(),
         }
     }
 
@@ -95,416 +622,2905 @@ impl RunSpec {
         self.working_dir = Some(dir);
         self
     }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum RunStatus {
-    Running,
-    Completed,
-    Failed,
-    Killed,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RunResult {
-    pub id: String,
-    pub spec: RunSpec,
-    pub started_at: String, // ISO 8601 timestamp
-    pub ended_at: Option<String>,
-    pub exit_code: Option<i32>,
-    pub stdout: Vec<String>,
-    pub stderr: Vec<String>,
-    pub duration_ms: Option<u64>,
-    pub status: RunStatus,
-    pub pid: Option<u32>, // Process ID for active process management
-}
 
-impl RunResult {
-    pub fn new(spec: RunSpec, run_id: String) -> Self {
-        Self {
-            id: run_id,
-            spec,
-            started_at: current_timestamp(),
-            ended_at: None,
-            exit_code: None,
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-            duration_ms: None,
-            status: RunStatus::Running,
-            pid: None, // Will be set when process starts
-        }
+    pub fn with_eval(mut self, eval: EvalSpec) -> Self {
+        self.eval = Some(eval);
+        self
     }
 
-    pub fn with_pid(mut self, pid: u32) -> Self {
-        self.pid = Some(pid);
+    pub fn after(mut self, run_id: String, condition: DependencyCondition) -> Self {
+        self.after = Some(RunDependency { run_id, condition });
         self
     }
 
-    pub fn complete(mut self, exit_code: i32, ended_at: String, duration_ms: u64) -> Self {
-        self.exit_code = Some(exit_code);
-        self.ended_at = Some(ended_at);
-        self.duration_ms = Some(duration_ms);
-        self.status = if exit_code == 0 {
-            RunStatus::Completed
-        } else {
-            RunStatus::Failed
-        };
+    pub fn simulated(mut self) -> Self {
+        self.simulate = true;
         self
     }
 
-    pub fn kill(mut self, ended_at: String, duration_ms: u64) -> Self {
-        self.ended_at = Some(ended_at);
-        self.duration_ms = Some(duration_ms);
-        self.status = RunStatus::Killed;
-        self
+    /// Priority the run queues with
+    pub fn effective_priority(&self) -> RunPriority {
+        self.priority.unwrap_or(match self.mode {
+            RunMode::Doctor => RunPriority::High,
+            RunMode::Eval => RunPriority::Low,
+            RunMode::Run | RunMode::Custom => RunPriority::Normal,
+        })
     }
 }
 
 // ============================================================================
-// Preflight Check Models
+// Run History Models
 // ============================================================================
 
+/// A user note attached to one line of a run's log
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolCheck {
-    pub installed: bool,
-    pub version: Option<String>,
-    pub path: Option<String>,
+#[serde(rename_all = "camelCase")]
+pub struct RunAnnotation {
+    pub id: String,
+    /// 1-based line number in the run's log
+    pub line_no: usize,
+    pub text: String,
+    pub created_at: String,
 }
 
-impl ToolCheck {
-    pub fn not_found() -> Self {
-        Self {
-            installed: false,
-            version: None,
-            path: None,
-        }
-    }
+/// Free-form note and line annotations for a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunNotes {
+    pub run_id: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<RunAnnotation>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
 
-    pub fn found(version: String, path: String) -> Self {
-        Self {
-            installed: true,
-            version: Some(version),
-            path: Some(path),
-        }
-    }
+/// A run from the history: its final result, when recorded, and the user's notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub result: Option<RunResult>,
+    pub notes: RunNotes,
+}
+
+// ============================================================================
+// Storage Models
+// ============================================================================
+
+/// Disk used by one top-level entry of the app data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCategoryUsage {
+    pub category: String,
+    pub bytes: u64,
+    pub files: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub path: String,
+    pub total_bytes: u64,
+    /// Largest first
+    pub categories: Vec<StorageCategoryUsage>,
+}
+
+/// What one pass of the storage vacuum removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacuumReport {
+    pub runs_removed: usize,
+    pub log_files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// Package runner whose cache holds a CLI install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub enum PreflightStatus {
-    Ready,
-    NeedsSetup,
-    CriticalIssues,
+pub enum CliCacheKind {
+    Npx,
+    Bunx,
 }
 
+/// A stale cache entry found by `clean_cli_caches`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct PreflightResult {
-    pub node: ToolCheck,
-    pub npm: ToolCheck,
-    pub eliza: ToolCheck,
-    pub recommendations: Vec<String>,
-    pub overall_status: PreflightStatus,
+pub struct CliCacheEntry {
+    pub kind: CliCacheKind,
+    pub path: String,
+    /// `name@spec` of the packages the entry provides
+    pub packages: Vec<String>,
+    pub bytes: u64,
+    pub last_modified: String,
+    pub removed: bool,
 }
 
-impl PreflightResult {
-    pub fn new(node: ToolCheck, npm: ToolCheck, eliza: ToolCheck) -> Self {
-        let mut recommendations = Vec::new();
-        let overall_status = Self::determine_status(&node, &npm, &eliza, &mut recommendations);
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliCacheReport {
+    pub entries: Vec<CliCacheEntry>,
+    /// Bytes freed, or that would be freed on a dry run
+    pub reclaimed_bytes: u64,
+    pub dry_run: bool,
+}
 
-        Self {
-            node,
-            npm,
-            eliza,
-            recommendations,
-            overall_status,
-        }
-    }
+// ============================================================================
+// App Lock Models
+// ============================================================================
 
-    fn determine_status(
-        node: &ToolCheck,
-        npm: &ToolCheck,
-        eliza: &ToolCheck,
-        recommendations: &mut Vec<String>,
-    ) -> PreflightStatus {
-        let mut critical_issues = 0;
-        let mut needs_setup = 0;
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    /// The vault must be unlocked before the API key or secrets can be used
+    pub locked: bool,
+    /// Minutes without use before locking again; `0` never locks automatically
+    pub auto_lock_minutes: Option<u32>,
+}
 
-        if !node.installed {
-            critical_issues += 1;
-            recommendations.push("Install Node.js 18+ from https://nodejs.org/".to_string());
-        } else if let Some(ref version) = node.version {
-            if !Self::is_node_version_compatible(version) {
-                critical_issues += 1;
-                recommendations.push("Update Node.js to version 18 or higher".to_string());
-            }
+// ============================================================================
+// Permission Models
+// ============================================================================
+
+/// Who is using the app: admins can do everything, operators only work with runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionProfile {
+    #[default]
+    Admin,
+    Operator,
+}
+
+impl PermissionProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionProfile::Admin => "admin",
+            PermissionProfile::Operator => "operator",
         }
+    }
+}
 
-        if !npm.installed {
-            needs_setup += 1;
-            recommendations.push("Install npm (usually comes with Node.js)".to_string());
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionStatus {
+    pub profile: PermissionProfile,
+    /// Commands only the admin profile may call
+    pub admin_commands: Vec<String>,
+}
+
+// ============================================================================
+// Session Models
+// ============================================================================
+
+/// A run that was still active when the previous session ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedRun {
+    pub run_id: String,
+    pub mode: RunMode,
+    pub pid: Option<u32>,
+    pub started_at: String,
+    /// The process outlived the app and is still running
+    pub orphaned: bool,
+}
+
+/// A schedule whose remaining runs never started because the app closed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissedSchedule {
+    pub schedule_id: String,
+    pub started_at: String,
+    /// Spec IDs of the runs that were still pending
+    pub pending_runs: Vec<String>,
+}
+
+/// What happened since the last session, produced once at launch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub previous_session_started_at: Option<String>,
+    pub interrupted_runs: Vec<InterruptedRun>,
+    pub missed_schedules: Vec<MissedSchedule>,
+    pub config_migrations: Vec<String>,
+    pub generated_at: String,
+}
+
+// ============================================================================
+// Run Scheduling Models
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyCondition {
+    /// Dependency must exit successfully
+    #[default]
+    Success,
+    /// Dependency must finish, regardless of outcome
+    Completion,
+    /// Dependency must fail (e.g. run a fallback)
+    Failure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDependency {
+    /// ID of the spec in the same schedule that must run first
+    pub run_id: String,
+    #[serde(default)]
+    pub condition: DependencyCondition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledRunStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Killed,
+    /// Not started because its dependency condition was not met
+    Skipped,
+}
+
+impl ScheduledRunStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(
+            self,
+            ScheduledRunStatus::Pending | ScheduledRunStatus::Running
+        )
+    }
+}
+
+impl From<RunStatus> for ScheduledRunStatus {
+    fn from(status: RunStatus) -> Self {
+        match status {
+            RunStatus::Running => ScheduledRunStatus::Running,
+            RunStatus::Completed => ScheduledRunStatus::Completed,
+            RunStatus::Failed => ScheduledRunStatus::Failed,
+            RunStatus::Killed => ScheduledRunStatus::Killed,
         }
+    }
+}
 
-        if !eliza.installed {
-            needs_setup += 1;
-            recommendations.push("ElizaOS CLI will be installed automatically via npx".to_string());
+impl DependencyCondition {
+    /// Whether a dependency that finished with `status` lets the dependent run start
+    pub fn is_met(&self, status: ScheduledRunStatus) -> bool {
+        match self {
+            DependencyCondition::Success => status == ScheduledRunStatus::Completed,
+            DependencyCondition::Completion => matches!(
+                status,
+                ScheduledRunStatus::Completed
+                    | ScheduledRunStatus::Failed
+                    | ScheduledRunStatus::Killed
+            ),
+            DependencyCondition::Failure => {
+                matches!(
+                    status,
+                    ScheduledRunStatus::Failed | ScheduledRunStatus::Killed
+                )
+            }
         }
+    }
+}
 
-        if critical_issues > 0 {
-            PreflightStatus::CriticalIssues
-        } else if needs_setup > 0 {
-            PreflightStatus::NeedsSetup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRun {
+    pub spec_id: String,
+    pub run_id: Option<String>,
+    pub after: Option<RunDependency>,
+    pub status: ScheduledRunStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSchedule {
+    pub id: String,
+    pub runs: Vec<ScheduledRun>,
+    pub started_at: String,
+    pub finished: bool,
+}
+
+// ============================================================================
+// Run Group Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunGroupMember {
+    pub run_id: String,
+    pub character_file: Option<String>,
+    pub port: Option<u16>,
+    pub status: RunStatus,
+    pub exit_code: Option<i32>,
+}
+
+/// Several agents launched and controlled together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunGroup {
+    pub id: String,
+    pub members: Vec<RunGroupMember>,
+    pub status: RunStatus,
+    pub started_at: String,
+}
+
+impl RunGroup {
+    pub fn new(id: String, members: Vec<RunGroupMember>) -> Self {
+        Self {
+            id,
+            members,
+            status: RunStatus::Running,
+            started_at: current_timestamp(),
+        }
+    }
+
+    /// Recompute the aggregate status after a member changes
+    pub fn refresh_status(&mut self) {
+        self.status = Self::aggregate_status(&self.members);
+    }
+
+    /// Running while any member runs; otherwise killed, failed, or completed in that order
+    fn aggregate_status(members: &[RunGroupMember]) -> RunStatus {
+        let any = |status: RunStatus| members.iter().any(|m| m.status == status);
+
+        if any(RunStatus::Running) {
+            RunStatus::Running
+        } else if any(RunStatus::Killed) {
+            RunStatus::Killed
+        } else if any(RunStatus::Failed) {
+            RunStatus::Failed
         } else {
-            PreflightStatus::Ready
+            RunStatus::Completed
         }
     }
+}
 
-    fn is_node_version_compatible(version: &str) -> bool {
-        // Extract major version number
-        version
-            .split('.')
-            .next()
-            .and_then(|v| v.trim_start_matches('v').parse::<u32>().ok())
-            .map_or(false, |major| major >= 18)
+// ============================================================================
+// Eval Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalSpec {
+    /// Scenario or dataset file to evaluate
+    pub scenario_path: String,
+    pub iterations: Option<u32>,
+    /// Where the CLI writes its JSON report; defaults to the temp directory
+    pub report_path: Option<String>,
+}
+
+impl EvalSpec {
+    pub fn new(scenario_path: String) -> Self {
+        Self {
+            scenario_path,
+            iterations: None,
+            report_path: None,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    pub fn with_report_path(mut self, report_path: String) -> Self {
+        self.report_path = Some(report_path);
+        self
+    }
+
+    /// Report path for a given run, falling back to a per-run temp file; characters
+    /// other than `[A-Za-z0-9_-]` in the ID are replaced, so the file stays in the temp dir
+    pub fn report_path_for(&self, run_id: &str) -> String {
+        self.report_path.clone().unwrap_or_else(|| {
+            let name: String = run_id
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            std::env::temp_dir()
+                .join(format!("eliza-eval-{}.json", name))
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalCaseResult {
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalResult {
+    pub report_path: String,
+    pub total: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub pass_rate: f64,
+    pub cases: Vec<EvalCaseResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Running,
+    Completed,
+    Failed,
+    Killed,
+}
+
+impl RunStatus {
+    /// Serialized name of the status
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+            RunStatus::Killed => "killed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResult {
+    pub id: String,
+    pub spec: RunSpec,
+    pub started_at: String, // ISO 8601 timestamp
+    pub ended_at: Option<String>,
+    pub exit_code: Option<i32>,
+    pub stdout: Vec<String>,
+    pub stderr: Vec<String>,
+    pub duration_ms: Option<u64>,
+    pub status: RunStatus,
+    pub pid: Option<u32>, // Process ID for active process management
+    #[serde(default)]
+    pub eval_result: Option<EvalResult>,
+    #[serde(default)]
+    pub doctor_report: Option<DoctorReport>,
+    /// Whether binary output was replaced with placeholders in stdout/stderr
+    #[serde(default)]
+    pub binary_output: bool,
+    /// Endpoints the run connected to, when egress monitoring is on
+    #[serde(default)]
+    pub network: Option<ConnectionSummary>,
+    /// Toolchain the run executed on, captured at start
+    #[serde(default)]
+    pub environment: Option<RunEnvironment>,
+}
+
+/// Tool versions and platform a run executed on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunEnvironment {
+    pub node_version: Option<String>,
+    pub npm_version: Option<String>,
+    pub cli_runner: CliRunner,
+    /// Package spec a package runner fetches, e.g. `@elizaos/cli@latest`
+    #[serde(default)]
+    pub cli_package: Option<String>,
+    /// Installed CLI version; package runners report only the pinned spec
+    #[serde(default)]
+    pub cli_version: Option<String>,
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    pub app_version: String,
+}
+
+/// A remote endpoint a run was seen connected to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConnection {
+    pub protocol: String,
+    pub remote_address: String,
+    pub remote_port: u16,
+    /// Allowed host name the address belongs to
+    #[serde(default)]
+    pub host_name: Option<String>,
+    pub allowed: bool,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// Samples the connection appeared in
+    pub samples: u32,
+}
+
+/// Network endpoints a run connected to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSummary {
+    pub connections: Vec<NetworkConnection>,
+    /// Remote addresses not on the allow list
+    pub unexpected_addresses: Vec<String>,
+    pub samples: u32,
+}
+
+/// One outbound call recorded by a run's capture proxy; secrets are redacted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedRequest {
+    pub id: u64,
+    pub method: String,
+    pub url: String,
+    /// Missing for tunnels and failed requests
+    pub status: Option<u16>,
+    /// Milliseconds since the Unix epoch
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub request_headers: HashMap<String, String>,
+    pub response_headers: HashMap<String, String>,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// HTTPS passes through an opaque tunnel, so only the host and sizes are known
+    pub tunneled: bool,
+    pub error: Option<String>,
+}
+
+/// Outbound calls a run made through its capture proxy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkCapture {
+    pub run_id: String,
+    pub proxy_url: String,
+    pub requests: Vec<CapturedRequest>,
+    /// Calls not recorded because the capture was full
+    pub dropped: u64,
+    pub finished: bool,
+}
+
+/// Payload of the `egress-alert` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressAlertEvent {
+    pub run_id: String,
+    pub protocol: String,
+    pub remote_address: String,
+    pub remote_port: u16,
+    pub timestamp: i64,
+}
+
+impl RunResult {
+    pub fn new(spec: RunSpec, run_id: String) -> Self {
+        Self {
+            id: run_id,
+            spec,
+            started_at: current_timestamp(),
+            ended_at: None,
+            exit_code: None,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            duration_ms: None,
+            status: RunStatus::Running,
+            pid: None, // Will be set when process starts
+            eval_result: None,
+            doctor_report: None,
+            binary_output: false,
+            network: None,
+            environment: None,
+        }
+    }
+
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    pub fn complete(mut self, exit_code: i32, ended_at: String, duration_ms: u64) -> Self {
+        self.exit_code = Some(exit_code);
+        self.ended_at = Some(ended_at);
+        self.duration_ms = Some(duration_ms);
+        self.status = if exit_code == 0 {
+            RunStatus::Completed
+        } else {
+            RunStatus::Failed
+        };
+        self
+    }
+
+    pub fn kill(mut self, ended_at: String, duration_ms: u64) -> Self {
+        self.ended_at = Some(ended_at);
+        self.duration_ms = Some(duration_ms);
+        self.status = RunStatus::Killed;
+        self
     }
 }
 
 // ============================================================================
-// Telemetry Models
+// Eval Matrix Models
+// ============================================================================
+
+/// One eval run of an experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalMatrixRun {
+    pub run_id: String,
+    pub status: RunStatus,
+    pub duration_ms: Option<u64>,
+    /// Pass rate from the eval report, when one was written
+    pub pass_rate: Option<f64>,
+    /// Estimated from the run's output
+    pub approx_tokens: u64,
+    pub error: Option<String>,
+    /// Seed exported to the run, when the experiment has one
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Aggregates over the runs of one character/model combination
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalMatrixSummary {
+    pub runs: u32,
+    /// Runs that exited cleanly with every eval case passing
+    pub succeeded: u32,
+    pub success_rate: f64,
+    pub mean_pass_rate: Option<f64>,
+    pub mean_duration_ms: Option<f64>,
+    pub total_tokens: u64,
+    pub mean_tokens: f64,
+}
+
+impl EvalMatrixSummary {
+    pub fn from_runs(runs: &[EvalMatrixRun]) -> Self {
+        fn mean(values: &[f64]) -> Option<f64> {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        }
+
+        let succeeded = runs
+            .iter()
+            .filter(|run| {
+                run.status == RunStatus::Completed && run.pass_rate.is_none_or(|rate| rate >= 1.0)
+            })
+            .count() as u32;
+        let pass_rates: Vec<f64> = runs.iter().filter_map(|run| run.pass_rate).collect();
+        let durations: Vec<f64> = runs
+            .iter()
+            .filter_map(|run| run.duration_ms.map(|ms| ms as f64))
+            .collect();
+        let total_tokens = runs.iter().map(|run| run.approx_tokens).sum();
+        let count = runs.len() as u32;
+
+        Self {
+            runs: count,
+            succeeded,
+            success_rate: if count > 0 {
+                succeeded as f64 / count as f64
+            } else {
+                0.0
+            },
+            mean_pass_rate: mean(&pass_rates),
+            mean_duration_ms: mean(&durations),
+            total_tokens,
+            mean_tokens: if count > 0 {
+                total_tokens as f64 / count as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Results for one character/model combination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalMatrixCell {
+    pub character_id: String,
+    pub model: String,
+    pub runs: Vec<EvalMatrixRun>,
+    pub summary: EvalMatrixSummary,
+}
+
+/// A finished eval matrix, stored as one experiment record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Experiment {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub scenario_path: String,
+    pub character_ids: Vec<String>,
+    pub models: Vec<String>,
+    pub iterations: u32,
+    /// Base seed; iteration `n` of every character/model pair runs with `seed + n`
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub started_at: String,
+    pub ended_at: String,
+    pub cells: Vec<EvalMatrixCell>,
+}
+
+/// Experiment listing entry, without per-run results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentSummary {
+    pub id: String,
+    pub name: Option<String>,
+    pub scenario_path: String,
+    pub character_ids: Vec<String>,
+    pub models: Vec<String>,
+    pub iterations: u32,
+    pub seed: Option<u64>,
+    pub started_at: String,
+    pub ended_at: String,
+    pub overall: EvalMatrixSummary,
+}
+
+/// Aggregates for one arm of an experiment (a character or a model)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentVariant {
+    pub key: String,
+    pub summary: EvalMatrixSummary,
+    /// Spread of pass rates across the arm's runs
+    pub pass_rate_std_dev: Option<f64>,
+}
+
+/// Aggregate statistics of an experiment for comparing its arms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentResults {
+    pub experiment: ExperimentSummary,
+    pub by_character: Vec<ExperimentVariant>,
+    pub by_model: Vec<ExperimentVariant>,
+    /// Character with the highest mean pass rate, then success rate
+    pub best_character: Option<String>,
+}
+
+// ============================================================================
+// Doctor Models
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DoctorCheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    pub id: String,
+    pub name: String,
+    pub status: DoctorCheckStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+impl DoctorCheck {
+    pub fn new(id: &str, name: &str, status: DoctorCheckStatus, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            message,
+            duration_ms: 0,
+        }
+    }
+
+    pub fn with_duration(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+    pub overall_status: DoctorCheckStatus,
+    pub started_at: String,
+    pub duration_ms: u64,
+}
+
+impl DoctorReport {
+    pub fn new(checks: Vec<DoctorCheck>, started_at: String, duration_ms: u64) -> Self {
+        let overall_status = Self::determine_status(&checks);
+        Self {
+            checks,
+            overall_status,
+            started_at,
+            duration_ms,
+        }
+    }
+
+    /// Worst status across all checks; skipped checks don't affect the outcome
+    fn determine_status(checks: &[DoctorCheck]) -> DoctorCheckStatus {
+        if checks.iter().any(|c| c.status == DoctorCheckStatus::Fail) {
+            DoctorCheckStatus::Fail
+        } else if checks.iter().any(|c| c.status == DoctorCheckStatus::Warn) {
+            DoctorCheckStatus::Warn
+        } else {
+            DoctorCheckStatus::Pass
+        }
+    }
+}
+
+/// Emitted as `doctor-progress` after each diagnostic check completes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorProgressEvent {
+    pub run_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub check: DoctorCheck,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailureConfidence {
+    Low,
+    Medium,
+    High,
+}
+
+/// A known failure the run's output matches, with how to fix it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureSuggestion {
+    /// Stable id of the failure signature, e.g. `port_in_use`
+    pub id: String,
+    pub title: String,
+    pub confidence: FailureConfidence,
+    /// Output lines that matched, redacted
+    pub evidence: Vec<String>,
+    /// Steps to try, in order
+    pub remediation: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunFailureDiagnosis {
+    pub run_id: String,
+    pub status: RunStatus,
+    pub exit_code: Option<i32>,
+    /// Most likely cause first; empty when no known failure matched
+    pub suggestions: Vec<FailureSuggestion>,
+    pub diagnosed_at: String,
+}
+
+// ============================================================================
+// Preflight Check Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCheck {
+    pub installed: bool,
+    pub version: Option<String>,
+    pub path: Option<String>,
+}
+
+impl ToolCheck {
+    pub fn not_found() -> Self {
+        Self {
+            installed: false,
+            version: None,
+            path: None,
+        }
+    }
+
+    pub fn found(version: String, path: String) -> Self {
+        Self {
+            installed: true,
+            version: Some(version),
+            path: Some(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightStatus {
+    Ready,
+    NeedsSetup,
+    CriticalIssues,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightResult {
+    pub node: ToolCheck,
+    pub npm: ToolCheck,
+    pub eliza: ToolCheck,
+    pub recommendations: Vec<String>,
+    pub overall_status: PreflightStatus,
+}
+
+impl PreflightResult {
+    pub fn new(node: ToolCheck, npm: ToolCheck, eliza: ToolCheck) -> Self {
+        let mut recommendations = Vec::new();
+        let overall_status = Self::determine_status(&node, &npm, &eliza, &mut recommendations);
+
+        Self {
+            node,
+            npm,
+            eliza,
+            recommendations,
+            overall_status,
+        }
+    }
+
+    fn determine_status(
+        node: &ToolCheck,
+        npm: &ToolCheck,
+        eliza: &ToolCheck,
+        recommendations: &mut Vec<String>,
+    ) -> PreflightStatus {
+        let mut critical_issues = 0;
+        let mut needs_setup = 0;
+
+        if !node.installed {
+            critical_issues += 1;
+            recommendations.push(t(MessageId::RecommendInstallNode));
+        } else if let Some(ref version) = node.version {
+            if !Self::is_node_version_compatible(version) {
+                critical_issues += 1;
+                recommendations.push(t(MessageId::RecommendUpdateNode));
+            }
+        }
+
+        if !npm.installed {
+            needs_setup += 1;
+            recommendations.push(t(MessageId::RecommendInstallNpm));
+        }
+
+        if !eliza.installed {
+            needs_setup += 1;
+            recommendations.push(t(MessageId::CliInstalledViaNpx));
+        }
+
+        if critical_issues > 0 {
+            PreflightStatus::CriticalIssues
+        } else if needs_setup > 0 {
+            PreflightStatus::NeedsSetup
+        } else {
+            PreflightStatus::Ready
+        }
+    }
+
+    fn is_node_version_compatible(version: &str) -> bool {
+        // Extract major version number
+        version
+            .split('.')
+            .next()
+            .and_then(|v| v.trim_start_matches('v').parse::<u32>().ok())
+            .is_some_and(|major| major >= 18)
+    }
+}
+
+/// A preflight result recorded at the time the check ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightSnapshot {
+    pub timestamp: String,
+    pub result: PreflightResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightChangeKind {
+    Appeared,
+    Disappeared,
+    Upgraded,
+    Downgraded,
+    VersionChanged,
+    Moved,
+    StatusChanged,
+}
+
+/// One difference between two preflight snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightChange {
+    /// `node`, `npm`, `eliza`, or `overall`
+    pub tool: String,
+    pub kind: PreflightChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub message: String,
+}
+
+/// Environment drift between two snapshots; `from`/`to` are None when no history exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightDiff {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub changes: Vec<PreflightChange>,
+}
+
+// ============================================================================
+// Telemetry Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryEvent {
+    pub device_id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub exit_code: i32,
+    pub bytes_out: u64,
+    pub approx_tokens: Option<u64>,
+    pub error: Option<String>,
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl TelemetryEvent {
+    pub fn new(
+        device_id: String,
+        command: String,
+        args: Vec<String>,
+        started_at: String,
+        duration_ms: u64,
+        exit_code: i32,
+        bytes_out: u64,
+    ) -> Self {
+        Self {
+            device_id,
+            command,
+            args,
+            started_at,
+            duration_ms,
+            exit_code,
+            bytes_out,
+            approx_tokens: None,
+            error: None,
+            metadata: None,
+        }
+    }
+
+    pub fn with_error(mut self, error: String) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    pub fn with_tokens(mut self, tokens: u64) -> Self {
+        self.approx_tokens = Some(tokens);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: HashMap<String, serde_json::Value>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+// ============================================================================
+// Audit Log Models
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    TerminalCommand,
+    RunStarted,
+    RunStopped,
+    RunKilled,
+    RunInterrupted,
+    ConfigSaved,
+    ConfigCleared,
+    ApprovalGranted,
+    ApprovalDenied,
+    CloudDeploy,
+    SecretsPushed,
+    AppLockEnabled,
+    AppLockDisabled,
+    AppUnlocked,
+    PermissionProfileChanged,
+    CommandDenied,
+    ConfigProfileActivated,
+    ConfigProfileDeleted,
+    ConfigExported,
+    ConfigImported,
+    ConfigRestored,
+}
+
+/// Where a privileged action was triggered from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOrigin {
+    Gui,
+    Cli,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub action: AuditAction,
+    pub origin: AuditOrigin,
+    /// What the action targeted: a command line, run ID or config file
+    pub subject: String,
+    pub success: bool,
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, origin: AuditOrigin, subject: String) -> Self {
+        Self {
+            timestamp: current_timestamp(),
+            action,
+            origin,
+            subject,
+            success: true,
+            detail: None,
+        }
+    }
+
+    pub fn with_outcome(mut self, success: bool, detail: Option<String>) -> Self {
+        self.success = success;
+        self.detail = detail;
+        self
+    }
+}
+
+/// Time window for audit log queries; both bounds are RFC 3339 and optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRange {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+}
+
+// ============================================================================
+// API Response Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<ApiError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiError {
+    pub code: String,
+    pub message: String,
+    /// Locale-independent id of the message, for codes with a localized message
+    #[serde(default)]
+    pub message_id: Option<String>,
+    pub details: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn error(code: String, message: String) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(ApiError {
+                message_id: MessageId::for_error_code(&code).map(|id| id.id().to_string()),
+                code,
+                message,
+                details: None,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+    pub metadata: Option<ConnectionMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionMetadata {
+    pub endpoint: String,
+    pub timestamp: String,
+    pub version: Option<String>,
+}
+
+/// One content delta of a streamed test prompt, emitted as `prompt-token`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTokenEvent {
+    pub prompt_id: String,
+    /// Position of the delta in the response, from 0
+    pub index: u32,
+    pub token: String,
+    /// Time since the request was sent
+    pub elapsed_ms: u64,
+}
+
+/// Result of a streamed test prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptStreamResult {
+    pub prompt_id: String,
+    pub content: String,
+    /// Content deltas received; providers may group several tokens into one
+    pub token_count: u32,
+    pub time_to_first_token_ms: Option<u64>,
+    pub total_ms: u64,
+    pub finish_reason: Option<String>,
+    /// False when the endpoint ignored streaming and returned the whole completion at once
+    pub streamed: bool,
+}
+
+/// Outcome of checking an API key against an authenticated Sandbox endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyStatus {
+    Valid,
+    /// Malformed, unknown or revoked
+    Invalid,
+    Expired,
+    /// Accepted, but not allowed to use the Sandbox API
+    InsufficientPermissions,
+    /// The Sandbox could not be reached, so the key is unverified
+    NetworkError,
+    /// The Sandbox answered with a status that says nothing about the key
+    Unexpected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCheck {
+    pub status: ApiKeyStatus,
+    pub message: String,
+    pub endpoint: String,
+    pub http_status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub checked_at: String,
+}
+
+/// Rate-limit budget a Sandbox host last reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitStatus {
+    pub host: String,
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset_at: Option<String>,
+    /// Whether new requests are being held until the window resets
+    pub throttled: bool,
+    /// Requests held back so far this session
+    pub queued_requests: u64,
+    pub updated_at: Option<String>,
+}
+
+/// Sandbox reachability as judged by the connectivity monitor
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectivityState {
+    Online,
+    /// Reachable, but slow or failing some probes
+    Degraded,
+    Offline,
+}
+
+/// Rolling results of the connectivity monitor's health probes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub running: bool,
+    /// Unknown until the first probe completes
+    pub state: Option<ConnectivityState>,
+    pub interval_secs: u64,
+    /// Probes in the rolling window
+    pub samples: usize,
+    /// Share of probes in the window that succeeded, 0.0 to 1.0
+    pub availability: f64,
+    pub average_latency_ms: Option<u64>,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    pub last_checked_at: Option<String>,
+}
+
+/// Payload of the `connectivity-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityChangedEvent {
+    pub previous: Option<ConnectivityState>,
+    pub state: ConnectivityState,
+    pub availability: f64,
+    pub average_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    pub timestamp: i64,
+}
+
+// ============================================================================
+// Error Models
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Process error: {0}")]
+    Process(String),
+
+    #[error("CLI not found: {0}")]
+    CliNotFound(String),
+
+    #[error("Environment setup failed: {0}")]
+    EnvironmentError(String),
+
+    #[error("Character file error: {0}")]
+    CharacterError(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Eval error: {0}")]
+    Eval(String),
+
+    #[error("Policy violation: {0}")]
+    Policy(String),
+
+    #[error("Quota exceeded: {0}")]
+    Quota(String),
+
+    #[error("App is locked: {0}")]
+    Locked(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Unknown error: {0}")]
+    Unknown(String),
+}
+
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", &self.error_code())?;
+        state.serialize_field("message", &self.localized_message())?;
+        state.serialize_field("messageId", self.message_id().id())?;
+        state.end()
+    }
+}
+
+impl AppError {
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::Config(_) => "CONFIG_ERROR",
+            AppError::Process(_) => "PROCESS_ERROR",
+            AppError::CliNotFound(_) => "CLI_NOT_FOUND",
+            AppError::EnvironmentError(_) => "ENVIRONMENT_ERROR",
+            AppError::CharacterError(_) => "CHARACTER_ERROR",
+            AppError::Network(_) => "NETWORK_ERROR",
+            AppError::Eval(_) => "EVAL_ERROR",
+            AppError::Policy(_) => "POLICY_VIOLATION",
+            AppError::Quota(_) => "QUOTA_EXCEEDED",
+            AppError::Locked(_) => "APP_LOCKED",
+            AppError::Io(_) => "IO_ERROR",
+            AppError::Serialization(_) => "SERIALIZATION_ERROR",
+            AppError::Request(_) => "REQUEST_ERROR",
+            AppError::Unknown(_) => "UNKNOWN_ERROR",
+        }
+    }
+
+    pub fn message_id(&self) -> MessageId {
+        MessageId::for_error_code(self.error_code()).unwrap_or(MessageId::ErrorUnknown)
+    }
+
+    /// The error message in the current locale; `to_string()` stays English for logs
+    pub fn localized_message(&self) -> String {
+        let detail = match self {
+            AppError::Config(detail)
+            | AppError::Process(detail)
+            | AppError::CliNotFound(detail)
+            | AppError::EnvironmentError(detail)
+            | AppError::CharacterError(detail)
+            | AppError::Network(detail)
+            | AppError::Eval(detail)
+            | AppError::Policy(detail)
+            | AppError::Quota(detail)
+            | AppError::Locked(detail)
+            | AppError::Unknown(detail) => detail.clone(),
+            AppError::Io(e) => e.to_string(),
+            AppError::Serialization(e) => e.to_string(),
+            AppError::Request(e) => e.to_string(),
+        };
+        t_args(self.message_id(), &[("detail", &detail)])
+    }
+}
+
+// ============================================================================
+// Log Streaming Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEvent {
+    pub run_id: String,
+    pub message: String,
+    pub log_type: LogType,
+    pub timestamp: i64,
+    /// Position of the line in the run's log buffer, for lines `tail_run_log` can return
+    #[serde(default)]
+    pub offset: Option<u64>,
+    /// Level parsed from the line's `INFO`/`WARN`/`ERROR` marker
+    #[serde(default)]
+    pub severity: Option<LogSeverity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogType {
+    Stdout,
+    Stderr,
+    Info,
+    Error,
+    System,
+}
+
+/// Level of a CLI log line, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSeverity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogEvent {
+    pub fn new(run_id: String, message: String, log_type: LogType) -> Self {
+        Self {
+            run_id,
+            message,
+            log_type,
+            timestamp: chrono::Utc::now().timestamp(),
+            offset: None,
+            severity: None,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn with_severity(mut self, severity: Option<LogSeverity>) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn stdout(run_id: String, message: String) -> Self {
+        Self::new(run_id, message, LogType::Stdout)
+    }
+
+    pub fn stderr(run_id: String, message: String) -> Self {
+        Self::new(run_id, message, LogType::Stderr)
+    }
+
+    pub fn info(run_id: String, message: String) -> Self {
+        Self::new(run_id, message, LogType::Info)
+    }
+
+    pub fn error(run_id: String, message: String) -> Self {
+        Self::new(run_id, message, LogType::Error)
+    }
+
+    pub fn system(run_id: String, message: String) -> Self {
+        Self::new(run_id, message, LogType::System)
+    }
+}
+
+/// A buffered output line of a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunLogLine {
+    pub offset: u64,
+    pub log_type: LogType,
+    #[serde(default)]
+    pub severity: Option<LogSeverity>,
+    pub text: String,
+    pub timestamp: i64,
+}
+
+/// Lines of a run's log from a given offset on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunLogTail {
+    pub run_id: String,
+    pub lines: Vec<RunLogLine>,
+    /// Offset to pass to the next call
+    pub next_offset: u64,
+    /// Oldest offset still available; lines before it were dropped from the buffer
+    pub first_offset: u64,
+    /// The run has exited, so no more lines will arrive
+    pub finished: bool,
+}
+
+/// A line rewritten in place with `\r` (progress bars); emitted as `run-progress-line`
+///
+/// `done` marks the final state, which is also delivered as a regular log event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressLineEvent {
+    pub run_id: String,
+    pub log_type: LogType,
+    pub text: String,
+    pub done: bool,
+    pub timestamp: i64,
+}
+
+impl ProgressLineEvent {
+    pub fn new(run_id: String, log_type: LogType, text: String, done: bool) -> Self {
+        Self {
+            run_id,
+            log_type,
+            text,
+            done,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Stage of the package download npx does before the CLI's first run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CliInstallPhase {
+    /// Fetching package metadata to work out the dependency tree
+    Resolving,
+    /// Fetching package tarballs
+    Downloading,
+    Complete,
+}
+
+/// Payload of the `cli-install-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliInstallProgressEvent {
+    pub run_id: String,
+    pub phase: CliInstallPhase,
+    pub packages_resolved: u32,
+    pub packages_downloaded: u32,
+    /// Known once downloading starts, or from npm's closing summary
+    pub total_packages: Option<u32>,
+    pub percent: Option<u8>,
+    pub timestamp: i64,
+}
+
+/// Payload of the `run-stalled` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStalledEvent {
+    pub run_id: String,
+    /// Seconds since the run last wrote to stdout or stderr
+    pub silent_secs: u64,
+    pub stall_after_secs: u64,
+    /// Whether the watchdog sent the run an interrupt
+    pub interrupted: bool,
+    pub timestamp: i64,
+}
+
+/// What happened to a run in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunQueueState {
+    Queued,
+    Started,
+    Paused,
+    Resumed,
+}
+
+/// Payload of the `run-queue` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunQueueEvent {
+    pub run_id: String,
+    pub state: RunQueueState,
+    pub priority: RunPriority,
+    /// 1-based place among waiting runs, for queued runs
+    pub position: Option<usize>,
+    pub timestamp: i64,
+}
+
+/// A run holding or waiting for a queue slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRun {
+    pub run_id: String,
+    pub priority: RunPriority,
+    /// Paused to make room for a higher-priority run
+    pub paused: bool,
+}
+
+/// Runs holding a slot and runs waiting for one, in start order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunQueueSnapshot {
+    pub max_concurrent_runs: Option<usize>,
+    pub running: Vec<QueuedRun>,
+    pub waiting: Vec<QueuedRun>,
+}
+
+// ============================================================================
+// Run Report Models
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// A rendered run report; `path` is set when it was written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport {
+    pub run_id: String,
+    pub format: ReportFormat,
+    pub content: String,
+    pub path: Option<String>,
+}
+
+// ============================================================================
+// Scenario Models
+// ============================================================================
+
+/// A scripted conversation replayed against a running agent, loaded from YAML or JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub name: String,
+    /// Agent ID or name to talk to; the agent's first agent when omitted
+    #[serde(default)]
+    pub agent: Option<String>,
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioStep {
+    pub message: String,
+    #[serde(default)]
+    pub expect: Vec<ScenarioAssertion>,
+}
+
+/// Check applied to an agent's response to one step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScenarioAssertion {
+    /// Response contains the text, ignoring case
+    Contains { value: String },
+    /// Response does not contain the text, ignoring case
+    NotContains { value: String },
+    /// Response equals the text after trimming
+    Equals { value: String },
+    /// Response has some non-whitespace text
+    NonEmpty,
+    /// Agent answered within the given time
+    MaxLatencyMs { value: u64 },
+}
+
+/// Outcome of one step; emitted as `scenario-step` while a scenario replays
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioStepResult {
+    pub scenario_id: String,
+    pub index: usize,
+    pub message: String,
+    pub response: Option<String>,
+    pub latency_ms: u64,
+    pub passed: bool,
+    /// One entry per failed assertion, or the bridge error
+    pub failures: Vec<String>,
+}
+
+/// Stored outcome of one scenario replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioResult {
+    pub id: String,
+    pub scenario_name: String,
+    pub scenario_path: String,
+    pub agent_url: String,
+    pub started_at: String,
+    pub ended_at: String,
+    pub passed: bool,
+    pub steps: Vec<ScenarioStepResult>,
+}
+
+// ============================================================================
+// Support Bundle Models
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundleFile {
+    pub name: String,
+    pub description: String,
+    pub included: bool,
+    /// Why the file was left out, when it was
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundleManifest {
+    pub created_at: String,
+    pub app_version: String,
+    pub files: Vec<SupportBundleFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub path: String,
+    pub size_bytes: u64,
+    pub manifest: SupportBundleManifest,
+}
+
+/// Condensed run record for diagnostics, without captured output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub id: String,
+    pub mode: RunMode,
+    pub status: RunStatus,
+    pub exit_code: Option<i32>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+impl From<&RunResult> for RunSummary {
+    fn from(result: &RunResult) -> Self {
+        Self {
+            id: result.id.clone(),
+            mode: result.spec.mode.clone(),
+            status: result.status,
+            exit_code: result.exit_code,
+            started_at: result.started_at.clone(),
+            ended_at: result.ended_at.clone(),
+            duration_ms: result.duration_ms,
+        }
+    }
+}
+
+// ============================================================================
+// App Log Models
+// ============================================================================
+
+/// One line of the backend's structured log file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub run_id: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// Filter for app log queries; every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLogFilter {
+    /// Minimum level: error, warn, info, debug or trace
+    pub level: Option<String>,
+    pub run_id: Option<String>,
+    pub target: Option<String>,
+    /// Case-insensitive substring of the message
+    pub contains: Option<String>,
+}
+
+// ============================================================================
+// Agent Models
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentLocation {
+    /// Run on this machine through the ElizaOS CLI
+    Local,
+    /// Hosted in the Sandbox cloud
+    Cloud,
+}
+
+/// An agent shown in the agents list, whether run locally or hosted in the Sandbox cloud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    /// Run ID for local agents, cloud agent ID for hosted ones
+    pub id: String,
+    pub name: String,
+    pub location: AgentLocation,
+    /// Run status for local agents; status reported by the Sandbox for cloud agents
+    pub status: String,
+    #[serde(default)]
+    pub character_file: Option<String>,
+    /// Public endpoint of a cloud agent
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+impl Agent {
+    /// Local agent backed by a run of the ElizaOS CLI
+    pub fn local(run: &RunResult) -> Self {
+        let character_file = run.spec.character_file.clone();
+        let name = character_file
+            .as_deref()
+            .and_then(|file| std::path::Path::new(file).file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| run.id.clone());
+
+        Self {
+            id: run.id.clone(),
+            name,
+            location: AgentLocation::Local,
+            status: run.status.as_str().to_string(),
+            character_file,
+            url: None,
+            created_at: Some(run.started_at.clone()),
+            updated_at: run.ended_at.clone(),
+        }
+    }
+
+    pub fn is_cloud(&self) -> bool {
+        self.location == AgentLocation::Cloud
+    }
+}
+
+/// A saved local agent: the run spec it starts with and whether it starts with the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentProfile {
+    /// Generated when empty on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Agent run (`run` mode) started for this agent
+    pub spec: RunSpec,
+    /// Start the agent when the app launches, once preflight checks pass
+    #[serde(default)]
+    pub start_on_launch: bool,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// Progress of starting an agent on app launch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentAutostartState {
+    /// Waiting for configuration and preflight checks
+    Pending,
+    /// Not started because the environment is not ready
+    Skipped,
+    /// Run launched
+    Started,
+    /// The run could not start or exited with an error
+    Failed,
+}
+
+/// Payload of the `agent-autostart` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAutostartEvent {
+    pub agent_id: String,
+    pub name: String,
+    pub state: AgentAutostartState,
+    pub run_id: Option<String>,
+    pub message: Option<String>,
+    pub timestamp: i64,
+}
+
+/// Payload of the `run-retry-suggested` event, sent when an agent run failed because
+/// its port was taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRetrySuggestedEvent {
+    pub run_id: String,
+    /// Port named in the error, when it gave one
+    pub conflicting_port: Option<u16>,
+    pub suggested_port: u16,
+    /// The failed run's spec moved to the suggested port, ready to start
+    pub spec: RunSpec,
+    /// Whether the retry was started without asking
+    pub automatic: bool,
+    /// ID of the automatic retry
+    pub retry_run_id: Option<String>,
+    pub attempt: u32,
+    pub timestamp: i64,
+}
+
+/// Where an agent shortcut is placed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutLocation {
+    Desktop,
+    /// Start Menu on Windows, the applications menu on Linux, `~/Applications` on macOS
+    StartMenu,
+}
+
+/// An OS shortcut that launches the app and starts a saved agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentShortcut {
+    pub agent_id: String,
+    pub location: ShortcutLocation,
+    pub path: String,
+    /// Arguments the app is launched with
+    pub launch_args: Vec<String>,
+}
+
+/// Stage of a character deployment to the Sandbox cloud
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeployStage {
+    Validating,
+    Uploading,
+    Deploying,
+    Ready,
+    Failed,
+}
+
+/// Payload of the `cloud-deploy-status` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudDeployEvent {
+    pub character_id: String,
+    pub stage: DeployStage,
+    pub message: String,
+    pub agent_id: Option<String>,
+    pub timestamp: i64,
+}
+
+impl CloudDeployEvent {
+    pub fn new(character_id: String, stage: DeployStage, message: String) -> Self {
+        Self {
+            character_id,
+            stage,
+            message,
+            agent_id: None,
+            timestamp: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    pub fn with_agent_id(mut self, agent_id: String) -> Self {
+        self.agent_id = Some(agent_id);
+        self
+    }
+}
+
+/// A model in the Sandbox catalog; accepts OpenAI-style `owned_by`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxModel {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, alias = "owned_by")]
+    pub owned_by: Option<String>,
+    /// Context window in tokens
+    #[serde(
+        default,
+        alias = "context_length",
+        alias = "context_window",
+        alias = "contextWindow"
+    )]
+    pub context_length: Option<u64>,
+    /// Pricing tier the Sandbox bills the model under, e.g. `standard` or `premium`
+    #[serde(default, alias = "pricing_tier", alias = "tier")]
+    pub pricing_tier: Option<String>,
+}
+
+impl SandboxModel {
+    /// Whether `model` (a `default_model` value) names this catalog entry
+    pub fn matches(&self, model: &str) -> bool {
+        self.id.eq_ignore_ascii_case(model.trim())
+    }
+}
+
+/// Account usage for the current billing period; fields the Sandbox leaves out stay empty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    #[serde(default, alias = "tokens_used", alias = "total_tokens")]
+    pub tokens_used: Option<u64>,
+    #[serde(default, alias = "token_limit", alias = "tokens_limit")]
+    pub token_limit: Option<u64>,
+    #[serde(default, alias = "tokens_remaining")]
+    pub tokens_remaining: Option<u64>,
+    #[serde(default, alias = "credits_used")]
+    pub credits_used: Option<f64>,
+    #[serde(default, alias = "credit_limit", alias = "credits_limit")]
+    pub credit_limit: Option<f64>,
+    #[serde(default, alias = "credits_remaining", alias = "balance")]
+    pub credits_remaining: Option<f64>,
+    #[serde(default, alias = "period_start")]
+    pub period_start: Option<String>,
+    #[serde(default, alias = "period_end", alias = "resets_at")]
+    pub period_end: Option<String>,
+    /// Request quota of the Sandbox host, from the rate-limit headers it last sent
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitStatus>,
+}
+
+impl UsageReport {
+    /// Fill in remaining budgets the Sandbox only reported as used and limit
+    pub fn with_remaining(mut self) -> Self {
+        if self.tokens_remaining.is_none() {
+            if let (Some(used), Some(limit)) = (self.tokens_used, self.token_limit) {
+                self.tokens_remaining = Some(limit.saturating_sub(used));
+            }
+        }
+        if self.credits_remaining.is_none() {
+            if let (Some(used), Some(limit)) = (self.credits_used, self.credit_limit) {
+                self.credits_remaining = Some((limit - used).max(0.0));
+            }
+        }
+        self
+    }
+}
+
+// ============================================================================
+// Character Models
+// ============================================================================
+
+/// Bookkeeping stored next to a managed character file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterMetadata {
+    /// Cloud agent this character was last deployed as
+    #[serde(default)]
+    pub cloud_agent_id: Option<String>,
+    #[serde(default)]
+    pub deployed_at: Option<String>,
+    /// Cloud agent this character was imported from
+    #[serde(default)]
+    pub imported_from: Option<CharacterProvenance>,
+    /// When the character was last pulled from the cloud
+    #[serde(default)]
+    pub synced_at: Option<String>,
+}
+
+/// Where an imported character came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterProvenance {
+    pub agent_id: String,
+    /// Sandbox base URL the agent was fetched from
+    pub base_url: String,
+}
+
+/// A character stored in the app data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedCharacter {
+    pub id: String,
+    pub name: String,
+    /// Character JSON file, as passed to `--character`
+    pub path: String,
+    pub metadata: CharacterMetadata,
+}
+
+/// A saved version of a managed character
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterRevision {
+    /// Increases by one with every save
+    pub number: u32,
+    /// SHA-256 of the saved character JSON
+    pub hash: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// A character field that differs between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterFieldChange {
+    /// JSON pointer to the field, e.g. `/settings/model`
+    pub path: String,
+    /// Absent when the field was added
+    pub before: Option<serde_json::Value>,
+    /// Absent when the field was removed
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterRevisionDiff {
+    pub character_id: String,
+    pub from: u32,
+    pub to: u32,
+    pub changes: Vec<CharacterFieldChange>,
+}
+
+/// A character file chosen in the native file dialog and validated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterFilePick {
+    /// File as selected, outside the managed directory
+    pub path: String,
+    pub name: String,
+    /// Managed copy, when the file was imported
+    #[serde(default)]
+    pub character: Option<ManagedCharacter>,
+}
+
+/// What a path dropped onto the window was handled as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DroppedItemKind {
+    /// Character JSON file
+    Character,
+    /// Character package zip
+    Package,
+    Folder,
+    Unsupported,
+}
+
+/// Payload of the `file-drop-result` event, one per dropped path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDropResult {
+    pub path: String,
+    pub kind: DroppedItemKind,
+    pub success: bool,
+    /// Managed character created from the drop
+    #[serde(default)]
+    pub character: Option<ManagedCharacter>,
+    #[serde(default)]
+    pub error_code: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A document attached to a managed character as a knowledge source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KnowledgeFile {
+    pub name: String,
+    /// Copy inside the character's knowledge directory
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// How much a lint finding matters, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// One best-practice finding about a character
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterLintIssue {
+    /// Stable rule name, e.g. `empty-bio`
+    pub rule: String,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// Character field the finding is about
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+/// Lint findings for a managed character, most severe first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterLintReport {
+    pub character_id: String,
+    pub issues: Vec<CharacterLintIssue>,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// A file inside a character package
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterPackageFile {
+    /// Path inside the archive, relative to the character directory
+    pub path: String,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+/// `manifest.json` of a character package
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterPackageManifest {
+    pub format_version: u32,
+    pub character_id: String,
+    pub name: String,
+    pub exported_at: String,
+    pub app_version: String,
+    pub files: Vec<CharacterPackageFile>,
+}
+
+/// A character package written to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterPackage {
+    pub path: String,
+    pub size_bytes: u64,
+    pub manifest: CharacterPackageManifest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharacterAssetKind {
+    Image,
+    Audio,
+}
+
+/// A media file stored in a managed character's `assets` directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterAsset {
+    /// File name inside `assets`
+    pub name: String,
+    /// Absolute path of the stored file
+    pub path: String,
+    pub kind: CharacterAssetKind,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    /// Whether the character's `avatar` field points at this asset
+    pub is_avatar: bool,
+}
+
+// ============================================================================
+// Prompt Template Models
+// ============================================================================
+
+/// A reusable prompt snippet; `{{name}}` placeholders are filled in when it is rendered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    /// Generated when empty on save
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub body: String,
+    /// Placeholder names found in the body, in order of first use; set on save
+    #[serde(default)]
+    pub variables: Vec<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// Character field a rendered prompt template can be inserted into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptTemplateTarget {
+    /// Replaces the character's system prompt
+    System,
+    /// Appended as a bio line
+    Bio,
+    /// Appended to the post examples
+    PostExamples,
+}
+
+impl PromptTemplateTarget {
+    /// Key of the field in the character JSON
+    pub fn field(&self) -> &'static str {
+        match self {
+            PromptTemplateTarget::System => "system",
+            PromptTemplateTarget::Bio => "bio",
+            PromptTemplateTarget::PostExamples => "postExamples",
+        }
+    }
+}
+
+// ============================================================================
+// Git Models
+// ============================================================================
+
+/// Working tree state of a project directory's git repository
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatus {
+    /// Repository root
+    pub root: String,
+    /// Absent on a detached HEAD
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+    pub files: Vec<GitFileStatus>,
+}
+
+/// A changed file, with the two-letter status from `git status --porcelain`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatus {
+    /// Relative to the repository root
+    pub path: String,
+    /// Previous path of a renamed or copied file
+    pub original_path: Option<String>,
+    /// Status in the index, e.g. `M`, `A`, `?`; a space when unchanged
+    pub staged: String,
+    /// Status in the working tree
+    pub unstaged: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitResult {
+    pub commit: String,
+    pub branch: Option<String>,
+    pub files_changed: usize,
+}
+
+/// How a diff line differs from the committed file
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitDiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffLine {
+    pub kind: GitDiffLineKind,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffHunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    pub lines: Vec<GitDiffLine>,
+}
+
+/// Uncommitted changes to one file against `HEAD`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileDiff {
+    pub path: String,
+    /// Not tracked yet, so every line shows as added
+    pub untracked: bool,
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<GitDiffHunk>,
+}
+
+// ============================================================================
+// Dependency Audit Models
+// ============================================================================
+
+/// Package manager whose audit was run, picked from the project's lockfile
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Bun,
+}
+
+/// Advisory severity as reported by the registry, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VulnerabilitySeverity {
+    Info,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+/// A vulnerable package found by a dependency audit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyVulnerability {
+    pub package: String,
+    pub severity: VulnerabilitySeverity,
+    /// Title of the first advisory; absent when only a dependency is vulnerable
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub vulnerable_versions: Option<String>,
+    /// Listed in `package.json` rather than pulled in by another package
+    pub direct: Option<bool>,
+    pub fix_available: Option<bool>,
+    /// Vulnerable dependencies that make this package vulnerable
+    #[serde(default)]
+    pub via: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VulnerabilityCounts {
+    pub info: usize,
+    pub low: usize,
+    pub moderate: usize,
+    pub high: usize,
+    pub critical: usize,
+    pub total: usize,
+}
+
+/// Vulnerable dependencies of a project directory, most severe first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyAuditReport {
+    pub project_dir: String,
+    pub package_manager: PackageManager,
+    pub vulnerabilities: Vec<DependencyVulnerability>,
+    pub counts: VulnerabilityCounts,
+    pub audited_at: String,
+}
+
+// ============================================================================
+// Project Env Models
 // ============================================================================
 
+/// Where a key of a project's `.env` goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectEnvTarget {
+    /// A `SandboxConfig` field; the app sets it for every run, so it is never merged
+    Config,
+    /// Merged into the env of runs in the project once imported
+    Run,
+    /// Always overridden by the app when spawning the CLI
+    AppManaged,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct TelemetryEvent {
-    pub device_id: String,
-    pub command: String,
-    pub args: Vec<String>,
-    pub started_at: String,
-    pub duration_ms: u64,
-    pub exit_code: i32,
-    pub bytes_out: u64,
-    pub approx_tokens: Option<u64>,
-    pub error: Option<String>,
-    pub metadata: Option<HashMap<String, serde_json::Value>>,
+pub struct ProjectEnvEntry {
+    pub key: String,
+    /// The value, or `[REDACTED]` for secrets
+    pub value_preview: String,
+    pub secret: bool,
+    pub target: ProjectEnvTarget,
+    /// `SandboxConfig` field for `Config` keys
+    pub config_field: Option<String>,
+    /// Whether runs in the project receive this key
+    pub imported: bool,
 }
 
-impl TelemetryEvent {
-    pub fn new(
-        device_id: String,
-        command: String,
-        args: Vec<String>,
-        started_at: String,
-        duration_ms: u64,
-        exit_code: i32,
-        bytes_out: u64,
-    ) -> Self {
-        Self {
-            device_id,
-            command,
-            args,
-            started_at,
-            duration_ms,
-            exit_code,
-            bytes_out,
-            approx_tokens: None,
-            error: None,
-            metadata: None,
+/// Keys of a project's `.env` and how each one is used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEnvReport {
+    pub env_path: String,
+    pub entries: Vec<ProjectEnvEntry>,
+    /// When the project was imported; None if its runs don't receive the file
+    pub imported_at: Option<String>,
+}
+
+/// Sandbox settings a project directory overrides through its `.eliza-desktop.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProjectConfigOverride {
+    #[serde(default, alias = "base_url")]
+    pub base_url: Option<String>,
+    #[serde(default, alias = "default_model", alias = "model")]
+    pub default_model: Option<String>,
+}
+
+/// A project's override file and the settings runs started in it use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfigReport {
+    pub config_path: String,
+    /// None when the project has no override file
+    pub overrides: Option<ProjectConfigOverride>,
+    pub base_url: String,
+    pub default_model: Option<String>,
+    /// The override points runs at a host that is neither the saved base URL's nor a
+    /// trusted project host; until it is approved, runs get no API key
+    pub needs_approval: bool,
+}
+
+// ============================================================================
+// Window Models
+// ============================================================================
+
+/// Secondary windows the backend can open; each kind has one window, labelled as serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowKind {
+    LogViewer,
+    Terminal,
+    Playground,
+}
+
+impl WindowKind {
+    pub const ALL: [WindowKind; 3] = [
+        WindowKind::LogViewer,
+        WindowKind::Terminal,
+        WindowKind::Playground,
+    ];
+
+    /// Window label, also passed to the frontend as `?window=<label>`
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowKind::LogViewer => "log-viewer",
+            WindowKind::Terminal => "terminal",
+            WindowKind::Playground => "playground",
         }
     }
 
-    pub fn with_error(mut self, error: String) -> Self {
-        self.error = Some(error);
-        self
+    pub fn title(self) -> &'static str {
+        match self {
+            WindowKind::LogViewer => "Logs - ElizaOS CLI Desktop",
+            WindowKind::Terminal => "Terminal - ElizaOS CLI Desktop",
+            WindowKind::Playground => "Playground - ElizaOS CLI Desktop",
+        }
     }
 
-    pub fn with_tokens(mut self, tokens: u64) -> Self {
-        self.approx_tokens = Some(tokens);
-        self
+    /// Logical size used until the window has been resized
+    pub fn default_size(self) -> (f64, f64) {
+        match self {
+            WindowKind::LogViewer => (960.0, 640.0),
+            WindowKind::Terminal => (900.0, 560.0),
+            WindowKind::Playground => (1000.0, 700.0),
+        }
     }
 
-    pub fn with_metadata(mut self, metadata: HashMap<String, serde_json::Value>) -> Self {
-        self.metadata = Some(metadata);
-        self
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.label() == label)
     }
 }
 
-// ============================================================================
-// API Response Models
-// ============================================================================
+/// Logical position and size of a window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Position and size are the ones to restore when un-maximizing
+    #[serde(default)]
+    pub maximized: bool,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<ApiError>,
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    pub kind: WindowKind,
+    pub label: String,
+    pub open: bool,
+    /// Remembered geometry, used the next time the window opens
+    pub geometry: Option<WindowGeometry>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiError {
-    pub code: String,
-    pub message: String,
-    pub details: Option<HashMap<String, serde_json::Value>>,
+// ============================================================================
+// Global Shortcut Models
+// ============================================================================
+
+/// App actions that can be bound to a global keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalShortcutAction {
+    StopAllRuns,
+    OpenTerminalWindow,
+    OpenLogViewer,
+    ToggleMainWindow,
 }
 
-impl<T> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            error: None,
-        }
-    }
+impl GlobalShortcutAction {
+    pub const ALL: [GlobalShortcutAction; 4] = [
+        GlobalShortcutAction::StopAllRuns,
+        GlobalShortcutAction::OpenTerminalWindow,
+        GlobalShortcutAction::OpenLogViewer,
+        GlobalShortcutAction::ToggleMainWindow,
+    ];
 
-    pub fn error(code: String, message: String) -> Self {
-        Self {
-            success: false,
-            data: None,
-            error: Some(ApiError {
-                code,
-                message,
-                details: None,
-            }),
+    pub fn name(self) -> &'static str {
+        match self {
+            GlobalShortcutAction::StopAllRuns => "stop_all_runs",
+            GlobalShortcutAction::OpenTerminalWindow => "open_terminal_window",
+            GlobalShortcutAction::OpenLogViewer => "open_log_viewer",
+            GlobalShortcutAction::ToggleMainWindow => "toggle_main_window",
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct ConnectionTestResult {
-    pub success: bool,
-    pub latency_ms: Option<u64>,
-    pub error: Option<String>,
-    pub metadata: Option<ConnectionMetadata>,
+pub struct ShortcutBinding {
+    pub action: GlobalShortcutAction,
+    /// Accelerator such as `CmdOrCtrl+Shift+T`; None when the action is unbound
+    pub accelerator: Option<String>,
+    /// Whether the OS accepted the shortcut; another app may already hold it
+    pub registered: bool,
 }
 
+/// Payload of the `global-shortcut` event
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConnectionMetadata {
-    pub endpoint: String,
-    pub timestamp: String,
-    pub version: Option<String>,
+#[serde(rename_all = "camelCase")]
+pub struct GlobalShortcutTriggered {
+    pub action: GlobalShortcutAction,
 }
 
 // ============================================================================
-// Error Models
+// Sandbox Login Models
 // ============================================================================
 
-#[derive(Debug, thiserror::Error)]
-pub enum AppError {
-    #[error("Configuration error: {0}")]
-    Config(String),
-
-    #[error("Process error: {0}")]
-    Process(String),
-
-    #[error("CLI not found: {0}")]
-    CliNotFound(String),
+/// A device-code login waiting for the user to approve it in the browser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxLoginSession {
+    pub login_id: String,
+    /// Code the user confirms on the verification page
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Verification page with the code already filled in, when the Sandbox offers one
+    pub verification_uri_complete: Option<String>,
+    pub expires_at: String,
+    /// Profile the API key is saved to once the login is approved
+    pub profile: String,
+}
 
-    #[error("Environment setup failed: {0}")]
-    EnvironmentError(String),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxLoginStatus {
+    Succeeded,
+    Denied,
+    Expired,
+    Cancelled,
+    Failed,
+}
 
-    #[error("Character file error: {0}")]
-    CharacterError(String),
+/// Payload of the `sandbox-login` event, sent once when a login finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxLoginEvent {
+    pub login_id: String,
+    pub status: SandboxLoginStatus,
+    pub profile: String,
+    pub message: Option<String>,
+}
 
-    #[error("Network error: {0}")]
-    Network(String),
+// ============================================================================
+// Clipboard Models
+// ============================================================================
 
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+/// What `copy_to_clipboard` assembles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardContentKind {
+    /// A run's command and output, with secrets redacted; the id is the run id
+    RunLogs,
+    /// A profile's configuration with the API key masked; the id is the profile name
+    Config,
+}
 
-    #[error("Serialization error: {0}")]
-    Serialization(#[from] serde_json::Error),
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardCopy {
+    pub kind: ClipboardContentKind,
+    pub id: String,
+    /// Characters placed on the clipboard
+    pub length: usize,
+    pub lines: usize,
+}
 
-    #[error("Request error: {0}")]
-    Request(#[from] reqwest::Error),
+// ============================================================================
+// Onboarding Models
+// ============================================================================
 
-    #[error("Unknown error: {0}")]
-    Unknown(String),
+/// Setup steps of the onboarding wizard, in the order they are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    PreflightPassed,
+    ConfigSaved,
+    ConnectionTested,
+    FirstRunCompleted,
 }
 
-impl Serialize for AppError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("AppError", 2)?;
-        state.serialize_field("code", &self.error_code())?;
-        state.serialize_field("message", &self.to_string())?;
-        state.end()
-    }
-}
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 4] = [
+        OnboardingStep::PreflightPassed,
+        OnboardingStep::ConfigSaved,
+        OnboardingStep::ConnectionTested,
+        OnboardingStep::FirstRunCompleted,
+    ];
 
-impl AppError {
-    pub fn error_code(&self) -> &'static str {
+    pub fn name(self) -> &'static str {
         match self {
-            AppError::Config(_) => "CONFIG_ERROR",
-            AppError::Process(_) => "PROCESS_ERROR",
-            AppError::CliNotFound(_) => "CLI_NOT_FOUND",
-            AppError::EnvironmentError(_) => "ENVIRONMENT_ERROR",
-            AppError::CharacterError(_) => "CHARACTER_ERROR",
-            AppError::Network(_) => "NETWORK_ERROR",
-            AppError::Io(_) => "IO_ERROR",
-            AppError::Serialization(_) => "SERIALIZATION_ERROR",
-            AppError::Request(_) => "REQUEST_ERROR",
-            AppError::Unknown(_) => "UNKNOWN_ERROR",
+            OnboardingStep::PreflightPassed => "preflight_passed",
+            OnboardingStep::ConfigSaved => "config_saved",
+            OnboardingStep::ConnectionTested => "connection_tested",
+            OnboardingStep::FirstRunCompleted => "first_run_completed",
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepStatus {
+    pub step: OnboardingStep,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    /// Every step in wizard order
+    pub steps: Vec<OnboardingStepStatus>,
+    /// First step not yet completed; None once onboarding is done
+    pub current_step: Option<OnboardingStep>,
+    pub complete: bool,
+}
+
+/// Payload of the `onboarding-step-completed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepCompleted {
+    pub step: OnboardingStep,
+    pub state: OnboardingState,
+}
+
 // ============================================================================
-// Log Streaming Models
+// Watch Models
 // ============================================================================
 
+/// What a project watch does when its sources change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchAction {
+    /// Stop the watch's agent run and start it again
+    RestartAgent,
+    /// Run the project's `build` script with its package manager
+    Rebuild,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct LogEvent {
-    pub run_id: String,
-    pub message: String,
-    pub log_type: LogType,
-    pub timestamp: i64,
+pub struct WatchConfig {
+    pub project_dir: String,
+    pub action: WatchAction,
+    /// Directory watched, relative to the project; `src` when omitted
+    #[serde(default)]
+    pub watch_dir: Option<String>,
+    /// Quiet time after the last change before acting
+    #[serde(default)]
+    pub debounce_ms: Option<u64>,
+    /// Agent run restarted on change, usually an eval; required for `RestartAgent`
+    #[serde(default)]
+    pub spec: Option<RunSpec>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum LogType {
-    Stdout,
-    Stderr,
-    Info,
-    Error,
-    System,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchOutcome {
+    Restarted,
+    Rebuilt,
+    BuildFailed,
+    /// The action could not be carried out, e.g. the run or build failed to start
+    Failed,
 }
 
-impl LogEvent {
-    pub fn new(run_id: String, message: String, log_type: LogType) -> Self {
-        Self {
-            run_id,
-            message,
-            log_type,
-            timestamp: chrono::Utc::now().timestamp(),
-        }
-    }
+/// A batch of source changes and what the watch did about it; payload of `watch-event`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchEvent {
+    pub watch_id: String,
+    /// Changed paths relative to the project, capped
+    pub changed_paths: Vec<String>,
+    pub total_changes: usize,
+    pub action: WatchAction,
+    pub outcome: WatchOutcome,
+    /// The agent run started for `RestartAgent`
+    pub run_id: Option<String>,
+    pub message: String,
+    pub timestamp: String,
+}
 
-    pub fn stdout(run_id: String, message: String) -> Self {
-        Self::new(run_id, message, LogType::Stdout)
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSession {
+    pub id: String,
+    pub config: WatchConfig,
+    pub started_at: String,
+    /// The agent run currently owned by the watch
+    pub run_id: Option<String>,
+    /// Recent decisions, oldest first
+    pub events: Vec<WatchEvent>,
+}
 
-    pub fn stderr(run_id: String, message: String) -> Self {
-        Self::new(run_id, message, LogType::Stderr)
-    }
+// ============================================================================
+// Activity Models
+// ============================================================================
 
-    pub fn info(run_id: String, message: String) -> Self {
-        Self::new(run_id, message, LogType::Info)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Run,
+    Terminal,
+    AgentHealth,
+    System,
+}
 
-    pub fn error(run_id: String, message: String) -> Self {
-        Self::new(run_id, message, LogType::Error)
-    }
+/// One entry of the activity timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    /// Increases by one per entry, across restarts
+    pub seq: u64,
+    pub timestamp: String,
+    pub kind: ActivityKind,
+    /// Event the entry was recorded from, e.g. `config-changed`
+    pub source: String,
+    pub summary: String,
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// The event's payload with secrets redacted; null when it was too large to keep
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
 
-    pub fn system(run_id: String, message: String) -> Self {
-        Self::new(run_id, message, LogType::System)
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityFeedPage {
+    /// Oldest first
+    pub entries: Vec<ActivityEntry>,
+    /// Pass as `since` to get only newer entries
+    pub latest_seq: u64,
 }
 
 // ============================================================================
@@ -0,0 +1,197 @@
+//! PATH augmentation for spawned processes
+//! GUI-launched apps often inherit a minimal PATH, so node/npx/bun installed via
+//! version managers are invisible. This appends well-known tool locations.
+
+use std::cmp::Reverse;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Well-known tool locations that exist on this machine
+pub fn well_known_tool_dirs() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    // Test harness builds resolve the mock CLI from next to the app binary
+    #[cfg(feature = "test-harness")]
+    candidates.extend(crate::test_harness::mock_cli_dir());
+
+    // Version managers that export their active bin dir
+    for var in ["NVM_BIN", "FNM_MULTISHELL_PATH", "PNPM_HOME"] {
+        if let Ok(dir) = std::env::var(var) {
+            candidates.push(PathBuf::from(dir));
+        }
+    }
+
+    if let Ok(volta_home) = std::env::var("VOLTA_HOME") {
+        candidates.push(PathBuf::from(volta_home).join("bin"));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        // nvm: every installed node version, newest first
+        let nvm_versions = home.join(".nvm").join("versions").join("node");
+        if let Ok(entries) = std::fs::read_dir(&nvm_versions) {
+            let mut versions: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect();
+            versions.sort_by_cached_key(|dir| Reverse(node_version(dir)));
+            candidates.extend(versions.into_iter().map(|dir| dir.join("bin")));
+        }
+
+        // fnm default alias (macOS and Linux layouts)
+        candidates.push(
+            home.join(".fnm")
+                .join("aliases")
+                .join("default")
+                .join("bin"),
+        );
+        candidates.push(
+            home.join(".local")
+                .join("share")
+                .join("fnm")
+                .join("aliases")
+                .join("default")
+                .join("bin"),
+        );
+
+        candidates.push(home.join(".volta").join("bin"));
+        candidates.push(home.join(".bun").join("bin"));
+        candidates.push(home.join(".npm-global").join("bin"));
+        candidates.push(home.join(".local").join("bin"));
+    }
+
+    if cfg!(windows) {
+        if let Ok(app_data) = std::env::var("APPDATA") {
+            candidates.push(PathBuf::from(app_data).join("npm"));
+        }
+    } else {
+        candidates.push(PathBuf::from("/opt/homebrew/bin"));
+        candidates.push(PathBuf::from("/usr/local/bin"));
+    }
+
+    candidates.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// Build the PATH for a spawned process: the current PATH, then user-configured
+/// extra dirs, then well-known tool locations
+pub fn build_spawn_path(extra_dirs: &[String]) -> String {
+    let base = std::env::var_os("PATH").unwrap_or_default();
+    join_spawn_path(&base, extra_dirs, well_known_tool_dirs())
+}
+
+/// Build the spawn PATH using the extra dirs from the saved configuration
+pub async fn spawn_path_for_app(app: &tauri::AppHandle) -> String {
+    let extra_dirs = crate::commands::config::load_config_from_file(app)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|config| config.extra_path_dirs)
+        .unwrap_or_default();
+
+    build_spawn_path(&extra_dirs)
+}
+
+/// Locate an executable on the given PATH, like `which`
+pub fn find_in_path(program: &str, path_env: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+
+    std::env::split_paths(path_env).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", program, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Split a PATH value into its entries for display
+pub fn path_entries(path_env: &str) -> Vec<String> {
+    std::env::split_paths(path_env)
+        .map(|dir| dir.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Append extra and well-known dirs to a base PATH, skipping duplicates
+fn join_spawn_path(base: &OsString, extra_dirs: &[String], known_dirs: Vec<PathBuf>) -> String {
+    let mut entries: Vec<PathBuf> = std::env::split_paths(base).collect();
+
+    let additions = extra_dirs
+        .iter()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .chain(known_dirs);
+
+    for dir in additions {
+        if !entries.contains(&dir) {
+            entries.push(dir);
+        }
+    }
+
+    match std::env::join_paths(&entries) {
+        Ok(joined) => joined.to_string_lossy().to_string(),
+        Err(e) => {
+            log::warn!(
+                "Failed to build augmented PATH, using inherited PATH: {}",
+                e
+            );
+            base.to_string_lossy().to_string()
+        }
+    }
+}
+
+/// Numeric version of an nvm install dir such as `v18.19.0`, so `v9` sorts below `v18`;
+/// `None` for names that are not versions
+fn node_version(dir: &Path) -> Option<Vec<u64>> {
+    dir.file_name()?
+        .to_str()?
+        .strip_prefix('v')?
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_join_spawn_path() {
+        let base = OsString::from("/usr/bin:/bin");
+        let extra = vec!["/custom/bin".to_string(), "".to_string()];
+        let known = vec![
+            PathBuf::from("/opt/homebrew/bin"),
+            PathBuf::from("/usr/bin"),
+        ];
+
+        let path = join_spawn_path(&base, &extra, known);
+        assert_eq!(path, "/usr/bin:/bin:/custom/bin:/opt/homebrew/bin");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path() {
+        assert!(find_in_path("sh", "/nonexistent:/bin:/usr/bin").is_some());
+        assert!(find_in_path("definitely-not-a-real-binary", "/bin:/usr/bin").is_none());
+    }
+
+    #[test]
+    fn test_node_versions_sort_numerically() {
+        let mut versions: Vec<PathBuf> = ["v9.11.2", "v18.19.0", "system", "v18.2.1", "v20.0.0"]
+            .iter()
+            .map(|name| PathBuf::from("/nvm").join(name))
+            .collect();
+        versions.sort_by_cached_key(|dir| Reverse(node_version(dir)));
+
+        let names: Vec<_> = versions
+            .iter()
+            .map(|dir| dir.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["v20.0.0", "v18.19.0", "v18.2.1", "v9.11.2", "system"]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Log line severity
+//! Recognizes the level marker CLI tools put near the start of their log lines
+//! (`INFO`, `[WARN]`, `ERROR:`, `npm ERR!`), looking past timestamps and ANSI color codes,
+//! and picks out the package-manager chatter that npx and bunx print to stderr
+
+use crate::models::LogSeverity;
+use std::borrow::Cow;
+
+/// Words at the start of a line searched for a level marker
+const MAX_PREFIX_WORDS: usize = 4;
+
+/// Lowercased prefixes of stderr lines that are progress or advice rather than errors
+const BENIGN_STDERR_PREFIXES: &[&str] = &[
+    "npm notice",
+    "npm http ",
+    "npm warn",
+    "npx: installed",
+    "need to install the following packages",
+    "ok to proceed?",
+    "added ",
+    "removed ",
+    "changed ",
+    "up to date",
+    "found 0 vulnerabilities",
+    "run `npm fund`",
+    "run `npm audit",
+    "bun add v",
+    "bunx v",
+    "resolving dependencies",
+    "resolved, downloaded and extracted",
+    "saved lockfile",
+    "(use `node --trace-",
+];
+
+/// Lowercased fragments of Node.js runtime warnings that don't affect the run
+const BENIGN_STDERR_FRAGMENTS: &[&str] = &[
+    "deprecationwarning",
+    "experimentalwarning",
+    "warning: deprecated",
+];
+
+/// Severity named by a line's level marker, if it has one
+pub fn parse_severity(line: &str) -> Option<LogSeverity> {
+    strip_ansi(line)
+        .split_whitespace()
+        .take(MAX_PREFIX_WORDS)
+        .find_map(|word| severity_word(word.trim_matches(|c: char| !c.is_ascii_alphanumeric())))
+}
+
+fn severity_word(word: &str) -> Option<LogSeverity> {
+    match word.to_ascii_uppercase().as_str() {
+        "TRACE" => Some(LogSeverity::Trace),
+        "DEBUG" | "DBG" => Some(LogSeverity::Debug),
+        "INFO" | "INF" => Some(LogSeverity::Info),
+        "WARN" | "WARNING" | "WRN" => Some(LogSeverity::Warn),
+        "ERROR" | "ERR" => Some(LogSeverity::Error),
+        "FATAL" | "CRITICAL" | "PANIC" => Some(LogSeverity::Fatal),
+        _ => None,
+    }
+}
+
+/// Whether a stderr line is known package-manager or runtime noise (install progress,
+/// npm notices, deprecation warnings) rather than a real error
+pub fn is_benign_stderr(line: &str) -> bool {
+    let line = strip_ansi(line).trim().to_lowercase();
+    if line.is_empty() {
+        return true;
+    }
+    BENIGN_STDERR_PREFIXES
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+        || BENIGN_STDERR_FRAGMENTS
+            .iter()
+            .any(|fragment| line.contains(fragment))
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement) from a line
+pub fn strip_ansi(line: &str) -> Cow<'_, str> {
+    if !line.contains('\x1b') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        // CSI sequences end with a byte in `@`..=`~`; other escapes are two characters
+        if chars.next_if_eq(&'[').is_some() {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_severity() {
+        assert_eq!(
+            parse_severity("[2024-05-01 10:00:00] INFO: Agent started"),
+            Some(LogSeverity::Info)
+        );
+        assert_eq!(
+            parse_severity("\x1b[33mWarn\x1b[39m Missing OPENAI_API_KEY"),
+            Some(LogSeverity::Warn)
+        );
+        assert_eq!(
+            parse_severity("npm ERR! code ENOENT"),
+            Some(LogSeverity::Error)
+        );
+        assert_eq!(
+            parse_severity("[FATAL] out of memory"),
+            Some(LogSeverity::Fatal)
+        );
+        assert_eq!(parse_severity("Agent replied: hello"), None);
+        assert_eq!(parse_severity("the first few words are not an error"), None);
+    }
+
+    #[test]
+    fn test_severity_order() {
+        assert!(LogSeverity::Warn >= LogSeverity::Info);
+        assert!(LogSeverity::Debug < LogSeverity::Warn);
+        assert!(LogSeverity::Fatal > LogSeverity::Error);
+    }
+
+    #[test]
+    fn test_benign_stderr() {
+        for line in [
+            "npm notice New major version of npm available! 10.2.4 -> 11.0.0",
+            "npm WARN deprecated inflight@1.0.6: This module is not supported",
+            "npm warn exec The following package was not found and will be installed",
+            "(node:4242) [DEP0040] DeprecationWarning: The `punycode` module is deprecated.",
+            "(Use `node --trace-deprecation ...` to show where the warning was created)",
+            "\x1b[2mResolving dependencies\x1b[0m",
+            "added 312 packages in 9s",
+            "",
+        ] {
+            assert!(is_benign_stderr(line), "{}", line);
+        }
+
+        for line in [
+            "npm ERR! code ENOENT",
+            "Error: Cannot find module '@elizaos/core'",
+            "TypeError: undefined is not a function",
+            "[ERROR] Failed to start agent",
+        ] {
+            assert!(!is_benign_stderr(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32m✓ done\x1b[0m"), "✓ done");
+        assert_eq!(strip_ansi("plain"), "plain");
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,363 @@
+//! Output stream splitting
+//! Splits child process output into lines, treating carriage-return rewrites
+//! (npm and download progress bars) as in-place updates rather than new lines, and
+//! collapsing binary output into a single summarized placeholder
+
+use std::collections::VecDeque;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Lines longer than this are flushed as-is so a missing newline can't grow the buffer forever
+pub const MAX_LINE_BYTES: usize = 64 * 1024;
+const READ_CHUNK_BYTES: usize = 8 * 1024;
+const BINARY_PREVIEW_BYTES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamItem {
+    /// A completed line; for rewritten lines this is the final state
+    Line(String),
+    /// An intermediate state of a line being rewritten with `\r`
+    Progress(String),
+    /// A run of consecutive binary output
+    Binary(BinaryChunk),
+}
+
+/// Summary of binary output that was withheld from the log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinaryChunk {
+    pub bytes: usize,
+    pub preview: Vec<u8>,
+}
+
+impl BinaryChunk {
+    /// Log line shown in place of the binary data
+    pub fn placeholder(&self) -> String {
+        let hex: Vec<String> = self.preview.iter().map(|b| format!("{:02x}", b)).collect();
+        format!(
+            "[binary output omitted: {} bytes, starting {}]",
+            self.bytes,
+            hex.join(" ")
+        )
+    }
+
+    fn extend(&mut self, bytes: &[u8]) {
+        self.bytes += bytes.len();
+        let room = BINARY_PREVIEW_BYTES.saturating_sub(self.preview.len());
+        self.preview
+            .extend_from_slice(&bytes[..room.min(bytes.len())]);
+    }
+}
+
+/// Incremental splitter on `\n`, `\r\n` and bare `\r`
+#[derive(Debug, Default)]
+pub struct LineSplitter {
+    current: Vec<u8>,
+    last_progress: Option<String>,
+    pending_cr: bool,
+    binary: Option<BinaryChunk>,
+}
+
+impl LineSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of output, returning the items it completes
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+
+        for &byte in bytes {
+            if self.pending_cr {
+                self.pending_cr = false;
+                if byte != b'\n' {
+                    // A bare `\r` rewrites the line: what we have so far is a progress state
+                    self.take_progress(&mut items);
+                }
+            }
+
+            match byte {
+                b'\n' => self.take_line(&mut items),
+                b'\r' => self.pending_cr = true,
+                _ => {
+                    self.current.push(byte);
+                    if self.current.len() >= MAX_LINE_BYTES {
+                        self.take_line(&mut items);
+                    }
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Flush whatever is left once the stream has ended
+    pub fn finish(&mut self) -> Vec<StreamItem> {
+        let mut items = Vec::new();
+        self.pending_cr = false;
+        if !self.current.is_empty() || self.last_progress.is_some() {
+            self.take_line(&mut items);
+        }
+        self.flush_binary(&mut items);
+        items
+    }
+
+    fn take_progress(&mut self, items: &mut Vec<StreamItem>) {
+        let segment = std::mem::take(&mut self.current);
+        if self.absorb_binary(&segment) {
+            return;
+        }
+        self.flush_binary(items);
+
+        let text = decode(&segment);
+        items.push(StreamItem::Progress(text.clone()));
+        self.last_progress = Some(text);
+    }
+
+    fn take_line(&mut self, items: &mut Vec<StreamItem>) {
+        let segment = std::mem::take(&mut self.current);
+        if self.absorb_binary(&segment) {
+            self.last_progress = None;
+            return;
+        }
+        self.flush_binary(items);
+
+        let text = decode(&segment);
+        // "50%\r100%\r\n" ends with an empty rewrite; keep the last visible state
+        match self.last_progress.take() {
+            Some(progress) if text.is_empty() => items.push(StreamItem::Line(progress)),
+            _ => items.push(StreamItem::Line(text)),
+        }
+    }
+
+    /// Fold a binary segment into the pending binary run
+    fn absorb_binary(&mut self, segment: &[u8]) -> bool {
+        if !is_binary(segment) {
+            return false;
+        }
+        self.binary
+            .get_or_insert_with(BinaryChunk::default)
+            .extend(segment);
+        true
+    }
+
+    fn flush_binary(&mut self, items: &mut Vec<StreamItem>) {
+        if let Some(chunk) = self.binary.take() {
+            items.push(StreamItem::Binary(chunk));
+        }
+    }
+}
+
+fn decode(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Whether a segment looks like binary data rather than text
+///
+/// NUL bytes and invalid UTF-8 are binary; so is a high share of control characters
+/// other than tab, backspace, bell and the ESC that starts ANSI color codes. A multi-byte
+/// character cut off at the end of the segment is not treated as invalid.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.contains(&0) {
+        return true;
+    }
+    if let Err(e) = std::str::from_utf8(bytes) {
+        if e.error_len().is_some() {
+            return true;
+        }
+    }
+
+    let control = bytes
+        .iter()
+        .filter(|&&b| (b < 0x20 && !matches!(b, b'\t' | 0x07 | 0x08 | 0x1b)) || b == 0x7f)
+        .count();
+    bytes.len() >= 8 && control * 10 > bytes.len()
+}
+
+/// Final lines of a stream, with binary runs replaced by their placeholders
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    pub lines: Vec<String>,
+    /// Whether any binary output was withheld
+    pub binary: bool,
+}
+
+impl CapturedOutput {
+    /// Record an item; progress states are dropped in favor of the final line
+    pub fn push(&mut self, item: StreamItem) {
+        match item {
+            StreamItem::Line(text) => self.lines.push(text),
+            StreamItem::Progress(_) => {}
+            StreamItem::Binary(chunk) => {
+                self.binary = true;
+                self.lines.push(chunk.placeholder());
+            }
+        }
+    }
+}
+
+/// Split a complete output buffer
+pub fn split_output(bytes: &[u8]) -> CapturedOutput {
+    let mut splitter = LineSplitter::new();
+    let mut output = CapturedOutput::default();
+    for item in splitter.push(bytes).into_iter().chain(splitter.finish()) {
+        output.push(item);
+    }
+    output
+}
+
+/// Read a pipe to the end
+pub async fn read_output<R: AsyncRead + Unpin>(reader: R) -> CapturedOutput {
+    let mut reader = StreamReader::new(reader);
+    let mut output = CapturedOutput::default();
+    while let Some(item) = reader.next_item().await {
+        output.push(item);
+    }
+    output
+}
+
+/// Async reader that yields [`StreamItem`]s from a child process pipe
+pub struct StreamReader<R> {
+    reader: R,
+    splitter: LineSplitter,
+    pending: VecDeque<StreamItem>,
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> StreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            splitter: LineSplitter::new(),
+            pending: VecDeque::new(),
+            eof: false,
+        }
+    }
+
+    /// Next line or progress update, or None once the stream is exhausted
+    pub async fn next_item(&mut self) -> Option<StreamItem> {
+        let mut buf = [0u8; READ_CHUNK_BYTES];
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(item);
+            }
+            if self.eof {
+                return None;
+            }
+
+            match self.reader.read(&mut buf).await {
+                Ok(0) | Err(_) => {
+                    self.eof = true;
+                    self.pending.extend(self.splitter.finish());
+                }
+                Ok(n) => self.pending.extend(self.splitter.push(&buf[..n])),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> StreamItem {
+        StreamItem::Line(text.to_string())
+    }
+
+    fn progress(text: &str) -> StreamItem {
+        StreamItem::Progress(text.to_string())
+    }
+
+    #[test]
+    fn test_plain_and_crlf_lines() {
+        let mut splitter = LineSplitter::new();
+        assert_eq!(
+            splitter.push(b"one\ntwo\r\nthr"),
+            vec![line("one"), line("two")]
+        );
+        assert_eq!(splitter.push(b"ee\n"), vec![line("three")]);
+        assert!(splitter.finish().is_empty());
+    }
+
+    #[test]
+    fn test_carriage_return_progress() {
+        let mut splitter = LineSplitter::new();
+        let mut items = splitter.push(b"10%\r50%\r");
+        items.extend(splitter.push(b"100%\r\ndone\n"));
+        assert_eq!(
+            items,
+            vec![progress("10%"), progress("50%"), line("100%"), line("done")]
+        );
+    }
+
+    #[test]
+    fn test_progress_ending_in_bare_cr() {
+        let mut splitter = LineSplitter::new();
+        let items = splitter.push(b"1/2\r2/2\r\n");
+        assert_eq!(items, vec![progress("1/2"), line("2/2")]);
+
+        let mut splitter = LineSplitter::new();
+        let mut items = splitter.push(b"1/2\r2/2\r");
+        items.extend(splitter.finish());
+        assert_eq!(items, vec![progress("1/2"), line("2/2")]);
+    }
+
+    #[test]
+    fn test_long_line_is_flushed() {
+        let mut splitter = LineSplitter::new();
+        let items = splitter.push(&vec![b'x'; MAX_LINE_BYTES + 10]);
+        assert_eq!(items.len(), 1);
+        assert_eq!(splitter.finish(), vec![line(&"x".repeat(10))]);
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(!is_binary(b"plain text"));
+        assert!(!is_binary("\x1b[32m✓ done\x1b[0m".as_bytes()));
+        assert!(!is_binary(&"é".as_bytes()[..1]));
+        assert!(is_binary(b"ELF\0\x02\x01"));
+        assert!(is_binary(&[0xff, 0xfe, b'a', b'b']));
+        assert!(is_binary(&[0x01, 0x02, 0x03, 0x04, 0x05, b'a', b'b', b'c']));
+    }
+
+    #[test]
+    fn test_binary_runs_are_coalesced() {
+        let mut splitter = LineSplitter::new();
+        let mut items = splitter.push(b"before\n\x7fELF\0\x01\n\0\0\xff\n");
+        items.extend(splitter.push(b"after\n"));
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], line("before"));
+        match &items[1] {
+            StreamItem::Binary(chunk) => {
+                assert_eq!(chunk.bytes, 9);
+                assert_eq!(chunk.preview[..4], *b"\x7fELF");
+                assert!(chunk
+                    .placeholder()
+                    .starts_with("[binary output omitted: 9 bytes, starting 7f 45 4c 46"));
+            }
+            other => panic!("expected binary chunk, got {:?}", other),
+        }
+        assert_eq!(items[2], line("after"));
+
+        let mut splitter = LineSplitter::new();
+        assert!(splitter.push(b"\0\0").is_empty());
+        assert!(matches!(splitter.finish()[..], [StreamItem::Binary(_)]));
+    }
+
+    #[test]
+    fn test_split_output() {
+        let output = split_output(b"50%\r100%\nok\n\0\0\n");
+        assert_eq!(output.lines.len(), 3);
+        assert_eq!(output.lines[..2], ["100%".to_string(), "ok".to_string()]);
+        assert!(output.binary);
+        assert!(!split_output(b"fine\n").binary);
+    }
+
+    #[tokio::test]
+    async fn test_stream_reader() {
+        let mut reader = StreamReader::new(&b"a\rb\nc"[..]);
+        assert_eq!(reader.next_item().await, Some(progress("a")));
+        assert_eq!(reader.next_item().await, Some(line("b")));
+        assert_eq!(reader.next_item().await, Some(line("c")));
+        assert_eq!(reader.next_item().await, None);
+    }
+}
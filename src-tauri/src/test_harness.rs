@@ -0,0 +1,149 @@
+//! Test harness
+//! A scripted stand-in for the ElizaOS CLI (built as the `mock-eliza` binary with the
+//! `test-harness` feature) so streaming, kill, timeout and telemetry paths can be
+//! exercised end to end without node or the real CLI
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Executable name of the mock CLI; resolved from PATH like the real runners
+pub const MOCK_CLI_PROGRAM: &str = "mock-eliza";
+/// Path to a JSON [`MockCliScript`] the mock CLI plays back
+pub const MOCK_SCRIPT_ENV: &str = "ELIZA_MOCK_SCRIPT";
+/// Directory containing the `mock-eliza` binary, when not next to the app executable
+pub const MOCK_CLI_DIR_ENV: &str = "ELIZA_MOCK_CLI_DIR";
+const MOCK_VERSION: &str = "0.0.0-mock";
+
+/// One scripted action; JSON as `{"stdout": "..."}`, `{"stderr": "..."}` or `{"sleepMs": 100}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MockStep {
+    Stdout(String),
+    Stderr(String),
+    SleepMs(u64),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockCliScript {
+    #[serde(default)]
+    pub steps: Vec<MockStep>,
+    #[serde(default)]
+    pub exit_code: i32,
+    /// Keep running after the last step until killed
+    #[serde(default)]
+    pub hang: bool,
+}
+
+impl MockCliScript {
+    /// Script used when no script file is configured: echo the arguments and succeed
+    pub fn echo(args: &[String]) -> Self {
+        Self {
+            steps: vec![
+                MockStep::Stdout(format!("mock-eliza: {}", args.join(" "))),
+                MockStep::Stderr("mock-eliza: no script configured".to_string()),
+            ],
+            exit_code: 0,
+            hang: false,
+        }
+    }
+}
+
+/// Directory that holds the mock CLI binary
+pub fn mock_cli_dir() -> Option<PathBuf> {
+    std::env::var_os(MOCK_CLI_DIR_ENV)
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+        })
+        .filter(|dir| dir.is_dir())
+}
+
+/// Entry point of the `mock-eliza` binary; returns the process exit code
+pub fn run_mock_cli(args: Vec<String>) -> i32 {
+    // Resolution probes call `--version`; answer without playing the script
+    if args.iter().any(|arg| arg == "--version") {
+        println!("{}", MOCK_VERSION);
+        return 0;
+    }
+
+    let script = match std::env::var_os(MOCK_SCRIPT_ENV) {
+        Some(path) => match load_script(Path::new(&path)) {
+            Ok(script) => script,
+            Err(e) => {
+                eprintln!("mock-eliza: failed to load {:?}: {}", path, e);
+                return 2;
+            }
+        },
+        None => MockCliScript::echo(&args),
+    };
+
+    play_script(&script, &mut std::io::stdout(), &mut std::io::stderr());
+
+    if script.hang {
+        loop {
+            std::thread::sleep(Duration::from_secs(3600));
+        }
+    }
+    script.exit_code
+}
+
+fn load_script(path: &Path) -> Result<MockCliScript, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&contents).map_err(|e| e.to_string())
+}
+
+/// Write each step's output, flushing per line so readers see it as it is produced
+fn play_script(script: &MockCliScript, stdout: &mut impl Write, stderr: &mut impl Write) {
+    for step in &script.steps {
+        match step {
+            MockStep::Stdout(line) => {
+                let _ = writeln!(stdout, "{}", line);
+                let _ = stdout.flush();
+            }
+            MockStep::Stderr(line) => {
+                let _ = writeln!(stderr, "{}", line);
+                let _ = stderr.flush();
+            }
+            MockStep::SleepMs(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let script: MockCliScript = serde_json::from_str(
+            r#"{"steps":[{"stdout":"ready"},{"sleepMs":5},{"stderr":"oops"}],"exitCode":3}"#,
+        )
+        .unwrap();
+        assert_eq!(script.steps.len(), 3);
+        assert_eq!(script.steps[1], MockStep::SleepMs(5));
+        assert_eq!(script.exit_code, 3);
+        assert!(!script.hang);
+    }
+
+    #[test]
+    fn test_play_script() {
+        let script = MockCliScript {
+            steps: vec![
+                MockStep::Stdout("one".to_string()),
+                MockStep::Stderr("two".to_string()),
+                MockStep::Stdout("three".to_string()),
+            ],
+            ..MockCliScript::default()
+        };
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        play_script(&script, &mut stdout, &mut stderr);
+
+        assert_eq!(String::from_utf8(stdout).unwrap(), "one\nthree\n");
+        assert_eq!(String::from_utf8(stderr).unwrap(), "two\n");
+    }
+}
@@ -463,8 +463,9 @@ export const useRunnerStore = create<RunnerState>()(
       runEval: async (filePath: string, config: SandboxConfig) => {
         return get().startStreamingRun({
           mode: 'eval',
-          args: ['eval', '-f', filePath],
+          args: [],
           env: {},
+          eval: { scenarioPath: filePath },
         }, config);
       },
     })),
@@ -1,42 +1,81 @@
 /**
  * Core Type Definitions for MVP Tauri ElizaOS CLI
- * These types define the data structures used throughout the application
- * and match the Rust models for proper IPC communication.
+ * The types shared with the backend are re-exported from `ipc.generated.ts`, which
+ * `cargo run --bin gen-ipc-types` generates from the Rust models; this file adds the
+ * frontend-only types, validation schemas and helpers around them.
  */
 
 import { z } from 'zod';
+import type {
+  ApiResponse,
+  RunSpec,
+  SandboxConfig,
+  TelemetryEvent,
+} from './ipc.generated';
+
+// Models shared with the backend come from the generated IPC contract
+export type * from './ipc.generated';
 
 // ============================================================================
 // Configuration Types
 // ============================================================================
 
-export interface SandboxConfig {
-  baseUrl: string;
-  apiKey: string;
-  defaultModel?: string;
+/** Shareable copy of a configuration written by `export_sandbox_config` */
+export interface SandboxConfigExport {
+  formatVersion: number;
+  exportedAt: string;
+  appVersion: string;
+  /** Whether `config.apiKey` holds the API key; it is blank otherwise */
+  includesSecrets: boolean;
+  config: SandboxConfig;
 }
 
 const SandboxConfigSchema = z.object({
   baseUrl: z.string().url('Invalid base URL format'),
   apiKey: z.string().min(1, 'API key is required').regex(/^eliza_[a-f0-9]{64}$/, 'Invalid API key format').length(70, 'API key must be exactly 70 characters'),
   defaultModel: z.string().optional(),
+  runnerPriority: z.array(z.enum(['elizaos', 'bunx', 'npx'])).optional(),
+  extraPathDirs: z.array(z.string()).optional(),
+  auditRetentionDays: z.number().int().positive().optional(),
+  budget: z.object({
+    dailyTokens: z.number().int().positive().optional(),
+    monthlyTokens: z.number().int().positive().optional(),
+    dailyCostUsd: z.number().positive().optional(),
+    monthlyCostUsd: z.number().positive().optional(),
+    usdPer1kTokens: z.number().nonnegative().optional(),
+    notify: z.boolean().optional(),
+    blockWhenExceeded: z.boolean().optional(),
+  }).optional(),
+  watchdog: z.object({
+    stallAfterSecs: z.number().int().positive(),
+    autoInterrupt: z.boolean().optional(),
+  }).optional(),
+  retention: z.object({
+    maxRuns: z.number().int().positive().optional(),
+    maxLogBytes: z.number().int().positive().optional(),
+    maxAgeDays: z.number().int().positive().optional(),
+  }).optional(),
+  queue: z.object({
+    maxConcurrentRuns: z.number().int().positive(),
+    preempt: z.boolean().optional(),
+  }).optional(),
+  egress: z.object({
+    allowedHosts: z.array(z.string()).optional(),
+    sampleIntervalSecs: z.number().int().positive().optional(),
+  }).optional(),
+  preventSleep: z.boolean().optional(),
+  autoRetryPortConflicts: z.boolean().optional(),
+  telemetrySink: z.discriminatedUnion('type', [
+    z.object({ type: z.literal('sandbox'), path: z.string().optional() }),
+    z.object({ type: z.literal('webhook'), url: z.string().url('Invalid webhook URL') }),
+    z.object({ type: z.literal('file'), path: z.string().min(1, 'File path is required') }),
+  ]).optional(),
 });
 
 // ============================================================================
 // Process Management Types
 // ============================================================================
 
-export type RunMode = 'doctor' | 'run' | 'eval' | 'custom';
-
-export interface RunSpec {
-  id: string;
-  mode: RunMode;
-  args: string[];
-  env: Record<string, string>;
-  workingDir?: string;
-  characterFile?: string;
-}
-
 const RunSpecSchema = z.object({
   id: z.string(),
   mode: z.enum(['doctor', 'run', 'eval', 'custom']),
@@ -44,56 +83,26 @@ const RunSpecSchema = z.object({
   env: z.record(z.string()),
   workingDir: z.string().optional(),
   characterFile: z.string().optional(),
+  eval: z.object({
+    scenarioPath: z.string(),
+    iterations: z.number().int().positive().optional(),
+    reportPath: z.string().optional(),
+  }).optional(),
+  after: z.object({
+    runId: z.string(),
+    condition: z.enum(['success', 'completion', 'failure']).optional(),
+  }).optional(),
+  simulate: z.boolean().optional(),
+  minEventSeverity: z.enum(['trace', 'debug', 'info', 'warn', 'error', 'fatal']).optional(),
+  priority: z.enum(['low', 'normal', 'high']).optional(),
+  captureNetwork: z.boolean().optional(),
+  variables: z.record(z.string()).optional(),
 });
 
-export interface RunResult {
-  id: string;
-  spec: RunSpec;
-  startedAt: Date;
-  endedAt?: Date;
-  exitCode?: number;
-  stdout: string[];
-  stderr: string[];
-  durationMs?: number;
-  status: 'running' | 'completed' | 'failed' | 'killed';
-  pid?: number; // Process ID for active process management
-}
-
-// ============================================================================
-// Preflight Check Types
-// ============================================================================
-
-export interface ToolCheck {
-  installed: boolean;
-  version?: string;
-  path?: string;
-}
-
-export interface PreflightResult {
-  node: ToolCheck;
-  npm: ToolCheck;
-  eliza: ToolCheck;
-  recommendations: string[];
-  overallStatus: 'ready' | 'needs_setup' | 'critical_issues';
-}
-
 // ============================================================================
 // Telemetry Types
 // ============================================================================
 
-export interface TelemetryEvent {
-  deviceId: string;
-  command: string;
-  args: string[];
-  startedAt: string;
-  durationMs: number;
-  exitCode: number;
-  bytesOut: number;
-  approxTokens?: number;
-  error?: string;
-  metadata?: Record<string, unknown>;
-}
-
 const TelemetryEventSchema = z.object({
   deviceId: z.string(),
   command: z.string(),
@@ -126,13 +135,6 @@ export interface LogEntry {
   source?: string;
 }
 
-export interface LogEvent {
-  runId: string;
-  message: string;
-  logType: 'stdout' | 'stderr' | 'info' | 'error' | 'system';
-  timestamp: number;
-}
-
 // ============================================================================
 // Terminal Types
 // ============================================================================
@@ -158,29 +160,29 @@ export interface TerminalSession {
 }
 
 // ============================================================================
-// API Response Types
+// Scenario Types
 // ============================================================================
 
-export interface ApiResponse<T = unknown> {
-  success: boolean;
-  data?: T;
-  error?: {
-    code: string;
-    message: string;
-    details?: Record<string, unknown>;
-  };
+export type ScenarioAssertion =
+  | { type: 'contains'; value: string }
+  | { type: 'notContains'; value: string }
+  | { type: 'equals'; value: string }
+  | { type: 'nonEmpty' }
+  | { type: 'maxLatencyMs'; value: number };
+
+/** Scenario file contents (YAML or JSON) */
+export interface Scenario {
+  name: string;
+  /** Agent ID or name; the server's first agent when omitted */
+  agent?: string;
+  steps: { message: string; expect?: ScenarioAssertion[] }[];
 }
 
-export interface ConnectionTestResult {
-  success: boolean;
-  latencyMs?: number;
-  error?: string;
-  metadata?: {
-    endpoint: string;
-    timestamp: string;
-    version?: string;
-  };
-}
+// ============================================================================
+// App Log Types
+// ============================================================================
+
+export type AppLogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace';
 
 // ============================================================================
 // Security Types
@@ -0,0 +1,3039 @@
+// Generated by `cargo run --bin gen-ipc-types` from the backend's Rust sources; do not
+// edit. `cargo test` fails while it is out of date, so regenerate it with the Rust change.
+
+import { invoke, type InvokeArgs } from '@tauri-apps/api/core';
+import { listen, type EventCallback, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface SandboxConfig {
+  baseUrl: string;
+  apiKey: string;
+  defaultModel?: string | null;
+  /** Runner lookup order for the ElizaOS CLI; defaults to elizaos > bunx > npx */
+  runnerPriority?: CliRunner[] | null;
+  /** Extra directories appended to PATH for spawned processes */
+  extraPathDirs?: string[] | null;
+  /**
+   * ElizaOS subcommands permitted in Custom runs; defaults to `DEFAULT_CUSTOM_SUBCOMMANDS`.
+   * Runs read it from the saved profile, where `set_allowed_custom_subcommands` sets it
+   */
+  allowedCustomSubcommands?: string[] | null;
+  /** Days to keep audit log entries; defaults to `DEFAULT_AUDIT_RETENTION_DAYS` */
+  auditRetentionDays?: number | null;
+  /** Token and cost limits checked against run telemetry */
+  budget?: BudgetConfig | null;
+  /** Warn about (and optionally interrupt) runs that stop producing output */
+  watchdog?: WatchdogConfig | null;
+  /** Limits enforced on run history and log files by the storage vacuum */
+  retention?: RetentionConfig | null;
+  /** Concurrency limit for streaming runs; unlimited when unset */
+  queue?: RunQueueConfig | null;
+  /** Sample and check the network endpoints streaming runs connect to */
+  egress?: EgressConfig | null;
+  /** Keep the system awake while agent or eval runs are active */
+  preventSleep?: boolean;
+  /**
+   * Retry agent runs that failed because their port was taken on a free port, instead
+   * of only suggesting it
+   */
+  autoRetryPortConflicts?: boolean;
+  /** OS keyring entry holding the API key when it is not stored in the file */
+  apiKeyRef?: string | null;
+  /** Where telemetry events go; the Sandbox's `/telemetry/cli` endpoint when unset */
+  telemetrySink?: TelemetrySink | null;
+  /** Proxy and extra root certificate used by the shared Sandbox HTTP client */
+  http?: HttpClientConfig | null;
+  /** Endpoint probed by connection tests and the connectivity monitor */
+  healthCheck?: HealthCheckConfig | null;
+  /**
+   * Hosts besides the base URL's that a project's `.eliza-desktop.json` may send runs
+   * and their API key to. Runs read it from the saved profile, where
+   * `set_trusted_project_hosts` sets it
+   */
+  trustedProjectHosts?: string[] | null;
+}
+
+/** Outcome of `import_sandbox_config` */
+export interface ConfigImportResult {
+  profile: ConfigProfileSummary;
+  /** The file carried an API key, which replaced the profile's */
+  apiKeyImported: boolean;
+  /** Whether the profile has an API key after the import */
+  hasApiKey: boolean;
+}
+
+export type ConfigChangeKind =
+  | 'saved'
+  | 'cleared'
+  /** Another profile became the active one */
+  | 'activated';
+
+/** Payload of the `config-changed` event */
+export interface ConfigChanged {
+  kind: ConfigChangeKind;
+  profile: string;
+  /** Whether the changed profile is the one `load_sandbox_config` uses */
+  active: boolean;
+  /** The profile's configuration with the API key redacted; None once cleared */
+  config?: SandboxConfig | null;
+  changedAt: string;
+}
+
+/** A named Sandbox configuration, without its API key */
+export interface ConfigProfileSummary {
+  name: string;
+  /** Whether `load_sandbox_config` and background tasks use this profile */
+  active: boolean;
+  baseUrl: string;
+  defaultModel?: string | null;
+}
+
+export type ConfigBackupReason =
+  /** Taken before a save, import, login or restore replaced the configuration */
+  | 'overwritten'
+  | 'cleared';
+
+/**
+ * A saved configuration kept before it was replaced, restorable with
+ * `restore_config_backup`
+ */
+export interface ConfigBackup {
+  id: string;
+  profile: string;
+  createdAt: string;
+  reason: ConfigBackupReason;
+  baseUrl: string;
+  defaultModel?: string | null;
+  /**
+   * Whether the backup holds the API key; backups taken while the app lock keeps the
+   * key don't, and restoring them keeps the profile's current key
+   */
+  hasApiKey: boolean;
+}
+
+/** Daily and monthly usage limits; unset limits are not checked */
+export interface BudgetConfig {
+  dailyTokens?: number | null;
+  monthlyTokens?: number | null;
+  dailyCostUsd?: number | null;
+  monthlyCostUsd?: number | null;
+  /** Price used to turn token counts into cost; cost limits need it */
+  usdPer1kTokens?: number | null;
+  /** Show an OS notification when a threshold is crossed */
+  notify?: boolean;
+  /** Refuse to start new runs while any limit is exceeded */
+  blockWhenExceeded?: boolean;
+}
+
+/** Output-inactivity watchdog for streaming runs */
+export interface WatchdogConfig {
+  /** Seconds without stdout/stderr output before a run counts as stalled */
+  stallAfterSecs: number;
+  /** Send the run an interrupt (Ctrl-C) when it stalls */
+  autoInterrupt?: boolean;
+}
+
+/** Storage retention limits; unset limits are not enforced */
+export interface RetentionConfig {
+  /** Most recent runs kept in history */
+  maxRuns?: number | null;
+  /** Total size of the app log files */
+  maxLogBytes?: number | null;
+  /** Runs and log files older than this are removed */
+  maxAgeDays?: number | null;
+}
+
+/** Limits how many streaming runs execute at once */
+export interface RunQueueConfig {
+  /** Runs allowed to execute at the same time; further runs wait in priority order */
+  maxConcurrentRuns: number;
+  /** Pause lower-priority runs to make room for a higher-priority one (Unix only) */
+  preempt?: boolean;
+}
+
+/** Network egress monitoring for streaming runs */
+export interface EgressConfig {
+  /**
+   * Host names or IP addresses runs are expected to reach; the Sandbox host and
+   * loopback addresses are always allowed
+   */
+  allowedHosts?: string[];
+  /** Seconds between connection samples; defaults to `DEFAULT_EGRESS_SAMPLE_SECS` */
+  sampleIntervalSecs?: number | null;
+}
+
+/** Network settings of the shared Sandbox HTTP client */
+export interface HttpClientConfig {
+  /**
+   * Proxy for all Sandbox requests, e.g. `http://proxy.corp:8080`; the system proxy
+   * settings apply when unset
+   */
+  proxyUrl?: string | null;
+  /**
+   * PEM file with a root certificate to trust in addition to the system ones, for
+   * proxies or self-hosted Sandboxes using a private CA
+   */
+  caCertPath?: string | null;
+}
+
+/** Endpoint probed to check that the Sandbox is up */
+export interface HealthCheckConfig {
+  /**
+   * Path at the Sandbox root (without any `/api/v1` suffix of the base URL); defaults to
+   * `DEFAULT_HEALTH_CHECK_PATH`
+   */
+  path?: string | null;
+  method?: HealthCheckMethod | null;
+  /**
+   * Statuses that count as healthy; any 2xx when unset. A 401 always counts as reachable
+   * with a rejected API key
+   */
+  expectedStatuses?: number[] | null;
+}
+
+export type HealthCheckMethod =
+  | 'GET'
+  | 'HEAD'
+  | 'POST';
+
+/** Destination for telemetry events */
+export type TelemetrySink =
+  /**
+   * POST to the Sandbox with its API key; `path` is relative to the base URL and
+   * defaults to `DEFAULT_TELEMETRY_PATH`
+   */
+  | { type: 'sandbox'; path?: string | null }
+  /** POST to a self-hosted collector, without the Sandbox API key */
+  | { type: 'webhook'; url: string }
+  /** Append events as JSON lines to a local file */
+  | { type: 'file'; path: string };
+
+export type RunMode =
+  | 'doctor'
+  | 'run'
+  | 'eval'
+  | 'custom';
+
+/** How the ElizaOS CLI is launched */
+export type CliRunner =
+  /** Globally installed `elizaos` binary */
+  | 'elizaos'
+  /** `bunx @elizaos/cli` - much faster startup than npx */
+  | 'bunx'
+  /** `npx -y @elizaos/cli@latest` */
+  | 'npx'
+  /** Scripted `mock-eliza` stand-in used by end-to-end tests */
+  | 'mock';
+
+/** Outcome of probing a single runner during CLI resolution */
+export interface RunnerProbe {
+  runner: CliRunner;
+  path?: string | null;
+  version?: string | null;
+  available: boolean;
+  rejectionReason?: string | null;
+}
+
+/** Explains which ElizaOS CLI will be used and why */
+export interface CliResolutionReport {
+  selected?: CliRunner | null;
+  selectedPath?: string | null;
+  selectedVersion?: string | null;
+  pathEntries: string[];
+  probes: RunnerProbe[];
+  cached: boolean;
+}
+
+export interface RunSpec {
+  id: string;
+  mode: RunMode;
+  args: string[];
+  env: Record<string, string>;
+  workingDir?: string | null;
+  characterFile?: string | null;
+  /** Evaluation parameters, required for `RunMode::Eval` */
+  eval?: EvalSpec | null;
+  /** Server port for agent runs; assigned automatically for run groups */
+  port?: number | null;
+  /** Run that must finish first; only honored by `schedule_runs` */
+  after?: RunDependency | null;
+  /** Generate fake agent output instead of spawning the CLI (demo mode) */
+  simulate?: boolean;
+  /**
+   * Streamed lines below this severity are kept in the run's output but not emitted
+   * as `log-event`s
+   */
+  minEventSeverity?: LogSeverity | null;
+  /** Queue priority; defaults to high for doctor checks, low for evals, normal otherwise */
+  priority?: RunPriority | null;
+  /** Route the run's HTTP(S) traffic through a local recording proxy */
+  captureNetwork?: boolean;
+  /** User-defined `{{name}}` values for args and env, resolved at spawn time */
+  variables?: Record<string, string>;
+}
+
+/** Order in which queued runs start, lowest first */
+export type RunPriority =
+  | 'low'
+  | 'normal'
+  | 'high';
+
+/** A user note attached to one line of a run's log */
+export interface RunAnnotation {
+  id: string;
+  /** 1-based line number in the run's log */
+  lineNo: number;
+  text: string;
+  createdAt: string;
+}
+
+/** Free-form note and line annotations for a run */
+export interface RunNotes {
+  runId: string;
+  note?: string | null;
+  annotations?: RunAnnotation[];
+  updatedAt?: string | null;
+}
+
+/** A run from the history: its final result, when recorded, and the user's notes */
+export interface RunRecord {
+  result?: RunResult | null;
+  notes: RunNotes;
+}
+
+/** Disk used by one top-level entry of the app data directory */
+export interface StorageCategoryUsage {
+  category: string;
+  bytes: number;
+  files: number;
+}
+
+export interface StorageUsage {
+  path: string;
+  totalBytes: number;
+  /** Largest first */
+  categories: StorageCategoryUsage[];
+}
+
+/** What one pass of the storage vacuum removed */
+export interface VacuumReport {
+  runsRemoved: number;
+  logFilesRemoved: number;
+  bytesFreed: number;
+}
+
+/** Package runner whose cache holds a CLI install */
+export type CliCacheKind =
+  | 'npx'
+  | 'bunx';
+
+/** A stale cache entry found by `clean_cli_caches` */
+export interface CliCacheEntry {
+  kind: CliCacheKind;
+  path: string;
+  /** `name@spec` of the packages the entry provides */
+  packages: string[];
+  bytes: number;
+  lastModified: string;
+  removed: boolean;
+}
+
+export interface CliCacheReport {
+  entries: CliCacheEntry[];
+  /** Bytes freed, or that would be freed on a dry run */
+  reclaimedBytes: number;
+  dryRun: boolean;
+}
+
+export interface AppLockStatus {
+  enabled: boolean;
+  /** The vault must be unlocked before the API key or secrets can be used */
+  locked: boolean;
+  /** Minutes without use before locking again; `0` never locks automatically */
+  autoLockMinutes?: number | null;
+}
+
+/** Who is using the app: admins can do everything, operators only work with runs */
+export type PermissionProfile =
+  | 'admin'
+  | 'operator';
+
+export interface PermissionStatus {
+  profile: PermissionProfile;
+  /** Commands only the admin profile may call */
+  adminCommands: string[];
+}
+
+/** A run that was still active when the previous session ended */
+export interface InterruptedRun {
+  runId: string;
+  mode: RunMode;
+  pid?: number | null;
+  startedAt: string;
+  /** The process outlived the app and is still running */
+  orphaned: boolean;
+}
+
+/** A schedule whose remaining runs never started because the app closed */
+export interface MissedSchedule {
+  scheduleId: string;
+  startedAt: string;
+  /** Spec IDs of the runs that were still pending */
+  pendingRuns: string[];
+}
+
+/** What happened since the last session, produced once at launch */
+export interface StartupReport {
+  previousSessionStartedAt?: string | null;
+  interruptedRuns: InterruptedRun[];
+  missedSchedules: MissedSchedule[];
+  configMigrations: string[];
+  generatedAt: string;
+}
+
+export type DependencyCondition =
+  /** Dependency must exit successfully */
+  | 'success'
+  /** Dependency must finish, regardless of outcome */
+  | 'completion'
+  /** Dependency must fail (e.g. run a fallback) */
+  | 'failure';
+
+export interface RunDependency {
+  /** ID of the spec in the same schedule that must run first */
+  runId: string;
+  condition?: DependencyCondition;
+}
+
+export type ScheduledRunStatus =
+  | 'pending'
+  | 'running'
+  | 'completed'
+  | 'failed'
+  | 'killed'
+  /** Not started because its dependency condition was not met */
+  | 'skipped';
+
+export interface ScheduledRun {
+  specId: string;
+  runId?: string | null;
+  after?: RunDependency | null;
+  status: ScheduledRunStatus;
+  reason?: string | null;
+}
+
+export interface RunSchedule {
+  id: string;
+  runs: ScheduledRun[];
+  startedAt: string;
+  finished: boolean;
+}
+
+export interface RunGroupMember {
+  runId: string;
+  characterFile?: string | null;
+  port?: number | null;
+  status: RunStatus;
+  exitCode?: number | null;
+}
+
+/** Several agents launched and controlled together */
+export interface RunGroup {
+  id: string;
+  members: RunGroupMember[];
+  status: RunStatus;
+  startedAt: string;
+}
+
+export interface EvalSpec {
+  /** Scenario or dataset file to evaluate */
+  scenarioPath: string;
+  iterations?: number | null;
+  /** Where the CLI writes its JSON report; defaults to the temp directory */
+  reportPath?: string | null;
+}
+
+export interface EvalCaseResult {
+  name: string;
+  passed: boolean;
+  durationMs?: number | null;
+  error?: string | null;
+}
+
+export interface EvalResult {
+  reportPath: string;
+  total: number;
+  passed: number;
+  failed: number;
+  passRate: number;
+  cases: EvalCaseResult[];
+}
+
+export type RunStatus =
+  | 'running'
+  | 'completed'
+  | 'failed'
+  | 'killed';
+
+export interface RunResult {
+  id: string;
+  spec: RunSpec;
+  startedAt: string;
+  endedAt?: string | null;
+  exitCode?: number | null;
+  stdout: string[];
+  stderr: string[];
+  durationMs?: number | null;
+  status: RunStatus;
+  pid?: number | null;
+  evalResult?: EvalResult | null;
+  doctorReport?: DoctorReport | null;
+  /** Whether binary output was replaced with placeholders in stdout/stderr */
+  binaryOutput?: boolean;
+  /** Endpoints the run connected to, when egress monitoring is on */
+  network?: ConnectionSummary | null;
+  /** Toolchain the run executed on, captured at start */
+  environment?: RunEnvironment | null;
+}
+
+/** Tool versions and platform a run executed on */
+export interface RunEnvironment {
+  nodeVersion?: string | null;
+  npmVersion?: string | null;
+  cliRunner: CliRunner;
+  /** Package spec a package runner fetches, e.g. `@elizaos/cli@latest` */
+  cliPackage?: string | null;
+  /** Installed CLI version; package runners report only the pinned spec */
+  cliVersion?: string | null;
+  os: string;
+  osVersion: string;
+  arch: string;
+  appVersion: string;
+}
+
+/** A remote endpoint a run was seen connected to */
+export interface NetworkConnection {
+  protocol: string;
+  remoteAddress: string;
+  remotePort: number;
+  /** Allowed host name the address belongs to */
+  hostName?: string | null;
+  allowed: boolean;
+  firstSeen: number;
+  lastSeen: number;
+  /** Samples the connection appeared in */
+  samples: number;
+}
+
+/** Network endpoints a run connected to */
+export interface ConnectionSummary {
+  connections: NetworkConnection[];
+  /** Remote addresses not on the allow list */
+  unexpectedAddresses: string[];
+  samples: number;
+}
+
+/** One outbound call recorded by a run's capture proxy; secrets are redacted */
+export interface CapturedRequest {
+  id: number;
+  method: string;
+  url: string;
+  /** Missing for tunnels and failed requests */
+  status?: number | null;
+  /** Milliseconds since the Unix epoch */
+  startedAt: number;
+  durationMs: number;
+  requestHeaders: Record<string, string>;
+  responseHeaders: Record<string, string>;
+  requestBody?: string | null;
+  responseBody?: string | null;
+  bytesSent: number;
+  bytesReceived: number;
+  /** HTTPS passes through an opaque tunnel, so only the host and sizes are known */
+  tunneled: boolean;
+  error?: string | null;
+}
+
+/** Outbound calls a run made through its capture proxy */
+export interface NetworkCapture {
+  runId: string;
+  proxyUrl: string;
+  requests: CapturedRequest[];
+  /** Calls not recorded because the capture was full */
+  dropped: number;
+  finished: boolean;
+}
+
+/** Payload of the `egress-alert` event */
+export interface EgressAlertEvent {
+  runId: string;
+  protocol: string;
+  remoteAddress: string;
+  remotePort: number;
+  timestamp: number;
+}
+
+/** One eval run of an experiment */
+export interface EvalMatrixRun {
+  runId: string;
+  status: RunStatus;
+  durationMs?: number | null;
+  /** Pass rate from the eval report, when one was written */
+  passRate?: number | null;
+  /** Estimated from the run's output */
+  approxTokens: number;
+  error?: string | null;
+  /** Seed exported to the run, when the experiment has one */
+  seed?: number | null;
+}
+
+/** Aggregates over the runs of one character/model combination */
+export interface EvalMatrixSummary {
+  runs: number;
+  /** Runs that exited cleanly with every eval case passing */
+  succeeded: number;
+  successRate: number;
+  meanPassRate?: number | null;
+  meanDurationMs?: number | null;
+  totalTokens: number;
+  meanTokens: number;
+}
+
+/** Results for one character/model combination */
+export interface EvalMatrixCell {
+  characterId: string;
+  model: string;
+  runs: EvalMatrixRun[];
+  summary: EvalMatrixSummary;
+}
+
+/** A finished eval matrix, stored as one experiment record */
+export interface Experiment {
+  id: string;
+  name?: string | null;
+  scenarioPath: string;
+  characterIds: string[];
+  models: string[];
+  iterations: number;
+  /** Base seed; iteration `n` of every character/model pair runs with `seed + n` */
+  seed?: number | null;
+  startedAt: string;
+  endedAt: string;
+  cells: EvalMatrixCell[];
+}
+
+/** Experiment listing entry, without per-run results */
+export interface ExperimentSummary {
+  id: string;
+  name?: string | null;
+  scenarioPath: string;
+  characterIds: string[];
+  models: string[];
+  iterations: number;
+  seed?: number | null;
+  startedAt: string;
+  endedAt: string;
+  overall: EvalMatrixSummary;
+}
+
+/** Aggregates for one arm of an experiment (a character or a model) */
+export interface ExperimentVariant {
+  key: string;
+  summary: EvalMatrixSummary;
+  /** Spread of pass rates across the arm's runs */
+  passRateStdDev?: number | null;
+}
+
+/** Aggregate statistics of an experiment for comparing its arms */
+export interface ExperimentResults {
+  experiment: ExperimentSummary;
+  byCharacter: ExperimentVariant[];
+  byModel: ExperimentVariant[];
+  /** Character with the highest mean pass rate, then success rate */
+  bestCharacter?: string | null;
+}
+
+export type DoctorCheckStatus =
+  | 'pass'
+  | 'warn'
+  | 'fail'
+  | 'skipped';
+
+export interface DoctorCheck {
+  id: string;
+  name: string;
+  status: DoctorCheckStatus;
+  message: string;
+  durationMs: number;
+}
+
+export interface DoctorReport {
+  checks: DoctorCheck[];
+  overallStatus: DoctorCheckStatus;
+  startedAt: string;
+  durationMs: number;
+}
+
+/** Emitted as `doctor-progress` after each diagnostic check completes */
+export interface DoctorProgressEvent {
+  runId: string;
+  index: number;
+  total: number;
+  check: DoctorCheck;
+}
+
+export type FailureConfidence =
+  | 'low'
+  | 'medium'
+  | 'high';
+
+/** A known failure the run's output matches, with how to fix it */
+export interface FailureSuggestion {
+  /** Stable id of the failure signature, e.g. `port_in_use` */
+  id: string;
+  title: string;
+  confidence: FailureConfidence;
+  /** Output lines that matched, redacted */
+  evidence: string[];
+  /** Steps to try, in order */
+  remediation: string[];
+}
+
+export interface RunFailureDiagnosis {
+  runId: string;
+  status: RunStatus;
+  exitCode?: number | null;
+  /** Most likely cause first; empty when no known failure matched */
+  suggestions: FailureSuggestion[];
+  diagnosedAt: string;
+}
+
+export interface ToolCheck {
+  installed: boolean;
+  version?: string | null;
+  path?: string | null;
+}
+
+export type PreflightStatus =
+  | 'ready'
+  | 'needssetup'
+  | 'criticalissues';
+
+export interface PreflightResult {
+  node: ToolCheck;
+  npm: ToolCheck;
+  eliza: ToolCheck;
+  recommendations: string[];
+  overallStatus: PreflightStatus;
+}
+
+/** A preflight result recorded at the time the check ran */
+export interface PreflightSnapshot {
+  timestamp: string;
+  result: PreflightResult;
+}
+
+export type PreflightChangeKind =
+  | 'appeared'
+  | 'disappeared'
+  | 'upgraded'
+  | 'downgraded'
+  | 'version_changed'
+  | 'moved'
+  | 'status_changed';
+
+/** One difference between two preflight snapshots */
+export interface PreflightChange {
+  /** `node`, `npm`, `eliza`, or `overall` */
+  tool: string;
+  kind: PreflightChangeKind;
+  before?: string | null;
+  after?: string | null;
+  message: string;
+}
+
+/** Environment drift between two snapshots; `from`/`to` are None when no history exists */
+export interface PreflightDiff {
+  from?: string | null;
+  to?: string | null;
+  changes: PreflightChange[];
+}
+
+export interface TelemetryEvent {
+  deviceId: string;
+  command: string;
+  args: string[];
+  startedAt: string;
+  durationMs: number;
+  exitCode: number;
+  bytesOut: number;
+  approxTokens?: number | null;
+  error?: string | null;
+  metadata?: Record<string, unknown> | null;
+}
+
+export type AuditAction =
+  | 'terminal_command'
+  | 'run_started'
+  | 'run_stopped'
+  | 'run_killed'
+  | 'run_interrupted'
+  | 'config_saved'
+  | 'config_cleared'
+  | 'approval_granted'
+  | 'approval_denied'
+  | 'cloud_deploy'
+  | 'secrets_pushed'
+  | 'app_lock_enabled'
+  | 'app_lock_disabled'
+  | 'app_unlocked'
+  | 'permission_profile_changed'
+  | 'command_denied'
+  | 'config_profile_activated'
+  | 'config_profile_deleted'
+  | 'config_exported'
+  | 'config_imported'
+  | 'config_restored';
+
+/** Where a privileged action was triggered from */
+export type AuditOrigin =
+  | 'gui'
+  | 'cli';
+
+export interface AuditEntry {
+  timestamp: string;
+  action: AuditAction;
+  origin: AuditOrigin;
+  /** What the action targeted: a command line, run ID or config file */
+  subject: string;
+  success: boolean;
+  detail?: string | null;
+}
+
+/** Time window for audit log queries; both bounds are RFC 3339 and optional */
+export interface AuditRange {
+  since?: string | null;
+  until?: string | null;
+  limit?: number | null;
+}
+
+export interface ApiResponse<T> {
+  success: boolean;
+  data?: T | null;
+  error?: ApiError | null;
+}
+
+export interface ApiError {
+  code: string;
+  message: string;
+  /** Locale-independent id of the message, for codes with a localized message */
+  messageId?: string | null;
+  details?: Record<string, unknown> | null;
+}
+
+export interface ConnectionTestResult {
+  success: boolean;
+  latencyMs?: number | null;
+  error?: string | null;
+  metadata?: ConnectionMetadata | null;
+}
+
+export interface ConnectionMetadata {
+  endpoint: string;
+  timestamp: string;
+  version?: string | null;
+}
+
+/** One content delta of a streamed test prompt, emitted as `prompt-token` */
+export interface PromptTokenEvent {
+  promptId: string;
+  /** Position of the delta in the response, from 0 */
+  index: number;
+  token: string;
+  /** Time since the request was sent */
+  elapsedMs: number;
+}
+
+/** Result of a streamed test prompt */
+export interface PromptStreamResult {
+  promptId: string;
+  content: string;
+  /** Content deltas received; providers may group several tokens into one */
+  tokenCount: number;
+  timeToFirstTokenMs?: number | null;
+  totalMs: number;
+  finishReason?: string | null;
+  /** False when the endpoint ignored streaming and returned the whole completion at once */
+  streamed: boolean;
+}
+
+/** Outcome of checking an API key against an authenticated Sandbox endpoint */
+export type ApiKeyStatus =
+  | 'valid'
+  /** Malformed, unknown or revoked */
+  | 'invalid'
+  | 'expired'
+  /** Accepted, but not allowed to use the Sandbox API */
+  | 'insufficient_permissions'
+  /** The Sandbox could not be reached, so the key is unverified */
+  | 'network_error'
+  /** The Sandbox answered with a status that says nothing about the key */
+  | 'unexpected';
+
+export interface ApiKeyCheck {
+  status: ApiKeyStatus;
+  message: string;
+  endpoint: string;
+  httpStatus?: number | null;
+  latencyMs?: number | null;
+  checkedAt: string;
+}
+
+/** Rate-limit budget a Sandbox host last reported */
+export interface RateLimitStatus {
+  host: string;
+  limit?: number | null;
+  remaining?: number | null;
+  resetAt?: string | null;
+  /** Whether new requests are being held until the window resets */
+  throttled: boolean;
+  /** Requests held back so far this session */
+  queuedRequests: number;
+  updatedAt?: string | null;
+}
+
+/** Sandbox reachability as judged by the connectivity monitor */
+export type ConnectivityState =
+  | 'online'
+  /** Reachable, but slow or failing some probes */
+  | 'degraded'
+  | 'offline';
+
+/** Rolling results of the connectivity monitor's health probes */
+export interface ConnectivityStatus {
+  running: boolean;
+  /** Unknown until the first probe completes */
+  state?: ConnectivityState | null;
+  intervalSecs: number;
+  /** Probes in the rolling window */
+  samples: number;
+  /** Share of probes in the window that succeeded, 0.0 to 1.0 */
+  availability: number;
+  averageLatencyMs?: number | null;
+  lastLatencyMs?: number | null;
+  lastError?: string | null;
+  lastCheckedAt?: string | null;
+}
+
+/** Payload of the `connectivity-changed` event */
+export interface ConnectivityChangedEvent {
+  previous?: ConnectivityState | null;
+  state: ConnectivityState;
+  availability: number;
+  averageLatencyMs?: number | null;
+  lastError?: string | null;
+  timestamp: number;
+}
+
+export interface LogEvent {
+  runId: string;
+  message: string;
+  logType: LogType;
+  timestamp: number;
+  /** Position of the line in the run's log buffer, for lines `tail_run_log` can return */
+  offset?: number | null;
+  /** Level parsed from the line's `INFO`/`WARN`/`ERROR` marker */
+  severity?: LogSeverity | null;
+}
+
+export type LogType =
+  | 'stdout'
+  | 'stderr'
+  | 'info'
+  | 'error'
+  | 'system';
+
+/** Level of a CLI log line, lowest first */
+export type LogSeverity =
+  | 'trace'
+  | 'debug'
+  | 'info'
+  | 'warn'
+  | 'error'
+  | 'fatal';
+
+/** A buffered output line of a run */
+export interface RunLogLine {
+  offset: number;
+  logType: LogType;
+  severity?: LogSeverity | null;
+  text: string;
+  timestamp: number;
+}
+
+/** Lines of a run's log from a given offset on */
+export interface RunLogTail {
+  runId: string;
+  lines: RunLogLine[];
+  /** Offset to pass to the next call */
+  nextOffset: number;
+  /** Oldest offset still available; lines before it were dropped from the buffer */
+  firstOffset: number;
+  /** The run has exited, so no more lines will arrive */
+  finished: boolean;
+}
+
+/**
+ * A line rewritten in place with `\r` (progress bars); emitted as `run-progress-line`
+ *
+ * `done` marks the final state, which is also delivered as a regular log event.
+ */
+export interface ProgressLineEvent {
+  runId: string;
+  logType: LogType;
+  text: string;
+  done: boolean;
+  timestamp: number;
+}
+
+/** Stage of the package download npx does before the CLI's first run */
+export type CliInstallPhase =
+  /** Fetching package metadata to work out the dependency tree */
+  | 'resolving'
+  /** Fetching package tarballs */
+  | 'downloading'
+  | 'complete';
+
+/** Payload of the `cli-install-progress` event */
+export interface CliInstallProgressEvent {
+  runId: string;
+  phase: CliInstallPhase;
+  packagesResolved: number;
+  packagesDownloaded: number;
+  /** Known once downloading starts, or from npm's closing summary */
+  totalPackages?: number | null;
+  percent?: number | null;
+  timestamp: number;
+}
+
+/** Payload of the `run-stalled` event */
+export interface RunStalledEvent {
+  runId: string;
+  /** Seconds since the run last wrote to stdout or stderr */
+  silentSecs: number;
+  stallAfterSecs: number;
+  /** Whether the watchdog sent the run an interrupt */
+  interrupted: boolean;
+  timestamp: number;
+}
+
+/** What happened to a run in the queue */
+export type RunQueueState =
+  | 'queued'
+  | 'started'
+  | 'paused'
+  | 'resumed';
+
+/** Payload of the `run-queue` event */
+export interface RunQueueEvent {
+  runId: string;
+  state: RunQueueState;
+  priority: RunPriority;
+  /** 1-based place among waiting runs, for queued runs */
+  position?: number | null;
+  timestamp: number;
+}
+
+/** A run holding or waiting for a queue slot */
+export interface QueuedRun {
+  runId: string;
+  priority: RunPriority;
+  /** Paused to make room for a higher-priority run */
+  paused: boolean;
+}
+
+/** Runs holding a slot and runs waiting for one, in start order */
+export interface RunQueueSnapshot {
+  maxConcurrentRuns?: number | null;
+  running: QueuedRun[];
+  waiting: QueuedRun[];
+}
+
+export type ReportFormat =
+  | 'markdown'
+  | 'html';
+
+/** A rendered run report; `path` is set when it was written to disk */
+export interface RunReport {
+  runId: string;
+  format: ReportFormat;
+  content: string;
+  path?: string | null;
+}
+
+/** Outcome of one step; emitted as `scenario-step` while a scenario replays */
+export interface ScenarioStepResult {
+  scenarioId: string;
+  index: number;
+  message: string;
+  response?: string | null;
+  latencyMs: number;
+  passed: boolean;
+  /** One entry per failed assertion, or the bridge error */
+  failures: string[];
+}
+
+/** Stored outcome of one scenario replay */
+export interface ScenarioResult {
+  id: string;
+  scenarioName: string;
+  scenarioPath: string;
+  agentUrl: string;
+  startedAt: string;
+  endedAt: string;
+  passed: boolean;
+  steps: ScenarioStepResult[];
+}
+
+export interface SupportBundleFile {
+  name: string;
+  description: string;
+  included: boolean;
+  /** Why the file was left out, when it was */
+  error?: string | null;
+}
+
+export interface SupportBundleManifest {
+  createdAt: string;
+  appVersion: string;
+  files: SupportBundleFile[];
+}
+
+export interface SupportBundle {
+  path: string;
+  sizeBytes: number;
+  manifest: SupportBundleManifest;
+}
+
+/** One line of the backend's structured log file */
+export interface AppLogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+  runId?: string | null;
+  sessionId?: string | null;
+}
+
+/** Filter for app log queries; every field is optional */
+export interface AppLogFilter {
+  /** Minimum level: error, warn, info, debug or trace */
+  level?: string | null;
+  runId?: string | null;
+  target?: string | null;
+  /** Case-insensitive substring of the message */
+  contains?: string | null;
+}
+
+export type AgentLocation =
+  /** Run on this machine through the ElizaOS CLI */
+  | 'local'
+  /** Hosted in the Sandbox cloud */
+  | 'cloud';
+
+/** An agent shown in the agents list, whether run locally or hosted in the Sandbox cloud */
+export interface Agent {
+  /** Run ID for local agents, cloud agent ID for hosted ones */
+  id: string;
+  name: string;
+  location: AgentLocation;
+  /** Run status for local agents; status reported by the Sandbox for cloud agents */
+  status: string;
+  characterFile?: string | null;
+  /** Public endpoint of a cloud agent */
+  url?: string | null;
+  createdAt?: string | null;
+  updatedAt?: string | null;
+}
+
+/** A saved local agent: the run spec it starts with and whether it starts with the app */
+export interface AgentProfile {
+  /** Generated when empty on save */
+  id?: string;
+  name: string;
+  /** Agent run (`run` mode) started for this agent */
+  spec: RunSpec;
+  /** Start the agent when the app launches, once preflight checks pass */
+  startOnLaunch?: boolean;
+  updatedAt?: string | null;
+}
+
+/** Progress of starting an agent on app launch */
+export type AgentAutostartState =
+  /** Waiting for configuration and preflight checks */
+  | 'pending'
+  /** Not started because the environment is not ready */
+  | 'skipped'
+  /** Run launched */
+  | 'started'
+  /** The run could not start or exited with an error */
+  | 'failed';
+
+/** Payload of the `agent-autostart` event */
+export interface AgentAutostartEvent {
+  agentId: string;
+  name: string;
+  state: AgentAutostartState;
+  runId?: string | null;
+  message?: string | null;
+  timestamp: number;
+}
+
+/**
+ * Payload of the `run-retry-suggested` event, sent when an agent run failed because
+ * its port was taken
+ */
+export interface RunRetrySuggestedEvent {
+  runId: string;
+  /** Port named in the error, when it gave one */
+  conflictingPort?: number | null;
+  suggestedPort: number;
+  /** The failed run's spec moved to the suggested port, ready to start */
+  spec: RunSpec;
+  /** Whether the retry was started without asking */
+  automatic: boolean;
+  /** ID of the automatic retry */
+  retryRunId?: string | null;
+  attempt: number;
+  timestamp: number;
+}
+
+/** Where an agent shortcut is placed */
+export type ShortcutLocation =
+  | 'desktop'
+  /** Start Menu on Windows, the applications menu on Linux, `~/Applications` on macOS */
+  | 'startMenu';
+
+/** An OS shortcut that launches the app and starts a saved agent */
+export interface AgentShortcut {
+  agentId: string;
+  location: ShortcutLocation;
+  path: string;
+  /** Arguments the app is launched with */
+  launchArgs: string[];
+}
+
+/** Stage of a character deployment to the Sandbox cloud */
+export type DeployStage =
+  | 'validating'
+  | 'uploading'
+  | 'deploying'
+  | 'ready'
+  | 'failed';
+
+/** Payload of the `cloud-deploy-status` event */
+export interface CloudDeployEvent {
+  characterId: string;
+  stage: DeployStage;
+  message: string;
+  agentId?: string | null;
+  timestamp: number;
+}
+
+/** A model in the Sandbox catalog; accepts OpenAI-style `owned_by` */
+export interface SandboxModel {
+  id: string;
+  name?: string | null;
+  ownedBy?: string | null;
+  /** Context window in tokens */
+  contextLength?: number | null;
+  /** Pricing tier the Sandbox bills the model under, e.g. `standard` or `premium` */
+  pricingTier?: string | null;
+}
+
+/** Account usage for the current billing period; fields the Sandbox leaves out stay empty */
+export interface UsageReport {
+  tokensUsed?: number | null;
+  tokenLimit?: number | null;
+  tokensRemaining?: number | null;
+  creditsUsed?: number | null;
+  creditLimit?: number | null;
+  creditsRemaining?: number | null;
+  periodStart?: string | null;
+  periodEnd?: string | null;
+  /** Request quota of the Sandbox host, from the rate-limit headers it last sent */
+  rateLimit?: RateLimitStatus | null;
+}
+
+/** Bookkeeping stored next to a managed character file */
+export interface CharacterMetadata {
+  /** Cloud agent this character was last deployed as */
+  cloudAgentId?: string | null;
+  deployedAt?: string | null;
+  /** Cloud agent this character was imported from */
+  importedFrom?: CharacterProvenance | null;
+  /** When the character was last pulled from the cloud */
+  syncedAt?: string | null;
+}
+
+/** Where an imported character came from */
+export interface CharacterProvenance {
+  agentId: string;
+  /** Sandbox base URL the agent was fetched from */
+  baseUrl: string;
+}
+
+/** A character stored in the app data directory */
+export interface ManagedCharacter {
+  id: string;
+  name: string;
+  /** Character JSON file, as passed to `--character` */
+  path: string;
+  metadata: CharacterMetadata;
+}
+
+/** A saved version of a managed character */
+export interface CharacterRevision {
+  /** Increases by one with every save */
+  number: number;
+  /** SHA-256 of the saved character JSON */
+  hash: string;
+  createdAt: string;
+  message?: string | null;
+  sizeBytes: number;
+}
+
+/** A character field that differs between two revisions */
+export interface CharacterFieldChange {
+  /** JSON pointer to the field, e.g. `/settings/model` */
+  path: string;
+  /** Absent when the field was added */
+  before?: unknown;
+  /** Absent when the field was removed */
+  after?: unknown;
+}
+
+export interface CharacterRevisionDiff {
+  characterId: string;
+  from: number;
+  to: number;
+  changes: CharacterFieldChange[];
+}
+
+/** A character file chosen in the native file dialog and validated */
+export interface CharacterFilePick {
+  /** File as selected, outside the managed directory */
+  path: string;
+  name: string;
+  /** Managed copy, when the file was imported */
+  character?: ManagedCharacter | null;
+}
+
+/** What a path dropped onto the window was handled as */
+export type DroppedItemKind =
+  /** Character JSON file */
+  | 'character'
+  /** Character package zip */
+  | 'package'
+  | 'folder'
+  | 'unsupported';
+
+/** Payload of the `file-drop-result` event, one per dropped path */
+export interface FileDropResult {
+  path: string;
+  kind: DroppedItemKind;
+  success: boolean;
+  /** Managed character created from the drop */
+  character?: ManagedCharacter | null;
+  errorCode?: string | null;
+  error?: string | null;
+}
+
+/** A document attached to a managed character as a knowledge source */
+export interface KnowledgeFile {
+  name: string;
+  /** Copy inside the character's knowledge directory */
+  path: string;
+  sizeBytes: number;
+}
+
+/** How much a lint finding matters, lowest first */
+export type LintSeverity =
+  | 'info'
+  | 'warning'
+  | 'error';
+
+/** One best-practice finding about a character */
+export interface CharacterLintIssue {
+  /** Stable rule name, e.g. `empty-bio` */
+  rule: string;
+  severity: LintSeverity;
+  message: string;
+  /** Character field the finding is about */
+  field?: string | null;
+}
+
+/** Lint findings for a managed character, most severe first */
+export interface CharacterLintReport {
+  characterId: string;
+  issues: CharacterLintIssue[];
+  errors: number;
+  warnings: number;
+}
+
+/** A file inside a character package */
+export interface CharacterPackageFile {
+  /** Path inside the archive, relative to the character directory */
+  path: string;
+  sha256: string;
+  sizeBytes: number;
+}
+
+/** `manifest.json` of a character package */
+export interface CharacterPackageManifest {
+  formatVersion: number;
+  characterId: string;
+  name: string;
+  exportedAt: string;
+  appVersion: string;
+  files: CharacterPackageFile[];
+}
+
+/** A character package written to disk */
+export interface CharacterPackage {
+  path: string;
+  sizeBytes: number;
+  manifest: CharacterPackageManifest;
+}
+
+export type CharacterAssetKind =
+  | 'image'
+  | 'audio';
+
+/** A media file stored in a managed character's `assets` directory */
+export interface CharacterAsset {
+  /** File name inside `assets` */
+  name: string;
+  /** Absolute path of the stored file */
+  path: string;
+  kind: CharacterAssetKind;
+  mimeType: string;
+  sizeBytes: number;
+  /** Whether the character's `avatar` field points at this asset */
+  isAvatar: boolean;
+}
+
+/** A reusable prompt snippet; `{{name}}` placeholders are filled in when it is rendered */
+export interface PromptTemplate {
+  /** Generated when empty on save */
+  id?: string;
+  name: string;
+  description?: string | null;
+  body: string;
+  /** Placeholder names found in the body, in order of first use; set on save */
+  variables?: string[];
+  updatedAt?: string | null;
+}
+
+/** Character field a rendered prompt template can be inserted into */
+export type PromptTemplateTarget =
+  /** Replaces the character's system prompt */
+  | 'system'
+  /** Appended as a bio line */
+  | 'bio'
+  /** Appended to the post examples */
+  | 'postExamples';
+
+/** Working tree state of a project directory's git repository */
+export interface GitStatus {
+  /** Repository root */
+  root: string;
+  /** Absent on a detached HEAD */
+  branch?: string | null;
+  upstream?: string | null;
+  ahead: number;
+  behind: number;
+  files: GitFileStatus[];
+}
+
+/** A changed file, with the two-letter status from `git status --porcelain` */
+export interface GitFileStatus {
+  /** Relative to the repository root */
+  path: string;
+  /** Previous path of a renamed or copied file */
+  originalPath?: string | null;
+  /** Status in the index, e.g. `M`, `A`, `?`; a space when unchanged */
+  staged: string;
+  /** Status in the working tree */
+  unstaged: string;
+}
+
+export interface GitCommitResult {
+  commit: string;
+  branch?: string | null;
+  filesChanged: number;
+}
+
+/** How a diff line differs from the committed file */
+export type GitDiffLineKind =
+  | 'context'
+  | 'added'
+  | 'removed';
+
+export interface GitDiffLine {
+  kind: GitDiffLineKind;
+  content: string;
+}
+
+export interface GitDiffHunk {
+  /** The `@@ -a,b +c,d @@` line */
+  header: string;
+  lines: GitDiffLine[];
+}
+
+/** Uncommitted changes to one file against `HEAD` */
+export interface GitFileDiff {
+  path: string;
+  /** Not tracked yet, so every line shows as added */
+  untracked: boolean;
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: GitDiffHunk[];
+}
+
+/** Package manager whose audit was run, picked from the project's lockfile */
+export type PackageManager =
+  | 'npm'
+  | 'pnpm'
+  | 'bun';
+
+/** Advisory severity as reported by the registry, lowest first */
+export type VulnerabilitySeverity =
+  | 'info'
+  | 'low'
+  | 'moderate'
+  | 'high'
+  | 'critical';
+
+/** A vulnerable package found by a dependency audit */
+export interface DependencyVulnerability {
+  package: string;
+  severity: VulnerabilitySeverity;
+  /** Title of the first advisory; absent when only a dependency is vulnerable */
+  title?: string | null;
+  url?: string | null;
+  vulnerableVersions?: string | null;
+  /** Listed in `package.json` rather than pulled in by another package */
+  direct?: boolean | null;
+  fixAvailable?: boolean | null;
+  /** Vulnerable dependencies that make this package vulnerable */
+  via?: string[];
+}
+
+export interface VulnerabilityCounts {
+  info: number;
+  low: number;
+  moderate: number;
+  high: number;
+  critical: number;
+  total: number;
+}
+
+/** Vulnerable dependencies of a project directory, most severe first */
+export interface DependencyAuditReport {
+  projectDir: string;
+  packageManager: PackageManager;
+  vulnerabilities: DependencyVulnerability[];
+  counts: VulnerabilityCounts;
+  auditedAt: string;
+}
+
+/** Where a key of a project's `.env` goes */
+export type ProjectEnvTarget =
+  /** A `SandboxConfig` field; the app sets it for every run, so it is never merged */
+  | 'config'
+  /** Merged into the env of runs in the project once imported */
+  | 'run'
+  /** Always overridden by the app when spawning the CLI */
+  | 'app_managed';
+
+export interface ProjectEnvEntry {
+  key: string;
+  /** The value, or `[REDACTED]` for secrets */
+  valuePreview: string;
+  secret: boolean;
+  target: ProjectEnvTarget;
+  /** `SandboxConfig` field for `Config` keys */
+  configField?: string | null;
+  /** Whether runs in the project receive this key */
+  imported: boolean;
+}
+
+/** Keys of a project's `.env` and how each one is used */
+export interface ProjectEnvReport {
+  envPath: string;
+  entries: ProjectEnvEntry[];
+  /** When the project was imported; None if its runs don't receive the file */
+  importedAt?: string | null;
+}
+
+/** Sandbox settings a project directory overrides through its `.eliza-desktop.json` */
+export interface ProjectConfigOverride {
+  baseUrl?: string | null;
+  defaultModel?: string | null;
+}
+
+/** A project's override file and the settings runs started in it use */
+export interface ProjectConfigReport {
+  configPath: string;
+  /** None when the project has no override file */
+  overrides?: ProjectConfigOverride | null;
+  baseUrl: string;
+  defaultModel?: string | null;
+  /**
+   * The override points runs at a host that is neither the saved base URL's nor a
+   * trusted project host; until it is approved, runs get no API key
+   */
+  needsApproval: boolean;
+}
+
+/** Secondary windows the backend can open; each kind has one window, labelled as serialized */
+export type WindowKind =
+  | 'log-viewer'
+  | 'terminal'
+  | 'playground';
+
+/** Logical position and size of a window */
+export interface WindowGeometry {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  /** Position and size are the ones to restore when un-maximizing */
+  maximized?: boolean;
+}
+
+export interface WindowInfo {
+  kind: WindowKind;
+  label: string;
+  open: boolean;
+  /** Remembered geometry, used the next time the window opens */
+  geometry?: WindowGeometry | null;
+}
+
+/** App actions that can be bound to a global keyboard shortcut */
+export type GlobalShortcutAction =
+  | 'stop_all_runs'
+  | 'open_terminal_window'
+  | 'open_log_viewer'
+  | 'toggle_main_window';
+
+export interface ShortcutBinding {
+  action: GlobalShortcutAction;
+  /** Accelerator such as `CmdOrCtrl+Shift+T`; None when the action is unbound */
+  accelerator?: string | null;
+  /** Whether the OS accepted the shortcut; another app may already hold it */
+  registered: boolean;
+}
+
+/** Payload of the `global-shortcut` event */
+export interface GlobalShortcutTriggered {
+  action: GlobalShortcutAction;
+}
+
+/** A device-code login waiting for the user to approve it in the browser */
+export interface SandboxLoginSession {
+  loginId: string;
+  /** Code the user confirms on the verification page */
+  userCode: string;
+  verificationUri: string;
+  /** Verification page with the code already filled in, when the Sandbox offers one */
+  verificationUriComplete?: string | null;
+  expiresAt: string;
+  /** Profile the API key is saved to once the login is approved */
+  profile: string;
+}
+
+export type SandboxLoginStatus =
+  | 'succeeded'
+  | 'denied'
+  | 'expired'
+  | 'cancelled'
+  | 'failed';
+
+/** Payload of the `sandbox-login` event, sent once when a login finishes */
+export interface SandboxLoginEvent {
+  loginId: string;
+  status: SandboxLoginStatus;
+  profile: string;
+  message?: string | null;
+}
+
+/** What `copy_to_clipboard` assembles */
+export type ClipboardContentKind =
+  /** A run's command and output, with secrets redacted; the id is the run id */
+  | 'run_logs'
+  /** A profile's configuration with the API key masked; the id is the profile name */
+  | 'config';
+
+export interface ClipboardCopy {
+  kind: ClipboardContentKind;
+  id: string;
+  /** Characters placed on the clipboard */
+  length: number;
+  lines: number;
+}
+
+/** Setup steps of the onboarding wizard, in the order they are shown */
+export type OnboardingStep =
+  | 'preflight_passed'
+  | 'config_saved'
+  | 'connection_tested'
+  | 'first_run_completed';
+
+export interface OnboardingStepStatus {
+  step: OnboardingStep;
+  completedAt?: string | null;
+}
+
+export interface OnboardingState {
+  /** Every step in wizard order */
+  steps: OnboardingStepStatus[];
+  /** First step not yet completed; None once onboarding is done */
+  currentStep?: OnboardingStep | null;
+  complete: boolean;
+}
+
+/** Payload of the `onboarding-step-completed` event */
+export interface OnboardingStepCompleted {
+  step: OnboardingStep;
+  state: OnboardingState;
+}
+
+/** What a project watch does when its sources change */
+export type WatchAction =
+  /** Stop the watch's agent run and start it again */
+  | 'restart_agent'
+  /** Run the project's `build` script with its package manager */
+  | 'rebuild';
+
+export interface WatchConfig {
+  projectDir: string;
+  action: WatchAction;
+  /** Directory watched, relative to the project; `src` when omitted */
+  watchDir?: string | null;
+  /** Quiet time after the last change before acting */
+  debounceMs?: number | null;
+  /** Agent run restarted on change, usually an eval; required for `RestartAgent` */
+  spec?: RunSpec | null;
+}
+
+export type WatchOutcome =
+  | 'restarted'
+  | 'rebuilt'
+  | 'build_failed'
+  /** The action could not be carried out, e.g. the run or build failed to start */
+  | 'failed';
+
+/** A batch of source changes and what the watch did about it; payload of `watch-event` */
+export interface WatchEvent {
+  watchId: string;
+  /** Changed paths relative to the project, capped */
+  changedPaths: string[];
+  totalChanges: number;
+  action: WatchAction;
+  outcome: WatchOutcome;
+  /** The agent run started for `RestartAgent` */
+  runId?: string | null;
+  message: string;
+  timestamp: string;
+}
+
+export interface WatchSession {
+  id: string;
+  config: WatchConfig;
+  startedAt: string;
+  /** The agent run currently owned by the watch */
+  runId?: string | null;
+  /** Recent decisions, oldest first */
+  events: WatchEvent[];
+}
+
+export type ActivityKind =
+  | 'run'
+  | 'terminal'
+  | 'agent_health'
+  | 'system';
+
+/** One entry of the activity timeline */
+export interface ActivityEntry {
+  /** Increases by one per entry, across restarts */
+  seq: number;
+  timestamp: string;
+  kind: ActivityKind;
+  /** Event the entry was recorded from, e.g. `config-changed` */
+  source: string;
+  summary: string;
+  runId?: string | null;
+  /** The event's payload with secrets redacted; null when it was too large to keep */
+  payload?: unknown;
+}
+
+export interface ActivityFeedPage {
+  /** Oldest first */
+  entries: ActivityEntry[];
+  /** Pass as `since` to get only newer entries */
+  latestSeq: number;
+}
+
+export interface PendingApproval {
+  id: string;
+  command: string;
+  args: string[];
+  workingDir: string;
+  reason: string;
+  requestedAt: string;
+}
+
+export interface BenchmarkOptions {
+  pings?: number | null;
+  events?: number | null;
+  skipSpawn?: boolean;
+}
+
+export interface LatencyStats {
+  samples: number;
+  minMs: number;
+  meanMs: number;
+  p50Ms: number;
+  p95Ms: number;
+  maxMs: number;
+}
+
+export interface EventThroughput {
+  events: number;
+  durationMs: number;
+  perSecond: number;
+}
+
+export interface SpawnLatency {
+  command: string;
+  spawnMs: number;
+  firstOutputMs?: number | null;
+  exitMs: number;
+  exitCode?: number | null;
+}
+
+export interface SelfBenchmarkReport {
+  startedAt: string;
+  appVersion: string;
+  os: string;
+  ipcRoundTrip?: LatencyStats | null;
+  eventThroughput: EventThroughput;
+  cliSpawn?: SpawnLatency | null;
+  notes: string[];
+}
+
+export interface BenchmarkPing {
+  id: string;
+  seq: number;
+}
+
+export type BudgetPeriod =
+  | 'daily'
+  | 'monthly';
+
+export type BudgetMetric =
+  | 'tokens'
+  | 'cost';
+
+/** Payload of the `budget-threshold` event */
+export interface BudgetThresholdEvent {
+  period: BudgetPeriod;
+  metric: BudgetMetric;
+  percent: number;
+  used: number;
+  limit: number;
+  /** Day (`YYYY-MM-DD`) or month (`YYYY-MM`) the usage belongs to */
+  periodKey: string;
+}
+
+/** Usage so far in the current day and month */
+export interface BudgetUsage {
+  dailyTokens: number;
+  monthlyTokens: number;
+  dailyCostUsd?: number | null;
+  monthlyCostUsd?: number | null;
+  budget: BudgetConfig;
+  /** Whether any configured limit is reached */
+  exceeded: boolean;
+}
+
+export interface KvEntry {
+  key: string;
+  value: unknown;
+  sizeBytes: number;
+}
+
+/** Payload of the `kv-changed` event; `value` is absent when the key was deleted */
+export interface KvChange {
+  namespace: string;
+  key: string;
+  value?: unknown;
+}
+
+export interface LogStreamStatus {
+  running: boolean;
+  /** Base URL; a run's events are at `<url>/<run_id>/logs` */
+  url?: string | null;
+  /** Token every request must present */
+  token?: string | null;
+  startedAt?: string | null;
+}
+
+export interface MockSandboxStatus {
+  running: boolean;
+  port?: number | null;
+  config?: SandboxConfig | null;
+  startedAt?: string | null;
+}
+
+export type PinKind =
+  | 'run'
+  | 'template'
+  | 'character';
+
+export interface PinnedItem {
+  kind: PinKind;
+  id: string;
+  /** Display name captured when pinned, so listings don't need to load the item */
+  label?: string | null;
+  pinnedAt: string;
+}
+
+export interface QuickAction {
+  id: string;
+  label: string;
+  description: string;
+  category: string;
+  command: string;
+  args: string[];
+}
+
+/** A secret the cloud project expects, and where it is set */
+export interface SecretStatus {
+  name: string;
+  required: boolean;
+  configuredInCloud: boolean;
+  availableLocally: boolean;
+}
+
+export interface RegistrySizes {
+  processes: number;
+  finishedProcesses: number;
+  terminalProcesses: number;
+  runGroups: number;
+  runSchedules: number;
+  reservedPorts: number;
+  pendingApprovals: number;
+}
+
+export interface EventEmitStats {
+  event: string;
+  count: number;
+  perSecond: number;
+}
+
+export interface RuntimeStats {
+  workers: number;
+  aliveTasks: number;
+}
+
+export interface BackendStats {
+  uptimeSecs: number;
+  registries: RegistrySizes;
+  telemetryInFlight: number;
+  logFiles: number;
+  logBytes: number;
+  events: EventEmitStats[];
+  runtime?: RuntimeStats | null;
+}
+
+export interface TerminalCommandResult {
+  success: boolean;
+  output: string[];
+  error?: string | null;
+  exitCode?: number | null;
+  durationMs: number;
+  /** Whether binary output was replaced with a placeholder */
+  binaryOutput?: boolean;
+}
+
+export interface TerminalProcess {
+  id: string;
+  command: string;
+  args: string[];
+  workingDir: string;
+  pid?: number | null;
+  startedAt: string;
+  status: string;
+}
+
+export interface WebhookListenerStatus {
+  running: boolean;
+  url?: string | null;
+  /** Shared secret the Sandbox signs callbacks with */
+  secret?: string | null;
+  startedAt?: string | null;
+}
+
+/** A verified callback from the Sandbox; payload of the `sandbox-webhook` event */
+export interface WebhookEvent {
+  id: string;
+  /** Event type reported by the Sandbox, e.g. `deploy.finished` */
+  eventType: string;
+  receivedAt: string;
+  payload: unknown;
+}
+
+export type Locale =
+  | 'en'
+  | 'es'
+  | 'fr';
+
+/** Arguments and response of each command, keyed by command name */
+export interface Commands {
+  /** Copy an image or audio file into a character's assets, replacing one of the same name */
+  add_character_asset: {
+    args: { id: string; path: string };
+    response: ApiResponse<CharacterAsset>;
+  };
+  /** Copy documents into a character's knowledge; a document with the same name is replaced */
+  add_character_knowledge: {
+    args: { id: string; paths: string[] };
+    response: ApiResponse<KnowledgeFile[]>;
+  };
+  /** Attach a note to one (1-based) line of a run's log */
+  add_run_annotation: {
+    args: { runId: string; lineNo: number; text: string };
+    response: ApiResponse<RunAnnotation>;
+  };
+  /** Audit the dependencies of the project in `project_dir` */
+  audit_project_dependencies: {
+    args: { projectDir: string };
+    response: ApiResponse<DependencyAuditReport>;
+  };
+  /** Answer a `benchmark-ping` event from the webview */
+  benchmark_pong: {
+    args: { id: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Stop polling for a login; its `sandbox-login` event reports it as cancelled */
+  cancel_sandbox_login: {
+    args: { loginId: string };
+    response: ApiResponse<null>;
+  };
+  /** Cancel a running terminal command */
+  cancel_terminal_command: {
+    args: { commandId: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Change working directory */
+  change_terminal_cwd: {
+    args: { path: string };
+    response: ApiResponse<string>;
+  };
+  /**
+   * Remove stale npx and bunx copies of the CLI, reporting the space reclaimed
+   *
+   * With `dry_run` the stale entries are only reported. Nothing is removed while runs are
+   * active, since one of them may be executing from the cache.
+   */
+  clean_cli_caches: {
+    args: { dryRun?: boolean | null };
+    response: ApiResponse<CliCacheReport>;
+  };
+  /** Clean up completed/failed processes from registry */
+  cleanup_terminal_processes: {
+    args: Record<string, never>;
+    response: ApiResponse<number>;
+  };
+  /**
+   * Clear saved Sandbox configuration
+   *
+   * Clears the active profile; clearing a named profile makes `default` active again.
+   */
+  clear_sandbox_config: {
+    args: Record<string, never>;
+    response: ApiResponse<null>;
+  };
+  /** Close a secondary window, returning whether it was open */
+  close_window: {
+    args: { kind: WindowKind };
+    response: ApiResponse<boolean>;
+  };
+  /**
+   * Copy redacted content to the clipboard: a run's logs (`id` is the run id) or a profile's
+   * configuration (`id` is the profile name, the active profile when omitted)
+   */
+  copy_to_clipboard: {
+    args: { kind: ClipboardContentKind; id?: string | null };
+    response: ApiResponse<ClipboardCopy>;
+  };
+  /**
+   * Create a shortcut that opens the app and starts a saved agent
+   *
+   * An existing shortcut for an agent with the same name is replaced.
+   */
+  create_agent_shortcut: {
+    args: { agentId: string; location: ShortcutLocation };
+    response: ApiResponse<AgentShortcut>;
+  };
+  /** Create a support bundle at `path` (a file, or a directory to place it in) */
+  create_support_bundle: {
+    args: { path: string };
+    response: ApiResponse<SupportBundle>;
+  };
+  /** Delete a profile other than the active one, returning whether it existed */
+  delete_config_profile: {
+    args: { name: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Remove a local secret, returning whether it existed */
+  delete_local_secret: {
+    args: { name: string };
+    response: ApiResponse<boolean>;
+  };
+  /**
+   * Deploy a managed character as a cloud agent, emitting `cloud-deploy-status` events
+   *
+   * Characters that were deployed before are updated in place; the resulting cloud agent
+   * ID is recorded in the character's metadata.
+   */
+  deploy_character_to_cloud: {
+    args: { characterId: string; config: SandboxConfig };
+    response: ApiResponse<Agent>;
+  };
+  /** Match a finished run's output against known failures and suggest fixes, most likely first */
+  diagnose_run_failure: {
+    args: { runId: string };
+    response: ApiResponse<RunFailureDiagnosis>;
+  };
+  /** Fields that changed between two revisions of a character */
+  diff_character_revisions: {
+    args: { id: string; a: number; b: number };
+    response: ApiResponse<CharacterRevisionDiff>;
+  };
+  /** Decrypt the vault back into plain files and turn the app lock off */
+  disable_app_lock: {
+    args: { passphrase: string };
+    response: ApiResponse<AppLockStatus>;
+  };
+  /**
+   * Move the API key and local secrets into a vault encrypted with `passphrase`
+   *
+   * The app stays unlocked until it is locked or sits unused for `auto_lock_minutes`.
+   */
+  enable_app_lock: {
+    args: { passphrase: string; autoLockMinutes?: number | null };
+    response: ApiResponse<AppLockStatus>;
+  };
+  /** Execute a terminal command with real-time output capture */
+  execute_terminal_command: {
+    args: { command: string; args: string[]; workingDir?: string | null };
+    response: TerminalCommandResult;
+  };
+  /** Export a managed character and its assets to a zip at `path` (a file, or a directory) */
+  export_character_package: {
+    args: { id: string; path: string };
+    response: ApiResponse<CharacterPackage>;
+  };
+  /**
+   * Export a profile's configuration to `path` (a file, or a directory) for sharing
+   *
+   * The API key is left out unless `include_secrets` is set.
+   */
+  export_sandbox_config: {
+    args: { path: string; includeSecrets: boolean; profileName?: string | null };
+    response: ApiResponse<string>;
+  };
+  /** Stop merging a project's `.env` into its runs, returning whether it was imported */
+  forget_project_env: {
+    args: { projectDir: string };
+    response: ApiResponse<boolean>;
+  };
+  /**
+   * Render a report for a run; written to `path` (a file, or a directory to place it in)
+   * when given, otherwise only returned
+   */
+  generate_run_report: {
+    args: { runId: string; format: ReportFormat; path?: string | null };
+    response: ApiResponse<RunReport>;
+  };
+  /**
+   * Activity entries after sequence number `since` (all kept entries when omitted), oldest
+   * first, limited to the newest `limit` and optionally to some kinds
+   */
+  get_activity_feed: {
+    args: { since?: number | null; kinds?: ActivityKind[] | null; limit?: number | null };
+    response: ApiResponse<ActivityFeedPage>;
+  };
+  /** Configured allowed roots; empty when paths are unrestricted */
+  get_allowed_roots: {
+    args: Record<string, never>;
+    response: ApiResponse<string[]>;
+  };
+  /** Whether the app lock is enabled and currently locked */
+  get_app_lock_status: {
+    args: Record<string, never>;
+    response: ApiResponse<AppLockStatus>;
+  };
+  /** Get the most recent backend log entries matching the filter, oldest first */
+  get_app_logs: {
+    args: { filter?: AppLogFilter | null; limit?: number | null };
+    response: ApiResponse<AppLogEntry[]>;
+  };
+  /** Get entries from the audit log, oldest first, optionally limited to a time range */
+  get_audit_log: {
+    args: { range?: AuditRange | null };
+    response: ApiResponse<AuditEntry[]>;
+  };
+  /** Get a snapshot of backend resource usage */
+  get_backend_stats: {
+    args: Record<string, never>;
+    response: ApiResponse<BackendStats>;
+  };
+  /** Get token usage for today and this month against the configured budget */
+  get_budget_usage: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<BudgetUsage>;
+  };
+  /** Report which ElizaOS CLI will be used, where it lives, and why alternatives were rejected */
+  get_cli_resolution_report: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<CliResolutionReport>;
+  };
+  /** Get one cloud agent using the saved Sandbox configuration */
+  get_cloud_agent: {
+    args: { id: string };
+    response: ApiResponse<Agent>;
+  };
+  /** Get the monitor's rolling latency and availability */
+  get_connectivity_status: {
+    args: Record<string, never>;
+    response: ApiResponse<ConnectivityStatus>;
+  };
+  /** Generate device ID for telemetry */
+  get_device_id: {
+    args: Record<string, never>;
+    response: ApiResponse<string>;
+  };
+  /** Load a stored experiment record */
+  get_experiment: {
+    args: { experimentId: string };
+    response: ApiResponse<Experiment>;
+  };
+  /** Aggregate an experiment's runs per character and per model */
+  get_experiment_results: {
+    args: { experimentId: string };
+    response: ApiResponse<ExperimentResults>;
+  };
+  /** Locale backend messages are currently rendered in */
+  get_locale: {
+    args: Record<string, never>;
+    response: ApiResponse<Locale>;
+  };
+  /** Get whether the log stream server is running, with its URL and token */
+  get_log_stream_status: {
+    args: Record<string, never>;
+    response: ApiResponse<LogStreamStatus>;
+  };
+  /** Get whether the local Sandbox stub is running and where */
+  get_mock_sandbox_status: {
+    args: Record<string, never>;
+    response: ApiResponse<MockSandboxStatus>;
+  };
+  /**
+   * Onboarding progress; a configuration that was saved before the progress was (for example
+   * by an earlier install) counts as the config step being done
+   */
+  get_onboarding_state: {
+    args: Record<string, never>;
+    response: ApiResponse<OnboardingState>;
+  };
+  /** List commands currently waiting for approval */
+  get_pending_approvals: {
+    args: Record<string, never>;
+    response: ApiResponse<PendingApproval[]>;
+  };
+  /** Current profile and the commands it may not call */
+  get_permission_profile: {
+    args: Record<string, never>;
+    response: ApiResponse<PermissionStatus>;
+  };
+  /** Diff the latest snapshot against the one in effect at `since` (RFC 3339, default 24 hours ago) */
+  get_preflight_changes: {
+    args: { since?: string | null };
+    response: ApiResponse<PreflightDiff>;
+  };
+  /** Get recorded preflight snapshots, newest first */
+  get_preflight_history: {
+    args: { limit?: number | null };
+    response: ApiResponse<PreflightSnapshot[]>;
+  };
+  /** Branch, upstream and changed files of the repository containing `project_dir` */
+  get_project_git_status: {
+    args: { projectDir: string };
+    response: ApiResponse<GitStatus>;
+  };
+  /** List the quick actions that apply to a project directory */
+  get_quick_actions: {
+    args: { projectDir?: string | null };
+    response: ApiResponse<QuickAction[]>;
+  };
+  /** Rate-limit budget of each Sandbox host called this session */
+  get_rate_limit_status: {
+    args: Record<string, never>;
+    response: ApiResponse<RateLimitStatus[]>;
+  };
+  /** Get the current status of a run group */
+  get_run_group_status: {
+    args: { groupId: string };
+    response: ApiResponse<RunGroup>;
+  };
+  /** Calls a run made through its capture proxy */
+  get_run_network_capture: {
+    args: { runId: string };
+    response: ApiResponse<NetworkCapture>;
+  };
+  /** Runs holding or waiting for a slot */
+  get_run_queue: {
+    args: Record<string, never>;
+    response: ApiResponse<RunQueueSnapshot>;
+  };
+  /** A run's recorded result, if any, together with its notes */
+  get_run_record: {
+    args: { runId: string };
+    response: ApiResponse<RunRecord>;
+  };
+  /** Get current run result by ID */
+  get_run_result: {
+    args: { runId: string };
+    response: ApiResponse<RunResult>;
+  };
+  /** Get the current status of a run schedule */
+  get_run_schedule: {
+    args: { scheduleId: string };
+    response: ApiResponse<RunSchedule>;
+  };
+  /**
+   * Token and credit usage with the host's request quota, cached briefly unless `refresh`
+   * is set, so the remaining budget can be checked before a long run
+   */
+  get_sandbox_usage: {
+    args: { config: SandboxConfig; refresh?: boolean | null };
+    response: ApiResponse<UsageReport>;
+  };
+  /** Load a stored scenario result */
+  get_scenario_result: {
+    args: { resultId: string };
+    response: ApiResponse<ScenarioResult>;
+  };
+  /** What happened since the last session, as found at launch */
+  get_startup_report: {
+    args: Record<string, never>;
+    response: ApiResponse<StartupReport>;
+  };
+  /** Disk used by the app data directory, per top-level entry */
+  get_storage_usage: {
+    args: Record<string, never>;
+    response: ApiResponse<StorageUsage>;
+  };
+  /** Get current working directory */
+  get_terminal_cwd: {
+    args: Record<string, never>;
+    response: ApiResponse<string>;
+  };
+  /** Get list of running terminal processes */
+  get_terminal_processes: {
+    args: Record<string, never>;
+    response: ApiResponse<TerminalProcess[]>;
+  };
+  /** Get the most recent persisted webhook events, oldest first */
+  get_webhook_events: {
+    args: { limit?: number | null };
+    response: ApiResponse<WebhookEvent[]>;
+  };
+  /** Get whether the webhook listener is running, with its URL and secret */
+  get_webhook_listener_status: {
+    args: Record<string, never>;
+    response: ApiResponse<WebhookListenerStatus>;
+  };
+  /** Stage every change under `project_dir` and commit it */
+  git_commit_project: {
+    args: { projectDir: string; message: string };
+    response: ApiResponse<GitCommitResult>;
+  };
+  /** Uncommitted changes to a file, staged or not, against `HEAD` */
+  git_diff_file: {
+    args: { path: string };
+    response: ApiResponse<GitFileDiff>;
+  };
+  /** Basic greet command for IPC testing */
+  greet: {
+    args: { name: string };
+    response: string;
+  };
+  /** Import a character package as a new managed character after verifying its checksums */
+  import_character_package: {
+    args: { path: string };
+    response: ApiResponse<ManagedCharacter>;
+  };
+  /**
+   * Pull a cloud agent's definition into a managed local character
+   *
+   * Importing the same agent again refreshes the character it was imported into.
+   */
+  import_cloud_agent: {
+    args: { agentId: string };
+    response: ApiResponse<ManagedCharacter>;
+  };
+  /**
+   * Merge a project's `.env` run variables into later runs started in that directory;
+   * only `keys` are merged when given. Values set in a run spec still win
+   */
+  import_project_env: {
+    args: { projectDir: string; keys?: string[] | null };
+    response: ApiResponse<ProjectEnvReport>;
+  };
+  /**
+   * Import a configuration written by `export_sandbox_config` into a profile
+   *
+   * Imports into `profile_name`, or the active profile. Without an API key in the file the
+   * profile keeps the key it already has.
+   */
+  import_sandbox_config: {
+    args: { path: string; profileName?: string | null };
+    response: ApiResponse<ConfigImportResult>;
+  };
+  /** Initialize terminal backend */
+  initialize_terminal: {
+    args: Record<string, never>;
+    response: ApiResponse<boolean>;
+  };
+  /** Render a template into a field of a managed character and save the character */
+  insert_prompt_template: {
+    args: { characterId: string; templateId: string; target: PromptTemplateTarget; variables: Record<string, string> };
+    response: ApiResponse<ManagedCharacter>;
+  };
+  /** Show a project's overrides and the settings its runs would use */
+  inspect_project_config: {
+    args: { projectDir: string };
+    response: ApiResponse<ProjectConfigReport>;
+  };
+  /** Show the keys of a project's `.env` and where each one would go, without importing */
+  inspect_project_env: {
+    args: { projectDir: string };
+    response: ApiResponse<ProjectEnvReport>;
+  };
+  /**
+   * Interrupt a running ElizaOS CLI process as Ctrl-C would, letting the CLI run its own
+   * interrupt handlers; the run keeps its handle until the process actually exits
+   */
+  interrupt_eliza_run: {
+    args: { runId: string };
+    response: ApiResponse<RunResult>;
+  };
+  /** Drop cached Sandbox responses under a path (e.g. `agents`), or all of them */
+  invalidate_api_cache: {
+    args: { path?: string | null };
+    response: ApiResponse<number>;
+  };
+  /** Kill a running ElizaOS CLI process forcefully */
+  kill_eliza_run: {
+    args: { runId: string };
+    response: ApiResponse<RunResult>;
+  };
+  /** Force kill every running agent in a group */
+  kill_run_group: {
+    args: { groupId: string };
+    response: ApiResponse<RunGroup>;
+  };
+  /** Delete a key, returning whether it existed */
+  kv_delete: {
+    args: { namespace: string; key: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Get a value, or null when the key is not set */
+  kv_get: {
+    args: { namespace: string; key: string };
+    response: ApiResponse<unknown>;
+  };
+  /** List all entries in a namespace, sorted by key */
+  kv_list: {
+    args: { namespace: string };
+    response: ApiResponse<KvEntry[]>;
+  };
+  /** Set a value, enforcing the per-value and per-namespace quotas */
+  kv_set: {
+    args: { namespace: string; key: string; value: unknown };
+    response: ApiResponse<KvEntry>;
+  };
+  /** Check a managed character against best practices */
+  lint_character: {
+    args: { id: string };
+    response: ApiResponse<CharacterLintReport>;
+  };
+  /** Saved local agents, in the order they were created */
+  list_agents: {
+    args: Record<string, never>;
+    response: ApiResponse<AgentProfile[]>;
+  };
+  /** Assets of a managed character, sorted by name */
+  list_character_assets: {
+    args: { id: string };
+    response: ApiResponse<CharacterAsset[]>;
+  };
+  /** Knowledge documents of a character, sorted by name */
+  list_character_knowledge: {
+    args: { id: string };
+    response: ApiResponse<KnowledgeFile[]>;
+  };
+  /** Revisions of a managed character, newest first */
+  list_character_revisions: {
+    args: { id: string };
+    response: ApiResponse<CharacterRevision[]>;
+  };
+  /**
+   * List the agents hosted in the Sandbox cloud for this API key
+   *
+   * The list is cached briefly; pass `refresh` to fetch it again regardless.
+   */
+  list_cloud_agents: {
+    args: { config: SandboxConfig; refresh?: boolean | null };
+    response: ApiResponse<Agent[]>;
+  };
+  /** List the secrets the cloud project expects, merged with the names stored locally */
+  list_cloud_secrets: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<SecretStatus[]>;
+  };
+  /**
+   * Configurations kept before they were overwritten or cleared, newest first; only those
+   * of `profile_name` when given
+   */
+  list_config_backups: {
+    args: { profileName?: string | null };
+    response: ApiResponse<ConfigBackup[]>;
+  };
+  /** Saved configuration profiles, without their API keys */
+  list_config_profiles: {
+    args: Record<string, never>;
+    response: ApiResponse<ConfigProfileSummary[]>;
+  };
+  /** List stored experiments, newest first */
+  list_experiments: {
+    args: Record<string, never>;
+    response: ApiResponse<ExperimentSummary[]>;
+  };
+  /** Names of the locally stored secrets */
+  list_local_secrets: {
+    args: Record<string, never>;
+    response: ApiResponse<string[]>;
+  };
+  /** Pinned items, most recently pinned first within each kind; all kinds unless `kind` is given */
+  list_pinned: {
+    args: { kind?: PinKind | null };
+    response: ApiResponse<PinnedItem[]>;
+  };
+  /** Active project watches with their recent events */
+  list_project_watches: {
+    args: Record<string, never>;
+    response: ApiResponse<WatchSession[]>;
+  };
+  /** Saved prompt templates, in the order they were created */
+  list_prompt_templates: {
+    args: Record<string, never>;
+    response: ApiResponse<PromptTemplate[]>;
+  };
+  /** Models the Sandbox offers, cached for a few minutes unless `refresh` is set */
+  list_sandbox_models: {
+    args: { config: SandboxConfig; refresh?: boolean | null };
+    response: ApiResponse<SandboxModel[]>;
+  };
+  /** Every action with its accelerator and whether the OS accepted the registration */
+  list_shortcuts: {
+    args: Record<string, never>;
+    response: ApiResponse<ShortcutBinding[]>;
+  };
+  /** Every secondary window with whether it is open and its remembered geometry */
+  list_windows: {
+    args: Record<string, never>;
+    response: ApiResponse<WindowInfo[]>;
+  };
+  /**
+   * Load Sandbox configuration from JSON file
+   *
+   * Loads `profile_name`, or the active profile, so runs can be started against either.
+   */
+  load_sandbox_config: {
+    args: { profileName?: string | null };
+    response: ApiResponse<SandboxConfig>;
+  };
+  /** Forget the decrypted vault until the next `unlock_app` */
+  lock_app: {
+    args: Record<string, never>;
+    response: ApiResponse<AppLockStatus>;
+  };
+  /** Open a secondary window, or focus it if it is already open */
+  open_window: {
+    args: { kind: WindowKind };
+    response: ApiResponse<WindowInfo>;
+  };
+  /**
+   * Let the user choose a character JSON file and validate it
+   *
+   * With `import` (the default) the character is also copied into the managed characters
+   * directory under a new ID. Returns `None` when the dialog is cancelled.
+   */
+  pick_character_file: {
+    args: { import?: boolean | null };
+    response: ApiResponse<CharacterFilePick | null>;
+  };
+  /** Pin an item; pinning it again moves it to the top and updates its label */
+  pin_item: {
+    args: { kind: PinKind; id: string; label?: string | null };
+    response: ApiResponse<PinnedItem>;
+  };
+  /** Post telemetry event to Sandbox API */
+  post_telemetry: {
+    args: { config: SandboxConfig; event: TelemetryEvent };
+    response: ApiResponse<null>;
+  };
+  /** Run comprehensive preflight checks */
+  preflight_check: {
+    args: Record<string, never>;
+    response: ApiResponse<PreflightResult>;
+  };
+  /** Push local secrets to the Sandbox, all of them or just `names`; returns the names pushed */
+  push_secrets_to_cloud: {
+    args: { config: SandboxConfig; names?: string[] | null };
+    response: ApiResponse<string[]>;
+  };
+  /** Clear the cached CLI resolution and resolve again */
+  refresh_cli_resolution: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<CliRunner>;
+  };
+  /** Delete a saved agent, returning whether it existed */
+  remove_agent: {
+    args: { agentId: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Delete an asset, returning whether it existed; removing the avatar clears the field */
+  remove_character_asset: {
+    args: { id: string; name: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Remove a knowledge document, returning whether it existed */
+  remove_character_knowledge: {
+    args: { id: string; name: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Delete a template, returning whether it existed */
+  remove_prompt_template: {
+    args: { templateId: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Fill in a template's placeholders, e.g. for the prompt playground */
+  render_prompt_template: {
+    args: { templateId: string; variables: Record<string, string> };
+    response: ApiResponse<string>;
+  };
+  /** Forget all completed steps so the wizard starts over */
+  reset_onboarding: {
+    args: Record<string, never>;
+    response: ApiResponse<OnboardingState>;
+  };
+  /** Forget remembered window sizes and positions; open windows keep their current layout */
+  reset_window_layout: {
+    args: Record<string, never>;
+    response: ApiResponse<null>;
+  };
+  /** Allow or deny a parked command */
+  resolve_approval: {
+    args: { id: string; allow: boolean };
+    response: ApiResponse<PendingApproval>;
+  };
+  /**
+   * Put a backed-up configuration back in place of its profile's current one
+   *
+   * The current configuration is backed up first, so a restore can itself be undone. A
+   * backup without an API key keeps the key the profile has now.
+   */
+  restore_config_backup: {
+    args: { id: string };
+    response: ApiResponse<ConfigProfileSummary>;
+  };
+  /**
+   * Restore a character to an earlier revision
+   *
+   * The restored content is saved as a new revision, so a rollback can itself be undone.
+   */
+  rollback_character: {
+    args: { id: string; revision: number };
+    response: ApiResponse<ManagedCharacter>;
+  };
+  /** Run the doctor diagnostics and return the structured report */
+  run_doctor: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<DoctorReport>;
+  };
+  /** Run a quick action by ID through the terminal execution path */
+  run_quick_action: {
+    args: { id: string; projectDir?: string | null };
+    response: ApiResponse<TerminalCommandResult>;
+  };
+  /** Replay a scenario file against the agent server on `port` and store the result */
+  run_scenario: {
+    args: { scenarioPath: string; port: number };
+    response: ApiResponse<ScenarioResult>;
+  };
+  /**
+   * Run the self benchmark and return a report
+   *
+   * The IPC round trip is measured by emitting `benchmark-ping` events that the
+   * webview answers by invoking `benchmark_pong`; it is skipped when nothing answers.
+   */
+  run_self_benchmark: {
+    args: { options?: BenchmarkOptions | null };
+    response: ApiResponse<SelfBenchmarkReport>;
+  };
+  /** Create an agent, or replace the one with the same ID */
+  save_agent: {
+    args: { profile: AgentProfile };
+    response: ApiResponse<AgentProfile>;
+  };
+  /** Create a template, or replace the one with the same ID */
+  save_prompt_template: {
+    args: { template: PromptTemplate };
+    response: ApiResponse<PromptTemplate>;
+  };
+  /**
+   * Save Sandbox configuration to JSON file
+   *
+   * Saves to `profile_name`, creating the profile if needed, or to the active profile.
+   */
+  save_sandbox_config: {
+    args: { config: SandboxConfig; profileName?: string | null };
+    response: ApiResponse<null>;
+  };
+  /** Schedule a batch of runs; runs declaring `after` wait for that spec to finish */
+  schedule_runs: {
+    args: { specs: RunSpec[]; config: SandboxConfig };
+    response: ApiResponse<RunSchedule>;
+  };
+  /** Send one message to an agent running on a local port */
+  send_agent_message: {
+    args: { port: number; agent?: string | null; text: string };
+    response: ApiResponse<string>;
+  };
+  /** Make a saved profile the one background tasks and `load_sandbox_config` use */
+  set_active_profile: {
+    args: { name: string };
+    response: ApiResponse<ConfigProfileSummary>;
+  };
+  /** Turn starting an agent on app launch on or off */
+  set_agent_start_on_launch: {
+    args: { agentId: string; enabled: boolean };
+    response: ApiResponse<AgentProfile>;
+  };
+  /**
+   * Set the subcommands Custom runs may invoke, or restore the default allowlist with `None`
+   *
+   * Run requests carry their own config, so runs read the allowlist from the saved profile
+   * and this admin-only command is the way to change it.
+   */
+  set_allowed_custom_subcommands: {
+    args: { subcommands?: string[] | null; profileName?: string | null };
+    response: ApiResponse<string[]>;
+  };
+  /**
+   * Replace the allowed roots; an empty list lifts the restriction
+   *
+   * Roots must be existing directories and are stored canonicalized.
+   */
+  set_allowed_roots: {
+    args: { roots: string[] };
+    response: ApiResponse<string[]>;
+  };
+  /**
+   * Use an image as the character's avatar; it is stored as `assets/avatar.<ext>` and the
+   * character's `avatar` field is pointed at it
+   */
+  set_character_avatar: {
+    args: { id: string; path: string };
+    response: ApiResponse<CharacterAsset>;
+  };
+  /** Store a secret locally, replacing any previous value */
+  set_local_secret: {
+    args: { name: string; value: string };
+    response: ApiResponse<null>;
+  };
+  /**
+   * Render backend messages in the given locale, e.g. `es` or `fr-CA`
+   *
+   * Only the language part of the tag is used.
+   */
+  set_locale: {
+    args: { locale: string };
+    response: ApiResponse<Locale>;
+  };
+  /** Change the backend log level at runtime */
+  set_log_level: {
+    args: { level: string };
+    response: ApiResponse<string>;
+  };
+  /**
+   * Switch profiles
+   *
+   * Switching to operator sets `password`; switching back to admin must present it.
+   */
+  set_permission_profile: {
+    args: { profile: PermissionProfile; password?: string | null };
+    response: ApiResponse<PermissionStatus>;
+  };
+  /**
+   * Only emit a running run's lines that match a regular expression (case-insensitive);
+   * an empty or missing pattern emits everything again. All lines are still buffered
+   */
+  set_run_log_filter: {
+    args: { runId: string; pattern?: string | null };
+    response: ApiResponse<string | null>;
+  };
+  /** Set the free-form note of a run; empty text clears it */
+  set_run_note: {
+    args: { runId: string; text: string };
+    response: ApiResponse<RunNotes>;
+  };
+  /**
+   * Bind an action to an accelerator such as `CmdOrCtrl+Shift+T`; an empty or missing
+   * accelerator removes the binding
+   */
+  set_shortcut: {
+    args: { action: GlobalShortcutAction; accelerator?: string | null };
+    response: ApiResponse<ShortcutBinding>;
+  };
+  /**
+   * Set the hosts, besides the base URL's, that a project's `.eliza-desktop.json` may point
+   * runs at with the API key; `None` trusts only the base URL's host
+   *
+   * Runs read the list from the saved profile, so override files from cloned projects can't
+   * send the key elsewhere until a user approves their host here.
+   */
+  set_trusted_project_hosts: {
+    args: { hosts?: string[] | null; profileName?: string | null };
+    response: ApiResponse<string[]>;
+  };
+  /** Start probing the Sandbox health endpoint, replacing a monitor already running */
+  start_connectivity_monitor: {
+    args: { config: SandboxConfig; intervalSecs?: number | null };
+    response: ApiResponse<ConnectivityStatus>;
+  };
+  /** Start a new ElizaOS CLI run (simplified version for MVP - kept for compatibility) */
+  start_eliza_run: {
+    args: { spec: RunSpec; config: SandboxConfig };
+    response: ApiResponse<RunResult>;
+  };
+  /** Start a new ElizaOS CLI run with live log streaming */
+  start_eliza_run_streaming: {
+    args: { spec: RunSpec; config: SandboxConfig };
+    response: ApiResponse<RunResult>;
+  };
+  /**
+   * Run an eval scenario for every character/model pair `iterations` times and aggregate
+   *
+   * With a `seed`, iteration `n` of every pair runs with `seed + n`, so arms are compared on
+   * the same sequence of seeds.
+   */
+  start_eval_matrix: {
+    args: { characterIds: string[]; models: string[]; iterations: number; scenarioPath: string; config: SandboxConfig; name?: string | null; seed?: number | null };
+    response: ApiResponse<Experiment>;
+  };
+  /** Start the log stream server on a random loopback port with a fresh token */
+  start_log_stream_server: {
+    args: Record<string, never>;
+    response: ApiResponse<LogStreamStatus>;
+  };
+  /** Start the local Sandbox stub and return a config pointing at it */
+  start_mock_sandbox: {
+    args: Record<string, never>;
+    response: ApiResponse<MockSandboxStatus>;
+  };
+  /**
+   * Start watching a project; `config` is the Sandbox configuration of the agent run and
+   * is required for `RestartAgent`, which also starts the agent right away
+   */
+  start_project_watch: {
+    args: { watch: WatchConfig; config?: SandboxConfig | null };
+    response: ApiResponse<WatchSession>;
+  };
+  /** Start several agent runs as a coordinated group */
+  start_run_group: {
+    args: { specs: RunSpec[]; config: SandboxConfig };
+    response: ApiResponse<RunGroup>;
+  };
+  /**
+   * Start a device-code login against `base_url`. The verification page is opened in the
+   * browser unless `open_browser` is false; the result arrives as a `sandbox-login` event
+   */
+  start_sandbox_login: {
+    args: { baseUrl: string; profileName?: string | null; openBrowser?: boolean | null };
+    response: ApiResponse<SandboxLoginSession>;
+  };
+  /** Start the webhook listener on a random local port */
+  start_webhook_listener: {
+    args: Record<string, never>;
+    response: ApiResponse<WebhookListenerStatus>;
+  };
+  /** Stop the connectivity monitor */
+  stop_connectivity_monitor: {
+    args: Record<string, never>;
+    response: ApiResponse<ConnectivityStatus>;
+  };
+  /** Stop a running ElizaOS CLI process gracefully */
+  stop_eliza_run: {
+    args: { runId: string };
+    response: ApiResponse<RunResult>;
+  };
+  /** Stop the log stream server; open streams end with it */
+  stop_log_stream_server: {
+    args: Record<string, never>;
+    response: ApiResponse<LogStreamStatus>;
+  };
+  /** Stop the local Sandbox stub */
+  stop_mock_sandbox: {
+    args: Record<string, never>;
+    response: ApiResponse<MockSandboxStatus>;
+  };
+  /** Stop a project watch, returning its final session */
+  stop_project_watch: {
+    args: { watchId: string };
+    response: ApiResponse<WatchSession>;
+  };
+  /** Gracefully stop every running agent in a group */
+  stop_run_group: {
+    args: { groupId: string };
+    response: ApiResponse<RunGroup>;
+  };
+  /** Stop the webhook listener */
+  stop_webhook_listener: {
+    args: Record<string, never>;
+    response: ApiResponse<WebhookListenerStatus>;
+  };
+  /**
+   * Lines of a run's log from `from_offset` on; with `follow`, waits briefly for new lines
+   * when there are none yet and the run is still going
+   */
+  tail_run_log: {
+    args: { runId: string; fromOffset: number; follow?: boolean | null };
+    response: ApiResponse<RunLogTail>;
+  };
+  /** Test API prompt execution */
+  test_api_prompt: {
+    args: { config: SandboxConfig; prompt: string };
+    response: ApiResponse<string>;
+  };
+  /**
+   * Send a test prompt with streaming enabled, emitting a `prompt-token` event per content
+   * delta so the settings screen can show the output live along with time to first token
+   */
+  test_api_prompt_streaming: {
+    args: { config: SandboxConfig; prompt: string; promptId?: string | null };
+    response: ApiResponse<PromptStreamResult>;
+  };
+  /** Test connection to Sandbox API */
+  test_sandbox_connection: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<ConnectionTestResult>;
+  };
+  /** Unlock the vault; required before commands that use the API key or secrets */
+  unlock_app: {
+    args: { passphrase: string };
+    response: ApiResponse<AppLockStatus>;
+  };
+  /** Unpin an item, returning whether it was pinned */
+  unpin_item: {
+    args: { kind: PinKind; id: string };
+    response: ApiResponse<boolean>;
+  };
+  /** Apply the saved retention limits now instead of waiting for the background pass */
+  vacuum_storage: {
+    args: Record<string, never>;
+    response: ApiResponse<VacuumReport>;
+  };
+  /**
+   * Check the API key of `config` against the Sandbox; the outcome is in `status`, so
+   * refusals come back as successful responses the UI can branch on
+   */
+  validate_api_key: {
+    args: { config: SandboxConfig };
+    response: ApiResponse<ApiKeyCheck>;
+  };
+}
+
+/** Payload of each event the backend emits, keyed by event name */
+export interface Events {
+  'activity-recorded': ActivityEntry;
+  'agent-autostart': AgentAutostartEvent;
+  'app-locked': unknown;
+  'approval-requested': PendingApproval;
+  'approval-resolved': PendingApproval;
+  'benchmark-event': BenchmarkPing;
+  'benchmark-ping': BenchmarkPing;
+  'budget-threshold': BudgetThresholdEvent;
+  'cli-install-progress': CliInstallProgressEvent;
+  'cloud-deploy-status': CloudDeployEvent;
+  'config-changed': ConfigChanged;
+  'connectivity-changed': ConnectivityChangedEvent;
+  'doctor-progress': DoctorProgressEvent;
+  'egress-alert': EgressAlertEvent;
+  'file-drop-result': FileDropResult;
+  'global-shortcut': GlobalShortcutTriggered;
+  'kv-changed': KvChange;
+  'log-event': LogEvent;
+  'onboarding-step-completed': OnboardingStepCompleted;
+  'prompt-token': PromptTokenEvent;
+  'run-group-status': RunGroup;
+  'run-progress-line': ProgressLineEvent;
+  'run-queue': RunQueueEvent;
+  'run-retry-suggested': RunRetrySuggestedEvent;
+  'run-schedule-status': RunSchedule;
+  'run-stalled': RunStalledEvent;
+  'sandbox-login': SandboxLoginEvent;
+  'sandbox-webhook': WebhookEvent;
+  'scenario-step': ScenarioStepResult;
+  'startup-report': StartupReport;
+  'watch-event': WatchEvent;
+}
+
+type CommandArgs<C extends keyof Commands> =
+  Record<string, never> extends Commands[C]['args']
+    ? [args?: Commands[C]['args']]
+    : [args: Commands[C]['args']];
+
+/** `invoke` with the arguments and response checked against the command's signature */
+export function invokeCommand<C extends keyof Commands>(
+  command: C,
+  ...args: CommandArgs<C>
+): Promise<Commands[C]['response']> {
+  return invoke<Commands[C]['response']>(command, args[0] as InvokeArgs | undefined);
+}
+
+/** `listen` with the payload typed by event name */
+export function listenEvent<E extends keyof Events>(
+  event: E,
+  handler: EventCallback<Events[E]>,
+): Promise<UnlistenFn> {
+  return listen<Events[E]>(event, handler);
+}